@@ -0,0 +1,918 @@
+/*
+ * spoked.c — libspoked, the C ABI of a spokedpy store (see spoked.h).
+ *
+ * A port of the read path of visual_editor_core/slot_resolver.py and of
+ * parse_file_header / body_matches_hash in snippet_staging.py; the
+ * tests (tests/test_ffi.py) check it against them.  C99 plus POSIX (or
+ * Win32) directory listing; SHA-256 is built in.
+ */
+
+#if !defined(_WIN32) && !defined(_POSIX_C_SOURCE)
+#  define _POSIX_C_SOURCE 200809L
+#endif
+#define SPOKED_BUILD
+
+#include "spoked.h"
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+
+#ifdef _WIN32
+#  include <windows.h>
+#  include <direct.h>
+#  define getcwd _getcwd
+#  define SEP '\\'
+#else
+#  include <dirent.h>
+#  include <unistd.h>
+#  define SEP '/'
+#endif
+
+static const char BANNER[] = "\xe2\x95\x90\xe2\x95\x90\xe2\x95\x90";    /* '═══' */
+static const char ELLIPSIS[] = "\xe2\x80\xa6";                           /* '…'   */
+
+/* ═════════════════════════════════════════════════════════════════════════
+ * SHA-256
+ * ═════════════════════════════════════════════════════════════════════════ */
+
+static const uint32_t K256[64] = {
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+};
+
+#define ROTR(x, n) (((x) >> (n)) | ((x) << (32 - (n))))
+
+static void sha256_block(uint32_t h[8], const unsigned char *p)
+{
+    uint32_t w[64], a, b, c, d, e, f, g, k, t1, t2;
+    int i;
+    for (i = 0; i < 16; i++)
+        w[i] = (uint32_t)p[4 * i] << 24 | (uint32_t)p[4 * i + 1] << 16
+             | (uint32_t)p[4 * i + 2] << 8 | (uint32_t)p[4 * i + 3];
+    for (i = 16; i < 64; i++) {
+        uint32_t s0 = ROTR(w[i - 15], 7) ^ ROTR(w[i - 15], 18) ^ (w[i - 15] >> 3);
+        uint32_t s1 = ROTR(w[i - 2], 17) ^ ROTR(w[i - 2], 19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+    }
+    a = h[0]; b = h[1]; c = h[2]; d = h[3]; e = h[4]; f = h[5]; g = h[6]; k = h[7];
+    for (i = 0; i < 64; i++) {
+        t1 = k + (ROTR(e, 6) ^ ROTR(e, 11) ^ ROTR(e, 25)) + ((e & f) ^ (~e & g)) + K256[i] + w[i];
+        t2 = (ROTR(a, 2) ^ ROTR(a, 13) ^ ROTR(a, 22)) + ((a & b) ^ (a & c) ^ (b & c));
+        k = g; g = f; f = e; e = d + t1; d = c; c = b; b = a; a = t1 + t2;
+    }
+    h[0] += a; h[1] += b; h[2] += c; h[3] += d; h[4] += e; h[5] += f; h[6] += g; h[7] += k;
+}
+
+/* Lower-case hex digest of data[0..length) into hex[65]. */
+static void sha256_hex(const char *data, size_t length, char hex[65])
+{
+    uint32_t h[8] = {0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                     0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19};
+    unsigned char tail[128];
+    size_t full = length / 64 * 64, rest = length - full, padded;
+    uint64_t bits = (uint64_t)length * 8;
+    size_t i;
+    for (i = 0; i < full; i += 64)
+        sha256_block(h, (const unsigned char *)data + i);
+    memset(tail, 0, sizeof tail);
+    memcpy(tail, data + full, rest);
+    tail[rest] = 0x80;
+    padded = rest < 56 ? 64 : 128;
+    for (i = 0; i < 8; i++)
+        tail[padded - 1 - i] = (unsigned char)(bits >> (8 * i));
+    sha256_block(h, tail);
+    if (padded == 128)
+        sha256_block(h, tail + 64);
+    for (i = 0; i < 8; i++)
+        sprintf(hex + 8 * i, "%08x", (unsigned)h[i]);
+}
+
+/* ═════════════════════════════════════════════════════════════════════════
+ * FILES
+ * ═════════════════════════════════════════════════════════════════════════ */
+
+static char *dup_range(const char *s, size_t n)
+{
+    char *copy = malloc(n + 1);
+    if (copy) {
+        memcpy(copy, s, n);
+        copy[n] = '\0';
+    }
+    return copy;
+}
+
+static char *join_path(const char *dir, const char *name)
+{
+    size_t a = strlen(dir), b = strlen(name);
+    char *path = malloc(a + b + 2);
+    if (path) {
+        memcpy(path, dir, a);
+        path[a] = SEP;
+        memcpy(path + a + 1, name, b + 1);
+    }
+    return path;
+}
+
+/* Whole file, NUL-terminated, line endings as Python's text mode reads them ('\n'). */
+static int read_text(const char *path, char **text, size_t *length)
+{
+    FILE *f = fopen(path, "rb");
+    char *buf = NULL;
+    size_t size = 0, cap = 0, n, i, j;
+    if (!f)
+        return SPOKED_FAILED;
+    for (;;) {
+        if (cap - size < 4096) {
+            char *grown = realloc(buf, cap = cap * 2 + 8192);
+            if (!grown) {
+                free(buf);
+                fclose(f);
+                return SPOKED_FAILED;
+            }
+            buf = grown;
+        }
+        n = fread(buf + size, 1, cap - size - 1, f);
+        size += n;
+        if (n == 0)
+            break;
+    }
+    if (ferror(f)) {
+        free(buf);
+        fclose(f);
+        return SPOKED_FAILED;
+    }
+    fclose(f);
+    for (i = j = 0; i < size; i++) {
+        if (buf[i] == '\r') {
+            buf[j++] = '\n';
+            if (i + 1 < size && buf[i + 1] == '\n')
+                i++;
+        } else {
+            buf[j++] = buf[i];
+        }
+    }
+    buf[j] = '\0';
+    *text = buf;
+    *length = j;
+    return SPOKED_OK;
+}
+
+static long long mtime_ns(const char *path, int *is_dir)
+{
+#ifdef _WIN32
+    WIN32_FILE_ATTRIBUTE_DATA data;
+    ULARGE_INTEGER t;
+    if (!GetFileAttributesExA(path, GetFileExInfoStandard, &data))
+        return -1;
+    if (is_dir)
+        *is_dir = (data.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+    t.LowPart = data.ftLastWriteTime.dwLowDateTime;
+    t.HighPart = data.ftLastWriteTime.dwHighDateTime;
+    return ((long long)t.QuadPart - 116444736000000000LL) * 100;     /* 1601 → 1970 */
+#else
+    struct stat st;
+    if (stat(path, &st) != 0)
+        return -1;
+    if (is_dir)
+        *is_dir = S_ISDIR(st.st_mode);
+#  ifdef __APPLE__
+    return (long long)st.st_mtimespec.tv_sec * 1000000000LL + st.st_mtimespec.tv_nsec;
+#  else
+    return (long long)st.st_mtim.tv_sec * 1000000000LL + st.st_mtim.tv_nsec;
+#  endif
+#endif
+}
+
+typedef int (*dir_visit)(void *context, const char *path, int is_dir, long long mtime);
+
+/* Call visit for every entry of dir (not '.' / '..'); stops at a non-zero return. */
+static int list_dir(const char *dir, dir_visit visit, void *context)
+{
+    int rc = SPOKED_OK;
+#ifdef _WIN32
+    WIN32_FIND_DATAA found;
+    char *pattern = join_path(dir, "*");
+    HANDLE h;
+    if (!pattern)
+        return SPOKED_FAILED;
+    h = FindFirstFileA(pattern, &found);
+    free(pattern);
+    if (h == INVALID_HANDLE_VALUE)
+        return SPOKED_OK;
+    do {
+        const char *name = found.cFileName;
+#else
+    struct dirent *found;
+    DIR *d = opendir(dir);
+    if (!d)
+        return SPOKED_OK;
+    while ((found = readdir(d)) != NULL) {
+        const char *name = found->d_name;
+#endif
+        char *path;
+        int is_dir = 0;
+        long long mtime;
+        if (strcmp(name, ".") == 0 || strcmp(name, "..") == 0)
+            continue;
+        if (!(path = join_path(dir, name))) {
+            rc = SPOKED_FAILED;
+            break;
+        }
+        mtime = mtime_ns(path, &is_dir);
+        if (mtime >= 0)
+            rc = visit(context, path, is_dir, mtime);
+        free(path);
+        if (rc != SPOKED_OK)
+            break;
+#ifdef _WIN32
+    } while (FindNextFileA(h, &found));
+    FindClose(h);
+#else
+    }
+    closedir(d);
+#endif
+    return rc;
+}
+
+/* os.path.abspath: absolute, '.' / '..' / repeated separators folded away. */
+static char *abspath(const char *path)
+{
+#ifdef _WIN32
+    char *full = _fullpath(NULL, path, 0);
+    char *copy = full ? dup_range(full, strlen(full)) : NULL;
+    free(full);
+    return copy;
+#else
+    char cwd[4096], *joined, *out;
+    size_t n, i, o = 0;
+    if (path[0] == '/') {
+        joined = dup_range(path, strlen(path));
+    } else {
+        if (!getcwd(cwd, sizeof cwd))
+            return NULL;
+        joined = join_path(cwd, path);
+    }
+    if (!joined)
+        return NULL;
+    n = strlen(joined);
+    out = malloc(n + 2);
+    if (!out) {
+        free(joined);
+        return NULL;
+    }
+    for (i = 0; i < n;) {
+        size_t start, len;
+        while (i < n && joined[i] == '/')
+            i++;
+        start = i;
+        while (i < n && joined[i] != '/')
+            i++;
+        len = i - start;
+        if (len == 0 || (len == 1 && joined[start] == '.'))
+            continue;
+        if (len == 2 && joined[start] == '.' && joined[start + 1] == '.') {
+            while (o > 0 && out[o - 1] != '/')
+                o--;
+            if (o > 0)
+                o--;
+            continue;
+        }
+        out[o++] = '/';
+        memcpy(out + o, joined + start, len);
+        o += len;
+    }
+    if (o == 0)
+        out[o++] = '/';
+    out[o] = '\0';
+    free(joined);
+    return out;
+#endif
+}
+
+/* ═════════════════════════════════════════════════════════════════════════
+ * HEADERS — parse_file_header
+ * ═════════════════════════════════════════════════════════════════════════ */
+
+enum { F_STAGING_ID, F_LANGUAGE, F_ENGINE, F_SLOT, F_LABEL, F_CODE_HASH, F_PROMOTED,
+       F_PROMOTED_SEQ, F_COUNT };
+
+static const char *const FIELD_NAMES[F_COUNT] = {
+    "staging_id", "language", "engine", "slot", "label", "code_hash", "promoted", "promoted_seq",
+};
+
+typedef struct header {
+    const char *value[F_COUNT];         /* into the text; not NUL-terminated */
+    size_t length[F_COUNT];
+    size_t body;                        /* offset of the code */
+} header;
+
+static int is_space(char c)
+{
+    return c == ' ' || (c >= '\t' && c <= '\r') || (c >= '\x1c' && c <= '\x1f');
+}
+
+static void strip(const char **s, size_t *n)
+{
+    while (*n && is_space(**s)) {
+        (*s)++;
+        (*n)--;
+    }
+    while (*n && is_space((*s)[*n - 1]))
+        (*n)--;
+}
+
+static int starts_with(const char *s, size_t n, const char *prefix)
+{
+    size_t p = strlen(prefix);
+    return n >= p && memcmp(s, prefix, p) == 0;
+}
+
+static int contains(const char *s, size_t n, const char *needle)
+{
+    size_t k = strlen(needle), i;
+    for (i = 0; i + k <= n; i++)
+        if (memcmp(s + i, needle, k) == 0)
+            return 1;
+    return 0;
+}
+
+/* 1 and `out` filled if text starts with a banner, else 0. */
+static int parse_header(const char *text, size_t length, header *out)
+{
+    const char *prefix, *line;
+    size_t prefix_len, line_len, pos, next, index, end_line = 0, body_line, last_field = 0;
+    int closed = 0, any_field = 0;
+    size_t line_count = 1, i;
+    const char *first;
+    size_t first_len;
+
+    memset(out, 0, sizeof *out);
+    for (i = 0; i < length; i++)
+        line_count += text[i] == '\n';
+
+    next = 0;
+    while (next < length && text[next] != '\n')
+        next++;
+    first = text;
+    first_len = next;
+    strip(&first, &first_len);
+    if (starts_with(first, first_len, "//"))
+        prefix = "//";
+    else if (starts_with(first, first_len, "#"))
+        prefix = "#";
+    else
+        return 0;
+    if (!contains(first, first_len, BANNER))
+        return 0;
+    prefix_len = strlen(prefix);
+
+    pos = next < length ? next + 1 : length;
+    for (index = 1; index < line_count; index++) {
+        const char *content, *colon;
+        size_t content_len;
+        next = pos;
+        while (next < length && text[next] != '\n')
+            next++;
+        line = text + pos;
+        line_len = next - pos;
+        pos = next < length ? next + 1 : length;
+        strip(&line, &line_len);
+        if (!starts_with(line, line_len, prefix))
+            break;
+        content = line + prefix_len;
+        content_len = line_len - prefix_len;
+        strip(&content, &content_len);
+        if (starts_with(content, content_len, BANNER)) {
+            end_line = index;
+            closed = 1;
+            break;
+        }
+        colon = memchr(content, ':', content_len);
+        if (!colon)
+            continue;                   /* the title (line 1), or a stray comment */
+        {
+            const char *key = content, *value = colon + 1;
+            size_t key_len = (size_t)(colon - content);
+            size_t value_len = content_len - key_len - 1;
+            int f;
+            strip(&key, &key_len);
+            strip(&value, &value_len);
+            for (f = 0; f < F_COUNT; f++) {
+                if (strlen(FIELD_NAMES[f]) == key_len && memcmp(FIELD_NAMES[f], key, key_len) == 0) {
+                    out->value[f] = value;
+                    out->length[f] = value_len;
+                }
+            }
+            last_field = index;
+            any_field = 1;
+        }
+    }
+    if (!closed)
+        end_line = any_field ? last_field : 0;
+
+    body_line = end_line + 1;
+    /* Offset of body_line; a blank line after the banner is skipped. */
+    for (pos = 0, index = 0; index < body_line && pos < length; pos++)
+        if (text[pos] == '\n')
+            index++;
+    if (index < body_line)
+        pos = length;                   /* body_line == line_count */
+    if (body_line < line_count) {
+        size_t end = pos, blank = 1;
+        while (end < length && text[end] != '\n') {
+            if (!is_space(text[end]))
+                blank = 0;
+            end++;
+        }
+        if (blank)
+            pos = end < length ? end + 1 : length;
+    }
+    out->body = pos;
+    return 1;
+}
+
+/* body_matches_hash: the body (or the body minus one trailing '\n') hashes to code_hash. */
+static int body_matches(const char *text, size_t length, const header *h)
+{
+    const char *expected = h->value[F_CODE_HASH];
+    size_t expected_len = h->length[F_CODE_HASH], body_len = length - h->body, e = strlen(ELLIPSIS);
+    char hex[65];
+    while (expected_len >= e && memcmp(expected + expected_len - e, ELLIPSIS, e) == 0)
+        expected_len -= e;
+    if (!expected || expected_len == 0 || expected_len > 64)
+        return 0;
+    sha256_hex(text + h->body, body_len, hex);
+    if (memcmp(hex, expected, expected_len) == 0)
+        return 1;
+    if (body_len && text[length - 1] == '\n') {
+        sha256_hex(text + h->body, body_len - 1, hex);
+        return memcmp(hex, expected, expected_len) == 0;
+    }
+    return 0;
+}
+
+/* parse_timestamp, or 0.0 when malformed (as SlotResolver orders such files). */
+static double parse_time(const char *s, size_t n)
+{
+    long long y, mo, d, hh = 0, mi = 0, ss = 0, micros = 0, offset = 0, days, era;
+    unsigned yoe, doy, doe;
+    size_t i = 0;
+    int digits;
+
+#define NUM(var, count)                                                     \
+    do {                                                                    \
+        int k_;                                                             \
+        var = 0;                                                            \
+        for (k_ = 0; k_ < (count); k_++, i++) {                             \
+            if (i >= n || s[i] < '0' || s[i] > '9')                         \
+                return 0.0;                                                 \
+            var = var * 10 + (s[i] - '0');                                  \
+        }                                                                   \
+    } while (0)
+
+    strip(&s, &n);
+    NUM(y, 4);
+    if (i >= n || s[i++] != '-') return 0.0;
+    NUM(mo, 2);
+    if (i >= n || s[i++] != '-') return 0.0;
+    NUM(d, 2);
+    if (mo < 1 || mo > 12 || d < 1 || d > 31)
+        return 0.0;
+    if (i < n && (s[i] == 'T' || s[i] == 't' || s[i] == ' ')) {
+        i++;
+        NUM(hh, 2);
+        if (i < n && s[i] == ':') {
+            i++;
+            NUM(mi, 2);
+            if (i < n && s[i] == ':') {
+                i++;
+                NUM(ss, 2);
+                if (i < n && (s[i] == '.' || s[i] == ',')) {
+                    i++;
+                    for (digits = 0; i < n && s[i] >= '0' && s[i] <= '9'; i++, digits++)
+                        if (digits < 6)
+                            micros = micros * 10 + (s[i] - '0');
+                    if (digits == 0)
+                        return 0.0;
+                    for (; digits < 6; digits++)
+                        micros *= 10;
+                }
+            }
+        }
+        if (hh > 23 || mi > 59 || ss > 59)
+            return 0.0;
+        if (i < n && (s[i] == 'Z' || s[i] == 'z')) {
+            i++;
+        } else if (i < n && (s[i] == '+' || s[i] == '-')) {
+            long long sign = s[i++] == '-' ? -1 : 1, oh, om = 0, os = 0;
+            NUM(oh, 2);
+            if (i < n && s[i] == ':') {
+                i++;
+                NUM(om, 2);
+                if (i < n && s[i] == ':') {
+                    i++;
+                    NUM(os, 2);
+                }
+            }
+            offset = sign * (oh * 3600 + om * 60 + os);
+        }
+    }
+    if (i != n)
+        return 0.0;
+#undef NUM
+
+    /* days_from_civil (proleptic Gregorian) */
+    y -= mo <= 2;
+    era = (y >= 0 ? y : y - 399) / 400;
+    yoe = (unsigned)(y - era * 400);
+    doy = (unsigned)((153 * (mo + (mo > 2 ? -3 : 9)) + 2) / 5 + d - 1);
+    doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    days = era * 146097 + (long long)doe - 719468;
+    /* Integer microseconds first, so the value is the one Python computes. */
+    return (double)((days * 86400 + hh * 3600 + mi * 60 + ss - offset) * 1000000LL + micros)
+           / 1e6;
+}
+
+/* ═════════════════════════════════════════════════════════════════════════
+ * STORE
+ * ═════════════════════════════════════════════════════════════════════════ */
+
+typedef struct entry {
+    spoked_snippet snippet;
+    double promoted_at;
+} entry;
+
+struct spoked_store {
+    char *snippets_dir;
+    char *audit_log;
+    entry *entries;                     /* every file that claims a slot */
+    size_t count, capacity;
+    char **rolled_back;                 /* sorted staging_ids */
+    size_t rolled_count, rolled_capacity;
+    entry **winners;                    /* one per occupied slot, sorted by slot */
+    size_t winner_count;
+};
+
+static char *field_copy(const header *h, int f)
+{
+    return dup_range(h->value[f] ? h->value[f] : "", h->length[f]);
+}
+
+/* The first whitespace-separated word of a field ('d3 (position 3)' → 'd3'). */
+static char *first_word(const header *h, int f)
+{
+    const char *s = h->value[f] ? h->value[f] : "";
+    size_t n = 0;
+    while (n < h->length[f] && !is_space(s[n]))
+        n++;
+    return dup_range(s, n);
+}
+
+static void free_snippet(spoked_snippet *s)
+{
+    free((char *)s->slot); free((char *)s->path); free((char *)s->code_hash);
+    free((char *)s->label); free((char *)s->engine); free((char *)s->language);
+    free((char *)s->staging_id); free((char *)s->promoted);
+}
+
+static int add_file(spoked_store *store, const char *path, long long mtime)
+{
+    char *text;
+    size_t length;
+    header h;
+    entry e;
+    int i;
+
+    if (read_text(path, &text, &length) != SPOKED_OK)
+        return SPOKED_OK;               /* vanished or unreadable: not indexed */
+    if (!parse_header(text, length, &h) || !h.value[F_SLOT] || h.length[F_SLOT] == 0
+        || is_space(h.value[F_SLOT][0])) {
+        free(text);
+        return SPOKED_OK;               /* spec sidecars, golden files */
+    }
+    memset(&e, 0, sizeof e);
+    e.snippet.slot = first_word(&h, F_SLOT);
+    e.snippet.path = dup_range(path, strlen(path));
+    e.snippet.code_hash = field_copy(&h, F_CODE_HASH);
+    e.snippet.label = field_copy(&h, F_LABEL);
+    e.snippet.engine = first_word(&h, F_ENGINE);
+    e.snippet.language = field_copy(&h, F_LANGUAGE);
+    e.snippet.staging_id = field_copy(&h, F_STAGING_ID);
+    e.snippet.promoted = field_copy(&h, F_PROMOTED);
+    e.snippet.mtime_ns = mtime;
+    e.promoted_at = h.value[F_PROMOTED] ? parse_time(h.value[F_PROMOTED], h.length[F_PROMOTED])
+                                        : 0.0;
+    for (i = 0; h.value[F_PROMOTED_SEQ] && (size_t)i < h.length[F_PROMOTED_SEQ]; i++) {
+        char c = h.value[F_PROMOTED_SEQ][i];
+        if (c < '0' || c > '9') {
+            e.snippet.promoted_seq = 0;
+            break;
+        }
+        e.snippet.promoted_seq = e.snippet.promoted_seq * 10 + (c - '0');
+    }
+    free(text);
+    if (!e.snippet.slot || !e.snippet.path || !e.snippet.code_hash || !e.snippet.label
+        || !e.snippet.engine || !e.snippet.language || !e.snippet.staging_id
+        || !e.snippet.promoted) {
+        free_snippet(&e.snippet);
+        return SPOKED_FAILED;
+    }
+    if (store->count == store->capacity) {
+        size_t capacity = store->capacity * 2 + 64;
+        entry *grown = realloc(store->entries, capacity * sizeof *grown);
+        if (!grown) {
+            free_snippet(&e.snippet);
+            return SPOKED_FAILED;
+        }
+        store->entries = grown;
+        store->capacity = capacity;
+    }
+    store->entries[store->count++] = e;
+    return SPOKED_OK;
+}
+
+static int visit_file(void *context, const char *path, int is_dir, long long mtime)
+{
+    return is_dir ? SPOKED_OK : add_file(context, path, mtime);
+}
+
+static int visit_shard(void *context, const char *path, int is_dir, long long mtime)
+{
+    if (is_dir)
+        return list_dir(path, visit_file, context);
+    return add_file(context, path, mtime);
+}
+
+static int visit_language(void *context, const char *path, int is_dir, long long mtime)
+{
+    (void)mtime;
+    return is_dir ? list_dir(path, visit_shard, context) : SPOKED_OK;
+}
+
+static int compare_strings(const void *a, const void *b)
+{
+    return strcmp(*(char *const *)a, *(char *const *)b);
+}
+
+static int is_rolled_back(const spoked_store *store, const char *staging_id)
+{
+    return store->rolled_count
+        && bsearch(&staging_id, store->rolled_back, store->rolled_count, sizeof(char *),
+                   compare_strings) != NULL;
+}
+
+/* The string value of the first "key": "…" in a JSON line (no unescaping needed for ids). */
+static int json_string(const char *line, size_t n, const char *key, const char **value,
+                       size_t *length)
+{
+    size_t k = strlen(key), i, j;
+    for (i = 0; i + k + 2 <= n; i++) {
+        if (line[i] != '"' || memcmp(line + i + 1, key, k) != 0 || line[i + k + 1] != '"')
+            continue;
+        j = i + k + 2;
+        while (j < n && is_space(line[j]))
+            j++;
+        if (j >= n || line[j++] != ':')
+            continue;
+        while (j < n && is_space(line[j]))
+            j++;
+        if (j >= n || line[j++] != '"')
+            return 0;
+        *value = line + j;
+        while (j < n && line[j] != '"')
+            j += line[j] == '\\' ? 2 : 1;
+        if (j >= n)
+            return 0;
+        *length = (size_t)(line + j - *value);
+        return 1;
+    }
+    return 0;
+}
+
+static int read_audit(spoked_store *store)
+{
+    char *text;
+    size_t length, pos = 0;
+    if (!store->audit_log || read_text(store->audit_log, &text, &length) != SPOKED_OK)
+        return SPOKED_OK;
+    while (pos < length) {
+        const char *line = text + pos, *event, *staging_id, *nl;
+        size_t event_len, id_len, n;
+        nl = memchr(line, '\n', length - pos);
+        if (!nl)
+            break;                      /* a line still being written waits */
+        n = (size_t)(nl - line);
+        pos += n + 1;
+        if (!contains(line, n, "\"rollback\"")
+            || !json_string(line, n, "event", &event, &event_len)
+            || event_len != 8 || memcmp(event, "rollback", 8) != 0
+            || !json_string(line, n, "staging_id", &staging_id, &id_len) || id_len == 0)
+            continue;
+        if (store->rolled_count == store->rolled_capacity) {
+            size_t capacity = store->rolled_capacity * 2 + 16;
+            char **grown = realloc(store->rolled_back, capacity * sizeof *grown);
+            if (!grown) {
+                free(text);
+                return SPOKED_FAILED;
+            }
+            store->rolled_back = grown;
+            store->rolled_capacity = capacity;
+        }
+        if (!(store->rolled_back[store->rolled_count] = dup_range(staging_id, id_len))) {
+            free(text);
+            return SPOKED_FAILED;
+        }
+        store->rolled_count++;
+    }
+    free(text);
+    if (store->rolled_count)
+        qsort(store->rolled_back, store->rolled_count, sizeof(char *), compare_strings);
+    return SPOKED_OK;
+}
+
+/* By slot; within a slot the winner first (promoted_at, promoted_seq, mtime_ns, path — all DESC). */
+static int compare_entries(const void *a, const void *b)
+{
+    const entry *x = a, *y = b;
+    int c = strcmp(x->snippet.slot, y->snippet.slot);
+    if (c)
+        return c;
+    if (x->promoted_at != y->promoted_at)
+        return x->promoted_at < y->promoted_at ? 1 : -1;
+    if (x->snippet.promoted_seq != y->snippet.promoted_seq)
+        return x->snippet.promoted_seq < y->snippet.promoted_seq ? 1 : -1;
+    if (x->snippet.mtime_ns != y->snippet.mtime_ns)
+        return x->snippet.mtime_ns < y->snippet.mtime_ns ? 1 : -1;
+    return -strcmp(x->snippet.path, y->snippet.path);
+}
+
+static void clear(spoked_store *store)
+{
+    size_t i;
+    for (i = 0; i < store->count; i++)
+        free_snippet(&store->entries[i].snippet);
+    for (i = 0; i < store->rolled_count; i++)
+        free(store->rolled_back[i]);
+    free(store->entries);
+    free(store->rolled_back);
+    free(store->winners);
+    store->entries = NULL;
+    store->rolled_back = NULL;
+    store->winners = NULL;
+    store->count = store->capacity = store->rolled_count = store->rolled_capacity = 0;
+    store->winner_count = 0;
+}
+
+SPOKED_API int spoked_abi_version(void)
+{
+    return SPOKED_ABI_VERSION;
+}
+
+SPOKED_API int spoked_refresh(spoked_store *store)
+{
+    size_t i;
+    int rc;
+    if (!store)
+        return SPOKED_USAGE;
+    clear(store);
+    if ((rc = list_dir(store->snippets_dir, visit_language, store)) != SPOKED_OK
+        || (rc = read_audit(store)) != SPOKED_OK) {
+        clear(store);
+        return rc;
+    }
+    qsort(store->entries, store->count, sizeof(entry), compare_entries);
+    if (!(store->winners = malloc((store->count + 1) * sizeof(entry *)))) {
+        clear(store);
+        return SPOKED_FAILED;
+    }
+    for (i = 0; i < store->count; i++) {
+        entry *e = &store->entries[i];
+        if (store->winner_count
+            && strcmp(store->winners[store->winner_count - 1]->snippet.slot, e->snippet.slot) == 0)
+            continue;                   /* the slot already has its winner */
+        if (!is_rolled_back(store, e->snippet.staging_id))
+            store->winners[store->winner_count++] = e;
+    }
+    return SPOKED_OK;
+}
+
+SPOKED_API spoked_store *spoked_open(const char *snippets_dir, const char *audit_log)
+{
+    spoked_store *store;
+    if (!snippets_dir)
+        return NULL;
+    if (!(store = calloc(1, sizeof *store)))
+        return NULL;
+    store->snippets_dir = abspath(snippets_dir);
+    if (audit_log && *audit_log)
+        store->audit_log = dup_range(audit_log, strlen(audit_log));
+    if (!store->snippets_dir || (audit_log && *audit_log && !store->audit_log)) {
+        spoked_close(store);
+        return NULL;
+    }
+    spoked_refresh(store);              /* a missing dir is an empty store */
+    return store;
+}
+
+SPOKED_API void spoked_close(spoked_store *store)
+{
+    if (!store)
+        return;
+    clear(store);
+    free(store->snippets_dir);
+    free(store->audit_log);
+    free(store);
+}
+
+SPOKED_API int spoked_resolve_slot(const spoked_store *store, const char *slot,
+                                   spoked_snippet *out)
+{
+    char key[64];
+    const char *s;
+    size_t n, i, lo = 0, hi;
+    if (!store || !slot || !out)
+        return SPOKED_USAGE;
+    s = slot;
+    n = strlen(slot);
+    strip(&s, &n);
+    if (n == 0 || n >= sizeof key)
+        return SPOKED_NOT_FOUND;
+    for (i = 0; i < n; i++)
+        key[i] = (s[i] >= 'A' && s[i] <= 'Z') ? (char)(s[i] - 'A' + 'a') : s[i];
+    key[n] = '\0';
+    hi = store->winner_count;
+    while (lo < hi) {
+        size_t mid = lo + (hi - lo) / 2;
+        int c = strcmp(store->winners[mid]->snippet.slot, key);
+        if (c == 0) {
+            *out = store->winners[mid]->snippet;
+            return SPOKED_OK;
+        }
+        if (c < 0)
+            lo = mid + 1;
+        else
+            hi = mid;
+    }
+    return SPOKED_NOT_FOUND;
+}
+
+SPOKED_API size_t spoked_list_slots(const spoked_store *store, const char **slots,
+                                    size_t capacity)
+{
+    size_t i;
+    if (!store)
+        return 0;
+    for (i = 0; slots && i < capacity && i < store->winner_count; i++)
+        slots[i] = store->winners[i]->snippet.slot;
+    return store->winner_count;
+}
+
+SPOKED_API int spoked_read_code(const char *path, char **code, size_t *length)
+{
+    char *text, *body;
+    size_t size, n;
+    header h;
+    if (!path || !code)
+        return SPOKED_USAGE;
+    if (read_text(path, &text, &size) != SPOKED_OK)
+        return SPOKED_FAILED;
+    if (!parse_header(text, size, &h)) {
+        free(text);
+        return SPOKED_NOT_FOUND;
+    }
+    if (!body_matches(text, size, &h)) {
+        free(text);
+        return SPOKED_STORE_CORRUPTION;
+    }
+    n = size - h.body;
+    body = dup_range(text + h.body, n);
+    free(text);
+    if (!body)
+        return SPOKED_FAILED;
+    *code = body;
+    if (length)
+        *length = n;
+    return SPOKED_OK;
+}
+
+SPOKED_API int spoked_verify_hash(const char *path)
+{
+    char *code = NULL;
+    int rc = spoked_read_code(path, &code, NULL);
+    free(code);
+    return rc;
+}
+
+SPOKED_API void spoked_free(void *memory)
+{
+    free(memory);
+}
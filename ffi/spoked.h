@@ -0,0 +1,116 @@
+/*
+ * spoked.h — C ABI for reading a spokedpy store (libspoked).
+ *
+ * Resolves which promoted file a slot holds, with the same rules as
+ * visual_editor_core.slot_resolver.SlotResolver: the headers of the files
+ * under the snippets dir (snippets/<lang>/ and its code_hash shard dirs)
+ * are the index; when several files claim a slot the latest `promoted`
+ * one wins (promoted_seq, then the file's mtime, then the path, break
+ * ties); with an audit log, snippets that were rolled back are left out.
+ * No Python, server or SQLite is needed.
+ *
+ *     spoked_store *store = spoked_open("data/snippets", "data/staging_audit.jsonl");
+ *     spoked_snippet snippet;
+ *     if (spoked_resolve_slot(store, "d3", &snippet) == SPOKED_OK
+ *         && spoked_verify_hash(snippet.path) == SPOKED_OK) {
+ *         ... load snippet.path ...
+ *     }
+ *     spoked_close(store);
+ *
+ * The store is read when it is opened and again on spoked_refresh(); the
+ * strings a store hands out stay valid until then (or spoked_close).  A
+ * store may be shared between threads if the caller serialises
+ * spoked_refresh / spoked_close against the lookups.
+ *
+ * Return codes are the `spokedpy` exit codes of the same failure
+ * (visual_editor_core/errors.py).  The ABI only grows: fields are added
+ * at the end of spoked_snippet, and SPOKED_ABI_VERSION goes up when they are.
+ */
+
+#ifndef SPOKED_H
+#define SPOKED_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#if defined(_WIN32)
+#  ifdef SPOKED_BUILD
+#    define SPOKED_API __declspec(dllexport)
+#  else
+#    define SPOKED_API __declspec(dllimport)
+#  endif
+#else
+#  define SPOKED_API __attribute__((visibility("default")))
+#endif
+
+#define SPOKED_ABI_VERSION 1
+
+#define SPOKED_OK               0
+#define SPOKED_FAILED           1     /* I/O error, out of memory              */
+#define SPOKED_USAGE            2     /* NULL or malformed argument            */
+#define SPOKED_NOT_FOUND        4     /* empty slot; not a promoted file       */
+#define SPOKED_STORE_CORRUPTION 10    /* body no longer matches its code_hash  */
+
+typedef struct spoked_store spoked_store;
+
+typedef struct spoked_snippet {
+    const char *slot;               /* 'd3'                                    */
+    const char *path;
+    const char *code_hash;          /* as in the header (banner v1: 16 hex + '…') */
+    const char *label;
+    const char *engine;             /* 'RUST'                                  */
+    const char *language;           /* 'rust'                                  */
+    const char *staging_id;
+    const char *promoted;           /* RFC 3339 UTC, from the header           */
+    long long mtime_ns;
+    long long promoted_seq;         /* store order of the promotion (0: older banner) */
+} spoked_snippet;
+
+/* SPOKED_ABI_VERSION of the loaded library. */
+SPOKED_API int spoked_abi_version(void);
+
+/* Read the store; audit_log may be NULL or "".  NULL if out of memory. */
+SPOKED_API spoked_store *spoked_open(const char *snippets_dir, const char *audit_log);
+
+/* Re-read the store; invalidates every string handed out so far. */
+SPOKED_API int spoked_refresh(spoked_store *store);
+
+SPOKED_API void spoked_close(spoked_store *store);
+
+/* The snippet promoted into `slot` (case-insensitive); SPOKED_NOT_FOUND if empty. */
+SPOKED_API int spoked_resolve_slot(const spoked_store *store, const char *slot,
+                                   spoked_snippet *out);
+
+/*
+ * Every occupied slot, sorted.  Writes up to `capacity` slot names into
+ * `slots` and returns how many there are in total, so a call with
+ * capacity 0 sizes the array.
+ */
+SPOKED_API size_t spoked_list_slots(const spoked_store *store, const char **slots,
+                                    size_t capacity);
+
+/*
+ * SPOKED_OK if the body of the promoted file at `path` still hashes to its
+ * header's code_hash (a trailing newline added by an editor is allowed),
+ * SPOKED_STORE_CORRUPTION if it was edited after promotion,
+ * SPOKED_NOT_FOUND if it is not a promoted file, SPOKED_FAILED if unreadable.
+ */
+SPOKED_API int spoked_verify_hash(const char *path);
+
+/*
+ * The body of the promoted file at `path` (without the header), verified as
+ * by spoked_verify_hash.  On SPOKED_OK *code is a NUL-terminated string of
+ * *length bytes (length may be NULL) to release with spoked_free.
+ */
+SPOKED_API int spoked_read_code(const char *path, char **code, size_t *length);
+
+SPOKED_API void spoked_free(void *memory);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SPOKED_H */
@@ -0,0 +1,2080 @@
+#!/usr/bin/env python3
+"""
+spokedpy — command-line front end for SpokedPy.
+
+Usage:
+    python spokedpy.py [--offline] [--profile NAME] [--server URL] [--tenant NAME] <command> …
+    python spokedpy.py doctor [--engine rust,python] [--format text|json]
+    python spokedpy.py toolchain install rust@1.84 [python@3.12.1 ...] [--dry-run]
+    python spokedpy.py toolchain list [--format text|json]
+    python spokedpy.py vendor path/to/Cargo.toml [more/Cargo.toml ...]
+    python spokedpy.py vendor --status
+    python spokedpy.py config show [--resolved] [--format text|json]
+    python spokedpy.py serve [--ui] [--read-only] [--host HOST] [--port PORT]
+    python spokedpy.py promote stg-… [stg-… ...] [--force --reason TEXT]
+    python spokedpy.py promote --interactive stg-…
+    python spokedpy.py promote stg-… --gate fuzz --seconds 30
+    python spokedpy.py promote stg-… --gate coverage --min 80
+    python spokedpy.py promote stg-… --at 2026-02-11T02:00Z | --window nightly
+    python spokedpy.py promote --cancel stg-…
+    python spokedpy.py promote stg-… --impact [--force]
+    python spokedpy.py show stg-…|data/snippets/… [...] [--format text|json|junit]
+    python spokedpy.py stage etl.py [--lang rust] [--label etl] [--meta team=payments ...]
+    python spokedpy.py ingest ./legacy-snippets [--lang auto] [--label-from filename|dir|path]
+    python spokedpy.py list [--meta team=payments] [--search csv] [--phase promoted]
+    python spokedpy.py lineage stg-… [--no-diff] [--format text|json]
+    python spokedpy.py spec check etl.py|etl.spec.toml [--format text|json]
+    python spokedpy.py spec run etl.py [--label Fibonacci] [--update-snapshots] [--follow]
+    python spokedpy.py spec mutate stg-… [--limit 40] [--min-kill-rate 0.6]
+    python spokedpy.py failures show stg-… [--format text|json]
+    python spokedpy.py quota show [--author NAME] [--namespace NAME] [--format text|json]
+    python spokedpy.py trends d3 [--label etl] [--engine rust] [--limit 20]
+    python spokedpy.py resolve d3 [--code] [--format text|json]
+    python spokedpy.py watch d3 'a*' [--since CURSOR] [--once] [--format text|json]
+    python spokedpy.py compare --label Fibonacci stg-A stg-B [--cases cases.toml] [--repeat 3]
+    python spokedpy.py respec --engine rust --all-promoted [--workers 8] [--quarantine]
+    python spokedpy.py repl --lang rust
+    python spokedpy.py jupyter install [--sys-prefix]
+    python spokedpy.py lsp
+    python spokedpy.py migrate-headers [--dry-run] [--backup-dir DIR]
+    python spokedpy.py bench-store [--jobs 50] [--per-job 4] [--shard-width 2] [--min-rate 100]
+    python spokedpy.py backup --to /mnt/backup [--encrypt age:RECIPIENT]
+    python spokedpy.py backup verify /mnt/backup [--identity KEYFILE] [--snapshot ID]
+    python spokedpy.py lint-store [--fix [orphan,stale,…]] [--stale-days 7] [--allow-label 'etl/*']
+    python spokedpy.py openapi [--out openapi.json] [--client spokedpy_client/api.py]
+    python spokedpy.py completions bash|zsh|fish
+    python spokedpy.py man
+
+Subcommands:
+    doctor       Check engine toolchains, data paths, and credentials, and
+                 print an actionable fix for everything that is missing.
+    toolchain    Provision pinned toolchains (rustup / pyenv / nvm) into the
+                 SpokedPy-managed prefix; engines use them automatically.
+    vendor       Snapshot crates.io dependencies so cargo builds offline.
+    config       Show the layered spokedpy.toml configuration; --resolved
+                 reports which layer (database / env / project / user /
+                 system / default) set each value.
+    serve        Run the web interface and REST API; --ui also mounts a
+                 store browser at /ui (slot grid, snippet detail, promotion
+                 history, rollback / approve buttons); --read-only opens
+                 the store read-only: promoted snippets resolve and run,
+                 every change is refused (HTTP 403).
+    promote      Promote staged snippets through the running server's
+                 pipeline; --force approves a failed verdict first, which
+                 profiles with gates.allow_override = false refuse.
+                 --interactive shows the header, diff against the slot's
+                 current snippet, gate results and spec output, then asks
+                 to approve, reject, or edit the label first.
+                 --gate NAME runs a built-in gate (fuzz, mutation,
+                 coverage) first; a deny blocks the promotion like any
+                 configured gate.
+                 --at TIME / --window NAME holds the approved promotion on
+                 the server until then ([gates] windows, UTC); due
+                 promotions are applied together, and --cancel releases
+                 the hold.
+                 --impact first lists what references the slot: promoted
+                 snippets declaring it in an ``# imports: d3`` comment,
+                 subscribed slots and mesh peers, and held promotions
+                 importing it; dependents refuse the promotion without
+                 --force.
+    show         A snippet's phase, slot and spec result, broken down per
+                 spec case (pass/fail, duration, first differing line);
+                 takes staging ids (from the server) or promoted files.
+                 --format junit writes JUnit XML for CI.
+    stage        Stage a snippet file on the running server and dry-run it
+                 (with its spec sidecar, if any, after the cases of its
+                 label's default spec, data/specs/<label>/default.spec.toml).
+                 --meta KEY=VALUE attaches custom metadata, kept through
+                 promotion as ``x-KEY:`` header lines.  Without --lang the language is detected
+                 (extension, #! line, syntax probes) and recorded with its
+                 confidence as x-lang-detected-by / x-lang-confidence; an
+                 ambiguous file is refused (exit 2).  A promoted file,
+                 edited or not, is staged with its staging_id as the
+                 parent (--parent names another).  Exits 1 if the
+                 dry-run fails.
+    ingest       Stage every source file of a directory (a pre-spokedpy
+                 snippet collection) on the running server: the language
+                 detected per file as for stage (--lang for all),
+                 the label from --label-from, spec sidecars attached and
+                 the file's path kept as x-ingested-from; prints (and
+                 --report writes) what was staged, failed or skipped.
+                 --dry-run only lists.  Exits 1 if any staging failed.
+    list         The server's staged and recent snippets, newest first by
+                 store sequence number (#seq), with their created / promoted
+                 time (UTC); --meta KEY=VALUE (VALUE a glob, or just KEY)
+                 and --search TEXT filter them.  A slot whose snippet fails
+                 revalidation shows DEGRADED, with why and since when.
+    lineage      The snippets a snippet was staged from — re-staged,
+                 edited and staged again, ingested: each records its
+                 ``parent:`` — newest first back to the first version, each
+                 with the diff from its parent; --no-diff lists the chain.
+    spec         check: parse a *.spec.toml sidecar (or a snippet file's)
+                 and print its cases and spec_hash.
+                 run: run a snippet file's spec cases on the server,
+                 after those its label (--label, default the file name)
+                 inherits; snapshot cases are diffed against their golden
+                 files, and --update-snapshots rewrites those from the new
+                 output.
+                 --follow streams compiler and program output live, with
+                 timed compile / spec started and finished markers.
+                 mutate: re-run operator/constant mutations of a staged
+                 snippet and report how many its spec run kills.
+    failures     show: print the failure bundle a failed dry-run left under
+                 data/failures/<staging_id>/ — compiler diagnostics,
+                 stdout/stderr, spec fixture inputs, exit status and an
+                 environment summary.  Reads the disk; no server needed.
+    quota        show: the server's [quota] limits (snippets staged at
+                 once, dry-run minutes per UTC day) and what each author
+                 and namespace (a label's first ``/`` segment) uses of
+                 them; staging past one is refused (exit 17).
+    trends       spec_time history of a slot per (label, engine): a
+                 sparkline, median / σ, and every run, with the runs more
+                 than [notify] anomaly_sigmas σ above the rolling median
+                 marked (the server also sends a spec_anomaly notification).
+    resolve      The promoted file a slot holds now (path, code_hash, label,
+                 engine), from the snippets dir and audit log — what
+                 slot_resolver.SlotResolver gives embedding services.
+                 --code prints the body instead.  No server needed; the
+                 slot index is kept in [store] index_path (not written
+                 for a read_only store).
+    watch        Follow the running server's slot changes (promotions and
+                 rollbacks into the given slots, fnmatch patterns; all
+                 slots if none), one line per change, by long-polling
+                 /api/slots/watch.  --once exits after the first change.
+    compare      Run two snippets (staged, or promoted) on the same spec
+                 cases in the server's sandbox, interleaved, and print them
+                 side by side: pass/fail, median time, peak memory (Python)
+                 and whether their outputs match, then which one to promote.
+                 --cases defaults to the candidates' own spec suite.
+    respec       Re-run the specs of every promoted snippet of an engine
+                 (or the given staging ids) under the toolchain installed
+                 now, in parallel, and list the ones that no longer pass;
+                 --quarantine rolls those back; otherwise their slots turn
+                 DEGRADED and snippets importing them are not promoted until
+                 they pass again.  Exits 1 if any fail.
+    repl         Type snippet bodies and run them in the staging sandbox
+                 on the running server; :stage <label> [slot] stages the
+                 buffer for review.
+    jupyter      Register the SpokedPy Jupyter kernel: cells run in the
+                 staging sandbox and ``%%spoked stage --label X --slot d3``
+                 stages a cell (needs ipykernel).
+    lsp          Language server (stdio) for promoted snippet files:
+                 banner/field checks, stale code_hash, slot conflicts, and
+                 "re-stage" / "show promoted predecessor" code actions.
+    migrate-headers
+                 Rewrite every promoted file in the snippets dir to the
+                 current banner layout (banner_version), after copying
+                 them to a backup dir; files edited since promotion are
+                 skipped and listed.  Exits 1 if any were skipped.
+    bench-store  Run parallel staging jobs (stage → dry-run → promote →
+                 roll back, with a stub engine) against a throwaway store
+                 and report promotions/s, p50/p95 latency, shard dirs and
+                 audit-log writes.  Exits 1 below --min-rate / above
+                 --max-p95, or on any error.
+    backup       Snapshot the store (snippets, audit log, checkpoint,
+                 failures, trends, trash, policy files) into a backup dir:
+                 incremental and content-addressed, so only changed chunks
+                 are written; --encrypt age:RECIPIENT encrypts them with
+                 age.  verify checks every snapshot's chunks (and, for a
+                 plain backup or with --identity, their contents); exits 1
+                 if any is damaged.  No server needed.
+    lint-store   Check the store on disk: dead slots (bound or locked in
+                 the checkpoint, no promoted file), orphans (a promoted
+                 file the checkpoint does not bind), labels promoted in
+                 more than one slot of an engine (--allow-label GLOB
+                 accepts them) and snippets staged and never referenced
+                 since (--stale-days).  --fix [CLASS,…] repairs them —
+                 drops the binding, rolls back the orphan or older
+                 duplicates, rejects the stale snippet; run it with the
+                 server stopped.  Exits 1 if findings remain.
+    openapi      Print (or --out FILE) the OpenAPI document of the server's
+                 HTTP API, generated from its routes — no server needed;
+                 --client FILE regenerates the typed async client
+                 (spokedpy_client) from it.
+    completions  Print a shell completion script; staging ids, slots,
+                 labels and profiles complete from the local audit log
+                 and state checkpoint, with or without a running server.
+    man          Print the spokedpy(1) man page (roff).
+
+--offline (or SPOKEDPY_OFFLINE=1) refuses every operation that needs the
+network with a clear error instead of hanging on a timeout.
+
+--profile NAME (or SPOKEDPY_PROFILE) overlays the [profiles.NAME] tables of
+spokedpy.toml on top of the base config, e.g. a forgiving ``dev`` loop and
+a locked-down ``prod`` pipeline with different gates, sandbox and store.
+
+--tenant NAME (or SPOKEDPY_TENANT) talks to that tenant's store on a
+multi-tenant server (/t/NAME/); SPOKEDPY_TOKEN is sent as its
+X-Spokedpy-Token.
+
+Exit status is 0 on success and 1 when a check or command fails; the other
+failure kinds have their own codes (2 usage, 3 server unreachable, 4 not
+found, 5 wrong phase, 10 store corruption, 11 read-only store, 12 slot full,
+13 slot frozen, 14 gate denied, 15 spec timeout, 16 engine missing, 17 quota
+exceeded, 18 imports a degraded slot — see visual_editor_core/errors.py).  With --format json a failing command prints
+{"success": false, "error": {"kind", "message", "exit_code", "context"}}
+on stdout.
+"""
+
+import argparse
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.abspath(__file__)))
+
+_ROOT = os.path.dirname(os.path.abspath(__file__))
+_DATA_DIR = os.path.join(_ROOT, 'data')
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SETTINGS — same DB → env → default resolution the server uses
+# ═══════════════════════════════════════════════════════════════════════════
+
+# name → (env var, default, kind)
+_PATH_SETTINGS = {
+    'snippets_dir':     ('SPOKEDPY_SNIPPETS_DIR',     os.path.join(_DATA_DIR, 'snippets'),            'dir'),
+    'audit_log':        ('SPOKEDPY_AUDIT_LOG',        os.path.join(_DATA_DIR, 'staging_audit.jsonl'), 'file'),
+    'state_checkpoint': ('SPOKEDPY_STATE_CHECKPOINT', os.path.join(_DATA_DIR, 'runtime_state.json'),  'file'),
+    'toolchains_dir':   ('SPOKEDPY_TOOLCHAINS_DIR',   os.path.join(_DATA_DIR, 'toolchains'),          'dir'),
+    'vendor_dir':       ('SPOKEDPY_VENDOR_DIR',       os.path.join(_DATA_DIR, 'vendor'),              'dir'),
+    'plugins_dir':      ('SPOKEDPY_PLUGINS_DIR',      os.path.join(_DATA_DIR, 'plugins'),             'dir'),
+    'failures_dir':     ('SPOKEDPY_FAILURES_DIR',     os.path.join(_DATA_DIR, 'failures'),            'dir'),
+    'trends_log':       ('SPOKEDPY_TRENDS_LOG',       os.path.join(_DATA_DIR, 'spec_trends.jsonl'),   'file'),
+    'trash_dir':        ('SPOKEDPY_TRASH_DIR',        os.path.join(_DATA_DIR, 'trash'),               'dir'),
+    'specs_dir':        ('SPOKEDPY_SPECS_DIR',        os.path.join(_DATA_DIR, 'specs'),               'dir'),
+    'index_path':       ('SPOKEDPY_INDEX_PATH',       os.path.join(_DATA_DIR, 'store_index.sqlite'),  'file'),
+    'policy_file':      ('SPOKEDPY_POLICY_FILE',      os.path.join(_DATA_DIR, 'promotion_policy.toml'), 'file'),
+    'constraints_file': ('SPOKEDPY_CONSTRAINTS_FILE', os.path.join(_DATA_DIR, 'slot_constraints.toml'), 'file'),
+    'owners_file':      ('SPOKEDPY_OWNERS_FILE',      os.path.join(_DATA_DIR, 'OWNERS'),              'file'),
+}
+
+# name → (env var, feature that needs it)
+_SECRET_SETTINGS = {
+    'ai_api_key': ('SPOKEDPY_AI_API_KEY', 'the AI chat agent'),
+}
+
+
+def _setting(key: str, env_var: str, default: str) -> str:
+    """Resolve a setting without letting a broken database abort the CLI."""
+    try:
+        from web_interface.project_db import resolve_setting
+        return resolve_setting(key, env_var, default)
+    except Exception:
+        return os.environ.get(env_var, '').strip() or default
+
+
+def _db_path() -> str:
+    env_path = os.environ.get('SPOKEDPY_DB_PATH', '').strip()
+    return env_path or os.path.join(_ROOT, 'web_interface', 'projects.db')
+
+
+def _toolchain_manager():
+    from visual_editor_core.toolchain_manager import ToolchainManager
+    env_var, default, _ = _PATH_SETTINGS['toolchains_dir']
+    return ToolchainManager(_setting('toolchains_dir', env_var, default))
+
+
+def _server_url(override: str = '') -> str:
+    """Base URL of the running server, from --server or the host/port settings
+    (under /t/<tenant> with --tenant)."""
+    tenant = os.environ.get('SPOKEDPY_TENANT', '').strip()
+    suffix = f"/t/{tenant}" if tenant else ''
+    if override:
+        return override.rstrip('/') + suffix
+    host = _setting('host', 'SPOKEDPY_HOST', '0.0.0.0')
+    if host in ('0.0.0.0', '::', ''):
+        host = '127.0.0.1'
+    return f"http://{host}:{_setting('port', 'SPOKEDPY_PORT', '5002')}{suffix}"
+
+
+def _token_headers() -> dict:
+    """The tenant token (SPOKEDPY_TOKEN) and reviewer token (SPOKEDPY_REVIEWER_TOKEN)
+    headers for server requests, if set."""
+    headers = {}
+    token = os.environ.get('SPOKEDPY_TOKEN', '').strip()
+    if token:
+        headers['X-Spokedpy-Token'] = token
+    reviewer = os.environ.get('SPOKEDPY_REVIEWER_TOKEN', '').strip()
+    if reviewer:
+        headers['Authorization'] = f'Bearer {reviewer}'
+    return headers
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# COMMANDS
+# ═══════════════════════════════════════════════════════════════════════════
+
+def cmd_doctor(args) -> int:
+    """Run the engine doctor and print the report."""
+    from visual_editor_core.engine_doctor import run_doctor
+
+    # Report the binaries the server will actually use.
+    _toolchain_manager().activate()
+
+    languages = [l for l in (args.engine or '').split(',') if l.strip()]
+
+    paths = {'db_path': (_db_path(), 'file', 'SPOKEDPY_DB_PATH')}
+    for name, (env_var, default, kind) in _PATH_SETTINGS.items():
+        paths[name] = (_setting(name, env_var, default), kind, env_var)
+
+    secrets = {}
+    for name, (env_var, feature) in _SECRET_SETTINGS.items():
+        secrets[name] = (_setting(name, env_var, ''), env_var, feature)
+
+    # Engines named explicitly on the command line are required to be present.
+    report = run_doctor(paths=paths, secrets=secrets,
+                        languages=languages or None, required=languages,
+                        plugins_dir=paths['plugins_dir'][0])
+
+    if args.format == 'json':
+        print(json.dumps(report.to_dict(), indent=2))
+    else:
+        print(report.format_text())
+    return 0 if report.ok else 1
+
+
+def cmd_toolchain_install(args) -> int:
+    """Install one or more pinned toolchains into the managed prefix."""
+    from visual_editor_core.offline import OfflineError
+    from visual_editor_core.toolchain_manager import ToolchainError
+
+    manager = _toolchain_manager()
+    status = 0
+    for spec in args.specs:
+        try:
+            if args.dry_run:
+                for step in manager.plan(spec):
+                    print(step.render())
+                continue
+            print(f"Installing {spec} into {manager.prefix} …", flush=True)
+            tc = manager.install(spec)
+            print(f"  ✔ {tc.spec} via {tc.manager} → {tc.bin_dir}")
+        except (ToolchainError, OfflineError) as exc:
+            print(f"  ✘ {spec}: {exc}", file=sys.stderr)
+            status = 1
+    return status
+
+
+def cmd_toolchain_list(args) -> int:
+    """List toolchains recorded in the managed prefix."""
+    manager = _toolchain_manager()
+    installed = manager.installed()
+    if args.format == 'json':
+        print(json.dumps({'prefix': manager.prefix,
+                          'toolchains': [dict(vars(t), spec=t.spec) for t in installed]},
+                         indent=2))
+        return 0
+    if not installed:
+        print(f"No managed toolchains in {manager.prefix}")
+        return 0
+    for tc in installed:
+        print(f"{tc.spec:<20} {tc.manager:<7} {tc.bin_dir}")
+    return 0
+
+
+def cmd_vendor(args) -> int:
+    """Vendor cargo dependencies, or show the current snapshot."""
+    from visual_editor_core import offline
+
+    snapshot = offline.CargoVendor(offline.vendor_dir())
+    if args.status or not args.manifests:
+        status = snapshot.status()
+        if args.format == 'json':
+            print(json.dumps(status, indent=2))
+        elif not status['present']:
+            print(f"No vendored snapshot in {snapshot.root}")
+        else:
+            print(f"{status['crate_count']} crate(s) in {snapshot.crates_dir}")
+            for manifest in status.get('manifests', []):
+                print(f"  from {manifest}")
+        return 0
+    try:
+        print(f"Vendoring into {snapshot.root} …", flush=True)
+        status = snapshot.sync(args.manifests)
+    except (offline.OfflineError, ValueError) as exc:
+        return _fail(args, exc)
+    print(f"  ✔ {status['crate_count']} crate(s); engines use {snapshot.cargo_home} while offline")
+    return 0
+
+
+def _mask(key: str, value: str) -> str:
+    from web_interface.config_layers import CONFIG_SCHEMA
+    meta = CONFIG_SCHEMA.get(key)
+    return '********' if meta and meta.secret and value else value
+
+
+def cmd_config_show(args) -> int:
+    """Print the merged config files, or every setting with its winning layer."""
+    from web_interface.config_layers import (
+        CONFIG_SCHEMA, active_profile, available_profiles, effective_layers, explain,
+        layer_paths, load_layers,
+    )
+
+    base = load_layers()
+    for cf in base:
+        for dotted in cf.unknown:
+            print(f"warning: {cf.path}: unknown key '{dotted}'", file=sys.stderr)
+    layers = effective_layers()
+    profile, profiles = active_profile(), available_profiles(base)
+
+    if args.resolved:
+        try:
+            from web_interface.project_db import get_all_settings
+            db = get_all_settings()
+        except Exception:
+            db = {}
+        rows = [explain(key, meta.env, meta.default, db.get(key))
+                for key, meta in CONFIG_SCHEMA.items()]
+        for row in rows:
+            row['value'] = _mask(row['key'], row['value'])
+            for layer in row['layers']:
+                layer['value'] = _mask(row['key'], layer['value'])
+        if args.format == 'json':
+            print(json.dumps(rows, indent=2))
+            return 0
+        print(f"# profile  {profile or '(none)'}")
+        width = max(len(k) for k in CONFIG_SCHEMA)
+        for row in rows:
+            origin = f"  ({row['origin']})" if row['origin'] else ''
+            print(f"{row['key']:<{width}}  {row['value']!r:<40} {row['source']}{origin}")
+        return 0
+
+    loaded = {cf.path for cf in base}
+    files = [{'layer': layer, 'path': path, 'loaded': path in loaded}
+             for layer, path in layer_paths()]
+    merged = {}
+    for cf in layers:
+        merged.update(cf.raw)
+    if args.format == 'json':
+        print(json.dumps({'files': files, 'profile': profile, 'profiles': profiles,
+                          'values': {CONFIG_SCHEMA[k].path: _mask(k, v) for k, v in merged.items()}},
+                         indent=2))
+        return 0
+    for f in files:
+        print(f"# {f['layer']:<8} {f['path']}{'' if f['loaded'] else '  (not found)'}")
+    if profiles:
+        print(f"# profile  {profile or '(none)'}  — available: {', '.join(profiles)}")
+    section = None
+    for key in CONFIG_SCHEMA:
+        if key not in merged:
+            continue
+        table, _, name = CONFIG_SCHEMA[key].path.partition('.')
+        if table != section:
+            print(f"\n[{table}]")
+            section = table
+        print(f"{name} = {json.dumps(_mask(key, merged[key]))}")
+    return 0
+
+
+def cmd_serve(args) -> int:
+    """Run the web interface (and with --ui, the store browser at /ui)."""
+    if args.read_only:
+        # Must be set before the import: web_interface.app initialises the
+        # runtime (and opens the store) when it is imported.  The environment
+        # variable is for tools that read the setting; the runtime flag is what
+        # keeps a read_only = 0 row in the settings DB from overriding it.
+        os.environ['SPOKEDPY_READ_ONLY'] = '1'
+        from web_interface import runtime
+        runtime.read_only_forced = True
+    from web_interface.app import run_server
+    run_server(host=args.host or None, port=args.port, ui=args.ui or None)
+    return 0
+
+
+def cmd_promote(args) -> int:
+    """Promote staged snippets through the running server's pipeline."""
+    import requests
+
+    base = _server_url(args.server)
+    if args.gate:
+        try:
+            denied = _run_gates(base, args.staging_ids, args.gate, args.seconds, args.min)
+        except requests.RequestException as exc:
+            return _fail(args, exc)
+    else:
+        denied = set()
+    if args.cancel:
+        return _cancel_scheduled(base, args.staging_ids, args.reason)
+    if args.interactive:
+        return _promote_interactive(base, args.staging_ids)
+    status = 0
+    for staging_id in args.staging_ids:
+        if staging_id in denied and not args.force:
+            print(f"  ✘ {staging_id}: not promoted — a gate denied it", file=sys.stderr)
+            status = 1
+            continue
+        try:
+            if args.impact and not _impact_allows(base, staging_id, args.force):
+                status = 1
+                continue
+            if args.force:
+                resp = requests.post(f"{base}/api/staging/verdict/{staging_id}",
+                                     json={'action': 'approve', 'reason': args.reason},
+                                     headers=_token_headers(), timeout=30)
+                if not resp.json().get('success'):
+                    raise ValueError(resp.json().get('error', f"HTTP {resp.status_code}"))
+            if args.at or args.window:
+                body = {'at': args.at} if args.at else {'window': args.window}
+                schedule = _api('POST', f"{base}/api/staging/schedule/{staging_id}",
+                                json=body)['schedule']
+                print(f"  ⏲ {staging_id} → {schedule['slot']} at {schedule['iso_at']}"
+                      f" (cancel with: promote --cancel {staging_id})")
+                continue
+            resp = requests.post(f"{base}/api/staging/promote/{staging_id}",
+                                 headers=_token_headers(), timeout=120)
+            data = resp.json()
+            if not data.get('success'):
+                raise ValueError(data.get('error', f"HTTP {resp.status_code}"))
+        except requests.RequestException as exc:
+            return _fail(args, exc, staging_id)
+        except ValueError as exc:
+            status = _fail(args, exc, staging_id)
+            continue
+        snippet = data['snippet']
+        print(f"  ✔ {staging_id} → {snippet['reserved_address']} ({snippet['saved_file_path']})")
+    return status
+
+
+def _impact_allows(base: str, staging_id: str, force: bool) -> bool:
+    """Print what references the snippet's slot; False if dependents block it (no --force)."""
+    from visual_editor_core.promotion_impact import Impact, format_impact
+
+    impact = Impact.from_dict(_api('GET', f"{base}/api/staging/impact/{staging_id}")['impact'])
+    print(format_impact(impact))
+    if impact.has_dependents and not force:
+        print(f"  ✘ {staging_id}: not promoted — {len(impact.dependents)} dependent(s) import "
+              f"{impact.slot} (--force to promote anyway)", file=sys.stderr)
+        return False
+    return True
+
+
+def _cancel_scheduled(base: str, staging_ids, reason: str = '') -> int:
+    import requests
+
+    status = 0
+    for staging_id in staging_ids:
+        try:
+            _api('DELETE', f"{base}/api/staging/schedule/{staging_id}", json={'reason': reason})
+        except requests.RequestException as exc:
+            return _fail(None, exc, staging_id, server=base)
+        except ValueError as exc:
+            status = _fail(None, exc, staging_id)
+            continue
+        print(f"  ✔ {staging_id}: scheduled promotion cancelled (still PASSED)")
+    return status
+
+
+def _run_gates(base: str, staging_ids, gates, seconds, minimum=None) -> set:
+    """Run ``--gate`` checks on the server; returns the ids a gate denied (or could not check)."""
+    denied = set()
+    for staging_id in staging_ids:
+        for gate in gates:
+            body = {'gate': gate, **({'seconds': seconds} if seconds else {}),
+                    **({'min': minimum} if minimum is not None else {})}
+            print(f"  … {staging_id}: running gate '{gate}'", flush=True)
+            try:
+                decision = _api('POST', f"{base}/api/staging/gate/{staging_id}",
+                                json=body)['decision']
+            except ValueError as exc:
+                print(f"  ✘ {staging_id}: {exc}", file=sys.stderr)
+                denied.add(staging_id)
+                break
+            reasons = '; '.join(decision.get('reasons') or [])
+            mark = '✔' if decision['allow'] else '✘'
+            print(f"  {mark} {gate}" + (f" — {reasons}" if reasons else ''))
+            if not decision['allow']:
+                denied.add(staging_id)
+                break
+    return denied
+
+
+def _api(method: str, url: str, **kwargs) -> dict:
+    """Call the server; raise SpokedError (a ValueError) with its error kind on failure."""
+    import requests
+    from visual_editor_core.errors import SpokedError
+
+    kwargs.setdefault('timeout', 120)
+    kwargs['headers'] = {**_token_headers(), **kwargs.get('headers', {})}
+    resp = requests.request(method, url, **kwargs)
+    data = resp.json()
+    if not data.get('success'):
+        raise SpokedError.from_response(data, resp.status_code)
+    return data
+
+
+def _request_errors() -> tuple:
+    """requests' exception base, when requests is installed (server commands need it)."""
+    try:
+        import requests
+    except ImportError:
+        return ()
+    return (requests.RequestException,)
+
+
+def _fail(args, exc: BaseException, prefix: str = '', server: str = '') -> int:
+    """Report a failed command and return its exit code (visual_editor_core.errors).
+
+    The error is a ``✘`` line on stderr, or with ``--format json`` a JSON
+    object on stdout; ``prefix`` (a staging id, a path) leads the message.
+    """
+    from visual_editor_core.errors import ErrorKind, SpokedError, classify
+
+    if isinstance(exc, _request_errors()):
+        server = server or _server_url(getattr(args, 'server', '') or '')
+        error = SpokedError(ErrorKind.SERVER_UNREACHABLE, f"cannot reach {server} — {exc}",
+                            server=server)
+    elif isinstance(exc, OSError) and not isinstance(exc, SpokedError):
+        kind = ErrorKind.NOT_FOUND if isinstance(exc, FileNotFoundError) else ErrorKind.FAILED
+        error = SpokedError(kind, str(exc), path=exc.filename or '')
+    else:
+        error = classify(exc)
+    if prefix:
+        error = SpokedError(error.kind, f"{prefix}: {error.message}", **error.context)
+    if getattr(args, 'format', '') == 'json':
+        print(json.dumps({'success': False, 'error': error.to_dict()}, indent=2))
+    else:
+        print(f"  ✘ {error.message}", file=sys.stderr)
+    return error.exit_code
+
+
+def _api_stream(method: str, url: str, on_event, **kwargs) -> dict:
+    """Call a streaming (NDJSON) endpoint, passing each event to ``on_event``;
+    returns the final ``result`` event.  Errors raise SpokedError like _api."""
+    import requests
+    from visual_editor_core.errors import SpokedError
+
+    kwargs['headers'] = {**_token_headers(), **kwargs.get('headers', {})}
+    with requests.request(method, url, timeout=120, stream=True, **kwargs) as resp:
+        if 'ndjson' not in resp.headers.get('Content-Type', ''):
+            raise SpokedError.from_response(resp.json(), resp.status_code)
+        for line in resp.iter_lines(decode_unicode=True):
+            if not line:
+                continue
+            event = json.loads(line)
+            if event['event'] == 'error':
+                raise SpokedError.from_response(event)
+            if event['event'] == 'result':
+                return event
+            on_event(event)
+    raise ValueError('stream ended without a result')
+
+
+def _follow_printer(out):
+    """An on_event callback printing live output with timed phase markers."""
+    started = {}
+
+    def stamp(event):
+        started.setdefault('t0', event['at'])
+        return f"[{event['at'] - started['t0']:7.2f}s]"
+
+    def on_event(event):
+        kind = event['event']
+        if kind == 'output':
+            mark = '│' if event['stream'] == 'stdout' else '┃'
+            for line in event['text'].splitlines() or ['']:
+                print(f"            {mark} {line}", file=out)
+        elif kind == 'spec_started':
+            print(f"  {stamp(event)} spec started — {event.get('cases', 0)} case(s)", file=out)
+        elif kind == 'spec_finished':
+            print(f"  {stamp(event)} spec finished — {'PASS' if event['success'] else 'FAIL'} "
+                  f"in {event.get('spec_time', 0.0):.4f}s", file=out)
+        elif kind == 'case_started':
+            print(f"  {stamp(event)} case {event['name']} started", file=out)
+        elif kind == 'case_finished':
+            from visual_editor_core.seccomp import spec_result
+            print(f"  {stamp(event)} case {event['name']} finished — "
+                  f"{spec_result(event['passed'], event.get('denied'))} "
+                  f"{event['duration']:.4f}s", file=out)
+        elif kind == 'process_started':
+            print(f"  {stamp(event)} {event['phase']} started ({event['process']})", file=out)
+        elif kind == 'process_finished':
+            status = 'timed out' if event.get('exit_code') is None else f"exit {event['exit_code']}"
+            print(f"  {stamp(event)} {event['phase']} finished ({event['process']}) — "
+                  f"{event['duration']:.4f}s, {status}", file=out)
+        elif kind == 'gate':
+            print(f"  {stamp(event)} gate {event['gate']} — {'allow' if event['allow'] else 'deny'}",
+                  file=out)
+        out.flush()
+
+    return on_event
+
+
+def _print_review(review: dict):
+    snippet = review['snippet']
+    print(review['header'].rstrip())
+    print(f"\n  phase: {snippet['phase']}")
+
+    gates = snippet.get('gate_results') or []
+    print(f"\n── gates ({len(gates) or 'none configured'})")
+    for gate in gates:
+        mark = '✔' if gate['allow'] else '✘'
+        reasons = '; '.join(gate.get('reasons') or [])
+        print(f"  {mark} {gate['gate']}" + (f" — {reasons}" if reasons else ''))
+
+    violations = review.get('constraints') or []
+    if violations:
+        print(f"\n── slot {snippet['reserved_address']} constraints not met (promotion will be refused)")
+        for v in violations:
+            print(f"  ✘ {v}")
+
+    owners = snippet.get('required_approvers') or []
+    if owners:
+        print(f"\n── owners: approval needed from {', '.join(owners)}")
+
+    cases = snippet.get('spec_cases') or []
+    if cases:
+        print(f"\n── spec cases ({sum(1 for c in cases if c['passed'])}/{len(cases)} passed)")
+        for c in cases:
+            print(f"  {'✔' if c['passed'] else '✘'} {c['name']} ({c['duration']:.4f}s)"
+                  + (f" — {c['first_diff']}" if c.get('first_diff') else ''))
+
+    print('\n── spec output')
+    output = (snippet.get('spec_output') or '').rstrip()
+    print(_indent(output[:2000] or '(no output)'))
+    if snippet.get('spec_error'):
+        print('\n── spec error')
+        print(_indent(snippet['spec_error'].rstrip()[:2000]))
+
+    current = review.get('current')
+    where = (f"{current['address']} ({current['staging_id']}, {current['label']})"
+             if current else 'empty slot')
+    print(f"\n── diff against {where}")
+    print(review['diff'].rstrip() or '  (identical)')
+
+
+def _indent(text: str) -> str:
+    return '\n'.join(f"  {line}" for line in text.splitlines())
+
+
+def _promote_interactive(base: str, staging_ids) -> int:
+    """Review each snippet and ask before promoting it."""
+    import requests
+
+    if not sys.stdin.isatty():
+        print('spokedpy: promote --interactive needs a terminal', file=sys.stderr)
+        return 2
+    status = 0
+    for staging_id in staging_ids:
+        try:
+            review = _api('GET', f"{base}/api/staging/review/{staging_id}")
+            while True:
+                _print_review(review)
+                choice = input('\n[a]pprove  [r]eject  [e]dit label  [q]uit > ').strip().lower()
+                if choice in ('a', 'approve'):
+                    if review['snippet']['phase'] == 'failed':
+                        reason = input('Override reason: ').strip()
+                        _api('POST', f"{base}/api/staging/verdict/{staging_id}",
+                             json={'action': 'approve', 'reason': reason})
+                    snippet = _api('POST', f"{base}/api/staging/promote/{staging_id}")['snippet']
+                    print(f"  ✔ {staging_id} → {snippet['reserved_address']} "
+                          f"({snippet['saved_file_path']})")
+                    break
+                if choice in ('r', 'reject'):
+                    reason = input('Rejection reason: ').strip()
+                    _api('POST', f"{base}/api/staging/verdict/{staging_id}",
+                         json={'action': 'reject', 'reason': reason})
+                    print(f"  ✘ {staging_id} rejected")
+                    break
+                if choice in ('e', 'edit'):
+                    label = input(f"New label [{review['snippet']['label']}]: ").strip()
+                    if label:
+                        _api('POST', f"{base}/api/staging/label/{staging_id}", json={'label': label})
+                        review = _api('GET', f"{base}/api/staging/review/{staging_id}")
+                    continue
+                if choice in ('q', 'quit'):
+                    print(f"  · {staging_id} left staged")
+                    break
+        except EOFError:
+            print(f"\n  · {staging_id} left staged")
+            return status
+        except requests.RequestException as exc:
+            return _fail(None, exc, staging_id, server=base)
+        except ValueError as exc:
+            status = _fail(None, exc, staging_id)
+    return status
+
+
+def _snippet_from_file(path: str) -> dict:
+    """The snippet fields ``show`` needs, read back from a promoted file's header."""
+    from visual_editor_core.snippet_staging import parse_file_header
+
+    with open(path, 'r', encoding='utf-8') as f:
+        header = parse_file_header(f.read())
+    if header is None:
+        raise ValueError(f"{path} is not a promoted snippet file")
+    fields = header.fields
+    try:
+        spec_time = float(fields.get('spec_time', '0').rstrip('s'))
+    except ValueError:
+        spec_time = 0.0
+    return {
+        'staging_id': fields.get('staging_id', ''), 'label': fields.get('label', ''),
+        'language': fields.get('language', ''), 'phase': 'promoted',
+        'reserved_address': fields.get('slot', '').split(' ')[0],
+        'spec_success': fields.get('spec_result') == 'PASS', 'spec_execution_time': spec_time,
+        'spec_hash': fields.get('spec_hash', ''), 'spec_cases': header.spec_cases,
+        'saved_file_path': path,
+    }
+
+
+def _print_show(snippet: dict):
+    print(f"  {snippet['staging_id']} {snippet['label']} ({snippet['language']}, "
+          f"{snippet.get('reserved_address') or '—'}) — {snippet['phase']}")
+    cases = snippet.get('spec_cases') or []
+    if not cases:
+        from visual_editor_core.seccomp import spec_result
+        mark = '✔ PASS' if snippet.get('spec_success') else \
+            '✘ ' + spec_result(False, snippet.get('spec_denied'))
+        print(f"  spec: {mark} ({snippet.get('spec_execution_time', 0):.4f}s, single run)")
+        if snippet.get('spec_error'):
+            print(_indent(snippet['spec_error'].rstrip()[:2000]))
+        return
+    passed = sum(1 for c in cases if c['passed'])
+    print(f"  spec: {passed}/{len(cases)} case(s) passed"
+          + (f", spec_hash {snippet['spec_hash']}" if snippet.get('spec_hash') else ''))
+    width = max(len(c['name']) for c in cases)
+    for c in cases:
+        print(f"    {'✔' if c['passed'] else '✘'} {c['name']:<{width}}  {c['duration']:.4f}s"
+              + (f"  {c['first_diff']}" if c.get('first_diff') else ''))
+
+
+def cmd_show(args) -> int:
+    """Show snippets' spec results, per case; JUnit XML with --format junit."""
+    import requests
+
+    base = _server_url(args.server)
+    snippets, status = [], 0
+    for target in args.targets:
+        try:
+            if os.path.isfile(target):
+                snippets.append(_snippet_from_file(target))
+            else:
+                snippets.append(_api('GET', f"{base}/api/staging/snippet/{target}")['snippet'])
+        except requests.RequestException as exc:
+            return _fail(args, exc)
+        except (OSError, ValueError) as exc:
+            status = _fail(args, exc, target)
+
+    if args.format == 'junit':
+        from visual_editor_core.junit_report import junit_xml
+        sys.stdout.write(junit_xml(snippets))
+    elif args.format == 'json':
+        print(json.dumps(snippets, indent=2))
+    else:
+        for i, snippet in enumerate(snippets):
+            if i:
+                print()
+            _print_show(snippet)
+    failed = any(not s.get('spec_success') for s in snippets)
+    return 1 if status or failed else 0
+
+
+def cmd_stage(args) -> int:
+    """Stage a snippet file on the running server and dry-run it."""
+    import requests
+    from visual_editor_core.errors import spec_failure
+    from visual_editor_core.snippet_repl import engine_letter
+    from visual_editor_core.snippet_staging import parse_meta_args
+    from visual_editor_core.spec_suite import sidecar_for, spec_format
+
+    base = _server_url(args.server)
+    try:
+        meta = parse_meta_args(args.meta)
+        detection = []
+        headers = []
+        language, code = _snippet_source(args.path, args.lang, detection, headers)
+        parent = args.parent if args.parent is not None else \
+            headers[0].fields.get('staging_id', '') if headers else ''
+        body = {'engine_letter': engine_letter(language), 'language': language, 'code': code,
+                'label': args.label or os.path.splitext(os.path.basename(args.path))[0],
+                'author': args.author, 'meta': {**detection[0].meta(), **meta}}
+        if parent:
+            body['parent'] = parent
+        sidecar = sidecar_for(args.path)
+        if sidecar is not None:
+            with open(sidecar, 'r', encoding='utf-8') as f:
+                body.update(spec=f.read(), spec_format=spec_format(sidecar))
+        snippet = _api('POST', f"{base}/api/staging/queue", json=body)['snippet']
+        snippet = _api('POST', f"{base}/api/staging/speculate/{snippet['staging_id']}")['snippet']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, args.path)
+
+    error = None
+    if not snippet.get('spec_success'):
+        error = spec_failure(snippet.get('spec_error'), staging_id=snippet.get('staging_id', ''))
+    if args.format == 'json':
+        print(json.dumps({**snippet, 'error': error.to_dict()} if error else snippet, indent=2))
+    else:
+        _print_show(snippet)
+        for key, value in (snippet.get('meta') or {}).items():
+            print(f"  x-{key}: {value}")
+    return error.exit_code if error else 0
+
+
+def cmd_ingest(args) -> int:
+    """Stage every source file of a directory on the running server."""
+    import requests
+    from visual_editor_core.snippet_staging import parse_meta_args
+    from visual_editor_core.source_ingest import format_ingest, ingest
+
+    base = _server_url(args.server)
+
+    def stage(body):
+        snippet = _api('POST', f"{base}/api/staging/queue", json=body)['snippet']
+        return _api('POST', f"{base}/api/staging/speculate/{snippet['staging_id']}")['snippet']
+
+    try:
+        report = ingest(args.dir, stage, args.lang, args.label_from, args.author,
+                        parse_meta_args(args.meta), args.dry_run)
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, args.dir)
+    if args.report:
+        with open(args.report, 'w', encoding='utf-8') as f:
+            json.dump(report.to_dict(), f, indent=2)
+    if args.format == 'json':
+        print(json.dumps(report.to_dict(), indent=2))
+    else:
+        print(format_ingest(report))
+        if args.report:
+            print(f"  report: {args.report}")
+    return 1 if report.failed else 0
+
+
+def cmd_list(args) -> int:
+    """List the server's staged and recent snippets, filtered by meta / text."""
+    import requests
+    from visual_editor_core.snippet_staging import utc_timestamp
+
+    base = _server_url(args.server)
+    params = {'include_history': '1', 'limit': args.limit, 'meta': args.meta,
+              'after': args.after, 'phase': args.phase}
+    if args.search:
+        params['q'] = args.search
+    snippets: list = []
+    try:
+        while True:
+            data = _api('GET', f"{base}/api/staging/snippets", params=params)
+            if 'snippets' not in data:          # a server from before paging: one page
+                data = {'snippets': [s for s in data['active'] + data.get('history', [])
+                                     if not args.phase or s['phase'] == args.phase],
+                        'next_cursor': None}
+                data['total'] = len(data['snippets'])
+            snippets += data['snippets']
+            if not (args.all and data['next_cursor']):
+                break
+            params['after'] = data['next_cursor']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, 'list')
+    # Newest first, in the store's own order (seq), not by timestamp.
+    snippets.sort(key=lambda s: s.get('seq', 0), reverse=True)
+    total, cursor = data['total'], data['next_cursor']
+
+    if args.format == 'json':
+        print(json.dumps({'snippets': snippets, 'total': total, 'next_cursor': cursor},
+                         indent=2))
+        return 0
+    if not snippets:
+        print('  no matching snippets')
+        return 0
+    width = max(len(s['label']) for s in snippets)
+    for s in snippets:
+        meta = ' '.join(f"{k}={v}" for k, v in (s.get('meta') or {}).items())
+        when = s.get('promoted') or s.get('created', '')
+        degraded = s.get('degraded') or {}
+        print(f"  {'#' + str(s.get('seq', 0)):>6}  {s['staging_id']}  "
+              f"{s.get('reserved_address') or '—':<4} {'DEGRADED' if degraded else s['phase']:<10} "
+              f"{s['label']:<{width}}  {when}  {meta}".rstrip())
+        if degraded:
+            since = utc_timestamp(degraded.get('since', 0))
+            print(f"  {'':>6}  ⚠ fails revalidation since {since}"
+                  f"{' under ' + degraded['toolchain'] if degraded.get('toolchain') else ''}: "
+                  f"{degraded.get('reason', '')}")
+    if cursor:
+        print(f"  … {len(snippets)} of {total} shown — next page: --after {cursor}")
+    return 0
+
+
+def cmd_lineage(args) -> int:
+    """Print the chain of snippets a snippet was staged from, with diffs."""
+    import requests
+    from visual_editor_core.lineage import Ancestor, format_lineage
+
+    base = _server_url(args.server)
+    try:
+        data = _api('GET', f"{base}/api/staging/lineage/{args.staging_id}")
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, args.staging_id)
+    entries = data['lineage']
+    if args.format == 'json':
+        print(json.dumps([{k: v for k, v in e.items() if k != 'diff'} if args.no_diff else e
+                          for e in entries], indent=2))
+        return 0
+    print(format_lineage([Ancestor.from_dict(e) for e in entries], diffs=not args.no_diff))
+    return 0
+
+
+def cmd_spec_check(args) -> int:
+    """Validate a spec sidecar and print its cases and spec_hash."""
+    from visual_editor_core.snippet_staging import parse_file_header
+    from visual_editor_core.spec_suite import load_spec, sidecar_for, spec_format
+
+    path, recorded = args.path, None
+    if not spec_format(path):
+        try:
+            with open(path, 'r', encoding='utf-8') as f:
+                header = parse_file_header(f.read())
+        except OSError as exc:
+            print(f"  ✘ {exc}", file=sys.stderr)
+            return 1
+        recorded = header.fields.get('spec_hash', '') if header else None
+        path = sidecar_for(args.path)
+        if path is None:
+            print(f"  ✘ {args.path} has no .spec.toml / .spec.yaml sidecar", file=sys.stderr)
+            return 1
+    try:
+        suite = load_spec(path)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, path)
+    stale = recorded is not None and recorded != suite.spec_hash
+    if args.format == 'json':
+        print(json.dumps({'path': path, **suite.to_dict(), 'recorded_spec_hash': recorded,
+                          'stale': stale}, indent=2))
+        return 1 if stale else 0
+
+    print(f"  {path}: {len(suite.cases)} case(s), spec_hash {suite.spec_hash}")
+    for case in suite.cases:
+        expects = [f"exit {case.exit_code}"] + (['stdout'] if case.stdout is not None else [])
+        feeds = [k for k in ('args', 'stdin', 'env') if getattr(case, k)]
+        print(f"    {case.name:<28} expects {', '.join(expects)}"
+              + (f"; feeds {', '.join(feeds)}" if feeds else '') + f"; timeout {case.timeout:g}s")
+    if stale:
+        print(f"  ✘ the promoted header records spec_hash {recorded or '(none)'} — re-stage "
+              f"to promote against this spec")
+    return 1 if stale else 0
+
+
+def _snippet_source(path: str, language: str = '', detection=None, headers=None):
+    """(language, code) of a snippet file — a promoted file's body, else the whole file.
+
+    Without ``language`` it is detected (language_detect), refusing an
+    ambiguous file; ``detection`` (a list) receives the Detection, and
+    ``headers`` (a list) a promoted file's SnippetFileHeader.
+    """
+    from visual_editor_core.language_detect import detect_language
+    from visual_editor_core.snippet_staging import parse_file_header
+
+    with open(path, 'r', encoding='utf-8') as f:
+        text = f.read()
+    found = detect_language(path, text, language)
+    if detection is not None:
+        detection.append(found)
+    header = parse_file_header(text)
+    if headers is not None and header is not None:
+        headers.append(header)
+    return found.language, header.body if header is not None else text
+
+
+def cmd_spec_run(args) -> int:
+    """Run a snippet file's spec on the server; review / update snapshot cases."""
+    import difflib
+    import requests
+    from dataclasses import replace
+    from visual_editor_core.spec_suite import (
+        SpecSuite, load_spec, sidecar_for, snapshot_path, write_snapshots,
+    )
+
+    base = _server_url(args.server)
+    label = args.label or os.path.splitext(os.path.basename(args.path))[0]
+    try:
+        language, code = _snippet_source(args.path, args.lang)
+        sidecar = sidecar_for(args.path)
+        suite = load_spec(sidecar) if sidecar is not None else SpecSuite([])
+        # Snapshot stdout is compared here, so the diff can be shown and accepted.
+        sent = SpecSuite([replace(c, stdout=None) if c.snapshot else c for c in suite.cases])
+        body = {'language': language, 'code': code, 'label': label,
+                **({'spec': sent.to_dict()} if suite.cases else {})}
+        if args.follow:
+            # Keep stdout parseable for --format json.
+            out = sys.stderr if args.format == 'json' else sys.stdout
+            data = _api_stream('POST', f"{base}/api/staging/spec-run", _follow_printer(out),
+                               json={**body, 'stream': True})
+        else:
+            data = _api('POST', f"{base}/api/staging/spec-run", json=body)
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, args.path)
+
+    # The label's default spec runs first; its golden files live with it on the server.
+    inherited = set(data.get('inherited', []))
+    own = {c.name: c for c in suite.cases}
+    updates, report = {}, []
+    for result in data['cases']:
+        if result['name'] in inherited:
+            report.append({**result, 'inherited': label, 'snapshot_diff': ''})
+            continue
+        case = own[result['name']]
+        golden, diff = snapshot_path(sidecar, case.name), ''
+        if case.snapshot and result['passed'] and result['output'] != case.stdout:
+            old = (case.stdout or '').splitlines(keepends=True)
+            diff = ''.join(difflib.unified_diff(
+                old, result['output'].splitlines(keepends=True),
+                fromfile=os.path.relpath(golden) if case.stdout is not None else '/dev/null',
+                tofile='new output'))
+            if args.update_snapshots:
+                updates[case.name] = result['output']
+            else:
+                result = {**result, 'passed': False,
+                          'first_diff': 'snapshot changed' if case.stdout is not None
+                          else 'no snapshot yet'}
+        report.append({**result, 'snapshot_diff': diff})
+
+    written = write_snapshots(sidecar, updates) if updates else []
+    passed = all(r['passed'] for r in report)
+    if args.format == 'json':
+        print(json.dumps({'path': args.path, 'language': language, 'cases': report,
+                          'updated': written, 'passed': passed}, indent=2))
+        return 0 if passed else 1
+
+    print(f"  {args.path} ({language}) — {len(report)} case(s)"
+          + (f", {len(inherited)} from label '{label}'" if inherited else ''))
+    width = max(len(r['name']) for r in report)
+    for r in report:
+        print(f"    {'✔' if r['passed'] else '✘'} {r['name']:<{width}}  {r['duration']:.4f}s"
+              + ('  (label spec)' if r.get('inherited') else '')
+              + (f"  {r['first_diff']}" if r.get('first_diff') else ''))
+        if r['snapshot_diff']:
+            print('\n'.join(f"        {line}" for line in r['snapshot_diff'].rstrip('\n').split('\n')))
+    if written:
+        print(f"  ✔ updated {len(written)} snapshot(s): "
+              f"{', '.join(os.path.relpath(p) for p in written)} — review the diff above")
+    elif not passed and any(r['snapshot_diff'] for r in report):
+        print('  run with --update-snapshots to accept the new output')
+    return 0 if passed else 1
+
+
+def cmd_spec_mutate(args) -> int:
+    """Mutation-test a staged snippet's spec on the running server."""
+    import requests
+
+    base = _server_url(args.server)
+    body = {'limit': args.limit, **({'seconds': args.seconds} if args.seconds else {})}
+    try:
+        data = _api('POST', f"{base}/api/staging/mutate/{args.staging_id}", json=body)
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, args.staging_id)
+    report = data['report']
+    minimum = data['min_kill_rate'] if args.min_kill_rate is None else args.min_kill_rate
+    passed = report['run'] > 0 and report['kill_rate'] >= minimum
+    if args.format == 'json':
+        print(json.dumps({**report, 'min_kill_rate': minimum, 'passed': passed}, indent=2))
+        return 0 if passed else 1
+
+    print(f"  {report['staging_id']} ({report['language']}): spec killed "
+          f"{report['killed']}/{report['run']} mutants ({report['kill_rate']:.0%})"
+          + (f" — {report['total']} sites, {report['run']} run" if report['total'] > report['run'] else ''))
+    survivors = [m for m in report['mutants'] if m['status'] == 'survived']
+    if survivors:
+        print('  survived (the spec cannot tell these apart):')
+        for m in survivors:
+            print(f"    {m['description']:<24} {m['mutated_line']}")
+    if not report['deterministic']:
+        print('  note: output varies between runs, so only crashes and timeouts count as kills')
+    if passed:
+        print(f"  ✔ at or above the minimum kill rate ({minimum:.0%})")
+    else:
+        print(f"  ✘ below the minimum kill rate ({minimum:.0%}) — make the spec print or "
+              f"assert more of the snippet's behaviour")
+    return 0 if passed else 1
+
+
+def cmd_failures_show(args) -> int:
+    """Print the failure bundle of a failed dry-run (read from disk)."""
+    from visual_editor_core.failure_bundle import format_bundle, read_bundle
+
+    env, default, _ = _PATH_SETTINGS['failures_dir']
+    try:
+        bundle = read_bundle(_setting('failures_dir', env, default), args.staging_id)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc)
+    if args.format == 'json':
+        print(json.dumps(bundle, indent=2))
+    else:
+        print(format_bundle(bundle))
+    return 0
+
+
+def cmd_delete(args) -> int:
+    """Move snippets on the running server to the trash."""
+    import requests
+
+    base = _server_url(args.server)
+    status = 0
+    for staging_id in args.staging_ids:
+        try:
+            tombstone = _api('DELETE', f"{base}/api/staging/snippet/{staging_id}",
+                             json={'reason': args.reason, 'deleted_by': args.by})['tombstone']
+        except requests.RequestException as exc:
+            return _fail(args, exc)
+        except ValueError as exc:
+            status = _fail(args, exc, staging_id)
+            continue
+        print(f"  ✔ {staging_id} ({tombstone['phase']}) moved to the trash "
+              f"(undo with: trash restore {staging_id})")
+    return status
+
+
+def cmd_trash_list(args) -> int:
+    """Print the deleted snippets (read from disk)."""
+    from visual_editor_core.snippet_trash import format_entries, list_entries
+
+    env, default, _ = _PATH_SETTINGS['trash_dir']
+    entries = list_entries(_setting('trash_dir', env, default))
+    if args.format == 'json':
+        print(json.dumps(entries, indent=2))
+        return 0
+    print(format_entries(entries, float(_setting('trash_days', 'SPOKEDPY_TRASH_DAYS', '30'))))
+    return 0
+
+
+def cmd_trash_restore(args) -> int:
+    """Put deleted snippets back into the running server's pipeline."""
+    import requests
+
+    base = _server_url(args.server)
+    status = 0
+    for staging_id in args.staging_ids:
+        try:
+            snippet = _api('POST', f"{base}/api/staging/trash/{staging_id}/restore")['snippet']
+        except requests.RequestException as exc:
+            return _fail(args, exc)
+        except ValueError as exc:
+            status = _fail(args, exc, staging_id)
+            continue
+        print(f"  ✔ {staging_id} restored → {snippet['reserved_address']} ({snippet['phase']})")
+    return status
+
+
+def cmd_trash_purge(args) -> int:
+    """Delete trash entries for good, through the running server."""
+    import requests
+    from visual_editor_core.errors import ErrorKind, SpokedError
+
+    if not (args.staging_ids or args.expired or args.all):
+        return _fail(args, SpokedError(ErrorKind.USAGE, 'name the staging ids to purge, '
+                                       'or pass --expired or --all'), 'trash purge')
+    base = _server_url(args.server)
+    try:
+        purged = _api('POST', f"{base}/api/staging/trash/purge",
+                      json={'staging_ids': args.staging_ids, 'expired': args.expired})['purged']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, 'trash purge')
+    if args.format == 'json':
+        print(json.dumps({'purged': purged}, indent=2))
+    else:
+        print(f"  ✔ purged {len(purged)} snippet(s) from the trash")
+    return 0
+
+
+def cmd_quota_show(args) -> int:
+    """Print the running server's [quota] limits and use per author and namespace."""
+    import requests
+    from visual_editor_core.snippet_quota import format_quota
+
+    base = _server_url(args.server)
+    params = {k: v for k, v in (('author', args.author), ('namespace', args.namespace)) if v}
+    try:
+        report = _api('GET', f"{base}/api/staging/quota", params=params)['quota']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, 'quota show')
+    if args.format == 'json':
+        print(json.dumps(report, indent=2))
+    else:
+        print(format_quota(report))
+    return 0
+
+
+def cmd_trends(args) -> int:
+    """Print the spec_time series recorded for a slot."""
+    from visual_editor_core.spec_trends import TrendIndex, format_report, trend_report
+
+    env, default, _ = _PATH_SETTINGS['trends_log']
+    index = TrendIndex(_setting('trends_log', env, default))
+    sigmas = float(_setting('anomaly_sigmas', 'SPOKEDPY_ANOMALY_SIGMAS', '3'))
+    window = int(_setting('anomaly_window', 'SPOKEDPY_ANOMALY_WINDOW', '20'))
+    series = index.series(args.slot, args.label, args.engine)
+    if not series:
+        print(f"  ✘ no spec_time measurements for slot {args.slot} in {index.path}", file=sys.stderr)
+        return 1
+    reports = [trend_report(runs, sigmas, window) for runs in series.values()]
+    if args.format == 'json':
+        for r in reports:
+            r['runs'] = r['runs'][-args.limit:]
+        print(json.dumps(reports, indent=2))
+        return 0
+    print('\n\n'.join(format_report(r, args.limit) for r in reports))
+    return 0
+
+
+def cmd_resolve(args) -> int:
+    """Print the promoted snippet a slot currently holds."""
+    from visual_editor_core.slot_resolver import SlotResolver
+
+    snippets_env, snippets_default, _ = _PATH_SETTINGS['snippets_dir']
+    audit_env, audit_default, _ = _PATH_SETTINGS['audit_log']
+    index_env, index_default, _ = _PATH_SETTINGS['index_path']
+    read_only = _setting('read_only', 'SPOKEDPY_READ_ONLY', '0').strip().lower() in (
+        '1', 'true', 'yes', 'on')
+    resolver = SlotResolver(_setting('snippets_dir', snippets_env, snippets_default),
+                            _setting('audit_log', audit_env, audit_default),
+                            '' if read_only else _setting('index_path', index_env, index_default))
+    snippet = resolver.resolve_slot(args.slot)
+    if snippet is None:
+        print(f"  ✘ no promoted snippet in slot {args.slot} under {resolver.snippets_dir}",
+              file=sys.stderr)
+        return 1
+    if args.code:
+        try:
+            sys.stdout.write(snippet.read_code())
+        except (OSError, ValueError) as exc:
+            return _fail(args, exc)
+        return 0
+    if args.format == 'json':
+        print(json.dumps(snippet.to_dict(), indent=2))
+        return 0
+    print(f"  {snippet.slot}  {snippet.label}  [{snippet.engine}]  {snippet.staging_id}  "
+          f"{snippet.code_hash}  promoted {snippet.promoted}\n  {snippet.path}")
+    return 0
+
+
+def cmd_watch(args) -> int:
+    """Print slot changes from the running server as they happen."""
+    import requests
+
+    base = _server_url(args.server)
+    cursor = args.since
+    while True:
+        params = {'slots': ','.join(args.slot), 'timeout': args.poll}
+        if cursor is not None:
+            params['since'] = cursor
+        try:
+            data = _api('GET', f"{base}/api/slots/watch", params=params, timeout=args.poll + 30)
+        except requests.RequestException as exc:
+            return _fail(args, exc)
+        except ValueError as exc:
+            return _fail(args, exc, 'watch')
+        if data['missed'] and cursor is not None:
+            print(f"  ! events before cursor {data['cursor']} were missed — re-resolve the slots",
+                  file=sys.stderr)
+        for event in data['events']:
+            if args.format == 'json':
+                print(json.dumps(event))
+            else:
+                now = event['staging_id'] or 'vacant'
+                print(f"  {event['seq']:>5}  {event['slot']:<4} {event['event']:<12} "
+                      f"{event['previous']} → {now}  {event['label']}  {event['path']}")
+        sys.stdout.flush()
+        cursor = data['cursor']
+        if args.once and data['events']:
+            return 0
+
+
+def cmd_compare(args) -> int:
+    """A/B-run two candidates for a label on the running server."""
+    import requests
+    from visual_editor_core.spec_compare import format_comparison
+    from visual_editor_core.spec_suite import load_spec, parse_spec, spec_format
+
+    base = _server_url(args.server)
+    body = {'a': args.a, 'b': args.b, 'label': args.label, 'repeat': args.repeat}
+    try:
+        if args.cases:
+            if spec_format(args.cases):
+                suite = load_spec(args.cases)
+            else:
+                with open(args.cases, 'r', encoding='utf-8') as f:
+                    suite = parse_spec(f.read(), 'yaml' if args.cases.endswith(('.yaml', '.yml'))
+                                       else 'toml')
+            body['spec'] = suite.to_dict()
+        report = _api('POST', f"{base}/api/staging/compare", json=body)['report']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, 'compare')
+    if args.format == 'json':
+        print(json.dumps(report, indent=2))
+    else:
+        print(format_comparison(report))
+    return 0
+
+
+def cmd_respec(args) -> int:
+    """Re-run promoted snippets' specs on the running server, e.g. after a toolchain bump."""
+    import requests
+    from visual_editor_core.respec import format_respec
+
+    if not args.all_promoted and not args.staging_ids:
+        print('  ✘ respec: pass staging ids or --all-promoted', file=sys.stderr)
+        return 1
+    base = _server_url(args.server)
+    body = {'engine': args.engine, 'staging_ids': args.staging_ids, 'workers': args.workers,
+            'quarantine': args.quarantine}
+    try:
+        # A full engine row can take a while; let the server finish.
+        report = _api('POST', f"{base}/api/staging/respec", json=body, timeout=None)['report']
+    except requests.RequestException as exc:
+        return _fail(args, exc)
+    except ValueError as exc:
+        return _fail(args, exc, 'respec')
+    if args.format == 'json':
+        print(json.dumps(report, indent=2))
+    else:
+        print(format_respec(report))
+    return 1 if report['failing'] else 0
+
+
+def cmd_repl(args) -> int:
+    """Interactive snippet evaluation against the running server's sandbox."""
+    import requests
+    from visual_editor_core.snippet_repl import ReplError, SnippetRepl, remote_backend
+
+    try:
+        import readline  # noqa: F401  line editing + history where available
+    except ImportError:
+        pass
+
+    base = _server_url(args.server)
+    try:
+        repl = SnippetRepl(args.lang, *remote_backend(base, author=args.author))
+    except ReplError as exc:
+        print(f"spokedpy: {exc}", file=sys.stderr)
+        return 2
+    print(f"spokedpy repl — {repl.language} via {base}; blank line runs, :help for commands")
+    while True:
+        try:
+            line = input(repl.prompt)
+        except EOFError:
+            print()
+            return 0
+        except KeyboardInterrupt:
+            print('\n  (buffer kept — :clear empties it, :quit leaves)')
+            continue
+        try:
+            if not repl.feed(line):
+                return 0
+        except requests.RequestException as exc:
+            print(f"  ✘ cannot reach {base} — {exc}", file=sys.stderr)
+        except ValueError as exc:
+            print(f"  ✘ {exc}")
+
+
+def cmd_jupyter_install(args) -> int:
+    """Install the Jupyter kernelspec pointing at this server."""
+    from visual_editor_core.jupyter_kernel import install_kernelspec
+
+    base = _server_url(args.server)
+    try:
+        path = install_kernelspec(base, user=not args.sys_prefix,
+                                  prefix=sys.prefix if args.sys_prefix else None)
+    except ImportError as exc:
+        print(f"spokedpy: {exc}", file=sys.stderr)
+        return 1
+    print(f"  ✔ kernel 'spokedpy' → {path} (server {base})")
+    return 0
+
+
+def cmd_lsp(args) -> int:
+    """Serve LSP over stdio for the snippets dir."""
+    from visual_editor_core.snippet_lsp import SnippetLanguageServer, SnippetWorkspace
+    from visual_editor_core.snippet_repl import remote_backend
+
+    paths = {key: _setting(key, env, default) for key, (env, default, _)
+             in _PATH_SETTINGS.items()
+             if key in ('snippets_dir', 'audit_log', 'state_checkpoint', 'specs_dir')}
+    workspace = SnippetWorkspace(paths['snippets_dir'], paths['audit_log'], paths['state_checkpoint'],
+                                 paths['specs_dir'])
+    _evaluate, stage = remote_backend(_server_url(args.server),
+                                      author=os.environ.get('USER', 'editor'))
+    return SnippetLanguageServer(workspace, stage).serve()
+
+
+def cmd_migrate_headers(args) -> int:
+    """Bring old promoted file banners up to the current layout."""
+    from visual_editor_core.header_migration import format_migration, migrate_headers
+
+    env, default, _ = _PATH_SETTINGS['snippets_dir']
+    snippets_dir = _setting('snippets_dir', env, default)
+    try:
+        report = migrate_headers(snippets_dir, args.backup_dir, args.dry_run)
+    except OSError as exc:
+        print(f"  ✘ migrate-headers: {exc}", file=sys.stderr)
+        return 1
+    print(format_migration(report))
+    return 1 if report.skipped else 0
+
+
+def cmd_lint_store(args) -> int:
+    """Report (and with --fix, repair) dead slots, orphans, duplicate labels and stale snippets."""
+    from visual_editor_core.errors import ErrorKind, SpokedError
+    from visual_editor_core.store_lint import fix_store, format_lint, lint_store, parse_kinds
+
+    paths = {key: _setting(key, env, default) for key, (env, default, _)
+             in _PATH_SETTINGS.items() if key in ('snippets_dir', 'audit_log', 'state_checkpoint')}
+    try:
+        kinds = parse_kinds(args.fix) if args.fix else []
+        if kinds and _setting('read_only', 'SPOKEDPY_READ_ONLY', '0').strip().lower() in (
+                '1', 'true', 'yes', 'on'):
+            raise SpokedError(ErrorKind.STORE_READ_ONLY,
+                              'the store is read-only ([store] read_only) — --fix changes it')
+        report = lint_store(paths['snippets_dir'], paths['audit_log'], paths['state_checkpoint'],
+                            args.stale_days, args.allow_label)
+        if kinds:
+            fix_store(report, paths['audit_log'], kinds)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, 'lint-store')
+    if args.format == 'json':
+        print(json.dumps(report.to_dict(), indent=2))
+    else:
+        print(format_lint(report))
+    return 1 if report.unfixed else 0
+
+
+def cmd_backup(args) -> int:
+    """Write an incremental snapshot of the store into a backup dir."""
+    from visual_editor_core.errors import ErrorKind, SpokedError
+    from visual_editor_core.store_backup import STORE_PARTS, backup, format_backup
+
+    if not args.to:
+        return _fail(args, SpokedError(ErrorKind.USAGE, 'name the backup dir with --to DIR '
+                                       '(or check one with: backup verify DIR)'), 'backup')
+    parts = {}
+    for key in STORE_PARTS:
+        env, default, _ = _PATH_SETTINGS[key]
+        parts[key] = _setting(key, env, default)
+    try:
+        report = backup(parts, args.to, args.encrypt)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, 'backup')
+    if args.format == 'json':
+        print(json.dumps(report.to_dict(), indent=2))
+    else:
+        print(format_backup(report))
+    return 0
+
+
+def cmd_backup_verify(args) -> int:
+    """Check the snapshots of a backup dir."""
+    from visual_editor_core.store_backup import format_verify, verify
+
+    try:
+        report = verify(args.dir, args.snapshot, args.identity)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, 'backup verify')
+    if args.format == 'json':
+        print(json.dumps(report.to_dict(), indent=2))
+    else:
+        print(format_verify(report))
+    return 0 if report.ok else 1
+
+
+def cmd_bench_store(args) -> int:
+    """Benchmark parallel staging against a throwaway store."""
+    from visual_editor_core.store_bench import bench_store, format_bench, meets_targets
+
+    shard_width = args.shard_width
+    if shard_width is None:
+        shard_width = int(_setting('shard_width', 'SPOKEDPY_SHARD_WIDTH', '2'))
+    try:
+        report = bench_store(args.jobs, args.per_job, shard_width, args.dir)
+    except (OSError, ValueError) as exc:
+        return _fail(args, exc, 'bench-store')
+    ok = meets_targets(report, args.min_rate, args.max_p95)
+    if args.format == 'json':
+        print(json.dumps({**report.to_dict(), 'min_rate': args.min_rate,
+                          'max_p95': args.max_p95, 'ok': ok}, indent=2))
+    else:
+        print(format_bench(report, args.min_rate, args.max_p95))
+        print(f"  {'✔ targets met' if ok else '✘ targets missed'}")
+    return 0 if ok else 1
+
+
+def cmd_openapi(args) -> int:
+    """Export the server's OpenAPI document; regenerate the typed client from it."""
+    from spokedpy_client.codegen import render_client
+    from web_interface.swagger import build_openapi_spec, openapi_app
+
+    spec = build_openapi_spec(openapi_app())
+    spec['servers'] = [{'url': _server_url(args.server or ''), 'description': 'spokedpy serve'}]
+    text = json.dumps(spec, indent=2) + '\n'
+    try:
+        if args.out:
+            with open(args.out, 'w', encoding='utf-8') as f:
+                f.write(text)
+            print(f"✔ OpenAPI document → {args.out} ({sum(map(len, spec['paths'].values()))} "
+                  f"operations)")
+        elif not args.client:
+            sys.stdout.write(text)
+        if args.client:
+            with open(args.client, 'w', encoding='utf-8') as f:
+                f.write(render_client(spec))
+            print(f"✔ client → {args.client}")
+    except OSError as exc:
+        return _fail(args, exc, 'openapi')
+    return 0
+
+
+def cmd_completions(args) -> int:
+    """Print a completion script generated from the argument parser."""
+    from visual_editor_core.cli_completion import SHELLS
+
+    sys.stdout.write(SHELLS[args.shell](build_parser(), prog='spokedpy'))
+    return 0
+
+
+def cmd_man(args) -> int:
+    """Print the man page generated from the argument parser."""
+    from visual_editor_core.cli_completion import man_page
+    from web_interface.config_layers import CONFIG_SCHEMA, layer_paths
+
+    environment = [('SPOKEDPY_CONFIG', 'Project spokedpy.toml to use instead of searching upward.'),
+                   ('SPOKEDPY_PROFILE', 'Same as --profile.'),
+                   ('SPOKEDPY_TENANT', 'Same as --tenant.'),
+                   ('SPOKEDPY_TOKEN', 'Tenant token sent as X-Spokedpy-Token.')]
+    environment += sorted((meta.env, f"Overrides {meta.path} in spokedpy.toml.")
+                          for meta in CONFIG_SCHEMA.values())
+    layers = dict(layer_paths())
+    files = [(layers['system'], 'System configuration layer.'),
+             (layers['user'].replace(os.path.expanduser('~'), '~', 1), 'User configuration layer.'),
+             ('spokedpy.toml', 'Project configuration layer: the nearest one above the working '
+                               'directory, or $SPOKEDPY_CONFIG.')]
+    sys.stdout.write(man_page(build_parser(), prog='spokedpy',
+                              environment=environment, files=files))
+    return 0
+
+
+def cmd_complete(args) -> int:
+    """Hidden callback for the completion scripts: one candidate per line."""
+    from visual_editor_core.cli_completion import complete, local_index
+
+    profiles = []
+    index = []
+    try:
+        if args.kind == 'profile':
+            from web_interface.config_layers import available_profiles
+            profiles = available_profiles()
+        else:
+            audit_env, audit_default, _ = _PATH_SETTINGS['audit_log']
+            state_env, state_default, _ = _PATH_SETTINGS['state_checkpoint']
+            index = local_index(_setting('audit_log', audit_env, audit_default),
+                                _setting('state_checkpoint', state_env, state_default))
+        for value, desc in complete(args.kind, args.prefix, index, profiles):
+            print(f"{value}\t{desc}")
+    except Exception:
+        # Never spill a traceback into the user's prompt.
+        return 1
+    return 0
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# ARGUMENT PARSING
+# ═══════════════════════════════════════════════════════════════════════════
+
+def build_parser() -> argparse.ArgumentParser:
+    from visual_editor_core.promotion_gates import BUILTIN_GATES
+    from visual_editor_core.store_bench import DEFAULT_MIN_RATE
+
+    parser = argparse.ArgumentParser(
+        prog='spokedpy',
+        description='SpokedPy command-line interface.',
+    )
+    parser.add_argument('--offline', action='store_true',
+                        help='refuse every operation that needs the network')
+    parser.add_argument('--profile', default=None, metavar='NAME',
+                        help='apply the [profiles.NAME] tables from spokedpy.toml')
+    parser.add_argument('--server', default='', metavar='URL',
+                        help='SpokedPy server to talk to (default: host/port settings)')
+    parser.add_argument('--tenant', default=None, metavar='NAME',
+                        help="use that tenant's store on a multi-tenant server")
+    sub = parser.add_subparsers(dest='command', metavar='<command>')
+    sub.required = True
+
+    p = sub.add_parser('doctor', help='check toolchains, paths, and credentials')
+    p.add_argument('--engine', default='',
+                   help='comma-separated engines to check (these must be installed)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_doctor)
+
+    p = sub.add_parser('toolchain', help='manage pinned engine toolchains')
+    tsub = p.add_subparsers(dest='toolchain_command', metavar='<action>')
+    tsub.required = True
+    tp = tsub.add_parser('install', help='install toolchains, e.g. rust@1.84 python@3.12.1 node@20')
+    tp.add_argument('specs', nargs='+', metavar='lang@version')
+    tp.add_argument('--dry-run', action='store_true', help='print the commands without running them')
+    tp.set_defaults(func=cmd_toolchain_install)
+    tp = tsub.add_parser('list', help='list managed toolchains')
+    tp.add_argument('--format', choices=('text', 'json'), default='text')
+    tp.set_defaults(func=cmd_toolchain_list)
+
+    p = sub.add_parser('vendor', help='snapshot cargo dependencies for offline builds')
+    p.add_argument('manifests', nargs='*', metavar='Cargo.toml')
+    p.add_argument('--status', action='store_true', help='show the current snapshot')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_vendor)
+
+    p = sub.add_parser('config', help='inspect layered spokedpy.toml configuration')
+    csub = p.add_subparsers(dest='config_command', metavar='<action>')
+    csub.required = True
+    cp = csub.add_parser('show', help='show merged config files')
+    cp.add_argument('--resolved', action='store_true',
+                    help='show every setting with the layer that set it')
+    cp.add_argument('--format', choices=('text', 'json'), default='text')
+    cp.set_defaults(func=cmd_config_show)
+
+    p = sub.add_parser('serve', help='run the web interface and REST API')
+    p.add_argument('--ui', action='store_true',
+                   help='also serve the store browser at /ui (default: [server] ui)')
+    p.add_argument('--read-only', action='store_true',
+                   help='open the store read-only: resolve and run promoted snippets, '
+                        'refuse every change (default: [store] read_only)')
+    p.add_argument('--host', default='', help='bind address (default: [server] host)')
+    p.add_argument('--port', type=int, default=None, help='port (default: [server] port)')
+    p.set_defaults(func=cmd_serve)
+
+    p = sub.add_parser('promote', help='promote staged snippets on the running server')
+    p.add_argument('staging_ids', nargs='+', metavar='staging_id')
+    p.add_argument('--force', action='store_true',
+                   help='approve the snippet first, overriding a failed verdict '
+                        '(and --impact dependents)')
+    p.add_argument('--impact', action='store_true',
+                   help="show the snippets, subscribers and held promotions referencing "
+                        "the slot first; dependents need --force")
+    p.add_argument('--reason', default='', help='reason recorded with --force or --cancel')
+    p.add_argument('--interactive', '-i', action='store_true',
+                   help='review header, diff, gates and spec output before deciding')
+    p.add_argument('--gate', action='append', default=[], choices=BUILTIN_GATES,
+                   help='run a built-in gate before promoting (repeatable)')
+    p.add_argument('--seconds', type=float, default=None,
+                   help='time budget for --gate checks (default: [gates] fuzz_seconds)')
+    p.add_argument('--min', type=float, default=None, metavar='PERCENT',
+                   help='minimum line coverage for --gate coverage (default: [gates] coverage_min)')
+    when = p.add_mutually_exclusive_group()
+    when.add_argument('--at', default='', metavar='TIME',
+                      help='hold the promotion until TIME (ISO 8601, e.g. 2026-02-11T02:00Z)')
+    when.add_argument('--window', default='', metavar='NAME',
+                      help='hold the promotion until the next NAME window ([gates] windows)')
+    when.add_argument('--cancel', action='store_true',
+                      help='cancel a scheduled promotion (the snippet stays PASSED)')
+    p.set_defaults(func=cmd_promote)
+
+    p = sub.add_parser('show', help="show snippets' spec results per case")
+    p.add_argument('targets', nargs='+', metavar='staging_id',
+                   help='staging ids on the server, or promoted snippet files')
+    p.add_argument('--format', choices=('text', 'json', 'junit'), default='text')
+    p.set_defaults(func=cmd_show)
+
+    p = sub.add_parser('stage', help='stage a snippet file on the running server and dry-run it')
+    p.add_argument('path', help='snippet file (a .spec.toml / .spec.yaml sidecar is sent along)')
+    p.add_argument('--lang', default='', metavar='LANGUAGE',
+                   help='snippet language (default: detected from the header, extension, '
+                        '#! line or syntax; an ambiguous file needs it)')
+    p.add_argument('--label', default='', help='label (default: the file name without extension)')
+    p.add_argument('--meta', action='append', default=[], metavar='KEY=VALUE',
+                   help='custom metadata, written to the header as x-KEY (repeatable)')
+    p.add_argument('--author', default=os.environ.get('USER', ''),
+                   help='author recorded on the staged snippet (default: $USER)')
+    p.add_argument('--parent', default=None, metavar='STAGING_ID',
+                   help="the snippet this one was staged from (default: a promoted file's own "
+                        "staging_id; '' for none)")
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_stage)
+
+    p = sub.add_parser('ingest', help='stage every source file of a directory on the running server')
+    p.add_argument('dir', help='directory of source files (walked recursively)')
+    p.add_argument('--lang', default='auto', metavar='LANGUAGE',
+                   help="language of every file (default: auto — per file, from its "
+                        "extension or #! line)")
+    p.add_argument('--label-from', choices=('filename', 'dir', 'path'), default='filename',
+                   help='label: the file name, its directory, or its relative path '
+                        '(without extension; default: filename)')
+    p.add_argument('--meta', action='append', default=[], metavar='KEY=VALUE',
+                   help='custom metadata for every snippet (repeatable)')
+    p.add_argument('--author', default=os.environ.get('USER', ''),
+                   help='author recorded on the staged snippets (default: $USER)')
+    p.add_argument('--dry-run', action='store_true', help='list what would be staged, stage nothing')
+    p.add_argument('--report', default='', metavar='FILE', help='also write the report as JSON')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_ingest)
+
+    p = sub.add_parser('list', help="list the server's staged and recent snippets")
+    p.add_argument('--meta', action='append', default=[], metavar='KEY[=VALUE]',
+                   help='only snippets with this x- field (VALUE may be a glob; repeatable)')
+    p.add_argument('--search', default='', metavar='TEXT',
+                   help='only snippets whose id, label, slot, author or meta contain TEXT')
+    p.add_argument('--phase', default='', help='only this phase (e.g. promoted, failed)')
+    p.add_argument('--limit', type=int, default=100, help='snippets per page (default: 100)')
+    p.add_argument('--after', default='', metavar='CURSOR',
+                   help="the page after this cursor (a page's next_cursor)")
+    p.add_argument('--all', action='store_true', help='fetch every page')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_list)
+
+    p = sub.add_parser('lineage', help='print the chain of snippets a snippet was staged from')
+    p.add_argument('staging_id', help='the newest snippet of the chain')
+    p.add_argument('--no-diff', action='store_true', help='list the chain without the diffs')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_lineage)
+
+    p = sub.add_parser('spec', help="check how well a snippet's spec constrains it")
+    ssub = p.add_subparsers(dest='spec_command', metavar='<action>')
+    ssub.required = True
+    sp = ssub.add_parser('check', help='validate a spec sidecar and print its spec_hash')
+    sp.add_argument('path', help='a *.spec.toml / *.spec.yaml file, or a snippet file next to one')
+    sp.add_argument('--format', choices=('text', 'json'), default='text')
+    sp.set_defaults(func=cmd_spec_check)
+    sp = ssub.add_parser('run', help="run a snippet file's spec cases on the server")
+    sp.add_argument('path', help='snippet file with a .spec.toml / .spec.yaml sidecar, '
+                                 'or whose label has a default spec')
+    sp.add_argument('--label', default='',
+                    help="label whose default spec runs too (default: the file's stem, as for stage)")
+    sp.add_argument('--lang', default='', metavar='LANGUAGE',
+                    help='snippet language (default: detected from the header, extension, '
+                         '#! line or syntax; an ambiguous file needs it)')
+    sp.add_argument('--update-snapshots', action='store_true',
+                    help='record the current output of snapshot cases as their golden files')
+    sp.add_argument('--follow', '-f', action='store_true',
+                    help='stream compile and program output live, with timed phase markers')
+    sp.add_argument('--format', choices=('text', 'json'), default='text')
+    sp.set_defaults(func=cmd_spec_run)
+    sp = ssub.add_parser('mutate', help='run mutants of the snippet and report the kill rate')
+    sp.add_argument('staging_id')
+    sp.add_argument('--limit', type=int, default=40, help='maximum mutants to run (default: 40)')
+    sp.add_argument('--seconds', type=float, default=None,
+                    help='stop launching mutants after this many seconds')
+    sp.add_argument('--min-kill-rate', type=float, default=None, metavar='RATE',
+                    help='exit 1 below this kill rate (default: [gates] mutation_min_kill_rate)')
+    sp.add_argument('--format', choices=('text', 'json'), default='text')
+    sp.set_defaults(func=cmd_spec_mutate)
+
+    p = sub.add_parser('failures', help='inspect failure bundles of failed dry-runs')
+    fsub = p.add_subparsers(dest='failures_command', metavar='<action>')
+    fsub.required = True
+    fp = fsub.add_parser('show', help="print a failed snippet's diagnostics, output and environment")
+    fp.add_argument('staging_id')
+    fp.add_argument('--format', choices=('text', 'json'), default='text')
+    fp.set_defaults(func=cmd_failures_show)
+
+    p = sub.add_parser('delete', help='move snippets on the running server to the trash')
+    p.add_argument('staging_ids', nargs='+', metavar='staging_id')
+    p.add_argument('--reason', default='', help='recorded in the tombstone and the audit log')
+    p.add_argument('--by', default='', metavar='NAME', help='who is deleting them')
+    p.set_defaults(func=cmd_delete)
+
+    p = sub.add_parser('trash', help='list, restore or purge deleted snippets')
+    tsub = p.add_subparsers(dest='trash_command', metavar='<action>')
+    tsub.required = True
+    tp = tsub.add_parser('list', help='list the deleted snippets (read from disk)')
+    tp.add_argument('--format', choices=('text', 'json'), default='text')
+    tp.set_defaults(func=cmd_trash_list)
+    tp = tsub.add_parser('restore', help='put deleted snippets back where they were')
+    tp.add_argument('staging_ids', nargs='+', metavar='staging_id')
+    tp.set_defaults(func=cmd_trash_restore)
+    tp = tsub.add_parser('purge', help='delete trash entries for good')
+    tp.add_argument('staging_ids', nargs='*', metavar='staging_id')
+    which = tp.add_mutually_exclusive_group()
+    which.add_argument('--expired', action='store_true',
+                       help='only entries older than [retention] trash_days')
+    which.add_argument('--all', action='store_true', help='the whole trash')
+    tp.add_argument('--format', choices=('text', 'json'), default='text')
+    tp.set_defaults(func=cmd_trash_purge)
+
+    p = sub.add_parser('quota', help='show staging quotas and their use per author and namespace')
+    qsub = p.add_subparsers(dest='quota_command', metavar='<action>')
+    qsub.required = True
+    qp = qsub.add_parser('show', help="print the server's [quota] limits and today's use")
+    qp.add_argument('--author', default='', help='only this author')
+    qp.add_argument('--namespace', default='', help="only this namespace (a label's first segment)")
+    qp.add_argument('--format', choices=('text', 'json'), default='text')
+    qp.set_defaults(func=cmd_quota_show)
+
+    p = sub.add_parser('trends', help="print a slot's spec_time history with anomalies marked")
+    p.add_argument('slot', help='slot address, e.g. d3')
+    p.add_argument('--label', default='', help='only this label')
+    p.add_argument('--engine', default='', help='only this engine (e.g. rust)')
+    p.add_argument('--limit', type=int, default=20, help='runs to list (default: 20)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_trends)
+
+    p = sub.add_parser('resolve', help='print the promoted snippet a slot holds now')
+    p.add_argument('slot', help='slot address, e.g. d3')
+    p.add_argument('--code', action='store_true', help='print the snippet body instead')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_resolve)
+
+    p = sub.add_parser('watch', help="follow the running server's slot changes")
+    p.add_argument('slot', nargs='*', help="slot addresses or patterns, e.g. d3 'a*' (default: all)")
+    p.add_argument('--since', type=int, default=None, metavar='CURSOR',
+                   help='replay the changes after this cursor (default: from now)')
+    p.add_argument('--once', action='store_true', help='exit after the first change')
+    p.add_argument('--poll', type=float, default=30.0,
+                   help='seconds each long-poll request waits (default: 30, max 60)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_watch)
+
+    p = sub.add_parser('compare', help='A/B-run two candidates on the same spec cases')
+    p.add_argument('a', metavar='staging_id', help='candidate A')
+    p.add_argument('b', metavar='staging_id', help='candidate B')
+    p.add_argument('--label', default='', help='both candidates must carry this label')
+    p.add_argument('--cases', default='', metavar='cases.toml',
+                   help='spec cases to run (default: the candidates\' own spec)')
+    p.add_argument('--repeat', type=int, default=1,
+                   help='runs per case and candidate; the median time counts (default: 1)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_compare)
+
+    p = sub.add_parser('respec', help='re-run promoted snippets\' specs under the current toolchain')
+    p.add_argument('staging_ids', nargs='*', metavar='staging_id', help='only these promoted snippets')
+    p.add_argument('--engine', default='', help='only this engine (e.g. rust)')
+    p.add_argument('--all-promoted', action='store_true',
+                   help="every slot's current promoted snippet")
+    p.add_argument('--workers', type=int, default=4,
+                   help='snippets run at once (default: 4; Python runs one at a time)')
+    p.add_argument('--quarantine', action='store_true',
+                   help='roll back the snippets that now fail')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_respec)
+
+    p = sub.add_parser('repl', help='evaluate snippets interactively in the staging sandbox')
+    p.add_argument('--lang', default='python', metavar='LANGUAGE',
+                   help='snippet language (default: python)')
+    p.add_argument('--author', default=os.environ.get('USER', ''),
+                   help='author recorded on staged snippets (default: $USER)')
+    p.set_defaults(func=cmd_repl)
+
+    p = sub.add_parser('jupyter', help='manage the SpokedPy Jupyter kernel')
+    jsub = p.add_subparsers(dest='jupyter_command', metavar='<action>')
+    jsub.required = True
+    jp = jsub.add_parser('install', help='register the kernel with Jupyter')
+    jp.add_argument('--sys-prefix', action='store_true',
+                    help="install into this Python's prefix instead of the user's")
+    jp.set_defaults(func=cmd_jupyter_install)
+
+    p = sub.add_parser('lsp', help='language server for snippet files (stdio)')
+    p.add_argument('--stdio', action='store_true',
+                   help='accepted for editor compatibility; stdio is the only transport')
+    p.set_defaults(func=cmd_lsp)
+
+    p = sub.add_parser('migrate-headers',
+                       help='rewrite promoted files to the current banner layout')
+    p.add_argument('--dry-run', action='store_true', help='list what would change, write nothing')
+    p.add_argument('--backup-dir', default='', metavar='DIR',
+                   help='where the originals are copied (default: <snippets_dir>.backup-<time>)')
+    p.set_defaults(func=cmd_migrate_headers)
+
+    p = sub.add_parser('bench-store', help='benchmark parallel staging against a throwaway store')
+    p.add_argument('--jobs', type=int, default=50, help='parallel staging jobs (default: 50)')
+    p.add_argument('--per-job', type=int, default=4, help='snippets per job (default: 4)')
+    p.add_argument('--shard-width', type=int, default=None, metavar='DIGITS',
+                   help='code_hash hex digits per subdirectory (default: [store] shard_width)')
+    p.add_argument('--min-rate', type=float, default=DEFAULT_MIN_RATE, metavar='N',
+                   help=f'fail below N promotions/s (default: {DEFAULT_MIN_RATE:g}; 0: no target)')
+    p.add_argument('--max-p95', type=float, default=None, metavar='SECONDS',
+                   help='fail if the p95 per-snippet latency is above this')
+    p.add_argument('--dir', default='', help='run in DIR and keep it (default: a temp dir)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_bench_store)
+
+    p = sub.add_parser('backup', help='incremental, content-addressed backup of the store')
+    p.add_argument('--to', default='', metavar='DIR', help='the backup dir (created if missing)')
+    p.add_argument('--encrypt', default='', metavar='age:RECIPIENT',
+                   help='encrypt with age for RECIPIENT (a public key, or a recipients file)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_backup)
+    bsub = p.add_subparsers(dest='backup_command', metavar='<action>')
+    bp = bsub.add_parser('verify', help="check a backup dir's snapshots")
+    bp.add_argument('dir', help='the backup dir')
+    bp.add_argument('--snapshot', default='', metavar='ID', help='only this snapshot')
+    bp.add_argument('--identity', default='', metavar='KEYFILE',
+                    help='age identity, to check the contents of an encrypted backup')
+    bp.add_argument('--format', choices=('text', 'json'), default='text')
+    bp.set_defaults(func=cmd_backup_verify)
+
+    p = sub.add_parser('lint-store', help='find dead slots, orphans, duplicate labels, stale snippets')
+    p.add_argument('--fix', nargs='?', const='all', default='', metavar='CLASS,…',
+                   help='repair the findings (all, or dead-slot, orphan, duplicate-label, stale)')
+    p.add_argument('--stale-days', type=float, default=7.0, metavar='N',
+                   help='a staged snippet is stale after N days without an event (default: 7)')
+    p.add_argument('--allow-label', action='append', default=[], metavar='GLOB',
+                   help='labels that may hold several slots of an engine (repeatable)')
+    p.add_argument('--format', choices=('text', 'json'), default='text')
+    p.set_defaults(func=cmd_lint_store)
+
+    p = sub.add_parser('openapi', help="export the server's OpenAPI document")
+    p.add_argument('--out', default='', metavar='FILE',
+                   help='write the document to FILE instead of stdout')
+    p.add_argument('--client', default='', metavar='FILE',
+                   help='regenerate the typed client module (spokedpy_client/api.py)')
+    p.set_defaults(func=cmd_openapi)
+
+    p = sub.add_parser('completions', help='print a shell completion script')
+    p.add_argument('shell', choices=('bash', 'zsh', 'fish'))
+    p.set_defaults(func=cmd_completions)
+
+    p = sub.add_parser('man', help='print the spokedpy(1) man page')
+    p.set_defaults(func=cmd_man)
+
+    # Callback for the completion scripts; hidden from --help.
+    p = sub.add_parser('__complete')
+    p.add_argument('kind', choices=('staging_id', 'slot', 'label', 'profile'))
+    p.add_argument('prefix', nargs='?', default='')
+    p.set_defaults(func=cmd_complete)
+
+    return parser
+
+
+def main(argv=None) -> int:
+    args = build_parser().parse_args(argv)
+
+    from visual_editor_core.errors import ErrorKind, SpokedError
+    from web_interface.config_layers import ConfigError, effective_layers, set_profile
+    if args.profile is not None:
+        set_profile(args.profile)
+    if args.tenant is not None:
+        os.environ['SPOKEDPY_TENANT'] = args.tenant.strip()
+    try:
+        effective_layers()
+    except ConfigError as exc:
+        return _fail(args, SpokedError(ErrorKind.USAGE, f"spokedpy: invalid config — {exc}"))
+
+    from visual_editor_core import offline
+    env_var, default, _ = _PATH_SETTINGS['vendor_dir']
+    enabled = args.offline or _setting('offline', 'SPOKEDPY_OFFLINE', '0').strip().lower() in ('1', 'true', 'yes', 'on')
+    offline.configure(enabled, vendor_dir=_setting('vendor_dir', env_var, default))
+    try:
+        offline.set_sandbox_backend(_setting('sandbox_backend', 'SPOKEDPY_SANDBOX_BACKEND', 'process'))
+    except ValueError as exc:
+        return _fail(args, SpokedError(ErrorKind.USAGE, f"spokedpy: invalid config — {exc}"))
+
+    try:
+        return args.func(args)
+    except (SpokedError,) + _request_errors() as exc:
+        # Anything a command did not report itself still exits with its kind's code.
+        return _fail(args, exc)
+
+
+if __name__ == '__main__':
+    sys.exit(main())
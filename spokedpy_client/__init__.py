@@ -0,0 +1,38 @@
+"""
+spokedpy_client — typed async client for the SpokedPy daemon's HTTP API.
+
+Internal tools talk to a running ``spokedpy serve`` through it instead of
+hand-writing requests:
+
+    from spokedpy_client import SpokedClient
+
+    async with SpokedClient('http://127.0.0.1:5002', tenant='payments',
+                            token=os.environ['SPOKEDPY_TOKEN']) as client:
+        reply = await client.staging_run_full(engine_letter='a', language='python',
+                                              code=code, label='etl')
+        snippet = reply['snippet']                # a StagedSnippet TypedDict
+        await client.staging_rollback(snippet['staging_id'], reason='bad deploy')
+
+One method per operation of the OpenAPI document (``spokedpy openapi``),
+named by its operationId: path parameters are positional, the request
+body's fields and the query parameters keyword-only (None is not sent).
+A method returns the reply's JSON as a TypedDict of its keys; a refused
+request raises SpokedError with the server's kind and context, exactly
+as the CLI's (visual_editor_core.errors), and a server that does not
+answer raises it as ``server_unreachable``.  Streamed replies (NDJSON,
+e.g. ``staging_stage_and_spec``) return their final ``result`` event.
+
+The methods run the blocking request on a worker thread (the loop's
+default executor, or the one passed in), like async_pipeline does.
+``api.py`` is generated from the document — regenerate it with
+
+    python spokedpy.py openapi --client spokedpy_client/api.py
+
+whenever a route, its docstring's ``Body:`` line or its reply changes.
+"""
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+
+from .api import SpokedClient
+
+__all__ = ['ErrorKind', 'SpokedClient', 'SpokedError']
@@ -0,0 +1,1872 @@
+"""
+SpokedPy API client — generated from the OpenAPI document by
+spokedpy_client.codegen; do not edit.  Regenerate with
+
+    python spokedpy.py openapi --client spokedpy_client/api.py
+"""
+
+from typing import Any, Dict, List, Literal, Optional, TypedDict
+
+from .transport import BaseClient
+
+
+# =============================================================================
+# SCHEMAS
+# =============================================================================
+
+class StagedSnippet(TypedDict, total=False):
+    """components/schemas/StagedSnippet"""
+    staging_id: str
+    language: str
+    engine_letter: str
+    label: str
+    code: str
+    code_hash: str
+    author: str
+    spec: Dict[str, Any]
+    spec_hash: str
+    meta: Dict[str, Any]
+    network: str
+    parent: str
+    phase: Literal[
+        'queued', 'speculating', 'passed', 'failed', 'promoting', 'promoted', 'rejected',
+        'rolled_back']
+    created_at: float
+    updated_at: float
+    seq: int
+    reserved_engine: str
+    reserved_position: int
+    reserved_address: str
+    spec_output: str
+    spec_error: str
+    spec_execution_time: float
+    spec_success: bool
+    spec_variables: Dict[str, Any]
+    spec_started_at: float
+    spec_completed_at: float
+    spec_cases: List[Dict[str, Any]]
+    sandbox_violations: List[Dict[str, Any]]
+    spec_denied: List[str]
+    network_not_enforced: List[str]
+    failure_bundle: str
+    gate_results: List[Dict[str, Any]]
+    metrics: Dict[str, Any]
+    saved_file_path: str
+    ledger_node_id: str
+    registry_slot_id: str
+    promoted_at: float
+    promoted_seq: int
+    degraded: Dict[str, Any]
+    reviewed_by: str
+    required_approvers: List[str]
+    scheduled_for: float
+    scheduled_by: str
+    policy_decision: Dict[str, Any]
+    rejection_reason: str
+    rejection_at: float
+    created: str
+    promoted: str
+
+
+class Degradation(TypedDict, total=False):
+    """components/schemas/Degradation"""
+    slot: str
+    staging_id: str
+    code_hash: str
+    label: str
+    reason: str
+    toolchain: str
+    since: float
+
+
+class Ancestor(TypedDict, total=False):
+    """components/schemas/Ancestor"""
+    staging_id: str
+    parent: str
+    label: str
+    language: str
+    slot: str
+    phase: str
+    author: str
+    code_hash: str
+    created: str
+    code: Optional[str]
+    source: str
+    diff: str
+
+
+class Error(TypedDict, total=False):
+    """components/schemas/Error"""
+    success: bool
+    error: str
+    kind: Literal[
+        'failed', 'spec_failed', 'usage', 'server_unreachable', 'not_found', 'invalid_state',
+        'store_corruption', 'store_read_only', 'slot_full', 'slot_frozen', 'gate_denied',
+        'spec_timeout', 'engine_missing', 'quota_exceeded', 'slot_degraded']
+    context: Dict[str, Any]
+
+
+# =============================================================================
+# REPLIES
+# =============================================================================
+
+class GetEngineManifestResponse(TypedDict, total=False):
+    """200 reply of GET /api/engines"""
+    success: bool
+    engines: Any
+    total: Any
+    enabled: Any
+    disabled: Any
+    warm_pool: Any
+
+
+class CommitNodeToRegistryResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/commit"""
+    success: bool
+    slot: Any
+
+
+class CommitAllToRegistryResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/commit-all"""
+    success: bool
+    committed: Any
+    slots: Any
+
+
+class RefreshRegistryResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/refresh"""
+    success: bool
+    dirty_count: Any
+    message: Any
+
+
+class GetRegistrySlotResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/slot/{slot_id}"""
+    success: bool
+    slot: Any
+
+
+class ReadRegistrySlotOutputResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/slot/{slot_id}/output"""
+    success: bool
+    output: Any
+
+
+class ExecuteRegistrySlotResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/slot/{slot_id}/execute"""
+    success: bool
+    result: Any
+
+
+class RollbackRegistrySlotResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/slot/{slot_id}/rollback"""
+    success: bool
+    message: Any
+
+
+class GetEngineRowResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/engine/{engine_name}"""
+    success: bool
+    engine: Any
+
+
+class UpdateEnginePermissionsResponse(TypedDict, total=False):
+    """200 reply of PUT /api/registry/engine/{engine_name}/permissions"""
+    success: bool
+    updated: Any
+
+
+class GetDirtySlotsResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/dirty"""
+    success: bool
+    count: Any
+    slots: Any
+
+
+class GetSlotForNodeResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/node/{node_id}/slot"""
+    success: bool
+    slot: Any
+
+
+class GetExecutionEnginesResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/engines"""
+    success: bool
+    engines: Any
+    supported_languages: Any
+
+
+class GetExecutableNodesResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/ledger/nodes"""
+    success: bool
+    nodes: Any
+
+
+class RunNodeFromLedgerResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/ledger/run/{node_id}"""
+    success: bool
+    result: Any
+
+
+class RunAllFromLedgerResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/ledger/run-all"""
+    success: bool
+    results: Any
+    summary: Any
+
+
+class GetNodeExecutionHistoryResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/ledger/history/{node_id}"""
+    success: bool
+    history: Any
+
+
+class ResetExecutionNamespaceResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/reset-namespace"""
+    success: bool
+    reset_count: Any
+
+
+class GetLiveVariablesResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/ledger/variables"""
+    success: bool
+    variables: Any
+
+
+class ResetLiveExecutorResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/ledger/reset"""
+    success: bool
+    message: Any
+
+
+class CreateDebugSessionRouteResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/multi-debug/create"""
+    success: bool
+    session_id: Any
+    state: Any
+    node_count: Any
+
+
+class StartDebugSessionResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/multi-debug/start/{session_id}"""
+    success: bool
+    session_id: Any
+    state: Any
+
+
+class StepDebugSessionResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/multi-debug/step/{session_id}"""
+    success: bool
+    session_id: Any
+    result: Any
+
+
+class StopDebugSessionResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/multi-debug/stop/{session_id}"""
+    success: bool
+    session_id: Any
+
+
+class RunAllDebugSessionsResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/multi-debug/run-all"""
+    success: bool
+    results: Any
+
+
+class ListDebugSessionsResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/multi-debug/list"""
+    success: bool
+    sessions: Any
+    count: Any
+
+
+class ApiListProjectsResponse(TypedDict, total=False):
+    """200 reply of GET /api/projects"""
+    success: bool
+    projects: Any
+
+
+class ApiSaveProjectResponse(TypedDict, total=False):
+    """200 reply of POST /api/projects"""
+    success: bool
+    project: Any
+
+
+class ApiLoadProjectResponse(TypedDict, total=False):
+    """200 reply of GET /api/projects/{project_id}"""
+    success: bool
+    project: Any
+
+
+class RunEnginesSimultaneousResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/engines/run-simultaneous"""
+    success: bool
+    results: Any
+    summary: Any
+
+
+class RunAllRegistrySlotsResponse(TypedDict, total=False):
+    """200 reply of POST /api/execution/registry/run-all-slots"""
+    success: bool
+    results: Any
+    summary: Any
+    message: Any
+
+
+class HydrateRegistrySlotsResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/registry/hydrate-slots"""
+    success: bool
+    tabs: Any
+    slot_count: Any
+
+
+class HydrateEngineTabsFromCanvasResponse(TypedDict, total=False):
+    """200 reply of GET /api/execution/engines/hydrate-from-canvas"""
+    success: bool
+    tabs: Any
+    node_count: Any
+    message: Any
+    language_count: Any
+
+
+class ApiDemoEngineTabsResponse(TypedDict, total=False):
+    """200 reply of GET /api/demos/engine-tabs"""
+    success: bool
+    tabs: Any
+    count: Any
+
+
+class ApiEnginesAvailableResponse(TypedDict, total=False):
+    """200 reply of GET /api/engines/available"""
+    success: bool
+    engines: Any
+    ready_count: Any
+    total: Any
+
+
+class StagingQueueResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/queue"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingStageAndSpecResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/stage-and-spec"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingEvaluateResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/evaluate"""
+    success: bool
+    result: Any
+
+
+class StagingSpecRunResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/spec-run"""
+    success: bool
+    cases: Any
+    inherited: Any
+
+
+class StagingSpeculateResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/speculate/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingVerdictResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/verdict/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingPromoteResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/promote/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingImpactResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/impact/{staging_id}"""
+    success: bool
+    impact: Any
+
+
+class StagingQuotaResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/quota"""
+    success: bool
+    quota: Any
+
+
+class StagingRunFullResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/run-full"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingRollbackResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/rollback/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingDeleteResponse(TypedDict, total=False):
+    """200 reply of DELETE /api/staging/snippet/{staging_id}"""
+    success: bool
+    tombstone: Any
+
+
+class StagingGetSnippetResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/snippet/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+    audit_trail: Any
+
+
+class StagingTrashResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/trash"""
+    success: bool
+    trash: Any
+
+
+class StagingTrashRestoreResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/trash/{staging_id}/restore"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingTrashPurgeResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/trash/purge"""
+    success: bool
+    purged: Any
+
+
+class StagingRelabelResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/label/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingListResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/snippets"""
+    success: bool
+    snippets: List[StagedSnippet]
+
+
+class StagingLineageResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/lineage/{staging_id}"""
+    success: bool
+    staging_id: Any
+    lineage: List[Ancestor]
+
+
+class StagingAuditResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/audit"""
+    success: bool
+    entries: Any
+    count: Any
+
+
+class StagingGatesResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/gates"""
+    success: bool
+    gates: Any
+
+
+class StagingRunGateResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/gate/{staging_id}"""
+    success: bool
+    decision: Any
+    snippet: StagedSnippet
+
+
+class StagingMutateResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/mutate/{staging_id}"""
+    success: bool
+    report: Any
+    min_kill_rate: Any
+
+
+class StagingCompareResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/compare"""
+    success: bool
+    report: Any
+
+
+class StagingRespecResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/respec"""
+    success: bool
+    report: Any
+
+
+class StagingDegradedResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/degraded"""
+    success: bool
+    degraded: List[Degradation]
+    interval_minutes: Any
+    last_run: Any
+
+
+class StagingGatesReloadResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/gates/reload"""
+    success: bool
+    gates: Any
+
+
+class ApprovalsListResponse(TypedDict, total=False):
+    """200 reply of GET /api/approvals"""
+    success: bool
+    approvals: Any
+    count: Any
+
+
+class ApprovalsDecideResponse(TypedDict, total=False):
+    """200 reply of POST /api/approvals/{staging_id}/{decision}"""
+    success: bool
+    principal: Any
+    snippet: StagedSnippet
+
+
+class StagingScheduleListResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/schedule"""
+    success: bool
+    scheduled: Any
+    windows: Any
+
+
+class StagingScheduleResponse(TypedDict, total=False):
+    """200 reply of POST /api/staging/schedule/{staging_id}"""
+    success: bool
+    schedule: Any
+    snippet: StagedSnippet
+
+
+class StagingScheduleCancelResponse(TypedDict, total=False):
+    """200 reply of DELETE /api/staging/schedule/{staging_id}"""
+    success: bool
+    snippet: StagedSnippet
+
+
+class StagingPolicyResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/policy"""
+    success: bool
+    policy: Any
+    default_auto_promote: Any
+
+
+class StagingConstraintsResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/constraints"""
+    success: bool
+    constraints: Any
+
+
+class StagingOwnersResponse(TypedDict, total=False):
+    """200 reply of GET /api/staging/owners"""
+    success: bool
+    owners: Any
+
+
+class SlotsWatchResponse(TypedDict, total=False):
+    """200 reply of GET /api/slots/watch"""
+    success: bool
+    cursor: Any
+    missed: Any
+    events: Any
+
+
+class SettingsListResponse(TypedDict, total=False):
+    """200 reply of GET /api/settings"""
+    success: bool
+    settings: Any
+
+
+class SettingsGetResponse(TypedDict, total=False):
+    """200 reply of GET /api/settings/{key}"""
+    success: bool
+    key: Any
+    value: Any
+    source: Any
+    origin: Any
+    layers: Any
+    db_override: Any
+    env_value: Any
+    default: Any
+    label: Any
+    restart_required: Any
+
+
+class SettingsSetResponse(TypedDict, total=False):
+    """200 reply of PUT /api/settings/{key}"""
+    success: bool
+    setting: Any
+    restart_required: Any
+    note: Any
+
+
+class SettingsDeleteResponse(TypedDict, total=False):
+    """200 reply of DELETE /api/settings/{key}"""
+    success: bool
+    reverted_to: Any
+    source: Any
+
+
+class RuntimeServerInfoResponse(TypedDict, total=False):
+    """200 reply of GET /api/runtime/server-info"""
+    success: bool
+    runtime_state: Any
+    engines: Any
+    engine_count: Any
+    available_engines: Any
+
+
+class GetEnrichedMatrixResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/matrix/enriched"""
+    success: bool
+    in_flight: Any
+    locked_count: Any
+
+
+class LockSlotResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/slot/{address}/lock"""
+    success: bool
+    address: Any
+    locked: Any
+
+
+class UnlockSlotResponse(TypedDict, total=False):
+    """200 reply of POST /api/registry/slot/{address}/unlock"""
+    success: bool
+    address: Any
+    was_locked: Any
+
+
+class EvictSlotResponse(TypedDict, total=False):
+    """200 reply of DELETE /api/registry/slot/{address}/evict"""
+    success: bool
+    address: Any
+    evicted_node: Any
+    slot_id: Any
+    ledger_deleted: Any
+    token_revoked: Any
+
+
+class SlotInfoResponse(TypedDict, total=False):
+    """200 reply of GET /api/registry/slot/{address}/info"""
+    success: bool
+    address: Any
+    occupied: Any
+    message: Any
+    engine: Any
+    node_id: Any
+    slot_id: Any
+    version: Any
+    provenance: Any
+    staging: Any
+    ledger: Any
+    locked: Any
+    lock_info: Any
+    origin: Any
+    exec_stats: Any
+
+
+class ForceCheckpointResponse(TypedDict, total=False):
+    """200 reply of POST /api/state/checkpoint"""
+    success: bool
+    message: Any
+    path: Any
+    promoted_count: Any
+    locked_count: Any
+    token_count: Any
+
+
+class GetCheckpointInfoResponse(TypedDict, total=False):
+    """200 reply of GET /api/state/checkpoint"""
+    success: bool
+    exists: Any
+    path: Any
+    version: Any
+    saved_at: Any
+    saved_at_iso: Any
+    promoted_count: Any
+    locked_count: Any
+    token_count: Any
+    snippets_summary: Any
+
+
+class MeshListPeersResponse(TypedDict, total=False):
+    """200 reply of GET /api/mesh/peers"""
+    success: bool
+    instance_id: Any
+    instance_name: Any
+    peers: Any
+
+
+class MeshAddPeerResponse(TypedDict, total=False):
+    """200 reply of POST /api/mesh/peers"""
+    success: bool
+    peer_id: Any
+    peers: Any
+
+
+class MeshRemovePeerResponse(TypedDict, total=False):
+    """200 reply of DELETE /api/mesh/peers/{peer_id}"""
+    success: bool
+    removed: Any
+
+
+class MeshSubscribeResponse(TypedDict, total=False):
+    """200 reply of POST /api/mesh/subscribe"""
+    success: bool
+    local_addr: Any
+    peer_id: Any
+
+
+class MeshHeartbeatResponse(TypedDict, total=False):
+    """200 reply of GET /api/mesh/heartbeat"""
+    success: bool
+    instance_id: Any
+    instance_name: Any
+    slot_count: Any
+    mesh_active: Any
+    timestamp: Any
+
+
+# =============================================================================
+# CLIENT
+# =============================================================================
+
+class SpokedClient(BaseClient):
+    """One awaitable method per operation of the SpokedPy API."""
+
+    async def get_engine_manifest(self) -> GetEngineManifestResponse:
+        """Return the canonical engine manifest — the SINGLE SOURCE OF TRUTH.
+
+        GET /api/engines
+        """
+        return await self._request('GET', '/api/engines')
+
+    async def get_registry_matrix(self) -> Dict[str, Any]:
+        """Get the full execution matrix — the grid view of all engines and slots.
+
+        GET /api/registry/matrix
+        """
+        return await self._request('GET', '/api/registry/matrix')
+
+    async def commit_node_to_registry(
+            self,
+            *,
+            node_id: Any,
+            engine: Optional[str] = None,
+            position: Any = None,
+            permissions: Any = None,
+    ) -> CommitNodeToRegistryResponse:
+        """Commit a ledger node into a registry slot.
+
+        POST /api/registry/commit
+        """
+        body = {
+            'node_id': node_id, 'engine': engine, 'position': position, 'permissions': permissions}
+        return await self._request('POST', '/api/registry/commit', body=body)
+
+    async def commit_all_to_registry(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> CommitAllToRegistryResponse:
+        """Commit all active ledger nodes into the registry matrix.
+
+        POST /api/registry/commit-all
+        """
+        return await self._request('POST', '/api/registry/commit-all', body=body or {})
+
+    async def refresh_registry(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RefreshRegistryResponse:
+        """Check all slots against the ledger for version bumps (hot-swap detection).
+
+        POST /api/registry/refresh
+        """
+        return await self._request('POST', '/api/registry/refresh', body=body or {})
+
+    async def get_registry_slot(self, slot_id: str) -> GetRegistrySlotResponse:
+        """Get a single slot by its global address (nra01, nra02, ...).
+
+        GET /api/registry/slot/{slot_id}
+        """
+        return await self._request('GET', f'/api/registry/slot/{slot_id}')
+
+    async def clear_registry_slot(self, slot_id: str) -> Dict[str, Any]:
+        """Clear a slot — remove the committed node. Checks DEL permission.
+
+        DELETE /api/registry/slot/{slot_id}
+        """
+        return await self._request('DELETE', f'/api/registry/slot/{slot_id}')
+
+    async def push_to_registry_slot(
+            self,
+            slot_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> Dict[str, Any]:
+        """Push data into a slot's input buffer (inter-slot communication).
+
+        POST /api/registry/slot/{slot_id}/push
+        """
+        return await self._request('POST', f'/api/registry/slot/{slot_id}/push', body=body or {})
+
+    async def read_registry_slot_output(self, slot_id: str) -> ReadRegistrySlotOutputResponse:
+        """Read from a slot's output buffer. Checks GET permission.
+
+        GET /api/registry/slot/{slot_id}/output
+        """
+        return await self._request('GET', f'/api/registry/slot/{slot_id}/output')
+
+    async def execute_registry_slot(
+            self,
+            slot_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> ExecuteRegistrySlotResponse:
+        """Execute a slot's committed code. Checks POST permission.
+
+        POST /api/registry/slot/{slot_id}/execute
+        """
+        return await self._request(
+            'POST', f'/api/registry/slot/{slot_id}/execute', body=body or {})
+
+    async def update_slot_permissions(
+            self,
+            slot_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> Dict[str, Any]:
+        """Update permissions for a specific slot.
+
+        PUT /api/registry/slot/{slot_id}/permissions
+        """
+        return await self._request(
+            'PUT', f'/api/registry/slot/{slot_id}/permissions', body=body or {})
+
+    async def rollback_registry_slot(
+            self,
+            slot_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RollbackRegistrySlotResponse:
+        """Rollback a slot to a previous code version. Zero-downtime rollback.
+
+        POST /api/registry/slot/{slot_id}/rollback
+        """
+        return await self._request(
+            'POST', f'/api/registry/slot/{slot_id}/rollback', body=body or {})
+
+    async def subscribe_slot(
+            self,
+            slot_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> Dict[str, Any]:
+        """Subscribe this slot to another slot's output.
+
+        POST /api/registry/slot/{slot_id}/subscribe
+        """
+        return await self._request(
+            'POST', f'/api/registry/slot/{slot_id}/subscribe', body=body or {})
+
+    async def get_engine_row(self, engine_name: str) -> GetEngineRowResponse:
+        """Get a single engine row with all its slots.
+
+        GET /api/registry/engine/{engine_name}
+        """
+        return await self._request('GET', f'/api/registry/engine/{engine_name}')
+
+    async def update_engine_permissions(
+            self,
+            engine_name: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> UpdateEnginePermissionsResponse:
+        """Set permissions for all slots in an engine row.
+
+        PUT /api/registry/engine/{engine_name}/permissions
+        """
+        return await self._request(
+            'PUT', f'/api/registry/engine/{engine_name}/permissions', body=body or {})
+
+    async def get_dirty_slots(self) -> GetDirtySlotsResponse:
+        """Get all slots that need hot-swapping (newer code in ledger).
+
+        GET /api/registry/dirty
+        """
+        return await self._request('GET', '/api/registry/dirty')
+
+    async def get_slot_for_node(self, node_id: str) -> GetSlotForNodeResponse:
+        """Find which slot a node is committed to.
+
+        GET /api/registry/node/{node_id}/slot
+        """
+        return await self._request('GET', f'/api/registry/node/{node_id}/slot')
+
+    async def record_registry_execution(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> Dict[str, Any]:
+        """Record an execution result for a node in its registry slot.
+
+        POST /api/registry/record-execution
+        """
+        return await self._request('POST', '/api/registry/record-execution', body=body or {})
+
+    async def get_execution_engines(self) -> GetExecutionEnginesResponse:
+        """Return the list of languages that have a live executor wired up.
+
+        GET /api/execution/engines
+        """
+        return await self._request('GET', '/api/execution/engines')
+
+    async def get_executable_nodes(self) -> GetExecutableNodesResponse:
+        """List all active nodes from the ledger that can be executed.
+
+        GET /api/execution/ledger/nodes
+        """
+        return await self._request('GET', '/api/execution/ledger/nodes')
+
+    async def run_node_from_ledger(
+            self,
+            node_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RunNodeFromLedgerResponse:
+        """Execute a single node using its current_source_code from the ledger.
+
+        POST /api/execution/ledger/run/{node_id}
+        """
+        return await self._request('POST', f'/api/execution/ledger/run/{node_id}', body=body or {})
+
+    async def run_all_from_ledger(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RunAllFromLedgerResponse:
+        """Execute all active nodes from the ledger in creation order.
+
+        POST /api/execution/ledger/run-all
+        """
+        return await self._request('POST', '/api/execution/ledger/run-all', body=body or {})
+
+    async def get_node_execution_history(self, node_id: str) -> GetNodeExecutionHistoryResponse:
+        """Get all past execution events for a node from the ledger.
+
+        GET /api/execution/ledger/history/{node_id}
+        """
+        return await self._request('GET', f'/api/execution/ledger/history/{node_id}')
+
+    async def reset_execution_namespace(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> ResetExecutionNamespaceResponse:
+        """Reset all executor namespaces — clears stale variables from prior runs.
+
+        POST /api/execution/reset-namespace
+        """
+        return await self._request('POST', '/api/execution/reset-namespace', body=body or {})
+
+    async def get_live_variables(self) -> GetLiveVariablesResponse:
+        """Get the current state of all variables in the live executor namespace.
+
+        GET /api/execution/ledger/variables
+        """
+        return await self._request('GET', '/api/execution/ledger/variables')
+
+    async def reset_live_executor(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> ResetLiveExecutorResponse:
+        """Reset all live executor namespaces (clear all variables).
+
+        POST /api/execution/ledger/reset
+        """
+        return await self._request('POST', '/api/execution/ledger/reset', body=body or {})
+
+    async def create_debug_session_route(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> CreateDebugSessionRouteResponse:
+        """Create a new debug session for a set of nodes
+
+        POST /api/execution/multi-debug/create
+        """
+        return await self._request('POST', '/api/execution/multi-debug/create', body=body or {})
+
+    async def start_debug_session(
+            self,
+            session_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StartDebugSessionResponse:
+        """Start a debug session
+
+        POST /api/execution/multi-debug/start/{session_id}
+        """
+        return await self._request(
+            'POST', f'/api/execution/multi-debug/start/{session_id}', body=body or {})
+
+    async def step_debug_session(
+            self,
+            session_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StepDebugSessionResponse:
+        """Execute one step in a debug session
+
+        POST /api/execution/multi-debug/step/{session_id}
+        """
+        return await self._request(
+            'POST', f'/api/execution/multi-debug/step/{session_id}', body=body or {})
+
+    async def get_debug_session_state(self, session_id: str) -> Dict[str, Any]:
+        """Get current state of a debug session
+
+        GET /api/execution/multi-debug/state/{session_id}
+        """
+        return await self._request('GET', f'/api/execution/multi-debug/state/{session_id}')
+
+    async def stop_debug_session(
+            self,
+            session_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StopDebugSessionResponse:
+        """Stop and remove a debug session
+
+        POST /api/execution/multi-debug/stop/{session_id}
+        """
+        return await self._request(
+            'POST', f'/api/execution/multi-debug/stop/{session_id}', body=body or {})
+
+    async def run_all_debug_sessions(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RunAllDebugSessionsResponse:
+        """Run all active debug sessions in parallel
+
+        POST /api/execution/multi-debug/run-all
+        """
+        return await self._request('POST', '/api/execution/multi-debug/run-all', body=body or {})
+
+    async def list_debug_sessions(self) -> ListDebugSessionsResponse:
+        """List all active debug sessions
+
+        GET /api/execution/multi-debug/list
+        """
+        return await self._request('GET', '/api/execution/multi-debug/list')
+
+    async def api_list_projects(self) -> ApiListProjectsResponse:
+        """List all saved projects (metadata only).
+
+        GET /api/projects
+        """
+        return await self._request('GET', '/api/projects')
+
+    async def api_save_project(
+            self,
+            *,
+            name: Any,
+            state: Any,
+            engine_tabs: Any,
+            description: Any = None,
+            project_id: Any = None,
+    ) -> ApiSaveProjectResponse:
+        """Save a project (canvas state + engine tabs).
+
+        POST /api/projects
+        """
+        body = {
+            'name': name, 'state': state, 'engine_tabs': engine_tabs, 'description': description,
+            'project_id': project_id}
+        return await self._request('POST', '/api/projects', body=body)
+
+    async def api_load_project(self, project_id: str) -> ApiLoadProjectResponse:
+        """Load a full project including canvas state and engine tabs.
+
+        GET /api/projects/{project_id}
+        """
+        return await self._request('GET', f'/api/projects/{project_id}')
+
+    async def api_delete_project(self, project_id: str) -> Dict[str, Any]:
+        """Delete a saved project.
+
+        DELETE /api/projects/{project_id}
+        """
+        return await self._request('DELETE', f'/api/projects/{project_id}')
+
+    async def run_engines_simultaneous(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RunEnginesSimultaneousResponse:
+        """Execute code snippets across multiple engines simultaneously.
+
+        POST /api/execution/engines/run-simultaneous
+        """
+        return await self._request(
+            'POST', '/api/execution/engines/run-simultaneous', body=body or {})
+
+    async def run_all_registry_slots(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> RunAllRegistrySlotsResponse:
+        """Execute every committed slot in the registry as an independent unit.
+
+        POST /api/execution/registry/run-all-slots
+        """
+        return await self._request(
+            'POST', '/api/execution/registry/run-all-slots', body=body or {})
+
+    async def hydrate_registry_slots(self) -> HydrateRegistrySlotsResponse:
+        """Build per-slot tabs from all committed registry slots.
+
+        GET /api/execution/registry/hydrate-slots
+        """
+        return await self._request('GET', '/api/execution/registry/hydrate-slots')
+
+    async def hydrate_engine_tabs_from_canvas(self) -> HydrateEngineTabsFromCanvasResponse:
+        """Build engine tab payloads from active canvas nodes in the ledger.
+
+        GET /api/execution/engines/hydrate-from-canvas
+        """
+        return await self._request('GET', '/api/execution/engines/hydrate-from-canvas')
+
+    async def api_demo_engine_tabs(self) -> ApiDemoEngineTabsResponse:
+        """Return demo code tabs for all engines with detected toolchains.
+
+        GET /api/demos/engine-tabs
+        """
+        return await self._request('GET', '/api/demos/engine-tabs')
+
+    async def api_engines_available(self) -> ApiEnginesAvailableResponse:
+        """Return which of the 14 engines have their toolchain on PATH.
+
+        GET /api/engines/available
+        """
+        return await self._request('GET', '/api/engines/available')
+
+    async def api_engines_doctor(self, *, engine: Optional[str] = None) -> Dict[str, Any]:
+        """Run the engine doctor: toolchains, versions, paths, credentials.
+
+        GET /api/engines/doctor
+        """
+        return await self._request('GET', '/api/engines/doctor', query={'engine': engine})
+
+    async def staging_queue(
+            self,
+            *,
+            engine_letter: str,
+            language: str,
+            code: str,
+            label: Optional[str] = None,
+            author: Optional[str] = None,
+            spec: Any = None,
+            spec_format: Optional[str] = None,
+            meta: Optional[Dict[str, Any]] = None,
+            parent: Optional[str] = None,
+    ) -> StagingQueueResponse:
+        """Queue a snippet into the staging pipeline.
+
+        POST /api/staging/queue
+        """
+        body = {
+            'engine_letter': engine_letter, 'language': language, 'code': code, 'label': label,
+            'author': author, 'spec': spec, 'spec_format': spec_format, 'meta': meta,
+            'parent': parent}
+        return await self._request('POST', '/api/staging/queue', body=body)
+
+    async def staging_stage_and_spec(
+            self,
+            *,
+            engine_letter: str,
+            language: str,
+            code: str,
+            label: Optional[str] = None,
+            author: Optional[str] = None,
+            spec: Any = None,
+            spec_format: Optional[str] = None,
+            meta: Optional[Dict[str, Any]] = None,
+            parent: Optional[str] = None,
+    ) -> StagingStageAndSpecResponse:
+        """Queue a snippet and speculate it, streaming progress live (StageAndSpec).
+
+        POST /api/staging/stage-and-spec
+        """
+        body = {
+            'engine_letter': engine_letter, 'language': language, 'code': code, 'label': label,
+            'author': author, 'spec': spec, 'spec_format': spec_format, 'meta': meta,
+            'parent': parent}
+        return await self._request('POST', '/api/staging/stage-and-spec', body=body)
+
+    async def staging_evaluate(self, *, language: str, code: str) -> StagingEvaluateResponse:
+        """Run code in the speculation sandbox without staging it.
+
+        POST /api/staging/evaluate
+        """
+        body = {'language': language, 'code': code}
+        return await self._request('POST', '/api/staging/evaluate', body=body)
+
+    async def staging_spec_run(
+            self,
+            *,
+            language: str,
+            code: str,
+            spec: Any,
+            spec_format: Optional[str] = None,
+            label: Optional[str] = None,
+            stream: Optional[bool] = None,
+    ) -> StagingSpecRunResponse:
+        """Run a spec suite against code in the sandbox without staging it.
+
+        POST /api/staging/spec-run
+        """
+        body = {
+            'language': language, 'code': code, 'spec': spec, 'spec_format': spec_format,
+            'label': label, 'stream': stream}
+        return await self._request('POST', '/api/staging/spec-run', body=body)
+
+    async def staging_speculate(
+            self,
+            staging_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StagingSpeculateResponse:
+        """Run speculative (dry-run) execution of a queued snippet.
+
+        POST /api/staging/speculate/{staging_id}
+        """
+        return await self._request('POST', f'/api/staging/speculate/{staging_id}', body=body or {})
+
+    async def staging_verdict(
+            self,
+            staging_id: str,
+            *,
+            action: Literal['auto', 'approve', 'reject', 'hold'],
+            reason: Optional[str] = None,
+    ) -> StagingVerdictResponse:
+        """Issue a verdict on a speculated snippet, as an authenticated reviewer.
+
+        POST /api/staging/verdict/{staging_id}
+        """
+        body = {'action': action, 'reason': reason}
+        return await self._request('POST', f'/api/staging/verdict/{staging_id}', body=body)
+
+    async def staging_promote(
+            self,
+            staging_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StagingPromoteResponse:
+        """Promote a PASSED snippet to production, as an authenticated reviewer.
+
+        POST /api/staging/promote/{staging_id}
+        """
+        return await self._request('POST', f'/api/staging/promote/{staging_id}', body=body or {})
+
+    async def staging_impact(self, staging_id: str) -> StagingImpactResponse:
+        """What references the slot a snippet would be promoted into (``promote --impact``).
+
+        GET /api/staging/impact/{staging_id}
+        """
+        return await self._request('GET', f'/api/staging/impact/{staging_id}')
+
+    async def staging_quota(
+            self,
+            *,
+            limit: Optional[int] = None,
+            author: Optional[str] = None,
+            namespace: Optional[str] = None,
+    ) -> StagingQuotaResponse:
+        """The store's [quota] limits and use per author and namespace (``quota show``).
+
+        GET /api/staging/quota
+        """
+        return await self._request(
+            'GET', '/api/staging/quota',
+            query={'limit': limit, 'author': author, 'namespace': namespace})
+
+    async def staging_run_full(
+            self,
+            *,
+            engine_letter: str,
+            language: str,
+            code: str,
+            label: Optional[str] = None,
+            auto_promote: Optional[bool] = None,
+            author: Optional[str] = None,
+            spec: Any = None,
+            spec_format: Optional[str] = None,
+            meta: Optional[Dict[str, Any]] = None,
+            parent: Optional[str] = None,
+    ) -> StagingRunFullResponse:
+        """Run the FULL staging pipeline in one call.
+
+        POST /api/staging/run-full
+        """
+        body = {
+            'engine_letter': engine_letter, 'language': language, 'code': code, 'label': label,
+            'auto_promote': auto_promote, 'author': author, 'spec': spec,
+            'spec_format': spec_format, 'meta': meta, 'parent': parent}
+        return await self._request('POST', '/api/staging/run-full', body=body)
+
+    async def staging_rollback(
+            self,
+            staging_id: str,
+            *,
+            reason: Optional[str] = None,
+    ) -> StagingRollbackResponse:
+        """Rollback a promoted snippet from production.
+
+        POST /api/staging/rollback/{staging_id}
+        """
+        body = {'reason': reason}
+        return await self._request('POST', f'/api/staging/rollback/{staging_id}', body=body)
+
+    async def staging_delete(self, staging_id: str) -> StagingDeleteResponse:
+        """Move a snippet that is not promoted to the trash.
+
+        DELETE /api/staging/snippet/{staging_id}
+        """
+        return await self._request('DELETE', f'/api/staging/snippet/{staging_id}')
+
+    async def staging_get_snippet(self, staging_id: str) -> StagingGetSnippetResponse:
+        """Get a single snippet by staging_id (with full audit trail).
+
+        GET /api/staging/snippet/{staging_id}
+        """
+        return await self._request('GET', f'/api/staging/snippet/{staging_id}')
+
+    async def staging_trash(self) -> StagingTrashResponse:
+        """The deleted snippets, most recently deleted first.
+
+        GET /api/staging/trash
+        """
+        return await self._request('GET', '/api/staging/trash')
+
+    async def staging_trash_restore(
+            self,
+            staging_id: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StagingTrashRestoreResponse:
+        """Put a deleted snippet back where it was.
+
+        POST /api/staging/trash/{staging_id}/restore
+        """
+        return await self._request(
+            'POST', f'/api/staging/trash/{staging_id}/restore', body=body or {})
+
+    async def staging_trash_purge(
+            self,
+            *,
+            staging_ids: Optional[List[Any]] = None,
+            expired: Optional[bool] = None,
+    ) -> StagingTrashPurgeResponse:
+        """Delete trash entries for good.
+
+        POST /api/staging/trash/purge
+        """
+        body = {'staging_ids': staging_ids, 'expired': expired}
+        return await self._request('POST', '/api/staging/trash/purge', body=body)
+
+    async def staging_review(self, staging_id: str) -> Dict[str, Any]:
+        """What promoting a snippet would change: header, gate results, spec output, and a unified
+        diff against the slot's current snippet.
+
+        GET /api/staging/review/{staging_id}
+        """
+        return await self._request('GET', f'/api/staging/review/{staging_id}')
+
+    async def staging_relabel(self, staging_id: str, *, label: str) -> StagingRelabelResponse:
+        """Rename a staged snippet before it is promoted.
+
+        POST /api/staging/label/{staging_id}
+        """
+        body = {'label': label}
+        return await self._request('POST', f'/api/staging/label/{staging_id}', body=body)
+
+    async def staging_list(
+            self,
+            *,
+            limit: Optional[int] = None,
+            include_history: Optional[int] = None,
+            meta: Optional[str] = None,
+            q: Optional[str] = None,
+    ) -> StagingListResponse:
+        """List active staged snippets + optional history.
+
+        GET /api/staging/snippets
+        """
+        return await self._request(
+            'GET', '/api/staging/snippets',
+            query={'limit': limit, 'include_history': include_history, 'meta': meta, 'q': q})
+
+    async def staging_lineage(self, staging_id: str) -> StagingLineageResponse:
+        """The snippets a snippet was staged from, newest first (see lineage).
+
+        GET /api/staging/lineage/{staging_id}
+        """
+        return await self._request('GET', f'/api/staging/lineage/{staging_id}')
+
+    async def staging_audit(
+            self,
+            *,
+            limit: Optional[int] = None,
+            staging_id: Optional[str] = None,
+    ) -> StagingAuditResponse:
+        """Get the full audit trail (most recent first).
+
+        GET /api/staging/audit
+        """
+        return await self._request(
+            'GET', '/api/staging/audit', query={'limit': limit, 'staging_id': staging_id})
+
+    async def staging_gates(self) -> StagingGatesResponse:
+        """List the promotion gates the pipeline runs after each dry-run.
+
+        GET /api/staging/gates
+        """
+        return await self._request('GET', '/api/staging/gates')
+
+    async def staging_run_gate(
+            self,
+            staging_id: str,
+            *,
+            gate: Literal['fuzz', 'mutation', 'coverage'],
+            seconds: Optional[float] = None,
+            min: Optional[float] = None,
+    ) -> StagingRunGateResponse:
+        """Run one built-in gate on demand before promoting (``promote --gate fuzz``).
+
+        POST /api/staging/gate/{staging_id}
+        """
+        body = {'gate': gate, 'seconds': seconds, 'min': min}
+        return await self._request('POST', f'/api/staging/gate/{staging_id}', body=body)
+
+    async def staging_mutate(
+            self,
+            staging_id: str,
+            *,
+            limit: Optional[int] = None,
+            seconds: Optional[float] = None,
+    ) -> StagingMutateResponse:
+        """Mutation-test a speculated snippet's spec (``spokedpy spec mutate``).
+
+        POST /api/staging/mutate/{staging_id}
+        """
+        body = {'limit': limit, 'seconds': seconds}
+        return await self._request('POST', f'/api/staging/mutate/{staging_id}', body=body)
+
+    async def staging_compare(
+            self,
+            *,
+            a: Any,
+            b: Any,
+            label: Optional[str] = None,
+            spec: Any = None,
+            spec_format: Optional[str] = None,
+            repeat: Optional[int] = None,
+    ) -> StagingCompareResponse:
+        """A/B-run two snippets on identical spec cases (``spokedpy compare``).
+
+        POST /api/staging/compare
+        """
+        body = {
+            'a': a, 'b': b, 'label': label, 'spec': spec, 'spec_format': spec_format,
+            'repeat': repeat}
+        return await self._request('POST', '/api/staging/compare', body=body)
+
+    async def staging_respec(
+            self,
+            *,
+            engine: Optional[str] = None,
+            staging_ids: Optional[List[str]] = None,
+            workers: Optional[int] = None,
+            quarantine: Optional[bool] = None,
+    ) -> StagingRespecResponse:
+        """Re-run the specs of promoted snippets under the current toolchain (``spokedpy respec``).
+
+        POST /api/staging/respec
+        """
+        body = {
+            'engine': engine, 'staging_ids': staging_ids, 'workers': workers,
+            'quarantine': quarantine}
+        return await self._request('POST', '/api/staging/respec', body=body)
+
+    async def staging_degraded(self) -> StagingDegradedResponse:
+        """Slots whose promoted snippet fails revalidation, and the revalidation schedule.
+
+        GET /api/staging/degraded
+        """
+        return await self._request('GET', '/api/staging/degraded')
+
+    async def staging_gates_reload(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> StagingGatesReloadResponse:
+        """Re-scan the plugins dir — pick up new or changed gate plugins without a restart.
+
+        POST /api/staging/gates/reload
+        """
+        return await self._request('POST', '/api/staging/gates/reload', body=body or {})
+
+    async def staging_notifications(self) -> Dict[str, Any]:
+        """Configured notifiers (Slack / email), their events, and recent delivery errors.
+
+        GET /api/staging/notifications
+        """
+        return await self._request('GET', '/api/staging/notifications')
+
+    async def staging_notifications_test(
+            self,
+            *,
+            event: Optional[Literal['spec_failure', 'promotion', 'rollback']] = None,
+    ) -> Dict[str, Any]:
+        """Send a sample notification synchronously to check the configuration.
+
+        POST /api/staging/notifications/test
+        """
+        body = {'event': event}
+        return await self._request('POST', '/api/staging/notifications/test', body=body)
+
+    async def staging_summary(self) -> Dict[str, Any]:
+        """Get pipeline summary: active counts, history stats, reserved slots.
+
+        GET /api/staging/summary
+        """
+        return await self._request('GET', '/api/staging/summary')
+
+    async def approvals_list(self) -> ApprovalsListResponse:
+        """Snippets waiting on a reviewer, each with its header and diff.
+
+        GET /api/approvals
+        """
+        return await self._request('GET', '/api/approvals')
+
+    async def approvals_decide(
+            self,
+            staging_id: str,
+            decision: str,
+            *,
+            reason: Optional[str] = None,
+    ) -> ApprovalsDecideResponse:
+        """Approve (promote) or reject a pending snippet as the authenticated reviewer.
+
+        POST /api/approvals/{staging_id}/{decision}
+        """
+        body = {'reason': reason}
+        return await self._request('POST', f'/api/approvals/{staging_id}/{decision}', body=body)
+
+    async def staging_schedule_list(self) -> StagingScheduleListResponse:
+        """Held promotions, soonest first, and the configured windows.
+
+        GET /api/staging/schedule
+        """
+        return await self._request('GET', '/api/staging/schedule')
+
+    async def staging_schedule(
+            self,
+            staging_id: str,
+            *,
+            at: Optional[str] = None,
+            window: Optional[str] = None,
+    ) -> StagingScheduleResponse:
+        """Hold a PASSED snippet and promote it at a time or in a named window.
+
+        POST /api/staging/schedule/{staging_id}
+        """
+        body = {'at': at, 'window': window}
+        return await self._request('POST', f'/api/staging/schedule/{staging_id}', body=body)
+
+    async def staging_schedule_cancel(self, staging_id: str) -> StagingScheduleCancelResponse:
+        """Cancel a held promotion; the snippet stays PASSED.
+
+        DELETE /api/staging/schedule/{staging_id}
+        """
+        return await self._request('DELETE', f'/api/staging/schedule/{staging_id}')
+
+    async def staging_policy(self) -> StagingPolicyResponse:
+        """The auto-promotion rules in effect (re-read if the file changed).
+
+        GET /api/staging/policy
+        """
+        return await self._request('GET', '/api/staging/policy')
+
+    async def staging_constraints(
+            self,
+            *,
+            slot: Optional[str] = None,
+    ) -> StagingConstraintsResponse:
+        """Slot constraints and engine capabilities; ``?slot=d2`` → that slot's only.
+
+        GET /api/staging/constraints
+        """
+        return await self._request('GET', '/api/staging/constraints', query={'slot': slot})
+
+    async def staging_owners(
+            self,
+            *,
+            slot: Optional[str] = None,
+            label: Optional[str] = None,
+    ) -> StagingOwnersResponse:
+        """The owners rules; ``?slot=d2&label=etl`` → who must approve that snippet.
+
+        GET /api/staging/owners
+        """
+        return await self._request(
+            'GET', '/api/staging/owners', query={'slot': slot, 'label': label})
+
+    async def slots_watch(
+            self,
+            *,
+            slots: Optional[str] = None,
+            since: Optional[str] = None,
+            timeout: Optional[str] = None,
+    ) -> SlotsWatchResponse:
+        """``?slots=d3,a*&since=<cursor>&timeout=30`` → the slot changes after ``since``.
+
+        GET /api/slots/watch
+        """
+        return await self._request(
+            'GET', '/api/slots/watch', query={'slots': slots, 'since': since, 'timeout': timeout})
+
+    async def settings_list(self) -> SettingsListResponse:
+        """Return all known settings with their effective values and sources.
+
+        GET /api/settings
+        """
+        return await self._request('GET', '/api/settings')
+
+    async def settings_get(self, key: str) -> SettingsGetResponse:
+        """Return one setting with full resolution info.
+
+        GET /api/settings/{key}
+        """
+        return await self._request('GET', f'/api/settings/{key}')
+
+    async def settings_set(self, key: str, *, value: str) -> SettingsSetResponse:
+        """Set a database override for a setting.
+
+        PUT /api/settings/{key}
+        """
+        body = {'value': value}
+        return await self._request('PUT', f'/api/settings/{key}', body=body)
+
+    async def settings_delete(self, key: str) -> SettingsDeleteResponse:
+        """Remove a database override (reverts to env / spokedpy.toml / default).
+
+        DELETE /api/settings/{key}
+        """
+        return await self._request('DELETE', f'/api/settings/{key}')
+
+    async def marshal_submit(
+            self,
+            *,
+            engine_letter: str,
+            language: str,
+            code: str,
+            label: Optional[str] = None,
+            auto_promote: Optional[bool] = None,
+            ttl: Optional[int] = None,
+    ) -> Dict[str, Any]:
+        """Submit code to the marshal.  Returns a unique token immediately.
+
+        POST /api/marshal
+        """
+        body = {
+            'engine_letter': engine_letter, 'language': language, 'code': code, 'label': label,
+            'auto_promote': auto_promote, 'ttl': ttl}
+        return await self._request('POST', '/api/marshal', body=body)
+
+    async def marshal_details(self, token: str) -> Dict[str, Any]:
+        """Full status report for a marshal token.
+
+        GET /api/marshal/{token}
+        """
+        return await self._request('GET', f'/api/marshal/{token}')
+
+    async def marshal_status(self, token: str) -> Dict[str, Any]:
+        """Compact phase-only check for a marshal token.
+
+        GET /api/marshal/{token}/status
+        """
+        return await self._request('GET', f'/api/marshal/{token}/status')
+
+    async def runtime_control(
+            self,
+            *,
+            action: Literal['start', 'pause', 'resume', 'stop'],
+            interval: Optional[int] = None,
+    ) -> Dict[str, Any]:
+        """Accept runtime control commands from the Runtime Panel.
+
+        POST /api/runtime/control
+        """
+        body = {'action': action, 'interval': interval}
+        return await self._request('POST', '/api/runtime/control', body=body)
+
+    async def runtime_status(self) -> Dict[str, Any]:
+        """Get the current runtime state.
+
+        GET /api/runtime/status
+        """
+        return await self._request('GET', '/api/runtime/status')
+
+    async def runtime_server_info(self) -> RuntimeServerInfoResponse:
+        """Return server information for the dashboard.
+
+        GET /api/runtime/server-info
+        """
+        return await self._request('GET', '/api/runtime/server-info')
+
+    async def get_enriched_matrix(self) -> GetEnrichedMatrixResponse:
+        """Return the full matrix with provenance, TTL, lock status, and staging pipeline state for
+        each occupied slot.
+
+        GET /api/registry/matrix/enriched
+        """
+        return await self._request('GET', '/api/registry/matrix/enriched')
+
+    async def lock_slot(self, address: str, *, reason: Optional[str] = None) -> LockSlotResponse:
+        """Lock (pin) a slot indefinitely — bypasses token TTL expiration.
+
+        POST /api/registry/slot/{address}/lock
+        """
+        body = {'reason': reason}
+        return await self._request('POST', f'/api/registry/slot/{address}/lock', body=body)
+
+    async def unlock_slot(
+            self,
+            address: str,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> UnlockSlotResponse:
+        """Unlock a previously pinned slot — restores normal TTL expiration.
+
+        POST /api/registry/slot/{address}/unlock
+        """
+        return await self._request('POST', f'/api/registry/slot/{address}/unlock', body=body or {})
+
+    async def evict_slot(self, address: str) -> EvictSlotResponse:
+        """Evict (delete) a snippet from a slot — removes from registry + ledger.
+
+        DELETE /api/registry/slot/{address}/evict
+        """
+        return await self._request('DELETE', f'/api/registry/slot/{address}/evict')
+
+    async def slot_info(self, address: str) -> SlotInfoResponse:
+        """Full forensic details for a single slot — token, provenance, code, performance stats.
+        Called by the single-click overlay.
+
+        GET /api/registry/slot/{address}/info
+        """
+        return await self._request('GET', f'/api/registry/slot/{address}/info')
+
+    async def force_checkpoint(
+            self,
+            *,
+            body: Optional[Dict[str, Any]] = None,
+    ) -> ForceCheckpointResponse:
+        """Force an immediate state checkpoint (synchronous write).
+
+        POST /api/state/checkpoint
+        """
+        return await self._request('POST', '/api/state/checkpoint', body=body or {})
+
+    async def get_checkpoint_info(self) -> GetCheckpointInfoResponse:
+        """Return metadata about the current checkpoint file.
+
+        GET /api/state/checkpoint
+        """
+        return await self._request('GET', '/api/state/checkpoint')
+
+    async def mesh_status(self) -> Dict[str, Any]:
+        """Get the current mesh relay status and topology.
+
+        GET /api/mesh/status
+        """
+        return await self._request('GET', '/api/mesh/status')
+
+    async def mesh_list_peers(self) -> MeshListPeersResponse:
+        """List all registered peers.
+
+        GET /api/mesh/peers
+        """
+        return await self._request('GET', '/api/mesh/peers')
+
+    async def mesh_add_peer(self, *, peer_id: str, url: str, role: str) -> MeshAddPeerResponse:
+        """Register a remote VPyD instance as a peer.
+
+        POST /api/mesh/peers
+        """
+        body = {'peer_id': peer_id, 'url': url, 'role': role}
+        return await self._request('POST', '/api/mesh/peers', body=body)
+
+    async def mesh_remove_peer(self, peer_id: str) -> MeshRemovePeerResponse:
+        """Remove a peer from the mesh.
+
+        DELETE /api/mesh/peers/{peer_id}
+        """
+        return await self._request('DELETE', f'/api/mesh/peers/{peer_id}')
+
+    async def mesh_activate(self, *, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        """Activate the mesh relay — starts heartbeat and relay threads.
+
+        POST /api/mesh/activate
+        """
+        return await self._request('POST', '/api/mesh/activate', body=body or {})
+
+    async def mesh_deactivate(self, *, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        """Deactivate the mesh relay — stops threads and clears relay lanes.
+
+        POST /api/mesh/deactivate
+        """
+        return await self._request('POST', '/api/mesh/deactivate', body=body or {})
+
+    async def mesh_subscribe(self, *, local_addr: str, peer_id: str) -> MeshSubscribeResponse:
+        """Subscribe a local slot's output to be forwarded to a peer.
+
+        POST /api/mesh/subscribe
+        """
+        body = {'local_addr': local_addr, 'peer_id': peer_id}
+        return await self._request('POST', '/api/mesh/subscribe', body=body)
+
+    async def mesh_heartbeat(self) -> MeshHeartbeatResponse:
+        """Heartbeat endpoint — called by remote peers to check liveness. Returns this instance's
+        basic status.
+
+        GET /api/mesh/heartbeat
+        """
+        return await self._request('GET', '/api/mesh/heartbeat')
+
+    async def mesh_relay_push(self, *, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        """Inbound relay — called by a remote peer to push data into one of this instance's inbound
+        relay lanes.
+
+        POST /api/mesh/relay/push
+        """
+        return await self._request('POST', '/api/mesh/relay/push', body=body or {})
+
+    async def mesh_topology(self) -> Dict[str, Any]:
+        """Full distributed topology view — local matrix + all peers' matrices. This powers the
+        mesh visualization in the runtime panel.
+
+        GET /api/mesh/topology
+        """
+        return await self._request('GET', '/api/mesh/topology')
@@ -0,0 +1,175 @@
+"""
+Codegen — render spokedpy_client/api.py from the OpenAPI document.
+
+    from web_interface.swagger import build_openapi_spec, openapi_app
+    source = render_client(build_openapi_spec(openapi_app()))
+
+Component schemas become TypedDicts (``total=False``: the server may
+leave keys out), each operation's 200 reply a ``<OperationId>Response``
+TypedDict, and the operation a method of SpokedClient named by its
+operationId.  The output depends only on the document's paths and
+schemas, not its servers, so regenerating it is stable.
+"""
+
+import keyword
+import re
+import textwrap
+from typing import Any, Dict, List, Tuple
+
+HEADER = '''"""
+SpokedPy API client — generated from the OpenAPI document by
+spokedpy_client.codegen; do not edit.  Regenerate with
+
+    python spokedpy.py openapi --client spokedpy_client/api.py
+"""
+
+from typing import Any, Dict, List, Literal, Optional, TypedDict
+
+from .transport import BaseClient
+'''
+
+
+def _ident(name: str) -> str:
+    name = re.sub(r'\W', '_', name)
+    return name + '_' if keyword.iskeyword(name) else name
+
+
+def _class_name(operation_id: str) -> str:
+    return ''.join(part[:1].upper() + part[1:] for part in operation_id.split('_')) + 'Response'
+
+
+def _py_type(schema: Dict[str, Any]) -> str:
+    """The annotation of a schema (Any when it does not say)."""
+    if '$ref' in schema:
+        name = schema['$ref'].rsplit('/', 1)[-1]
+    elif 'enum' in schema and schema.get('type') == 'string':
+        name = 'Literal[' + ', '.join(repr(v) for v in schema['enum']) + ']'
+    elif schema.get('type') == 'array':
+        name = f"List[{_py_type(schema.get('items', {}))}]"
+    else:
+        name = {'string': 'str', 'integer': 'int', 'number': 'float', 'boolean': 'bool',
+                'object': 'Dict[str, Any]'}.get(schema.get('type', ''), 'Any')
+    return f"Optional[{name}]" if schema.get('nullable') and name != 'Any' else name
+
+
+def _optional(schema: Dict[str, Any]) -> str:
+    name = _py_type(schema)
+    return name if name == 'Any' or name.startswith('Optional[') else f"Optional[{name}]"
+
+
+def _wrapped(head: str, items: List[str], tail: str, indent: int) -> List[str]:
+    """``head + ', '.join(items) + tail``, continued on lines of ``indent`` past 99 chars."""
+    line = head + ', '.join(items) + tail
+    if len(line) <= 99:
+        return [line]
+    lines, current = [head], ''
+    for i, item in enumerate(items):
+        item += tail if i == len(items) - 1 else ','
+        if current and len(' ' * indent + current + ' ' + item) > 99:
+            lines.append(' ' * indent + current)
+            current = item
+        else:
+            current = f"{current} {item}" if current else item
+    return lines + [' ' * indent + current]
+
+
+def _typed_dict(name: str, properties: Dict[str, Dict[str, Any]], doc: str) -> List[str]:
+    fields = [(key, _py_type(schema)) for key, schema in properties.items()]
+    if not all(key.isidentifier() and not keyword.iskeyword(key) for key, _ in fields):
+        body = ', '.join(f"{key!r}: {kind}" for key, kind in fields)
+        return [f"{name} = TypedDict({name!r}, {{{body}}}, total=False)", '']
+    lines = [f"class {name}(TypedDict, total=False):", f'    """{doc}"""']
+    for key, kind in fields:
+        if kind.startswith('Literal[') and len(f"    {key}: {kind}") > 99:
+            lines += _wrapped(f"    {key}: Literal[", [v.strip() for v in kind[8:-1].split(',')],
+                              ']', 8)
+        else:
+            lines.append(f"    {key}: {kind}")
+    return lines + ['']
+
+
+def _summary(op: Dict[str, Any]) -> str:
+    """The first paragraph of the operation's description (its summary is one line)."""
+    paragraph = (op.get('description') or op.get('summary', '')).split('\n\n')[0]
+    return ' '.join(line.strip() for line in paragraph.splitlines())
+
+
+def _docstring(summary: str, method: str, path: str) -> List[str]:
+    text = summary.replace('\\', '\\\\').replace('"""', "'''").strip() or 'No description.'
+    lines = textwrap.wrap(f'"""{text}', 91, break_long_words=False, break_on_hyphens=False)
+    return [f"        {line}" for line in lines] + ['', f'        {method} {path}', '        """']
+
+
+def _operations(spec: Dict[str, Any]) -> List[Tuple[str, str, Dict[str, Any]]]:
+    return [(path, method, op) for path, ops in spec['paths'].items()
+            for method, op in ops.items()]
+
+
+def _schema_of(content_holder: Dict[str, Any]) -> Dict[str, Any]:
+    return content_holder.get('content', {}).get('application/json', {}).get('schema', {})
+
+
+def _method(path: str, method: str, op: Dict[str, Any]) -> Tuple[List[str], str]:
+    """The method's source lines, and the TypedDict of its reply ('' when untyped)."""
+    name = _ident(op['operationId'])
+    path_params = [p['name'] for p in op.get('parameters', []) if p['in'] == 'path']
+    query = [p for p in op.get('parameters', []) if p['in'] == 'query']
+    body = _schema_of(op.get('requestBody', {}))
+    fields = body.get('properties', {})
+    required = set(body.get('required', []))
+
+    params = ['self'] + [f"{_ident(p)}: str" for p in path_params]
+    keywords = [f"{_ident(k)}: {_py_type(s)}" for k, s in fields.items() if k in required]
+    keywords += [f"{_ident(k)}: {_optional(s)} = None" for k, s in fields.items()
+                 if k not in required]
+    if 'requestBody' in op and not fields:
+        keywords.append('body: Optional[Dict[str, Any]] = None')
+    keywords += [f"{_ident(p['name'])}: {_optional(p['schema'])} = None" for p in query]
+    if keywords:
+        params += ['*'] + keywords
+
+    reply = _schema_of(op['responses'].get('200', {}))
+    returns = _class_name(op['operationId']) if len(reply.get('properties', {})) > 1 else ''
+    url = re.sub(r'\{(\w+)\}', lambda m: '{' + _ident(m.group(1)) + '}', path)
+    call = [repr(method.upper()), f"f{url!r}" if path_params else repr(url)]
+    if fields:
+        call.append('body=body')
+    elif 'requestBody' in op:
+        call.append('body=body or {}')
+    if query:
+        call.append('query={' + ', '.join(f"{p['name']!r}: {_ident(p['name'])}"
+                                          for p in query) + '}')
+
+    signature = f"    async def {name}({', '.join(params)}) -> {returns or 'Dict[str, Any]'}:"
+    lines = [signature] if len(signature) <= 99 else \
+        [f"    async def {name}("] + [f"            {p}," for p in params] + \
+        [f"    ) -> {returns or 'Dict[str, Any]'}:"]
+    lines += _docstring(_summary(op), method.upper(), path)
+    if fields:
+        lines += _wrapped('        body = {', [f"{k!r}: {_ident(k)}" for k in fields], '}', 12)
+    lines += _wrapped('        return await self._request(', call, ')', 12)
+    return lines + [''], returns
+
+
+def render_client(spec: Dict[str, Any]) -> str:
+    """The source of spokedpy_client/api.py for ``spec``."""
+    out = [HEADER, '', '# ' + '=' * 77, '# SCHEMAS', '# ' + '=' * 77, '']
+    for name, schema in spec.get('components', {}).get('schemas', {}).items():
+        out += _typed_dict(name, schema.get('properties', {}), f"components/schemas/{name}")
+        out.append('')
+
+    methods, replies = [], []
+    for path, method, op in _operations(spec):
+        lines, returns = _method(path, method, op)
+        methods += lines
+        if returns:
+            properties = _schema_of(op['responses']['200'])['properties']
+            replies += _typed_dict(returns, properties,
+                                   f"200 reply of {method.upper()} {path}") + ['']
+
+    out += ['# ' + '=' * 77, '# REPLIES', '# ' + '=' * 77, ''] + replies
+    out += ['# ' + '=' * 77, '# CLIENT', '# ' + '=' * 77, '',
+            'class SpokedClient(BaseClient):',
+            '    """One awaitable method per operation of the SpokedPy API."""', '']
+    source = '\n'.join(out + methods).rstrip() + '\n'
+    return re.sub(r'\n{4,}', '\n\n\n', source)
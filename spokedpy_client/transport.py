@@ -0,0 +1,97 @@
+"""
+Transport — the blocking HTTP call under every generated client method.
+"""
+
+import asyncio
+import functools
+import json
+from concurrent.futures import Executor
+from typing import Any, Dict, Optional
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+
+TOKEN_HEADER = 'X-Spokedpy-Token'
+
+
+def _drop_none(values: Optional[Dict[str, Any]]) -> Dict[str, Any]:
+    return {k: v for k, v in (values or {}).items() if v is not None}
+
+
+class BaseClient:
+    """Base URL, tenant token and session shared by the generated methods.
+
+    ``reviewer_token`` goes out as ``Authorization: Bearer``, for the routes
+    that need a reviewer ([server] reviewers).  ``session`` is anything with
+    requests' ``request(method, url, **kwargs)`` (a requests.Session by
+    default), so tests can answer without a server.
+    """
+
+    def __init__(self, base_url: str = 'http://127.0.0.1:5002', token: str = '',
+                 tenant: str = '', timeout: float = 120.0,
+                 executor: Optional[Executor] = None, session=None,
+                 reviewer_token: str = ''):
+        self.base_url = base_url.rstrip('/') + (f"/t/{tenant}" if tenant else '')
+        self.token = token
+        self.reviewer_token = reviewer_token
+        self.timeout = timeout
+        self.executor = executor
+        self._session = session
+
+    async def __aenter__(self) -> 'BaseClient':
+        return self
+
+    async def __aexit__(self, *exc_info):
+        self.close()
+
+    def close(self):
+        if self._session is not None and hasattr(self._session, 'close'):
+            self._session.close()
+
+    async def _request(self, method: str, path: str, body: Optional[Dict[str, Any]] = None,
+                       query: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        loop = asyncio.get_running_loop()
+        return await loop.run_in_executor(
+            self.executor, functools.partial(self._send, method, path, body, query))
+
+    def _send(self, method: str, path: str, body: Optional[Dict[str, Any]],
+              query: Optional[Dict[str, Any]]) -> Dict[str, Any]:
+        if self._session is None:
+            import requests
+            self._session = requests.Session()
+        url = self.base_url + path
+        headers = {TOKEN_HEADER: self.token} if self.token else {}
+        if self.reviewer_token:
+            headers['Authorization'] = f'Bearer {self.reviewer_token}'
+        kwargs: Dict[str, Any] = {'timeout': self.timeout, 'stream': True, 'headers': headers}
+        if body is not None:
+            kwargs['json'] = _drop_none(body)
+        if query:
+            kwargs['params'] = _drop_none(query)
+        try:
+            resp = self._session.request(method, url, **kwargs)
+        except OSError as exc:          # requests' ConnectionError / Timeout are OSErrors
+            raise SpokedError(ErrorKind.SERVER_UNREACHABLE, f"cannot reach {self.base_url} — "
+                              f"{exc}", server=self.base_url) from None
+        if 'ndjson' in resp.headers.get('Content-Type', ''):
+            return self._result(resp)
+        try:
+            data = resp.json()
+        except ValueError:
+            raise SpokedError(ErrorKind.FAILED, f"{method} {path}: HTTP {resp.status_code}, "
+                              f"not a JSON reply", status=resp.status_code) from None
+        if not data.get('success'):
+            raise SpokedError.from_response(data, resp.status_code)
+        return data
+
+    @staticmethod
+    def _result(resp) -> Dict[str, Any]:
+        """The ``result`` event of a streamed (NDJSON) reply; an ``error`` event raises."""
+        for line in resp.iter_lines(decode_unicode=True):
+            if not line:
+                continue
+            event = json.loads(line)
+            if event.get('event') == 'error':
+                raise SpokedError.from_response(event)
+            if event.get('event') == 'result':
+                return {k: v for k, v in event.items() if k != 'event'}
+        raise SpokedError(ErrorKind.FAILED, 'the stream ended without a result')
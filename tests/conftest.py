@@ -0,0 +1,54 @@
+"""
+Shared fixtures for the test suite.
+
+  - make_pipeline: builds a StagingPipeline over a throwaway store
+  - pipeline:      one such pipeline with the defaults
+"""
+
+import os
+
+import pytest
+
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.snippet_staging import StagingPipeline
+
+
+@pytest.fixture
+def make_pipeline(tmp_path):
+    """
+    ``make_pipeline(**overrides)`` → a StagingPipeline with no engines, its
+    promoted files in ``snippets/`` and its audit log in ``audit.jsonl``
+    under ``root`` (default ``tmp_path``).  Keyword arguments are passed on
+    to StagingPipeline and win over those defaults; a relative ``*_dir`` or
+    ``*_path`` (``failures_dir='failures'``) is taken under ``root`` too.
+    Every call builds a fresh pipeline over the same store, as a restarted
+    server would.
+
+    The node registry shares the pipeline's session ledger, as in
+    init_runtime.  Pass ``node_registry=NodeRegistry(SessionLedger())`` for
+    one that never sees the pipeline's sessions, so that every run into a
+    letter lands in its first slot.
+    """
+    def make(root=None, **overrides):
+        root = tmp_path if root is None else root
+        ledger = overrides.get('session_ledger') or SessionLedger()
+        options = {
+            'executors': {},
+            'node_registry': NodeRegistry(ledger),
+            'session_ledger': ledger,
+            'snippets_dir': 'snippets',
+            'audit_log_path': 'audit.jsonl',
+        }
+        options.update(overrides)
+        for key, value in options.items():
+            if key.endswith(('_dir', '_path')) and isinstance(value, str) \
+                    and not os.path.isabs(value):
+                options[key] = str(root / value)
+        return StagingPipeline(**options)
+    return make
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline()
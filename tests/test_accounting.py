@@ -0,0 +1,152 @@
+"""
+Test suite for resource accounting of dry-runs (accounting).
+
+Tests cover:
+  - Meter totals per stage; nested (per-case) meters hand their stages on
+  - Metered spawns record wall, CPU, peak memory and the exit code when
+    reaped — by wait(), poll() or a timeout kill — and nothing outside a Meter
+  - _run_subprocess names the engine process and its stage; the streaming
+    path is metered too
+  - The pipeline's spec_exec_* and respec entries carry ``resources``:
+    in-process Python as a run stage, spec cases by name
+"""
+
+import os
+import subprocess
+import sys
+import time
+
+import pytest
+
+from visual_editor_core import accounting, output_stream
+from visual_editor_core.execution_engine import _run_subprocess
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import parse_spec
+
+ALLOCATE = 'x = bytearray(64 * 1024 * 1024); print(len(x))'
+PYTHON = os.path.basename(sys.executable)
+
+
+def _entry(pipeline, staging_id, event):
+    return next(e['data'] for e in pipeline.get_audit_trail(staging_id) if e['event'] == event)
+
+
+# =============================================================================
+# METER
+# =============================================================================
+
+class TestMeter:
+
+    def test_summary(self):
+        assert accounting.Meter().summary() == {}
+        with accounting.Meter() as meter:
+            accounting.record('compile', 'rustc', 0.5, cpu=0.4, peak_rss_kb=1000)
+            accounting.record('run', 'main', 0.25, cpu=0.2, peak_rss_kb=300, exit_code=0)
+            accounting.record('compile', 'rustc', 0.01, cache_hit=True)
+        summary = meter.summary()
+        assert summary['compile_time'] == 0.51 and summary['run_time'] == 0.25
+        assert summary['cpu_time'] == 0.6 and summary['peak_rss_kb'] == 1000
+        assert summary['processes'] == 3 and summary['cache_hits'] == 1
+        assert summary['stages'][2] == {'stage': 'compile', 'process': 'rustc', 'case': '',
+                                        'wall': 0.01, 'cpu': None, 'peak_rss_kb': None,
+                                        'exit_code': None, 'cache_hit': True}
+
+    def test_nested_meters_tag_the_case(self):
+        accounting.record('run', 'lost', 1.0)                   # no meter: dropped
+        with accounting.Meter() as outer:
+            with accounting.Meter('adds'):
+                accounting.record('run', 'main', 0.1)
+            assert accounting.current() is outer
+        assert accounting.current() is None
+        assert [(s['process'], s['case']) for s in outer.stages] == [('main', 'adds')]
+
+    def test_stages_are_capped(self):
+        with accounting.Meter() as meter:
+            for _ in range(accounting.MAX_STAGES + 5):
+                accounting.record('run', 'main', 0.1)
+        summary = meter.summary()
+        assert len(summary['stages']) == accounting.MAX_STAGES and summary['processes'] == 55
+        assert summary['run_time'] == 5.5
+
+
+# =============================================================================
+# METERED SPAWNS
+# =============================================================================
+
+class TestSpawns:
+
+    def test_reaped_by_wait(self):
+        with accounting.Meter() as meter:
+            proc = accounting.run(accounting.popen_for('run', 'python'),
+                                  [sys.executable, '-c', ALLOCATE + '; raise SystemExit(3)'],
+                                  capture_output=True, text=True)
+        assert proc.returncode == 3 and proc.stdout.strip() == str(64 * 1024 * 1024)
+        [stage] = meter.stages
+        assert stage['exit_code'] == 3 and stage['peak_rss_kb'] > 64 * 1024
+        assert stage['cpu'] > 0 and stage['wall'] >= stage['cpu'] * 0.5
+
+    def test_reaped_by_poll(self):
+        with accounting.Meter() as meter:
+            proc = accounting.popen_for('run', 'sh')([sys.executable, '-c', 'pass'])
+            while proc.poll() is None:
+                time.sleep(0.01)
+        assert [s['exit_code'] for s in meter.stages] == [0]
+
+    def test_timeout_kill_is_recorded(self):
+        with accounting.Meter() as meter:
+            with pytest.raises(subprocess.TimeoutExpired):
+                accounting.run(accounting.popen_for('run', 'python'),
+                               [sys.executable, '-c', 'import time; time.sleep(30)'],
+                               capture_output=True, timeout=0.5)
+        [stage] = meter.stages
+        assert stage['exit_code'] == -9 and stage['wall'] < 10
+
+    def test_engine_spawns(self):
+        assert accounting.popen_for('run', 'x') is subprocess.Popen
+        _run_subprocess([sys.executable, '-c', 'pass'])                  # outside: not metered
+        with accounting.Meter() as meter:
+            _run_subprocess([sys.executable, '-c', 'print(1)'], capture_output=True, text=True)
+            _run_subprocess([sys.executable, 'build'], capture_output=True)       # '<tool> build'
+        assert [(s['stage'], s['process']) for s in meter.stages] == \
+            [('run', PYTHON), ('compile', PYTHON)]
+
+    def test_streaming_spawns(self):
+        events = []
+        with output_stream.streaming(events.append), accounting.Meter() as meter:
+            proc = _run_subprocess([sys.executable, '-c', 'print("hi")'],
+                                   capture_output=True, text=True)
+        assert proc.stdout == 'hi\n'
+        assert [e['text'] for e in events if e['event'] == 'output'] == ['hi\n']
+        assert [(s['process'], s['exit_code']) for s in meter.stages] == [(PYTHON, 0)]
+
+
+# =============================================================================
+# AUDIT ENTRIES
+# =============================================================================
+
+class TestAudit:
+
+    def test_in_process_python(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(sum(range(10)))', 'sum')
+        resources = _entry(pipeline, snippet.staging_id, 'spec_exec_completed')['resources']
+        [stage] = resources['stages']
+        assert (stage['stage'], stage['process'], stage['exit_code']) == ('run', 'python', 0)
+        assert stage['cpu'] is not None and stage['peak_rss_kb'] is None
+        assert resources['compile_time'] == 0 and resources['cache_hits'] == 0
+
+    def test_spec_cases_are_named(self, pipeline):
+        spec = parse_spec('[[case]]\nname = "big"\nargs = ["1"]\n'
+                          '[[case]]\nname = "fails"\nargs = ["2"]\nstdout = "nope\\n"\n')
+        snippet = pipeline.run_full_pipeline('a', 'python', ALLOCATE, 'alloc', spec=spec)
+        assert snippet.phase == StagingPhase.REJECTED
+        resources = _entry(pipeline, snippet.staging_id, 'spec_exec_failed')['resources']
+        assert [s['case'] for s in resources['stages']] == ['big', 'fails']
+        assert resources['processes'] == 2 and resources['peak_rss_kb'] > 64 * 1024
+        assert resources['run_time'] == pytest.approx(
+            sum(s['wall'] for s in resources['stages']), abs=1e-3)
+
+    def test_respec(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+        [result] = pipeline.respec('python')['results']
+        assert result['resources']['processes'] == 1
+        assert _entry(pipeline, snippet.staging_id, 'respec')['resources'] == result['resources']
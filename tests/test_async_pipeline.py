@@ -0,0 +1,133 @@
+"""
+Test suite for the asyncio API (AsyncStagingPipeline).
+
+Tests cover:
+  - stage / run_full_pipeline / verdict / promote / rollback awaited, with
+    the engine on a worker thread and the event loop free meanwhile
+  - stream_stage yields queued, the output_stream events and the result,
+    output arriving while the spec is still running
+  - stream_spec runs a suite without staging
+  - Errors are raised from the stream; leaving the stream early lets the
+    run finish
+"""
+
+import asyncio
+import threading
+import time
+
+import pytest
+
+from visual_editor_core.async_pipeline import AsyncStagingPipeline
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.execution_engine import ExecutionResult
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import parse_spec
+
+SLOW = 'import time\nprint("one", flush=True)\ntime.sleep(0.3)\nprint("two")\n'
+SPEC = '[[case]]\nname = "counts"\nstdout = "one\\ntwo\\n"\n'
+
+
+class SlowExecutor:
+    def __init__(self, seconds=0.3):
+        self.seconds, self.threads = seconds, []
+
+    def execute(self, code):
+        self.threads.append(threading.current_thread())
+        time.sleep(self.seconds)
+        return ExecutionResult(True, 'ok\n')
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return AsyncStagingPipeline(make_pipeline())
+
+
+async def _collect(stream):
+    return [event async for event in stream]
+
+
+# =============================================================================
+# AWAITED CALLS
+# =============================================================================
+
+class TestAwaited:
+
+    def test_loop_stays_free(self, make_pipeline):
+        engine = SlowExecutor()
+        pipeline = AsyncStagingPipeline(make_pipeline(executors={'javascript': engine}))
+        ticks = []
+
+        async def ticker():
+            while len(ticks) < 100:
+                ticks.append(time.perf_counter())
+                await asyncio.sleep(0.01)
+
+        async def main():
+            tick = asyncio.ensure_future(ticker())
+            snippets = await asyncio.gather(*(pipeline.stage('b', 'javascript', f"// {n}\n", f"js{n}")
+                                              for n in range(3)))
+            tick.cancel()
+            return snippets
+
+        started = time.perf_counter()
+        snippets = asyncio.run(main())
+        assert [s.phase for s in snippets] == [StagingPhase.PASSED] * 3
+        assert time.perf_counter() - started < 0.8          # ran side by side
+        assert len(ticks) >= 10                              # the loop kept ticking
+        assert threading.main_thread() not in engine.threads
+
+    def test_lifecycle(self, pipeline):
+
+        async def main():
+            held = await pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib',
+                                                    auto_promote=False)
+            await pipeline.verdict(held.staging_id, 'approve')
+            promoted = await pipeline.promote(held.staging_id)
+            assert promoted.phase == StagingPhase.PROMOTED
+            return await pipeline.rollback(held.staging_id, 'test')
+
+        assert asyncio.run(main()).phase == StagingPhase.ROLLED_BACK
+
+
+# =============================================================================
+# STREAMS
+# =============================================================================
+
+class TestStreams:
+
+    def test_stream_stage(self, pipeline):
+        events = asyncio.run(_collect(pipeline.stream_stage(
+            'a', 'python', SLOW, 'slow', spec=parse_spec(SPEC))))
+        kinds = [e['event'] for e in events]
+        assert kinds[0] == 'queued' and kinds[-2:] == ['spec_finished', 'result']
+        assert 'case_started' in kinds and 'case_finished' in kinds
+        result = events[-1]['snippet']
+        assert result.staging_id == events[0]['snippet'].staging_id
+        assert result.phase == StagingPhase.PASSED
+        one = next(e for e in events if e['event'] == 'output' and e['text'] == 'one\n')
+        finished = next(e for e in events if e['event'] == 'process_finished')
+        assert finished['at'] - one['at'] >= 0.25           # streamed, not replayed
+
+    def test_stream_spec(self, pipeline):
+        events = asyncio.run(_collect(pipeline.stream_spec('python', parse_spec(SPEC), SLOW)))
+        assert events[0]['event'] == 'spec_started' and events[-1]['event'] == 'result'
+        assert [r['passed'] for r in events[-1]['results']] == [True]
+
+    def test_error_raised(self, pipeline):
+        with pytest.raises(SpokedError) as info:
+            asyncio.run(_collect(pipeline.stream_speculate('stg-missing')))
+        assert info.value.kind == ErrorKind.NOT_FOUND
+
+    def test_leaving_early(self, pipeline):
+
+        async def main():
+            snippet = await pipeline.queue_snippet('a', 'python', SLOW, 'slow',
+                                                   spec=parse_spec(SPEC))
+            stream = pipeline.stream_speculate(snippet.staging_id)
+            async for event in stream:
+                break
+            await stream.aclose()
+            return snippet
+
+        snippet = asyncio.run(main())     # asyncio.run waits for the worker thread
+        assert pipeline.pipeline.get_snippet(snippet.staging_id).phase == StagingPhase.PASSED
@@ -0,0 +1,134 @@
+"""
+Test suite for CLI Completion — completion scripts and man page generation.
+
+Tests cover:
+  - Command tree mirrors the argparse parser (hidden commands excluded)
+  - bash / zsh / fish scripts list commands, flags and choices
+  - Dynamic kinds wired to the ``__complete`` callback
+  - Local index built from the audit log and state checkpoint
+  - Man page sections
+"""
+
+import json
+import shutil
+import subprocess
+
+import pytest
+
+from spokedpy import build_parser
+from visual_editor_core.cli_completion import (
+    bash_script, command_tree, complete, fish_script, local_index, man_page,
+    zsh_script,
+)
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def audit_log(tmp_path, pipeline):
+    pipeline.run_full_pipeline('a', 'python', 'x = 1', 'nightly etl')
+    staged = pipeline.run_full_pipeline('a', 'python', 'x = 2', 'draft', auto_promote=False)
+    pipeline.relabel(staged.staging_id, 'report')
+    return str(tmp_path / 'audit.jsonl')
+
+
+# =============================================================================
+# COMMAND TREE
+# =============================================================================
+
+class TestCommandTree:
+
+    def test_tree_mirrors_parser(self):
+        tree = command_tree(build_parser())
+        names = [' '.join(c.path) for c in tree.walk()]
+        assert 'promote' in names and 'config show' in names
+        assert not any(n.startswith('__') for n in names)
+
+        promote = next(c for c in tree.walk() if c.path == ['promote'])
+        assert ['--interactive', '-i'] in [o.flags for o in promote.options]
+        assert promote.positionals[0].dynamic == 'staging_id'
+
+    def test_profile_option_is_dynamic(self):
+        tree = command_tree(build_parser())
+        profile, = [o for o in tree.options if '--profile' in o.flags]
+        assert profile.dynamic == 'profile' and profile.takes_value
+
+
+# =============================================================================
+# SCRIPTS
+# =============================================================================
+
+class TestScripts:
+
+    def test_bash_script(self):
+        parser = build_parser()
+        script = bash_script(parser)
+        commands = ' '.join(c.path[0] for c in command_tree(parser).subcommands)
+        assert f"cmds='{commands}'" in script and 'promote' in commands
+        assert "'config show:--format') COMPREPLY=($(compgen -W 'text json'" in script
+        assert '__complete "$dyn"' in script
+        assert script.rstrip().endswith('complete -F _spokedpy spokedpy')
+
+    @pytest.mark.skipif(shutil.which('bash') is None, reason='bash not installed')
+    def test_bash_script_parses(self, tmp_path):
+        path = tmp_path / 'spokedpy.bash'
+        path.write_text(bash_script(build_parser()))
+        assert subprocess.run(['bash', '-n', str(path)]).returncode == 0
+
+    def test_zsh_script(self):
+        script = zsh_script(build_parser())
+        assert script.startswith('#compdef spokedpy')
+        assert "'promote:promote staged snippets on the running server'" in script
+        assert '{_spokedpy_dynamic staging_id}' in script
+
+    def test_fish_script(self):
+        script = fish_script(build_parser())
+        assert ("complete -c spokedpy -n '__fish_seen_subcommand_from config; "
+                "and __fish_seen_subcommand_from show' -l resolved") in script
+        assert "'(spokedpy __complete staging_id (commandline -ct))'" in script
+
+
+# =============================================================================
+# LOCAL INDEX
+# =============================================================================
+
+class TestLocalIndex:
+
+    def test_index_from_audit_log(self, audit_log):
+        index = local_index(audit_log)
+        assert [e.label for e in index] == ['report', 'nightly etl']
+        assert index[1].state == 'promoted' and index[0].slot
+
+    def test_checkpoint_entries_included(self, tmp_path):
+        checkpoint = tmp_path / 'state.json'
+        checkpoint.write_text(json.dumps({'promoted_snippets': [
+            {'staging_id': 'stg-restored01', 'label': 'cron', 'address': 'b4'}]}))
+        index = local_index(str(tmp_path / 'missing.jsonl'), str(checkpoint))
+        assert [(e.staging_id, e.slot, e.state) for e in index] == [('stg-restored01', 'b4', 'promoted')]
+
+    def test_complete_by_kind_and_prefix(self, audit_log):
+        index = local_index(audit_log)
+        ids = complete('staging_id', 'stg-', index)
+        assert len(ids) == 2 and 'report' in ids[0][1]
+        assert complete('label', 'nig', index) == [('nightly etl', index[1].slot)]
+        assert complete('profile', 'p', [], profiles=['dev', 'prod']) == [('prod', 'profile')]
+        with pytest.raises(ValueError, match='choose from'):
+            complete('engine', '', index)
+
+
+# =============================================================================
+# MAN PAGE
+# =============================================================================
+
+class TestManPage:
+
+    def test_sections(self):
+        page = man_page(build_parser(), environment=[('SPOKEDPY_PORT', 'Server port.')],
+                        files=[('spokedpy.toml', 'Project config.')])
+        assert page.splitlines()[1].startswith('.TH SPOKEDPY 1')
+        for section in ('.SH NAME', '.SH SYNOPSIS', '.SH COMMANDS', '.SH ENVIRONMENT',
+                        '.SH FILES', '.SH EXIT STATUS'):
+            assert section in page
+        assert '.SS promote' in page and '\\-\\-interactive' in page
@@ -0,0 +1,137 @@
+"""
+Test suite for Config Layers — layered spokedpy.toml resolution.
+
+Tests cover:
+  - system → user → project precedence, env + DB on top
+  - TOML value normalisation (bools, lists, relative paths)
+  - Unknown keys and parse errors
+  - explain() provenance used by `spokedpy config show --resolved`
+  - [profiles.<name>] overlays selected with --profile
+"""
+
+import pytest
+
+from web_interface import config_layers
+from web_interface.config_layers import ConfigError, explain, file_value, load_file
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def layers(tmp_path, monkeypatch):
+    """Point every file layer into tmp_path; returns a writer per layer."""
+    paths = {
+        'system': tmp_path / 'etc' / 'spokedpy.toml',
+        'user': tmp_path / 'home' / 'spokedpy.toml',
+        'project': tmp_path / 'proj' / 'spokedpy.toml',
+    }
+    monkeypatch.setattr(config_layers, 'layer_paths',
+                        lambda cwd=None: [(k, str(v)) for k, v in paths.items()])
+    monkeypatch.delenv('SPOKEDPY_PORT', raising=False)
+
+    def write(layer, text):
+        paths[layer].parent.mkdir(parents=True, exist_ok=True)
+        paths[layer].write_text(text)
+        return paths[layer]
+    return write
+
+
+# =============================================================================
+# PRECEDENCE
+# =============================================================================
+
+class TestPrecedence:
+
+    def test_project_beats_user_beats_system(self, layers):
+        layers('system', '[server]\nport = 1\nhost = "sys"\nreloader = true\n')
+        layers('user', '[server]\nport = 2\nhost = "user"\n')
+        layers('project', '[server]\nport = 3\n')
+        assert file_value('port')[0] == '3'
+        assert file_value('host')[0] == 'user'
+        assert file_value('reloader')[0] == '1'
+
+    def test_env_and_db_sit_above_files(self, layers, monkeypatch):
+        layers('project', '[server]\nport = 3\n')
+        monkeypatch.setenv('SPOKEDPY_PORT', '4')
+        resolved = explain('port', 'SPOKEDPY_PORT', '5002')
+        assert (resolved['value'], resolved['source']) == ('4', 'environment')
+        assert [l['layer'] for l in resolved['layers']] == ['environment', 'project', 'default']
+
+        resolved = explain('port', 'SPOKEDPY_PORT', '5002', db_value='5')
+        assert resolved['source'] == 'database'
+
+    def test_default_when_no_layer_sets_key(self, layers):
+        resolved = explain('port', 'SPOKEDPY_PORT', '5002')
+        assert resolved == {'key': 'port', 'value': '5002', 'source': 'default',
+                            'origin': None, 'layers': [{'layer': 'default', 'value': '5002'}]}
+
+    def test_origin_names_the_file(self, layers):
+        path = layers('user', '[gates]\nauto_promote = false\n')
+        resolved = explain('auto_promote', 'SPOKEDPY_AUTO_PROMOTE', '1')
+        assert resolved['value'] == '0'
+        assert resolved['origin'] == str(path)
+
+
+# =============================================================================
+# PROFILES
+# =============================================================================
+
+class TestProfiles:
+
+    @pytest.fixture(autouse=True)
+    def _reset_profile(self):
+        yield
+        config_layers.set_profile(None)
+
+    def test_profile_overlays_every_base_layer(self, layers):
+        layers('user', '[profiles.prod.sandbox]\nbackend = "netns"\n')
+        path = layers('project', '[gates]\nauto_promote = true\n'
+                                 '[profiles.prod.gates]\nauto_promote = false\n')
+        config_layers.set_profile('prod')
+        resolved = explain('auto_promote', 'SPOKEDPY_AUTO_PROMOTE', '1')
+        assert (resolved['value'], resolved['source']) == ('0', 'profile')
+        assert resolved['origin'] == f"{path} [profiles.prod]"
+        assert file_value('sandbox_backend')[0] == 'netns'
+
+    def test_no_profile_ignores_profile_tables(self, layers):
+        layers('project', '[profiles.prod.gates]\nauto_promote = false\n')
+        assert file_value('auto_promote') is None
+        assert config_layers.available_profiles() == ['prod']
+
+    def test_undefined_profile_raises(self, layers):
+        layers('project', '[profiles.dev.gates]\nallow_override = true\n')
+        config_layers.set_profile('prod')
+        with pytest.raises(ConfigError, match="profile 'prod'.*available: dev"):
+            file_value('port')
+
+
+# =============================================================================
+# PARSING
+# =============================================================================
+
+class TestParsing:
+
+    def test_lists_join_and_paths_resolve_against_file(self, tmp_path):
+        cfg = tmp_path / 'spokedpy.toml'
+        cfg.write_text('[engines]\nenabled = ["python", "rust"]\n'
+                       '[store]\nsnippets_dir = "snips"\n')
+        loaded = load_file('project', str(cfg))
+        assert loaded.values['engines'] == 'python,rust'
+        assert loaded.values['snippets_dir'] == str(tmp_path / 'snips')
+        assert loaded.raw['engines'] == ['python', 'rust']
+
+    def test_unknown_keys_reported(self, tmp_path):
+        cfg = tmp_path / 'spokedpy.toml'
+        cfg.write_text('[server]\nbogus = 1\n')
+        assert load_file('project', str(cfg)).unknown == ['server.bogus']
+
+    def test_parse_error_names_file(self, tmp_path):
+        cfg = tmp_path / 'spokedpy.toml'
+        cfg.write_text('[server\n')
+        with pytest.raises(ConfigError, match='spokedpy.toml'):
+            load_file('project', str(cfg))
+
+    def test_missing_file_is_none(self, tmp_path):
+        assert load_file('user', str(tmp_path / 'nope.toml')) is None
@@ -0,0 +1,168 @@
+"""
+Test suite for the built-in coverage gate (``--gate coverage --min 80``).
+
+Tests cover:
+  - llvm-cov show line-count parsing
+  - Allow / deny against the minimum, with uncovered line ranges
+  - #[test] snippets also run under the --test harness
+  - Non-Rust snippets skipped; missing LLVM tools deny via GateError
+  - The percentage stored in metrics and the promoted file's header
+(rustc and the LLVM tools are faked — no Rust toolchain needed.)
+"""
+
+import os
+import subprocess
+
+import pytest
+
+from visual_editor_core.coverage_gate import CoverageGate, parse_line_counts
+from visual_editor_core.promotion_gates import (
+    GateDecision, GateError, PromotionGate, evaluate_gates,
+)
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import parse_file_header
+
+
+RUST = """\
+fn classify(n: i32) -> &'static str {
+    if n < 0 {
+        "negative"
+    } else {
+        "non-negative"
+    }
+}
+
+fn main() { println!("{}", classify(3)); }
+"""
+
+# llvm-cov show -show-line-counts for RUST: the negative branch never ran.
+SHOW = """\
+    1|      1|fn classify(n: i32) -> &'static str {
+    2|      1|    if n < 0 {
+    3|      0|        "negative"
+    4|      1|    } else {
+    5|      1|        "non-negative"
+    6|      1|    }
+    7|      1|}
+    8|       |
+    9|  1.20k|fn main() { println!("{}", classify(3)); }
+"""
+
+
+class FakeToolchain:
+    """Stands in for rustc, the instrumented binaries, llvm-profdata and llvm-cov."""
+
+    def __init__(self, show=SHOW, fail=None):
+        self.show, self.fail, self.calls = show, fail, []
+
+    def __call__(self, argv, **kwargs):
+        self.calls.append(argv)
+        tool = os.path.basename(argv[0])
+        if tool == self.fail:
+            return subprocess.CompletedProcess(argv, 1, '', f'{tool}: error')
+        if tool == 'rustc' and '-o' in argv:
+            open(argv[argv.index('-o') + 1], 'w').close()
+        elif 'LLVM_PROFILE_FILE' in kwargs.get('env', {}):
+            profile = kwargs['env']['LLVM_PROFILE_FILE'].replace('%p', '1')
+            open(profile, 'w').close()
+        return subprocess.CompletedProcess(argv, 0, self.show if tool == 'llvm-cov' else '', '')
+
+
+def _gate(runner, minimum=80.0):
+    return CoverageGate(minimum=minimum, runner=runner, rustc='rustc',
+                        profdata='llvm-profdata', llvm_cov='llvm-cov')
+
+
+RUST_HEADER = {'language': 'rust', 'label': 'classify'}
+
+
+class MeasuredGate(PromotionGate):
+    """Test gate that reports a fixed coverage figure."""
+
+    def check(self, header, body):
+        return GateDecision(self.name, True, ['measured'], metrics={'line_coverage': 87.5})
+
+
+# =============================================================================
+# PARSING
+# =============================================================================
+
+class TestParsing:
+
+    def test_counts_executable_and_uncovered_lines(self):
+        assert parse_line_counts(SHOW) == (8, [3])
+
+    def test_ignores_blank_and_non_count_lines(self):
+        assert parse_line_counts('  ------------------\n    1|       |// comment\n') == (0, [])
+
+
+# =============================================================================
+# GATE
+# =============================================================================
+
+class TestCoverageGate:
+
+    def test_above_minimum_allows_with_metric(self):
+        toolchain = FakeToolchain()
+        decision = _gate(toolchain, minimum=80).check(RUST_HEADER, RUST)
+        assert decision.allow
+        assert decision.reasons[0] == 'line coverage 87.5% (7/8 lines, minimum 80%)'
+        assert decision.metrics == {'line_coverage': 87.5}
+        assert toolchain.calls[0][:3] == ['rustc', '-C', 'instrument-coverage']
+        assert toolchain.calls[-1][:2] == ['llvm-cov', 'show']
+
+    def test_below_minimum_denies_with_uncovered_ranges(self):
+        show = SHOW.replace('    4|      1|', '    4|      0|')
+        decision = _gate(FakeToolchain(show), minimum=90).check(RUST_HEADER, RUST)
+        assert not decision.allow
+        assert decision.reasons[1] == 'uncovered lines: 3-4'
+        assert decision.metrics == {'line_coverage': 75.0}
+
+    def test_test_functions_run_under_test_harness(self):
+        toolchain = FakeToolchain()
+        _gate(toolchain).check(RUST_HEADER, RUST + '\n#[test]\nfn t() { classify(-1); }\n')
+        compiles = [argv for argv in toolchain.calls if argv[0] == 'rustc']
+        assert [('--test' in argv) for argv in compiles] == [False, True]
+        show = toolchain.calls[-1]
+        assert show[show.index('-object') + 1].endswith('main_test')
+
+    def test_non_rust_skipped(self):
+        toolchain = FakeToolchain()
+        decision = _gate(toolchain).check({'language': 'python'}, 'x = 1')
+        assert decision.allow and decision.reasons == ['skipped: not a Rust snippet']
+        assert toolchain.calls == []
+
+    def test_failing_tools_deny(self):
+        decision, = evaluate_gates([_gate(FakeToolchain(fail='llvm-profdata'))], RUST_HEADER, RUST)
+        assert not decision.allow and 'llvm-profdata merge failed' in decision.reasons[0]
+
+    def test_missing_llvm_tools(self, monkeypatch):
+        monkeypatch.setattr('shutil.which', lambda name: None)
+        gate = CoverageGate(runner=FakeToolchain(), rustc='rustc')
+        with pytest.raises(GateError, match='llvm-tools-preview'):
+            gate.check(RUST_HEADER, RUST)
+
+    def test_minimum_must_be_percentage(self):
+        with pytest.raises(ValueError):
+            CoverageGate(minimum=120)
+
+
+# =============================================================================
+# METRICS + HEADER
+# =============================================================================
+
+class TestStoredCoverage:
+
+    def test_metric_lands_in_header(self, tmp_path, make_pipeline):
+        pipeline = make_pipeline(gates=[MeasuredGate('coverage')])
+        snippet = pipeline.run_full_pipeline('a', 'python', 'x = 1', 'covered')
+        assert snippet.metrics == {'line_coverage': 87.5}
+        with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+            text = f.read()
+        assert parse_file_header(text).fields['coverage'] == '87.5%'
+
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        assert workspace.diagnose(snippet.saved_file_path, text) == []
+        bad = text.replace('coverage:    87.5%', 'coverage:    most')
+        [diag] = workspace.diagnose(snippet.saved_file_path, bad)
+        assert 'coverage must be a percentage' in diag.message
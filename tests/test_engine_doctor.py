@@ -0,0 +1,131 @@
+"""
+Test suite for the Engine Doctor — toolchain / path / credential diagnostics.
+
+Tests cover:
+  - Engine toolchain detection and version probing (fake binaries on PATH)
+  - Missing engines: WARN by default, FAIL when explicitly required
+  - Path validation (missing, creatable, wrong kind)
+  - Credential checks never echo secret values
+  - Report aggregation and text rendering
+"""
+
+import sys
+import stat
+import pytest
+
+from visual_editor_core import offline
+from visual_editor_core.engine_doctor import (
+    CheckStatus, DoctorReport, run_doctor, check_engines, check_path,
+    check_credentials, find_toolchain, toolchain_hint,
+)
+
+pytestmark = pytest.mark.skipif(sys.platform == 'win32', reason='uses POSIX shell shims')
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def fake_path(tmp_path, monkeypatch):
+    """PATH containing only a fake `rustc` that prints a version."""
+    rustc = tmp_path / 'rustc'
+    rustc.write_text('#!/bin/sh\necho "rustc 1.84.0 (fake 2025-01-01)"\n')
+    rustc.chmod(rustc.stat().st_mode | stat.S_IEXEC)
+    monkeypatch.setenv('PATH', str(tmp_path))
+    return tmp_path
+
+
+# =============================================================================
+# ENGINE CHECKS
+# =============================================================================
+
+class TestEngineChecks:
+
+    def test_found_toolchain_reports_version(self, fake_path):
+        [check] = check_engines(['rust'])
+        assert check.status == CheckStatus.OK
+        assert check.version == 'rustc 1.84.0 (fake 2025-01-01)'
+        assert check.path == str(fake_path / 'rustc')
+
+    def test_missing_engine_warns_with_fix(self, fake_path):
+        [check] = check_engines(['go'])
+        assert check.status == CheckStatus.WARN
+        assert 'go.dev' in check.fix
+
+    def test_required_missing_engine_fails(self, fake_path):
+        [check] = check_engines(['go'], required=['go'])
+        assert check.status == CheckStatus.FAIL
+
+    def test_unknown_engine_fails(self, fake_path):
+        [check] = check_engines(['cobol'])
+        assert check.status == CheckStatus.FAIL
+        assert 'rust' in check.fix
+
+    def test_python_is_always_available(self, fake_path):
+        [check] = check_engines(['python'])
+        assert check.status == CheckStatus.OK
+        assert find_toolchain('python') == sys.executable
+
+    def test_toolchain_hint_mentions_doctor(self):
+        assert 'spokedpy doctor' in toolchain_hint('rust')
+        assert 'rustup' in toolchain_hint('rust')
+
+
+# =============================================================================
+# PATH + CREDENTIAL CHECKS
+# =============================================================================
+
+class TestPathChecks:
+
+    def test_existing_dir_ok(self, tmp_path):
+        assert check_path('snippets_dir', str(tmp_path), 'dir').status == CheckStatus.OK
+
+    def test_creatable_dir_warns(self, tmp_path):
+        check = check_path('snippets_dir', str(tmp_path / 'a' / 'b'), 'dir')
+        assert check.status == CheckStatus.WARN
+        assert check.fix.startswith('mkdir -p')
+
+    def test_file_where_dir_expected_fails(self, tmp_path):
+        f = tmp_path / 'file.txt'
+        f.write_text('x')
+        assert check_path('snippets_dir', str(f), 'dir').status == CheckStatus.FAIL
+
+    def test_empty_path_fails_with_env_hint(self):
+        check = check_path('audit_log', '', 'file', 'SPOKEDPY_AUDIT_LOG')
+        assert check.status == CheckStatus.FAIL
+        assert 'SPOKEDPY_AUDIT_LOG' in check.fix
+
+    def test_credentials_never_echo_values(self):
+        checks = check_credentials({
+            'ai_api_key': ('sk-secret', 'SPOKEDPY_AI_API_KEY', 'AI chat'),
+            'smtp_password': ('', 'SPOKEDPY_SMTP_PASSWORD', 'email'),
+        })
+        assert checks[0].status == CheckStatus.OK
+        assert 'sk-secret' not in str(checks[0].to_dict())
+        assert checks[1].status == CheckStatus.WARN
+
+
+# =============================================================================
+# REPORT
+# =============================================================================
+
+class TestReport:
+
+    def test_report_ok_unless_failure(self, fake_path, tmp_path, monkeypatch):
+        monkeypatch.setitem(offline._state, 'network', 'full')     # no offline checks
+        report = run_doctor(paths={'snippets_dir': (str(tmp_path), 'dir', '')},
+                            languages=['rust', 'go'])
+        assert report.ok
+        assert report.counts() == {'ok': 2, 'warn': 1, 'fail': 0}
+
+        report = run_doctor(languages=['rust', 'go'], required=['go'])
+        assert not report.ok
+
+    def test_text_report_contains_fix_lines(self, fake_path):
+        text = run_doctor(languages=['go']).format_text()
+        assert 'fix:' in text
+        assert '0 failure(s)' in text
+
+    def test_empty_report_is_ok(self):
+        assert DoctorReport().ok
@@ -0,0 +1,160 @@
+"""
+Test suite for typed errors (SpokedError / ErrorKind) and spokedpy exit codes.
+
+Tests cover:
+  - Pipeline refusals carry a kind and structured context (not found, wrong
+    phase, slot full, slot frozen, read-only store, store corruption) and
+    are still ValueErrors
+  - SpokedError survives the trip through a server response; dry-run
+    errors are classified (timeout, missing engine, failure)
+  - spokedpy exits with the kind's code and, with --format json, prints the
+    error as JSON on stdout; an unreachable server exits 3
+  - The man page documents every exit code
+"""
+
+import io
+import json
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+import requests
+
+from visual_editor_core.cli_completion import man_page
+from visual_editor_core.errors import EXIT_CODES, ErrorKind, SpokedError, spec_failure
+from visual_editor_core.slot_constraints import SlotConstraints
+from visual_editor_core.snippet_staging import ReadOnlyStoreError, StoreMode
+
+import spokedpy
+
+
+def _kind(call):
+    with pytest.raises(SpokedError) as info:
+        call()
+    assert isinstance(info.value, ValueError)
+    return info.value
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipelineErrors:
+
+    def test_not_found_and_phase(self, pipeline):
+        error = _kind(lambda: pipeline.promote('stg-nope'))
+        assert error.kind is ErrorKind.NOT_FOUND and error.context == {'staging_id': 'stg-nope'}
+        snippet = pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')
+        error = _kind(lambda: pipeline.promote(snippet.staging_id))
+        assert error.kind is ErrorKind.INVALID_STATE and error.exit_code == 5
+        assert error.context == {'staging_id': snippet.staging_id, 'phase': 'queued'}
+
+    def test_engine_and_slots(self, pipeline):
+        assert _kind(lambda: pipeline.queue_snippet('z', 'cobol', 'x', 'x')).kind is \
+            ErrorKind.ENGINE_MISSING
+        for n in range(16):
+            pipeline.queue_snippet('d', 'rust', f"fn main() {{ /* {n} */ }}", f"r{n}")
+        error = _kind(lambda: pipeline.queue_snippet('d', 'rust', 'fn main() {}', 'full'))
+        assert error.kind is ErrorKind.SLOT_FULL and error.context['max_slots'] == 16
+
+    def test_slot_frozen(self, tmp_path, make_pipeline):
+        path = tmp_path / 'constraints.toml'
+        path.write_text('[slot.a1]\nrequire_spec = true\n', encoding='utf-8')
+        pipeline = make_pipeline(constraints=SlotConstraints(str(path)))
+        snippet = pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')
+        pipeline.speculate(snippet.staging_id)
+        pipeline.verdict(snippet.staging_id, 'approve')
+        error = _kind(lambda: pipeline.promote(snippet.staging_id))
+        assert error.kind is ErrorKind.SLOT_FROZEN and error.context['slot'] == 'a1'
+        assert error.context['violations']
+
+    def test_store(self, make_pipeline):
+        writer = make_pipeline()
+        snippet = writer.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        reader = make_pipeline(mode=StoreMode.READ_ONLY)
+        error = _kind(lambda: reader.queue_snippet('a', 'python', 'print(2)', 'fib'))
+        assert isinstance(error, ReadOnlyStoreError) and error.exit_code == 11
+        snap = {'staging_id': snippet.staging_id, 'code': 'print(2)',
+                'code_hash': snippet.code_hash, 'saved_file_path': snippet.saved_file_path}
+        error = _kind(lambda: reader.load_promoted(snap))
+        assert error.kind is ErrorKind.STORE_CORRUPTION
+        assert error.context['path'] == snippet.saved_file_path
+
+
+# =============================================================================
+# RESPONSES
+# =============================================================================
+
+class TestResponses:
+
+    def test_round_trip(self):
+        error = SpokedError(ErrorKind.GATE_DENIED, 'owned by alice', slot='d3')
+        body = {'success': False, 'error': str(error), 'kind': error.kind.value,
+                'context': error.context}
+        again = SpokedError.from_response(body, 400)
+        assert again.to_dict() == {'kind': 'gate_denied', 'message': 'owned by alice',
+                                   'exit_code': 14, 'context': {'slot': 'd3'}}
+        assert SpokedError.from_response({'error': 'Not found'}, 404).kind is ErrorKind.NOT_FOUND
+        assert SpokedError.from_response({'kind': 'martian'}, 500).kind is ErrorKind.FAILED
+
+    @pytest.mark.parametrize('text, kind', [
+        ('JavaScript execution timed out after 10s', ErrorKind.SPEC_TIMEOUT),
+        ('No executor for language "zig" — install zig', ErrorKind.ENGINE_MISSING),
+        ('AssertionError: 3 != 4', ErrorKind.SPEC_FAILED),
+        (None, ErrorKind.SPEC_FAILED),
+    ])
+    def test_spec_failure(self, text, kind):
+        assert spec_failure(text).kind is kind
+
+    def test_codes_documented(self):
+        assert set(EXIT_CODES) == set(ErrorKind)
+        page = man_page(spokedpy.build_parser(), 'spokedpy')
+        section = page.split('.SH EXIT STATUS')[1]
+        for kind in ErrorKind:
+            assert f"\\fB{kind.exit_code}\\fR ({kind.value})" in section
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestExitCodes:
+
+    def _main(self, argv, api, monkeypatch):
+        monkeypatch.setattr(spokedpy, '_api', api)
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = spokedpy.main(argv)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_kind_from_server(self, monkeypatch):
+        def api(*args, **kwargs):
+            raise SpokedError(ErrorKind.SLOT_FULL, "Engine 'RUST' has no free slots",
+                              engine='RUST', max_slots=16)
+
+        code, _, err = self._main(['list'], api, monkeypatch)
+        assert code == 12 and "✘ list: Engine 'RUST' has no free slots" in err
+        code, out, err = self._main(['list', '--format', 'json'], api, monkeypatch)
+        assert code == 12 and err == ''
+        assert json.loads(out) == {'success': False, 'error': {
+            'kind': 'slot_full', 'message': "list: Engine 'RUST' has no free slots",
+            'exit_code': 12, 'context': {'engine': 'RUST', 'max_slots': 16}}}
+
+    def test_server_unreachable(self, monkeypatch):
+        def api(*args, **kwargs):
+            raise requests.ConnectionError('refused')
+
+        code, _, err = self._main(['--server', 'http://127.0.0.1:9', 'list'], api, monkeypatch)
+        assert code == 3 and 'cannot reach http://127.0.0.1:9' in err
+
+    def test_spec_timeout(self, tmp_path, monkeypatch):
+        path = tmp_path / 'slow.js'
+        path.write_text('while (true) {}\n', encoding='utf-8')
+
+        def api(method, url, **kwargs):
+            return {'success': True, 'snippet': {
+                'staging_id': 'stg-1', 'label': 'slow', 'language': 'javascript',
+                'reserved_address': 'b1', 'phase': 'failed', 'spec_success': False,
+                'spec_error': 'JavaScript execution timed out after 10s'}}
+
+        code, out, _ = self._main(['stage', str(path), '--format', 'json'], api, monkeypatch)
+        assert code == 15 and json.loads(out)['error']['kind'] == 'spec_timeout'
@@ -0,0 +1,151 @@
+"""
+Test suite for failure bundles (data/failures/<staging_id>/).
+
+Tests cover:
+  - A failing spec run writes bundle.json, source, stdout/stderr, fixtures
+  - Exit status, per-case results and the environment summary are kept;
+    a case stopped at its first divergent stdout line is 'stopped', with
+    its stderr
+  - Passing runs write nothing; re-speculating replaces the bundle
+  - Compile-phase output lands in diagnostics.txt
+  - spokedpy failures show prints the bundle (text and json), exit 1 if missing
+"""
+
+import io
+import json
+import os
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.failure_bundle import format_bundle, read_bundle, write_bundle
+from visual_editor_core.spec_suite import parse_spec
+
+import spokedpy
+
+
+FAILING = 'import sys\nprint("partial")\nprint("bad input", file=sys.stderr)\nsys.exit(3)\n'
+STALLING = ('import sys, time\nprint("bad input", file=sys.stderr, flush=True)\n'
+            'print("partial")\ntime.sleep(30)\n')
+SPEC = ('[[case]]\nname = "greets"\nargs = ["--name", "ada"]\nstdin = "ada\\n"\n'
+        'stdout = "hi ada\\n"\n')
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(failures_dir='failures')
+
+
+def _failed(pipeline, code=FAILING, spec=SPEC):
+    snippet = pipeline.queue_snippet('a', 'python', code, 'greeter', spec=parse_spec(spec))
+    pipeline.speculate(snippet.staging_id)
+    return pipeline, snippet
+
+
+# =============================================================================
+# WRITING BUNDLES
+# =============================================================================
+
+class TestWriteBundle:
+
+    def test_failing_spec_writes_bundle(self, tmp_path, pipeline):
+        pipeline, snippet = _failed(pipeline)
+        path = tmp_path / 'failures' / snippet.staging_id
+        assert snippet.failure_bundle == str(path)
+        assert {'bundle.json', 'source.py', 'stdout.txt', 'stderr.txt', 'fixtures.json'} <= \
+            set(os.listdir(path))
+        assert (path / 'source.py').read_text() == FAILING
+        assert 'partial' in (path / 'stdout.txt').read_text()
+        assert 'bad input' in (path / 'stderr.txt').read_text()
+        fixtures = json.loads((path / 'fixtures.json').read_text())
+        assert fixtures[0]['args'] == ['--name', 'ada'] and fixtures[0]['stdin'] == 'ada\n'
+
+        bundle = read_bundle(str(tmp_path / 'failures'), snippet.staging_id)
+        assert bundle['exit_status'] == 3 and bundle['phase'] == 'failed'
+        assert bundle['cases'][0]['name'] == 'greets' and not bundle['cases'][0]['passed']
+        assert bundle['processes'][0]['case'] == 'greets'
+        assert bundle['processes'][0]['exit_code'] == 3
+        assert bundle['environment']['python'] and 'offline' in bundle['environment']
+        events = [e['event'] for e in pipeline.get_audit_trail(snippet.staging_id)]
+        assert 'failure_recorded' in events
+
+    def test_passing_run_writes_nothing(self, tmp_path, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', 'print("hi")', 'ok')
+        pipeline.speculate(snippet.staging_id)
+        assert snippet.phase.value == 'passed' and snippet.failure_bundle == ''
+        assert not os.path.exists(tmp_path / 'failures' / snippet.staging_id)
+
+    def test_respeculating_replaces_bundle(self, tmp_path, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', FAILING, 'greeter', spec=parse_spec(SPEC))
+        pipeline.speculate(snippet.staging_id, on_event=None)
+        stale = tmp_path / 'failures' / snippet.staging_id / 'stale.txt'
+        stale.write_text('old')
+        events = []
+        pipeline.speculate(snippet.staging_id, on_event=events.append)
+        assert not stale.exists() and events[-1]['event'] == 'spec_finished'
+
+    def test_without_failures_dir_nothing_is_written(self, make_pipeline):
+        pipeline = make_pipeline()
+        snippet = pipeline.queue_snippet('a', 'python', '1/0', 'boom')
+        pipeline.speculate(snippet.staging_id)
+        assert snippet.phase.value == 'failed' and snippet.failure_bundle == ''
+
+    def test_compile_output_is_diagnostics(self, tmp_path, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', '1/0', 'boom')
+        processes = [{'process': 'rustc', 'phase': 'compile', 'case': '', 'stdout': '',
+                      'stderr': 'error[E0425]: cannot find value `x`\n', 'exit_code': 1,
+                      'duration': 0.2}]
+        path = write_bundle(str(tmp_path / 'failures'), snippet, processes, '.rs')
+        with open(os.path.join(path, 'diagnostics.txt')) as f:
+            assert 'E0425' in f.read()
+        assert os.path.exists(os.path.join(path, 'source.rs'))
+        assert 'E0425' in format_bundle(read_bundle(str(tmp_path / 'failures'), snippet.staging_id))
+
+    def test_stopped_case(self, tmp_path, pipeline):
+        _, snippet = _failed(pipeline, code=STALLING)
+        path = tmp_path / 'failures' / snippet.staging_id
+        assert 'bad input' in (path / 'stderr.txt').read_text()
+        bundle = read_bundle(str(tmp_path / 'failures'), snippet.staging_id)
+        assert bundle['exit_status'] == 'stopped'
+        assert bundle['cases'][0]['stopped'] and bundle['cases'][0]['error'].endswith('bad input')
+        assert bundle['processes'][0]['stopped'] and bundle['processes'][0]['exit_code'] is None
+        text = format_bundle(bundle)
+        assert 'exit status: stopped' in text and '(stopped)' in text
+
+    def test_bad_staging_id(self, tmp_path):
+        with pytest.raises(ValueError):
+            read_bundle(str(tmp_path), '../etc')
+
+
+# =============================================================================
+# spokedpy failures show
+# =============================================================================
+
+class TestFailuresShow:
+
+    def _show(self, monkeypatch, tmp_path, *argv):
+        monkeypatch.setenv('SPOKEDPY_FAILURES_DIR', str(tmp_path / 'failures'))
+        args = spokedpy.build_parser().parse_args(['failures', 'show', *argv])
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = args.func(args)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_text(self, tmp_path, pipeline, monkeypatch):
+        _, snippet = _failed(pipeline)
+        code, out, _ = self._show(monkeypatch, tmp_path, snippet.staging_id)
+        assert code == 0
+        assert 'exit status: 3' in out and 'FAIL  greets' in out
+        assert 'partial' in out and 'bad input' in out
+        assert 'greets: args=["--name", "ada"]' in out and 'toolchain' in out
+
+    def test_json(self, tmp_path, pipeline, monkeypatch):
+        _, snippet = _failed(pipeline)
+        code, out, _ = self._show(monkeypatch, tmp_path, snippet.staging_id, '--format', 'json')
+        bundle = json.loads(out)
+        assert code == 0 and bundle['staging_id'] == snippet.staging_id
+        assert bundle['label'] == 'greeter'
+
+    def test_missing_bundle(self, tmp_path, monkeypatch):
+        code, _, err = self._show(monkeypatch, tmp_path, 'stg-nope')
+        assert code == 1 and 'No failure bundle' in err
@@ -0,0 +1,217 @@
+"""
+Test suite for libspoked, the C ABI of the store (ffi/).
+
+The library is built with the system C compiler into a temp dir and loaded
+through ctypes; without a compiler the tests are skipped.
+
+Tests cover:
+  - spoked_resolve_slot / spoked_list_slots agree with SlotResolver: the
+    latest promotion wins (promoted_seq on a tie), rolled-back snippets are
+    left out, shard dirs and sidecars are handled, slots are case-insensitive
+  - The same on the bundled data/snippets store (banner v1, flat dirs)
+  - spoked_verify_hash / spoked_read_code agree with body_matches_hash and
+    read_code: edited, CRLF, not a snippet, missing file
+  - spoked_refresh picks up a new promotion
+"""
+
+import ctypes
+import os
+import shutil
+import subprocess
+
+import pytest
+
+from visual_editor_core.errors import EXIT_CODES, ErrorKind
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.slot_resolver import SlotResolver
+from visual_editor_core.snippet_staging import parse_file_header
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+FFI = os.path.join(ROOT, 'ffi')
+
+OK, FAILED, NOT_FOUND, CORRUPTION = 0, 1, 4, 10
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(node_registry=NodeRegistry(SessionLedger()))
+
+
+class Snippet(ctypes.Structure):
+    _fields_ = [(name, ctypes.c_char_p) for name in (
+        'slot', 'path', 'code_hash', 'label', 'engine', 'language', 'staging_id', 'promoted')] + [
+        ('mtime_ns', ctypes.c_longlong), ('promoted_seq', ctypes.c_longlong)]
+
+
+@pytest.fixture(scope='module')
+def lib(tmp_path_factory):
+    cc = shutil.which(os.environ.get('CC', 'cc')) or shutil.which('gcc')
+    if cc is None:
+        pytest.skip('no C compiler')
+    out = str(tmp_path_factory.mktemp('ffi') / 'libspoked.so')
+    subprocess.run([cc, '-std=c99', '-O2', '-Wall', '-Wextra', '-Werror', '-fPIC', '-shared',
+                    '-fvisibility=hidden', '-o', out, os.path.join(FFI, 'spoked.c')], check=True)
+    lib = ctypes.CDLL(out)
+    lib.spoked_open.restype = ctypes.c_void_p
+    lib.spoked_open.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+    lib.spoked_refresh.argtypes = [ctypes.c_void_p]
+    lib.spoked_close.argtypes = [ctypes.c_void_p]
+    lib.spoked_resolve_slot.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.POINTER(Snippet)]
+    lib.spoked_list_slots.restype = ctypes.c_size_t
+    lib.spoked_list_slots.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_char_p),
+                                      ctypes.c_size_t]
+    lib.spoked_verify_hash.argtypes = [ctypes.c_char_p]
+    lib.spoked_read_code.argtypes = [ctypes.c_char_p, ctypes.POINTER(ctypes.c_void_p),
+                                     ctypes.POINTER(ctypes.c_size_t)]
+    lib.spoked_free.argtypes = [ctypes.c_void_p]
+    return lib
+
+
+class Store:
+    """libspoked through ctypes, shaped like SlotResolver."""
+
+    def __init__(self, lib, snippets_dir, audit_log=''):
+        self.lib = lib
+        self.handle = lib.spoked_open(snippets_dir.encode(), audit_log.encode())
+
+    def resolve(self, slot):
+        out = Snippet()
+        if self.lib.spoked_resolve_slot(self.handle, slot.encode(), ctypes.byref(out)) != OK:
+            return None
+        return {name: (getattr(out, name).decode() if isinstance(getattr(out, name), bytes)
+                       else getattr(out, name)) for name, _type in Snippet._fields_}
+
+    def slots(self):
+        total = self.lib.spoked_list_slots(self.handle, None, 0)
+        names = (ctypes.c_char_p * total)()
+        assert self.lib.spoked_list_slots(self.handle, names, total) == total
+        return [n.decode() for n in names]
+
+    def close(self):
+        self.lib.spoked_close(self.handle)
+
+
+def _read_code(lib, path):
+    code, length = ctypes.c_void_p(), ctypes.c_size_t()
+    rc = lib.spoked_read_code(path.encode(), ctypes.byref(code), ctypes.byref(length))
+    if rc != OK:
+        return rc, None
+    text = ctypes.string_at(code, length.value).decode()
+    lib.spoked_free(code)
+    return rc, text
+
+
+def _same(store, resolver):
+    expected = resolver.slots()
+    assert store.slots() == sorted(expected)
+    for slot, snippet in expected.items():
+        got = store.resolve(slot)
+        assert got is not None, slot
+        for name in ('path', 'code_hash', 'label', 'engine', 'language', 'staging_id',
+                     'promoted', 'mtime_ns', 'promoted_seq'):
+            assert got[name] == getattr(snippet, name), (slot, name)
+
+
+# =============================================================================
+# RESOLUTION
+# =============================================================================
+
+class TestResolve:
+
+    def test_matches_slot_resolver(self, lib, tmp_path, pipeline, monkeypatch):
+        import visual_editor_core.snippet_staging as mod
+        monkeypatch.setattr(mod.time, 'time', lambda: 1_770_724_634.0)
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        assert old.reserved_address == new.reserved_address   # a tie: promoted_seq decides
+        os.utime(new.saved_file_path, (1_000, 1_000))        # mtime says the opposite
+        monkeypatch.undo()
+        gone = pipeline.run_full_pipeline('a', 'python', 'print(3)', 'gone')
+        pipeline.rollback(gone.staging_id, 'test')
+        (tmp_path / 'snippets' / 'python' / 'notes.txt').write_text('not a snippet\n')
+        snippets, audit = str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl')
+
+        resolver = SlotResolver(snippets, audit)
+        store = Store(lib, snippets, audit)
+        try:
+            _same(store, resolver)
+            assert store.slots() == [new.reserved_address]
+            assert store.resolve(new.reserved_address)['staging_id'] == new.staging_id
+            assert store.resolve(f" {new.reserved_address.upper()} ")['staging_id'] == new.staging_id
+            assert store.resolve('z99') is None
+        finally:
+            store.close()
+        without_audit = Store(lib, snippets)
+        try:
+            assert without_audit.resolve(gone.reserved_address)['staging_id'] == gone.staging_id
+            _same(without_audit, SlotResolver(snippets))
+        finally:
+            without_audit.close()
+
+    def test_bundled_store(self, lib):
+        snippets = os.path.join(ROOT, 'data', 'snippets')
+        resolver = SlotResolver(snippets)
+        store = Store(lib, os.path.relpath(snippets))         # abspath'd like SlotResolver
+        try:
+            assert store.slots()
+            _same(store, resolver)
+            for slot, snippet in resolver.slots().items():
+                with open(snippet.path, 'r', encoding='utf-8') as f:
+                    good = parse_file_header(f.read()).body_matches_hash()
+                assert (lib.spoked_verify_hash(snippet.path.encode()) == OK) == good, slot
+        finally:
+            store.close()
+
+    def test_refresh(self, lib, tmp_path, pipeline):
+        first = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'one')
+        store = Store(lib, str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        try:
+            assert store.resolve(first.reserved_address)['staging_id'] == first.staging_id
+            second = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'one')
+            assert store.resolve(second.reserved_address) in (      # read at open …
+                None, store.resolve(first.reserved_address))
+            assert lib.spoked_refresh(store.handle) == OK           # … and on refresh
+            assert store.resolve(second.reserved_address)['staging_id'] == second.staging_id
+        finally:
+            store.close()
+        missing = Store(lib, str(tmp_path / 'nowhere'))
+        assert missing.slots() == []
+        missing.close()
+
+
+# =============================================================================
+# HASHES
+# =============================================================================
+
+class TestVerify:
+
+    def test_read_code(self, lib, tmp_path, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python', 'print("é")\n', 'one')
+        path = snippet.saved_file_path
+        resolved = SlotResolver(str(tmp_path / 'snippets')).resolve_slot(snippet.reserved_address)
+        assert lib.spoked_verify_hash(path.encode()) == OK
+        assert _read_code(lib, path) == (OK, resolved.read_code())
+
+        with open(path, 'a', encoding='utf-8') as f:
+            f.write('\n')                                     # an editor's trailing newline
+        assert lib.spoked_verify_hash(path.encode()) == OK
+        with open(path, 'rb') as f:
+            raw = f.read()
+        with open(path, 'wb') as f:
+            f.write(raw.replace(b'\r\n', b'\n').replace(b'\n', b'\r\n'))
+        assert lib.spoked_verify_hash(path.encode()) == OK   # read as text mode reads it
+        with open(path, 'a', encoding='utf-8') as f:
+            f.write('print("edited")\n')
+        assert lib.spoked_verify_hash(path.encode()) == CORRUPTION
+        assert CORRUPTION == EXIT_CODES[ErrorKind.STORE_CORRUPTION]
+        with pytest.raises(ValueError):
+            resolved.read_code()
+
+    def test_not_a_snippet(self, lib, tmp_path):
+        plain = tmp_path / 'plain.py'
+        plain.write_text('print(1)\n')
+        assert lib.spoked_verify_hash(str(plain).encode()) == NOT_FOUND
+        assert NOT_FOUND == EXIT_CODES[ErrorKind.NOT_FOUND]
+        assert lib.spoked_verify_hash(str(tmp_path / 'missing.py').encode()) == FAILED
+        assert lib.spoked_abi_version() == 1
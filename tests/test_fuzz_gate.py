@@ -0,0 +1,124 @@
+"""
+Test suite for the built-in fuzz gate (``--gate fuzz``).
+
+Tests cover:
+  - fuzz_target: hint parsing and harness generation
+  - Non-Rust / unhinted snippets are skipped, not denied
+  - Crash artifacts deny and are persisted as regression fixtures
+  - Saved fixtures are replayed as seed corpus on the next run
+  - A harness that fails to build denies via GateError
+(cargo is faked — no Rust toolchain needed.)
+"""
+
+import os
+import subprocess
+
+import pytest
+
+from visual_editor_core.fuzz_gate import FuzzGate, FuzzTarget, fuzz_harness, parse_fuzz_target
+from visual_editor_core.promotion_gates import GateError, evaluate_gates
+
+
+RUST = """\
+// fuzz_target: parse(&str)
+fn parse(s: &str) -> usize {
+    s.split(',').map(|f| f.len()).sum()
+}
+
+fn main() { println!("{}", parse("a,bc")); }
+"""
+
+
+class FakeCargo:
+    """Stands in for `cargo fuzz run`; optionally leaves a crash artifact."""
+
+    def __init__(self, crash=b'', returncode=None, output='Done 1234 runs in 2 second(s)'):
+        self.crash, self.output, self.calls = crash, output, []
+        self.returncode = returncode if returncode is not None else (1 if crash else 0)
+
+    def __call__(self, argv, cwd, **kwargs):
+        self.calls.append({'argv': argv, 'cwd': cwd, **kwargs,
+                           'lib': open(os.path.join(cwd, 'src', 'lib.rs')).read()})
+        if self.crash:
+            artifacts = os.path.join(cwd, 'fuzz', 'artifacts', 'snippet')
+            os.makedirs(artifacts)
+            with open(os.path.join(artifacts, 'crash-deadbeef'), 'wb') as f:
+                f.write(self.crash)
+        return subprocess.CompletedProcess(argv, self.returncode, '', self.output)
+
+
+def _gate(tmp_path, runner, seconds=5):
+    return FuzzGate(seconds=seconds, fixtures_dir=str(tmp_path / 'fixtures'),
+                    runner=runner, cargo='cargo')
+
+
+RUST_HEADER = {'language': 'rust', 'label': 'csv fields'}
+
+
+# =============================================================================
+# HARNESS
+# =============================================================================
+
+class TestHarness:
+
+    def test_hint_parsing(self):
+        assert parse_fuzz_target(RUST) == FuzzTarget('parse', '&str')
+        assert parse_fuzz_target('// fuzz_target: decode\nfn decode(b: &[u8]) {}') == \
+            FuzzTarget('decode', '&[u8]')
+        assert parse_fuzz_target('fn main() {}') is None
+        with pytest.raises(GateError, match='unsupported argument type'):
+            parse_fuzz_target('// fuzz_target: f(u32)')
+
+    def test_harness_exports_target_and_calls_it(self):
+        files = fuzz_harness(RUST, FuzzTarget('parse', '&str'))
+        assert 'pub fn parse(s: &str)' in files['src/lib.rs']
+        target = files['fuzz/fuzz_targets/snippet.rs']
+        assert 'fuzz_target!(|data: &[u8]|' in target
+        assert 'std::str::from_utf8(data)' in target and 'spokedpy_snippet::parse(s)' in target
+        assert 'cargo-fuzz = true' in files['fuzz/Cargo.toml']
+
+    def test_missing_function_rejected(self):
+        with pytest.raises(GateError, match='defines no fn nope'):
+            fuzz_harness('fn main() {}', FuzzTarget('nope'))
+
+
+# =============================================================================
+# GATE
+# =============================================================================
+
+class TestFuzzGate:
+
+    def test_skips_without_running_cargo(self, tmp_path):
+        cargo = FakeCargo()
+        gate = _gate(tmp_path, cargo)
+        assert gate.check({'language': 'python'}, 'x = 1').allow
+        decision = gate.check(RUST_HEADER, 'fn main() {}')
+        assert decision.allow and 'no fuzz_target' in decision.reasons[0]
+        assert cargo.calls == []
+
+    def test_clean_run_allows(self, tmp_path):
+        cargo = FakeCargo()
+        decision = _gate(tmp_path, cargo, seconds=7).check(RUST_HEADER, RUST)
+        assert decision.allow and '1,234 runs' in decision.reasons[0]
+        argv = cargo.calls[0]['argv']
+        assert argv[:4] == ['cargo', 'fuzz', 'run', 'snippet']
+        assert argv[-1] == '-max_total_time=7'
+        assert cargo.calls[0]['env']['RUSTUP_TOOLCHAIN'] == 'nightly'
+
+    def test_crash_denies_and_becomes_fixture(self, tmp_path):
+        gate = _gate(tmp_path, FakeCargo(crash=b'\xff,,', output="thread panicked at 'index'"))
+        decision = gate.check(RUST_HEADER, RUST)
+        assert not decision.allow
+        assert 'crash-deadbeef' in decision.reasons[0]
+        assert "panicked at 'index'" in decision.reasons[1]
+        fixture = tmp_path / 'fixtures' / 'csv_fields' / 'crash-deadbeef'
+        assert fixture.read_bytes() == b'\xff,,'
+
+        replay = FakeCargo()
+        _gate(tmp_path, replay).check(RUST_HEADER, RUST)
+        assert str(tmp_path / 'fixtures' / 'csv_fields') in replay.calls[0]['argv']
+
+    def test_build_failure_denies(self, tmp_path):
+        gate = _gate(tmp_path, FakeCargo(returncode=101, output='error: no such command: `fuzz`'))
+        decision, = evaluate_gates([gate], RUST_HEADER, RUST)
+        assert not decision.allow and 'no such command' in decision.reasons[0]
@@ -0,0 +1,154 @@
+"""
+Test suite for banner versioning (banner_version) and ``spokedpy migrate-headers``.
+
+Tests cover:
+  - promote() writes banner_version 2 with the full code_hash
+  - Version 1 files (no banner_version, 16-digit code_hash) still parse and
+    get an informational "old layout" diagnostic, not errors
+  - migrate_text upgrades v1 → v2 and leaves the body alone
+  - migrate_headers backs up before writing, keeps line endings and mtime,
+    skips edited and unknown-version files, and --dry-run writes nothing
+"""
+
+import hashlib
+import io
+import os
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core.header_migration import migrate_headers, migrate_text
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import BANNER_VERSION, parse_file_header
+
+import spokedpy
+
+BODY = 'def fib(n):\n    return n if n < 2 else fib(n - 1) + fib(n - 2)\nprint(fib(10))\n'
+
+
+def _v1(body=BODY, code_hash=None):
+    """A banner as promote() wrote it before banner_version existed."""
+    code_hash = code_hash or hashlib.sha256(body.encode('utf-8')).hexdigest()[:16]
+    return (
+        "# ═══════════════════════════════════════════════════════\n"
+        "#  VPyD Staged Snippet — PROMOTED TO PRODUCTION\n"
+        "#  staging_id:  stg-1eff746c636f\n"
+        "#  language:    python\n"
+        "#  engine:      PYTHON (a)\n"
+        "#  slot:        a1 (position 1)\n"
+        "#  label:       fib\n"
+        f"#  code_hash:   {code_hash}…\n"
+        "#  created:     2026-02-10T11:57:14Z\n"
+        "#  promoted:    2026-02-10T11:57:14Z\n"
+        "#  spec_time:   0.0014s\n"
+        "#  spec_result: PASS\n"
+        "# ═══════════════════════════════════════════════════════\n"
+        "\n" + body
+    )
+
+
+@pytest.fixture
+def snippets(tmp_path):
+    root = tmp_path / 'snippets' / 'python'
+    root.mkdir(parents=True)
+    return root
+
+
+# =============================================================================
+# VERSIONED PARSING
+# =============================================================================
+
+class TestBannerVersion:
+
+    def test_promote_writes_current_layout(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        assert header.version == BANNER_VERSION == 2
+        assert header.fields['banner_version'] == '2'
+        assert header.fields['code_hash'] == snippet.code_hash and header.body_matches_hash()
+
+    def test_v1_still_reads(self, snippets):
+        header = parse_file_header(_v1())
+        assert header.version == 1 and 'banner_version' not in header.required_fields
+        assert header.body == BODY and header.body_matches_hash()
+        path = snippets / 'a1_stg-1eff746c636f.py'
+        diagnostics = SnippetWorkspace(str(snippets.parent)).diagnose(str(path), _v1())
+        assert [(d.code, d.severity) for d in diagnostics] == [('banner', 3)]
+        assert 'migrate-headers' in diagnostics[0].message
+
+    def test_unknown_version_flagged(self, snippets):
+        text = _v1().replace('PRODUCTION\n', 'PRODUCTION\n#  banner_version: 9\n', 1)
+        assert parse_file_header(text).version == 9
+        messages = [d.message for d in SnippetWorkspace(str(snippets.parent)).diagnose(
+            str(snippets / 'a1.py'), text)]
+        assert any("Unknown banner_version '9'" in m for m in messages)
+
+
+# =============================================================================
+# MIGRATION
+# =============================================================================
+
+class TestMigrateText:
+
+    def test_v1_to_v2(self):
+        migrated, version = migrate_text(_v1())
+        header = parse_file_header(migrated)
+        assert version == 1 and header.version == 2
+        assert header.fields['code_hash'] == hashlib.sha256(BODY.encode()).hexdigest()
+        assert header.body == BODY and header.fields['label'] == 'fib'
+        assert migrate_text(migrated) == (migrated, 2)
+
+    def test_edited_body_refused(self):
+        with pytest.raises(ValueError, match='no longer matches code_hash'):
+            migrate_text(_v1(body=BODY + 'print("edited")\n',
+                             code_hash=hashlib.sha256(BODY.encode()).hexdigest()[:16]))
+
+
+class TestMigrateHeaders:
+
+    def _tree(self, snippets):
+        old = snippets / 'a1_old.py'
+        old.write_bytes(_v1().replace('\n', '\r\n').encode('utf-8'))
+        os.utime(old, (1_700_000_000, 1_700_000_000))
+        edited = snippets / 'a2_edited.py'
+        edited.write_text(_v1(body='x = 1\n', code_hash='0' * 16), encoding='utf-8')
+        current, _ = migrate_text(_v1())
+        (snippets / 'a3_current.py').write_text(current, encoding='utf-8')
+        (snippets / 'a1_old.spec.toml').write_text('[[case]]\nname = "x"\n', encoding='utf-8')
+        return old, edited
+
+    def test_one_pass_with_backup(self, snippets, tmp_path):
+        old, edited = self._tree(snippets)
+        original = old.read_bytes()
+        report = migrate_headers(str(snippets.parent), str(tmp_path / 'backup'))
+        assert [(os.path.basename(p), v) for p, v in report.migrated] == [('a1_old.py', 1)]
+        assert report.current == 1
+        assert [os.path.basename(p) for p, _ in report.skipped] == ['a2_edited.py']
+        assert (tmp_path / 'backup' / 'python' / 'a1_old.py').read_bytes() == original
+        assert not (tmp_path / 'backup' / 'python' / 'a2_edited.py').exists()
+        data = old.read_bytes()
+        assert b'banner_version: 2\r\n' in data and data.count(b'\n') == data.count(b'\r\n')
+        assert os.stat(old).st_mtime == 1_700_000_000
+        assert parse_file_header(data.decode().replace('\r\n', '\n')).body_matches_hash()
+
+    def test_dry_run_writes_nothing(self, snippets, tmp_path):
+        old, _ = self._tree(snippets)
+        before = old.read_bytes()
+        report = migrate_headers(str(snippets.parent), str(tmp_path / 'backup'), dry_run=True)
+        assert len(report.migrated) == 1 and old.read_bytes() == before
+        assert not (tmp_path / 'backup').exists()
+
+    def test_cli(self, snippets, tmp_path, monkeypatch):
+        self._tree(snippets)
+        monkeypatch.setenv('SPOKEDPY_SNIPPETS_DIR', str(snippets.parent))
+        args = spokedpy.build_parser().parse_args(
+            ['migrate-headers', '--backup-dir', str(tmp_path / 'backup')])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 1                     # a2_edited was skipped
+        assert 'migrated 1 file(s) to banner_version 2' in out.getvalue()
+        assert 'a2_edited.py — body no longer matches code_hash' in out.getvalue()
+        with redirect_stdout(io.StringIO()):
+            assert args.func(args) == 1
+        assert len(os.listdir(tmp_path / 'backup' / 'python')) == 1
@@ -0,0 +1,63 @@
+"""
+Test suite for the JUnit reporter (``spokedpy show --format junit``).
+
+Tests cover:
+  - One testcase per spec case, failures carrying the first diff line
+  - Snippets without a suite report a single dry-run case
+  - Promoted files read back through their spec_case: header lines
+"""
+
+import io
+import xml.etree.ElementTree as ET
+from contextlib import redirect_stdout
+
+from visual_editor_core.junit_report import junit_xml
+from visual_editor_core.spec_suite import parse_spec
+
+import spokedpy
+
+
+SPEC = """\
+[[case]]
+name = "one"
+stdout = "1\\n"
+
+[[case]]
+name = "two"
+stdout = "2\\n"
+"""
+
+
+class TestJUnit:
+
+    def test_case_per_testcase_with_failures(self, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', 'print(1)', 'counter',
+                                         spec=parse_spec(SPEC))
+        snippet = pipeline.speculate(snippet.staging_id)
+        root = ET.fromstring(junit_xml([snippet.to_dict()]))
+        assert (root.get('tests'), root.get('failures')) == ('2', '1')
+        [suite] = root
+        assert suite.get('name') == f"counter ({snippet.staging_id})"
+        one, two = suite.findall('testcase')
+        assert one.get('classname') == 'python.counter' and one.find('failure') is None
+        failure = two.find('failure')
+        assert failure.get('message') == "line 1: expected '2', got '1'"
+        assert two.find('system-out').text == '1\n'
+
+    def test_snippet_without_suite_is_one_dry_run(self, pipeline):
+        snippet = pipeline.speculate(pipeline.queue_snippet('a', 'python', '1/0', 'boom').staging_id)
+        [case] = ET.fromstring(junit_xml([snippet.to_dict()]))[0]
+        assert case.get('name') == 'dry-run'
+        assert 'division by zero' in case.find('failure').get('message')
+
+    def test_show_reads_promoted_file(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'counter',
+                                             spec=parse_spec(SPEC.replace('"2\\n"', '"1\\n"')))
+        args = spokedpy.build_parser().parse_args(
+            ['show', snippet.saved_file_path, '--format', 'junit'])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        root = ET.fromstring(out.getvalue())
+        assert [c.get('name') for c in root.iter('testcase')] == ['one', 'two']
+        assert root.get('failures') == '0'
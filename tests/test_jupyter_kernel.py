@@ -0,0 +1,103 @@
+"""
+Test suite for the Jupyter kernel bridge (``%%spoked`` cell magic).
+
+Tests cover:
+  - Cell magic parsing: run / stage / lang, slot → language inference
+  - Plain cells run in the current language
+  - Staging a cell queues + speculates it without promoting
+  - Kernelspec contents; the ipykernel class itself
+    (skipped unless the optional ipykernel package is installed)
+"""
+
+import pytest
+
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.jupyter_kernel import (
+    CellMagicError, CellRunner, kernel_spec, make_kernel_class, parse_cell,
+)
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def runner(pipeline):
+    def stage(letter, language, code, label):
+        snippet = pipeline.queue_snippet(letter, language, code, label, author='notebook')
+        return pipeline.speculate(snippet.staging_id).to_dict()
+    return CellRunner('python', pipeline.evaluate, stage)
+
+
+# =============================================================================
+# PARSING
+# =============================================================================
+
+class TestParseCell:
+
+    def test_plain_cell(self):
+        request = parse_cell('x = 1\nprint(x)')
+        assert (request.action, request.body, request.language) == ('run', 'x = 1\nprint(x)', '')
+
+    def test_stage_infers_language_from_slot(self):
+        request = parse_cell('%%spoked stage --label Fib --slot d3\nfn main() {}')
+        assert (request.action, request.label, request.slot, request.language) == \
+               ('stage', 'Fib', 'd3', 'rust')
+        assert request.body == 'fn main() {}'
+
+    def test_lang_switch(self):
+        assert parse_cell('%%spoked lang go').language == 'go'
+
+    @pytest.mark.parametrize('line', [
+        '%%spoked stage --slot d3',          # no label
+        '%%spoked deploy',                   # unknown action
+        '%%spoked run rust',                 # positional language outside `lang`
+        '%%spoked lang',
+    ])
+    def test_bad_magic(self, line):
+        with pytest.raises(CellMagicError):
+            parse_cell(line + '\nx = 1')
+
+
+# =============================================================================
+# EXECUTION
+# =============================================================================
+
+class TestCellRunner:
+
+    def test_plain_cell_runs(self, runner):
+        text, ok = runner.execute('print(6 * 7)')
+        assert ok and text.startswith('42')
+
+    def test_failing_cell_reports_error(self, runner):
+        text, ok = runner.execute('1/0')
+        assert not ok and 'division' in text
+
+    def test_stage_cell(self, runner, pipeline):
+        text, ok = runner.execute('%%spoked stage --label Answer --slot a3\nprint(42)')
+        snippet, = pipeline.get_active()
+        assert ok and snippet.staging_id in text
+        assert (snippet.label, snippet.phase, snippet.author) == ('Answer', StagingPhase.PASSED, 'notebook')
+
+    def test_lang_cell_changes_default(self, runner):
+        text, ok = runner.execute('%%spoked lang ruby')
+        assert ok and runner.language == 'ruby'
+        text, ok = runner.execute('%%spoked lang cobol')
+        assert not ok and 'Unknown language' in text and runner.language == 'ruby'
+
+
+# =============================================================================
+# KERNELSPEC
+# =============================================================================
+
+class TestKernel:
+
+    def test_kernel_spec(self):
+        spec = kernel_spec('http://10.0.0.5:5002', python='/usr/bin/python3')
+        assert spec['argv'][:3] == ['/usr/bin/python3', '-m', 'visual_editor_core.jupyter_kernel']
+        assert spec['env']['SPOKEDPY_SERVER'] == 'http://10.0.0.5:5002'
+
+    def test_kernel_class(self):
+        pytest.importorskip('ipykernel')
+        kernel_class = make_kernel_class()
+        assert kernel_class.implementation == 'spokedpy'
@@ -0,0 +1,212 @@
+"""
+Test suite for per-label default specs (label_specs, [store] specs_dir).
+
+Tests cover:
+  - data/specs/<label>/default.spec.toml: nested labels, unsafe labels
+    ignored, listing, a malformed label spec reported with its path
+  - inherit(): the label's cases first, a redefined case or a different
+    fixture refused, the suite records what it inherited
+  - Staging: every engine's snippet under the label runs the label's
+    cases (with or without a sidecar), spec_hash covers them, a clash is a
+    usage error, the header gets spec_inherits:, only the own sidecar is
+    copied next to the promoted file
+  - The LSP's stale-spec check hashes the sidecar with the label spec
+  - spokedpy spec run: inherited cases run and are marked, no sidecar
+    needed
+"""
+
+import io
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.execution_engine import ExecutionResult
+from visual_editor_core.label_specs import LabelSpecs, inherit, label_dir
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import StagingPhase, parse_file_header
+from visual_editor_core.spec_suite import SpecSuite, load_spec, parse_spec, sidecar_for
+
+LABEL_SPEC = """\
+fixtures = ["terms.txt"]
+
+[[case]]
+name = "first ten"
+stdout = "0 1 1 2 3 5 8 13 21 34\\n"
+"""
+
+OWN_SPEC = """\
+[[case]]
+name = "exits cleanly"
+exit_code = 0
+"""
+
+FIB = "a, b = 0, 1\nterms = []\nfor _ in range(10):\n    terms.append(a)\n    a, b = b, a + b\n" \
+      "print(*terms)\n"
+
+
+@pytest.fixture
+def specs(tmp_path):
+    """data/specs with a Fibonacci default spec."""
+    folder = tmp_path / 'specs' / 'Fibonacci'
+    folder.mkdir(parents=True)
+    (folder / 'default.spec.toml').write_text(LABEL_SPEC, encoding='utf-8')
+    (folder / 'terms.txt').write_text('10\n', encoding='utf-8')
+    return LabelSpecs(str(tmp_path / 'specs'))
+
+
+class FakeExecutor:
+    def __init__(self, output):
+        self.output = output
+
+    def execute(self, code):
+        return ExecutionResult(True, self.output, None)
+
+
+@pytest.fixture
+def pipeline(make_pipeline, specs):
+    return make_pipeline(executors={'rust': FakeExecutor('0 1 1 2 3 5 8 13 21 34\n')},
+                         label_specs=specs)
+
+
+# =============================================================================
+# LABEL SPECS
+# =============================================================================
+
+class TestLabelSpecs:
+
+    def test_lookup(self, specs, tmp_path):
+        assert specs.path_for('Fibonacci').endswith('Fibonacci/default.spec.toml')
+        assert specs.default_for('Fibonacci').fixtures == {'terms.txt': '10\n'}
+        assert specs.default_for('etl') is None and specs.path_for('') is None
+        nested = tmp_path / 'specs' / 'math' / 'fib'
+        nested.mkdir(parents=True)
+        (nested / 'default.spec.toml').write_text(OWN_SPEC, encoding='utf-8')
+        assert [c.name for c in specs.default_for('math/fib').cases] == ['exits cleanly']
+        assert specs.labels() == ['Fibonacci', 'math/fib']
+
+    @pytest.mark.parametrize('label', ['../etc', '/abs', '..', ' '])
+    def test_unsafe_labels_inherit_nothing(self, specs, label):
+        assert label_dir(specs.specs_dir, label) is None
+        assert specs.apply(label, None) is None
+
+    def test_malformed_label_spec(self, specs):
+        with open(specs.path_for('Fibonacci'), 'w', encoding='utf-8') as f:
+            f.write('[[case]]\nexit_code = "zero"\n')
+        with pytest.raises(ValueError, match=r'label spec .*default\.spec\.toml: .*exit_code'):
+            specs.default_for('Fibonacci')
+
+
+# =============================================================================
+# INHERIT
+# =============================================================================
+
+class TestInherit:
+
+    def test_label_cases_first(self, specs):
+        own = parse_spec(OWN_SPEC)
+        suite = specs.apply('Fibonacci', own)
+        assert [c.name for c in suite.cases] == ['first ten', 'exits cleanly']
+        assert (suite.inherits, suite.inherited) == ('Fibonacci', ['first ten'])
+        assert suite.source == OWN_SPEC and suite.spec_hash != own.spec_hash
+        assert SpecSuite.from_dict(suite.to_dict()).to_dict() == suite.to_dict()
+        alone = specs.apply('Fibonacci', None)
+        assert [c.name for c in alone.cases] == ['first ten'] and alone.source == ''
+
+    def test_extend_not_replace(self, specs):
+        base = specs.default_for('Fibonacci')
+        with pytest.raises(ValueError, match=r"'first ten' are inherited from label 'Fibonacci'"):
+            inherit(base, parse_spec(LABEL_SPEC.replace('34', '99')), 'Fibonacci')
+        other = SpecSuite(parse_spec(OWN_SPEC).cases, fixtures={'terms.txt': '20\n'})
+        with pytest.raises(ValueError, match="fixture 'terms.txt' differs"):
+            inherit(base, other, 'Fibonacci')
+        same = SpecSuite(parse_spec(OWN_SPEC).cases, fixtures={'terms.txt': None, 'more.txt': 'x'})
+        assert inherit(base, same, 'Fibonacci').fixtures == {'terms.txt': '10\n', 'more.txt': 'x'}
+
+
+# =============================================================================
+# STAGING
+# =============================================================================
+
+class TestStaging:
+
+    def test_every_engine_is_held_to_the_label(self, pipeline):
+        python = pipeline.run_full_pipeline('a', 'python', FIB, 'Fibonacci',
+                                            spec=parse_spec(OWN_SPEC))
+        rust = pipeline.run_full_pipeline('d', 'rust', 'fn main() {}', 'Fibonacci')
+        assert python.phase == rust.phase == StagingPhase.PROMOTED, python.spec_error
+        assert [c['name'] for c in python.spec_cases] == ['first ten', 'exits cleanly']
+        assert [c['name'] for c in rust.spec_cases] == ['first ten']
+        assert python.spec['inherits'] == {'label': 'Fibonacci', 'cases': ['first ten']}
+        [queued] = [e for e in pipeline.get_audit_trail(python.staging_id)
+                    if e['event'] == 'snippet_queued']
+        assert queued['data']['spec_inherits'] == 'Fibonacci' and queued['data']['spec_cases'] == 2
+
+        with open(python.saved_file_path, encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        assert header.fields['spec_inherits'] == 'Fibonacci'
+        assert header.fields['spec_hash'] == python.spec_hash
+        assert load_spec(sidecar_for(python.saved_file_path)).source == OWN_SPEC
+        assert sidecar_for(rust.saved_file_path) is None
+
+    def test_a_wrong_implementation_fails(self, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', 'print(0, 1, 2)', 'Fibonacci')
+        snippet = pipeline.speculate(snippet.staging_id)
+        assert snippet.phase == StagingPhase.FAILED
+        assert snippet.spec_error.startswith('1/1 spec case(s) failed: first ten')
+        other = pipeline.run_full_pipeline('a', 'python', 'print(0, 1, 2)', 'etl')
+        assert other.phase == StagingPhase.PROMOTED and not other.spec_hash
+
+    def test_clash_is_a_usage_error(self, pipeline):
+        with pytest.raises(SpokedError, match="inherited from label 'Fibonacci'") as exc:
+            pipeline.queue_snippet('a', 'python', FIB, 'Fibonacci', spec=parse_spec(LABEL_SPEC))
+        assert exc.value.kind == ErrorKind.USAGE and exc.value.context['label'] == 'Fibonacci'
+        assert pipeline.get_active() == [] and pipeline.get_reserved_positions() == {}
+
+    def test_lsp_hashes_the_label_spec_too(self, pipeline, specs, tmp_path):
+        snippet = pipeline.run_full_pipeline('a', 'python', FIB, 'Fibonacci',
+                                             spec=parse_spec(OWN_SPEC))
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            text = f.read()
+        assert workspace.diagnose(snippet.saved_file_path, text) == []
+        with open(specs.path_for('Fibonacci'), 'a', encoding='utf-8') as f:
+            f.write('\n[[case]]\nname = "twice"\nargs = ["2"]\n')
+        [diag] = workspace.diagnose(snippet.saved_file_path, text)
+        assert diag.code == 'stale-spec'
+        assert "with label 'Fibonacci's default spec hashes to" in diag.message
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def _spec_run(self, pipeline, monkeypatch, path, *flags):
+        def api(method, url, json=None, **kwargs):
+            spec = SpecSuite.from_dict(json['spec']) if json.get('spec') else None
+            suite = pipeline.inherit_spec(json['label'], spec)
+            return {'cases': pipeline.run_spec(json['language'], spec, json['code'],
+                                               label=json['label']),
+                    'inherited': suite.inherited if suite is not None else []}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        out = io.StringIO()
+        with redirect_stdout(out):
+            status = spokedpy.main(['spec', 'run', str(path), *flags])
+        return status, out.getvalue()
+
+    def test_spec_run(self, pipeline, monkeypatch, tmp_path):
+        path = tmp_path / 'fib.py'
+        path.write_text(FIB, encoding='utf-8')
+        status, out = self._spec_run(pipeline, monkeypatch, path, '--label', 'Fibonacci')
+        assert status == 0
+        assert "1 case(s), 1 from label 'Fibonacci'" in out and '(label spec)' in out
+        (tmp_path / 'fib.spec.toml').write_text(OWN_SPEC, encoding='utf-8')
+        path.write_text('print(1)\n', encoding='utf-8')
+        status, out = self._spec_run(pipeline, monkeypatch, path, '--label', 'Fibonacci')
+        assert status == 1 and '✘ first ten' in out and '✔ exits cleanly' in out
+        (tmp_path / 'fib.spec.toml').unlink()
+        assert self._spec_run(pipeline, monkeypatch, path, '--label', 'etl')[0] == 2
@@ -0,0 +1,143 @@
+"""
+Test suite for language auto-detection (language_detect, spokedpy stage).
+
+Tests cover:
+  - The extension decides (aliases too); probes that agree raise its
+    confidence; a spokedpy banner's language wins
+  - #! lines: env, flags and version suffixes
+  - Probes alone: a clear winner, too few hits, two close languages; a
+    Python candidate must compile; .h picks C or C++ by probe
+  - Refusal: detect_language raises a usage error naming the candidates,
+    unless --lang is given
+  - stage records lang-detected-by / lang-confidence in the snippet's
+    meta, not for --lang, and refuses an ambiguous file without a request
+"""
+
+import io
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.language_detect import detect, detect_language, shebang_language
+
+RUST = 'use std::io;\nfn main() {\n    let mut n = 1;\n    println!("{}", n);\n}\n'
+RUBY_OR_PYTHON = 'import json\nputs(1)\nprint(1)\n'
+
+
+# =============================================================================
+# EXTENSIONS + SHEBANGS
+# =============================================================================
+
+class TestEvidence:
+
+    @pytest.mark.parametrize('path, text, expected', [
+        ('a.py', 'x = 1\n', ('python', 'extension', 0.9)),
+        ('a.py', 'def f(x):\n    return x\nprint(f(1))\n', ('python', 'extension', 1.0)),
+        ('a.R', 'x <- 1\n', ('r', 'extension', 1.0)),
+        ('a.cc', '', ('cpp', 'extension', 0.9)),
+        ('a.mjs', 'console.log(1)\n', ('javascript', 'extension', 0.97)),
+        ('run', '#!/usr/bin/env python3.12\n', ('python', 'shebang', 0.95)),
+        ('run', '#!/usr/bin/env -S node --no-warnings\n', ('javascript', 'shebang', 0.95)),
+        ('run', '#!/bin/sh\necho hi\n', ('bash', 'shebang', 0.95)),
+        ('run.py', '#!/usr/bin/env python3\n', ('python', 'extension', 1.0)),
+    ])
+    def test_detect(self, path, text, expected):
+        found = detect(path, text)
+        assert (found.language, found.how, found.confidence) == expected
+
+    def test_banner_wins(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'one')
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            found = detect('copy.rb', f.read())
+        assert (found.language, found.how, found.meta()) == ('python', 'header', {})
+
+    def test_shebangs(self):
+        assert shebang_language('#!/usr/local/bin/ruby -w\n') == 'ruby'
+        assert shebang_language('#!/usr/bin/env Rscript\n') == 'r'
+        assert shebang_language('#!/usr/bin/env lua\n') == ''
+        assert shebang_language('print(1)') == ''
+
+
+# =============================================================================
+# PROBES
+# =============================================================================
+
+class TestProbes:
+
+    def test_clear_winner(self):
+        found = detect('main', RUST)
+        assert (found.language, found.how) == ('rust', 'probes')
+        assert found.confidence >= 0.6 and found.candidates[0][0] == 'rust'
+        assert found.meta() == {'lang-detected-by': 'probes',
+                                'lang-confidence': f"{found.confidence:.2f}"}
+
+    def test_too_few_hits(self):
+        found = detect('job', 'x = foo(1)\n')
+        assert found.language == '' and not found.ambiguous and found.describe() == 'unknown'
+        found = detect('job', 'echo hi\n')
+        assert found.language == '' and found.ambiguous
+
+    def test_close_languages_are_ambiguous(self):
+        found = detect('job', RUBY_OR_PYTHON)
+        assert found.language == '' and found.ambiguous
+        assert [lang for lang, _ in found.candidates] == ['python', 'ruby']
+        assert found.describe() == 'ambiguous: python 0.50, ruby 0.50'
+
+    def test_python_must_compile(self):
+        found = detect('job', 'require "json"\nputs(1)\nprint(1) if x\n')
+        assert 'python' not in dict(found.candidates)
+        assert found.language == 'ruby'
+
+    def test_shared_extension(self):
+        assert detect('vec.h', '#include <vector>\nstd::vector<int> v;\n').language == 'cpp'
+        assert detect('io.h', '#include <stdio.h>\n').language == 'c'
+        found = detect('util.h', 'int twice(int x);\n')
+        assert found.language == '' and found.candidates == [('c', 0.5), ('cpp', 0.5)]
+
+
+# =============================================================================
+# REFUSAL + STAGE
+# =============================================================================
+
+class TestStage:
+
+    def test_refused_without_lang(self):
+        with pytest.raises(SpokedError, match=r'cannot tell the language of job '
+                                              r'\(ambiguous: python 0.50, ruby 0.50\)') as exc:
+            detect_language('/src/job', RUBY_OR_PYTHON)
+        assert exc.value.kind == ErrorKind.USAGE
+        assert exc.value.context['candidates'] == ['python', 'ruby']
+        found = detect_language('/src/job', RUBY_OR_PYTHON, 'Ruby')
+        assert (found.language, found.how, found.meta()) == ('ruby', 'flag', {})
+
+    def test_stage_records_the_detection(self, tmp_path, monkeypatch):
+        bodies = []
+
+        def api(method, url, json=None, **kwargs):
+            if json is not None:
+                bodies.append(json)
+            return {'snippet': {'staging_id': 'stg-1', 'spec_success': True, 'phase': 'speculated',
+                                'language': 'rust', 'label': 'main'}}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        main = tmp_path / 'main'
+        main.write_text(RUST, encoding='utf-8')
+        with redirect_stderr(io.StringIO()), redirect_stdout(io.StringIO()):
+            assert spokedpy.main(['stage', str(main), '--meta', 'team=core']) == 0
+            assert spokedpy.main(['stage', str(main), '--lang', 'rust']) == 0
+        detected, explicit = bodies
+        assert detected['language'] == 'rust' and detected['meta']['team'] == 'core'
+        assert detected['meta']['lang-detected-by'] == 'probes'
+        assert float(detected['meta']['lang-confidence']) >= 0.6
+        assert explicit['meta'] == {}
+
+    def test_stage_refuses_an_ambiguous_file(self, tmp_path, monkeypatch):
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **kw: pytest.fail('staged'))
+        job = tmp_path / 'job'
+        job.write_text(RUBY_OR_PYTHON, encoding='utf-8')
+        err = io.StringIO()
+        with redirect_stderr(err):
+            assert spokedpy.main(['stage', str(job)]) == ErrorKind.USAGE.exit_code
+        assert 'cannot tell the language of job' in err.getvalue()
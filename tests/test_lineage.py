@@ -0,0 +1,259 @@
+"""
+Test suite for snippet provenance (``parent:``) and lineage.
+
+Tests cover:
+  - A snippet staged with a parent keeps it: StagedSnippet, the queue
+    audit event, the promoted file's ``parent:`` header line (a known
+    optional field, checked by the language server)
+  - lineage(): newest first with the diff from each parent, across a
+    restart (from promoted files), a failure bundle and the audit log
+    (code not kept); loops, long chains and unknown ids
+  - Parents set automatically: spokedpy stage of a promoted file
+    (--parent overrides), ingest of a banner file, the editor's
+    re-stage, Spoked.stage
+  - GET /api/staging/lineage and spokedpy lineage (text, --no-diff, json)
+"""
+
+import io
+import json
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.lineage import (
+    MAX_LINEAGE, Ancestor, build_lineage, format_lineage, with_diffs,
+)
+from visual_editor_core.scripting import Spoked
+from visual_editor_core.snippet_lsp import (
+    CMD_RESTAGE, SnippetLanguageServer, SnippetWorkspace, path_to_uri,
+)
+from visual_editor_core.snippet_staging import AuditEventType, StagingPhase, parse_file_header
+from visual_editor_core.source_ingest import IngestItem, stage_body
+from web_interface import runtime
+from web_interface.swagger import openapi_app
+
+
+def _chain(pipeline):
+    """etl v1 → v2 → v3, each promoted and staged from the one before."""
+    v1 = pipeline.run_full_pipeline('a', 'python', 'x = 1\nprint(x)', 'etl')
+    v2 = pipeline.run_full_pipeline('a', 'python', 'x = 2\nprint(x)', 'etl', parent=v1.staging_id)
+    v3 = pipeline.run_full_pipeline('a', 'python', 'x = 2\nprint(x * 3)', 'etl',
+                                    parent=v2.staging_id)
+    return v1, v2, v3
+
+
+def _read(path):
+    with open(path, 'r', encoding='utf-8') as f:
+        return f.read()
+
+
+# =============================================================================
+# PARENT
+# =============================================================================
+
+class TestParent:
+
+    def test_kept_on_snippet_audit_and_header(self, pipeline, tmp_path):
+        v1, v2, _v3 = _chain(pipeline)
+        assert v2.parent == v1.staging_id and v1.parent == ''
+        [event] = [e for e in pipeline.get_audit_trail(v2.staging_id)
+                   if e['event'] == AuditEventType.SNIPPET_QUEUED.value]
+        assert event['data']['parent'] == v1.staging_id
+        assert 'parent' not in pipeline.get_audit_trail(v1.staging_id)[-1]['data']
+
+        header = parse_file_header(_read(v2.saved_file_path))
+        assert header.fields['parent'] == v1.staging_id
+        assert 'parent' not in parse_file_header(_read(v1.saved_file_path)).fields
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'))
+        assert not [d for d in workspace.diagnose(v2.saved_file_path,
+                                                  _read(v2.saved_file_path))
+                    if d.code == 'fields']
+
+    def test_malformed_parent_flagged(self, pipeline, tmp_path):
+        v1, v2, _v3 = _chain(pipeline)
+        text = _read(v2.saved_file_path).replace(v1.staging_id, 'etl-v1')
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'))
+        [bad] = [d for d in workspace.diagnose(v2.saved_file_path, text) if d.code == 'fields']
+        assert "Malformed parent 'etl-v1'" in bad.message
+
+
+# =============================================================================
+# LINEAGE
+# =============================================================================
+
+class TestLineage:
+
+    def test_newest_first_with_diffs(self, pipeline):
+        v1, v2, v3 = _chain(pipeline)
+        chain = pipeline.lineage(v3.staging_id)
+        assert [a.staging_id for a in chain] == [v3.staging_id, v2.staging_id, v1.staging_id]
+        assert {a.source for a in chain} == {'store'} and chain[0].slot == 'a3'
+
+        entries = with_diffs(chain)
+        assert '-print(x)\n+print(x * 3)' in entries[0]['diff']
+        assert '-x = 1\n+x = 2' in entries[1]['diff'] and entries[2]['diff'] == ''
+        text = format_lineage(chain)
+        assert f"staged from {v2.staging_id}\n" in text + '\n'
+        assert '      +print(x * 3)' in text and text.endswith('    first version')
+        assert '+print' not in format_lineage(chain, diffs=False)
+
+    def test_after_a_restart_from_files(self, make_pipeline):
+        first = make_pipeline()
+        v1, v2, v3 = _chain(first)
+        first.rollback(v3.staging_id, 'broken')
+        chain = make_pipeline().lineage(v3.staging_id)
+        assert [a.source for a in chain] == ['file'] * 3
+        assert [a.parent for a in chain] == [v2.staging_id, v1.staging_id, '']
+        assert chain[2].code == 'x = 1\nprint(x)' and chain[1].slot == 'a2'
+        assert [a.phase for a in chain] == ['rolled_back', 'promoted', 'promoted']
+        assert chain[0].label == 'etl'
+
+    def test_failed_ancestors_bundle_and_audit(self, make_pipeline):
+        first = make_pipeline(failures_dir='failures')
+        bundled = first.run_full_pipeline('a', 'python', 'raise RuntimeError(3)', 'etl')
+        assert bundled.phase != StagingPhase.PROMOTED
+        later = make_pipeline()                     # no failures dir: the audit log only
+        lost = later.run_full_pipeline('a', 'python', 'raise RuntimeError(4)', 'etl',
+                                       parent=bundled.staging_id)
+        fixed = make_pipeline(failures_dir='failures').run_full_pipeline(
+            'a', 'python', 'print("ok")', 'etl', parent=lost.staging_id)
+
+        chain = make_pipeline(failures_dir='failures').lineage(fixed.staging_id)
+        assert [a.source for a in chain] == ['file', 'audit', 'bundle']
+        assert chain[1].code is None and chain[1].parent == bundled.staging_id
+        assert chain[2].code == 'raise RuntimeError(3)' and chain[2].phase == 'failed'
+        text = format_lineage(chain)
+        assert f"staged from {lost.staging_id} — code not kept, no diff" in text
+        assert f"staged from {bundled.staging_id} — code not kept, no diff" in text
+
+    def test_unknown_id_and_parent(self, pipeline):
+        with pytest.raises(SpokedError, match="No snippet 'stg-000000000000'") as info:
+            pipeline.lineage('stg-000000000000')
+        assert info.value.kind is ErrorKind.NOT_FOUND
+        orphan = pipeline.queue_snippet('a', 'python', 'print(1)', 'etl',
+                                        parent='stg-0000000000ff')
+        chain = pipeline.lineage(orphan.staging_id)
+        assert chain[1] == Ancestor('stg-0000000000ff')
+        assert 'stg-0000000000ff  (not found' in format_lineage(chain)
+
+    def test_loops_and_long_chains_stop(self):
+        links = {'c': 'b', 'b': 'a', 'a': 'c'}
+        chain = build_lineage('c', lambda sid: Ancestor(sid, links[sid], source='store'))
+        assert [a.staging_id for a in chain] == ['c', 'b', 'a']
+        assert format_lineage(chain).endswith('staged from c, listed above — the chain loops')
+
+        chain = build_lineage('n0', lambda sid: Ancestor(sid, f"n{int(sid[1:]) + 1}",
+                                                         source='store'))
+        assert len(chain) == MAX_LINEAGE
+        assert format_lineage(chain).endswith(f"chain cut at {MAX_LINEAGE} versions")
+
+
+# =============================================================================
+# AUTOMATIC PARENTS
+# =============================================================================
+
+class TestAutomatic:
+
+    def _promoted(self, pipeline):
+        return pipeline.run_full_pipeline('a', 'python', 'x = 1\nprint(x)', 'etl')
+
+    def test_stage_of_a_promoted_file(self, pipeline, monkeypatch):
+        v1 = self._promoted(pipeline)
+        sent = []
+
+        def api(method, url, json=None, **_):
+            if json is not None:
+                sent.append(json)
+            return {'success': True, 'snippet': {'staging_id': 'stg-000000000001',
+                                                 'spec_success': True, 'label': 'etl',
+                                                 'language': 'python', 'phase': 'passed'}}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        with redirect_stdout(io.StringIO()):
+            assert spokedpy.main(['stage', v1.saved_file_path]) == 0
+            assert spokedpy.main(['stage', v1.saved_file_path, '--parent', '']) == 0
+            assert spokedpy.main(['stage', v1.saved_file_path,
+                                  '--parent', 'stg-0000000000aa']) == 0
+        assert [b.get('parent') for b in sent] == [v1.staging_id, None, 'stg-0000000000aa']
+        assert sent[0]['code'] == 'x = 1\nprint(x)'
+
+    def test_ingest_of_a_banner_file(self, pipeline, tmp_path):
+        v1 = self._promoted(pipeline)
+        root = tmp_path / 'legacy'
+        root.mkdir()
+        (root / 'etl.py').write_text(_read(v1.saved_file_path), encoding='utf-8')
+        (root / 'new.py').write_text('print(2)\n', encoding='utf-8')
+        body = stage_body(str(root), IngestItem('etl.py', 'python', label='etl'))
+        assert body['parent'] == v1.staging_id
+        assert 'parent' not in stage_body(str(root), IngestItem('new.py', 'python', label='new'))
+
+    def test_editor_restage(self, pipeline, tmp_path):
+        v1 = self._promoted(pipeline)
+        staged = []
+
+        def stage(letter, language, code, label, **extra):
+            staged.append(extra['parent'])
+            return {'staging_id': 'stg-000000000001', 'reserved_address': 'a2', 'phase': 'passed'}
+
+        server = SnippetLanguageServer(SnippetWorkspace(str(tmp_path / 'snippets')), stage,
+                                       io.BytesIO(), io.BytesIO())
+        server.handle({'id': 1, 'method': 'workspace/executeCommand',
+                       'params': {'command': CMD_RESTAGE,
+                                  'arguments': [path_to_uri(v1.saved_file_path)]}})
+        assert staged == [v1.staging_id]
+
+    def test_scripting_stage(self, tmp_path):
+        spoked = Spoked(str(tmp_path), engines=['python'])
+        v1 = spoked.stage(code='print(1)', language='python', label='etl')
+        v1 = spoked.promote(v1.staging_id)
+        v2 = spoked.stage(v1.saved_file_path)
+        assert v2.parent == v1.staging_id
+        assert spoked.stage(v1.saved_file_path, parent='').parent == ''
+        assert [a.staging_id for a in spoked.lineage(v2.staging_id)] == \
+            [v2.staging_id, v1.staging_id]
+
+
+# =============================================================================
+# API + CLI
+# =============================================================================
+
+class TestInterfaces:
+
+    def test_route(self, pipeline, monkeypatch):
+        v1, v2, _v3 = _chain(pipeline)
+        monkeypatch.setattr(runtime, 'staging_pipeline', pipeline)
+        client = openapi_app().test_client()
+        data = client.get(f'/api/staging/lineage/{v2.staging_id}').get_json()
+        assert [e['staging_id'] for e in data['lineage']] == [v2.staging_id, v1.staging_id]
+        assert '+x = 2' in data['lineage'][0]['diff']
+        resp = client.get('/api/staging/lineage/stg-000000000000')
+        assert resp.status_code == 404 and resp.get_json()['kind'] == 'not_found'
+
+        body = {'engine_letter': 'a', 'language': 'python', 'code': 'print(9)', 'label': 'etl',
+                'parent': v2.staging_id}
+        queued = client.post('/api/staging/queue', json=body).get_json()['snippet']
+        assert queued['parent'] == v2.staging_id
+
+    def test_cli(self, pipeline, monkeypatch):
+        v1, v2, _v3 = _chain(pipeline)
+        page = {'success': True, 'staging_id': v2.staging_id,
+                'lineage': with_diffs(pipeline.lineage(v2.staging_id))}
+        urls = []
+        monkeypatch.setattr(spokedpy, '_api', lambda method, url, **k: urls.append(url) or page)
+
+        for argv, check in ((['lineage', v2.staging_id], lambda out: '      +x = 2' in out),
+                            (['lineage', v2.staging_id, '--no-diff'],
+                             lambda out: '+x = 2' not in out and 'first version' in out)):
+            out = io.StringIO()
+            with redirect_stdout(out):
+                assert spokedpy.main(argv) == 0
+            assert check(out.getvalue()), out.getvalue()
+        assert urls[0].endswith(f'/api/staging/lineage/{v2.staging_id}')
+
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert spokedpy.main(['lineage', v2.staging_id, '--format', 'json', '--no-diff']) == 0
+        entries = json.loads(out.getvalue())
+        assert [e['parent'] for e in entries] == [v1.staging_id, ''] and 'diff' not in entries[0]
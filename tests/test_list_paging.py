@@ -0,0 +1,159 @@
+"""
+Test suite for paged listing (StagingPipeline.list_snippets, spokedpy list).
+
+Tests cover:
+  - Pages come newest first by seq, with the total and a next_cursor that
+    is None on the last page
+  - A cursor stays put while snippets are staged and archived in front of it
+  - phase / meta / q filter before paging; a malformed cursor is a usage error
+  - Snippets trimmed from the history (history_limit) or staged before a
+    restart still list, from the audit log; deleted ones do not
+  - spokedpy list sends --limit / --after, prints the next cursor, follows
+    every page with --all and prints {snippets, total, next_cursor} as JSON;
+    an older server's unpaged answer still lists
+"""
+
+import io
+import json
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+
+import spokedpy
+
+
+def _populated(pipeline, count):
+    for n in range(count):
+        snippet = pipeline.queue_snippet('b', 'javascript', f"// {n}\n", f"s{n}",
+                                         meta={'team': 'even' if n % 2 == 0 else 'odd'})
+        if n % 3 == 0:
+            pipeline.verdict(snippet.staging_id, 'reject', 'paging test')
+    return pipeline
+
+
+def _walk(pipeline, **kwargs):
+    pages, cursor = [], ''
+    while True:
+        page = pipeline.list_snippets(after=cursor, **kwargs)
+        pages.append([s.label for s in page['snippets']])
+        cursor = page['next_cursor']
+        if cursor is None:
+            return pages, page['total']
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestListSnippets:
+
+    def test_pages_newest_first(self, pipeline):
+        _populated(pipeline, 10)
+        pages, total = _walk(pipeline, limit=4)
+        assert total == 10
+        assert pages == [['s9', 's8', 's7', 's6'], ['s5', 's4', 's3', 's2'], ['s1', 's0']]
+
+    def test_exact_last_page_has_no_cursor(self, pipeline):
+        page = _populated(pipeline, 4).list_snippets(limit=4)
+        assert len(page['snippets']) == 4 and page['next_cursor'] is None
+
+    def test_cursor_is_stable(self, pipeline):
+        _populated(pipeline, 6)
+        first = pipeline.list_snippets(limit=3)
+        newer = pipeline.queue_snippet('b', 'javascript', '// new\n', 'new')
+        pipeline.verdict(newer.staging_id, 'reject', 'archived in front')
+        second = pipeline.list_snippets(limit=3, after=first['next_cursor'])
+        assert [s.label for s in second['snippets']] == ['s2', 's1', 's0']
+        assert second['total'] == 7 and second['next_cursor'] is None
+
+    def test_filters_before_paging(self, pipeline):
+        _populated(pipeline, 10)
+        assert _walk(pipeline, limit=2, meta={'team': 'even'}) == (
+            [['s8', 's6'], ['s4', 's2'], ['s0']], 5)
+        assert _walk(pipeline, limit=10, phase='rejected') == ([['s9', 's6', 's3', 's0']], 4)
+        assert _walk(pipeline, limit=10, query='s7') == ([['s7']], 1)
+        assert _walk(pipeline, include_history=False) == (
+            [['s8', 's7', 's5', 's4', 's2', 's1']], 6)
+
+    def test_trimmed_history_still_lists(self, make_pipeline):
+        pipeline = make_pipeline(history_limit=1)
+        first = pipeline.queue_snippet('b', 'javascript', '// first\n', 'first')
+        pipeline.relabel(first.staging_id, 'renamed')
+        pipeline.verdict(first.staging_id, 'reject', 'trimmed')
+        _populated(pipeline, 10)
+        pages, total = _walk(pipeline, limit=4)
+        assert total == 11
+        assert pages == [['s9', 's8', 's7', 's6'], ['s5', 's4', 's3', 's2'],
+                         ['s1', 's0', 'renamed']]
+        assert _walk(pipeline, limit=10, phase='rejected') == (
+            [['s9', 's6', 's3', 's0', 'renamed']], 5)
+        oldest = pipeline.list_snippets(limit=20)['snippets'][-1]
+        assert (oldest.staging_id, oldest.reserved_address) == (first.staging_id, 'b1')
+
+    def test_restarted_store_lists(self, make_pipeline):
+        pipeline = _populated(make_pipeline(trash_dir='trash'), 5)
+        pipeline.delete(pipeline.list_snippets(query='s3')['snippets'][0].staging_id)
+        assert _walk(make_pipeline(), limit=3) == ([['s4', 's2', 's1'], ['s0']], 4)
+
+    def test_malformed_cursor(self, pipeline):
+        with pytest.raises(SpokedError) as err:
+            _populated(pipeline, 1).list_snippets(after='stg-1')
+        assert err.value.kind == ErrorKind.USAGE
+
+
+# =============================================================================
+# spokedpy list
+# =============================================================================
+
+class TestCli:
+
+    def _run(self, argv, api, monkeypatch):
+        monkeypatch.setattr(spokedpy, '_api', api)
+        args = spokedpy.build_parser().parse_args(argv)
+        out = io.StringIO()
+        with redirect_stdout(out):
+            code = args.func(args)
+        return code, out.getvalue()
+
+    @staticmethod
+    def _server(pipeline, calls):
+        def api(method, url, params=None, **kwargs):
+            calls.append(dict(params))
+            page = pipeline.list_snippets(params['limit'], params['after'], params['phase'])
+            return {'success': True, **page,
+                    'snippets': [s.to_dict() for s in page['snippets']]}
+        return api
+
+    def test_one_page_and_the_next_cursor(self, pipeline, monkeypatch):
+        pipeline, calls = _populated(pipeline, 5), []
+        code, out = self._run(['list', '--limit', '2'], self._server(pipeline, calls),
+                              monkeypatch)
+        lines = out.splitlines()
+        assert code == 0 and len(calls) == 1 and calls[0]['after'] == ''
+        assert len(lines) == 3 and 's4' in lines[0] and 's3' in lines[1]
+        cursor = pipeline.list_snippets(2)['next_cursor']
+        assert lines[-1] == f"  … 2 of 5 shown — next page: --after {cursor}"
+        code, out = self._run(['list', '--limit', '2', '--after', cursor, '--format', 'json'],
+                              self._server(pipeline, calls), monkeypatch)
+        data = json.loads(out)
+        assert [s['label'] for s in data['snippets']] == ['s2', 's1']
+        assert data['total'] == 5 and data['next_cursor'] is not None
+
+    def test_all_pages(self, pipeline, monkeypatch):
+        pipeline, calls = _populated(pipeline, 5), []
+        code, out = self._run(['list', '--limit', '2', '--all', '--format', 'json'],
+                              self._server(pipeline, calls), monkeypatch)
+        data = json.loads(out)
+        assert code == 0 and len(calls) == 3
+        assert [s['label'] for s in data['snippets']] == ['s4', 's3', 's2', 's1', 's0']
+        assert data['total'] == 5 and data['next_cursor'] is None
+
+    def test_older_server(self, monkeypatch):
+        snippets = [{'staging_id': f"stg-{n}", 'seq': n, 'reserved_address': 'a1',
+                     'phase': 'promoted', 'label': 'fib', 'meta': {}} for n in (3, 9)]
+        api = lambda *a, **k: {'success': True, 'active': snippets[:1], 'history': snippets[1:]}
+        code, out = self._run(['list', '--format', 'json', '--all'], api, monkeypatch)
+        assert code == 0 and json.loads(out) == {
+            'snippets': [snippets[1], snippets[0]], 'total': 2, 'next_cursor': None}
@@ -0,0 +1,214 @@
+"""
+Test suite for the spec runs' network policy ([sandbox] network, offline).
+
+Tests cover:
+  - Parsing "none, javascript=loopback"; unknown levels are refused
+  - Header declarations (# network: loopback, // network: full) and the
+    level a spec run gets from them or from its engine
+  - Engine spawns in a spec run get the namespace prefix (plus the loopback
+    helper); outside one, or at full, they run as before
+  - A spawn that gets the host's network anyway (no namespaces, shell=True)
+    is listed as not enforced, on the snippet and in its audit entry
+  - The in-process Python guard refuses loopback under none only
+  - Queue refuses an unknown declaration; the promoted header keeps it
+  - max_network slot constraints refuse snippets that declare more
+  - With namespaces available, none has no loopback and loopback has it
+"""
+
+import os
+import socket
+import sys
+
+import pytest
+
+from visual_editor_core import offline
+from visual_editor_core.engine_doctor import run_doctor
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.scripting import SnippetHeader
+from visual_editor_core.slot_constraints import SlotConstraints, parse_constraints
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import parse_spec
+
+
+@pytest.fixture
+def policy(monkeypatch):
+    """Set the network policy for one test; restored afterwards."""
+    monkeypatch.setitem(offline._state, 'network', offline._state['network'])
+    monkeypatch.setitem(offline._state, 'network_engines', offline._state['network_engines'])
+    return offline.set_network_policy
+
+
+# =============================================================================
+# POLICY + DECLARATIONS
+# =============================================================================
+
+class TestPolicy:
+
+    def test_parse(self):
+        assert offline.parse_network_policy('') == ('none', {})
+        assert offline.parse_network_policy('Loopback') == ('loopback', {})
+        assert offline.parse_network_policy('none, JavaScript=loopback, go = full') == \
+            ('none', {'javascript': 'loopback', 'go': 'full'})
+
+    @pytest.mark.parametrize('value, match', [
+        ('internet', "unknown network level 'internet'"),
+        ('none, rust=some', r'network policy \(rust\)'),
+        ('=full', 'names no engine'),
+    ])
+    def test_parse_rejects(self, value, match):
+        with pytest.raises(ValueError, match=match):
+            offline.parse_network_policy(value)
+
+    def test_declarations(self):
+        assert offline.declared_network('#!/usr/bin/env python\n# network: loopback\nx = 1\n') \
+            == 'loopback'
+        assert offline.declared_network('// SPDX: MIT\n//network:full\nfn main() {}\n') == 'full'
+        assert offline.declared_network('-- network: none\nSELECT 1;\n') == 'none'
+        assert offline.declared_network('x = 1\n# network: full\n') == ''    # leading only
+        with pytest.raises(ValueError, match="unknown network level 'lan'"):
+            offline.declared_network('# network: lan\n')
+
+    def test_level_per_engine(self, policy):
+        policy('none, javascript=loopback')
+        assert offline.network_for('python') == 'none'
+        assert offline.network_for('JavaScript') == 'loopback'
+        assert offline.network_for('python', 'full') == 'full'
+
+
+# =============================================================================
+# ENFORCEMENT
+# =============================================================================
+
+class TestEnforcement:
+
+    @pytest.fixture(autouse=True)
+    def namespaces(self, monkeypatch):
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', ['unshare', '--net', '--'])
+
+    def test_spawns_in_a_spec_run_get_a_namespace(self):
+        with offline.spec_network('none'):
+            args, _ = offline.apply((['node', 'x.js'],), {})
+        assert args[0] == ['unshare', '--net', '--', 'node', 'x.js']
+
+        with offline.spec_network('loopback'):
+            args, _ = offline.apply((['node', 'x.js'],), {})
+        assert args[0][:5] == ['unshare', '--net', '--', sys.executable, '-I']
+        assert args[0][-2:] == ['node', 'x.js']
+
+    def test_full_and_outside_spec_runs_are_untouched(self):
+        assert offline.current_network() is None
+        assert offline.apply((['node', 'x.js'],), {})[0] == (['node', 'x.js'],)
+        with offline.spec_network('full'):
+            assert offline.apply((['node', 'x.js'],), {})[0] == (['node', 'x.js'],)
+
+    def test_spawns_past_the_policy_are_listed(self, monkeypatch):
+        with offline.spec_network('none') as scope:
+            offline.apply(('make test',), {'shell': True})
+            offline.apply((['node', 'x.js'],), {})
+        assert scope.not_enforced == ['make']
+
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', None)
+        with offline.spec_network('loopback') as scope:
+            assert offline.apply((['/usr/bin/node', 'x.js'],), {})[0] == \
+                (['/usr/bin/node', 'x.js'],)
+            offline.apply((['node', 'y.js'],), {})
+        assert scope.not_enforced == ['node']
+        with offline.spec_network('full') as scope:
+            offline.apply((['node', 'x.js'],), {})
+        assert scope.not_enforced == []
+
+    def test_in_process_guard(self):
+        with offline.spec_network('none'), offline.network_guard():
+            with pytest.raises(offline.OfflineError, match=r'network policy \(none\)'):
+                socket.create_connection(('127.0.0.1', 9), timeout=1)
+        with offline.spec_network('loopback'), offline.network_guard():
+            with pytest.raises(offline.OfflineError, match='example.com'):
+                socket.getaddrinfo('example.com', 80)
+            with pytest.raises(OSError) as excinfo:
+                socket.create_connection(('127.0.0.1', 9), timeout=1)
+            assert not isinstance(excinfo.value, offline.OfflineError)
+
+    def test_doctor_reports_isolation(self, policy, monkeypatch):
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', None)
+        policy('none')
+        [check] = [c for c in run_doctor(languages=[]).checks if c.name == 'network_isolation']
+        assert 'network policy' in check.detail
+        policy('full')
+        assert not [c for c in run_doctor(languages=[]).checks if c.category == 'offline']
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipeline:
+
+    def test_unknown_declaration_is_refused(self, pipeline):
+        with pytest.raises(SpokedError) as excinfo:
+            pipeline.queue_snippet('a', 'python', '# network: intranet\n', 'x')
+        assert excinfo.value.kind == ErrorKind.USAGE
+
+    def test_header_keeps_the_declaration(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', '# network: loopback\nprint(1)\n',
+                                             'local')
+        assert snippet.phase == StagingPhase.PROMOTED and snippet.network == 'loopback'
+        assert SnippetHeader.from_file(snippet.saved_file_path).network == 'loopback'
+        started = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                   if e['event'] == 'spec_exec_started']
+        assert started[0]['data']['network'] == 'loopback'
+
+        plain = pipeline.run_full_pipeline('a', 'python', 'print(2)\n', 'plain')
+        assert SnippetHeader.from_file(plain.saved_file_path).network == ''
+
+    def test_unenforced_policy_is_marked(self, pipeline, monkeypatch):
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', None)
+        spec = parse_spec('[[case]]\nname = "one"\nstdout = "1\\n"\n')
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)\n', 'spawned', spec=spec)
+        python = os.path.basename(sys.executable)
+        assert snippet.network_not_enforced == [python]
+        [completed] = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                       if e['event'] == 'spec_exec_completed']
+        assert completed['data']['network_not_enforced'] == [python]
+        assert completed['data']['warning'].startswith(f"network not enforced: {python} ")
+
+        plain = pipeline.run_full_pipeline('a', 'python', 'print(2)\n', 'in-process')
+        assert plain.network_not_enforced == []
+
+        assert pipeline.evaluate('python', 'print(3)\n', timeout=10)['network_not_enforced'] \
+            == [python]
+        assert 'network_not_enforced' not in pipeline.evaluate('python', 'print(4)\n')
+
+    def test_max_network_keeps_declaring_snippets_out(self, tmp_path, make_pipeline):
+        path = tmp_path / 'constraints.toml'
+        path.write_text('[slot."a*"]\nmax_network = "none"\n', encoding='utf-8')
+        pipeline = make_pipeline(constraints=SlotConstraints(str(path)))
+        snippet = pipeline.run_full_pipeline('a', 'python', '# network: full\nprint(1)\n', 'x')
+        assert snippet.phase == StagingPhase.PASSED
+        assert pipeline.review(snippet.staging_id)['constraints'] == \
+            ['declares network: full, slot allows none']
+        assert pipeline.run_full_pipeline('a', 'python', 'print(1)\n', 'y').phase == \
+            StagingPhase.PROMOTED
+
+        with pytest.raises(ValueError, match='max_network must be one of'):
+            parse_constraints('[slot.a1]\nmax_network = "lan"\n')
+
+
+@pytest.mark.skipif(offline.isolation_prefix() is None, reason='needs network namespaces')
+class TestNamespaces:
+
+    CODE = ("import socket\n"
+            "server = socket.socket()\n"
+            "server.bind(('127.0.0.1', 0))\n"
+            "server.listen(1)\n"
+            "socket.create_connection(server.getsockname(), timeout=2).close()\n"
+            "print('connected')\n")
+    SPEC = parse_spec('[[case]]\nname = "local"\nstdout = "connected\\n"\n')
+
+    def test_none_has_no_loopback(self, pipeline):
+        [result] = pipeline.run_spec('python', self.SPEC, self.CODE)
+        assert not result['passed']
+
+    def test_loopback_declared(self, pipeline):
+        [result] = pipeline.run_spec('python', self.SPEC,
+                                                '# network: loopback\n' + self.CODE)
+        assert result['passed'], result['error']
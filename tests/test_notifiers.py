@@ -0,0 +1,178 @@
+"""
+Test suite for Notifiers — Slack / email messages on pipeline events.
+
+Tests cover:
+  - Pipeline emits spec_failure / promotion / rollback to listeners
+  - Templates: built-in and overridden, diff link placeholders
+  - Per-notifier event filters
+  - Slack webhook payload (against a loopback HTTP server)
+  - SMTP message headers and STARTTLS / login sequence
+  - Delivery failures are recorded, never raised into the pipeline
+"""
+
+import json
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+import pytest
+
+from visual_editor_core.notifiers import (
+    EmailNotifier, Notification, NotificationHub, Notifier, NotifyError,
+    SlackNotifier, hub_from_settings,
+)
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+class RecordingNotifier(Notifier):
+    kind = 'recording'
+
+    def __init__(self, fail=False, **kwargs):
+        super().__init__(**kwargs)
+        self.sent, self.fail = [], fail
+
+    def send(self, note):
+        if self.fail:
+            raise NotifyError('service down')
+        self.sent.append((note, self.text(note)))
+
+
+def _note(**overrides):
+    fields = dict(event='promotion', staging_id='stg-abc', slot='a3', label='etl',
+                  language='python', author='ada')
+    fields.update(overrides)
+    return Notification(**fields)
+
+
+# =============================================================================
+# PIPELINE EVENTS
+# =============================================================================
+
+class TestPipelineEvents:
+
+    def test_promotion_and_rollback_notify(self, pipeline):
+        recorder = RecordingNotifier()
+        pipeline.add_listener(NotificationHub([recorder], diff_url='https://ui/$staging_id',
+                                              background=False))
+        snippet = pipeline.run_full_pipeline('a', 'python', 'x = 1', 'etl', author='ada')
+        pipeline.rollback(snippet.staging_id, 'bad data')
+
+        assert [n.event for n, _ in recorder.sent] == ['promotion', 'rollback']
+        note, text = recorder.sent[0]
+        assert (note.slot, note.label, note.author) == (snippet.reserved_address, 'etl', 'ada')
+        assert f'https://ui/{snippet.staging_id}' in text
+        assert 'Reason: bad data' in recorder.sent[1][1]
+
+    def test_spec_failure_notifies_with_error(self, pipeline):
+        recorder = RecordingNotifier(events=['spec_failure'])
+        pipeline.add_listener(NotificationHub([recorder], background=False))
+        pipeline.run_full_pipeline('a', 'python', '1/0', 'divide')
+        note, text = recorder.sent[0]
+        assert note.event == 'spec_failure'
+        assert 'ZeroDivisionError' in text or 'division' in text
+
+    def test_broken_notifier_never_fails_pipeline(self, pipeline):
+        hub = NotificationHub([RecordingNotifier(fail=True)], background=False)
+        pipeline.add_listener(hub)
+        snippet = pipeline.run_full_pipeline('a', 'python', 'x = 1')
+        assert snippet.phase.value == 'promoted'
+        assert hub.errors[0]['error'] == 'service down'
+
+
+# =============================================================================
+# TEMPLATES + FILTERS
+# =============================================================================
+
+class TestTemplates:
+
+    def test_override_template(self):
+        recorder = RecordingNotifier(templates={'promotion': '$author shipped $label to $slot'})
+        assert recorder.text(_note()) == 'ada shipped etl to a3'
+
+    def test_unknown_placeholder_left_alone(self):
+        recorder = RecordingNotifier(templates={'promotion': '$label by $nobody'})
+        assert recorder.text(_note()) == 'etl by $nobody'
+
+    def test_event_filter(self):
+        hub = NotificationHub([RecordingNotifier(events=['rollback'])], background=False)
+        hub.dispatch(_note(event='promotion'))
+        assert hub.notifiers[0].sent == []
+
+    def test_unknown_event_rejected(self):
+        with pytest.raises(ValueError, match='choose from'):
+            RecordingNotifier(events=['deploy'])
+
+    def test_hub_from_settings(self):
+        values = {'slack_webhook': 'http://127.0.0.1:9/hook', 'slack_events': 'rollback',
+                  'smtp_host': 'mail.local', 'email_to': 'ops@example.com, dev@example.com'}
+        hub = hub_from_settings(lambda key, env, default: values.get(key, default))
+        slack, email = hub.notifiers
+        assert slack.events == ['rollback']
+        assert email.recipients == ['ops@example.com', 'dev@example.com']
+        assert email.events == ['spec_failure', 'spec_anomaly', 'approval_request', 'promotion',
+                                'rollback', 'degraded']
+
+
+# =============================================================================
+# DELIVERY
+# =============================================================================
+
+class TestDelivery:
+
+    def test_slack_posts_text_payload(self):
+        received = []
+
+        class Handler(BaseHTTPRequestHandler):
+            def do_POST(self):
+                received.append(json.loads(self.rfile.read(int(self.headers['Content-Length']))))
+                self.send_response(200)
+                self.end_headers()
+
+            def log_message(self, *args):
+                pass
+
+        server = HTTPServer(('127.0.0.1', 0), Handler)
+        threading.Thread(target=server.handle_request, daemon=True).start()
+        try:
+            SlackNotifier(f'http://127.0.0.1:{server.server_port}/hook').send(_note())
+        finally:
+            server.server_close()
+        assert received[0]['text'].startswith('✅ Promoted — etl (python) to slot a3')
+
+    def test_slack_unreachable_raises(self):
+        with pytest.raises(NotifyError, match='Slack webhook failed'):
+            SlackNotifier('http://127.0.0.1:9/hook', timeout=2).send(_note())
+
+    def test_email_sequence_and_headers(self):
+        calls = []
+
+        class FakeSMTP:
+            def __init__(self, host, port, timeout):
+                calls.append(('connect', host, port))
+
+            def __enter__(self):
+                return self
+
+            def __exit__(self, *exc):
+                return False
+
+            def starttls(self):
+                calls.append(('starttls',))
+
+            def login(self, user, password):
+                calls.append(('login', user))
+
+            def send_message(self, msg):
+                calls.append(('send', msg['Subject'], msg['To'], msg.get_content()))
+
+        notifier = EmailNotifier('mail.local', 587, 'spokedpy@example.com',
+                                 ['ops@example.com'], username='bot', password='pw',
+                                 smtp_factory=FakeSMTP)
+        notifier.send(_note(event='rollback', reason='regression'))
+        assert [c[0] for c in calls] == ['connect', 'starttls', 'login', 'send']
+        _, subject, to, body = calls[-1]
+        assert subject == '[spokedpy] Rolled back: etl (a3)'
+        assert to == 'ops@example.com'
+        assert 'Reason: regression' in body
@@ -0,0 +1,143 @@
+"""
+Test suite for Offline Mode — air-gapped enforcement.
+
+Tests cover:
+  - require_network / require_host (loopback stays allowed)
+  - Subprocess rewriting: offline env, network-namespace prefix
+  - In-process Python guard blocks non-loopback DNS / connects
+  - Cargo vendor snapshot: command, config.toml, metadata, CARGO_HOME
+"""
+
+import os
+import json
+import pytest
+
+from visual_editor_core import offline
+from visual_editor_core.offline import OfflineError, CargoVendor
+from visual_editor_core.execution_engine import PythonExecutor
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def offline_mode(tmp_path, monkeypatch):
+    """Offline mode on with a temp vendor dir; restored afterwards."""
+    monkeypatch.setitem(offline._isolation_probe, 'prefix', ['unshare', '--net', '--'])
+    offline.configure(True, vendor_dir=str(tmp_path / 'vendor'))
+    yield tmp_path / 'vendor'
+    offline.configure(False, vendor_dir='')
+    os.environ.pop('SPOKEDPY_OFFLINE', None)
+
+
+# =============================================================================
+# MODE + GUARDS
+# =============================================================================
+
+class TestRequireNetwork:
+
+    def test_online_allows_everything(self):
+        offline.configure(False)
+        offline.require_network('Installing rust@1.84')
+        offline.require_host('https://api.openai.com/v1', 'AI chat')
+
+    def test_offline_raises_clear_error(self, offline_mode):
+        with pytest.raises(OfflineError, match='Installing rust@1.84 needs network access'):
+            offline.require_network('Installing rust@1.84')
+
+    def test_loopback_hosts_allowed_offline(self, offline_mode):
+        offline.require_host('http://localhost:11434/v1', 'AI chat')
+        offline.require_host('http://127.0.0.1:8080', 'AI chat')
+        offline.require_host('http://[::1]:8080', 'AI chat')
+        with pytest.raises(OfflineError):
+            offline.require_host('https://api.openai.com/v1', 'AI chat')
+
+    def test_python_engine_blocks_remote_dns(self, offline_mode):
+        result = PythonExecutor().execute(
+            "import socket\nsocket.getaddrinfo('example.com', 80)")
+        assert not result.success
+        assert 'blocked in offline mode' in str(result.error)
+
+    def test_python_engine_allows_loopback(self, offline_mode):
+        result = PythonExecutor().execute(
+            "import socket\nsocket.getaddrinfo('localhost', 80)")
+        assert result.success
+
+
+# =============================================================================
+# SUBPROCESS REWRITING
+# =============================================================================
+
+class TestSubprocessRewrite:
+
+    def test_noop_when_online(self):
+        offline.configure(False)
+        args, kwargs = offline.apply((['rustc', 'main.rs'],), {'text': True})
+        assert args == (['rustc', 'main.rs'],)
+        assert 'env' not in kwargs
+
+    def test_offline_env_and_namespace_prefix(self, offline_mode):
+        args, kwargs = offline.apply((['go', 'run', 'main.go'],), {'env': {'PATH': '/bin'}})
+        assert args[0] == ['unshare', '--net', '--', 'go', 'run', 'main.go']
+        assert kwargs['env']['PATH'] == '/bin'
+        assert kwargs['env']['CARGO_NET_OFFLINE'] == 'true'
+        assert kwargs['env']['GOPROXY'] == 'off'
+
+    def test_shell_commands_not_prefixed(self, offline_mode):
+        args, _ = offline.apply(('echo hi',), {'shell': True})
+        assert args == ('echo hi',)
+
+    def test_netns_backend_isolates_while_online(self, monkeypatch):
+        offline.configure(False)
+        monkeypatch.setitem(offline._state, 'backend', 'netns')
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', ['unshare', '--net', '--'])
+        args, kwargs = offline.apply((['rustc', 'main.rs'],), {})
+        assert args[0][:3] == ['unshare', '--net', '--']
+        assert 'env' not in kwargs
+
+        monkeypatch.setitem(offline._isolation_probe, 'prefix', None)
+        with pytest.raises(OfflineError, match="backend 'netns'"):
+            offline.apply((['rustc', 'main.rs'],), {})
+
+    def test_unknown_backend_rejected(self):
+        with pytest.raises(ValueError, match='choose from: process, netns'):
+            offline.set_sandbox_backend('docker')
+
+
+# =============================================================================
+# CARGO VENDOR SNAPSHOT
+# =============================================================================
+
+class TestCargoVendor:
+
+    def _fake_cargo(self, calls):
+        def runner(argv):
+            calls.append(argv)
+            os.makedirs(os.path.join(argv[-1], 'serde-1.0.200'), exist_ok=True)
+            return 0, ''
+        return runner
+
+    def test_sync_writes_config_and_metadata(self, offline_mode, tmp_path, monkeypatch):
+        monkeypatch.setattr(offline.shutil, 'which', lambda name: '/usr/bin/' + name)
+        calls = []
+        snapshot = CargoVendor(str(offline_mode), runner=self._fake_cargo(calls))
+        status = snapshot.sync([str(tmp_path / 'Cargo.toml')])
+
+        assert '--offline' in calls[0]          # offline → local registry cache only
+        assert status['present'] and status['crate_count'] == 1
+        config = open(os.path.join(snapshot.cargo_home, 'config.toml')).read()
+        assert 'replace-with = "spokedpy-vendor"' in config
+        assert json.dumps(snapshot.crates_dir) in config
+        assert offline.subprocess_env()['CARGO_HOME'] == snapshot.cargo_home
+
+    def test_failed_vendor_raises(self, offline_mode, tmp_path, monkeypatch):
+        monkeypatch.setattr(offline.shutil, 'which', lambda name: '/usr/bin/' + name)
+        snapshot = CargoVendor(str(offline_mode), runner=lambda argv: (101, 'no matching package'))
+        with pytest.raises(OfflineError, match='no matching package'):
+            snapshot.sync([str(tmp_path / 'Cargo.toml')])
+        assert not snapshot.status()['present']
+
+    def test_manifest_required(self, tmp_path):
+        with pytest.raises(ValueError):
+            CargoVendor(str(tmp_path)).command([])
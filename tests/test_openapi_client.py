@@ -0,0 +1,228 @@
+"""
+Test suite for the OpenAPI document and the typed client (spokedpy_client).
+
+Tests cover:
+  - The document: a unique operationId per operation, request bodies from
+    the ``Body:`` lines (required / optional, enums, ``same as``), query
+    parameters, replies referring to StagedSnippet, the Error schema
+  - The generated client is up to date and has one method per operation
+  - The client against the runtime blueprint: run-full, a streamed
+    stage-and-spec, a refusal raised as SpokedError with its kind
+  - Transport: tenant prefix and token, None left out of the body, an
+    unreachable server
+  - spokedpy openapi --out / --client
+"""
+
+import asyncio
+import inspect
+import json
+
+import pytest
+
+import spokedpy
+from spokedpy_client import SpokedClient, SpokedError
+from spokedpy_client.codegen import render_client
+from visual_editor_core.errors import ErrorKind
+from web_interface import runtime
+from web_interface.swagger import build_openapi_spec, openapi_app
+
+
+@pytest.fixture(scope='module')
+def app():
+    return openapi_app()
+
+
+@pytest.fixture(scope='module')
+def spec(app):
+    return build_openapi_spec(app)
+
+
+def _operations(spec):
+    return {op['operationId']: (method, path, op)
+            for path, ops in spec['paths'].items() for method, op in ops.items()}
+
+
+def _body(op):
+    return op['requestBody']['content']['application/json']['schema']
+
+
+class _Reply:
+    """The part of a requests.Response the client reads."""
+
+    def __init__(self, status_code, data=None, text='', content_type='application/json'):
+        self.status_code, self._data, self._text = status_code, data, text
+        self.headers = {'Content-Type': content_type}
+
+    def json(self):
+        if self._data is None:
+            raise ValueError('not JSON')
+        return self._data
+
+    def iter_lines(self, decode_unicode=False):
+        return iter(self._text.splitlines())
+
+
+class _FlaskSession:
+    """requests.Session-like: answers from a Flask test client."""
+
+    def __init__(self, app):
+        self.client = app.test_client()
+
+    def request(self, method, url, json=None, params=None, headers=None, **_):
+        path = '/' + url.split('://', 1)[1].split('/', 1)[1]
+        resp = self.client.open(path, method=method, json=json, query_string=params,
+                                headers=headers)
+        return _Reply(resp.status_code, resp.get_json(silent=True),
+                      resp.get_data(as_text=True), resp.headers.get('Content-Type', ''))
+
+
+class _Canned:
+    def __init__(self, reply):
+        self.reply, self.calls = reply, []
+
+    def request(self, method, url, **kwargs):
+        self.calls.append((method, url, kwargs))
+        if isinstance(self.reply, Exception):
+            raise self.reply
+        return self.reply
+
+
+# =============================================================================
+# THE DOCUMENT
+# =============================================================================
+
+class TestDocument:
+
+    def test_operation_ids(self, app, spec):
+        ops = _operations(spec)
+        documented = sum(len(r.methods & {'GET', 'POST', 'PUT', 'PATCH', 'DELETE'})
+                         for r in app.url_map.iter_rules() if r.rule.startswith('/api/'))
+        assert len(ops) == documented
+        assert ops['staging_promote'][:2] == ('post', '/api/staging/promote/{staging_id}')
+
+    def test_request_bodies(self, spec):
+        ops = _operations(spec)
+        queue = _body(ops['staging_queue'][2])
+        assert queue['required'] == ['engine_letter', 'language', 'code']
+        assert queue['properties']['code'] == {'type': 'string'}
+        assert _body(ops['staging_stage_and_spec'][2]) == queue          # "same as"
+        action = _body(ops['staging_verdict'][2])['properties']['action']
+        assert action == {'type': 'string', 'enum': ['auto', 'approve', 'reject', 'hold']}
+        assert _body(ops['staging_trash_purge'][2])['properties']['expired'] == \
+            {'type': 'boolean'}
+        assert _body(ops['mesh_relay_push'][2]) == {'type': 'object'}    # multi-line: untyped
+
+    def test_query_and_replies(self, spec):
+        ops = _operations(spec)
+        query = [p['name'] for p in ops['slots_watch'][2]['parameters'] if p['in'] == 'query']
+        assert query == ['slots', 'since', 'timeout']
+        reply = ops['staging_promote'][2]['responses']
+        assert reply['200']['content']['application/json']['schema']['properties']['snippet'] \
+            == {'$ref': '#/components/schemas/StagedSnippet'}
+        assert reply['default']['content']['application/json']['schema'] == \
+            {'$ref': '#/components/schemas/Error'}
+
+    def test_component_schemas(self, spec):
+        schemas = spec['components']['schemas']
+        assert schemas['Error']['properties']['kind']['enum'] == [k.value for k in ErrorKind]
+        snippet = schemas['StagedSnippet']['properties']
+        assert snippet['phase']['enum'][0] == 'queued' and snippet['seq'] == {'type': 'integer'}
+        assert schemas['Degradation']['properties']['since'] == {'type': 'number'}
+
+
+# =============================================================================
+# THE GENERATED CLIENT
+# =============================================================================
+
+class TestGenerated:
+
+    def test_up_to_date(self, spec):
+        import spokedpy_client.api as api
+        with open(api.__file__, 'r', encoding='utf-8') as f:
+            assert f.read() == render_client(spec), \
+                'regenerate: python spokedpy.py openapi --client spokedpy_client/api.py'
+
+    def test_one_method_per_operation(self, spec):
+        methods = {name for name, fn in inspect.getmembers(SpokedClient, inspect.iscoroutinefunction)
+                   if not name.startswith('_')}
+        assert methods == set(_operations(spec))
+        signature = inspect.signature(SpokedClient.staging_rollback)
+        assert list(signature.parameters) == ['self', 'staging_id', 'reason']
+        assert signature.parameters['reason'].kind is inspect.Parameter.KEYWORD_ONLY
+
+
+# =============================================================================
+# AGAINST THE RUNTIME BLUEPRINT
+# =============================================================================
+
+class TestRoundTrip:
+
+    @pytest.fixture
+    def client(self, app, pipeline, monkeypatch):
+        monkeypatch.setattr(runtime, 'staging_pipeline', pipeline)
+        monkeypatch.setenv('SPOKEDPY_REVIEWERS', 'alice:s3cret')
+        return SpokedClient('http://daemon', session=_FlaskSession(app), reviewer_token='s3cret')
+
+    def test_run_full_and_get(self, client):
+        async def go():
+            reply = await client.staging_run_full(engine_letter='a', language='python',
+                                                  code='print(1)', label='etl')
+            got = await client.staging_get_snippet(reply['snippet']['staging_id'])
+            return reply, got
+        reply, got = asyncio.run(go())
+        assert reply['snippet']['phase'] == 'promoted'
+        assert got['snippet']['reserved_address'] == reply['snippet']['reserved_address'] == 'a1'
+
+    def test_streamed_reply(self, client):
+        reply = asyncio.run(client.staging_stage_and_spec(
+            engine_letter='a', language='python', code='print(2)', label='etl'))
+        assert reply['snippet']['phase'] == 'passed' and 'event' not in reply
+
+    def test_refusal_raises(self, client):
+        with pytest.raises(SpokedError) as info:
+            asyncio.run(client.staging_promote('stg-missing'))
+        assert info.value.kind is ErrorKind.NOT_FOUND and info.value.exit_code == 4
+
+
+# =============================================================================
+# TRANSPORT + CLI
+# =============================================================================
+
+class TestTransport:
+
+    def test_tenant_token_and_body(self):
+        session = _Canned(_Reply(200, {'success': True, 'snippet': {}}))
+        client = SpokedClient('http://daemon:5002/', token='s3cret', tenant='payments',
+                              session=session)
+        asyncio.run(client.staging_rollback('stg-1'))
+        asyncio.run(client.staging_list(limit=5))
+        (method, url, kwargs), (_, list_url, list_kwargs) = session.calls
+        assert (method, url) == ('POST', 'http://daemon:5002/t/payments/api/staging/rollback/stg-1')
+        assert kwargs['headers'] == {'X-Spokedpy-Token': 's3cret'} and kwargs['json'] == {}
+        assert list_url.endswith('/api/staging/snippets') and list_kwargs['params'] == {'limit': 5}
+
+        reviewer = SpokedClient(session=session, reviewer_token='r3view')
+        asyncio.run(reviewer.staging_promote('stg-1'))
+        assert session.calls[-1][2]['headers'] == {'Authorization': 'Bearer r3view'}
+
+    def test_errors(self):
+        refused = _Canned(_Reply(429, {'success': False, 'error': 'over quota',
+                                       'kind': 'quota_exceeded', 'context': {'limit': 50}}))
+        with pytest.raises(SpokedError, match='over quota') as info:
+            asyncio.run(SpokedClient(session=refused).staging_queue(
+                engine_letter='a', language='python', code='x'))
+        assert info.value.kind is ErrorKind.QUOTA_EXCEEDED and info.value.context == {'limit': 50}
+
+        down = _Canned(ConnectionRefusedError('refused'))
+        with pytest.raises(SpokedError) as info:
+            asyncio.run(SpokedClient('http://nowhere:1', session=down).staging_summary())
+        assert info.value.kind is ErrorKind.SERVER_UNREACHABLE and info.value.exit_code == 3
+
+    def test_cli_export(self, tmp_path, spec):
+        out, client = tmp_path / 'openapi.json', tmp_path / 'api.py'
+        assert spokedpy.main(['--server', 'http://daemon:9000', 'openapi', '--out', str(out),
+                              '--client', str(client)]) == 0
+        document = json.loads(out.read_text(encoding='utf-8'))
+        assert document['servers'][0]['url'] == 'http://daemon:9000'
+        assert document['paths'] == json.loads(json.dumps(spec['paths']))
+        assert client.read_text(encoding='utf-8') == render_client(spec)
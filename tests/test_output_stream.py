@@ -0,0 +1,131 @@
+"""
+Test suite for live output streaming (POST /api/staging/stage-and-spec).
+
+Tests cover:
+  - Lines reach the sink while the process is still running
+  - stdin, stderr, exit status and timeouts match subprocess.run
+  - Compile / run phase classification
+  - speculate(on_event=…) phase markers around a spec suite
+  - No sink, no change; a failing sink never fails the run
+  - spokedpy spec run --follow prints output between timed markers
+"""
+
+import io
+import subprocess
+import sys
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core import output_stream
+from visual_editor_core.execution_engine import _run_subprocess
+from visual_editor_core.spec_suite import SpecSuite, parse_spec
+
+import spokedpy
+
+
+SLOW = 'import sys, time\nprint("one", flush=True)\ntime.sleep(0.3)\nprint("two")\nprint("oops", file=sys.stderr)'
+
+
+def _run(argv, **kwargs):
+    events = []
+    with output_stream.streaming(events.append):
+        proc = _run_subprocess(argv, capture_output=True, text=True, **kwargs)
+    return proc, events
+
+
+# =============================================================================
+# SUBPROCESS STREAMING
+# =============================================================================
+
+class TestRunStreaming:
+
+    def test_lines_arrive_before_exit(self):
+        proc, events = _run([sys.executable, '-c', SLOW])
+        assert (proc.returncode, proc.stdout, proc.stderr) == (0, 'one\ntwo\n', 'oops\n')
+        assert [e['event'] for e in events][0] == 'process_started'
+        first = next(e for e in events if e['event'] == 'output')
+        finished = events[-1]
+        assert first['text'] == 'one\n' and first['stream'] == 'stdout'
+        assert finished['event'] == 'process_finished' and finished['exit_code'] == 0
+        assert finished['at'] - first['at'] >= 0.25
+        assert {(e['stream'], e['text']) for e in events if e['event'] == 'output'} == \
+            {('stdout', 'one\n'), ('stdout', 'two\n'), ('stderr', 'oops\n')}
+
+    def test_stdin_and_exit_status(self):
+        proc, events = _run([sys.executable, '-c', 'import sys; print(sys.stdin.read().upper()); sys.exit(3)'],
+                            input='hi')
+        assert (proc.returncode, proc.stdout) == (3, 'HI\n')
+        assert events[-1]['exit_code'] == 3
+
+    def test_timeout_raises_like_subprocess_run(self):
+        with pytest.raises(subprocess.TimeoutExpired):
+            _run([sys.executable, '-c', 'import time; time.sleep(5)'], timeout=0.2)
+
+    def test_phases(self):
+        assert output_stream.process_phase(['/usr/bin/rustc', 'main.rs']) == 'compile'
+        assert output_stream.process_phase(['go', 'build', '-o', 'main']) == 'compile'
+        assert output_stream.process_phase(['go', 'run', 'main.go']) == 'run'
+        assert output_stream.process_phase(['/tmp/vpyd_rs_1/main']) == 'run'
+
+    def test_no_sink_no_events(self):
+        proc = _run_subprocess([sys.executable, '-c', 'print(1)'], capture_output=True, text=True)
+        assert proc.stdout == '1\n' and output_stream.current_sink() is None
+
+    def test_failing_sink_is_ignored(self):
+        def broken(event):
+            raise ConnectionError('client went away')
+        with output_stream.streaming(broken):
+            proc = _run_subprocess([sys.executable, '-c', 'print(1)'], capture_output=True, text=True)
+        assert proc.stdout == '1\n'
+
+
+# =============================================================================
+# PIPELINE EVENTS
+# =============================================================================
+
+class TestSpeculateEvents:
+
+    def test_spec_suite_markers(self, pipeline):
+        spec = parse_spec('[[case]]\nname = "greets"\nstdout = "hi\\n"\n')
+        snippet = pipeline.queue_snippet('a', 'python', 'print("hi")', 'greeter', spec=spec)
+        events = []
+        pipeline.speculate(snippet.staging_id, on_event=events.append)
+        names = [e['event'] for e in events]
+        assert names == ['spec_started', 'case_started', 'process_started', 'output',
+                         'process_finished', 'case_finished', 'spec_finished']
+        assert events[0]['cases'] == 1
+        assert events[5]['passed'] and events[-1]['phase'] == 'passed'
+        assert output_stream.current_sink() is None
+
+
+# =============================================================================
+# spec run --follow
+# =============================================================================
+
+class TestFollow:
+
+    def test_markers_around_live_output(self, tmp_path, pipeline, monkeypatch):
+        (tmp_path / 'greet.py').write_text('print("hi")\n')
+        (tmp_path / 'greet.spec.toml').write_text('[[case]]\nname = "greets"\nstdout = "hi\\n"\n')
+
+        def api_stream(method, url, on_event, json=None, **kwargs):
+            assert url.endswith('/api/staging/spec-run') and json['stream']
+            cases = pipeline.run_spec(json['language'], SpecSuite.from_dict(json['spec']),
+                                      json['code'], on_event=on_event)
+            return {'event': 'result', 'success': True, 'cases': cases}
+
+        monkeypatch.setattr(spokedpy, '_api_stream', api_stream)
+        args = spokedpy.build_parser().parse_args(['spec', 'run', str(tmp_path / 'greet.py'), '--follow'])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        lines = out.getvalue().splitlines()
+
+        def at(fragment):
+            return next(i for i, line in enumerate(lines) if fragment in line)
+
+        assert at('spec started — 1 case(s)') < at('case greets started') < at('run started (python')
+        assert at('run started (python') < at('│ hi') < at('case greets finished — PASS')
+        assert at('spec finished — PASS') < at('✔ greets')
+        assert lines[0].startswith('  [   0.00s]')
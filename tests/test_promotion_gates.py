@@ -0,0 +1,184 @@
+"""
+Test suite for Promotion Gates — plugin allow/deny checks.
+
+Tests cover:
+  - Any denying gate turns a passing snippet FAILED with its reasons
+  - Gate results recorded on the snippet and in the audit trail
+  - allow_override decides whether 'approve' can overrule a gate
+  - Plugin discovery: broken plugins deny instead of disappearing
+  - WASM ABI: reply decoding, fuel budget, host imports refused
+    (skipped unless the optional wasmtime package is installed)
+"""
+
+import json
+import pytest
+
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.promotion_gates import (
+    BrokenGate, GateDecision, GateError, PromotionGate, WasmGate,
+    evaluate_gates, load_builtin_gates, load_plugin_gates,
+)
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+class FixedGate(PromotionGate):
+    """Test gate that always votes the same way and remembers what it saw."""
+
+    def __init__(self, name, allow, reasons=()):
+        super().__init__(name)
+        self.allow, self.reasons, self.seen = allow, list(reasons), []
+
+    def check(self, header, body):
+        self.seen.append((header, body))
+        return GateDecision(self.name, self.allow, self.reasons)
+
+
+class CrashingGate(PromotionGate):
+    def check(self, header, body):
+        raise RuntimeError('boom')
+
+
+def _speculate(pipeline, code='x = 1'):
+    snippet = pipeline.queue_snippet('a', 'python', code, 'gate test')
+    return pipeline.speculate(snippet.staging_id)
+
+
+def _static_plugin(reply) -> str:
+    """WAT for a plugin that answers every request with ``reply``."""
+    text = json.dumps(reply)
+    escaped = text.replace('"', '\\"')
+    return f'''(module
+      (memory (export "memory") 1)
+      (data (i32.const 0) "{escaped}")
+      (func (export "spokedpy_alloc") (param i32) (result i32) (i32.const 1024))
+      (func (export "spokedpy_check") (param i32 i32) (result i64)
+        (i64.const {len(text.encode())})))'''
+
+
+# =============================================================================
+# PIPELINE INTEGRATION
+# =============================================================================
+
+class TestPipelineGates:
+
+    def test_all_allow_keeps_snippet_passed(self, make_pipeline):
+        gate = FixedGate('style', True)
+        snippet = _speculate(make_pipeline(gates=[gate]), code='answer = 42')
+        assert snippet.phase == StagingPhase.PASSED
+        header, body = gate.seen[0]
+        assert body == 'answer = 42'
+        assert header['language'] == 'python' and header['spec_success'] is True
+        assert snippet.gate_results[0]['gate'] == 'style'
+
+    def test_deny_fails_snippet_with_reasons(self, make_pipeline):
+        pipeline = make_pipeline(gates=[FixedGate('ok', True),
+                                        FixedGate('no-eval', False, ['eval() is banned'])])
+        snippet = _speculate(pipeline)
+        assert snippet.phase == StagingPhase.FAILED
+        assert snippet.spec_success            # the dry-run itself passed
+        assert "Gate 'no-eval' denied: eval() is banned" in snippet.spec_error
+        assert [r['allow'] for r in snippet.gate_results] == [True, False]
+
+        events = [e['event'] for e in pipeline.get_audit_trail(snippet.staging_id)]
+        assert 'gate_passed' in events and 'gate_denied' in events
+
+    def test_gates_skipped_when_dry_run_fails(self, make_pipeline):
+        gate = FixedGate('style', True)
+        snippet = _speculate(make_pipeline(gates=[gate]), code='1/0')
+        assert snippet.phase == StagingPhase.FAILED
+        assert gate.seen == [] and snippet.gate_results == []
+
+    def test_override_respects_allow_override(self, make_pipeline):
+        pipeline = make_pipeline(gates=[FixedGate('deny', False)], allow_override=False)
+        snippet = _speculate(pipeline)
+        with pytest.raises(ValueError, match='allow_override = false'):
+            pipeline.verdict(snippet.staging_id, 'approve')
+
+        pipeline = make_pipeline(gates=[FixedGate('deny', False)])
+        snippet = _speculate(pipeline)
+        assert pipeline.verdict(snippet.staging_id, 'approve').phase == StagingPhase.PASSED
+
+    def test_run_gate_appends_and_can_fail(self, make_pipeline):
+        pipeline = make_pipeline(gates=[FixedGate('style', True)])
+        snippet = _speculate(pipeline)
+        pipeline.run_gate(snippet.staging_id, FixedGate('fuzz', False, ['crash-1']))
+        assert [r['gate'] for r in snippet.gate_results] == ['style', 'fuzz']
+        assert snippet.phase == StagingPhase.FAILED
+        with pytest.raises(ValueError, match='must be PASSED'):
+            pipeline.run_gate(snippet.staging_id, FixedGate('again', True))
+
+    def test_crashing_gate_denies(self):
+        decision, = evaluate_gates([CrashingGate('oops')], {}, '')
+        assert not decision.allow and 'boom' in decision.reasons[0]
+
+
+# =============================================================================
+# PLUGIN DISCOVERY
+# =============================================================================
+
+class TestDiscovery:
+
+    def test_missing_dir_means_no_gates(self, tmp_path):
+        assert load_plugin_gates(str(tmp_path / 'nope')) == []
+        assert load_plugin_gates('') == []
+
+    def test_invalid_plugin_becomes_denying_gate(self, tmp_path):
+        (tmp_path / 'b_policy.wasm').write_bytes(b'not wasm')
+        (tmp_path / 'a_readme.txt').write_text('ignored')
+        gates = load_plugin_gates(str(tmp_path))
+        assert [g.name for g in gates] == ['b_policy']
+        assert isinstance(gates[0], BrokenGate)
+        decision, = evaluate_gates(gates, {}, '')
+        assert not decision.allow and decision.reasons[0].startswith('gate unavailable')
+
+    def test_builtin_gates_by_name(self, tmp_path):
+        fuzz, unknown = load_builtin_gates('fuzz, lint', seconds=5, fixtures_dir=str(tmp_path))
+        assert fuzz.describe()['seconds'] == 5 and fuzz.fixtures_dir == str(tmp_path)
+        assert isinstance(unknown, BrokenGate) and 'lint' in unknown.error
+        assert load_builtin_gates('') == []
+
+
+# =============================================================================
+# WASM ABI
+# =============================================================================
+
+class TestWasmGate:
+
+    @pytest.fixture(autouse=True)
+    def _needs_wasmtime(self):
+        pytest.importorskip('wasmtime')
+
+    def test_allow_and_deny_replies(self):
+        allow = WasmGate('allow', _static_plugin({'allow': True, 'reasons': []}))
+        assert allow.check({'language': 'python'}, 'x = 1').allow
+
+        deny = WasmGate('deny', _static_plugin({'allow': False, 'reasons': ['no network']}))
+        decision = deny.check({}, 'import socket')
+        assert (decision.allow, decision.reasons) == (False, ['no network'])
+
+    def test_malformed_reply_raises(self):
+        gate = WasmGate('bad', _static_plugin({'verdict': 'yes'}))
+        with pytest.raises(GateError, match='malformed reply'):
+            gate.check({}, '')
+
+    def test_runaway_plugin_stopped_by_fuel(self):
+        spin = '''(module
+          (memory (export "memory") 1)
+          (func (export "spokedpy_alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "spokedpy_check") (param i32 i32) (result i64)
+            (loop $spin (br $spin))
+            (i64.const 0)))'''
+        with pytest.raises(GateError, match='fuel budget'):
+            WasmGate('spin', spin, fuel=10_000).check({}, '')
+
+    def test_host_imports_refused(self):
+        wat = '''(module
+          (import "wasi_snapshot_preview1" "proc_exit" (func (param i32)))
+          (memory (export "memory") 1)
+          (func (export "spokedpy_alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "spokedpy_check") (param i32 i32) (result i64) (i64.const 0)))'''
+        with pytest.raises(GateError, match='imports host functions'):
+            WasmGate('wasi', wat)
@@ -0,0 +1,167 @@
+"""
+Test suite for promotion impact analysis (promotion_impact, promote --impact).
+
+Tests cover:
+  - ``# imports:`` declarations in leading comments; a malformed address
+    refuses staging
+  - analyze(): promoted snippets importing the slot, registry subscribers
+    and mesh peers, held promotions importing it, the snippet it replaces
+  - spokedpy promote --impact: the report, dependents refused without
+    --force
+"""
+
+import io
+import time
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.promotion_impact import Impact, analyze, declared_imports, format_impact
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.snippet_staging import StagingPhase
+
+
+@pytest.fixture
+def ledger():
+    return SessionLedger()
+
+
+@pytest.fixture
+def registry(ledger):
+    return NodeRegistry(ledger)
+
+
+@pytest.fixture
+def pipeline(make_pipeline, registry, ledger):
+    return make_pipeline(node_registry=registry, session_ledger=ledger)
+
+
+class FakeRelay:
+    def __init__(self, subscriptions):
+        self.subscriptions = subscriptions
+
+    def peers_for(self, local_addr):
+        return list(self.subscriptions.get(local_addr, []))
+
+
+def _vacated(pipeline):
+    """a1 rolled back (so the next python snippet lands there), a2 importing it."""
+    old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'source')
+    reader = pipeline.run_full_pipeline('a', 'python', '# imports: a1\nprint(2)', 'reader')
+    pipeline.rollback(old.staging_id)
+    return reader
+
+
+# =============================================================================
+# DECLARATIONS
+# =============================================================================
+
+class TestDeclarations:
+
+    @pytest.mark.parametrize('code, expected', [
+        ('# imports: d3, a1\nprint(1)', ['d3', 'a1']),
+        ('#!/usr/bin/env node\n// imports: D3 d3\n', ['d3']),
+        ('-- imports:\nselect 1', []),
+        ('print(1)\n# imports: d3', []),
+        ('# network: loopback\n# imports: b7', ['b7']),
+    ])
+    def test_leading_comments(self, code, expected):
+        assert declared_imports(code) == expected
+
+    def test_malformed_address_refuses_staging(self, pipeline):
+        with pytest.raises(ValueError, match="'slot-3' is not a slot address"):
+            declared_imports('# imports: slot-3')
+        with pytest.raises(SpokedError, match='imports declaration') as exc:
+            pipeline.queue_snippet('a', 'python', '# imports: d3, 3d\nprint(1)', 'bad')
+        assert exc.value.kind == ErrorKind.USAGE
+
+
+# =============================================================================
+# ANALYZE
+# =============================================================================
+
+class TestAnalyze:
+
+    def test_dependents_consumers_and_scheduled(self, pipeline, registry):
+        reader = _vacated(pipeline)
+        new = pipeline.queue_snippet('a', 'python', 'print(10)', 'source')
+        assert new.reserved_address == 'a1'
+        held = pipeline.run_full_pipeline('a', 'python', '# imports: a1, a2\nprint(3)', 'later',
+                                          auto_promote=False)
+        pipeline.schedule_promotion(held.staging_id, time.time() + 3600)
+        pipeline.run_full_pipeline('a', 'python', '# imports: a2\nprint(4)', 'unrelated')
+        a1 = registry.get_slot_by_address('a', 1)
+        registry.subscribe(registry.get_slot_by_address('a', 2).slot_id, a1.slot_id)
+
+        impact = analyze(pipeline, new.staging_id, registry, FakeRelay({'a1': ['node-2']}))
+        assert (impact.slot, impact.replaces) == ('a1', '')
+        assert impact.dependents == [{'staging_id': reader.staging_id, 'slot': 'a2',
+                                      'label': 'reader'}]
+        assert impact.consumers == ['a2'] and impact.peers == ['node-2']
+        assert [s['staging_id'] for s in impact.scheduled] == [held.staging_id]
+        assert impact.has_dependents
+        assert Impact.from_dict(impact.to_dict()) == impact
+
+        text = format_impact(impact)
+        assert f"{new.staging_id} → a1 (source; vacant)" in text
+        assert 'dependents (1) — import a1:' in text and 'mesh peers (1)' in text
+
+    def test_nothing_references_the_slot(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', '# imports: a1\nprint(1)', 'self',
+                                             auto_promote=False)
+        impact = analyze(pipeline, snippet.staging_id)
+        assert not impact.has_dependents and impact.scheduled == []
+        assert format_impact(impact).endswith('nothing else references the slot')
+        with pytest.raises(SpokedError) as exc:
+            analyze(pipeline, 'stg-nope')
+        assert exc.value.kind == ErrorKind.NOT_FOUND
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def _promote(self, pipeline, registry, monkeypatch, *argv):
+        import requests
+
+        def api(method, url, json=None, **kwargs):
+            staging_id = url.rsplit('/', 1)[1]
+            return {'impact': analyze(pipeline, staging_id, registry).to_dict()}
+
+        class Response:
+            def __init__(self, data):
+                self.data, self.status_code = data, 200
+
+            def json(self):
+                return self.data
+
+        def post(url, **kwargs):
+            staging_id = url.rsplit('/', 1)[1]
+            if '/verdict/' in url:
+                return Response({'success': True,
+                                 'snippet': pipeline.verdict(staging_id, 'approve').to_dict()})
+            return Response({'success': True, 'snippet': pipeline.promote(staging_id).to_dict()})
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        monkeypatch.setattr(requests, 'post', post)
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            status = spokedpy.main(['promote', *argv, '--impact'])
+        return status, out.getvalue(), err.getvalue()
+
+    def test_dependents_need_force(self, pipeline, registry, monkeypatch):
+        _vacated(pipeline)
+        new = pipeline.run_full_pipeline('a', 'python', 'print(10)', 'source', auto_promote=False)
+        status, out, err = self._promote(pipeline, registry, monkeypatch, new.staging_id)
+        assert status == 1 and 'dependents (1)' in out
+        assert '1 dependent(s) import a1 (--force to promote anyway)' in err
+        assert pipeline.get_snippet(new.staging_id).phase == StagingPhase.PASSED
+
+        status, out, _err = self._promote(pipeline, registry, monkeypatch, new.staging_id, '--force')
+        assert status == 0 and f"✔ {new.staging_id} → a1" in out
+        assert pipeline.get_snippet(new.staging_id).phase == StagingPhase.PROMOTED
@@ -0,0 +1,163 @@
+"""
+Test suite for the per-slot auto-promotion policy ([gates] policy_file).
+
+Tests cover:
+  - Parsing [[rule]] tables; malformed policies are rejected
+  - First matching rule decides: protected slots, spec_time, trusted authors,
+    required gates, require_spec
+  - Uncovered slots fall back to the request / [gates] auto_promote
+  - An explicit auto_promote: false always holds; a broken file holds everything
+  - run_full_pipeline promotes or holds per the policy and audits the decision
+  - The file is re-read when it changes
+"""
+
+import os
+import time
+from types import SimpleNamespace
+
+import pytest
+
+from visual_editor_core.promotion_policy import PromotionPolicy, parse_policy
+from visual_editor_core.snippet_staging import StagingPhase
+
+
+POLICY = """
+[[rule]]
+name = "protected"
+slots = ["a1"]
+auto_promote = false
+
+[[rule]]
+name = "scratch"
+slots = ["a*"]
+max_spec_time = 2.0
+authors = ["alice", "ci-bot"]
+"""
+
+
+def _snippet(slot='d3', spec_time=0.5, author='alice', gates=(), spec=None, language='python'):
+    return SimpleNamespace(reserved_address=slot, language=language, spec_execution_time=spec_time,
+                           author=author, spec=spec or {},
+                           gate_results=[{'gate': g, 'allow': allow} for g, allow in gates])
+
+
+@pytest.fixture
+def policy(tmp_path):
+    path = tmp_path / 'policy.toml'
+    path.write_text(POLICY, encoding='utf-8')
+    return PromotionPolicy(str(path))
+
+
+# =============================================================================
+# PARSING
+# =============================================================================
+
+class TestParse:
+
+    def test_rules(self):
+        protected, scratch = parse_policy(POLICY)
+        assert protected.name == 'protected' and not protected.auto_promote
+        assert scratch.slots == ['a*'] and scratch.max_spec_time == 2.0
+        assert scratch.authors == ['alice', 'ci-bot']
+        assert parse_policy('[[rule]]\nslots = "d3"')[0].slots == ['d3']
+
+    @pytest.mark.parametrize('text, match', [
+        ('[[rule]]\nname = "x"', "'slots' is required"),
+        ('[[rule]]\nslots = ["a1"]\nowner = "bob"', 'unknown key'),
+        ('[[rule]]\nslots = ["a1"]\nmax_spec_time = "fast"', 'number of seconds'),
+        ('[[rule]]\nslots = ["a1"]\nauto_promote = "yes"', 'true or false'),
+        ('slots = ["a1"]', 'expected \\[\\[rule\\]\\]'),
+        ('[[rule', 'invalid policy TOML'),
+    ])
+    def test_rejects(self, text, match):
+        with pytest.raises(ValueError, match=match):
+            parse_policy(text)
+
+
+# =============================================================================
+# DECISIONS
+# =============================================================================
+
+class TestDecide:
+
+    def test_protected_slot_holds_even_when_requested(self, policy):
+        decision = policy.decide(_snippet('a1'), True, True)
+        assert not decision.promote and decision.rule == 'protected'
+        assert 'protected' in decision.reasons[0]
+
+    def test_conditions(self, policy):
+        assert policy.decide(_snippet('a2'), None, False).promote       # rule beats the default
+        slow = policy.decide(_snippet('a2', spec_time=3.0), None, True)
+        assert not slow.promote and 'spec_time' in slow.reasons[0]
+        stranger = policy.decide(_snippet('a2', author='mallory'), None, True)
+        assert not stranger.promote and "'mallory' is not trusted" in stranger.reasons[0]
+
+    def test_required_gates_and_spec(self):
+        rule, = parse_policy('[[rule]]\nslots = ["*"]\ngates = ["fuzz"]\nrequire_spec = true')
+        assert len(rule.check(_snippet(gates=[('fuzz', False)]))) == 2
+        assert rule.check(_snippet(gates=[('fuzz', True)], spec={'cases': []})) == []
+
+    def test_languages(self):
+        rule, = parse_policy('[[rule]]\nslots = ["*"]\nlanguages = ["rust"]')
+        assert rule.matches(_snippet(language='rust')) and not rule.matches(_snippet())
+
+    def test_uncovered_slot_falls_back(self, policy):
+        assert policy.decide(_snippet('d3'), None, True).promote
+        assert not policy.decide(_snippet('d3'), None, False).promote
+        assert policy.decide(_snippet('d3'), True, False).promote
+        decision = policy.decide(_snippet('d3'), False, True)
+        assert not decision.promote and decision.rule == ''
+
+    def test_broken_or_missing_file(self, tmp_path):
+        missing = PromotionPolicy(str(tmp_path / 'none.toml'))
+        assert missing.rules == [] and missing.decide(_snippet(), None, True).promote
+        path = tmp_path / 'bad.toml'
+        path.write_text('[[rule]]\nname = "x"', encoding='utf-8')
+        broken = PromotionPolicy(str(path))
+        decision = broken.decide(_snippet(), True, True)
+        assert not decision.promote and 'slots' in broken.error
+
+    def test_reloads_on_change(self, policy):
+        assert len(policy.rules) == 2
+        with open(policy.path, 'w', encoding='utf-8') as f:
+            f.write('[[rule]]\nslots = ["*"]\nauto_promote = false\n')
+        later = time.time() + 5
+        os.utime(policy.path, (later, later))
+        assert [r.name for r in policy.rules] == ['rule-1']
+        assert not policy.decide(_snippet('d3'), True, True).promote
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipeline:
+
+    def test_protected_slot_waits_for_review(self, tmp_path, make_pipeline):
+        path = tmp_path / 'policy.toml'
+        path.write_text('[[rule]]\nname = "core"\nslots = ["a*"]\nauto_promote = false\n',
+                        encoding='utf-8')
+        pipeline = make_pipeline(policy=PromotionPolicy(str(path)))
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'core', auto_promote=True)
+        assert snippet.phase == StagingPhase.PASSED
+        assert snippet.policy_decision['rule'] == 'core' and not snippet.policy_decision['promote']
+        assert [a['snippet']['staging_id'] for a in pipeline.pending_approvals()] == [snippet.staging_id]
+        trail = {e['event']: e['data'] for e in pipeline.get_audit_trail(snippet.staging_id)}
+        assert trail['policy_evaluated']['rule'] == 'core'
+
+    def test_trusted_author_promotes(self, tmp_path, make_pipeline):
+        path = tmp_path / 'policy.toml'
+        path.write_text('[[rule]]\nslots = ["a*"]\nauthors = ["ci-bot"]\n', encoding='utf-8')
+        pipeline = make_pipeline(policy=PromotionPolicy(str(path)))
+        trusted = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'x', auto_promote=None,
+                                             author='ci-bot', default_auto_promote=False)
+        stranger = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'y', auto_promote=None,
+                                              author='mallory')
+        assert trusted.phase == StagingPhase.PROMOTED
+        assert stranger.phase == StagingPhase.PASSED
+
+    def test_without_policy(self, pipeline):
+        held = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'x', auto_promote=None,
+                                          default_auto_promote=False)
+        assert held.phase == StagingPhase.PASSED and held.policy_decision == {}
+        assert pipeline.run_full_pipeline('a', 'python', 'print(2)', 'y').phase == StagingPhase.PROMOTED
@@ -0,0 +1,159 @@
+"""
+Test suite for the promotion review flow (``spokedpy promote --interactive``).
+
+Tests cover:
+  - Review diff against the slot's current promoted snippet
+  - New-slot review diffs against /dev/null
+  - Header, gate results and spec output included in the review
+  - Relabelling before promotion, and its audit entry
+  - The approvals queue: pending list, approve / reject with a principal
+  - Reviewer authentication on the verdict and promote routes
+"""
+
+import pytest
+from flask import Flask
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from web_interface import runtime
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+def _staged(pipeline, code, label='etl'):
+    return pipeline.run_full_pipeline('a', 'python', code, label, auto_promote=False)
+
+
+# =============================================================================
+# REVIEW
+# =============================================================================
+
+class TestReview:
+
+    def test_first_snippet_diffs_against_empty_slot(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1\nprint(x)')
+        review = pipeline.review(snippet.staging_id)
+        assert review['current'] is None
+        assert review['diff'].startswith('--- /dev/null')
+        assert '+x = 1\n' in review['diff']
+
+    def test_diff_against_current_promoted_snippet(self, pipeline):
+        old = pipeline.run_full_pipeline('a', 'python', 'x = 1\nprint(x)', 'etl')
+        new = _staged(pipeline, 'x = 2\nprint(x)')
+        review = pipeline.review(new.staging_id)
+        assert review['current']['staging_id'] == old.staging_id
+        assert '-x = 1\n' in review['diff'] and '+x = 2\n' in review['diff']
+        assert ' print(x)\n' in review['diff']
+
+    def test_review_carries_header_and_spec_output(self, pipeline):
+        snippet = _staged(pipeline, 'print("hello")')
+        review = pipeline.review(snippet.staging_id)
+        assert f'staging_id:  {snippet.staging_id}' in review['header']
+        assert 'hello' in review['snippet']['spec_output']
+        assert review['snippet']['gate_results'] == []
+
+    def test_unknown_or_promoted_snippet_rejected(self, pipeline):
+        with pytest.raises(ValueError, match='No staged snippet'):
+            pipeline.review('stg-missing')
+        promoted = pipeline.run_full_pipeline('a', 'python', 'x = 1')
+        with pytest.raises(ValueError, match='No staged snippet'):
+            pipeline.review(promoted.staging_id)
+
+
+# =============================================================================
+# RELABEL
+# =============================================================================
+
+class TestRelabel:
+
+    def test_relabel_before_promotion(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1', label='draft')
+        pipeline.relabel(snippet.staging_id, '  nightly etl ')
+        promoted = pipeline.promote(snippet.staging_id)
+        assert promoted.label == 'nightly etl'
+
+        entry, = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                  if e['event'] == 'label_changed']
+        assert entry['data'] == {'from': 'draft', 'to': 'nightly etl'}
+
+    def test_empty_label_rejected(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1')
+        with pytest.raises(SpokedError, match='empty') as info:
+            pipeline.relabel(snippet.staging_id, '   ')
+        assert info.value.kind == ErrorKind.USAGE
+
+
+# =============================================================================
+# APPROVALS
+# =============================================================================
+
+class TestApprovals:
+
+    def test_pending_lists_unpromoted_snippets_with_diffs(self, pipeline):
+        first = _staged(pipeline, 'x = 1', label='first')
+        second = _staged(pipeline, 'x = 2', label='second')
+        pipeline.run_full_pipeline('a', 'python', 'x = 3', 'live')
+        pending = pipeline.pending_approvals()
+        assert [p['snippet']['staging_id'] for p in pending] == [first.staging_id, second.staging_id]
+        assert '+x = 1\n' in pending[0]['diff']
+
+    def test_approve_promotes_and_records_principal(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1')
+        promoted = pipeline.decide(snippet.staging_id, True, 'alice', 'looks good')
+        assert promoted.phase.value == 'promoted' and promoted.reviewed_by == 'alice'
+        with open(promoted.saved_file_path, 'r', encoding='utf-8') as f:
+            assert '#  approved_by: alice\n' in f.read()
+        entry, = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                  if e['event'] == 'approval_recorded']
+        assert entry['data'] == {'decision': 'approve', 'principal': 'alice', 'reason': 'looks good'}
+        assert pipeline.pending_approvals() == []
+
+    def test_reject_archives_with_principal_reason(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1')
+        rejected = pipeline.decide(snippet.staging_id, False, 'bob')
+        assert rejected.phase.value == 'rejected'
+        assert rejected.rejection_reason == 'Rejected by bob'
+
+    def test_failed_snippet_needs_override(self, make_pipeline):
+        strict = make_pipeline(allow_override=False)
+        failed = strict.speculate(strict.queue_snippet('a', 'python', '1/0').staging_id)
+        assert strict.pending_approvals() == []
+        with pytest.raises(ValueError, match='overrides are disabled'):
+            strict.decide(failed.staging_id, True, 'alice')
+        assert strict.decide(failed.staging_id, False, 'alice').phase.value == 'rejected'
+
+    def test_decision_needs_principal(self, pipeline):
+        snippet = _staged(pipeline, 'x = 1')
+        with pytest.raises(ValueError, match='principal'):
+            pipeline.decide(snippet.staging_id, True, '')
+
+
+class TestStagingRoutes:
+
+    @pytest.fixture
+    def client(self, pipeline, monkeypatch):
+        monkeypatch.setenv('SPOKEDPY_REVIEWERS', 'alice:s3cret')
+        monkeypatch.setattr(runtime, 'staging_pipeline', pipeline)
+        app = Flask(__name__)
+        app.register_blueprint(runtime.runtime_bp)
+        return app.test_client()
+
+    @pytest.mark.parametrize('route', ['verdict', 'promote'])
+    def test_needs_a_reviewer(self, client, pipeline, route):
+        snippet = _staged(pipeline, 'x = 1')
+        url = f'/api/staging/{route}/{snippet.staging_id}'
+        assert client.post(url, json={'action': 'approve'}).status_code == 401
+        bad = {'Authorization': 'Bearer nope'}
+        assert client.post(url, json={'action': 'approve'}, headers=bad).status_code == 401
+        assert snippet.phase.value == 'passed'
+
+    def test_promote_as_the_reviewer(self, client, pipeline):
+        snippet = _staged(pipeline, 'x = 1')
+        resp = client.post(f'/api/staging/promote/{snippet.staging_id}',
+                           headers={'Authorization': 'Bearer s3cret'})
+        assert resp.status_code == 200 and snippet.phase.value == 'promoted'
+        remote = _staged(pipeline, 'x = 2', label='other')
+        resp = client.post(f'/api/staging/promote/{remote.staging_id}',
+                           environ_base={'REMOTE_USER': 'bob'})
+        assert resp.status_code == 200 and remote.phase.value == 'promoted'
@@ -0,0 +1,151 @@
+"""
+Test suite for scheduled promotions (``spokedpy promote --at / --window``).
+
+Tests cover:
+  - ISO times, named windows and their next occurrence (UTC)
+  - Holding a PASSED snippet; only due promotions are applied, together
+  - Cancelling leaves the snippet PASSED; held snippets leave the approvals queue
+  - The scheduler thread's tick
+  - promote --at / --window / --cancel call the schedule API
+"""
+
+import calendar
+import io
+import time
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.promotion_schedule import (
+    PromotionScheduler, format_at, next_window, parse_at, parse_windows, resolve_when,
+)
+from visual_editor_core.snippet_staging import StagingPhase
+
+import spokedpy
+
+
+# Wednesday 2026-02-11 01:00 UTC
+NOW = float(calendar.timegm((2026, 2, 11, 1, 0, 0, 0, 0, 0)))
+
+
+def _passed(pipeline, label='etl'):
+    return pipeline.run_full_pipeline('a', 'python', f'print("{label}")', label, auto_promote=False)
+
+
+# =============================================================================
+# TIMES AND WINDOWS
+# =============================================================================
+
+class TestWhen:
+
+    def test_parse_at(self):
+        assert parse_at('2026-02-11T02:00Z') == NOW + 3600
+        assert parse_at('2026-02-11T02:00') == NOW + 3600
+        assert parse_at('2026-02-11T03:00+01:00') == NOW + 3600
+        assert format_at(NOW + 3600) == '2026-02-11T02:00Z'
+        with pytest.raises(ValueError, match='Cannot parse'):
+            parse_at('tomorrow')
+
+    def test_windows(self):
+        windows = parse_windows('nightly=02:00, weekly=sun 03:30')
+        assert windows == {'nightly': (None, 2, 0), 'weekly': (6, 3, 30)}
+        assert next_window(windows['nightly'], NOW) == NOW + 3600
+        assert next_window(windows['nightly'], NOW + 7200) == NOW + 3600 + 86400
+        assert format_at(next_window(windows['weekly'], NOW)) == '2026-02-15T03:30Z'
+        with pytest.raises(ValueError, match='Bad window'):
+            parse_windows('nightly')
+
+    def test_resolve_when(self):
+        assert resolve_when(window='nightly', windows='nightly=02:00', now=NOW) == NOW + 3600
+        with pytest.raises(ValueError, match='Unknown window'):
+            resolve_when(window='monthly', windows='nightly=02:00')
+        with pytest.raises(ValueError, match='exactly one'):
+            resolve_when('2026-02-11T02:00Z', 'nightly')
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestSchedule:
+
+    def test_only_due_promotions_apply(self, pipeline):
+        soon, later = _passed(pipeline, 'soon'), _passed(pipeline, 'later')
+        now = time.time()
+        pipeline.schedule_promotion(soon.staging_id, now + 60, 'alice')
+        pipeline.schedule_promotion(later.staging_id, now + 3600)
+        assert [s.staging_id for s in pipeline.scheduled()] == [soon.staging_id, later.staging_id]
+        assert pipeline.promote_due(now) == []
+        promoted = pipeline.promote_due(now + 61)
+        assert [s.staging_id for s in promoted] == [soon.staging_id]
+        assert soon.phase == StagingPhase.PROMOTED and later.phase == StagingPhase.PASSED
+        events = [e['event'] for e in pipeline.get_audit_trail(soon.staging_id)]
+        assert 'promotion_scheduled' in events and 'promotion_completed' in events
+
+    def test_cancel_keeps_snippet_passed(self, pipeline):
+        snippet = _passed(pipeline)
+        pipeline.schedule_promotion(snippet.staging_id, time.time() + 60)
+        assert pipeline.pending_approvals() == []
+        pipeline.cancel_schedule(snippet.staging_id, 'freeze')
+        assert snippet.phase == StagingPhase.PASSED and snippet.scheduled_for == 0.0
+        assert pipeline.promote_due(time.time() + 120) == []
+        assert [a['snippet']['staging_id'] for a in pipeline.pending_approvals()] == [snippet.staging_id]
+        with pytest.raises(ValueError, match='No scheduled promotion'):
+            pipeline.cancel_schedule(snippet.staging_id)
+
+    def test_only_passed_future_schedules(self, pipeline):
+        snippet = _passed(pipeline)
+        with pytest.raises(ValueError, match='in the past'):
+            pipeline.schedule_promotion(snippet.staging_id, time.time() - 1)
+        failed = pipeline.run_full_pipeline('a', 'python', '1/0', 'boom', auto_promote=False)
+        with pytest.raises(ValueError):
+            pipeline.schedule_promotion(failed.staging_id, time.time() + 60)
+
+    def test_scheduler_tick(self, pipeline):
+        snippet = _passed(pipeline)
+        pipeline.schedule_promotion(snippet.staging_id, time.time() + 0.05)
+        seen = []
+        scheduler = PromotionScheduler(pipeline.promote_due, interval=0.02, on_promoted=seen.extend)
+        scheduler.start()
+        deadline = time.time() + 5
+        while not seen and time.time() < deadline:
+            time.sleep(0.02)
+        scheduler.stop()
+        assert [s.staging_id for s in seen] == [snippet.staging_id]
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestPromoteCli:
+
+    def _run(self, monkeypatch, *argv):
+        calls = []
+
+        def api(method, url, json=None, **kwargs):
+            calls.append((method, url.split('/api/')[1], json))
+            return {'success': True, 'schedule': {'slot': 'a1', 'iso_at': '2026-02-11T02:00Z'}}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        args = spokedpy.build_parser().parse_args(['promote', *argv])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            code = args.func(args)
+        return code, calls, out.getvalue()
+
+    def test_at_and_window(self, monkeypatch):
+        code, calls, out = self._run(monkeypatch, 'stg-1', '--at', '2026-02-11T02:00Z')
+        assert code == 0 and calls == [('POST', 'staging/schedule/stg-1', {'at': '2026-02-11T02:00Z'})]
+        assert 'stg-1 → a1 at 2026-02-11T02:00Z' in out
+        _, calls, _ = self._run(monkeypatch, 'stg-1', '--window', 'nightly')
+        assert calls == [('POST', 'staging/schedule/stg-1', {'window': 'nightly'})]
+
+    def test_cancel(self, monkeypatch):
+        code, calls, out = self._run(monkeypatch, '--cancel', 'stg-1', '--reason', 'freeze')
+        assert code == 0 and calls == [('DELETE', 'staging/schedule/stg-1', {'reason': 'freeze'})]
+        assert 'cancelled' in out
+
+    def test_at_and_window_are_exclusive(self):
+        with redirect_stderr(io.StringIO()), pytest.raises(SystemExit):
+            spokedpy.build_parser().parse_args(['promote', 'stg-1', '--at', 'x', '--window', 'nightly'])
@@ -0,0 +1,205 @@
+"""
+Test suite for the read-only store mode ([store] read_only / serve --read-only).
+
+Tests cover:
+  - Every mutating pipeline call raises ReadOnlyStoreError
+  - Opening the store read-only creates no directories and writes no audit
+  - load_promoted fills a slot from a checkpoint entry without running it,
+    and refuses a missing file or a code_hash mismatch
+  - A read-only StatePersistence restores but never writes a checkpoint
+  - The settings hub refuses to change settings (403) while the store is read-only
+  - spokedpy serve --read-only switches the setting on before the server starts,
+    and a read_only = 0 row in the settings DB cannot switch it off
+"""
+
+import io
+import os
+import sys
+import time
+import types
+from contextlib import redirect_stdout
+
+import pytest
+from flask import Flask
+
+from visual_editor_core.snippet_staging import (
+    ReadOnlyStoreError,
+    StagingPhase,
+    StoreMode,
+)
+from web_interface import runtime, settings_hub
+from web_interface.state_persistence import StatePersistence, build_promoted_snapshots
+
+import spokedpy
+
+
+@pytest.fixture
+def make_store(make_pipeline):
+    # The audit log one directory down, which a read-only store must not create.
+    return lambda mode=StoreMode.READ_WRITE: make_pipeline(
+        audit_log_path='logs/audit.jsonl', mode=mode)
+
+
+@pytest.fixture
+def snapshot(make_store):
+    """A checkpoint entry for a snippet promoted by a read-write server."""
+    writer = make_store()
+    snippet = writer.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+    assert snippet.phase == StagingPhase.PROMOTED
+    (snap,) = build_promoted_snapshots(writer, {}, {})
+    return snap
+
+
+# =============================================================================
+# REFUSING CHANGES
+# =============================================================================
+
+class TestReadOnlyPipeline:
+
+    def test_mutations_refused(self, make_store):
+        pipeline = make_store(StoreMode.READ_ONLY)
+        assert pipeline.read_only and pipeline.mode == StoreMode.READ_ONLY
+        calls = [
+            lambda: pipeline.queue_snippet('a', 'python', 'print(1)', 'fib'),
+            lambda: pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib'),
+            lambda: pipeline.speculate('stg-x'),
+            lambda: pipeline.promote('stg-x'),
+            lambda: pipeline.rollback('stg-x', 'why'),
+            lambda: pipeline.relabel('stg-x', 'other'),
+            lambda: pipeline.decide('stg-x', True, 'alice'),
+            lambda: pipeline.schedule_promotion('stg-x', time.time() + 60, 'alice'),
+            lambda: pipeline.respec(),
+        ]
+        for call in calls:
+            with pytest.raises(ReadOnlyStoreError, match='The store is read-only: cannot'):
+                call()
+
+    def test_nothing_written(self, tmp_path, make_store):
+        pipeline = make_store(StoreMode.READ_ONLY)
+        with pytest.raises(ReadOnlyStoreError):
+            pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')
+        assert os.listdir(tmp_path) == []
+
+    def test_is_a_value_error(self):
+        # Routes map ValueError to 400; the blueprint guard answers 403 first.
+        assert issubclass(ReadOnlyStoreError, ValueError)
+
+
+# =============================================================================
+# RESOLVING PROMOTED SNIPPETS
+# =============================================================================
+
+class TestLoadPromoted:
+
+    def test_fills_the_slot(self, tmp_path, make_store, snapshot):
+        audit = tmp_path / 'logs' / 'audit.jsonl'
+        before = audit.read_text(encoding='utf-8')
+        reader = make_store(StoreMode.READ_ONLY)
+        snippet = reader.load_promoted(snapshot)
+        assert snippet.phase == StagingPhase.PROMOTED
+        assert snippet.staging_id == snapshot['staging_id']
+        assert snippet.reserved_address == snapshot['address']
+        slot = reader._registry.get_slot_by_address('a', snapshot['position'])
+        assert slot is not None and slot.slot_id == snippet.registry_slot_id
+        assert not slot.permissions.push and not slot.permissions.delete
+        assert reader.get_history()[-1] is snippet
+        assert audit.read_text(encoding='utf-8') == before
+
+    def test_missing_file(self, make_store, snapshot):
+        os.remove(snapshot['saved_file_path'])
+        with pytest.raises(ValueError, match='is missing'):
+            make_store(StoreMode.READ_ONLY).load_promoted(snapshot)
+
+    def test_hash_mismatch(self, make_store, snapshot):
+        snapshot['code'] = 'print("tampered")'
+        with pytest.raises(ValueError, match='does not match its code_hash'):
+            make_store(StoreMode.READ_ONLY).load_promoted(snapshot)
+
+
+# =============================================================================
+# CHECKPOINTS
+# =============================================================================
+
+class TestReadOnlyCheckpoint:
+
+    def test_restores_but_never_writes(self, tmp_path):
+        path = tmp_path / 'state' / 'checkpoint.json'
+        StatePersistence(str(path)).checkpoint_now({}, {}, [{'staging_id': 'stg-1'}])
+        before = path.read_text(encoding='utf-8')
+        reader = StatePersistence(str(path), read_only=True)
+        assert reader.restore()['promoted_snippets'] == [{'staging_id': 'stg-1'}]
+        reader.checkpoint_now({}, {}, [])
+        assert path.read_text(encoding='utf-8') == before
+        StatePersistence(str(tmp_path / 'none' / 'checkpoint.json'), read_only=True)
+        assert not (tmp_path / 'none').exists()
+
+
+# =============================================================================
+# SETTINGS HUB
+# =============================================================================
+
+class TestSettingsHub:
+
+    def test_settings_writes_refused(self, make_store, monkeypatch):
+        writes = []
+        monkeypatch.setattr(settings_hub, 'set_setting', lambda *args: writes.append(args))
+        monkeypatch.setattr(settings_hub, 'delete_setting', lambda *args: writes.append(args))
+        monkeypatch.setattr(runtime, 'staging_pipeline', make_store(StoreMode.READ_ONLY))
+        app = Flask(__name__)
+        app.register_blueprint(settings_hub.hub_bp)
+        client = app.test_client()
+
+        resp = client.post('/api/hub/settings/bulk', json={'updates': {'read_only': '0'}})
+        assert resp.status_code == 403 and resp.get_json()['kind'] == 'store_read_only'
+        assert client.delete('/api/hub/settings/read_only').status_code == 403
+        assert client.get('/api/hub/settings').status_code == 200
+        assert writes == []
+
+        monkeypatch.setattr(runtime, 'staging_pipeline', make_store())
+        assert client.delete('/api/hub/settings/read_only').status_code == 200
+        assert writes == [('read_only',)]
+
+    def test_logs_and_test_runs_refused(self, make_store, monkeypatch):
+        calls = []
+        monkeypatch.setattr(settings_hub, '_get_db', lambda: calls.append('db'))
+        monkeypatch.setattr(settings_hub, '_log', lambda *args, **kwargs: calls.append(args))
+        monkeypatch.setattr(runtime, 'staging_pipeline', make_store(StoreMode.READ_ONLY))
+        app = Flask(__name__)
+        app.register_blueprint(settings_hub.hub_bp)
+        client = app.test_client()
+
+        resp = client.post('/api/hub/logs', json={'message': 'hello'})
+        assert resp.status_code == 403 and resp.get_json()['kind'] == 'store_read_only'
+        assert client.delete('/api/hub/logs').status_code == 403
+        assert client.post('/api/hub/tests/run', json={}).status_code == 403
+        assert calls == []
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestServeReadOnly:
+
+    def test_flag_sets_the_setting_before_import(self, monkeypatch):
+        seen = []
+        # Stand-in for web_interface.app, whose import starts the runtime.
+        app = types.ModuleType('web_interface.app')
+        app.run_server = lambda **kw: seen.append((os.environ.get('SPOKEDPY_READ_ONLY'), kw))
+        monkeypatch.setitem(sys.modules, 'web_interface.app', app)
+        monkeypatch.delenv('SPOKEDPY_READ_ONLY', raising=False)
+        monkeypatch.setattr(runtime, 'read_only_forced', False)
+        args = spokedpy.build_parser().parse_args(['serve', '--read-only', '--port', '8123'])
+        with redirect_stdout(io.StringIO()):
+            assert args.func(args) == 0
+        (env, kwargs), = seen
+        assert env == '1' and kwargs['port'] == 8123 and runtime.read_only_forced
+        assert not spokedpy.build_parser().parse_args(['serve']).read_only
+
+    def test_flag_wins_over_a_db_row(self, monkeypatch):
+        def db_row(key, env_var, default):
+            return '0'                          # read_only = 0 in the settings DB
+        monkeypatch.setattr(runtime, 'read_only_forced', False)
+        assert not runtime._store_read_only(db_row)
+        monkeypatch.setattr(runtime, 'read_only_forced', True)
+        assert runtime._store_read_only(db_row)
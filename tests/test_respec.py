@@ -0,0 +1,144 @@
+"""
+Test suite for bulk re-spec of promoted snippets (``spokedpy respec``).
+
+Tests cover:
+  - Only each slot's current promoted snippet is re-run, filtered by engine
+  - Snippets that no longer pass are reported with the toolchain that ran them
+  - --quarantine rolls them back; every run is audited as 'respec'
+  - Parallel runs keep the order, and an exception counts as a failure
+  - spokedpy respec sends the request, prints the summary and exits 1 on failures
+"""
+
+import io
+import json
+from contextlib import redirect_stderr, redirect_stdout
+from types import SimpleNamespace
+
+import pytest
+
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.respec import format_respec, respec, toolchain_version
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.snippet_staging import StagingPhase
+
+import spokedpy
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(node_registry=NodeRegistry(SessionLedger()))
+
+
+def _break(pipeline, snippet):
+    """Simulate a toolchain upgrade that breaks this snippet."""
+    snippet.code = 'raise RuntimeError("removed in this version")'
+
+
+# =============================================================================
+# RUNNING
+# =============================================================================
+
+class TestRespec:
+
+    def test_parallel_keeps_order_and_catches_errors(self):
+        snippets = [SimpleNamespace(staging_id=f'stg-{i}', reserved_address=f'd{i}', label='x',
+                                    language='rust', spec_execution_time=0.1) for i in range(6)]
+
+        def run(snippet):
+            if snippet.staging_id == 'stg-3':
+                raise OSError('rustc vanished')
+            return {'success': snippet.staging_id != 'stg-1', 'error': 'E0658',
+                    'execution_time': 0.2}
+
+        report = respec(snippets, run, workers=3, engine='rust')
+        assert [r.staging_id for r in report.results] == [s.staging_id for s in snippets]
+        assert [r.staging_id for r in report.failing] == ['stg-1', 'stg-3']
+        assert report.failing[1].error == 'rustc vanished'
+        assert report.results[0].previous_spec_time == 0.1
+
+    def test_toolchain_version(self):
+        assert toolchain_version('python').startswith('python 3.')
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipelineRespec:
+
+    def test_reports_newly_failing(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+        assert pipeline.respec('python')['failing'] == 0
+        _break(pipeline, snippet)
+        report = pipeline.respec('python')
+        assert report['checked'] == 1 and report['failing'] == 1
+        (failing,) = report['results']
+        assert failing['staging_id'] == snippet.staging_id and 'removed' in failing['error']
+        assert snippet.phase == StagingPhase.PROMOTED and not failing['quarantined']
+        runs = [e['data'] for e in pipeline.get_audit_trail(snippet.staging_id)
+                if e['event'] == 'respec']                # newest first
+        assert [r['passed'] for r in runs] == [False, True]
+        assert runs[0]['toolchain'].startswith('python')
+
+    def test_quarantine_rolls_back(self, pipeline):
+        bad = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'bad')
+        _break(pipeline, bad)
+        report = pipeline.respec(quarantine=True)
+        assert report['quarantined'] == 1 and bad.phase == StagingPhase.ROLLED_BACK
+        assert bad.rejection_reason.startswith('Quarantined: spec fails under python')
+        assert pipeline.respec()['checked'] == 0
+
+    def test_only_current_snippet_and_engine(self, pipeline):
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'x')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'x')
+        assert old.reserved_address == new.reserved_address
+        report = pipeline.respec('PYTHON')
+        assert [r['staging_id'] for r in report['results']] == [new.staging_id]
+        assert pipeline.respec('rust')['checked'] == 0
+        with pytest.raises(ValueError, match="unknown engine 'cobol'"):
+            pipeline.respec('cobol')
+        with pytest.raises(ValueError, match=f'current promoted .*: {old.staging_id}'):
+            pipeline.respec(staging_ids=[old.staging_id])
+        assert pipeline.respec(staging_ids=[new.staging_id])['checked'] == 1
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestRespecCli:
+
+    def _run(self, argv):
+        args = spokedpy.build_parser().parse_args(argv)
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = args.func(args)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_summary_and_exit_status(self, pipeline, monkeypatch):
+        bad = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'bad')
+        _break(pipeline, bad)
+        sent = []
+
+        def api(method, url, json=None, **kwargs):
+            sent.append((url.split('/api/')[1], json, kwargs))
+            return {'success': True, 'report': pipeline.respec(json['engine'])}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        code, out, _ = self._run(['respec', '--engine', 'python', '--all-promoted'])
+        assert code == 1
+        (path, body, kwargs), = sent
+        assert path == 'staging/respec' and body['workers'] == 4 and kwargs['timeout'] is None
+        assert '1 now failing' in out and bad.staging_id in out and '--quarantine' in out
+
+    def test_needs_ids_or_all_promoted(self):
+        code, _, err = self._run(['respec', '--engine', 'rust'])
+        assert code == 1 and '--all-promoted' in err
+
+    def test_json(self, monkeypatch):
+        report = {'engine': 'rust', 'toolchains': {}, 'results': [], 'checked': 0,
+                  'failing': 0, 'quarantined': 0, 'duration': 0.0}
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **k: {'success': True, 'report': report})
+        code, out, _ = self._run(['respec', '--all-promoted', '--format', 'json'])
+        assert code == 0 and json.loads(out)['engine'] == 'rust'
+        assert 'nothing promoted' in format_respec(report)
@@ -0,0 +1,190 @@
+"""
+Test suite for revalidation and DEGRADED slots (revalidation).
+
+Tests cover:
+  - A promoted snippet failing respec marks its slot DEGRADED: audited,
+    its owners notified once, the snippet's ``degraded`` set; --quarantine
+    rolls it back instead
+  - While degraded, snippets importing the slot are not auto-promoted and
+    promote() refuses them (slot_degraded, exit 18); others promote
+  - The slot recovers when its snippet passes again, is rolled back or is
+    replaced; the state survives a restart (replayed from the audit log)
+  - Revalidator: tick() runs and reports, interval 0 never starts
+  - spokedpy list shows DEGRADED with the reason
+"""
+
+import io
+import time
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.notifiers import DEFAULT_TEMPLATES, Notification, render
+from visual_editor_core.respec import format_respec
+from visual_editor_core.revalidation import Revalidator, failure_reason, load_degraded
+from visual_editor_core.snippet_owners import SnippetOwners
+from visual_editor_core.snippet_staging import AuditEventType, AuditLogger, StagingPhase
+
+DEPENDENT = '# imports: a1\nprint("report")'
+
+
+def _break(snippet):
+    """Simulate a toolchain upgrade that breaks this snippet."""
+    snippet.code = 'raise RuntimeError("removed in this version")'
+
+
+def _degraded_pair(pipeline):
+    etl = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+    assert etl.reserved_address == 'a1'
+    _break(etl)
+    pipeline.respec()
+    return etl
+
+
+# =============================================================================
+# DEGRADING
+# =============================================================================
+
+class TestDegrade:
+
+    def test_failing_respec_degrades_once(self, tmp_path, make_pipeline):
+        path = tmp_path / 'OWNERS'
+        path.write_text('label:etl  carol dave@example.com\n', encoding='utf-8')
+        pipeline, events = make_pipeline(owners=SnippetOwners(str(path))), []
+        pipeline.add_listener(lambda event, snippet, details: events.append((event, details)))
+        etl = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+        etl = pipeline.decide(etl.staging_id, True, 'carol')
+        _break(etl)
+        report = pipeline.respec()
+        assert report['degraded'] == 1 and report['results'][0]['degraded']
+        assert '[degraded]' in format_respec(report)
+        assert etl.degraded['slot'] == 'a1' and 'removed in this version' in etl.degraded['reason']
+        assert etl.to_dict()['degraded']['toolchain'].startswith('python')
+        assert list(pipeline.degraded()) == ['a1']
+        assert pipeline.get_pipeline_summary()['degraded'] == ['a1']
+
+        pipeline.respec()                       # still failing: no second notification
+        notes = [d for e, d in events if e == 'degraded']
+        assert len(notes) == 1 and notes[0]['owners'] == ['carol', 'dave@example.com']
+        [event] = AuditLogger(str(tmp_path / 'audit.jsonl')).read_events(
+            AuditEventType.SLOT_DEGRADED)
+        assert (event['staging_id'], event['data']['code_hash']) == (etl.staging_id, etl.code_hash)
+
+        note = Notification.from_snippet('degraded', etl, notes[0]['reason'],
+                                         owners=notes[0]['owners'])
+        text = render(DEFAULT_TEMPLATES['degraded'], note)
+        assert 'slot a1 fails revalidation' in text and 'Owners: carol dave@example.com' in text
+
+    def test_quarantine_rolls_back_instead(self, pipeline):
+        etl = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+        _break(etl)
+        report = pipeline.respec(quarantine=True)
+        assert report['quarantined'] == 1 and report['degraded'] == 0
+        assert etl.phase == StagingPhase.ROLLED_BACK and pipeline.degraded() == {}
+
+    def test_failure_reason(self):
+        assert failure_reason('Traceback:\n\n  boom\n') == 'Traceback: — boom'
+        assert failure_reason('') == 'spec failed'
+        assert len(failure_reason('x' * 1000)) == 300
+
+
+# =============================================================================
+# BLOCKING DEPENDENTS
+# =============================================================================
+
+class TestBlocking:
+
+    def test_dependents_held(self, pipeline):
+        _degraded_pair(pipeline)
+        held = pipeline.run_full_pipeline('a', 'python', DEPENDENT, 'report')
+        assert held.phase == StagingPhase.PASSED
+        with pytest.raises(SpokedError, match='imports degraded slot.*a1: .*removed') as info:
+            pipeline.promote(held.staging_id)
+        assert info.value.kind is ErrorKind.SLOT_DEGRADED and info.value.exit_code == 18
+        assert info.value.context['slots'] == ['a1']
+        assert held.phase == StagingPhase.PASSED
+
+        free = pipeline.run_full_pipeline('a', 'python', 'print("other")', 'other')
+        assert free.phase == StagingPhase.PROMOTED
+
+    def test_scheduled_dependent_dropped(self, pipeline):
+        _degraded_pair(pipeline)
+        held = pipeline.run_full_pipeline('a', 'python', DEPENDENT, 'report', auto_promote=False)
+        pipeline.schedule_promotion(held.staging_id, time.time() + 60)
+        assert pipeline.promote_due(now=time.time() + 120) == [] and held.scheduled_for == 0.0
+        assert held.phase == StagingPhase.PASSED
+
+
+# =============================================================================
+# RECOVERY
+# =============================================================================
+
+class TestRecovery:
+
+    def test_passing_again(self, pipeline):
+        etl = _degraded_pair(pipeline)
+        held = pipeline.run_full_pipeline('a', 'python', DEPENDENT, 'report')
+        etl.code = 'print(1)'
+        assert pipeline.respec()['degraded'] == 0
+        assert etl.degraded == {} and pipeline.degraded() == {}
+        assert pipeline.promote(held.staging_id).phase == StagingPhase.PROMOTED
+        [event] = pipeline._audit.read_events(AuditEventType.SLOT_RECOVERED)
+        assert event['data']['reason'] == 'passed revalidation'
+
+    def test_rolled_back(self, pipeline):
+        etl = _degraded_pair(pipeline)
+        pipeline.rollback(etl.staging_id, 'broken')
+        assert pipeline.degraded() == {} and etl.degraded == {}
+        [event] = pipeline._audit.read_events(AuditEventType.SLOT_RECOVERED)
+        assert event['data']['reason'] == 'rolled back'
+
+    def test_replaced(self, make_pipeline):
+        _degraded_pair(make_pipeline())
+        restarted = make_pipeline()              # another snippet lands in a1
+        other = restarted.run_full_pipeline('a', 'python', 'print(2)', 'etl')
+        assert other.reserved_address == 'a1' and other.phase == StagingPhase.PROMOTED
+        assert restarted.degraded() == {} and other.degraded == {}
+        [event] = restarted._audit.read_events(AuditEventType.SLOT_RECOVERED)
+        assert event['data']['reason'] == f'replaced by {other.staging_id}'
+
+    def test_survives_a_restart(self, tmp_path, make_pipeline):
+        etl = _degraded_pair(make_pipeline())
+        assert list(load_degraded(AuditLogger(str(tmp_path / 'audit.jsonl')))) == ['a1']
+
+        restarted = make_pipeline()
+        again = restarted.run_full_pipeline('a', 'python', 'print(1)', 'etl')  # same code
+        assert again.code_hash == etl.code_hash and again.degraded['staging_id'] == etl.staging_id
+        held = restarted.run_full_pipeline('a', 'python', DEPENDENT, 'report')
+        assert held.phase == StagingPhase.PASSED
+        assert restarted.promote(held.staging_id, allow_degraded=True).phase == \
+            StagingPhase.PROMOTED
+
+
+# =============================================================================
+# SCHEDULE + CLI
+# =============================================================================
+
+class TestRevalidator:
+
+    def test_tick_and_interval(self, pipeline):
+        _degraded_pair(pipeline)
+        reports = []
+        revalidator = Revalidator(pipeline.revalidate, 0, on_report=reports.append)
+        revalidator.start()
+        assert revalidator._thread is None
+        assert revalidator.tick()['failing'] == 1 and reports[0]['degraded'] == 1
+        assert revalidator.last_run > 0
+
+    def test_list_shows_degraded(self, pipeline, monkeypatch):
+        etl = _degraded_pair(pipeline)
+        page = {'snippets': [s.to_dict() for s in pipeline.get_history()], 'total': 1,
+                'next_cursor': None}
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **k: {'success': True, **page})
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert spokedpy.main(['list']) == 0
+        row, why = out.getvalue().splitlines()
+        assert etl.staging_id in row and 'DEGRADED' in row and 'promoted' not in row.split()
+        assert '⚠ fails revalidation since' in why and 'removed in this version' in why
@@ -0,0 +1,181 @@
+"""
+Test suite for scratch dirs and the write guard (scratch, spec fixtures).
+
+Tests cover:
+  - fixtures in a sidecar: paths must stay inside its directory; load_spec
+    reads files and directories into the suite and they count towards
+    spec_hash (a suite without fixtures keeps its hash)
+  - A Python case runs in a scratch dir (cwd, HOME) that holds its fixtures
+    read-only and is removed afterwards
+  - Writes outside the scratch dir, or to a fixture, are refused; the case
+    fails with the violation and the tracebacks stay as python -c prints them
+  - Starting a process or loading native code is refused and fails the case,
+    even when the snippet catches the error
+  - An in-process dry-run that writes a file fails, with sandbox_violations
+    on the snippet and in its failure bundle
+  - Engine subprocesses start in the scratch dir; a changed fixture is found
+  - scratch=False runs as before
+"""
+
+import os
+import shutil
+
+import pytest
+
+from visual_editor_core import scratch
+from visual_editor_core.execution_engine import _run_subprocess
+from visual_editor_core.failure_bundle import format_bundle, read_bundle
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import SpecSuite, load_spec, parse_spec, run_suite
+
+
+def _suite(fixtures=None):
+    suite = parse_spec('[[case]]\nname = "only"\n')
+    suite.fixtures = dict(fixtures or {})
+    return suite
+
+
+# =============================================================================
+# FIXTURES IN THE SIDECAR
+# =============================================================================
+
+class TestFixtures:
+
+    @pytest.mark.parametrize('path', ['/etc/passwd', '../secrets.txt', 'data/../../x'])
+    def test_paths_stay_inside_the_sidecar_dir(self, path):
+        with pytest.raises(ValueError, match='inside the sidecar'):
+            parse_spec(f'fixtures = ["{path}"]\n[[case]]\nname = "a"\n')
+
+    def test_load_spec_reads_files_and_directories(self, tmp_path):
+        (tmp_path / 'testdata' / 'deep').mkdir(parents=True)
+        (tmp_path / 'testdata' / 'deep' / 'b.json').write_text('{}\n')
+        (tmp_path / 'in.csv').write_text('a,b\n')
+        sidecar = tmp_path / 'etl.spec.toml'
+        sidecar.write_text('fixtures = ["in.csv", "testdata/"]\n[[case]]\nname = "a"\n')
+        suite = load_spec(str(sidecar))
+        assert suite.fixtures == {'in.csv': 'a,b\n', 'testdata/deep/b.json': '{}\n'}
+        assert suite.to_dict()['fixtures'] == suite.fixtures
+
+        before = suite.spec_hash
+        (tmp_path / 'in.csv').write_text('a,b,c\n')
+        assert load_spec(str(sidecar)).spec_hash != before
+        assert SpecSuite(suite.cases).spec_hash != suite.spec_hash
+        assert 'fixtures' not in SpecSuite(suite.cases).to_dict()
+
+    def test_missing_and_binary_fixtures_are_refused(self, tmp_path):
+        sidecar = tmp_path / 'etl.spec.toml'
+        sidecar.write_text('fixtures = ["in.bin"]\n[[case]]\nname = "a"\n')
+        with pytest.raises(ValueError, match='not found'):
+            load_spec(str(sidecar))
+        (tmp_path / 'in.bin').write_bytes(b'\xff\xfe\x00')
+        with pytest.raises(ValueError, match='not UTF-8'):
+            load_spec(str(sidecar))
+
+
+# =============================================================================
+# PYTHON CASES
+# =============================================================================
+
+class TestPythonCases:
+
+    def test_case_runs_in_its_scratch_dir(self):
+        code = (
+            "import os\n"
+            "print(os.path.basename(os.getcwd()).startswith('spokedpy-scratch-'))\n"
+            "print(os.environ['HOME'].startswith(os.getcwd()))\n"
+            "print(open('data/in.csv').read().strip())\n"
+            "open('out.txt', 'w').write('ok')\n"
+            "print(os.getcwd())\n"
+        )
+        [result] = run_suite(_suite({'data/in.csv': '1,2\n'}), 'python', code, None, sandbox=True)
+        assert result.passed, result.error
+        lines = result.output.splitlines()
+        assert lines[:3] == ['True', 'True', '1,2']
+        assert not os.path.exists(lines[3])            # removed afterwards
+
+    def test_write_outside_is_refused(self, tmp_path):
+        target = tmp_path / 'evil.txt'
+        code = f"open({str(target)!r}, 'w').write('x')\n"
+        [result] = run_suite(_suite(), 'python', code, None, sandbox=True)
+        assert not result.passed
+        assert not target.exists()
+        assert result.violations == [{'case': 'only', 'op': 'open', 'path': str(target),
+                                      'reason': f"open outside the scratch dir: {target}"}]
+        assert result.first_diff.startswith('sandbox: open outside the scratch dir')
+
+    def test_write_to_a_fixture_is_refused(self):
+        code = "import os\nos.remove('in.csv')\n"
+        [result] = run_suite(_suite({'in.csv': 'a\n'}), 'python', code, None, sandbox=True)
+        assert not result.passed
+        assert [v['reason'] for v in result.violations] == ['remove of read-only fixture in.csv']
+
+    @pytest.mark.parametrize('code, op', [
+        ("import os\nos.system('touch /tmp/spokedpy-escaped')\n", 'system'),
+        ("import subprocess\nsubprocess.run(['touch', '/tmp/spokedpy-escaped'])\n", 'popen'),
+        ("import os\nos.execv('/bin/sh', ['sh'])\n", 'exec'),
+        ("import ctypes\n", 'dlopen'),
+    ])
+    def test_spawn_and_native_code_are_refused(self, code, op):
+        [result] = run_suite(_suite(), 'python', code, None, sandbox=True)
+        assert not result.passed
+        assert [v['op'] for v in result.violations] == [op]
+        assert 'escapes the write guard' in result.violations[0]['reason']
+
+    def test_caught_refusal_still_fails(self):
+        code = "import os\ntry:\n    os.system('true')\nexcept PermissionError:\n    pass\n"
+        [result] = run_suite(_suite(), 'python', code, None, sandbox=True)
+        assert not result.passed
+        assert result.violations[0]['path'] == 'true'
+
+    def test_traceback_is_the_snippets(self):
+        [result] = run_suite(_suite(), 'python', "x = 1\nraise ValueError('boom')\n", None,
+                             sandbox=True)
+        assert result.error.endswith('ValueError: boom')
+        assert not result.violations
+
+
+# =============================================================================
+# PIPELINE / ENGINES
+# =============================================================================
+
+class TestPipeline:
+
+    def test_in_process_write_fails_the_dry_run(self, tmp_path, make_pipeline):
+        pipeline = make_pipeline(failures_dir='failures')
+        target = tmp_path / 'written.txt'
+        snippet = pipeline.run_full_pipeline('a', 'python', f"open({str(target)!r}, 'w')\n",
+                                             'writer')
+        assert snippet.phase == StagingPhase.REJECTED and not snippet.spec_success
+        assert not target.exists()
+        assert [v['op'] for v in snippet.sandbox_violations] == ['open']
+        assert 'sandbox: open outside the scratch dir' in snippet.spec_error
+
+        bundle = read_bundle(str(tmp_path / 'failures'), snippet.staging_id)
+        assert bundle['violations'] == snippet.sandbox_violations
+        assert '── sandbox violations' in format_bundle(bundle)
+
+    def test_in_process_spawn_fails_the_dry_run(self, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python',
+                                                    "import os\nos.system('true')\n", 'spawner')
+        assert snippet.phase == StagingPhase.REJECTED
+        assert [v['op'] for v in snippet.sandbox_violations] == ['system']
+
+    def test_scratch_off_runs_as_before(self, tmp_path, make_pipeline):
+        pipeline = make_pipeline(scratch=False)
+        target = tmp_path / 'written.txt'
+        snippet = pipeline.run_full_pipeline('a', 'python',
+                                             f"open({str(target)!r}, 'w').write('x')\n", 'writer')
+        assert snippet.phase == StagingPhase.PROMOTED
+        assert target.read_text() == 'x'
+        assert snippet.sandbox_violations == []
+
+    @pytest.mark.skipif(shutil.which('sh') is None, reason='needs sh')
+    def test_engine_spawns_start_in_scratch_and_changed_fixtures_are_found(self):
+        with scratch.Scratch({'f.txt': 'a\n'}, 'sh') as box:
+            proc = _run_subprocess(['sh', '-c', 'pwd; echo "$HOME"; chmod u+w f.txt; '
+                                    'echo b > f.txt'], capture_output=True, text=True)
+        cwd, home = proc.stdout.split()
+        assert cwd == box.root and home == os.path.join(box.root, '.home')
+        assert box.violations == [{'case': 'sh', 'op': 'modify', 'path': 'f.txt',
+                                   'reason': 'modify of read-only fixture f.txt'}]
+        assert not os.path.exists(box.root)
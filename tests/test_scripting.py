@@ -0,0 +1,148 @@
+"""
+Test suite for the in-process scripting API (visual_editor_core.scripting).
+
+Tests cover:
+  - SnippetHeader: typed fields of a promoted file (times, slot position,
+    spec time, cases, meta), older banners, edited bodies
+  - Spoked.stage from a file (language from the extension, sidecar spec,
+    label from the stem) or from code; promote, list, slots, header
+  - Spoked.spec runs a suite without staging
+  - Usage errors and a read-only store raise SpokedError
+"""
+
+import os
+from datetime import datetime, timezone
+
+import pytest
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.scripting import SnippetHeader, Spoked, default_executors
+from visual_editor_core.snippet_staging import StagingPhase
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+
+CODE = 'import sys\nprint(sys.stdin.read().upper(), end="")\n'
+SPEC = '[[case]]\nname = "shouts"\nstdin = "hi"\nstdout = "HI"\n'
+
+
+@pytest.fixture
+def spoked(tmp_path):
+    return Spoked(str(tmp_path / 'data'), engines=['python'])
+
+
+@pytest.fixture
+def shout(tmp_path):
+    (tmp_path / 'shout.py').write_text(CODE, encoding='utf-8')
+    (tmp_path / 'shout.spec.toml').write_text(SPEC, encoding='utf-8')
+    return str(tmp_path / 'shout.py')
+
+
+# =============================================================================
+# HEADERS
+# =============================================================================
+
+class TestSnippetHeader:
+
+    def test_typed(self, spoked, shout):
+        snippet = spoked.stage(shout, meta={'team': 'payments'})
+        spoked.promote(snippet.staging_id)
+        header = SnippetHeader.from_file(snippet.saved_file_path)
+        assert (header.staging_id, header.language, header.engine, header.label) == (
+            snippet.staging_id, 'python', 'PYTHON', 'shout')
+        assert (header.slot, header.position) == (snippet.reserved_address,
+                                                  snippet.reserved_position)
+        assert isinstance(header.promoted, datetime) and header.promoted.tzinfo == timezone.utc
+        assert header.created <= header.promoted
+        assert header.spec_passed and header.spec_time >= 0 and header.verified
+        assert header.promoted_seq == snippet.promoted_seq > 0
+        assert [c['name'] for c in header.spec_cases] == ['shouts']
+        assert header.meta == {'team': 'payments'} and header.read_code() == CODE
+
+        with open(snippet.saved_file_path, 'a', encoding='utf-8') as f:
+            f.write('print("edited")\n')
+        edited = SnippetHeader.from_file(snippet.saved_file_path)
+        assert not edited.verified
+        with pytest.raises(SpokedError) as info:
+            edited.read_code()
+        assert info.value.kind == ErrorKind.STORE_CORRUPTION
+
+    def test_older_banner(self):
+        path = os.path.join(ROOT, 'data', 'snippets', 'python')
+        name = sorted(n for n in os.listdir(path) if n.endswith('.py'))[0]
+        header = SnippetHeader.from_file(os.path.join(path, name))
+        assert header.banner_version == 1 and header.promoted_seq == 0
+        assert header.code_hash.endswith('…') and header.promoted is not None
+
+    def test_not_promoted(self, shout):
+        with pytest.raises(ValueError):
+            SnippetHeader.from_file(shout)
+
+
+# =============================================================================
+# STAGE → PROMOTE → LIST
+# =============================================================================
+
+class TestSpoked:
+
+    def test_stage_file(self, spoked, shout):
+        snippet = spoked.stage(shout)
+        assert snippet.phase == StagingPhase.PASSED and snippet.label == 'shout'
+        assert [c['passed'] for c in snippet.spec_cases] == [True]
+        promoted = spoked.promote(snippet.staging_id)
+        assert promoted.phase == StagingPhase.PROMOTED
+        assert spoked.header(promoted.reserved_address).staging_id == snippet.staging_id
+        assert spoked.header(promoted.saved_file_path).label == 'shout'
+        assert list(spoked.slots()) == [promoted.reserved_address]
+
+    def test_stage_code_and_list(self, spoked):
+        failed = spoked.stage(code='raise ValueError("bad")', language='python', label='bad',
+                              meta={'team': 'search'})
+        assert failed.phase == StagingPhase.FAILED and not failed.spec_success
+        good = spoked.stage(code='print(1)', language='Python', label='good',
+                            meta={'team': 'payments'})
+        spoked.promote(good.staging_id)
+        assert [s.label for s in spoked.list()] == ['good', 'bad']
+        assert [s.label for s in spoked.list(phase='promoted')] == ['good']
+        assert [s.label for s in spoked.list(meta={'team': 'sea*'})] == ['bad']
+        assert [s.label for s in spoked.list(query='GOOD')] == ['good']
+        assert spoked.get(good.staging_id) is good
+        spoked.rollback(good.staging_id, 'test')
+        assert spoked.slots() == {}
+
+    def test_spec(self, spoked, shout):
+        (case,) = spoked.spec(shout)
+        assert case['name'] == 'shouts' and case['passed']
+        (case,) = spoked.spec(code='print("hi")', language='python', spec=SPEC)
+        assert not case['passed']
+        assert spoked.list() == []                    # nothing staged
+
+    @pytest.mark.parametrize('call', [
+        lambda s: s.stage(code='print(1)'),
+        lambda s: s.stage(code='x', language='cobol'),
+        lambda s: s.stage(),
+        lambda s: s.spec(code='print(1)', language='python'),
+    ])
+    def test_usage(self, spoked, call):
+        with pytest.raises(SpokedError) as info:
+            call(spoked)
+        assert info.value.kind == ErrorKind.USAGE
+
+    def test_missing(self, spoked):
+        for call in (lambda: spoked.get('stg-nope'), lambda: spoked.header('z9'),
+                     lambda: spoked.promote('stg-nope')):
+            with pytest.raises(SpokedError) as info:
+                call()
+            assert info.value.kind == ErrorKind.NOT_FOUND
+
+    def test_read_only(self, tmp_path, shout):
+        writer = Spoked(str(tmp_path / 'data'), engines=['python'])
+        slot = writer.promote(writer.stage(shout).staging_id).reserved_address
+        reader = Spoked(str(tmp_path / 'data'), read_only=True)
+        with pytest.raises(SpokedError) as info:
+            reader.stage(shout)
+        assert info.value.kind == ErrorKind.STORE_READ_ONLY
+        assert reader.header(slot).label == 'shout'
+
+    def test_engines(self):
+        assert set(default_executors(['rust'])) == {'python', 'rust'}
+        assert len(default_executors()) > 10
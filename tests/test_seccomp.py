@@ -0,0 +1,157 @@
+"""
+Test suite for seccomp profiles of spec runs ([sandbox] seccomp, seccomp).
+
+Tests cover:
+  - Profile names; the BPF program notifies on each denied syscall of the
+    machine (a syscall it lacks is left out) and on foreign ABIs
+  - DENIED(syscall) outcomes: spec_result, case lines round-trip
+  - Engine spawns get the supervisor only inside Denials, with a profile,
+    where filtering is supported; exit codes come through unchanged
+  - With filtering available: a denied syscall fails with EPERM and makes
+    the case DENIED; the snippet keeps spec_denied, the audit trail and
+    failure bundle say DENIED(ptrace)
+  - The doctor warns when a profile is set but cannot be applied
+"""
+
+import shutil
+import sys
+
+import pytest
+
+from visual_editor_core import seccomp
+from visual_editor_core.engine_doctor import CheckStatus, run_doctor
+from visual_editor_core.failure_bundle import format_bundle, read_bundle
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import case_line, parse_case_line, parse_spec, run_suite
+
+# Run without a scratch dir: its write guard refuses ctypes before seccomp sees a syscall.
+PTRACE = ("import ctypes\n"
+          "libc = ctypes.CDLL(None, use_errno=True)\n"
+          "print(libc.ptrace(0, 0, 0, 0), ctypes.get_errno())\n")
+
+
+@pytest.fixture
+def profile(monkeypatch):
+    """Set the seccomp profile for one test; restored afterwards."""
+    monkeypatch.setitem(seccomp._state, 'profile', seccomp._state['profile'])
+    return seccomp.set_profile
+
+
+# =============================================================================
+# PROFILES + FILTER
+# =============================================================================
+
+class TestProfiles:
+
+    def test_names(self, profile):
+        profile(' Prod ')
+        assert seccomp.profile() == 'prod'
+        profile('')
+        assert seccomp.profile() == 'off'
+        with pytest.raises(ValueError, match="unknown seccomp profile 'strict'"):
+            profile('strict')
+        assert set(seccomp.PROFILES['dev']) < set(seccomp.PROFILES['prod'])
+
+    @pytest.mark.parametrize('machine', ['x86_64', 'aarch64'])
+    def test_program_notifies_on_each_denied_syscall(self, machine):
+        arch, _nr, table = seccomp._SYSCALLS[machine]
+        code = seccomp.program(machine, seccomp.PROFILES['dev'])
+        notify = len(code) - 1
+        assert code[notify] == (0x06, 0, 0, seccomp._RET_USER_NOTIF)
+        assert code[1] == (0x15, 1, 0, arch)
+        checked = {}
+        for pc, (op, jt, _jf, k) in enumerate(code):
+            if op == 0x15 and pc > 1:
+                assert pc + 1 + jt == notify
+                checked[k] = True
+        assert sorted(checked) == sorted(table[n] for n in seccomp.PROFILES['dev'] if n in table)
+        assert ('iopl' in table) == (machine == 'x86_64')
+
+    def test_names_of_denied_syscalls(self):
+        arch = seccomp._SYSCALLS['x86_64'][0]
+        assert seccomp.syscall_name('x86_64', arch, 101) == 'ptrace'
+        assert seccomp.syscall_name('x86_64', arch, 0x40000000 | 101) == 'x32_syscall_101'
+        assert seccomp.syscall_name('x86_64', 0x40000003, 26) == 'compat_syscall_26'
+
+    def test_outcomes(self):
+        assert seccomp.spec_result(True) == 'PASS'
+        assert seccomp.spec_result(False, []) == 'FAIL'
+        assert seccomp.spec_result(False, ['ptrace', 'mount']) == 'DENIED(ptrace,mount)'
+        case = {'name': 'attaches', 'passed': False, 'duration': 0.25,
+                'first_diff': 'seccomp: denied ptrace', 'denied': ['ptrace']}
+        assert case_line(case) == 'DENIED(ptrace)  0.2500s  attaches — seccomp: denied ptrace'
+        assert parse_case_line(case_line(case)) == case
+        assert 'denied' not in parse_case_line('FAIL  0.1000s  plain')
+
+
+# =============================================================================
+# ENGINE SPAWNS
+# =============================================================================
+
+class TestSpawns:
+
+    def test_supervisor_only_inside_denials(self, profile, monkeypatch):
+        monkeypatch.setitem(seccomp._probe, 'ok', True)
+        profile('dev')
+        assert seccomp.apply((['node', 'x.js'],), {})[0] == (['node', 'x.js'],)
+        with seccomp.Denials() as denials:
+            args, _ = seccomp.apply((['node', 'x.js'],), {})
+            assert seccomp.apply(('node x.js',), {'shell': True})[0] == ('node x.js',)
+        assert args[0][:4] == [sys.executable, '-I', seccomp.__file__, 'dev']
+        assert args[0][4].startswith(denials.dir) and args[0][5:] == ['node', 'x.js']
+        assert denials.syscalls == []
+
+        profile('off')
+        with seccomp.Denials():
+            assert seccomp.apply((['node', 'x.js'],), {})[0] == (['node', 'x.js'],)
+        monkeypatch.setitem(seccomp._probe, 'ok', False)
+        profile('dev')
+        with seccomp.Denials():
+            assert seccomp.apply((['node', 'x.js'],), {})[0] == (['node', 'x.js'],)
+
+    def test_doctor_warns_when_not_applied(self, profile, monkeypatch):
+        monkeypatch.setitem(seccomp._probe, 'ok', False)
+        profile('prod')
+        [check] = [c for c in run_doctor(languages=[]).checks if c.name == 'seccomp']
+        assert check.status == CheckStatus.WARN and "'prod'" in check.detail
+        profile('off')
+        assert not [c for c in run_doctor(languages=[]).checks if c.name == 'seccomp']
+
+
+@pytest.mark.skipif(not seccomp.supported(), reason='needs seccomp user notifications')
+class TestFiltered:
+
+    SPEC = parse_spec('[[case]]\nname = "attaches"\n')
+
+    def test_exit_status_comes_through(self, profile):
+        from visual_editor_core.execution_engine import _run_subprocess
+        profile('prod')
+        with seccomp.Denials() as denials:
+            proc = _run_subprocess([shutil.which('sh') or '/bin/sh', '-c', 'exit 3'])
+        assert proc.returncode == 3 and denials.syscalls == []
+
+    def test_denied_syscall_makes_the_case_denied(self, profile):
+        profile('dev')
+        [result] = run_suite(self.SPEC, 'python', PTRACE, None, sandbox=False)
+        assert not result.passed and result.denied == ['ptrace']
+        assert result.output.split() == ['-1', '1']                    # EPERM
+        assert result.first_diff == 'seccomp: denied ptrace'
+        assert case_line(result.to_dict()).startswith('DENIED(ptrace)')
+
+        profile('off')
+        [result] = run_suite(self.SPEC, 'python', PTRACE, None, sandbox=False)
+        assert result.passed and result.denied == []
+
+    def test_pipeline_records_denied(self, tmp_path, make_pipeline, profile):
+        profile('prod')
+        pipeline = make_pipeline(failures_dir='failures', scratch=False)
+        snippet = pipeline.run_full_pipeline('a', 'python', PTRACE, 'tracer', spec=self.SPEC)
+        assert snippet.phase == StagingPhase.REJECTED and snippet.spec_denied == ['ptrace']
+        [failed] = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                    if e['event'] == 'spec_exec_failed']
+        assert failed['data']['spec_result'] == 'DENIED(ptrace)'
+
+        bundle = read_bundle(str(tmp_path / 'failures'), snippet.staging_id)
+        assert bundle['denied'] == ['ptrace'] and bundle['environment']['seccomp'] == 'prod'
+        text = format_bundle(bundle)
+        assert 'spec_result: DENIED(ptrace)' in text and 'DENIED(ptrace)  attaches' in text
@@ -0,0 +1,142 @@
+"""
+Test suite for slot constraints ([gates] constraints_file) and engine capabilities.
+
+Tests cover:
+  - Parsing [slot.<pattern>] tables; malformed files are rejected
+  - Engine, max_spec_time, required gates, capabilities and require_spec checks
+  - Every matching pattern applies; a broken file refuses every promotion
+  - promote() and schedule_promotion() refuse an unmet constraint (audited);
+    run_full_pipeline holds the snippet instead of promoting it
+  - review() lists the unmet constraints
+"""
+
+import time
+from types import SimpleNamespace
+
+import pytest
+
+from visual_editor_core.slot_constraints import (
+    SlotConstraints, capabilities, engine_language, parse_constraints,
+)
+from visual_editor_core.snippet_staging import StagingPhase
+
+
+CONSTRAINTS = """
+[slot.d2]
+engine = "RUST"
+max_spec_time = 2
+gates = ["clippy", "miri"]
+capabilities = ["compiled"]
+
+[slot."a*"]
+require_spec = true
+"""
+
+
+def _snippet(slot='d2', language='rust', spec_time=0.5, gates=('clippy', 'miri'), spec=None,
+             network=''):
+    return SimpleNamespace(reserved_address=slot, language=language, spec_execution_time=spec_time,
+                           gate_results=[{'gate': g, 'allow': True} for g in gates],
+                           spec=spec or {}, network=network)
+
+
+@pytest.fixture
+def constraints(tmp_path):
+    path = tmp_path / 'constraints.toml'
+    path.write_text(CONSTRAINTS, encoding='utf-8')
+    return SlotConstraints(str(path))
+
+
+# =============================================================================
+# PARSING + CAPABILITIES
+# =============================================================================
+
+class TestParse:
+
+    def test_tables(self):
+        d2, a = parse_constraints(CONSTRAINTS)
+        assert d2.slot == 'd2' and d2.engine == 'rust' and d2.max_spec_time == 2.0
+        assert d2.gates == ['clippy', 'miri'] and a.slot == 'a*' and a.require_spec
+
+    @pytest.mark.parametrize('text, match', [
+        ('[slot.d2]\nengine = "cobol"', "unknown engine 'cobol'"),
+        ('[slot.d2]\nowner = "bob"', 'unknown key'),
+        ('[slot.d2]\ncapabilities = ["teleport"]', 'unknown capability teleport'),
+        ('[slot.d2]\ngates = "clippy"', 'list of strings'),
+        ('[slot.d2]\nmax_spec_time = "2s"', 'number of seconds'),
+        ('[slots.d2]\nengine = "rust"', 'expected \\[slot.<address>\\]'),
+    ])
+    def test_rejects(self, text, match):
+        with pytest.raises(ValueError, match=match):
+            parse_constraints(text)
+
+    def test_capabilities(self):
+        assert 'compiled' in capabilities('rust') and 'compiled' not in capabilities('python')
+        assert 'spec_io' in capabilities('python') and capabilities('bash') == frozenset()
+        assert engine_language('RUST') == 'rust' and engine_language('c') == 'c'
+
+
+# =============================================================================
+# CHECKS
+# =============================================================================
+
+class TestViolations:
+
+    def test_meets_every_constraint(self, constraints):
+        assert constraints.violations(_snippet()) == []
+
+    def test_each_violation(self, constraints):
+        violations = constraints.violations(
+            _snippet(language='python', spec_time=3.0, gates=('clippy',)))
+        assert violations == ['engine python, slot requires rust',
+                              'spec_time 3.0000s > 2s',
+                              "gate 'miri' has not passed",
+                              'python lacks compiled']
+
+    def test_patterns_and_unconstrained_slots(self, constraints):
+        assert constraints.violations(_snippet('a7', 'python', gates=())) == ['no spec suite']
+        assert constraints.violations(_snippet('a7', 'python', spec={'cases': []})) == []
+        assert constraints.violations(_snippet('e1', 'java', gates=())) == []
+
+    def test_broken_file_refuses(self, tmp_path):
+        path = tmp_path / 'bad.toml'
+        path.write_text('[slot.d2]\nengine = "cobol"', encoding='utf-8')
+        violations = SlotConstraints(str(path)).violations(_snippet('e1', 'java'))
+        assert len(violations) == 1 and 'unreadable' in violations[0]
+        assert SlotConstraints(str(tmp_path / 'none.toml')).violations(_snippet()) == []
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipeline:
+
+    @pytest.fixture
+    def pipeline(self, tmp_path, make_pipeline):
+        path = tmp_path / 'constraints.toml'
+        path.write_text('[slot."a*"]\nrequire_spec = true\n', encoding='utf-8')
+        return make_pipeline(constraints=SlotConstraints(str(path)))
+
+    def test_promote_refuses(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'x', auto_promote=False)
+        with pytest.raises(ValueError, match='does not accept .*no spec suite'):
+            pipeline.promote(snippet.staging_id)
+        assert snippet.phase == StagingPhase.PASSED
+        with pytest.raises(ValueError, match='no spec suite'):
+            pipeline.schedule_promotion(snippet.staging_id, time.time() + 60)
+        events = [e['event'] for e in pipeline.get_audit_trail(snippet.staging_id)]
+        assert events.count('constraint_violated') == 2
+        assert pipeline.review(snippet.staging_id)['constraints'] == ['no spec suite']
+
+    def test_run_full_holds_instead_of_promoting(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'x', auto_promote=True)
+        assert snippet.phase == StagingPhase.PASSED
+        from visual_editor_core.spec_suite import parse_spec
+        ok = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'y',
+                                        spec=parse_spec('[[case]]\nname = "runs"\n'))
+        assert ok.phase == StagingPhase.PROMOTED
+
+    def test_without_constraints(self, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python', 'print(1)', 'x')
+        assert snippet.phase == StagingPhase.PROMOTED
@@ -0,0 +1,211 @@
+"""
+Test suite for slot resolution from the promoted files (SlotResolver).
+
+Tests cover:
+  - resolve_slot returns path, code_hash, label and engine of the slot's file
+  - The latest promotion into a slot wins; rolled-back snippets are skipped
+  - The index is cached, and rebuilt when a file is added, edited or the
+    audit log records a rollback
+  - read_code returns the body and refuses an edited file
+  - A SQLite index_path outlives the resolver: reopening reads no header,
+    picks up new files and rollbacks, and falls back to memory when the
+    path is unusable; opening a 20k-file store stays under 50ms
+  - spokedpy resolve prints the slot (or its code) without a server
+"""
+
+import hashlib
+import io
+import json
+import os
+import time
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.slot_resolver import SlotResolver
+
+import spokedpy
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(node_registry=NodeRegistry(SessionLedger()))
+
+
+@pytest.fixture
+def resolver(tmp_path):
+    return SlotResolver(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+
+
+def _touch_later(path):
+    st = os.stat(path)
+    os.utime(path, ns=(st.st_atime_ns, st.st_mtime_ns + 1_000_000_000))
+
+
+# =============================================================================
+# RESOLUTION
+# =============================================================================
+
+class TestResolveSlot:
+
+    def test_resolves_the_promoted_file(self, pipeline, resolver):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        found = resolver.resolve_slot('A1')
+        assert found.path == snippet.saved_file_path
+        assert found.code_hash == snippet.code_hash
+        assert (found.label, found.engine, found.language) == ('fib', 'PYTHON', 'python')
+        assert found.staging_id == snippet.staging_id
+        assert found.read_code() == 'print(1)'
+        assert resolver.resolve_slot('d3') is None
+
+    def test_latest_wins_and_rollback_skipped(self, pipeline, resolver):
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        _touch_later(new.saved_file_path)       # same second: mtime breaks the tie
+        assert resolver.resolve_slot('a1').staging_id == new.staging_id
+        pipeline.rollback(new.staging_id, 'regressed')
+        assert resolver.resolve_slot('a1').staging_id == old.staging_id
+        assert list(resolver.slots()) == ['a1']
+
+    def test_without_audit_log(self, pipeline, tmp_path):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        pipeline.rollback(snippet.staging_id, 'regressed')
+        # Only the audit log knows about the rollback.
+        assert SlotResolver(str(tmp_path / 'snippets')).resolve_slot('a1') is not None
+
+
+# =============================================================================
+# CACHING
+# =============================================================================
+
+class TestInvalidation:
+
+    def test_cached_until_something_changes(self, pipeline, resolver, monkeypatch):
+        pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        first = resolver.resolve_slot('a1')
+        import visual_editor_core.slot_resolver as mod
+        reads = []
+        real = mod._read_entry
+        monkeypatch.setattr(mod, '_read_entry', lambda p: reads.append(p) or real(p))
+        assert resolver.resolve_slot('a1') is first and reads == []
+        resolver.invalidate()
+        assert resolver.resolve_slot('a1') == first and reads
+
+    def test_edited_file_is_reread(self, pipeline, resolver):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert resolver.resolve_slot('a1').read_code() == 'print(1)'
+        with open(snippet.saved_file_path, 'a', encoding='utf-8') as f:
+            f.write('\nprint("patched")')
+        _touch_later(snippet.saved_file_path)
+        found = resolver.resolve_slot('a1')
+        assert found.mtime_ns == os.stat(snippet.saved_file_path).st_mtime_ns
+        with pytest.raises(ValueError, match='edited after promotion'):
+            found.read_code()
+        assert 'patched' in found.read_code(verify=False)
+
+    def test_deleted_file_empties_the_slot(self, pipeline, resolver):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert resolver.resolve_slot('a1') is not None
+        os.remove(snippet.saved_file_path)
+        assert resolver.resolve_slot('a1') is None
+
+
+# =============================================================================
+# PERSISTENT INDEX
+# =============================================================================
+
+class TestIndexPath:
+
+    def _open(self, tmp_path, index='index.sqlite'):
+        return SlotResolver(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'),
+                            str(tmp_path / index))
+
+    def test_reopen_reads_no_header(self, pipeline, tmp_path, monkeypatch):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib',
+                                             meta={'team': 'payments'})
+        first = self._open(tmp_path)
+        assert first.resolve_slot('a1').staging_id == snippet.staging_id
+        first.close()
+        import visual_editor_core.slot_resolver as mod
+        reads = []
+        real = mod._read_entry
+        monkeypatch.setattr(mod, '_read_entry', lambda p: reads.append(p) or real(p))
+        reopened = self._open(tmp_path)
+        found = reopened.resolve_slot('a1')
+        assert found.path == snippet.saved_file_path and found.meta == {'team': 'payments'}
+        assert reads == [] and list(reopened.slots()) == ['a1']
+        newer = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        _touch_later(newer.saved_file_path)
+        assert self._open(tmp_path).resolve_slot('a1').staging_id == newer.staging_id
+        assert reads == [newer.saved_file_path]
+
+    def test_rollback_after_reopen(self, pipeline, tmp_path):
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        _touch_later(new.saved_file_path)
+        assert self._open(tmp_path).resolve_slot('a1').staging_id == new.staging_id
+        pipeline.rollback(new.staging_id, 'regressed')
+        assert self._open(tmp_path).resolve_slot('a1').staging_id == old.staging_id
+
+    def test_unusable_path_falls_back(self, pipeline, tmp_path):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        (tmp_path / 'taken').write_text('not a database', encoding='utf-8')
+        resolver = self._open(tmp_path, 'taken')
+        assert resolver.resolve_slot('a1').staging_id == snippet.staging_id
+        assert (tmp_path / 'taken').read_text(encoding='utf-8') == 'not a database'
+
+    def test_large_store_opens_fast(self, pipeline, tmp_path):
+        template = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        with open(template.saved_file_path, 'r', encoding='utf-8') as f:
+            text = f.read()
+        for n in range(20_000):
+            digest = hashlib.sha256(str(n).encode()).hexdigest()
+            shard = tmp_path / 'snippets' / 'python' / digest[:2]
+            shard.mkdir(exist_ok=True)
+            staging_id = f"stg-{n:012x}"
+            slot = f"a{n % 16 + 1} ("
+            (shard / f"{staging_id}.py").write_text(
+                text.replace(template.staging_id, staging_id).replace('a1 (', slot),
+                encoding='utf-8')
+        self._open(tmp_path).slots()                        # builds the index once
+        timings = []
+        for _ in range(3):
+            began = time.perf_counter()
+            resolver = self._open(tmp_path)
+            assert resolver.resolve_slot('a7') is not None
+            timings.append(time.perf_counter() - began)
+            resolver.close()
+        assert min(timings) < 0.05, timings
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestResolveCli:
+
+    def _run(self, argv, tmp_path, monkeypatch):
+        monkeypatch.setenv('SPOKEDPY_SNIPPETS_DIR', str(tmp_path / 'snippets'))
+        monkeypatch.setenv('SPOKEDPY_AUDIT_LOG', str(tmp_path / 'audit.jsonl'))
+        monkeypatch.setenv('SPOKEDPY_INDEX_PATH', str(tmp_path / 'index.sqlite'))
+        args = spokedpy.build_parser().parse_args(argv)
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = args.func(args)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_resolve(self, pipeline, tmp_path, monkeypatch):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        code, out, _ = self._run(['resolve', 'a1'], tmp_path, monkeypatch)
+        assert code == 0 and snippet.staging_id in out and snippet.saved_file_path in out
+        code, out, _ = self._run(['resolve', 'a1', '--code'], tmp_path, monkeypatch)
+        assert code == 0 and out == 'print(1)'
+        code, out, _ = self._run(['resolve', 'a1', '--format', 'json'], tmp_path, monkeypatch)
+        assert json.loads(out)['label'] == 'fib'
+        assert (tmp_path / 'index.sqlite').is_file()
+
+    def test_empty_slot(self, tmp_path, monkeypatch):
+        code, _, err = self._run(['resolve', 'd3'], tmp_path, monkeypatch)
+        assert code == 1 and 'no promoted snippet in slot d3' in err
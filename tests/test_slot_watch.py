@@ -0,0 +1,120 @@
+"""
+Test suite for slot change notifications (SlotWatch, GET /api/slots/watch).
+
+Tests cover:
+  - Promotions and rollbacks are numbered events naming what the slot holds now
+  - wait() long-polls: returns at once with pending events, wakes on a new
+    one, or times out empty; slot patterns filter the events
+  - A cursor older than the kept events, or from before a restart, is "missed"
+  - spokedpy watch --once prints the change and passes the cursor back
+"""
+
+import io
+import json
+import threading
+import time
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.slot_watch import SlotWatch
+
+import spokedpy
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    pipeline = make_pipeline(node_registry=NodeRegistry(SessionLedger()))
+    pipeline.watch = SlotWatch(pipeline.current_promoted)
+    pipeline.add_listener(pipeline.watch)
+    return pipeline
+
+
+# =============================================================================
+# EVENTS
+# =============================================================================
+
+class TestEvents:
+
+    def test_promotion_and_rollback(self, pipeline):
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        pipeline.rollback(new.staging_id, 'regressed')
+        pipeline.rollback(old.staging_id, 'regressed')
+        events, cursor, missed = pipeline.watch.wait(['a1'], since=0, timeout=0)
+        assert cursor == 4 and not missed
+        assert [(e.seq, e.event, e.previous, e.staging_id) for e in events] == [
+            (1, 'promoted', old.staging_id, old.staging_id),
+            (2, 'promoted', new.staging_id, new.staging_id),
+            (3, 'rolled_back', new.staging_id, old.staging_id),     # falls back
+            (4, 'rolled_back', old.staging_id, ''),                 # now vacant
+        ]
+        assert events[2].path == old.saved_file_path and events[3].path == ''
+
+    def test_patterns_filter(self, pipeline):
+        pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert len(pipeline.watch.wait(['a*'], since=0, timeout=0)[0]) == 1
+        assert pipeline.watch.wait(['D3'], since=0, timeout=0) == ([], 1, False)
+
+
+# =============================================================================
+# LONG POLL
+# =============================================================================
+
+class TestWait:
+
+    def test_wakes_on_change(self, pipeline):
+        threading.Timer(0.2, pipeline.run_full_pipeline,
+                        ('a', 'python', 'print(1)', 'fib')).start()
+        started = time.monotonic()
+        events, cursor, _ = pipeline.watch.wait(['a1'], timeout=10)
+        assert [e.event for e in events] == ['promoted'] and cursor == 1
+        assert time.monotonic() - started < 5
+
+    def test_times_out_empty(self, pipeline):
+        started = time.monotonic()
+        assert pipeline.watch.wait(['a1'], timeout=0.1) == ([], 0, False)
+        assert time.monotonic() - started >= 0.1
+
+    def test_missed(self, pipeline):
+        watch = SlotWatch(pipeline.current_promoted, keep=1)
+        pipeline.add_listener(watch)
+        pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        assert watch.wait([], since=0, timeout=0)[2]
+        assert not watch.wait([], since=1, timeout=0)[2]
+        events, cursor, missed = watch.wait([], since=99, timeout=0)    # server restarted
+        assert missed and cursor == 2
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestWatchCli:
+
+    def test_once(self, monkeypatch):
+        calls = []
+        replies = [
+            {'success': True, 'cursor': 7, 'missed': False, 'events': []},
+            {'success': True, 'cursor': 8, 'missed': False, 'events': [
+                {'seq': 8, 'slot': 'd3', 'event': 'promoted', 'staging_id': 'stg-b',
+                 'previous': 'stg-b', 'label': 'parse-csv', 'code_hash': '',
+                 'path': 'data/snippets/rust/d3.rs', 'timestamp': 0.0}]},
+        ]
+
+        def api(method, url, params=None, **kwargs):
+            calls.append(dict(params))
+            return replies.pop(0)
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        args = spokedpy.build_parser().parse_args(['watch', 'd3', 'a*', '--once',
+                                                   '--format', 'json'])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        assert calls[0] == {'slots': 'd3,a*', 'timeout': 30.0}
+        assert calls[1]['since'] == 7
+        assert json.loads(out.getvalue())['staging_id'] == 'stg-b'
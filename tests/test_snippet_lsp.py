@@ -0,0 +1,201 @@
+"""
+Test suite for the snippet language server (``spokedpy lsp``).
+
+Tests cover:
+  - Header parsing round-trips the banner promote() writes
+  - Banner / field diagnostics, with x- fields allowed
+  - Stale code_hash after editing the body, stale spec_hash after editing the sidecar
+  - Slot conflicts and superseded snippets from the local index
+  - Code actions: re-stage and show promoted predecessor
+  - JSON-RPC framing: initialize + didOpen → publishDiagnostics
+"""
+
+import io
+import json
+
+import pytest
+
+from visual_editor_core.snippet_staging import parse_file_header
+from visual_editor_core.spec_suite import parse_spec, sidecar_for
+from visual_editor_core.snippet_lsp import (
+    CMD_PREDECESSOR, CMD_RESTAGE, SnippetLanguageServer, SnippetWorkspace, path_to_uri,
+)
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def workspace(tmp_path, pipeline):
+    return SnippetWorkspace(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+
+
+def _promoted(pipeline, code='x = 1\nprint(x)', label='etl'):
+    snippet = pipeline.run_full_pipeline('a', 'python', code, label)
+    with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+        return snippet, f.read()
+
+
+def _codes(diagnostics):
+    return [d.code for d in diagnostics]
+
+
+# =============================================================================
+# HEADER DIAGNOSTICS
+# =============================================================================
+
+class TestHeader:
+
+    def test_promoted_file_is_clean(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        header = parse_file_header(text)
+        assert header.fields['staging_id'] == snippet.staging_id
+        assert header.body == 'x = 1\nprint(x)' and header.body_matches_hash()
+        assert workspace.diagnose(snippet.saved_file_path, text) == []
+
+    def test_missing_banner_only_inside_snippets_dir(self, pipeline, workspace, tmp_path):
+        snippet, _ = _promoted(pipeline)
+        assert _codes(workspace.diagnose(snippet.saved_file_path, 'print(1)')) == ['banner']
+        assert workspace.diagnose(str(tmp_path / 'elsewhere.py'), 'print(1)') == []
+
+    def test_missing_and_unknown_fields(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        text = '\n'.join(l for l in text.split('\n') if 'spec_result:' not in l)
+        text = text.replace('#  label:', '#  x-owner:     data-team\n#  colour:      red\n#  label:')
+        messages = [d.message for d in workspace.diagnose(snippet.saved_file_path, text)]
+        assert any('Missing header field(s): spec_result' in m for m in messages)
+        assert any("Unknown header field 'colour'" in m for m in messages)
+        assert not any('x-owner' in m for m in messages)
+
+    def test_slot_on_wrong_row(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        text = text.replace(f'slot:        {snippet.reserved_address}', 'slot:        d1')
+        [diag] = workspace.diagnose(snippet.saved_file_path, text)
+        assert 'not on the python row' in diag.message
+        assert diag.line == parse_file_header(text).field_lines['slot']
+
+    def test_edited_body_is_stale(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        [diag] = workspace.diagnose(snippet.saved_file_path, text.replace('x = 1', 'x = 2'))
+        assert diag.code == 'stale-hash'
+
+    def test_edited_spec_sidecar_is_stale(self, pipeline, workspace):
+        spec = '[[case]]\nname = "prints"\nstdout = "1\\n"\n'
+        snippet = pipeline.run_full_pipeline('a', 'python', 'x = 1\nprint(x)', 'etl',
+                                             spec=parse_spec(spec))
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            text = f.read()
+        assert workspace.diagnose(snippet.saved_file_path, text) == []
+
+        sidecar = sidecar_for(snippet.saved_file_path)
+        with open(sidecar, 'w', encoding='utf-8') as f:
+            f.write(spec.replace('1\\n', '2\\n'))
+        [diag] = workspace.diagnose(snippet.saved_file_path, text)
+        assert diag.code == 'stale-spec' and 'changed since promotion' in diag.message
+        assert _codes(workspace.diagnose(sidecar, '[[case]]\nargv = 1\n')) == ['spec']
+
+
+# =============================================================================
+# SLOT STATE
+# =============================================================================
+
+class TestSlotState:
+
+    def test_conflicting_live_snippets(self, pipeline, workspace):
+        first, _ = _promoted(pipeline, label='one')
+        second, text = _promoted(pipeline, code='print(2)', label='two')
+        text = text.replace(f'slot:        {second.reserved_address}',
+                            f'slot:        {first.reserved_address}')
+        [diag] = workspace.diagnose(second.saved_file_path, text)
+        assert diag.code == 'slot-conflict' and first.staging_id in diag.message
+
+    def test_rolled_back_snippet(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        pipeline.rollback(snippet.staging_id, 'bad data')
+        [diag] = workspace.diagnose(snippet.saved_file_path, text)
+        assert diag.code == 'superseded' and 'rolled back' in diag.message
+
+
+# =============================================================================
+# CODE ACTIONS
+# =============================================================================
+
+class TestCodeActions:
+
+    def test_restage_is_quickfix_for_stale_hash(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        uri = path_to_uri(snippet.saved_file_path)
+        stale = [{'code': 'stale-hash', 'message': '…'}]
+        [action] = workspace.code_actions(uri, text.replace('x = 1', 'x = 2'), stale)
+        assert action['kind'] == 'quickfix'
+        assert action['command'] == {'title': 'Re-stage this snippet', 'command': CMD_RESTAGE,
+                                     'arguments': [uri]}
+
+    def test_predecessor_is_previous_promotion_of_label(self, pipeline, workspace):
+        old, _ = _promoted(pipeline, code='print(1)')
+        new, text = _promoted(pipeline, code='print(2)')
+        assert workspace.predecessor(new.saved_file_path, parse_file_header(text)) == old.saved_file_path
+        actions = workspace.code_actions(path_to_uri(new.saved_file_path), text)
+        assert [a['command']['command'] for a in actions] == [CMD_RESTAGE, CMD_PREDECESSOR]
+
+    def test_restage_command_stages_edited_body(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        staged = []
+
+        def stage(letter, language, code, label, **spec):
+            staged.append((letter, language, code, label))
+            return {'staging_id': 'stg-000000000001', 'reserved_address': 'a2', 'phase': 'passed'}
+
+        out = io.BytesIO()
+        server = SnippetLanguageServer(workspace, stage, io.BytesIO(), out)
+        uri = path_to_uri(snippet.saved_file_path)
+        server.documents[uri] = text.replace('x = 1', 'x = 2')
+        server.handle({'id': 1, 'method': 'workspace/executeCommand',
+                       'params': {'command': CMD_RESTAGE, 'arguments': [uri]}})
+        assert staged == [('a', 'python', 'x = 2\nprint(x)', 'etl')]
+        assert b'"result": "stg-000000000001"' in out.getvalue()
+
+
+# =============================================================================
+# JSON-RPC
+# =============================================================================
+
+def _frame(message):
+    body = json.dumps({'jsonrpc': '2.0', **message}).encode('utf-8')
+    return f'Content-Length: {len(body)}\r\n\r\n'.encode('ascii') + body
+
+
+def _unframe(data):
+    messages = []
+    while data:
+        head, _, rest = data.partition(b'\r\n\r\n')
+        length = int(head.split(b':')[1])
+        messages.append(json.loads(rest[:length]))
+        data = rest[length:]
+    return messages
+
+
+class TestServer:
+
+    def test_session_publishes_diagnostics(self, pipeline, workspace):
+        snippet, text = _promoted(pipeline)
+        uri = path_to_uri(snippet.saved_file_path)
+        stdin = io.BytesIO(b''.join(_frame(m) for m in [
+            {'id': 1, 'method': 'initialize', 'params': {}},
+            {'method': 'initialized', 'params': {}},
+            {'method': 'textDocument/didOpen',
+             'params': {'textDocument': {'uri': uri, 'text': text.replace('x = 1', 'x = 3')}}},
+            {'id': 2, 'method': 'textDocument/hover', 'params': {}},
+            {'id': 3, 'method': 'shutdown'},
+            {'method': 'exit'},
+        ]))
+        stdout = io.BytesIO()
+        assert SnippetLanguageServer(workspace, None, stdin, stdout).serve() == 0
+
+        init, published, hover, shutdown = _unframe(stdout.getvalue())
+        assert init['result']['capabilities']['codeActionProvider'] is True
+        assert published['method'] == 'textDocument/publishDiagnostics'
+        assert [d['code'] for d in published['params']['diagnostics']] == ['stale-hash']
+        assert hover['error']['code'] == -32601
+        assert shutdown == {'jsonrpc': '2.0', 'id': 3, 'result': None}
@@ -0,0 +1,164 @@
+"""
+Test suite for custom metadata fields (``--meta KEY=VALUE`` / ``x-`` header lines).
+
+Tests cover:
+  - normalize_meta / parse_meta_args: keys lower-cased, ``x-`` dropped,
+    bad keys and multi-line values refused, bare keys as filters
+  - Staged meta reaches the SNIPPET_QUEUED audit entry and the promoted
+    header as ``x-`` lines, which the LSP accepts and SlotResolver reads
+  - Meta survives a checkpoint (state snapshot → load_promoted) and is
+    picked up by the local index
+  - StagedSnippet.matches filters on meta (globs, bare keys) and text
+  - spokedpy stage sends --meta; spokedpy list passes the filters on
+"""
+
+import io
+import json
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.cli_completion import local_index
+from visual_editor_core.slot_resolver import SlotResolver
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import (
+    StoreMode,
+    normalize_meta,
+    parse_file_header,
+    parse_meta_args,
+)
+from web_interface.state_persistence import build_promoted_snapshots
+
+import spokedpy
+
+META = {'team': 'payments', 'ticket': 'PAY-1234'}
+
+
+# =============================================================================
+# PARSING
+# =============================================================================
+
+class TestParseMeta:
+
+    def test_normalize(self):
+        assert normalize_meta({'X-Team': ' payments ', 'cost.centre': 42}) == {
+            'cost.centre': '42', 'team': 'payments'}
+        assert normalize_meta(None) == {}
+
+    @pytest.mark.parametrize('meta', [{'bad key': 'x'}, {'-team': 'x'}, {'team': 'a\nb'},
+                                      {'team': 'x' * 201}])
+    def test_refused(self, meta):
+        with pytest.raises(ValueError):
+            normalize_meta(meta)
+
+    def test_args(self):
+        assert parse_meta_args(['team=payments', 'x-ticket=PAY-1234']) == META
+        with pytest.raises(ValueError, match='KEY=VALUE'):
+            parse_meta_args(['team'])
+        assert parse_meta_args(['team', 'ticket=PAY-*'], filters=True) == {
+            'team': None, 'ticket': 'PAY-*'}
+
+
+# =============================================================================
+# THROUGH THE PIPELINE
+# =============================================================================
+
+class TestPromotedMeta:
+
+    def test_header_and_audit(self, tmp_path, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python', 'print(1)', 'fib',
+                                                        meta={'X-Team': 'payments'})
+        assert snippet.meta == {'team': 'payments'}
+        with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+            text = f.read()
+        assert '#  x-team: payments\n' in text
+        header = parse_file_header(text)
+        assert header.meta == {'team': 'payments'} and header.body_matches_hash()
+        assert SnippetWorkspace(str(tmp_path / 'snippets')).diagnose(
+            snippet.saved_file_path, text) == []
+        queued = [e for e in make_pipeline().get_audit_trail(snippet.staging_id)
+                  if e['event'] == 'snippet_queued']
+        assert queued[0]['data']['meta'] == {'team': 'payments'}
+
+    def test_resolver_and_index(self, tmp_path, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python', 'print(1)', 'fib',
+                                                        meta=META)
+        resolver = SlotResolver(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        assert resolver.resolve_slot('a1').meta == META
+        (entry,) = local_index(str(tmp_path / 'audit.jsonl'))
+        assert entry.staging_id == snippet.staging_id and entry.meta == META
+
+    def test_survives_checkpoint(self, make_pipeline):
+        writer = make_pipeline()
+        writer.run_full_pipeline('a', 'python', 'print(1)', 'fib', meta=META)
+        (snap,) = build_promoted_snapshots(writer, {}, {})
+        assert snap['meta'] == META
+        reader = make_pipeline(mode=StoreMode.READ_ONLY)
+        assert reader.load_promoted(snap).meta == META
+
+    def test_matches(self, make_pipeline):
+        pipeline = make_pipeline()
+        payments = pipeline.queue_snippet('a', 'python', 'print(1)', 'pay', meta=META)
+        search = pipeline.queue_snippet('a', 'python', 'print(2)', 'search',
+                                        meta={'team': 'search'})
+        wanted = parse_meta_args(['team=pay*'], filters=True)
+        assert [s.label for s in (payments, search) if s.matches(wanted)] == ['pay']
+        assert not search.matches({'ticket': None})
+        assert payments.matches(query='pay-12') and search.matches(query='SEARCH')
+        assert not search.matches(query='payments')
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def _run(self, argv, api, monkeypatch):
+        monkeypatch.setattr(spokedpy, '_api', api)
+        args = spokedpy.build_parser().parse_args(argv)
+        out = io.StringIO()
+        with redirect_stdout(out), redirect_stderr(io.StringIO()):
+            code = args.func(args)
+        return code, out.getvalue()
+
+    def test_stage(self, tmp_path, monkeypatch):
+        path = tmp_path / 'etl.py'
+        path.write_text('print(1)\n', encoding='utf-8')
+        bodies = []
+
+        def api(method, url, json=None, **kwargs):
+            if url.endswith('/api/staging/queue'):
+                bodies.append(json)
+                return {'success': True, 'snippet': {'staging_id': 'stg-1'}}
+            return {'success': True, 'snippet': {
+                'staging_id': 'stg-1', 'label': 'etl', 'language': 'python',
+                'reserved_address': 'a1', 'phase': 'passed', 'spec_success': True,
+                'spec_execution_time': 0.01, 'meta': META}}
+
+        code, out = self._run(['stage', str(path), '--meta', 'team=payments',
+                               '--meta', 'ticket=PAY-1234'], api, monkeypatch)
+        assert code == 0 and bodies[0]['meta'] == {
+            **META, 'lang-detected-by': 'extension', 'lang-confidence': '1.00'}
+        assert (bodies[0]['engine_letter'], bodies[0]['label']) == ('a', 'etl')
+        assert 'x-team: payments' in out
+        code, _ = self._run(['stage', str(path), '--meta', 'team'], api, monkeypatch)
+        assert code == 1 and len(bodies) == 1
+
+    def test_list(self, monkeypatch):
+        calls = []
+
+        def api(method, url, params=None, **kwargs):
+            calls.append(params)
+            return {'success': True, 'active': [], 'history': [
+                {'staging_id': 'stg-1', 'reserved_address': 'a1', 'phase': 'promoted',
+                 'label': 'pay', 'meta': META},
+                {'staging_id': 'stg-2', 'reserved_address': 'a2', 'phase': 'failed',
+                 'label': 'pay2', 'meta': META}]}
+
+        code, out = self._run(['list', '--meta', 'team=payments', '--search', 'pay',
+                               '--phase', 'promoted'], api, monkeypatch)
+        assert code == 0 and calls[0]['meta'] == ['team=payments'] and calls[0]['q'] == 'pay'
+        assert 'stg-1' in out and 'team=payments ticket=PAY-1234' in out and 'stg-2' not in out
+        code, out = self._run(['list', '--format', 'json'], api, monkeypatch)
+        assert [s['staging_id'] for s in json.loads(out)['snippets']] == ['stg-1', 'stg-2']
@@ -0,0 +1,179 @@
+"""
+Test suite for snippet ownership ([gates] owners_file).
+
+Tests cover:
+  - Parsing the CODEOWNERS-style file: slot and label: patterns, comments
+  - The last matching line wins; a line without owners un-owns
+  - Staging into an owned slot records required_approvers, audits and sends
+    an approval_request notification (email owners become recipients)
+  - Owned snippets are not auto-promoted; only an owner may approve or
+    schedule them, anyone may reject
+  - Relabelling re-routes the request; the approvals queue shows the owners
+"""
+
+import time
+
+import pytest
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.notifiers import EmailNotifier, Notification, NotificationHub
+from visual_editor_core.snippet_owners import SnippetOwners, parse_owners
+from visual_editor_core.snippet_staging import StagingPhase
+
+
+OWNERS = """
+# pattern          owners
+a*                 alice
+a2                 bob carol@example.com    # more specific, later: wins
+label:etl*         dave
+a9
+"""
+
+
+@pytest.fixture
+def owners(tmp_path):
+    path = tmp_path / 'OWNERS'
+    path.write_text(OWNERS, encoding='utf-8')
+    return SnippetOwners(str(path))
+
+
+@pytest.fixture
+def pipeline(make_pipeline, owners):
+    pipeline = make_pipeline(owners=owners)
+    pipeline.events = []
+    pipeline.add_listener(lambda event, snippet, details: pipeline.events.append((event, details)))
+    return pipeline
+
+
+# =============================================================================
+# PARSING + MATCHING
+# =============================================================================
+
+class TestOwnersFile:
+
+    def test_parse(self):
+        rules = parse_owners(OWNERS)
+        assert [(r.pattern, r.owners, r.line) for r in rules] == [
+            ('a*', ['alice'], 3), ('a2', ['bob', 'carol@example.com'], 4),
+            ('label:etl*', ['dave'], 5), ('a9', [], 6)]
+
+    def test_bare_label_rejected(self):
+        with pytest.raises(ValueError, match="line 1: 'label:' needs a pattern"):
+            parse_owners('label: alice')
+
+    def test_last_match_wins(self, owners):
+        assert owners.match('a1', 'fib')[0] == ['alice']
+        assert owners.match('a2', 'fib')[0] == ['bob', 'carol@example.com']
+        approvers, rule = owners.match('a2', 'etl-nightly')
+        assert approvers == ['dave'] and rule.pattern == 'label:etl*'
+        assert owners.match('a9', 'fib') == ([], owners.rules[3])
+        assert owners.match('d1', 'fib') == ([], None)
+
+    def test_missing_and_broken_files(self, tmp_path):
+        assert SnippetOwners(str(tmp_path / 'none')).match('a1', 'x') == ([], None)
+        path = tmp_path / 'OWNERS'
+        path.write_text('label:\n', encoding='utf-8')
+        broken = SnippetOwners(str(path))
+        assert broken.rules == [] and 'needs a pattern' in broken.error
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestRouting:
+
+    def test_staging_requests_approval(self, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')
+        assert snippet.required_approvers == ['alice']
+        (event, details), = pipeline.events
+        assert event == 'approval_request' and details['owners'] == ['alice']
+        audit = {e['event']: e['data'] for e in pipeline.get_audit_trail(snippet.staging_id)}
+        assert audit['approval_requested']['rule'] == 'a*'
+
+    def test_unowned_slot_is_quiet(self, tmp_path, make_pipeline):
+        path = tmp_path / 'OWNERS'
+        path.write_text('d* ferris\n', encoding='utf-8')
+        pipeline = make_pipeline(owners=SnippetOwners(str(path)))
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert snippet.required_approvers == [] and snippet.phase == StagingPhase.PROMOTED
+
+    def test_owned_snippet_waits_for_an_owner(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib', auto_promote=True)
+        assert snippet.phase == StagingPhase.PASSED
+        (pending,) = pipeline.pending_approvals()
+        assert pending['snippet']['required_approvers'] == ['alice']
+        with pytest.raises(ValueError, match="owned by alice; 'mallory' cannot approve"):
+            pipeline.decide(snippet.staging_id, True, 'mallory')
+        with pytest.raises(ValueError, match='owned by alice'):
+            pipeline.schedule_promotion(snippet.staging_id, time.time() + 60, 'mallory')
+        assert pipeline.decide(snippet.staging_id, True, 'alice').phase == StagingPhase.PROMOTED
+
+    def test_plain_promote_is_refused(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib', auto_promote=False)
+        with pytest.raises(SpokedError, match="alice; 'anonymous' cannot approve") as exc:
+            pipeline.promote(snippet.staging_id)
+        assert exc.value.kind == ErrorKind.GATE_DENIED and snippet.phase == StagingPhase.PASSED
+        with pytest.raises(SpokedError, match="'mallory' cannot approve"):
+            pipeline.promote(snippet.staging_id, approved_by='mallory')
+        assert pipeline.promote(snippet.staging_id, approved_by='alice').phase == \
+            StagingPhase.PROMOTED
+
+    def test_scheduled_promotion_carries_the_owner(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib', auto_promote=False)
+        pipeline.schedule_promotion(snippet.staging_id, time.time() + 60, 'alice')
+        (promoted,) = pipeline.promote_due(time.time() + 61)
+        assert promoted.staging_id == snippet.staging_id
+
+    def test_anyone_may_reject(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib', auto_promote=False)
+        assert pipeline.decide(snippet.staging_id, False, 'mallory').phase == StagingPhase.REJECTED
+
+    def test_relabel_reroutes(self, pipeline):
+        snippet = pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')
+        pipeline.relabel(snippet.staging_id, 'etl-nightly')
+        assert snippet.required_approvers == ['dave']
+        assert [d['owners'] for e, d in pipeline.events] == [['alice'], ['dave']]
+        pipeline.relabel(snippet.staging_id, 'etl-hourly')
+        assert len(pipeline.events) == 2                # same owners: no second request
+
+
+# =============================================================================
+# NOTIFICATION
+# =============================================================================
+
+class TestNotification:
+
+    def test_email_goes_to_owner_addresses(self, pipeline):
+        sent = []
+
+        class FakeSMTP:
+            def __init__(self, host, port, timeout):
+                pass
+
+            def __enter__(self):
+                return self
+
+            def __exit__(self, *exc):
+                return False
+
+            def starttls(self):
+                pass
+
+            def send_message(self, msg):
+                sent.append((msg['Subject'], msg['To'], msg.get_content()))
+
+        email = EmailNotifier('mail.local', 587, 'spokedpy@example.com', ['ops@example.com'],
+                              events=['approval_request'], smtp_factory=FakeSMTP)
+        pipeline.add_listener(NotificationHub([email], background=False))
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'fib')   # a1: alice
+        pipeline.queue_snippet('a', 'python', 'print(2)', 'fib')   # a2: bob, carol@
+        (_, to_alice, body), (subject, to, _) = sent
+        assert to_alice == 'ops@example.com' and 'Owners: alice' in body
+        assert subject == '[spokedpy] Approval requested: fib (a2)'
+        assert to == 'ops@example.com, carol@example.com'
+
+    def test_owners_placeholder(self):
+        note = Notification(event='approval_request', staging_id='stg-abc', slot='a3',
+                            label='etl', language='python', owners='alice bob')
+        assert 'Owners: alice bob' in EmailNotifier('h', 25, 's', []).text(note)
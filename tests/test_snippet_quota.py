@@ -0,0 +1,178 @@
+"""
+Test suite for staging quotas (snippet_quota, [quota], spokedpy quota show).
+
+Tests cover:
+  - Scopes: the author ("unknown" when blank) and the namespace, a
+    label's first path segment
+  - max_staged per author and per namespace: refused with
+    quota_exceeded, the reserved slot (and a deterministic id) released,
+    room again once a snippet leaves the pipeline
+  - max_spec_minutes: dry-run wall time counted per UTC day, re-read from
+    the audit log by a new pipeline, yesterday's runs not counted; spec
+    run, compare and the REPL count too
+  - spokedpy quota show: the limits and each author's and namespace's use
+"""
+
+import io
+import time
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.snippet_quota import Quotas, format_quota, namespace_of, scopes_of
+from visual_editor_core.snippet_staging import deterministic_id
+from visual_editor_core.spec_suite import parse_spec
+
+
+def _refused(fn) -> SpokedError:
+    with pytest.raises(SpokedError) as exc:
+        fn()
+    assert exc.value.kind is ErrorKind.QUOTA_EXCEEDED and exc.value.exit_code == 17
+    return exc.value
+
+
+# =============================================================================
+# SCOPES
+# =============================================================================
+
+class TestScopes:
+
+    @pytest.mark.parametrize('label, namespace', [
+        ('billing/invoice', 'billing'),
+        ('billing/tax/vat', 'billing'),
+        ('Fibonacci', ''),
+        ('/billing', ''),
+        ('', ''),
+    ])
+    def test_namespace(self, label, namespace):
+        assert namespace_of(label) == namespace
+
+    def test_scopes(self):
+        assert scopes_of('', 'billing/invoice') == [('author', 'unknown'),
+                                                    ('namespace', 'billing')]
+        assert scopes_of('alice', 'etl') == [('author', 'alice')]
+        with pytest.raises(ValueError, match='cannot be negative'):
+            Quotas(max_staged=-1)
+
+
+# =============================================================================
+# STAGED SNIPPETS
+# =============================================================================
+
+class TestMaxStaged:
+
+    def test_per_author(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_staged=2))
+        first = pipeline.queue_snippet('a', 'python', 'print(1)', 'one', 'bot')
+        pipeline.queue_snippet('a', 'python', 'print(2)', 'two', 'bot')
+        error = _refused(lambda: pipeline.queue_snippet('a', 'python', 'print(3)', 'three', 'bot'))
+        assert error.context == {'scope': 'author', 'name': 'bot', 'limit': 'max_staged',
+                                 'used': 2, 'allowed': 2}
+        assert "author 'bot' has 2 snippet(s) staged" in str(error)
+        assert pipeline.get_reserved_positions().get('PYTHON') == [1, 2]
+        pipeline.queue_snippet('a', 'python', 'print(3)', 'three', 'alice')   # another author
+
+        pipeline.verdict(first.staging_id, 'reject')
+        assert pipeline.queue_snippet('a', 'python', 'print(4)', 'four', 'bot').author == 'bot'
+
+    def test_refusal_keeps_the_deterministic_id(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_staged=1), id_mode='deterministic')
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'one', 'bot')
+        _refused(lambda: pipeline.queue_snippet('a', 'python', 'print(2)', 'two', 'bot'))
+        second = pipeline.queue_snippet('a', 'python', 'print(2)', 'two', 'alice')
+        assert second.staging_id == deterministic_id(second.code_hash, 'two', 'a2')
+
+    def test_per_namespace(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_staged=1))
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'billing/invoice', 'alice')
+        error = _refused(lambda: pipeline.queue_snippet('a', 'python', 'print(2)',
+                                                        'billing/tax', 'bob'))
+        assert (error.context['scope'], error.context['name']) == ('namespace', 'billing')
+        pipeline.queue_snippet('a', 'python', 'print(3)', 'etl/load', 'carol')
+
+    def test_failed_dry_runs_count(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_staged=1))
+        failed = pipeline.queue_snippet('a', 'python', 'raise ValueError(1)', 'x', 'bot')
+        assert pipeline.speculate(failed.staging_id).phase.value == 'failed'
+        _refused(lambda: pipeline.run_full_pipeline('a', 'python', 'print(1)', 'y', author='bot'))
+
+
+# =============================================================================
+# SPEC MINUTES
+# =============================================================================
+
+class TestSpecMinutes:
+
+    def test_counted_and_refused(self, make_pipeline):
+        quotas = Quotas(max_spec_minutes=0.001)              # 60 ms
+        pipeline = make_pipeline(quotas=quotas)
+        snippet = pipeline.run_full_pipeline('a', 'python', 'import time\ntime.sleep(0.1)',
+                                             'billing/slow', author='bot')
+        assert quotas.spent('author', 'bot') >= 0.1
+        assert quotas.spent('namespace', 'billing') == quotas.spent('author', 'bot')
+        assert snippet.phase.value == 'promoted'
+        error = _refused(lambda: pipeline.queue_snippet('a', 'python', 'print(1)', 'z', 'bot'))
+        assert error.context['limit'] == 'max_spec_minutes' and 'resets at 00:00 UTC' in str(error)
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'z', 'alice')
+
+    def test_reread_after_restart(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_spec_minutes=60))
+        pipeline.run_full_pipeline('a', 'python', 'import time\ntime.sleep(0.05)', 'ns/a',
+                                   author='bot')
+        spent = pipeline.quotas.spent('author', 'bot')
+        again = make_pipeline(quotas=Quotas(max_spec_minutes=60))
+        assert again.quotas.spent('author', 'bot') == pytest.approx(spent, abs=0.05)
+        assert again.quotas.spent('namespace', 'ns') > 0
+
+    def test_runs_outside_the_pipeline_count(self, make_pipeline):
+        pipeline = make_pipeline(quotas=Quotas(max_spec_minutes=60))
+        spent = pipeline.quotas.spent
+        spec = parse_spec('[[case]]\nname = "one"\n')
+        pipeline.run_spec('python', spec, 'import time\ntime.sleep(0.05)', label='ns/run')
+        assert spent('namespace', 'ns') >= 0.05 and spent('author', 'unknown') >= 0.05
+
+        a = pipeline.queue_snippet('a', 'python', 'print(1)', 'ab/x', 'bot')
+        b = pipeline.queue_snippet('a', 'python', 'import time\ntime.sleep(0.05)', 'ab/x', 'bot')
+        pipeline.compare(a.staging_id, b.staging_id, spec)
+        assert spent('author', 'bot') >= 0.05 and spent('namespace', 'ab') >= 0.05
+
+        before = spent('author', 'unknown')
+        pipeline.evaluate('python', 'import time\ntime.sleep(0.05)')
+        assert spent('author', 'unknown') >= before + 0.05
+
+    def test_only_today(self):
+        quotas = Quotas(max_spec_minutes=1)
+        quotas.record('bot', 'x', 120, at=time.time() - 86400 * 2)
+        quotas.record('bot', 'x', 30)
+        assert quotas.spent('author', 'bot') == 30
+        quotas.check([], 'bot', 'x')
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestReport:
+
+    def test_quota_show(self, make_pipeline, monkeypatch):
+        pipeline = make_pipeline(quotas=Quotas(max_staged=5))
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'billing/invoice', 'alice')
+        pipeline.queue_snippet('a', 'python', 'print(2)', 'etl', 'alice')
+        calls = []
+
+        def api(method, url, params=None, **kwargs):
+            calls.append((url, params))
+            return {'quota': pipeline.quotas.report(pipeline.get_active())}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert spokedpy.main(['quota', 'show', '--author', 'alice']) == 0
+        assert calls[0][0].endswith('/api/staging/quota') and calls[0][1] == {'author': 'alice'}
+        text = out.getvalue()
+        assert 'max_staged 5, max_spec_minutes ∞' in text
+        assert 'author     alice' in text and 'staged    2/5' in text
+        assert 'namespace  billing' in text
+        assert format_quota(Quotas().report([])).endswith('no spec minutes used today')
@@ -0,0 +1,102 @@
+"""
+Test suite for the Snippet REPL (``spokedpy repl``).
+
+Tests cover:
+  - Blank line runs the buffer in the staging sandbox, which refuses
+    writes outside its scratch dir (in process and as a child process)
+  - :undo drops the failing block
+  - :stage queues + speculates the buffer on the language's row
+  - Command errors are reported, never raised
+"""
+
+import pytest
+
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.snippet_repl import ReplError, SnippetRepl
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def repl(pipeline):
+    def stage(letter, language, code, label):
+        snippet = pipeline.queue_snippet(letter, language, code, label)
+        return pipeline.speculate(snippet.staging_id).to_dict()
+
+    output = []
+    shell = SnippetRepl('python', pipeline.evaluate, stage, out=output.append)
+    shell.output = output
+    return shell
+
+
+def _feed(repl, *lines):
+    for line in lines:
+        assert repl.feed(line)
+
+
+# =============================================================================
+# RUN
+# =============================================================================
+
+class TestRun:
+
+    def test_blank_line_runs_buffer(self, repl):
+        _feed(repl, 'x = 6', 'print(x * 7)')
+        assert repl.prompt == 'python… '
+        _feed(repl, '')
+        assert repl.output[0] == '42'
+        assert repl.output[-1].startswith('  [ok · ')
+        assert repl.prompt == 'python> '
+
+    def test_undo_drops_failing_block(self, repl):
+        _feed(repl, 'x = 1', '', 'print(missing)', '')
+        assert not repl.last_result['success']
+        _feed(repl, ':undo')
+        assert repl.buffer == 'x = 1'
+
+    @pytest.mark.parametrize('timeout', [None, 10.0])
+    def test_evaluate_is_sandboxed(self, pipeline, tmp_path, timeout):
+        target = tmp_path / 'escaped.txt'
+        result = pipeline.evaluate('python', f"open({str(target)!r}, 'w').write('x')\n",
+                                   timeout=timeout)
+        assert not result['success'] and not target.exists()
+        assert [v['op'] for v in result['violations']] == ['open']
+        assert 'outside the scratch dir' in result['error']
+
+    def test_evaluate_leaves_no_audit_trail(self, repl, pipeline):
+        _feed(repl, 'x = 1', '')
+        assert pipeline.get_active() == []
+        assert pipeline.get_audit_trail('') == []
+
+
+# =============================================================================
+# COMMANDS
+# =============================================================================
+
+class TestCommands:
+
+    def test_stage_buffer(self, repl, pipeline):
+        _feed(repl, 'print("hi")', ':stage greeting a4')
+        snippet, = pipeline.get_active()
+        assert (snippet.label, snippet.phase) == ('greeting', StagingPhase.PASSED)
+        assert repl.output[-1].startswith(f'  ✔ staged {snippet.staging_id} → {snippet.reserved_address}')
+
+    def test_stage_on_wrong_row_rejected(self, repl, pipeline):
+        _feed(repl, 'x = 1', ':stage etl c')
+        assert "not on the python row" in repl.output[-1]
+        assert pipeline.get_active() == []
+
+    def test_bad_commands_reported(self, repl):
+        _feed(repl, ':stage', ':frobnicate', ':lang cobol', ':run')
+        assert len(repl.output) == 4
+        assert all(line.startswith('  ✘') for line in repl.output)
+        assert 'empty buffer' in repl.output[-1]
+
+    def test_quit(self, repl):
+        assert repl.feed(':quit') is False
+
+    def test_unknown_language(self, pipeline):
+        with pytest.raises(ReplError, match='choose from'):
+            SnippetRepl('cobol', pipeline.evaluate, None)
@@ -0,0 +1,194 @@
+"""
+Test suite for soft-delete and the trash (snippet_trash, StagingPipeline.delete).
+
+Tests cover:
+  - Deleting a staged snippet frees its slot and leaves a tombstone; restore
+    re-reserves the slot (the same one while it is free)
+  - A rolled-back snippet's promoted file moves to the trash and back
+  - Promoted snippets and unknown ids are refused; no trash_dir is a usage error
+  - purge by id or all; gc_trash only purges entries older than trash_days
+  - Every step is in the audit log
+  - spokedpy trash list reads the trash from disk; delete / restore / purge
+    go through the server, and a bare purge is a usage error
+"""
+
+import io
+import json
+import os
+import time
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.snippet_staging import AuditEventType, StagingPhase
+from visual_editor_core.snippet_trash import TOMBSTONE_FILE, list_entries
+
+import spokedpy
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(trash_dir='trash')
+
+
+def _events(pipeline, staging_id):
+    return [e['event'] for e in reversed(pipeline.get_audit_trail(staging_id))]
+
+
+# =============================================================================
+# DELETE / RESTORE
+# =============================================================================
+
+class TestDeleteRestore:
+
+    def test_staged_snippet(self, pipeline):
+        snippet = pipeline.queue_snippet('b', 'javascript', '// 1\n', 'one')
+        tombstone = pipeline.delete(snippet.staging_id, 'typo', 'ana')
+        assert pipeline.get_snippet(snippet.staging_id) is None
+        assert pipeline.get_reserved_positions() == {}
+        assert (tombstone['phase'], tombstone['reason'], tombstone['deleted_by']) == (
+            'queued', 'typo', 'ana')
+        assert os.path.isfile(os.path.join(tombstone['path'], TOMBSTONE_FILE))
+        assert tombstone['snippet']['code'] == '// 1\n'
+
+        restored = pipeline.restore(snippet.staging_id)
+        assert restored.phase == StagingPhase.QUEUED and restored.reserved_address == 'b1'
+        assert pipeline.get_reserved_positions() == {'JAVASCRIPT': [1]}
+        assert pipeline.trash() == []
+        assert _events(pipeline, snippet.staging_id)[-2:] == ['snippet_deleted',
+                                                              'snippet_restored']
+
+    def test_restored_into_another_free_slot(self, pipeline):
+        snippet = pipeline.queue_snippet('b', 'javascript', '// 1\n', 'one')
+        pipeline.delete(snippet.staging_id)
+        pipeline.queue_snippet('b', 'javascript', '// 2\n', 'two')       # takes b1
+        assert pipeline.restore(snippet.staging_id).reserved_address == 'b2'
+
+    def test_rolled_back_file_goes_along(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'etl')
+        path = snippet.saved_file_path
+        with pytest.raises(SpokedError) as err:
+            pipeline.delete(snippet.staging_id)
+        assert err.value.kind == ErrorKind.INVALID_STATE and 'roll it back' in str(err.value)
+
+        pipeline.rollback(snippet.staging_id, 'bad')
+        tombstone = pipeline.delete(snippet.staging_id)
+        assert not os.path.exists(path) and tombstone['file'] == path
+        assert os.path.isfile(os.path.join(tombstone['path'], os.path.basename(path)))
+        restored = pipeline.restore(snippet.staging_id)
+        assert restored.phase == StagingPhase.ROLLED_BACK and os.path.isfile(path)
+        assert pipeline.get_history()[0].staging_id == snippet.staging_id
+
+    def test_refusals(self, tmp_path, pipeline, make_pipeline):
+        with pytest.raises(SpokedError) as err:
+            pipeline.delete('stg-000000000000')
+        assert err.value.kind == ErrorKind.NOT_FOUND
+        with pytest.raises(SpokedError) as err:
+            pipeline.restore('stg-000000000000')
+        assert err.value.kind == ErrorKind.NOT_FOUND
+        snippet = pipeline.queue_snippet('b', 'javascript', '// 1\n', 'one')
+        with pytest.raises(SpokedError) as err:
+            pipeline.restore(snippet.staging_id)
+        assert err.value.kind == ErrorKind.INVALID_STATE
+        with pytest.raises(SpokedError) as err:
+            make_pipeline(root=tmp_path / 'bare').delete(snippet.staging_id)
+        assert err.value.kind == ErrorKind.USAGE
+
+
+# =============================================================================
+# PURGE / GC
+# =============================================================================
+
+class TestPurge:
+
+    def _trashed(self, pipeline, count):
+        ids = []
+        for n in range(count):
+            snippet = pipeline.queue_snippet('b', 'javascript', f"// {n}\n", f"s{n}")
+            pipeline.delete(snippet.staging_id)
+            ids.append(snippet.staging_id)
+        return ids
+
+    def test_by_id_and_all(self, pipeline):
+        ids = self._trashed(pipeline, 3)
+        assert pipeline.purge_trash([ids[0]]) == [ids[0]]
+        assert {e['staging_id'] for e in pipeline.trash()} == set(ids[1:])
+        assert sorted(pipeline.purge_trash()) == sorted(ids[1:])
+        assert pipeline.trash() == [] and _events(pipeline, ids[0])[-1] == 'trash_purged'
+        with pytest.raises(SpokedError) as err:
+            pipeline.purge_trash([ids[0]])
+        assert err.value.kind == ErrorKind.NOT_FOUND
+
+    def test_gc_respects_trash_days(self, tmp_path, make_pipeline):
+        pipeline = make_pipeline(trash_dir='trash', trash_days=7)
+        old, new = self._trashed(pipeline, 2)
+        tombstone = os.path.join(tmp_path, 'trash', old, TOMBSTONE_FILE)
+        with open(tombstone, 'r', encoding='utf-8') as f:
+            entry = json.load(f)
+        entry['deleted_at'] = time.time() - 8 * 86400
+        with open(tombstone, 'w', encoding='utf-8') as f:
+            json.dump(entry, f)
+        assert pipeline.gc_trash() == [old]
+        assert [e['staging_id'] for e in pipeline.trash()] == [new]
+        entry = pipeline.get_audit_trail(old)[0]                  # newest first
+        assert entry['event'] == AuditEventType.TRASH_PURGED.value and entry['data']['expired']
+
+    def test_zero_days_keeps_everything(self, make_pipeline):
+        pipeline = make_pipeline(trash_dir='trash', trash_days=0)
+        self._trashed(pipeline, 1)
+        assert pipeline.gc_trash() == [] and len(pipeline.trash()) == 1
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def _main(self, argv, monkeypatch, api=None):
+        if api is not None:
+            monkeypatch.setattr(spokedpy, '_api', api)
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = spokedpy.main(argv)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_list_reads_the_trash(self, tmp_path, pipeline, monkeypatch):
+        snippet = pipeline.queue_snippet('b', 'javascript', '// 1\n', 'one')
+        pipeline.delete(snippet.staging_id, 'typo', 'ana')
+        monkeypatch.setenv('SPOKEDPY_TRASH_DIR', str(tmp_path / 'trash'))
+        code, out, _ = self._main(['trash', 'list'], monkeypatch)
+        assert code == 0 and snippet.staging_id in out and 'by ana — typo' in out
+        code, out, _ = self._main(['trash', 'list', '--format', 'json'], monkeypatch)
+        assert [e['staging_id'] for e in json.loads(out)] == [snippet.staging_id]
+        assert list_entries(str(tmp_path / 'nowhere')) == []
+
+    def test_delete_restore_purge_call_the_server(self, monkeypatch):
+        calls = []
+
+        def api(method, url, json=None, **kwargs):
+            calls.append((method, url.split('/api/staging/')[1], json))
+            if method == 'DELETE':
+                return {'success': True, 'tombstone': {'phase': 'failed'}}
+            if url.endswith('/restore'):
+                return {'success': True, 'snippet': {'reserved_address': 'b1',
+                                                     'phase': 'failed'}}
+            return {'success': True, 'purged': ['stg-1']}
+
+        code, out, _ = self._main(['delete', 'stg-1', '--reason', 'typo', '--by', 'ana'],
+                                  monkeypatch, api)
+        assert code == 0 and 'trash restore stg-1' in out
+        code, out, _ = self._main(['trash', 'restore', 'stg-1'], monkeypatch, api)
+        assert code == 0 and 'restored → b1' in out
+        code, out, _ = self._main(['trash', 'purge', '--expired'], monkeypatch, api)
+        assert code == 0 and 'purged 1' in out
+        assert calls == [
+            ('DELETE', 'snippet/stg-1', {'reason': 'typo', 'deleted_by': 'ana'}),
+            ('POST', 'trash/stg-1/restore', None),
+            ('POST', 'trash/purge', {'staging_ids': [], 'expired': True}),
+        ]
+
+    def test_bare_purge_is_a_usage_error(self, monkeypatch):
+        code, _, err = self._main(['trash', 'purge'], monkeypatch, lambda *a, **k: {})
+        assert code == 2 and '--expired or --all' in err
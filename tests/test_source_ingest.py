@@ -0,0 +1,179 @@
+"""
+Test suite for ingesting source trees (source_ingest).
+
+Tests cover:
+  - The plan: hidden and build dirs left out; spec sidecars attached, not
+    staged; empty, binary and undetectable files skipped with a reason;
+    labels from the file name, dir or path; --lang for every file
+  - Ingesting into a real pipeline: each snippet carries x-ingested-from
+    into its promoted banner; refused stagings and failed dry-runs are
+    recorded and the ingest goes on
+  - The CLI: --dry-run stages nothing, --report writes the JSON report,
+    exit 1 when a file failed
+"""
+
+import io
+import json
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.snippet_staging import parse_file_header
+from visual_editor_core.source_ingest import format_ingest, ingest, plan
+
+
+@pytest.fixture
+def legacy(tmp_path):
+    """A pre-spokedpy snippet collection."""
+    root = tmp_path / 'legacy'
+    files = {
+        'math/fib.py': 'print(55)\n',
+        'math/fib.spec.toml': '[[case]]\nname = "prints"\nstdout = "55\\n"\n',
+        'tools/cleanup': '#!/usr/bin/env python3\nprint("clean")\n',
+        'web/hello.mjs': 'console.log("hi")\n',
+        'README.md': '# old snippets\n',
+        'empty.py': '',
+        '.git/config.py': 'print("no")\n',
+        'node_modules/dep/index.js': 'module.exports = 1\n',
+    }
+    for rel, text in files.items():
+        path = root / rel
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(text, encoding='utf-8')
+    (root / 'logo.py').write_bytes(b'\x89PNG\r\n\x1a\n\xff\xfe')
+    return root
+
+
+def _stage_into(pipeline):
+    def stage(body):
+        from visual_editor_core.spec_suite import parse_spec
+        spec = parse_spec(body['spec']) if body.get('spec') else None
+        return pipeline.run_full_pipeline(body['engine_letter'], body['language'], body['code'],
+                                          body['label'], author=body['author'], spec=spec,
+                                          meta=body['meta']).to_dict()
+    return stage
+
+
+# =============================================================================
+# PLAN
+# =============================================================================
+
+class TestPlan:
+
+    def test_files(self, legacy):
+        items = {i.path: i for i in plan(str(legacy))}
+        assert sorted(items) == ['README.md', 'empty.py', 'logo.py', 'math/fib.py',
+                                 'math/fib.spec.toml', 'tools/cleanup', 'web/hello.mjs']
+        fib = items['math/fib.py']
+        assert (fib.language, fib.detected_by, fib.label, fib.spec) == \
+            ('python', 'extension', 'fib', 'math/fib.spec.toml')
+        cleanup = items['tools/cleanup']
+        assert (cleanup.language, cleanup.detected_by, cleanup.confidence) == \
+            ('python', 'shebang', 0.95)
+        assert items['web/hello.mjs'].language == 'javascript'
+        assert items['README.md'].skipped == 'cannot tell the language (unknown; pass --lang)'
+        assert items['empty.py'].skipped == 'empty'
+        assert items['logo.py'].skipped == 'not UTF-8 text'
+        assert items['math/fib.spec.toml'].skipped.startswith('spec sidecar')
+
+    def test_ambiguous_files_are_skipped(self, tmp_path):
+        (tmp_path / 'util.h').write_text('int twice(int x);\n', encoding='utf-8')
+        (tmp_path / 'vec.h').write_text('#include <vector>\nstd::vector<int> v;\n',
+                                        encoding='utf-8')
+        items = {i.path: i for i in plan(str(tmp_path))}
+        assert items['util.h'].skipped == \
+            'cannot tell the language (ambiguous: c 0.50, cpp 0.50; pass --lang)'
+        assert (items['vec.h'].language, items['vec.h'].detected_by) == ('cpp', 'probes')
+
+    def test_labels(self, legacy):
+        def label(how):
+            return {i.path: i.label for i in plan(str(legacy), label_from=how)}['math/fib.py']
+        assert (label('filename'), label('dir'), label('path')) == ('fib', 'math', 'math/fib')
+        with pytest.raises(ValueError, match="--label-from 'owner'"):
+            plan(str(legacy), label_from='owner')
+
+    def test_one_language_for_all(self, legacy):
+        items = {i.path: i for i in plan(str(legacy), lang='python')}
+        assert (items['README.md'].language, items['README.md'].detected_by) == ('python', 'flag')
+        with pytest.raises(ValueError, match="Unknown language 'cobol'"):
+            plan(str(legacy), lang='cobol')
+        with pytest.raises(FileNotFoundError):
+            plan(str(legacy / 'nope'))
+
+
+# =============================================================================
+# INGEST
+# =============================================================================
+
+class TestIngest:
+
+    def test_into_a_pipeline(self, legacy, pipeline):
+        report = ingest(str(legacy), _stage_into(pipeline), meta={'team': 'core'})
+        assert [i.path for i in report.staged] == ['math/fib.py', 'tools/cleanup', 'web/hello.mjs']
+        assert len(report.skipped) == 4 and report.languages() == {'python': 2, 'javascript': 1}
+        fib = report.staged[0]
+        assert fib.spec_success and fib.phase == 'promoted' and fib.slot.startswith('a')
+        snippet = pipeline.get_snippet(fib.staging_id)
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        assert header.fields['x-ingested-from'] == 'math/fib.py'
+        assert header.fields['x-team'] == 'core'
+        assert header.fields['x-lang-detected-by'] == 'extension'
+        assert header.fields['x-lang-confidence'] == '1.00'          # print( agrees
+        assert header.body.startswith('print(55)')
+
+    def test_failures_are_recorded(self, legacy, pipeline):
+        (legacy / 'math' / 'fib.spec.toml').write_text(
+            '[[case]]\nname = "prints"\nstdout = "56\\n"\n', encoding='utf-8')
+
+        def stage(body):
+            if body['language'] == 'javascript':
+                raise ValueError('Engine row b has no free slot')
+            return _stage_into(pipeline)(body)
+
+        report = ingest(str(legacy), stage)
+        failed = {i.path: i.error for i in report.failed}
+        assert failed['web/hello.mjs'] == 'Engine row b has no free slot'
+        assert failed['math/fib.py']
+        assert [i.path for i in report.staged] == ['math/fib.py', 'tools/cleanup']
+        text = format_ingest(report)
+        assert '(2 failed), 4 skipped' in text and '✘ web/hello.mjs' in text
+        assert '– README.md' in text
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def test_dry_run_and_report(self, legacy, tmp_path, monkeypatch):
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **kw: pytest.fail('staged on a dry run'))
+        report_path = tmp_path / 'ingest.json'
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert spokedpy.main(['ingest', str(legacy), '--dry-run', '--label-from', 'path',
+                                  '--report', str(report_path)]) == 0
+        assert 'would stage 3, 4 skipped' in out.getvalue()
+        report = json.loads(report_path.read_text(encoding='utf-8'))
+        assert report['dry_run'] and report['staged'] == 0
+        assert {i['path']: i['label'] for i in report['items']}['tools/cleanup'] == 'tools/cleanup'
+
+    def test_stages_through_the_server(self, legacy, monkeypatch):
+        calls = []
+
+        def api(method, url, json=None, **kwargs):
+            calls.append(url.rsplit('/api/', 1)[1])
+            if json is not None:
+                return {'snippet': {'staging_id': f"stg-{len(calls)}", **json}}
+            ok = not calls[-1].endswith('stg-5')         # the third file fails its dry-run
+            return {'snippet': {'staging_id': calls[-1].rsplit('/', 1)[1], 'phase': 'speculated',
+                                'reserved_address': 'a1', 'spec_success': ok,
+                                'spec_error': '' if ok else 'SyntaxError: bad'}}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        with redirect_stdout(io.StringIO()):
+            assert spokedpy.main(['ingest', str(legacy), '--format', 'json']) == 1
+        assert calls[:2] == ['staging/queue', 'staging/speculate/stg-1']
+        assert len(calls) == 6
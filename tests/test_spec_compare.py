@@ -0,0 +1,209 @@
+"""
+Test suite for A/B comparison runs (``spokedpy compare``).
+
+Tests cover:
+  - The measured runner returns output and exit code, and records peak RSS
+  - Both candidates run every case; outputs compared per case
+  - Recommendation: more passing cases, then faster, then less memory
+  - The pipeline checks labels and languages, falls back to the snippets' spec,
+    and audits both sides; each side runs in the scratch sandbox, so a write
+    outside it fails that side
+  - spokedpy compare sends the cases file and prints the side-by-side report
+"""
+
+import io
+import json
+import os
+import subprocess
+import sys
+from contextlib import redirect_stderr, redirect_stdout
+
+import pytest
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.spec_compare import (
+    CaseComparison, Comparison, MeasuredRunner, Side, format_comparison,
+)
+from visual_editor_core.spec_suite import SpecSuite, parse_spec
+
+import spokedpy
+
+
+CASES = """
+[[case]]
+name = "ten"
+args = ["10"]
+stdout = "55\\n"
+
+[[case]]
+name = "one"
+args = ["1"]
+stdout = "1\\n"
+"""
+
+ITERATIVE = """
+import sys
+a, b = 0, 1
+for _ in range(int(sys.argv[1])):
+    a, b = b, a + b
+print(a)
+"""
+
+BROKEN = """
+import sys
+print(int(sys.argv[1]) * 5 + 5)
+"""
+
+
+def _report(a_times, b_times, a_passed=(True, True), b_passed=(True, True), a_kb=None, b_kb=None):
+    report = Comparison('fib', {'staging_id': 'stg-a'}, {'staging_id': 'stg-b'}, 1)
+    for i, (ta, tb) in enumerate(zip(a_times, b_times)):
+        report.cases.append(CaseComparison(
+            f'case{i}', Side(a_passed[i], ta, a_kb, '1\n'), Side(b_passed[i], tb, b_kb, '1\n')))
+    return report
+
+
+# =============================================================================
+# MEASURING
+# =============================================================================
+
+class TestMeasuredRunner:
+
+    def test_runs_and_measures(self):
+        runner = MeasuredRunner()
+        proc = runner([sys.executable, '-c', 'import sys; print(sys.stdin.read().upper()); sys.exit(3)'],
+                      input='hi', capture_output=True, text=True, timeout=30, env=dict(os.environ))
+        assert proc.stdout == 'HI\n' and proc.returncode == 3
+        if hasattr(os, 'wait4'):
+            assert runner.peak_kb and runner.peak_kb > 0
+
+    def test_timeout(self):
+        with pytest.raises(subprocess.TimeoutExpired):
+            MeasuredRunner()([sys.executable, '-c', 'import time; time.sleep(30)'],
+                             timeout=0.5, env=dict(os.environ))
+
+
+# =============================================================================
+# RECOMMENDATION
+# =============================================================================
+
+class TestRecommendation:
+
+    def test_more_passing_cases_wins(self):
+        rec = _report([0.1, 0.1], [0.01, 0.01], a_passed=(True, True),
+                      b_passed=(True, False)).recommendation()
+        assert rec['winner'] == 'a' and 'passes 2/2' in rec['reason']
+
+    def test_faster_wins(self):
+        rec = _report([0.4, 0.1], [0.1, 0.1]).recommendation()
+        assert rec['winner'] == 'b' and '2.5× faster' in rec['reason']
+
+    def test_memory_breaks_a_tie(self):
+        rec = _report([0.1, 0.1], [0.101, 0.1], a_kb=20480, b_kb=10240).recommendation()
+        assert rec['winner'] == 'b' and 'less memory' in rec['reason']
+        assert _report([0.1], [0.1]).recommendation()['winner'] == ''
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipelineCompare:
+
+    def _stage(self, pipeline, code, label='fib', spec=None):
+        return pipeline.run_full_pipeline('a', 'python', code, label, auto_promote=False,
+                                          spec=spec)
+
+    def test_side_by_side(self, pipeline):
+        a = self._stage(pipeline, ITERATIVE)
+        b = self._stage(pipeline, BROKEN)
+        report = pipeline.compare(a.staging_id, b.staging_id, parse_spec(CASES), 'fib')
+        assert [c['name'] for c in report['cases']] == ['ten', 'one']
+        ten, one = report['cases']
+        assert ten['a']['passed'] and ten['b']['passed'] and ten['same_output']
+        assert one['a']['passed'] and not one['b']['passed'] and not one['same_output']
+        assert report['recommendation']['winner'] == 'a'
+        events = {e['event']: e['data'] for e in pipeline.get_audit_trail(b.staging_id)}
+        assert events['compared']['against'] == a.staging_id and not events['compared']['winner']
+        assert 'promote A' in format_comparison(report)
+
+    def test_write_outside_scratch_fails_the_side(self, pipeline, tmp_path):
+        target = tmp_path / 'escaped.txt'
+        a = self._stage(pipeline, ITERATIVE)
+        b = pipeline.queue_snippet('a', 'python', f"open({str(target)!r}, 'w').write('x')\n"
+                                   + ITERATIVE, 'fib')
+        report = pipeline.compare(a.staging_id, b.staging_id, parse_spec(CASES), 'fib')
+        assert report['totals']['a']['passed'] == 2 and report['totals']['b']['passed'] == 0
+        assert 'outside the scratch dir' in report['cases'][0]['b']['error']
+        assert not target.exists()
+
+    def test_uses_the_snippets_spec(self, pipeline):
+        spec = parse_spec(CASES)
+        a = self._stage(pipeline, ITERATIVE, spec=spec)
+        b = self._stage(pipeline, ITERATIVE.replace('a, b = b, a + b', 'b, a = a + b, b'))
+        report = pipeline.compare(a.staging_id, b.staging_id, repeat=2)
+        assert report['repeat'] == 2 and report['totals']['same_output'] == 2
+
+    def test_rejects_mismatches(self, pipeline):
+        a = self._stage(pipeline, ITERATIVE)
+        other = self._stage(pipeline, ITERATIVE, label='other')
+        with pytest.raises(SpokedError, match="labelled 'other'") as info:
+            pipeline.compare(a.staging_id, other.staging_id, parse_spec(CASES), 'fib')
+        assert info.value.kind == ErrorKind.USAGE
+        with pytest.raises(SpokedError, match='No spec cases') as info:
+            pipeline.compare(a.staging_id, other.staging_id)
+        assert info.value.kind == ErrorKind.USAGE
+        with pytest.raises(SpokedError, match='two different') as info:
+            pipeline.compare(a.staging_id, a.staging_id, parse_spec(CASES))
+        assert info.value.kind == ErrorKind.USAGE
+        with pytest.raises(SpokedError, match="No snippet 'stg-nope'") as info:
+            pipeline.compare(a.staging_id, 'stg-nope', parse_spec(CASES))
+        assert info.value.kind == ErrorKind.NOT_FOUND
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCompareCli:
+
+    def test_sends_cases_and_prints(self, tmp_path, pipeline, monkeypatch):
+        a = pipeline.run_full_pipeline('a', 'python', ITERATIVE, 'fib', auto_promote=False)
+        b = pipeline.run_full_pipeline('a', 'python', BROKEN, 'fib', auto_promote=False)
+        cases = tmp_path / 'cases.toml'
+        cases.write_text(CASES, encoding='utf-8')
+        sent = []
+
+        def api(method, url, json=None, **kwargs):
+            sent.append((url.split('/api/')[1], json))
+            return {'success': True, 'report': pipeline.compare(
+                json['a'], json['b'], SpecSuite.from_dict(json['spec']), json['label'],
+                json['repeat'])}
+
+        monkeypatch.setattr(spokedpy, '_api', api)
+        args = spokedpy.build_parser().parse_args(
+            ['compare', '--label', 'fib', a.staging_id, b.staging_id, '--cases', str(cases)])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        (path, body), = sent
+        assert path == 'staging/compare' and len(body['spec']['cases']) == 2
+        assert 'DIFFERS' in out.getvalue() and f'promote A ({a.staging_id})' in out.getvalue()
+
+    def test_bad_cases_file(self, tmp_path):
+        cases = tmp_path / 'cases.toml'
+        cases.write_text('[[case]]\nbogus = 1\n', encoding='utf-8')
+        args = spokedpy.build_parser().parse_args(['compare', 'stg-a', 'stg-b', '--cases', str(cases)])
+        err = io.StringIO()
+        with redirect_stderr(err):
+            assert args.func(args) == 1
+        assert 'compare:' in err.getvalue()
+
+    def test_json(self, monkeypatch):
+        report = _report([0.1], [0.2]).to_dict()
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **k: {'success': True, 'report': report})
+        args = spokedpy.build_parser().parse_args(['compare', 'stg-a', 'stg-b', '--format', 'json'])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        assert json.loads(out.getvalue())['recommendation']['winner'] == 'a'
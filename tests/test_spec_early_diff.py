@@ -0,0 +1,136 @@
+"""
+Test suite for incremental stdout diffing of spec cases (StdoutCheck).
+
+Tests cover:
+  - StdoutCheck: complete lines compared on arrival, CRLF normalized, a
+    partial last line and output after the first difference ignored
+  - A case whose output differs stops at that line, long before its
+    timeout, with only the output up to it kept and its stderr; output
+    past the expected end stops it too; one that exits by itself right
+    after keeps its exit code
+  - A timed-out case keeps the output it printed and names the first
+    line it did not print
+  - Other engines (through their executor) stop the same way; streaming
+    clients see the stopped process finish without an exit code
+"""
+
+import subprocess
+import sys
+import time
+
+from visual_editor_core import output_stream
+from visual_editor_core.execution_engine import _run_subprocess
+from visual_editor_core.spec_suite import StdoutCheck, parse_spec, run_suite
+
+
+def _suite(stdout, timeout=20):
+    return parse_spec(f'[[case]]\nname = "count"\nstdout = "{stdout}"\ntimeout = {timeout}\n')
+
+
+COUNT_THEN_STALL = """\
+import time
+for line in ('1', '2', 'x', '4'):
+    print(line, flush=True)
+time.sleep(30)
+"""
+
+
+# =============================================================================
+# THE CHECK
+# =============================================================================
+
+class TestStdoutCheck:
+
+    def test_lines_on_arrival(self):
+        check = StdoutCheck('a\nb\n')
+        assert check('a\r\n') is None and check('b') is None      # partial: not yet
+        assert check('c\n') == "line 2: expected 'b', got 'c'"
+        assert check('d\n') is None and check.output == 'a\nc\n'
+        assert check.first_diff == "line 2: expected 'b', got 'c'"
+
+    def test_past_the_end(self):
+        check = StdoutCheck('a')
+        assert check('a\n') is None
+        assert check('more\n') == "line 2: expected end of output, got 'more'"
+
+
+# =============================================================================
+# PYTHON CASES
+# =============================================================================
+
+class TestPython:
+
+    def test_stops_at_the_first_differing_line(self):
+        started = time.time()
+        [result] = run_suite(_suite('1\\n2\\n3\\n4\\n'), 'python', COUNT_THEN_STALL, run=None)
+        assert time.time() - started < 10
+        assert not result.passed and result.exit_code is None
+        assert result.error.endswith('stopped there, before the timeout')
+        assert result.first_diff == "line 3: expected '3', got 'x'"
+        assert result.output == '1\n2\nx\n'
+
+    def test_keeps_stderr(self):
+        code = 'import sys\nprint("no", file=sys.stderr, flush=True)\n' + COUNT_THEN_STALL
+        [result] = run_suite(_suite('1\\n2\\n3\\n'), 'python', code, run=None)
+        assert result.stopped and result.exit_code is None
+        assert result.error.endswith('stopped there, before the timeout; no')
+
+    def test_exiting_by_itself_keeps_its_exit_code(self):
+        code = 'import sys\nprint("x")\nprint("bad", file=sys.stderr)\nsys.exit(3)'
+        [result] = run_suite(_suite('1\\n'), 'python', code, run=None)
+        assert not result.stopped and result.exit_code == 3
+        assert result.error == ("exit code 3, expected 0; bad; "
+                                "stdout differs — line 1: expected '1', got 'x'")
+
+    def test_stops_past_the_expected_end(self):
+        code = 'import itertools\nfor n in itertools.count():\n    print(n, flush=True)\n'
+        [result] = run_suite(_suite('0\\n1\\n'), 'python', code, run=None)
+        assert result.first_diff == "line 3: expected end of output, got '2'"
+
+    def test_matching_output_runs_to_the_end(self):
+        [result] = run_suite(_suite('1\\n2\\n'), 'python', 'print(1)\nprint(2)', run=None)
+        assert result.passed and result.exit_code == 0
+
+    def test_timeout_keeps_the_output(self):
+        code = 'import time\nprint("1", flush=True)\ntime.sleep(30)\nprint("2")'
+        [result] = run_suite(_suite('1\\n2\\n', timeout=1), 'python', code, run=None)
+        assert result.output == '1\n'
+        assert result.error == "timed out after 1s; stdout differs — line 2: expected '2', got ''"
+
+
+# =============================================================================
+# OTHER ENGINES
+# =============================================================================
+
+class TestExecutors:
+
+    def test_executor_run_stops(self):
+        def run(language, code):                # as an executor does: errors become results
+            try:
+                proc = _run_subprocess([sys.executable, '-u', '-c', COUNT_THEN_STALL],
+                                       capture_output=True, text=True, timeout=20)
+            except Exception as exc:
+                return {'success': False, 'output': '', 'error': str(exc)}
+            return {'success': proc.returncode == 0, 'output': proc.stdout}
+
+        started = time.time()
+        [result] = run_suite(_suite('1\\n2\\n3\\n'), 'go', 'package main', run=run)
+        assert time.time() - started < 10
+        assert result.first_diff == "line 3: expected '3', got 'x'"
+        assert result.output == '1\n2\nx\n'
+
+    def test_streaming_sees_the_stop(self):
+        events = []
+        with output_stream.streaming(events.append):
+            run_suite(_suite('1\\n2\\n3\\n'), 'python', COUNT_THEN_STALL, run=None)
+        finished = [e for e in events if e['event'] == 'process_finished']
+        assert finished[-1]['exit_code'] is None
+        assert [e['text'] for e in events if e['event'] == 'output'] == ['1\n', '2\n', 'x\n']
+        [case] = [e for e in events if e['event'] == 'case_finished']
+        assert case['first_diff'] == "line 3: expected '3', got 'x'"
+
+    def test_without_a_check_nothing_stops(self):
+        with output_stream.checking(None):
+            proc = _run_subprocess([sys.executable, '-c', 'print("x")'],
+                                   capture_output=True, text=True)
+        assert isinstance(proc, subprocess.CompletedProcess) and proc.stdout == 'x\n'
@@ -0,0 +1,155 @@
+"""
+Test suite for generated spec cases (``[case.generate]`` matrices).
+
+Tests cover:
+  - Generators: inclusive ranges with a step, value lists, seeded random
+    values (distinct, the same for the same seed)
+  - Expansion: $name substituted into name, args, stdin, stdout and env;
+    a name without a variable gets its values appended; cartesian
+    product or zip; malformed generators and oversized matrices refused
+  - spec_hash covers the expanded cases
+  - Pipeline: a Fibonacci spec across n=0..40 from one case; a wrong
+    term fails only its generated case
+"""
+
+import pytest
+
+from visual_editor_core.snippet_staging import StagingPhase
+from visual_editor_core.spec_suite import MAX_GENERATED_CASES, parse_spec
+
+
+def _fib(n):
+    a, b = 0, 1
+    for _ in range(n):
+        a, b = b, a + b
+    return a
+
+
+FIB_SPEC = f"""\
+[[case]]
+name = "fib($n)"
+args = ["$n"]
+stdout = "${{term}}\\n"
+[case.generate]
+mode = "zip"
+n = {{ range = [0, 40] }}
+term = {[str(_fib(n)) for n in range(41)]}
+""".replace("'", '"')
+
+FIB_CODE = """\
+import sys
+a, b = 0, 1
+for _ in range(int(sys.argv[1])):
+    a, b = b, a + b
+print(a)
+"""
+
+
+def _names(text):
+    return [c.name for c in parse_spec(text).cases]
+
+
+# =============================================================================
+# GENERATORS
+# =============================================================================
+
+class TestGenerators:
+
+    @pytest.mark.parametrize('spec, values', [
+        ('{ range = [0, 3] }', ['0', '1', '2', '3']),
+        ('{ range = [10, 0, -5] }', ['10', '5', '0']),
+        ('{ range = [1, 6, 2] }', ['1', '3', '5']),
+        ('["a", 2, 1.5]', ['a', '2', '1.5']),
+        ('{ values = ["x"] }', ['x']),
+    ])
+    def test_values(self, spec, values):
+        suite = parse_spec(f'[[case]]\nname = "c$v"\nargs = ["$v"]\n[case.generate]\nv = {spec}\n')
+        assert [c.args for c in suite.cases] == [[v] for v in values]
+
+    def test_random_is_seeded_and_distinct(self):
+        text = ('[[case]]\nname = "r$x"\n[case.generate]\nx = {{ random = [1, 10], count = 10 }}\n'
+                'seed = {seed}\n')
+        first = _names(text.format(seed=7))
+        assert first == _names(text.format(seed=7)) != _names(text.format(seed=8))
+        assert sorted(first) == sorted(f'r{n}' for n in range(1, 11))
+        floats = parse_spec('[[case]]\nname = "f$x"\n[case.generate]\n'
+                            'x = { random = [0.0, 1.0], count = 3 }\n').cases
+        assert all(0.0 <= float(c.name[1:]) <= 1.0 for c in floats)
+
+
+# =============================================================================
+# EXPANSION
+# =============================================================================
+
+class TestExpansion:
+
+    def test_substituted_everywhere(self):
+        [case] = parse_spec('env = { BASE = "x" }\n[[case]]\nname = "sep ${s}"\n'
+                            'args = ["--sep", "$s"]\nstdin = "a${s}b"\nstdout = "$$ $s\\n"\n'
+                            'env = { SEP = "[$s]" }\nexit_code = 3\ntimeout = 2\n'
+                            '[case.generate]\ns = [";"]\n').cases
+        assert (case.name, case.args, case.stdin, case.stdout) == (
+            'sep ;', ['--sep', ';'], 'a;b', '$ ;\n')
+        assert case.env == {'BASE': 'x', 'SEP': '[;]'}
+        assert (case.exit_code, case.timeout) == (3, 2.0)
+
+    def test_product_and_plain_names(self):
+        names = _names('[[case]]\nname = "grid"\n[case.generate]\nx = [1, 2]\ny = ["a", "b"]\n'
+                       '[[case]]\nname = "after"\n')
+        assert names == ['grid [x=1, y=a]', 'grid [x=1, y=b]', 'grid [x=2, y=a]',
+                         'grid [x=2, y=b]', 'after']
+
+    @pytest.mark.parametrize('generate, message', [
+        ('', 'at least one variable'),
+        ('seed = 1', 'at least one variable'),
+        ('n = 3', 'must be a list, or a table'),
+        ('n = []', 'non-empty list'),
+        ('n = { range = [0] }', 'range must be'),
+        ('n = { range = [0, 5, -1] }', 'never reaches 5'),
+        ('n = { range = [0, 5], step = 2 }', 'unknown key(s) step'),
+        ('n = { random = [1, 3] }', 'positive integer count'),
+        ('n = { random = [1, 3], count = 4 }', 'cannot draw 4 distinct'),
+        ('n = { random = [5, 1], count = 1 }', 'random must be [low, high]'),
+        ('n = [1, 2]\nm = [1]\nmode = "zip"', 'lists of one length (n: 2, m: 1)'),
+        ('n = [1]\nmode = "pairs"', 'mode must be'),
+        ('n = [1]\nseed = "x"', 'seed must be an integer'),
+        ('"a-b" = [1]', "'a-b' is not a name"),
+        ('n = { range = [1, 40] }\nm = { range = [1, 40] }', f'at most {MAX_GENERATED_CASES}'),
+    ])
+    def test_refused(self, generate, message):
+        text = f'[[case]]\nname = "c $n $m"\n[case.generate]\n{generate}\n'
+        with pytest.raises(ValueError, match=message.replace('(', r'\(').replace(')', r'\)')
+                           .replace('[', r'\[').replace(']', r'\]')):
+            parse_spec(text)
+
+    def test_clashing_names_and_hash(self):
+        with pytest.raises(ValueError, match='duplicate case name'):
+            parse_spec('[[case]]\nname = "c$x"\n[case.generate]\nx = [1, 2]\ny = [1, 2]\n')
+        three = parse_spec('[[case]]\nname = "c$x"\n[case.generate]\nx = { range = [1, 3] }\n')
+        listed = parse_spec('[[case]]\nname = "c$x"\n[case.generate]\nx = [1, 2, 3]\n')
+        four = parse_spec('[[case]]\nname = "c$x"\n[case.generate]\nx = { range = [1, 4] }\n')
+        assert three.spec_hash == listed.spec_hash != four.spec_hash
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipeline:
+
+    def test_fibonacci_across_forty_terms(self, pipeline):
+        suite = parse_spec(FIB_SPEC)
+        assert len(suite.cases) == 41 and suite.cases[40].stdout == '102334155\n'
+        snippet = pipeline.run_full_pipeline('a', 'python', FIB_CODE, 'Fibonacci', spec=suite)
+        assert snippet.phase == StagingPhase.PROMOTED, snippet.spec_error
+        assert [c['name'] for c in snippet.spec_cases][:3] == ['fib(0)', 'fib(1)', 'fib(2)']
+        assert all(c['passed'] for c in snippet.spec_cases)
+
+    def test_one_wrong_term_fails_its_case(self, pipeline):
+        code = FIB_CODE.replace('print(a)', 'print(a + (sys.argv[1] == "7"))')
+        snippet = pipeline.queue_snippet('a', 'python', code, 'Fibonacci', spec=parse_spec(FIB_SPEC))
+        snippet = pipeline.speculate(snippet.staging_id)
+        assert snippet.phase == StagingPhase.FAILED
+        failed = [c for c in snippet.spec_cases if not c['passed']]
+        assert [c['name'] for c in failed] == ['fib(7)']
+        assert "expected '13', got '14'" in failed[0]['first_diff']
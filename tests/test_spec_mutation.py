@@ -0,0 +1,137 @@
+"""
+Test suite for spec mutation testing (``spokedpy spec mutate``).
+
+Tests cover:
+  - Mutants only touch operators / constants outside strings and comments
+  - Unspaced < > (generics) are left alone
+  - Kill detection: failures, changed output, timeouts, nondeterminism
+  - Pipeline integration and audit trail
+  - The mutation gate's minimum kill rate
+"""
+
+import pytest
+
+from visual_editor_core.promotion_gates import GateError
+from visual_editor_core.spec_mutation import MutationGate, generate_mutants, run_mutation
+
+
+FIB = """\
+def fib(n):
+    # fib(n - 1) + fib(n - 2)
+    if n < 2:
+        return n
+    return fib(n - 1) + fib(n - 2)
+"""
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+def _speculated(pipeline, code):
+    snippet = pipeline.queue_snippet('a', 'python', code, 'fib')
+    return pipeline.speculate(snippet.staging_id)
+
+
+def fake_run(outputs):
+    """Run that answers from a {code → result} map; the original prints 'ok'."""
+    def run(language, code, timeout):
+        return outputs.get(code, {'success': True, 'output': 'ok\n'})
+    return run
+
+
+# =============================================================================
+# GENERATION
+# =============================================================================
+
+class TestGeneration:
+
+    def test_operators_and_constants_outside_comments(self):
+        mutants, total = generate_mutants(FIB, 'python')
+        assert total == len(mutants) == 7
+        assert {m.line for m in mutants} == {3, 5}          # the comment on line 2 is skipped
+        assert [m.description for m in mutants if m.line == 3] == ['line 3: < → <=',
+                                                                   'line 3: 2 → 3']
+        assert 'return fib(n - 1) - fib(n - 2)' in mutants[4].code
+
+    def test_strings_generics_and_unary_minus_untouched(self):
+        code = 'fn f(v: Vec<u8>) -> i32 { let s = "a + b"; -1 }'
+        mutants, _ = generate_mutants(code, 'rust')
+        assert [m.description for m in mutants] == ['line 1: 1 → 2']
+
+    def test_limit_spreads_over_the_snippet(self):
+        code = '\n'.join(f'x{i} = {i} + {i}' for i in range(30))
+        mutants, total = generate_mutants(code, 'python', limit=10)
+        assert total == 90 and len(mutants) == 10
+        assert mutants[-1].line > 25
+
+
+# =============================================================================
+# KILLING
+# =============================================================================
+
+class TestKilling:
+
+    def test_failures_changed_output_and_timeouts_kill(self):
+        code = 'print(1 + 2)'
+        mutants, _ = generate_mutants(code, 'python')
+        run = fake_run({
+            mutants[0].code: {'success': False, 'error': 'boom'},
+            mutants[1].code: {'success': True, 'output': 'different\n'},
+            mutants[2].code: {'success': False, 'timed_out': True},
+        })
+        report = run_mutation('stg-1', 'python', code, run)
+        assert [m.status for m in report.mutants] == ['killed', 'killed', 'timeout']
+        assert report.kill_rate == 1.0
+
+    def test_nondeterministic_output_only_counts_failures(self):
+        calls = iter(range(100))
+
+        def run(language, code, timeout):
+            return {'success': True, 'output': f'{next(calls)}\n'}
+
+        report = run_mutation('stg-1', 'python', 'print(1 + 2)', run)
+        assert not report.deterministic
+        assert report.killed == 0 and len(report.survivors) == report.run == 3
+
+    def test_failing_original_rejected(self):
+        with pytest.raises(ValueError, match='fails its own spec run'):
+            run_mutation('stg-1', 'python', 'x = 1', fake_run({'x = 1': {'success': False}}))
+
+
+# =============================================================================
+# PIPELINE + GATE
+# =============================================================================
+
+class TestPipelineAndGate:
+
+    def test_printing_spec_kills_more_than_silent_one(self, pipeline):
+        strong = _speculated(pipeline, FIB + 'print([fib(i) for i in range(8)])\n')
+        weak = _speculated(pipeline, FIB + 'fib(5)\n')
+        strong_report = pipeline.mutate(strong.staging_id)
+        weak_report = pipeline.mutate(weak.staging_id)
+        assert strong_report['kill_rate'] > weak_report['kill_rate']
+        assert strong_report['kill_rate'] == 1.0
+        # Only the mutants that recurse forever crash the silent spec.
+        assert {m['detail'].split(':')[0] for m in weak_report['mutants']
+                if m['status'] == 'killed'} == {'RecursionError'}
+
+        [event] = [e for e in pipeline.get_audit_trail(weak.staging_id)
+                   if e['event'] == 'mutation_tested']
+        assert event['data']['run'] == weak_report['run']
+
+    def test_gate_enforces_minimum_kill_rate(self):
+        code = 'print(1 + 2)'
+        mutants, _ = generate_mutants(code, 'python')
+        run = fake_run({mutants[0].code: {'success': False, 'error': 'boom'}})
+        header = {'staging_id': 'stg-1', 'language': 'python'}
+        assert MutationGate(run, min_kill_rate=0.3).check(header, code).allow
+        decision = MutationGate(run, min_kill_rate=0.5).check(header, code)
+        assert not decision.allow
+        assert decision.reasons[0].startswith('spec killed 1/3 mutants (33%')
+        assert decision.reasons[1].startswith('survived line 1:')
+
+    def test_gate_cannot_check_failing_snippet(self):
+        gate = MutationGate(fake_run({'x': {'success': False}}))
+        with pytest.raises(GateError):
+            gate.check({'language': 'python'}, 'x')
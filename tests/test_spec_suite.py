@@ -0,0 +1,295 @@
+"""
+Test suite for declarative spec suites (``*.spec.toml`` sidecars).
+
+Tests cover:
+  - Parsing: suite defaults merged into cases, malformed specs rejected
+  - spec_hash: stable across formatting, TOML and YAML agree, edits change it
+  - Pipeline: every case must pass; args / stdin / env / exit code honoured
+  - Non-Python engines: pass/fail and stdout only
+  - spec_hash in the promoted header, sidecar written next to the file
+  - Per-case results (duration, pass/fail, first diff line) on the snippet,
+    in the audit trail and as spec_case: header lines
+  - Snapshot cases: golden files, ``spec run`` review diff and --update-snapshots
+"""
+
+import io
+import os
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+
+from visual_editor_core.execution_engine import ExecutionResult
+from visual_editor_core.snippet_staging import StagingPhase, parse_file_header
+from visual_editor_core.spec_suite import (
+    SpecSuite, case_line, first_diff_line, load_spec, parse_case_line, parse_spec, sidecar_for,
+    snapshot_path,
+)
+
+
+SPEC = """\
+timeout = 10
+env = { GREETING = "hello" }
+
+[[case]]
+name = "greets"
+args = ["world"]
+stdout = "hello world\\n"
+
+[[case]]
+name = "counts stdin"
+stdin = "a\\nb\\nc\\n"
+stdout = "3\\n"
+timeout = 5
+
+[[case]]
+name = "no args"
+exit_code = 2
+"""
+
+CODE = """\
+import os, sys
+if len(sys.argv) > 1:
+    print(os.environ['GREETING'], sys.argv[1])
+elif not sys.stdin.isatty() and (lines := sys.stdin.read().splitlines()):
+    print(len(lines))
+else:
+    sys.exit(2)
+"""
+
+
+class FakeExecutor:
+    def __init__(self, success=True, output='ok\n'):
+        self.success, self.output = success, output
+
+    def execute(self, code):
+        return ExecutionResult(self.success, self.output,
+                               None if self.success else Exception('exit 1'))
+
+
+# =============================================================================
+# PARSING + HASHING
+# =============================================================================
+
+class TestParsing:
+
+    def test_defaults_merged_into_cases(self):
+        suite = parse_spec(SPEC)
+        greets, counts, no_args = suite.cases
+        assert greets.args == ['world'] and greets.env == {'GREETING': 'hello'}
+        assert greets.timeout == 10 and counts.timeout == 5
+        assert no_args.stdout is None and no_args.exit_code == 2
+
+    @pytest.mark.parametrize('text, message', [
+        ('timeout = 1\n', 'at least one'),
+        ('[[case]]\nname = "a"\n[[case]]\nname = "a"\n', 'duplicate case name'),
+        ('[[case]]\nargv = ["x"]\n', 'unknown key(s) argv'),
+        ('[[case]]\nargs = "x"\n', 'args must be a list'),
+        ('[[case]]\nexit_code = "0"\n', 'exit_code must be an integer'),
+        ('[[case]]\ntimeout = 0\n', 'timeout must be a positive'),
+        ('[[case\n', 'invalid spec TOML'),
+    ])
+    def test_malformed_specs_rejected(self, text, message):
+        with pytest.raises(ValueError, match=message.replace('(', r'\(').replace(')', r'\)')):
+            parse_spec(text)
+
+    def test_hash_ignores_formatting_but_not_cases(self):
+        base = parse_spec(SPEC).spec_hash
+        assert parse_spec('# reformatted\n' + SPEC.replace('timeout = 10', 'timeout=10.0')).spec_hash == base
+        assert parse_spec(SPEC.replace('"3\\n"', '"4\\n"')).spec_hash != base
+
+    def test_yaml_matches_toml(self):
+        pytest.importorskip('yaml')
+        text = ('timeout: 10\nenv: {GREETING: hello}\ncase:\n'
+                '  - {name: greets, args: [world], stdout: "hello world\\n"}\n'
+                '  - {name: counts stdin, stdin: "a\\nb\\nc\\n", stdout: "3\\n", timeout: 5}\n'
+                '  - {name: no args, exit_code: 2}\n')
+        assert parse_spec(text, 'yaml').spec_hash == parse_spec(SPEC).spec_hash
+
+    def test_first_diff_line(self):
+        assert first_diff_line('a\nb\n', 'a\nc\n') == "line 2: expected 'b', got 'c'"
+        assert first_diff_line('a\n', 'a\nextra\n') == "line 2: expected '', got 'extra'"
+        assert first_diff_line('a\nb', 'a') == "line 2: expected 'b', got end of output"
+        assert first_diff_line('same', 'same') == ''
+
+    def test_case_line_round_trip(self):
+        case = {'name': 'counts stdin', 'passed': False, 'duration': 0.25,
+                'first_diff': "timed out — line 1: expected '4', got '3'"}
+        assert case_line(case) == "FAIL  0.2500s  counts stdin — timed out — line 1: expected '4', got '3'"
+        assert parse_case_line(case_line(case)) == case
+        assert parse_case_line('garbage') is None
+
+    def test_sidecar_lookup(self, tmp_path):
+        snippet = tmp_path / 'etl.py'
+        assert sidecar_for(str(snippet)) is None
+        (tmp_path / 'etl.spec.toml').write_text(SPEC)
+        assert sidecar_for(str(snippet)) == str(tmp_path / 'etl.spec.toml')
+        assert len(load_spec(sidecar_for(str(snippet))).cases) == 3
+
+
+# =============================================================================
+# PIPELINE
+# =============================================================================
+
+class TestPipeline:
+
+    def test_all_cases_pass_and_hash_lands_in_header(self, pipeline):
+        suite = parse_spec(SPEC)
+        snippet = pipeline.run_full_pipeline('a', 'python', CODE, 'greeter', spec=suite)
+        assert snippet.phase == StagingPhase.PROMOTED, snippet.spec_error
+        assert '── counts stdin: PASS ──\n3\n' in snippet.spec_output
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        assert header.fields['spec_hash'] == suite.spec_hash
+        sidecar = sidecar_for(snippet.saved_file_path)
+        assert load_spec(sidecar).spec_hash == suite.spec_hash
+
+        [queued] = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                    if e['event'] == 'snippet_queued']
+        assert queued['data']['spec_hash'] == suite.spec_hash
+        assert queued['data']['spec_cases'] == 3
+
+    def test_failing_case_fails_snippet(self, pipeline):
+        suite = parse_spec(SPEC.replace('exit_code = 2', 'exit_code = 0'))
+        snippet = pipeline.queue_snippet('a', 'python', CODE, 'greeter', spec=suite)
+        snippet = pipeline.speculate(snippet.staging_id)
+        assert snippet.phase == StagingPhase.FAILED
+        assert snippet.spec_error.startswith("1/3 spec case(s) failed: no args: exit code 2")
+
+    def test_wrong_stdout_and_timeout(self, pipeline):
+        suite = parse_spec('[[case]]\nname = "slow"\ntimeout = 0.5\n'
+                           '[[case]]\nname = "wrong"\nstdout = "2\\n"\n')
+        snippet = pipeline.speculate(pipeline.queue_snippet('a', 'python', 'print(1)',
+                                                            spec=suite).staging_id)
+        assert "wrong: stdout differs — line 1: expected '2', got '1'" in snippet.spec_error
+        slow = pipeline.speculate(pipeline.queue_snippet(
+            'a', 'python', 'import time\ntime.sleep(5)', spec=suite).staging_id)
+        assert 'slow: timed out after 0.5s' in slow.spec_error
+
+    def test_other_engines_check_pass_fail_and_stdout(self, make_pipeline):
+        pipeline = make_pipeline(executors={'javascript': FakeExecutor(output='ok\n')})
+        ok = parse_spec('[[case]]\nname = "prints ok"\nstdout = "ok\\n"\n')
+        snippet = pipeline.speculate(pipeline.queue_snippet(
+            'b', 'javascript', 'console.log("ok")', spec=ok).staging_id)
+        assert snippet.phase == StagingPhase.PASSED
+
+        feeds = parse_spec('[[case]]\nname = "with stdin"\nstdin = "x"\n')
+        snippet = pipeline.speculate(pipeline.queue_snippet(
+            'b', 'javascript', 'console.log("ok")', spec=feeds).staging_id)
+        assert 'only Python cases may use them' in snippet.spec_error
+
+    def test_per_case_results(self, pipeline):
+        suite = parse_spec(SPEC.replace('"3\\n"', '"4\\n"'))
+        snippet = pipeline.speculate(pipeline.queue_snippet('a', 'python', CODE, 'greeter',
+                                                            spec=suite).staging_id)
+        greets, counts, no_args = snippet.spec_cases
+        assert greets['passed'] and no_args['passed'] and greets['first_diff'] == ''
+        assert not counts['passed'] and counts['output'] == '3\n'
+        assert counts['first_diff'] == "line 1: expected '4', got '3'"
+        assert all(c['duration'] > 0 for c in snippet.spec_cases)
+
+        [failed] = [e for e in pipeline.get_audit_trail(snippet.staging_id)
+                    if e['event'] == 'spec_exec_failed']
+        assert [c['name'] for c in failed['data']['cases']] == ['greets', 'counts stdin', 'no args']
+        assert 'output' not in failed['data']['cases'][0]
+
+    def test_case_lines_in_header(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', CODE, 'greeter', spec=parse_spec(SPEC))
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        assert [c['name'] for c in header.spec_cases] == ['greets', 'counts stdin', 'no args']
+        assert all(c['passed'] for c in header.spec_cases)
+        assert header.fields['spec_result'] == 'PASS'
+
+    def test_no_suite_is_a_single_run(self, pipeline):
+        snippet = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'plain')
+        assert snippet.spec_hash == '' and snippet.spec_output == '1\n'
+        with open(snippet.saved_file_path, encoding='utf-8') as f:
+            assert 'spec_hash' not in parse_file_header(f.read()).fields
+        assert sidecar_for(snippet.saved_file_path) is None
+
+
+# =============================================================================
+# SNAPSHOTS
+# =============================================================================
+
+SNAPSHOT_SPEC = """\
+[[case]]
+name = "report"
+snapshot = true
+
+[[case]]
+name = "exit"
+stdout = "total 3\\n"
+"""
+
+
+def _spec_run(pipeline, monkeypatch, path, *flags):
+    """spokedpy spec run against the pipeline instead of a server."""
+    def api(method, url, json=None, **kwargs):
+        assert url.endswith('/api/staging/spec-run')
+        spec = SpecSuite.from_dict(json['spec'])
+        return {'cases': pipeline.run_spec(json['language'], spec, json['code'])}
+
+    monkeypatch.setattr(spokedpy, '_api', api)
+    args = spokedpy.build_parser().parse_args(['spec', 'run', str(path), *flags])
+    out = io.StringIO()
+    with redirect_stdout(out):
+        status = args.func(args)
+    return status, out.getvalue()
+
+
+class TestSnapshots:
+
+    @pytest.fixture
+    def snippet_file(self, tmp_path):
+        (tmp_path / 'report.py').write_text("print('total', 3)\n")
+        (tmp_path / 'report.spec.toml').write_text(SNAPSHOT_SPEC)
+        return tmp_path / 'report.py'
+
+    def test_stdout_and_snapshot_exclusive(self):
+        with pytest.raises(ValueError, match='stdout or snapshot'):
+            parse_spec('[[case]]\nsnapshot = true\nstdout = "x"\n')
+
+    def test_golden_file_feeds_stdout_and_hash(self, snippet_file):
+        sidecar = sidecar_for(str(snippet_file))
+        before = load_spec(sidecar)
+        assert before.cases[0].stdout is None
+        golden = snapshot_path(sidecar, 'report')
+        assert golden == os.path.join(os.path.dirname(sidecar), 'snapshots', 'report__report.snap')
+        os.makedirs(os.path.dirname(golden))
+        with open(golden, 'w') as f:
+            f.write('total 3\n')
+        after = load_spec(sidecar)
+        assert after.cases[0].stdout == 'total 3\n'
+        assert after.spec_hash != before.spec_hash
+
+    def test_new_snapshot_then_update_then_change(self, pipeline, monkeypatch, snippet_file):
+        status, out = _spec_run(pipeline, monkeypatch, snippet_file)
+        assert status == 1 and 'no snapshot yet' in out and '+total 3' in out
+
+        status, out = _spec_run(pipeline, monkeypatch, snippet_file, '--update-snapshots')
+        assert status == 0 and 'updated 1 snapshot(s)' in out
+        golden = snapshot_path(sidecar_for(str(snippet_file)), 'report')
+        with open(golden) as f:
+            assert f.read() == 'total 3\n'
+        assert _spec_run(pipeline, monkeypatch, snippet_file)[0] == 0
+
+        snippet_file.write_text("print('total', 4)\n")
+        status, out = _spec_run(pipeline, monkeypatch, snippet_file)
+        assert status == 1 and 'snapshot changed' in out
+        assert '-total 3' in out and '+total 4' in out
+        assert "line 1: expected 'total 3', got 'total 4'" in out
+
+    def test_promoted_sidecar_keeps_golden_files(self, pipeline, snippet_file):
+        sidecar = sidecar_for(str(snippet_file))
+        os.makedirs(os.path.dirname(snapshot_path(sidecar, 'report')))
+        with open(snapshot_path(sidecar, 'report'), 'w') as f:
+            f.write('total 3\n')
+        suite = load_spec(sidecar)
+        snippet = pipeline.run_full_pipeline('a', 'python', "print('total', 3)\n", 'report',
+                                             spec=suite)
+        assert snippet.saved_file_path
+        promoted = load_spec(sidecar_for(snippet.saved_file_path))
+        assert promoted.spec_hash == suite.spec_hash
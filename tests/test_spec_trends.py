@@ -0,0 +1,149 @@
+"""
+Test suite for spec_time trends (spokedpy trends) and spec_anomaly alerts.
+
+Tests cover:
+  - Rolling-median outlier detection (threshold, window, minimum samples)
+  - The index stores one measurement per run, grouped by (label, slot, engine)
+  - The pipeline emits spec_anomaly for an outlier run, and only then
+  - spokedpy trends prints the series and marks anomalies; exit 1 when empty
+"""
+
+import io
+import json
+from contextlib import redirect_stderr, redirect_stdout
+from types import SimpleNamespace
+
+from visual_editor_core.notifiers import DEFAULT_TEMPLATES, NOTIFY_EVENTS
+from visual_editor_core.spec_trends import (
+    TrendIndex, detect_anomaly, format_report, sparkline, trend_report,
+)
+
+import spokedpy
+
+
+BASELINE = [1.0, 1.1, 0.9, 1.0, 1.05, 0.95]
+
+
+def _snippet(spec_time, success=True, label='etl', slot='d3', staging_id='stg-1'):
+    return SimpleNamespace(staging_id=staging_id, label=label, reserved_address=slot,
+                           reserved_engine='RUST', language='rust',
+                           spec_execution_time=spec_time, spec_success=success)
+
+
+# =============================================================================
+# DETECTION
+# =============================================================================
+
+class TestDetectAnomaly:
+
+    def test_outlier(self):
+        anomaly = detect_anomaly(BASELINE, 5.0, sigmas=3)
+        assert anomaly is not None and anomaly.median == 1.0
+        assert anomaly.deviation > 3 and '5.0000s' in anomaly.describe()
+
+    def test_within_threshold(self):
+        assert detect_anomaly(BASELINE, 1.08, sigmas=3) is None
+        assert detect_anomaly(BASELINE, 0.1, sigmas=3) is None          # faster is fine
+
+    def test_needs_enough_samples(self):
+        assert detect_anomaly(BASELINE[:3], 50.0, min_samples=5) is None
+
+    def test_window_uses_recent_runs(self):
+        history = [10.0] * 5 + BASELINE
+        assert detect_anomaly(history, 5.0, window=6) is not None
+        assert detect_anomaly(history, 5.0, window=0) is None
+
+    def test_flat_history_never_alerts(self):
+        assert detect_anomaly([1.0] * 10, 9.0) is None
+
+
+# =============================================================================
+# INDEX + PIPELINE
+# =============================================================================
+
+class TestTrendIndex:
+
+    def test_records_and_groups(self, tmp_path):
+        index = TrendIndex(str(tmp_path / 'trends.jsonl'))
+        for t in BASELINE:
+            assert index.record(_snippet(t)) is None
+        index.record(_snippet(0.5, label='other'))
+        index.record(_snippet(0.5, slot='a1'))
+        series = index.series('d3')
+        assert set(series) == {('etl', 'd3', 'RUST'), ('other', 'd3', 'RUST')}
+        assert [m.spec_time for m in series[('etl', 'd3', 'RUST')]] == BASELINE
+        assert index.series('d3', label='other', engine='rust')
+
+    def test_outlier_and_failed_runs(self, tmp_path):
+        index = TrendIndex(str(tmp_path / 'trends.jsonl'), sigmas=3)
+        for t in BASELINE:
+            index.record(_snippet(t))
+        assert index.record(_snippet(9.0, success=False)) is None      # failures never alert
+        assert index.record(_snippet(9.0)) is not None
+        report = trend_report(index.series('d3')[('etl', 'd3', 'RUST')])
+        assert report['count'] == 8 and report['anomalies'] == 1
+        assert '⚠' in format_report(report) and '✘' in format_report(report)
+
+    def _speculated(self, tmp_path, make_pipeline, history):
+        index = TrendIndex(str(tmp_path / 'trends.jsonl'), sigmas=3)
+        pipeline = make_pipeline(trends=index)
+        events = []
+        pipeline.add_listener(lambda event, snippet, details: events.append((event, details)))
+        snippet = pipeline.queue_snippet('a', 'python', 'import time\ntime.sleep(0.05)', 'nap')
+        for t in history:
+            index.record(SimpleNamespace(
+                staging_id='stg-old', label='nap', reserved_address=snippet.reserved_address,
+                reserved_engine=snippet.reserved_engine, language='python',
+                spec_execution_time=t, spec_success=True))
+        pipeline.speculate(snippet.staging_id)
+        key = ('nap', snippet.reserved_address, snippet.reserved_engine)
+        return pipeline, snippet, [d for e, d in events if e == 'spec_anomaly'], index.series()[key]
+
+    def test_pipeline_emits_spec_anomaly(self, tmp_path, make_pipeline):
+        pipeline, snippet, anomalies, runs = self._speculated(
+            tmp_path, make_pipeline, [0.001, 0.0011, 0.0009, 0.001, 0.00105])
+        assert len(runs) == 6 and runs[-1].staging_id == snippet.staging_id
+        assert len(anomalies) == 1 and 'rolling median' in anomalies[0]['reason']
+        trail = [e['event'] for e in pipeline.get_audit_trail(snippet.staging_id)]
+        assert 'spec_anomaly' in trail
+
+    def test_pipeline_quiet_for_usual_run(self, tmp_path, make_pipeline):
+        _, _, anomalies, runs = self._speculated(
+            tmp_path, make_pipeline, [10.0, 11.0, 9.0, 10.5, 9.5])
+        assert len(runs) == 6 and anomalies == []
+
+    def test_notification_event(self):
+        assert 'spec_anomaly' in NOTIFY_EVENTS and '$reason' in DEFAULT_TEMPLATES['spec_anomaly']
+
+    def test_sparkline(self):
+        assert sparkline([1, 2, 3]) == '▁▅█' and sparkline([]) == ''
+
+
+# =============================================================================
+# spokedpy trends
+# =============================================================================
+
+class TestTrendsCommand:
+
+    def _run(self, monkeypatch, tmp_path, *argv):
+        monkeypatch.setenv('SPOKEDPY_TRENDS_LOG', str(tmp_path / 'trends.jsonl'))
+        args = spokedpy.build_parser().parse_args(['trends', *argv])
+        out, err = io.StringIO(), io.StringIO()
+        with redirect_stdout(out), redirect_stderr(err):
+            code = args.func(args)
+        return code, out.getvalue(), err.getvalue()
+
+    def test_text_and_json(self, tmp_path, monkeypatch):
+        index = TrendIndex(str(tmp_path / 'trends.jsonl'))
+        for i, t in enumerate(BASELINE + [9.0]):
+            index.record(_snippet(t, staging_id=f'stg-{i}'))
+        code, out, _ = self._run(monkeypatch, tmp_path, 'd3')
+        assert code == 0 and 'etl @ d3 (rust) — 7 run(s), 1 anomaly' in out
+        assert '⚠' in out and 'stg-6' in out
+        code, out, _ = self._run(monkeypatch, tmp_path, 'd3', '--format', 'json', '--limit', '2')
+        report, = json.loads(out)
+        assert report['count'] == 7 and len(report['runs']) == 2 and report['runs'][-1]['anomaly']
+
+    def test_empty_slot(self, tmp_path, monkeypatch):
+        code, _, err = self._run(monkeypatch, tmp_path, 'z9')
+        assert code == 1 and 'no spec_time measurements' in err
@@ -0,0 +1,158 @@
+"""
+Test suite for the staging_id modes ([store] id_mode).
+
+Tests cover:
+  - random (the default) ids are unrelated to the content
+  - deterministic ids are derived from code_hash, label and slot: two
+    fresh stores staging the same content hand out the same ids, in order
+  - re-staging the same content into the same slot moves the counter on,
+    also in a reopened store (read from the audit log)
+  - an unknown id_mode is a usage error
+  - ULID ids sort in staging order (also within one millisecond and when
+    the clock steps back) and carry their time; the LSP accepts them
+  - a drawn id that is already in use is drawn again and counted; a source
+    that only collides is an error
+"""
+
+import time
+import uuid
+from types import SimpleNamespace
+
+import pytest
+
+from visual_editor_core import snippet_staging
+
+from visual_editor_core.errors import ErrorKind, SpokedError
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import (
+    IdMode,
+    UlidGenerator,
+    deterministic_id,
+    ulid_time,
+)
+
+CODE = "console.log('hi')\n"
+
+
+def _stage_twice(pipeline):
+    """Stage, reject and re-stage the same snippet — it lands in a1 both times."""
+    first = pipeline.queue_snippet('b', 'javascript', CODE, 'greet')
+    pipeline.verdict(first.staging_id, 'reject', 'again')
+    second = pipeline.queue_snippet('b', 'javascript', CODE, 'greet')
+    return first, second
+
+
+# =============================================================================
+# MODES
+# =============================================================================
+
+class TestIdModes:
+
+    def test_random_by_default(self, pipeline):
+        assert pipeline.id_mode == IdMode.RANDOM
+        a = pipeline.queue_snippet('b', 'javascript', CODE, 'greet')
+        assert a.staging_id.startswith('stg-') and len(a.staging_id) == 16
+        assert a.staging_id != deterministic_id(a.code_hash, 'greet', a.reserved_address)
+
+    def test_unknown_mode_is_a_usage_error(self, make_pipeline):
+        with pytest.raises(SpokedError) as err:
+            make_pipeline(id_mode='sequential')
+        assert err.value.kind == ErrorKind.USAGE
+        assert 'deterministic' in str(err.value)
+
+    def test_setting_accepts_the_string(self, make_pipeline):
+        assert make_pipeline(id_mode='deterministic').id_mode == IdMode.DETERMINISTIC
+
+
+# =============================================================================
+# DETERMINISTIC IDS
+# =============================================================================
+
+class TestDeterministicIds:
+
+    def test_derived_from_content_label_and_slot(self, make_pipeline):
+        pipeline = make_pipeline(id_mode='deterministic')
+        snippet = pipeline.queue_snippet('b', 'javascript', CODE, 'greet')
+        assert snippet.staging_id == deterministic_id(snippet.code_hash, 'greet', 'b1')
+        assert deterministic_id(snippet.code_hash, 'other', 'b1') != snippet.staging_id
+        assert deterministic_id(snippet.code_hash, 'greet', 'b2') != snippet.staging_id
+
+    def test_same_ids_across_stores(self, tmp_path, make_pipeline):
+        here = _stage_twice(make_pipeline(root=tmp_path / 'here', id_mode='deterministic'))
+        there = _stage_twice(make_pipeline(root=tmp_path / 'there', id_mode='deterministic'))
+        assert [s.staging_id for s in here] == [s.staging_id for s in there]
+        assert here[0].staging_id != here[1].staging_id
+        assert here[1].staging_id == deterministic_id(here[1].code_hash, 'greet', 'b1', 1)
+
+    def test_reopened_store_continues_the_counter(self, make_pipeline):
+        first, second = _stage_twice(make_pipeline(id_mode='deterministic'))
+        reopened = make_pipeline(id_mode='deterministic')
+        third = reopened.queue_snippet('b', 'javascript', CODE, 'greet')
+        assert third.reserved_address == 'b1'
+        assert third.staging_id not in (first.staging_id, second.staging_id)
+        assert third.staging_id == deterministic_id(third.code_hash, 'greet', 'b1', 2)
+
+
+# =============================================================================
+# ULIDS
+# =============================================================================
+
+class TestUlids:
+
+    def test_sorted_by_id_is_staging_order(self, make_pipeline):
+        pipeline = make_pipeline(id_mode='ulid')
+        snippets = [pipeline.queue_snippet('b', 'javascript', f"// {n}\n", f"s{n}")
+                    for n in range(12)]
+        assert all(len(s.staging_id) == 30 for s in snippets)
+        assert sorted(snippets, key=lambda s: s.staging_id) == sorted(snippets,
+                                                                      key=lambda s: s.seq)
+        assert len({s.staging_id for s in snippets}) == 12
+
+    def test_monotonic_within_a_millisecond_and_backwards(self):
+        ulids = UlidGenerator()
+        same = [ulids.next(1_770_724_634.25) for _ in range(5)]
+        earlier = ulids.next(1_770_724_630.0)
+        assert same == sorted(same) and len(set(same)) == 5
+        assert earlier > same[-1]
+
+    def test_carries_its_time(self):
+        ulid = UlidGenerator().next(1_770_724_634.25)
+        assert ulid_time(f"stg-{ulid}") == 1_770_724_634.25
+        assert abs(ulid_time(f"stg-{UlidGenerator().next()}") - time.time()) < 5
+        assert ulid_time('stg-3f2a9c01b2d4') is None
+
+    def test_promoted_file_is_clean_for_the_lsp(self, tmp_path, make_pipeline):
+        snippet = make_pipeline(id_mode='ulid').run_full_pipeline('a', 'python',
+                                                                         'print(1)', 'etl')
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+            assert workspace.diagnose(snippet.saved_file_path, f.read()) == []
+
+
+# =============================================================================
+# COLLISIONS
+# =============================================================================
+
+class TestCollisions:
+
+    def test_redrawn_and_counted(self, make_pipeline, monkeypatch):
+        taken = uuid.UUID('1' + '0' * 31)
+        draws = iter([taken, taken, uuid.UUID('2' + '0' * 31)])
+        monkeypatch.setattr(snippet_staging, 'uuid', SimpleNamespace(uuid4=lambda: next(draws)))
+        pipeline = make_pipeline(id_mode='random')
+        first = pipeline.queue_snippet('b', 'javascript', CODE, 'a')
+        second = pipeline.queue_snippet('b', 'javascript', CODE, 'b')
+        assert first.staging_id != second.staging_id
+        assert pipeline.id_collisions == 1
+        assert pipeline.get_pipeline_summary()['id_collisions'] == 1
+
+    def test_a_source_that_only_collides_is_an_error(self, make_pipeline, monkeypatch):
+        monkeypatch.setattr(snippet_staging, 'uuid',
+                            SimpleNamespace(uuid4=lambda: uuid.UUID('0' * 32)))
+        pipeline = make_pipeline(id_mode='random')
+        pipeline.queue_snippet('b', 'javascript', CODE, 'a')
+        with pytest.raises(SpokedError) as err:
+            pipeline.queue_snippet('b', 'javascript', CODE, 'b')
+        assert err.value.kind == ErrorKind.INVALID_STATE
+        assert pipeline.id_collisions == 8
+        assert pipeline.get_reserved_positions() == {'JAVASCRIPT': [1]}
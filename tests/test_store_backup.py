@@ -0,0 +1,235 @@
+"""
+Test suite for store backups (store_backup, spokedpy backup).
+
+Tests cover:
+  - A backup holds every file of the store parts that exist; a second one
+    writes only the chunks that changed, and files span several chunks
+  - verify passes on an intact dir and names the snapshot of a missing or
+    corrupt chunk, a tampered manifest, a dir that is no backup
+  - --encrypt age:… stores ciphertext (an age stand-in on PATH); verify
+    checks stored bytes without an identity and contents with one; a dir
+    keeps the encryption of its first backup; bad --encrypt specs refused
+  - The CLI backs up the configured store paths and exits 1 on damage
+"""
+
+import io
+import json
+import os
+import stat
+import sys
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core import store_backup
+from visual_editor_core.errors import ErrorKind, SpokedError
+
+# Stands in for age: "encrypts" to the recipient, which is also the identity
+FAKE_AGE = f"""#!{sys.executable}
+import sys
+args, data = sys.argv[1:], sys.stdin.buffer.read()
+if '-d' in args:
+    key = open(args[args.index('-i') + 1]).read().strip().encode()
+    head, _, body = data.partition(b'\\n')
+    if head != b'age:' + key:
+        sys.exit('age: no identity matched any of the recipients')
+    sys.stdout.buffer.write(bytes(b ^ 0x5a for b in body))
+else:
+    key = args[args.index('-r') + 1].encode()
+    sys.stdout.buffer.write(b'age:' + key + b'\\n' + bytes(b ^ 0x5a for b in data))
+"""
+
+
+@pytest.fixture
+def store(tmp_path):
+    """A small store: two promoted files, an audit log, no trash."""
+    snippets = tmp_path / 'store' / 'snippets' / 'python' / 'd2'
+    snippets.mkdir(parents=True)
+    (snippets / 'a1_stg-1.py').write_text('print(1)\n', encoding='utf-8')
+    (snippets / 'a2_stg-2.py').write_text('print(2)\n', encoding='utf-8')
+    audit = tmp_path / 'store' / 'audit.jsonl'
+    audit.write_text('{"event": "promoted"}\n', encoding='utf-8')
+    return {'snippets_dir': str(tmp_path / 'store' / 'snippets'), 'audit_log': str(audit),
+            'trash_dir': str(tmp_path / 'store' / 'trash')}
+
+
+@pytest.fixture
+def age(tmp_path, monkeypatch):
+    """Put the age stand-in on PATH; returns an identity file for recipient 'age1test'."""
+    bin_dir = tmp_path / 'bin'
+    bin_dir.mkdir()
+    (bin_dir / 'age').write_text(FAKE_AGE, encoding='utf-8')
+    os.chmod(bin_dir / 'age', stat.S_IRWXU)
+    monkeypatch.setenv('PATH', f"{bin_dir}{os.pathsep}{os.environ.get('PATH', '')}")
+    identity = tmp_path / 'key.txt'
+    identity.write_text('age1test\n', encoding='utf-8')
+    return str(identity)
+
+
+def _objects(dest):
+    return sorted(name for _root, _dirs, names in os.walk(os.path.join(dest, 'objects'))
+                  for name in names)
+
+
+# =============================================================================
+# BACKUP
+# =============================================================================
+
+class TestBackup:
+
+    def test_first_backup(self, store, tmp_path):
+        dest = str(tmp_path / 'bk')
+        report = store_backup.backup(store, dest)
+        assert (report.files, report.chunks, report.new_chunks) == (3, 3, 3)
+        assert report.bytes == 18 + len('{"event": "promoted"}\n')
+        [seal] = store_backup._Repo(dest).seals()
+        assert seal['snapshot'] == report.snapshot and len(seal['objects']) == 3
+        with open(os.path.join(dest, 'snapshots', f"{report.snapshot}.json"), encoding='utf-8') as f:
+            manifest = json.load(f)
+        assert sorted((e['part'], e['path']) for e in manifest['files']) == [
+            ('audit_log', ''), ('snippets_dir', 'python/d2/a1_stg-1.py'),
+            ('snippets_dir', 'python/d2/a2_stg-2.py')]
+
+    def test_incremental(self, store, tmp_path):
+        dest = str(tmp_path / 'bk')
+        first = store_backup.backup(store, dest)
+        with open(store['audit_log'], 'a', encoding='utf-8') as f:
+            f.write('{"event": "rolled_back"}\n')
+        second = store_backup.backup(store, dest)
+        assert second.snapshot != first.snapshot
+        assert (second.files, second.new_chunks) == (3, 1)
+        assert len(_objects(dest)) == 4
+        assert store_backup.backup(store, dest).new_chunks == 0
+
+    def test_large_files_are_chunked(self, store, tmp_path, monkeypatch):
+        monkeypatch.setattr(store_backup, 'CHUNK_SIZE', 8)
+        dest = str(tmp_path / 'bk')
+        with open(store['audit_log'], 'w', encoding='utf-8') as f:
+            f.write('0123456789abcdef' * 4)
+        report = store_backup.backup({'audit_log': store['audit_log']}, dest)
+        assert report.chunks == 8 and report.new_chunks == 2        # two distinct chunks
+        with open(store['audit_log'], 'a', encoding='utf-8') as f:
+            f.write('tail')
+        assert store_backup.backup({'audit_log': store['audit_log']}, dest).new_chunks == 1
+
+
+# =============================================================================
+# VERIFY
+# =============================================================================
+
+class TestVerify:
+
+    def test_intact(self, store, tmp_path):
+        dest = str(tmp_path / 'bk')
+        store_backup.backup(store, dest)
+        store_backup.backup(store, dest)
+        report = store_backup.verify(dest)
+        assert report.ok and report.contents_checked
+        assert len(report.snapshots) == 2 and report.objects == 3
+        assert '✔ 2 snapshot(s), 3 chunk(s) intact (contents checked)' in \
+            store_backup.format_verify(report)
+
+    def test_missing_and_corrupt_chunks(self, store, tmp_path):
+        dest = str(tmp_path / 'bk')
+        snapshot = store_backup.backup(store, dest).snapshot
+        repo = store_backup._Repo(dest)
+        missing, corrupt, _ = sorted(repo.index)
+        os.remove(repo.object_path(missing))
+        with open(repo.object_path(corrupt), 'ab') as f:
+            f.write(b'!')
+        report = store_backup.verify(dest)
+        assert not report.ok
+        assert report.problems == [
+            (snapshot, f"chunk {missing[:12]} is missing"),
+            (snapshot, f"chunk {corrupt[:12]} is corrupt (stored sha256 differs)")]
+        assert store_backup.format_verify(report).startswith('  ✘ 1 of 1 snapshot(s) damaged')
+
+    def test_tampered_manifest(self, store, tmp_path):
+        dest = str(tmp_path / 'bk')
+        snapshot = store_backup.backup(store, dest).snapshot
+        with open(store_backup._Repo(dest).manifest_path(snapshot), 'a', encoding='utf-8') as f:
+            f.write(' ')
+        assert store_backup.verify(dest).problems == \
+            [(snapshot, 'manifest is corrupt (sha256 differs from the seal)')]
+
+    def test_not_a_backup(self, tmp_path, store):
+        with pytest.raises(SpokedError, match='is not a spokedpy backup') as exc:
+            store_backup.verify(str(tmp_path))
+        assert exc.value.kind == ErrorKind.NOT_FOUND
+        store_backup.backup(store, str(tmp_path / 'bk'))
+        with pytest.raises(SpokedError, match="no snapshot 'nope'"):
+            store_backup.verify(str(tmp_path / 'bk'), snapshot='nope')
+
+
+# =============================================================================
+# ENCRYPTION
+# =============================================================================
+
+class TestEncryption:
+
+    def test_encrypted_backup(self, store, tmp_path, age):
+        dest = str(tmp_path / 'bk')
+        report = store_backup.backup(store, dest, 'age:age1test')
+        assert report.encrypt == 'age:age1test'
+        assert all(name.endswith('.age') for name in _objects(dest))
+        for root, _dirs, names in os.walk(dest):
+            for name in names:
+                with open(os.path.join(root, name), 'rb') as f:
+                    assert b'print(1)' not in f.read()
+
+        unkeyed = store_backup.verify(dest)
+        assert unkeyed.ok and not unkeyed.contents_checked
+        assert 'pass --identity' in store_backup.format_verify(unkeyed)
+        keyed = store_backup.verify(dest, identity=age)
+        assert keyed.ok and keyed.contents_checked and keyed.objects == 3
+
+    def test_wrong_identity(self, store, tmp_path, age):
+        dest = str(tmp_path / 'bk')
+        store_backup.backup(store, dest, 'age:age1test')
+        other = tmp_path / 'other.txt'
+        other.write_text('age1other\n', encoding='utf-8')
+        report = store_backup.verify(dest, identity=str(other))
+        assert not report.ok and 'no identity matched' in report.problems[0][1]
+
+    def test_dir_keeps_its_encryption(self, store, tmp_path, age):
+        dest = str(tmp_path / 'bk')
+        store_backup.backup(store, dest, 'age:age1test')
+        with pytest.raises(SpokedError, match='holds backups with age:age1test') as exc:
+            store_backup.backup(store, dest)
+        assert exc.value.kind == ErrorKind.USAGE
+        with pytest.raises(SpokedError, match='holds backups with age:age1test'):
+            store_backup.backup(store, dest, 'age:age1else')
+
+    @pytest.mark.parametrize('spec', ['gpg:ABCD', 'age:', 'age1test'])
+    def test_bad_spec(self, spec):
+        with pytest.raises(SpokedError, match='use age:<recipient>'):
+            store_backup.parse_encrypt(spec)
+
+    def test_age_missing(self, store, tmp_path, monkeypatch):
+        monkeypatch.setenv('PATH', str(tmp_path / 'empty'))
+        with pytest.raises(ValueError, match='age is not installed'):
+            store_backup.backup(store, str(tmp_path / 'bk'), 'age:age1test')
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def test_backup_and_verify(self, store, tmp_path, monkeypatch):
+        for key in store_backup.STORE_PARTS:
+            env, _default, _ = spokedpy._PATH_SETTINGS[key]
+            monkeypatch.setenv(env, store.get(key, str(tmp_path / 'absent' / key)))
+        dest = str(tmp_path / 'bk')
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert spokedpy.main(['backup', '--to', dest, '--format', 'json']) == 0
+        assert json.loads(out.getvalue())['files'] == 3
+        with redirect_stdout(io.StringIO()):
+            assert spokedpy.main(['backup', 'verify', dest]) == 0
+            os.remove(store_backup._Repo(dest).object_path(
+                sorted(store_backup._Repo(dest).index)[0]))
+            assert spokedpy.main(['backup', 'verify', dest]) == 1
+            assert spokedpy.main(['backup']) == ErrorKind.USAGE.exit_code
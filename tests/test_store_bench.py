@@ -0,0 +1,109 @@
+"""
+Test suite for the sharded store layout, audit-log group commit and bench-store.
+
+Tests cover:
+  - Promoted files land in <lang>/<code_hash[:shard_width]>/; shard_width 0
+    keeps the flat layout; widths outside 0-8 are refused
+  - SlotResolver finds promoted files in shard dirs and in a flat store
+  - Concurrent AuditLogger.log calls get unique seq numbers and share writes
+  - bench_store runs clean on a small load; spokedpy bench-store exits 1
+    when the throughput target is missed
+"""
+
+import io
+import json
+import os
+import threading
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core.slot_resolver import SlotResolver
+from visual_editor_core.snippet_staging import (
+    AuditEventType,
+    AuditLogger,
+)
+from visual_editor_core.store_bench import bench_store, meets_targets
+
+import spokedpy
+
+
+# =============================================================================
+# LAYOUT
+# =============================================================================
+
+class TestShardLayout:
+
+    def test_sharded(self, tmp_path, make_pipeline):
+        snippet = make_pipeline().run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert os.path.dirname(snippet.saved_file_path) == str(
+            tmp_path / 'snippets' / 'python' / snippet.code_hash[:2])
+        assert SlotResolver(str(tmp_path / 'snippets')).resolve_slot('a1').staging_id == \
+            snippet.staging_id
+
+    def test_flat(self, tmp_path, make_pipeline):
+        snippet = make_pipeline(shard_width=0).run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        assert os.path.dirname(snippet.saved_file_path) == str(tmp_path / 'snippets' / 'python')
+        assert SlotResolver(str(tmp_path / 'snippets')).resolve_slot('a1').staging_id == \
+            snippet.staging_id
+
+    @pytest.mark.parametrize('width', [-1, 9])
+    def test_refused(self, make_pipeline, width):
+        with pytest.raises(ValueError, match='shard_width'):
+            make_pipeline(shard_width=width)
+
+
+# =============================================================================
+# GROUP COMMIT
+# =============================================================================
+
+class TestGroupCommit:
+
+    def test_concurrent_log(self, tmp_path):
+        log = AuditLogger(str(tmp_path / 'audit.jsonl'))
+        seqs, lock = [], threading.Lock()
+
+        def worker(n):
+            for _ in range(25):
+                entry = log.log(AuditEventType.ERROR, f"stg-{n}")
+                with lock:
+                    seqs.append(entry['seq'])
+
+        threads = [threading.Thread(target=worker, args=(n,)) for n in range(8)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        assert sorted(seqs) == list(range(1, 201))
+        written = [e['seq'] for e in log.read_all()]
+        assert sorted(written) == list(range(1, 201))
+        stats = log.stats()
+        assert stats['events'] == 200 and 1 <= stats['batches'] <= 200
+
+
+# =============================================================================
+# BENCH
+# =============================================================================
+
+class TestBench:
+
+    def test_small_run(self, tmp_path):
+        report = bench_store(jobs=6, per_job=2, root=str(tmp_path))
+        assert report.errors == [] and report.promoted == 12
+        assert report.audit_events >= report.audit_batches > 0
+        assert report.shards >= 1 and (tmp_path / 'snippets').is_dir()
+        assert meets_targets(report) and not meets_targets(report, min_rate=1e12)
+
+    def test_cli(self, monkeypatch):
+        monkeypatch.delenv('SPOKEDPY_SHARD_WIDTH', raising=False)
+        parser = spokedpy.build_parser()
+        out = io.StringIO()
+        with redirect_stdout(out):
+            args = parser.parse_args(['bench-store', '--jobs', '4', '--per-job', '1', '--min-rate', '0',
+                        '--format', 'json'])
+            assert args.func(args) == 0
+        data = json.loads(out.getvalue())
+        assert data['promoted'] == 4 and data['ok'] and data['shard_width'] == 2
+        with redirect_stdout(io.StringIO()):
+            args = parser.parse_args(['bench-store', '--jobs', '2', '--per-job', '1', '--min-rate', '1e12'])
+            assert args.func(args) == 1
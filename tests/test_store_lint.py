@@ -0,0 +1,195 @@
+"""
+Test suite for store linting (store_lint, spokedpy lint-store).
+
+Tests cover:
+  - A consistent store (checkpoint bindings match the promoted files) has
+    no findings
+  - Dead slots: a binding whose file was rolled back, a lock on an empty
+    slot; --fix drops them from the checkpoint
+  - Orphans: a promoted file the checkpoint does not bind, --fix rolls it
+    back; without a checkpoint neither class is checked
+  - Duplicate labels in one engine row, the latest kept; --allow-label
+  - Stale snippets: staged and no event for stale_days; --fix rejects them
+  - spokedpy lint-store: JSON report, exit 1 while findings remain,
+    --fix refused on a read-only store
+"""
+
+import io
+import json
+import time
+from contextlib import redirect_stdout
+
+import pytest
+
+import spokedpy
+from visual_editor_core.slot_resolver import SlotResolver
+from visual_editor_core.snippet_staging import AuditEventType, AuditLogger
+from visual_editor_core.store_lint import fix_store, format_lint, lint_store, parse_kinds
+
+
+@pytest.fixture
+def store(tmp_path, pipeline):
+    return pipeline, tmp_path
+
+
+def _checkpoint(tmp_path, snippets, locked=()):
+    path = tmp_path / 'runtime_state.json'
+    path.write_text(json.dumps({
+        'version': 2, 'saved_at': time.time(),
+        'locked_slots': {slot: {'reason': 'held'} for slot in locked},
+        'promoted_snippets': [{'staging_id': s.staging_id, 'address': s.reserved_address,
+                               'label': s.label, 'saved_file_path': s.saved_file_path}
+                              for s in snippets],
+    }))
+    return str(path)
+
+
+def _lint(tmp_path, checkpoint='', **kwargs):
+    return lint_store(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'),
+                      checkpoint, **kwargs)
+
+
+def _kinds(report):
+    return [(f.kind, f.slot) for f in report.findings]
+
+
+# =============================================================================
+# SLOTS
+# =============================================================================
+
+class TestSlots:
+
+    def test_consistent_store_is_clean(self, store):
+        pipeline, tmp_path = store
+        snippets = [pipeline.run_full_pipeline('a', 'python', f'print({n})', f'job{n}')
+                    for n in range(2)]
+        report = _lint(tmp_path, _checkpoint(tmp_path, snippets))
+        assert report.findings == [] and not report.unfixed
+        assert format_lint(report).endswith('no dead slots, orphans, duplicate labels or '
+                                            'stale snippets')
+
+    def test_dead_slots(self, store):
+        pipeline, tmp_path = store
+        kept = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'kept')
+        gone = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'gone')
+        checkpoint = _checkpoint(tmp_path, [kept, gone], locked=['a1', 'a7'])
+        pipeline.rollback(gone.staging_id, 'regressed')
+        report = _lint(tmp_path, checkpoint)
+        assert _kinds(report) == [('dead-slot', 'a2'), ('dead-slot', 'a7')]
+        assert 'was rolled back' in report.findings[0].detail
+
+        fix_store(report, str(tmp_path / 'audit.jsonl'), ['dead-slot'])
+        assert not report.unfixed
+        state = json.loads(open(checkpoint).read())
+        assert [s['address'] for s in state['promoted_snippets']] == ['a1']
+        assert list(state['locked_slots']) == ['a1']
+        assert _lint(tmp_path, checkpoint).findings == []
+
+    def test_orphans(self, store):
+        pipeline, tmp_path = store
+        bound = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'bound')
+        checkpoint = _checkpoint(tmp_path, [bound])
+        orphan = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'orphan')
+        report = _lint(tmp_path, checkpoint)
+        assert _kinds(report) == [('orphan', 'a2')]
+        assert report.findings[0].path == orphan.saved_file_path
+        assert _lint(tmp_path).findings == [] and 'no checkpoint' in format_lint(_lint(tmp_path))
+
+        fix_store(report, str(tmp_path / 'audit.jsonl'), parse_kinds('all'))
+        resolver = SlotResolver(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        assert list(resolver.slots()) == ['a1']
+        [rollback] = AuditLogger(str(tmp_path / 'audit.jsonl')).read_events(AuditEventType.ROLLBACK)
+        assert (rollback['staging_id'], rollback['data']['address']) == (orphan.staging_id, 'a2')
+        assert _lint(tmp_path, checkpoint).findings == []
+
+
+# =============================================================================
+# DUPLICATE LABELS
+# =============================================================================
+
+class TestDuplicateLabels:
+
+    def test_latest_kept(self, store):
+        pipeline, tmp_path = store
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        pipeline.run_full_pipeline('a', 'python', 'print(3)', 'other')
+        report = _lint(tmp_path)
+        [finding] = report.findings
+        assert (finding.kind, finding.slot, finding.staging_id) == ('duplicate-label', 'a1',
+                                                                     old.staging_id)
+        assert 'a1, a2; a2 is the latest' in finding.detail
+        assert _lint(tmp_path, allow_labels=['f*']).findings == []
+
+        fix_store(report, str(tmp_path / 'audit.jsonl'), ['duplicate-label'])
+        resolver = SlotResolver(str(tmp_path / 'snippets'), str(tmp_path / 'audit.jsonl'))
+        assert list(resolver.slots()) == ['a2', 'a3']
+
+
+# =============================================================================
+# STALE SNIPPETS
+# =============================================================================
+
+class TestStale:
+
+    def test_stale_after_days(self, store):
+        pipeline, tmp_path = store
+        idle = pipeline.queue_snippet('a', 'python', 'print(1)', 'idle', 'bot')
+        pipeline.run_full_pipeline('a', 'python', 'print(2)', 'done')
+        rejected = pipeline.queue_snippet('a', 'python', 'print(3)', 'no', 'bot')
+        pipeline.verdict(rejected.staging_id, 'reject')
+        assert _lint(tmp_path).findings == []
+        report = _lint(tmp_path, now=time.time() + 8 * 86400)
+        assert [f.staging_id for f in report.findings] == [idle.staging_id]
+        assert 'by bot, nothing since for 8 day(s)' in report.findings[0].detail
+        assert _lint(tmp_path, stale_days=30, now=time.time() + 8 * 86400).findings == []
+
+        fix_store(report, str(tmp_path / 'audit.jsonl'), ['stale'])
+        events = AuditLogger(str(tmp_path / 'audit.jsonl')).read_events(AuditEventType.REJECTION)
+        assert events[-1]['staging_id'] == idle.staging_id
+        assert _lint(tmp_path, now=time.time() + 8 * 86400).findings == []
+
+    def test_parse_kinds(self):
+        assert parse_kinds('orphan, stale') == ['orphan', 'stale']
+        with pytest.raises(ValueError, match='unknown lint class'):
+            parse_kinds('orphans')
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    @pytest.fixture
+    def env(self, store, monkeypatch):
+        pipeline, tmp_path = store
+        monkeypatch.setenv('SPOKEDPY_SNIPPETS_DIR', str(tmp_path / 'snippets'))
+        monkeypatch.setenv('SPOKEDPY_AUDIT_LOG', str(tmp_path / 'audit.jsonl'))
+        monkeypatch.setenv('SPOKEDPY_STATE_CHECKPOINT', str(tmp_path / 'runtime_state.json'))
+        return pipeline, tmp_path, monkeypatch
+
+    def _run(self, *argv):
+        out = io.StringIO()
+        with redirect_stdout(out):
+            code = spokedpy.main(['lint-store', *argv])
+        return code, out.getvalue()
+
+    def test_report_and_fix(self, env):
+        pipeline, tmp_path, _ = env
+        pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        code, out = self._run('--format', 'json')
+        assert code == 1 and json.loads(out)['counts']['duplicate-label'] == 1
+        assert self._run('--allow-label', 'fib')[0] == 0
+        code, out = self._run('--fix')
+        assert code == 0 and '✔ fixed' in out and '1 finding(s): 1 duplicate-label; 1 fixed' in out
+        assert self._run()[0] == 0
+
+    def test_fix_refused_when_read_only(self, env):
+        pipeline, tmp_path, monkeypatch = env
+        pipeline.queue_snippet('a', 'python', 'print(1)', 'idle')
+        monkeypatch.setenv('SPOKEDPY_READ_ONLY', '1')
+        code, out = self._run('--fix', 'stale', '--stale-days', '0', '--format', 'json')
+        assert code == 11 and json.loads(out)['error']['kind'] == 'store_read_only'
+        assert self._run('--stale-days', '0')[0] == 1
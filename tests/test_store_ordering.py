@@ -0,0 +1,120 @@
+"""
+Test suite for store timestamps (UTC RFC 3339) and the store sequence number.
+
+Tests cover:
+  - utc_timestamp keeps microseconds; parse_timestamp normalises offsets,
+    accepts whole-second (older) timestamps and refuses garbage
+  - Audit entries are numbered, and the numbering continues in a reopened
+    store (also after a log written before seq existed)
+  - Two promotions in the same instant are ordered by promoted_seq: in the
+    header, in SlotResolver and in the LSP's predecessor lookup
+  - spokedpy list prints snippets newest first by seq
+"""
+
+import io
+import json
+import os
+from contextlib import redirect_stdout
+
+import pytest
+
+from visual_editor_core.node_registry import NodeRegistry
+from visual_editor_core.session_ledger import SessionLedger
+from visual_editor_core.slot_resolver import SlotResolver
+from visual_editor_core.snippet_lsp import SnippetWorkspace
+from visual_editor_core.snippet_staging import (
+    AuditEventType,
+    AuditLogger,
+    parse_file_header,
+    parse_timestamp,
+    utc_timestamp,
+)
+
+import spokedpy
+
+
+@pytest.fixture
+def pipeline(make_pipeline):
+    return make_pipeline(node_registry=NodeRegistry(SessionLedger()))
+
+
+def _header(snippet):
+    with open(snippet.saved_file_path, 'r', encoding='utf-8') as f:
+        return parse_file_header(f.read())
+
+
+# =============================================================================
+# TIMESTAMPS
+# =============================================================================
+
+class TestTimestamps:
+
+    def test_round_trip(self):
+        assert utc_timestamp(1_770_724_634.25) == '2026-02-10T11:57:14.250000Z'
+        assert parse_timestamp('2026-02-10T11:57:14.250000Z') == 1_770_724_634.25
+
+    def test_normalised(self):
+        assert parse_timestamp('2026-02-10T12:57:14+01:00') == 1_770_724_634
+        assert parse_timestamp('2026-02-10T11:57:14Z') == 1_770_724_634    # banner v1
+        assert parse_timestamp('2026-02-10T11:57:14') == 1_770_724_634     # naive → UTC
+        with pytest.raises(ValueError):
+            parse_timestamp('yesterday')
+
+
+# =============================================================================
+# SEQUENCE
+# =============================================================================
+
+class TestSequence:
+
+    def test_numbered_and_continued(self, tmp_path):
+        path = str(tmp_path / 'audit.jsonl')
+        log = AuditLogger(path)
+        assert [log.log(AuditEventType.ERROR, 'stg-a')['seq'] for _ in range(3)] == [1, 2, 3]
+        reopened = AuditLogger(path)
+        entry = reopened.log(AuditEventType.ERROR, 'stg-a')
+        assert entry['seq'] == 4 and entry['iso_time'].endswith('Z')
+
+    def test_old_log(self, tmp_path):
+        path = tmp_path / 'audit.jsonl'
+        path.write_text(''.join(json.dumps({'event': 'error', 'staging_id': 'x'}) + '\n'
+                                for _ in range(5)), encoding='utf-8')
+        assert AuditLogger(str(path)).log(AuditEventType.ERROR, 'x')['seq'] == 6
+
+    def test_same_instant_promotions(self, tmp_path, pipeline, monkeypatch):
+        import visual_editor_core.snippet_staging as mod
+        monkeypatch.setattr(mod.time, 'time', lambda: 1_770_724_634.0)
+        old = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'fib')
+        new = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'fib')
+        assert old.seq < old.promoted_seq < new.seq < new.promoted_seq
+        assert old.promoted_at == new.promoted_at
+        old_header, new_header = _header(old), _header(new)
+        assert new_header.fields['promoted'] == '2026-02-10T11:57:14.000000Z'
+        assert old_header.promoted_order < new_header.promoted_order
+        os.utime(new.saved_file_path, (1_000, 1_000))     # mtime says the opposite
+        resolver = SlotResolver(str(tmp_path / 'snippets'))
+        assert resolver.resolve_slot('a1').staging_id == new.staging_id
+        workspace = SnippetWorkspace(str(tmp_path / 'snippets'))
+        assert workspace.predecessor(new.saved_file_path, new_header) == old.saved_file_path
+        assert workspace.predecessor(old.saved_file_path, old_header) is None
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestListOrder:
+
+    def test_newest_first(self, monkeypatch):
+        snippets = [{'staging_id': f"stg-{n}", 'seq': n, 'reserved_address': 'a1',
+                     'phase': 'promoted', 'label': 'fib', 'meta': {},
+                     'promoted': f"2026-02-10T11:57:14.00000{n}Z"} for n in (3, 9, 5)]
+        monkeypatch.setattr(spokedpy, '_api', lambda *a, **k: {
+            'success': True, 'active': snippets[:1], 'history': snippets[1:]})
+        args = spokedpy.build_parser().parse_args(['list'])
+        out = io.StringIO()
+        with redirect_stdout(out):
+            assert args.func(args) == 0
+        lines = out.getvalue().splitlines()
+        assert [line.split()[1] for line in lines] == ['stg-9', 'stg-5', 'stg-3']
+        assert lines[0].split()[0] == '#9' and '2026-02-10T11:57:14.000009Z' in lines[0]
@@ -0,0 +1,44 @@
+"""
+Test suite for the embedded store browser (``spokedpy serve --ui``).
+
+Tests cover:
+  - /ui serves the page, which talks only to the REST API
+  - Registering twice is a no-op
+  - serve --ui / --host / --port reach run_server
+"""
+
+import sys
+import types
+
+from flask import Flask
+
+from web_interface.store_ui import register_store_ui
+
+import spokedpy
+
+
+class TestStoreUI:
+
+    def test_page_served_at_ui(self):
+        app = Flask('store-ui-test')
+        register_store_ui(app)
+        register_store_ui(app)
+        client = app.test_client()
+        for path in ('/ui', '/ui/'):
+            page = client.get(path)
+            assert page.status_code == 200
+            html = page.get_data(as_text=True)
+            assert '/api/staging/snippets?include_history=1' in html
+            assert "'/api/approvals/'" in html and '/api/staging/rollback/' in html
+            assert 'src="http' not in html and 'href="http' not in html     # works offline
+
+    def test_serve_flags_reach_run_server(self, monkeypatch):
+        calls = []
+        fake = types.ModuleType('web_interface.app')
+        fake.run_server = lambda **kw: calls.append(kw)
+        monkeypatch.setitem(sys.modules, 'web_interface.app', fake)
+        for argv in (['serve', '--ui', '--port', '6001'], ['serve']):
+            args = spokedpy.build_parser().parse_args(argv)
+            assert args.func(args) == 0
+        assert calls == [{'host': None, 'port': 6001, 'ui': True},
+                         {'host': None, 'port': None, 'ui': None}]
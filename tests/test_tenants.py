@@ -0,0 +1,203 @@
+"""
+Test suite for multi-tenant serving ([tenants.<name>], tenants).
+
+Tests cover:
+  - [tenants.<name>] tables: token, data_dir (default data/tenants/<name>),
+    store settings; server settings, unknown keys and bad names refused;
+    a higher layer replaces a tenant whole
+  - tenant_setting: the tenant's own value, then a file in its data_dir for
+    store paths, then the server's settings
+  - Request routing: /t/<name> prefix (stripped onto the script root), the
+    token header, 404 for an unknown tenant, 401 for a wrong or missing
+    token, 404 for routes outside the store API under a tenant
+  - Routed stand-ins follow the current store; two stores keep their slots
+    and audit logs apart
+  - The CLI's --tenant / SPOKEDPY_TOKEN
+"""
+
+import os
+
+import pytest
+from flask import Blueprint, Flask, jsonify, request
+
+import spokedpy
+from visual_editor_core.snippet_staging import StagingPhase
+from web_interface import config_layers, tenants
+from web_interface.config_layers import ConfigError
+
+
+@pytest.fixture(autouse=True)
+def registry(monkeypatch):
+    """An empty tenant registry for each test."""
+    monkeypatch.setattr(tenants, '_stores', {})
+    return tenants._stores
+
+
+@pytest.fixture
+def toml(tmp_path):
+    """Write a project spokedpy.toml and parse it."""
+    def load(text):
+        path = tmp_path / 'spokedpy.toml'
+        path.write_text(text, encoding='utf-8')
+        return config_layers.load_file('project', str(path))
+    return load
+
+
+def _server(key, env_var, default):
+    return f"server:{key}"
+
+
+def _app():
+    app = Flask(__name__)
+    runtime_bp, other_bp = Blueprint('runtime', __name__), Blueprint('other', __name__)
+
+    @runtime_bp.route('/api/whoami')
+    def whoami():
+        return jsonify({'tenant': tenants.current().name, 'root': request.script_root})
+
+    @other_bp.route('/api/settings')
+    def settings():
+        return jsonify({'success': True})
+
+    app.register_blueprint(runtime_bp)
+    app.register_blueprint(other_bp)
+    tenants.install(app)
+    return app.test_client()
+
+
+# =============================================================================
+# CONFIG
+# =============================================================================
+
+class TestConfig:
+
+    def test_tables(self, toml, tmp_path):
+        cf = toml('[tenants.payments]\ntoken = " s3cret "\ndata_dir = "pay"\n'
+                  '[tenants.payments.retention]\nhistory_limit = 200\n'
+                  '[tenants.search]\n[tenants.search.store]\nread_only = true\n')
+        payments, search = cf.tenants['payments'], cf.tenants['search']
+        assert payments.token == 's3cret' and payments.data_dir == str(tmp_path / 'pay')
+        assert payments.values == {'history_limit': '200'}
+        assert search.token == '' and search.values == {'read_only': '1'}
+        assert search.data_dir == os.path.join(config_layers._DATA_DIR, 'tenants', 'search')
+        assert 'tenants' not in cf.values
+
+    @pytest.mark.parametrize('text, message', [
+        ('[tenants.payments.server]\nport = 5100\n', 'is a server setting'),
+        ('[tenants.payments]\ncolour = "red"\n', "unknown key 'tenants.payments.colour'"),
+        ('[tenants.Payments]\n', 'a tenant name is lowercase'),
+        ('tenants = 3\n', "'tenants' must be a table"),
+    ])
+    def test_refused(self, toml, text, message):
+        with pytest.raises(ConfigError, match=message):
+            toml(text)
+
+    def test_higher_layer_replaces_a_tenant(self, toml):
+        user = toml('[tenants.payments]\ntoken = "a"\n[tenants.search]\ndata_dir = "/s"\n')
+        project = toml('[tenants.payments]\n')
+        configs = config_layers.tenant_configs([user, project])
+        assert list(configs) == ['payments', 'search']
+        assert configs['payments'].token == '' and configs['search'].data_dir == '/s'
+
+    def test_tenant_setting(self, toml):
+        config = toml('[tenants.payments]\ndata_dir = "/srv/pay"\n'
+                      '[tenants.payments.store]\naudit_log = "/var/audit.jsonl"\n'
+                      ).tenants['payments']
+        setting = tenants.tenant_setting(config, _server)
+        assert setting('audit_log', 'SPOKEDPY_AUDIT_LOG', '') == '/var/audit.jsonl'
+        snippets = config_layers.CONFIG_SCHEMA['snippets_dir'].default
+        assert setting('snippets_dir', 'SPOKEDPY_SNIPPETS_DIR', '') == \
+            os.path.join('/srv/pay', os.path.basename(snippets))
+        assert setting('reviewers', 'SPOKEDPY_REVIEWERS', '') == 'server:reviewers'
+        assert setting('port', 'SPOKEDPY_PORT', '') == 'server:port'
+
+
+# =============================================================================
+# ROUTING
+# =============================================================================
+
+class TestRouting:
+
+    @pytest.fixture
+    def client(self):
+        tenants.register(tenants.Store('', _server))
+        tenants.register(tenants.Store('open', _server))
+        tenants.register(tenants.Store('payments', _server, token='s3cret'))
+        return _app()
+
+    def test_prefix_and_token(self, client):
+        assert client.get('/api/whoami').get_json() == {'tenant': '', 'root': ''}
+        assert client.get('/t/open/api/whoami').get_json() == {'tenant': 'open', 'root': '/t/open'}
+        headers = {tenants.TOKEN_HEADER: 's3cret'}
+        assert client.get('/api/whoami', headers=headers).get_json()['tenant'] == 'payments'
+        assert client.get('/t/payments/api/whoami', headers=headers).get_json() == \
+            {'tenant': 'payments', 'root': '/t/payments'}
+        assert tenants.current().name == ''
+
+    def test_refusals(self, client):
+        resp = client.get('/t/nope/api/whoami')
+        assert resp.status_code == 404 and resp.get_json()['kind'] == 'not_found'
+        assert client.get('/t/payments/api/whoami').status_code == 401
+        assert client.get('/api/whoami', headers={tenants.TOKEN_HEADER: 'wrong'}).status_code == 401
+        assert client.get('/t/open/api/whoami',
+                          headers={tenants.TOKEN_HEADER: 's3cret'}).status_code == 401
+
+    def test_only_the_store_api_under_a_tenant(self, client):
+        assert client.get('/api/settings').status_code == 200
+        resp = client.get('/t/open/api/settings')
+        assert resp.status_code == 404 and resp.get_json()['context'] == {'tenant': 'open'}
+
+
+# =============================================================================
+# STORES
+# =============================================================================
+
+class TestStores:
+
+    def test_routed_follows_the_current_store(self):
+        tenants.register(tenants.Store('', _server, locked_slots={'a1': 'x'}))
+        tenants.register(tenants.Store('payments', _server))
+        locked = tenants.Routed('locked_slots')
+        assert 'a1' in locked and len(locked) == 1
+        with tenants.using('payments') as store:
+            assert not locked and 'a1' not in locked
+            locked['b2'] = 'y'
+            assert store.locked_slots == {'b2': 'y'} and list(locked) == ['b2']
+            assert tenants.setting('port', 'SPOKEDPY_PORT', '') == 'server:port'
+        assert [s.name for s in tenants.stores()] == ['', 'payments'] and tenants.hosted()
+
+    def test_stores_are_isolated(self, tmp_path, make_pipeline):
+        tenants.register(tenants.Store('', _server, pipeline=make_pipeline(root=tmp_path / 'own')))
+        tenants.register(tenants.Store('payments', _server,
+                                       pipeline=make_pipeline(root=tmp_path / 'payments')))
+        pipeline = tenants.Routed('pipeline')
+        own = pipeline.run_full_pipeline('a', 'python', 'print(1)', 'own')
+        with tenants.using('payments'):
+            theirs = pipeline.run_full_pipeline('a', 'python', 'print(2)', 'theirs')
+            assert [s.staging_id for s in pipeline.get_history()] == [theirs.staging_id]
+        assert own.phase == theirs.phase == StagingPhase.PROMOTED
+        assert own.reserved_address == theirs.reserved_address       # same slot, two stores
+        assert [s.staging_id for s in pipeline.get_history()] == [own.staging_id]
+        assert 'theirs' not in (tmp_path / 'own' / 'audit.jsonl').read_text(encoding='utf-8')
+
+
+# =============================================================================
+# CLI
+# =============================================================================
+
+class TestCli:
+
+    def test_tenant_and_token(self, monkeypatch):
+        monkeypatch.delenv('SPOKEDPY_TOKEN', raising=False)
+        monkeypatch.delenv('SPOKEDPY_REVIEWER_TOKEN', raising=False)
+        monkeypatch.setenv('SPOKEDPY_TENANT', '')
+        assert spokedpy._server_url('http://h:1/') == 'http://h:1'
+        assert spokedpy._token_headers() == {}
+        args = spokedpy.build_parser().parse_args(['--tenant', 'payments', 'list'])
+        assert args.tenant == 'payments'
+        monkeypatch.setenv('SPOKEDPY_TENANT', 'payments')
+        monkeypatch.setenv('SPOKEDPY_TOKEN', 's3cret')
+        assert spokedpy._server_url('http://h:1/') == 'http://h:1/t/payments'
+        assert spokedpy._token_headers() == {'X-Spokedpy-Token': 's3cret'}
+        monkeypatch.setenv('SPOKEDPY_REVIEWER_TOKEN', 'r3view')
+        assert spokedpy._token_headers()['Authorization'] == 'Bearer r3view'
@@ -0,0 +1,130 @@
+"""
+Test suite for the Toolchain Manager — pinned rustup / pyenv / nvm installs.
+
+Tests cover:
+  - lang@version spec parsing and aliases
+  - Install plans for a fake rustup on PATH (managed RUSTUP_HOME/CARGO_HOME)
+  - Install with an injected runner, manifest round-trip, failure reporting
+  - PATH activation (newest install per language wins)
+"""
+
+import os
+import sys
+import stat
+import pytest
+
+from visual_editor_core.toolchain_manager import (
+    ToolchainManager, ToolchainError, InstalledToolchain, parse_toolchain_spec,
+)
+
+pytestmark = pytest.mark.skipif(sys.platform == 'win32', reason='uses POSIX shell shims')
+
+
+# =============================================================================
+# FIXTURES
+# =============================================================================
+
+@pytest.fixture
+def fake_rustup(tmp_path, monkeypatch):
+    """PATH containing a fake `rustup` whose `which rustc` points into tmp_path."""
+    bin_dir = tmp_path / 'bin'
+    bin_dir.mkdir()
+    rustc_dir = tmp_path / 'toolchain' / 'bin'
+    rustc_dir.mkdir(parents=True)
+    rustup = bin_dir / 'rustup'
+    rustup.write_text(f'#!/bin/sh\necho "{rustc_dir}/rustc"\n')
+    rustup.chmod(rustup.stat().st_mode | stat.S_IEXEC)
+    monkeypatch.setenv('PATH', str(bin_dir))
+    return rustc_dir
+
+
+@pytest.fixture
+def manager(tmp_path):
+    calls = []
+
+    def runner(step):
+        calls.append(step)
+        return 0, ''
+
+    mgr = ToolchainManager(str(tmp_path / 'prefix'), runner=runner)
+    mgr.calls = calls
+    return mgr
+
+
+# =============================================================================
+# SPEC PARSING
+# =============================================================================
+
+class TestSpecParsing:
+
+    def test_parses_language_and_version(self):
+        assert parse_toolchain_spec('rust@1.84') == ('rust', '1.84')
+
+    def test_aliases_normalize(self):
+        assert parse_toolchain_spec('Node@20')[0] == 'javascript'
+
+    def test_version_required(self):
+        with pytest.raises(ToolchainError):
+            parse_toolchain_spec('rust')
+
+    def test_unknown_language_rejected(self):
+        with pytest.raises(ToolchainError):
+            parse_toolchain_spec('cobol@85')
+
+
+# =============================================================================
+# PLAN + INSTALL
+# =============================================================================
+
+class TestInstall:
+
+    def test_plan_uses_managed_rustup_home(self, fake_rustup, manager):
+        [step] = manager.plan('rust@1.84')
+        assert step.argv[1:4] == ['toolchain', 'install', '1.84']
+        assert step.env['RUSTUP_HOME'].startswith(manager.prefix)
+        assert 'RUSTUP_HOME=' in step.render()
+
+    def test_install_records_manifest(self, fake_rustup, manager):
+        tc = manager.install('rust@1.84')
+        assert tc.bin_dir == str(fake_rustup)
+        assert len(manager.calls) == 1
+        [recorded] = ToolchainManager(manager.prefix).installed()
+        assert recorded.spec == 'rust@1.84'
+        assert recorded.manager == 'rustup'
+
+    def test_reinstall_replaces_entry(self, fake_rustup, manager):
+        manager.install('rust@1.84')
+        manager.install('rust@1.84')
+        assert len(manager.installed()) == 1
+
+    def test_failed_step_raises_with_output(self, fake_rustup, tmp_path):
+        mgr = ToolchainManager(str(tmp_path / 'p'), runner=lambda step: (1, 'boom'))
+        with pytest.raises(ToolchainError, match='boom'):
+            mgr.install('rust@1.84')
+        assert mgr.installed() == []
+
+
+# =============================================================================
+# ACTIVATION
+# =============================================================================
+
+class TestActivate:
+
+    def _seed(self, mgr, tmp_path, version, installed_at):
+        bin_dir = tmp_path / f'rust-{version}'
+        bin_dir.mkdir()
+        mgr._record(InstalledToolchain('rust', version, 'rustup', str(bin_dir), installed_at))
+        return str(bin_dir)
+
+    def test_newest_install_prepended(self, manager, tmp_path):
+        self._seed(manager, tmp_path, '1.83', 1.0)
+        newest = self._seed(manager, tmp_path, '1.84', 2.0)
+        env = {'PATH': '/usr/bin'}
+        assert manager.activate(env) == [newest]
+        assert env['PATH'] == os.pathsep.join([newest, '/usr/bin'])
+
+    def test_missing_bin_dir_skipped(self, manager):
+        manager._record(InstalledToolchain('rust', '1.84', 'rustup', '/nonexistent', 1.0))
+        env = {'PATH': '/usr/bin'}
+        assert manager.activate(env) == []
+        assert env['PATH'] == '/usr/bin'
@@ -0,0 +1,211 @@
+"""
+Test suite for warm interpreter pools ([engines] warm_pool, warm_recycle).
+
+Tests cover:
+  - warm_pool sizes: per engine, a bare number for both, node as an alias,
+    unknown engines and bad counts refused
+  - A Python spec case on a warm worker: args, stdin, env, cwd, exit codes
+    and tracebacks as a cold ``python -I -u -c`` run gives them; no state
+    carries over between runs; a timeout kills the run, not the worker
+  - Health: a worker is replaced after warm_recycle runs, or when it dies;
+    the Meter gets the run's CPU time and peak RSS
+  - The sandbox still holds: a spec suite in scratch dirs refuses writes
+    outside them; seccomp-filtered and streamed runs start cold
+  - Node: a booted worker runs one file, then a fresh one takes its place
+"""
+
+import os
+import shutil
+import subprocess
+import sys
+import time
+
+import pytest
+
+from visual_editor_core import accounting, offline, output_stream, seccomp, warm_pool
+from visual_editor_core.execution_engine import JavaScriptExecutor, _run_subprocess
+from visual_editor_core.spec_suite import parse_spec, run_suite
+
+
+@pytest.fixture
+def pool():
+    """Configure the pools for one test (inside a spec run's network level)."""
+    def configure(sizes='python=1', recycle=100):
+        warm_pool.configure(sizes, recycle)
+        for engine in warm_pool.sizes():
+            _wait_idle(engine)
+
+    with offline.spec_network(offline.network_for('python')):
+        yield configure
+    warm_pool.configure('')
+
+
+def _wait_idle(engine, count=1, timeout=10.0):
+    deadline = time.time() + timeout
+    while warm_pool.stats().get(engine, {}).get('idle', 0) < count:
+        assert time.time() < deadline, f"no idle {engine} worker: {warm_pool.stats()}"
+        time.sleep(0.02)
+
+
+def _python(code, *args, **kwargs):
+    kwargs.setdefault('capture_output', True)
+    kwargs.setdefault('text', True)
+    kwargs.setdefault('timeout', 10)
+    return _run_subprocess([sys.executable, '-I', '-u', '-c', code, *args], **kwargs)
+
+
+def _served():
+    return warm_pool.stats()['python']['served']
+
+
+# =============================================================================
+# SIZES
+# =============================================================================
+
+class TestSizes:
+
+    @pytest.mark.parametrize('value, expected', [
+        ('python=4, javascript=2', {'python': 4, 'javascript': 2}),
+        ('2', {'python': 2, 'javascript': 2}),
+        ('3, node=0', {'python': 3}),
+        ('', {}),
+    ])
+    def test_parse(self, value, expected):
+        assert warm_pool.parse_sizes(value) == expected
+
+    @pytest.mark.parametrize('value, message', [
+        ('rust=2', "no warm pool for engine 'rust'"),
+        ('python=many', "'python=many' is not ENGINE=WORKERS"),
+        ('python=-1', 'cannot be negative'),
+    ])
+    def test_refused(self, value, message):
+        with pytest.raises(ValueError, match=message):
+            warm_pool.parse_sizes(value)
+        with pytest.raises(ValueError, match='at least 1'):
+            warm_pool.configure('python=1', recycle=0)
+
+
+# =============================================================================
+# PYTHON
+# =============================================================================
+
+class TestPython:
+
+    def test_runs_like_a_cold_spawn(self, pool, tmp_path):
+        pool()
+        code = ("import os, sys\nprint(sys.argv, os.environ['GREETING'], os.getcwd())\n"
+                "print(sys.stdin.read().upper(), end='')\nsys.exit(3)\n")
+        env = {**os.environ, 'GREETING': 'hi'}
+        warm = _python(code, 'a', 'b', input='abc\n', env=env, cwd=str(tmp_path))
+        assert _served() == 1
+        warm_pool.configure('')
+        cold = _python(code, 'a', 'b', input='abc\n', env=env, cwd=str(tmp_path))
+        assert (warm.returncode, warm.stdout, warm.stderr) == (cold.returncode, cold.stdout, '')
+        assert warm.stdout == f"['-c', 'a', 'b'] hi {tmp_path}\nABC\n"
+
+    @pytest.mark.parametrize('code, status, stderr', [
+        ('raise KeyError("k")', 1, "KeyError: 'k'"),
+        ('raise SystemExit("bye")', 1, 'bye'),
+        ('import sys; sys.exit()', 0, ''),
+        ('import os; os._exit(7)', 7, ''),
+    ])
+    def test_exit_codes(self, pool, code, status, stderr):
+        pool()
+        result = _python(code)
+        assert result.returncode == status and stderr in result.stderr and _served() == 1
+
+    def test_no_state_carries_over(self, pool):
+        pool()
+        first = _python("import sys, json\njson.leak = 1\nsys.modules['leak'] = 1\nprint(1)")
+        second = _python("import sys, json\nprint(hasattr(json, 'leak'), 'leak' in sys.modules)")
+        assert first.stdout == '1\n' and second.stdout == 'False False\n' and _served() == 2
+
+    def test_timeout_kills_the_run_only(self, pool):
+        pool()
+        with pytest.raises(subprocess.TimeoutExpired):
+            _python('import time\nprint("started", flush=True)\ntime.sleep(30)', timeout=0.5)
+        assert _python('print(2)').stdout == '2\n'
+        assert _served() == 2 and warm_pool.stats()['python']['recycled'] == 0
+
+
+# =============================================================================
+# HEALTH
+# =============================================================================
+
+class TestHealth:
+
+    def test_recycled_after_n_runs(self, pool):
+        pool(recycle=2)
+        servers = [_python('import os; print(os.getppid())').stdout for _ in range(4)]
+        assert servers[0] == servers[1] != servers[2] == servers[3]
+        assert warm_pool.stats()['python']['recycled'] == 2 and _served() == 4
+
+    def test_dead_worker_is_replaced(self, pool):
+        pool()
+        server = int(_python('import os; print(os.getppid())').stdout)
+        os.kill(server, 9)
+        time.sleep(0.1)
+        assert _python('print(1)').stdout == '1\n'               # cold: the pool was empty
+        _wait_idle('python')
+        assert _python('print(2)').stdout == '2\n'
+        stats = warm_pool.stats()['python']
+        assert (stats['served'], stats['cold'], stats['recycled']) == (2, 1, 1)
+
+    def test_meter_gets_the_run(self, pool):
+        pool()
+        with accounting.Meter('case') as meter:
+            _python('x = bytearray(8 * 1024 * 1024)\nprint(len(x))')
+        [stage] = meter.stages
+        assert (stage['stage'], stage['process'], stage['exit_code']) == ('run', 'python', 0)
+        assert stage['cpu'] is not None and stage['peak_rss_kb'] > 8 * 1024
+
+
+# =============================================================================
+# SANDBOX
+# =============================================================================
+
+class TestSandbox:
+
+    def test_scratch_guard_holds(self, pool, tmp_path):
+        pool()
+        outside = tmp_path / 'outside.txt'
+        suite = parse_spec('[[case]]\nname = "writes"\n\n[[case]]\nname = "reads fixture"\n'
+                           'stdout = "10\\n"\n')
+        suite.fixtures['terms.txt'] = '10\n'
+        code = (f"import os\nif os.path.exists('terms.txt'):\n"
+                f"    print(open('terms.txt').read(), end='')\n"
+                f"open({str(outside)!r}, 'w').write('x')\n")
+        results = run_suite(suite, 'python', code, run=None, sandbox=True)
+        assert [r.passed for r in results] == [False, False]
+        assert all('sandbox:' in r.error for r in results) and not outside.exists()
+        assert results[1].output == '10\n' and _served() == 2
+
+    def test_cold_under_seccomp_or_streaming(self, pool, monkeypatch):
+        pool()
+        monkeypatch.setattr(seccomp, 'apply', lambda args, kwargs: ((['filtered'],), kwargs))
+        monkeypatch.setattr(seccomp, 'current', lambda: None)
+        assert warm_pool.serve(([sys.executable, '-I', '-u', '-c', 'print(1)'],),
+                               {'capture_output': True}) is None
+        monkeypatch.undo()
+        with output_stream.streaming(lambda event: None):
+            assert _python('print(1)').stdout == '1\n'
+        assert _served() == 0
+
+
+# =============================================================================
+# NODE
+# =============================================================================
+
+@pytest.mark.skipif(shutil.which('node') is None, reason='needs Node.js')
+class TestNode:
+
+    def test_one_run_per_worker(self, pool):
+        pool('javascript=1')
+        executor = JavaScriptExecutor()
+        first = executor.execute('console.log(process.argv.length, process.cwd() !== "")')
+        assert first.success and first.output == '2 true\n'
+        _wait_idle('javascript')
+        failed = executor.execute('console.error("no"); process.exit(4)')
+        assert not failed.success and 'no' in str(failed.error)
+        stats = warm_pool.stats()['javascript']
+        assert (stats['served'], stats['booted']) == (2, 3)
@@ -0,0 +1,177 @@
+"""
+Resource Accounting — what each dry-run cost, per stage, kept in the audit log.
+
+While a Meter is active on a thread, every engine process it spawns is
+measured as it is reaped (wait4): wall time, CPU time and peak memory —
+the max RSS of the process and of everything it waited for, so a
+compiler's own children and the sandbox wrappers (unshare, the seccomp
+supervisor) are covered.  The in-process Python engine is a ``run``
+stage with wall and CPU time only.  The pipeline puts the summary on
+its ``spec_exec_completed`` / ``spec_exec_failed`` and ``respec``
+entries:
+
+    "resources": {
+      "compile_time": 0.4120, "run_time": 0.0213, "cpu_time": 0.3871,
+      "peak_rss_kb": 183204, "processes": 2, "cache_hits": 0,
+      "stages": [
+        {"stage": "compile", "process": "rustc", "case": "", "wall": 0.4120,
+         "cpu": 0.3702, "peak_rss_kb": 183204, "exit_code": 0, "cache_hit": false},
+        {"stage": "run", "process": "main", ...}
+      ]
+    }
+
+Stages are 'compile' for the compilers output_stream knows and 'run' for
+everything else (``go run`` compiles and runs in one stage).
+``cache_hit`` marks a stage that was served from a cache instead of
+being run; such a stage takes only its wall time.
+"""
+
+import os
+import subprocess
+import sys
+import threading
+import time
+from typing import Any, Dict, List, Optional
+
+MAX_STAGES = 50                             # per audit entry; the totals cover all of them
+
+_active = threading.local()
+
+
+class Meter:
+    """
+    Context manager: measure this thread's engine processes while active.
+    Nested meters (one per spec case) tag their stages with ``case`` and
+    hand them on to the meter around them.
+    """
+
+    def __init__(self, case: str = ''):
+        self.case = case
+        self.stages: List[Dict[str, Any]] = []
+        self._lock = threading.Lock()
+        self._previous = None
+
+    def __enter__(self) -> 'Meter':
+        self._previous = getattr(_active, 'meter', None)
+        if self._previous is not None and not self.case:
+            self.case = self._previous.case
+        _active.meter = self
+        return self
+
+    def __exit__(self, *exc):
+        _active.meter = self._previous
+        if self._previous is not None:
+            with self._previous._lock:
+                self._previous.stages.extend(self.stages)
+        return False
+
+    def add(self, stage: str, process: str, wall: float, cpu: Optional[float] = None,
+            peak_rss_kb: Optional[int] = None, exit_code: Optional[int] = None,
+            cache_hit: bool = False):
+        with self._lock:
+            self.stages.append({
+                'stage': stage, 'process': process, 'case': self.case,
+                'wall': round(wall, 4), 'cpu': None if cpu is None else round(cpu, 4),
+                'peak_rss_kb': peak_rss_kb, 'exit_code': exit_code, 'cache_hit': cache_hit,
+            })
+
+    def summary(self) -> Dict[str, Any]:
+        """The ``resources`` value of an audit entry ({} if nothing was measured)."""
+        with self._lock:
+            stages = list(self.stages)
+        if not stages:
+            return {}
+
+        def total(key, stage=None):
+            return round(sum(s[key] or 0.0 for s in stages
+                             if stage is None or s['stage'] == stage), 4)
+
+        peaks = [s['peak_rss_kb'] for s in stages if s['peak_rss_kb'] is not None]
+        return {
+            'compile_time': total('wall', 'compile'),
+            'run_time': total('wall', 'run'),
+            'cpu_time': total('cpu'),
+            'peak_rss_kb': max(peaks) if peaks else None,
+            'processes': len(stages),
+            'cache_hits': sum(1 for s in stages if s['cache_hit']),
+            'stages': stages[:MAX_STAGES],
+        }
+
+
+def current() -> Optional[Meter]:
+    """The meter active on this thread, if any."""
+    return getattr(_active, 'meter', None)
+
+
+def record(stage: str, process: str, wall: float, **measured):
+    """Add a stage to this thread's meter (no-op without one) — see Meter.add."""
+    meter = current()
+    if meter is not None:
+        meter.add(stage, process, wall, **measured)
+
+
+def max_rss_kb(usage) -> int:
+    # ru_maxrss is kilobytes on Linux, bytes on macOS
+    return int(usage.ru_maxrss // 1024 if sys.platform == 'darwin' else usage.ru_maxrss)
+
+
+class MeteredPopen(subprocess.Popen):
+    """subprocess.Popen that adds the child's wait4() resource usage to ``meter`` when reaped."""
+
+    def __init__(self, *args, meter: Meter, stage: str, process: str, **kwargs):
+        self._meter, self._stage, self._process = meter, stage, process
+        self._started = time.time()
+        super().__init__(*args, **kwargs)
+
+    if hasattr(os, 'wait4'):                # POSIX; elsewhere only the engine's wall time counts
+        def _reap(self, pid, flags):
+            pid, status, usage = os.wait4(pid, flags)
+            if pid == self.pid:
+                self._meter.add(self._stage, self._process, time.time() - self._started,
+                                cpu=usage.ru_utime + usage.ru_stime,
+                                peak_rss_kb=max_rss_kb(usage),
+                                exit_code=os.waitstatus_to_exitcode(status))
+            return pid, status
+
+        def _try_wait(self, wait_flags):    # wait() / communicate()
+            try:
+                return self._reap(self.pid, wait_flags)
+            except ChildProcessError:
+                return self.pid, 0          # as Popen: reaped elsewhere (SIGCHLD ignored)
+
+        def _internal_poll(self, _deadstate=None, **_ignored):     # poll()
+            return super()._internal_poll(_deadstate, _waitpid=self._reap)
+
+
+def popen_for(stage: str, process: str):
+    """The Popen to spawn with on this thread: metered inside a Meter, plain outside one."""
+    meter = current()
+    if meter is None:
+        return subprocess.Popen
+
+    def popen(*args, **kwargs):
+        return MeteredPopen(*args, meter=meter, stage=stage, process=process, **kwargs)
+    return popen
+
+
+def run(popen, *popenargs, input=None, capture_output=False, timeout=None, check=False,
+        **kwargs) -> subprocess.CompletedProcess:
+    """subprocess.run() spawning with ``popen`` (see popen_for)."""
+    if input is not None:
+        kwargs['stdin'] = subprocess.PIPE
+    if capture_output:
+        kwargs['stdout'] = kwargs['stderr'] = subprocess.PIPE
+    with popen(*popenargs, **kwargs) as proc:
+        try:
+            stdout, stderr = proc.communicate(input, timeout=timeout)
+        except subprocess.TimeoutExpired as exc:
+            proc.kill()
+            exc.stdout, exc.stderr = proc.communicate()
+            raise
+        except BaseException:
+            proc.kill()
+            raise
+        code = proc.poll()
+    if check and code:
+        raise subprocess.CalledProcessError(code, proc.args, output=stdout, stderr=stderr)
+    return subprocess.CompletedProcess(proc.args, code, stdout, stderr)
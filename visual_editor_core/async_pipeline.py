@@ -0,0 +1,121 @@
+"""
+Async Pipeline — asyncio variants of the staging and spec API.
+
+A service built on asyncio can drive a StagingPipeline without wrapping
+every call in a thread of its own:
+
+    pipeline = AsyncStagingPipeline(staging_pipeline)
+    snippet = await pipeline.stage('a', 'python', code, 'etl')     # queue + speculate
+    async for event in pipeline.stream_stage('a', 'python', code, 'etl'):
+        ...                                   # queued, output_stream events, result
+
+The engines are blocking subprocesses, so each call still runs on a
+worker thread (the loop's default executor, or the one passed in); what
+the wrapper adds is that the caller awaits it and the event loop stays
+free.  The ``stream_*`` methods are async generators: every progress
+event (see output_stream) is handed to the loop as it is emitted, and
+the last one is ``{'event': 'result', …}`` with the StagedSnippet (or
+the spec results).  A failure is raised from the generator, not sent as
+an event.  Leaving the loop early, or cancelling the awaiting task,
+stops the events but not the run — the worker thread finishes it.
+"""
+
+import asyncio
+import functools
+import time
+from concurrent.futures import Executor
+from typing import Any, AsyncIterator, Callable, Dict, List, Optional
+
+from .snippet_staging import StagedSnippet, StagingPipeline
+
+Event = Dict[str, Any]
+
+
+class AsyncStagingPipeline:
+    """Awaitable front for a StagingPipeline; the pipeline itself is shared, not copied."""
+
+    def __init__(self, pipeline: StagingPipeline, executor: Optional[Executor] = None):
+        self.pipeline = pipeline
+        self.executor = executor
+
+    async def _call(self, fn: Callable, *args, **kwargs):
+        loop = asyncio.get_running_loop()
+        return await loop.run_in_executor(self.executor, functools.partial(fn, *args, **kwargs))
+
+    # ─────────────────────────────────────────────────────────────────────
+    # ONE CALL, ONE RESULT
+    # ─────────────────────────────────────────────────────────────────────
+
+    async def queue_snippet(self, *args, **kwargs) -> StagedSnippet:
+        return await self._call(self.pipeline.queue_snippet, *args, **kwargs)
+
+    async def speculate(self, staging_id: str) -> StagedSnippet:
+        return await self._call(self.pipeline.speculate, staging_id)
+
+    async def verdict(self, staging_id: str, action: str = 'auto',
+                      reason: str = '') -> StagedSnippet:
+        return await self._call(self.pipeline.verdict, staging_id, action, reason)
+
+    async def promote(self, staging_id: str) -> StagedSnippet:
+        return await self._call(self.pipeline.promote, staging_id)
+
+    async def rollback(self, staging_id: str, reason: str = '') -> StagedSnippet:
+        return await self._call(self.pipeline.rollback, staging_id, reason)
+
+    async def run_full_pipeline(self, *args, **kwargs) -> StagedSnippet:
+        return await self._call(self.pipeline.run_full_pipeline, *args, **kwargs)
+
+    async def run_spec(self, language: str, spec, code: str) -> List[Dict[str, Any]]:
+        return await self._call(self.pipeline.run_spec, language, spec, code)
+
+    async def stage(self, engine_letter: str, language: str, code: str,
+                    label: str = '', author: str = '', spec=None,
+                    meta: Optional[Dict[str, Any]] = None) -> StagedSnippet:
+        """Queue and speculate (as POST /api/staging/stage-and-spec), without the events."""
+        snippet = await self.queue_snippet(engine_letter, language, code, label,
+                                           author, spec, meta)
+        return await self.speculate(snippet.staging_id)
+
+    # ─────────────────────────────────────────────────────────────────────
+    # STREAMED
+    # ─────────────────────────────────────────────────────────────────────
+
+    async def _stream(self, work: Callable[[Callable[[Event], None]], Any],
+                      result_key: str) -> AsyncIterator[Event]:
+        loop = asyncio.get_running_loop()
+        events: asyncio.Queue = asyncio.Queue()
+        done = object()
+
+        def sink(event: Event):
+            loop.call_soon_threadsafe(events.put_nowait, event)
+
+        future = loop.run_in_executor(self.executor, work, sink)
+        # Both land on the loop in order: every event the run emitted
+        # comes out of the queue before ``done``.
+        future.add_done_callback(lambda _f: events.put_nowait(done))
+        while True:
+            event = await events.get()
+            if event is done:
+                break
+            yield event
+        yield {'event': 'result', 'at': time.time(), result_key: future.result()}
+
+    def stream_speculate(self, staging_id: str) -> AsyncIterator[Event]:
+        """Speculate a queued snippet; the result event carries ``snippet``."""
+        return self._stream(
+            lambda sink: self.pipeline.speculate(staging_id, on_event=sink), 'snippet')
+
+    async def stream_stage(self, engine_letter: str, language: str, code: str,
+                           label: str = '', author: str = '', spec=None,
+                           meta: Optional[Dict[str, Any]] = None) -> AsyncIterator[Event]:
+        """Queue and speculate, streaming — the events POST /api/staging/stage-and-spec sends."""
+        snippet = await self.queue_snippet(engine_letter, language, code, label,
+                                           author, spec, meta)
+        yield {'event': 'queued', 'at': time.time(), 'snippet': snippet}
+        async for event in self.stream_speculate(snippet.staging_id):
+            yield event
+
+    def stream_spec(self, language: str, spec, code: str) -> AsyncIterator[Event]:
+        """Run a SpecSuite without staging; the result event carries ``results``."""
+        return self._stream(
+            lambda sink: self.pipeline.run_spec(language, spec, code, on_event=sink), 'results')
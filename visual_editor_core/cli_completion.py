@@ -0,0 +1,491 @@
+"""
+CLI Completion — shell completion scripts and a man page for ``spokedpy``.
+
+Everything is generated from the argparse command tree, so a new subcommand
+or flag shows up in completions and ``man spokedpy`` without edits here:
+
+    ┌──────────────────────────────┬──────────────────────────────────────┐
+    │  spokedpy completions bash   │  source from ~/.bashrc               │
+    │  spokedpy completions zsh    │  save as _spokedpy on $fpath         │
+    │  spokedpy completions fish   │  save to ~/.config/fish/completions  │
+    │  spokedpy man                │  roff for man(1) — `| man -l -`      │
+    └──────────────────────────────┴──────────────────────────────────────┘
+
+Arguments named ``staging_id``, ``slot`` or ``label`` (and ``--profile``)
+complete dynamically: the scripts call back into the hidden
+``spokedpy __complete <kind> <prefix>`` command, which reads the local index
+— the staging audit log plus the runtime state checkpoint — so completion
+works whether or not the server is running.  Callback output is one
+candidate per line as ``value<TAB>description``.
+"""
+
+import argparse
+import json
+import time
+from dataclasses import dataclass, field
+from typing import Dict, Iterable, List, Optional, Tuple
+
+from .errors import DESCRIPTIONS, ErrorKind
+
+
+DYNAMIC_KINDS = ('staging_id', 'slot', 'label', 'profile')
+
+# Audit events that settle a snippet's state, for completion descriptions.
+_STATE_EVENTS = {
+    'snippet_queued': 'staged',
+    'verdict_fail': 'failed',
+    'verdict_manual_hold': 'held',
+    'promotion_completed': 'promoted',
+    'rejection': 'rejected',
+    'rollback': 'rolled back',
+}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# COMMAND TREE — flattened view of an argparse parser
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class Option:
+    flags: List[str]
+    help: str = ''
+    takes_value: bool = False
+    choices: List[str] = field(default_factory=list)
+    metavar: str = ''
+    dynamic: str = ''           # one of DYNAMIC_KINDS, or ''
+    many: bool = False          # positional takes several values
+    path: bool = False          # value is a file or directory
+
+
+@dataclass
+class Command:
+    path: List[str]             # [] for the top level, ['config', 'show'], …
+    help: str = ''
+    description: str = ''
+    options: List[Option] = field(default_factory=list)
+    positionals: List[Option] = field(default_factory=list)
+    subcommands: List['Command'] = field(default_factory=list)
+
+    def walk(self) -> Iterable['Command']:
+        yield self
+        for sub in self.subcommands:
+            yield from sub.walk()
+
+
+def _is_path(action: argparse.Action) -> bool:
+    names = f"{action.dest} {action.metavar or ''}".lower()
+    return any(word in names for word in ('path', 'dir', 'file', '.toml'))
+
+
+def _dynamic_kind(action: argparse.Action) -> str:
+    for name in (action.metavar, action.dest):
+        if isinstance(name, str) and name in DYNAMIC_KINDS:
+            return name
+    return ''
+
+
+def command_tree(parser: argparse.ArgumentParser, path: Optional[List[str]] = None,
+                 help_text: str = '') -> Command:
+    """Walk ``parser`` into a Command tree; ``__``-prefixed commands are hidden."""
+    cmd = Command(path=list(path or []), help=help_text,
+                  description=(parser.description or '').strip())
+    for action in parser._actions:
+        if isinstance(action, argparse._SubParsersAction):
+            helps = {a.dest: a.help or '' for a in action._choices_actions}
+            for name, sub in action.choices.items():
+                if name.startswith('__'):
+                    continue
+                cmd.subcommands.append(command_tree(sub, cmd.path + [name], helps.get(name, '')))
+            continue
+        if action.help == argparse.SUPPRESS or isinstance(action, argparse._HelpAction):
+            continue
+        opt = Option(
+            flags=list(action.option_strings) or [action.dest],
+            help=action.help or '',
+            takes_value=action.nargs != 0,
+            choices=[str(c) for c in action.choices or []],
+            metavar=action.metavar if isinstance(action.metavar, str) else action.dest.upper(),
+            dynamic=_dynamic_kind(action),
+            many=action.nargs in ('+', '*', argparse.REMAINDER),
+            path=_is_path(action),
+        )
+        if action.option_strings:
+            cmd.options.append(opt)
+        else:
+            cmd.positionals.append(opt)
+    return cmd
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# LOCAL INDEX — staging ids, slots and labels without the server
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class IndexEntry:
+    staging_id: str
+    label: str = ''
+    slot: str = ''
+    language: str = ''
+    state: str = ''
+    updated: float = 0.0
+    meta: Dict[str, str] = field(default_factory=dict)
+
+
+def _tail_lines(path: str, limit: int) -> List[str]:
+    try:
+        with open(path, 'r', encoding='utf-8', errors='replace') as f:
+            return f.readlines()[-limit:]
+    except OSError:
+        return []
+
+
+def local_index(audit_log: str, state_checkpoint: str = '',
+                limit: int = 5000) -> List[IndexEntry]:
+    """Snippets known locally, most recently touched first."""
+    entries: Dict[str, IndexEntry] = {}
+
+    def entry(staging_id: str) -> IndexEntry:
+        if staging_id not in entries:
+            entries[staging_id] = IndexEntry(staging_id)
+        return entries[staging_id]
+
+    try:
+        with open(state_checkpoint, 'r', encoding='utf-8') as f:
+            state = json.load(f)
+    except (OSError, ValueError):
+        state = {}
+    for snap in state.get('promoted_snippets', []) if isinstance(state, dict) else []:
+        if snap.get('staging_id'):
+            e = entry(snap['staging_id'])
+            e.label, e.slot = snap.get('label', ''), snap.get('address', '')
+            e.language, e.state = snap.get('language', ''), 'promoted'
+            e.meta = snap.get('meta') or {}
+            e.updated = snap.get('promoted_at', 0.0) or 0.0
+
+    for line in _tail_lines(audit_log, limit):
+        try:
+            event = json.loads(line)
+        except ValueError:
+            continue
+        staging_id = event.get('staging_id', '')
+        if not staging_id or staging_id == '*':
+            continue
+        e = entry(staging_id)
+        data = event.get('data') or {}
+        kind = event.get('event', '')
+        if kind == 'snippet_queued':
+            e.label, e.language = data.get('label', e.label), data.get('language', e.language)
+            e.meta = data.get('meta') or e.meta
+        elif kind == 'slot_reserved':
+            e.slot = data.get('address', e.slot)
+        elif kind == 'label_changed':
+            e.label = data.get('to', e.label)
+        e.state = _STATE_EVENTS.get(kind, e.state)
+        e.updated = max(e.updated, event.get('timestamp', 0.0) or 0.0)
+
+    return sorted(entries.values(), key=lambda e: e.updated, reverse=True)
+
+
+def complete(kind: str, prefix: str, index: List[IndexEntry],
+             profiles: Iterable[str] = ()) -> List[Tuple[str, str]]:
+    """Candidates for one dynamic argument as (value, description)."""
+    if kind == 'profile':
+        return [(p, 'profile') for p in profiles if p.startswith(prefix)]
+    seen, out = set(), []
+    for e in index:
+        if kind == 'staging_id':
+            value = e.staging_id
+            desc = ' · '.join(x for x in (e.label, e.slot, e.state) if x)
+        elif kind == 'slot':
+            value, desc = e.slot, e.label
+        elif kind == 'label':
+            value, desc = e.label, e.slot
+        else:
+            raise ValueError(f"unknown completion kind '{kind}' "
+                             f"(choose from: {', '.join(DYNAMIC_KINDS)})")
+        if value and value not in seen and value.startswith(prefix):
+            seen.add(value)
+            out.append((value, desc))
+    return out
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SHELL SCRIPTS
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _sq(text: str) -> str:
+    """Single-quote for sh/zsh/fish."""
+    return "'" + text.replace("'", "'\\''") + "'"
+
+
+def _first_line(text: str) -> str:
+    return ' '.join(text.split())[:120]
+
+
+def _help(o: Option) -> str:
+    if o.help:
+        return o.help
+    return f"one of: {', '.join(o.choices)}" if o.choices else ''
+
+
+def bash_script(parser: argparse.ArgumentParser, prog: str = 'spokedpy') -> str:
+    tree = command_tree(parser)
+    fn = '_' + prog.replace('-', '_')
+    cases, value_cases = [], []
+    for cmd in tree.walk():
+        key = ' '.join(cmd.path)
+        flags = ' '.join(f for o in cmd.options for f in o.flags)
+        subs = ' '.join(s.path[-1] for s in cmd.subcommands)
+        first = cmd.positionals[0] if cmd.positionals else None
+        vals = ' '.join(first.choices) if first else ''
+        dynamic = first.dynamic if first else ''
+        files = '1' if first and first.path else ''
+        cases.append(f'        {_sq(key)}) opts={_sq(flags)}; cmds={_sq(subs)}; vals={_sq(vals)}; '
+                     f'dyn={_sq(dynamic)}; files={_sq(files)} ;;')
+        for o in cmd.options:
+            if not o.takes_value:
+                continue
+            pattern = '|'.join(_sq(f'{key}:{f}') for f in o.flags)
+            if o.choices:
+                action = f'COMPREPLY=($(compgen -W {_sq(" ".join(o.choices))} -- "$cur"))'
+            elif o.dynamic:
+                action = f'COMPREPLY=($("${{COMP_WORDS[0]}}" __complete {o.dynamic} "$cur" 2>/dev/null | cut -f1))'
+            elif o.path:
+                action = 'COMPREPLY=($(compgen -f -- "$cur"))'
+            else:
+                action = 'COMPREPLY=()'
+            value_cases.append(f'        {pattern}) {action}; return ;;')
+
+    lines = [
+        f'# bash completion for {prog} — generated by `{prog} completions bash`',
+        f'{fn}() {{',
+        '    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"',
+        '    local path="" opts cmds vals dyn files i w',
+        '    for ((i = 1; i < COMP_CWORD; i++)); do',
+        '        w="${COMP_WORDS[i]}"',
+        '        [[ "$w" == -* ]] && continue',
+        '        case "$path" in',
+        *cases,
+        '        esac',
+        '        [[ " $cmds " == *" $w "* ]] && path="${path:+$path }$w"',
+        '    done',
+        '    case "$path:$prev" in',
+        *value_cases,
+        '    esac',
+        '    case "$path" in',
+        *cases,
+        '    esac',
+        '    if [[ "$cur" == -* ]]; then',
+        '        COMPREPLY=($(compgen -W "$opts" -- "$cur"))',
+        '    elif [[ -n "$cmds" ]]; then',
+        '        COMPREPLY=($(compgen -W "$cmds" -- "$cur"))',
+        '    elif [[ -n "$vals" ]]; then',
+        '        COMPREPLY=($(compgen -W "$vals" -- "$cur"))',
+        '    elif [[ -n "$dyn" ]]; then',
+        '        COMPREPLY=($("${COMP_WORDS[0]}" __complete "$dyn" "$cur" 2>/dev/null | cut -f1))',
+        '    elif [[ -n "$files" ]]; then',
+        '        COMPREPLY=($(compgen -f -- "$cur"))',
+        '    fi',
+        '}',
+        f'complete -F {fn} {prog}',
+        '',
+    ]
+    return '\n'.join(lines)
+
+
+def _zsh_escape(text: str) -> str:
+    """For a description inside a single-quoted ``_arguments`` spec."""
+    return (text.replace('\\', '\\\\').replace('[', '\\[').replace(']', '\\]')
+                .replace("'", "'\\''"))
+
+
+def zsh_script(parser: argparse.ArgumentParser, prog: str = 'spokedpy') -> str:
+    tree = command_tree(parser)
+    fn = '_' + prog.replace('-', '_')
+    lines = [
+        f'#compdef {prog}',
+        f'# zsh completion for {prog} — generated by `{prog} completions zsh`',
+        '',
+        f'{fn}_dynamic() {{',
+        '    local -a candidates',
+        '    local line',
+        f'    for line in "${{(@f)$({prog} __complete $1 "$PREFIX" 2>/dev/null)}}"; do',
+        '        [[ -n "$line" ]] && candidates+=("${${line%%$\'\\t\'*}//:/\\\\:}:${line#*$\'\\t\'}")',
+        '    done',
+        "    _describe -t $1 $1 candidates",
+        '}',
+        '',
+    ]
+    for cmd in tree.walk():
+        name = fn + ''.join('_' + p.replace('-', '_') for p in cmd.path)
+        specs = []
+        for o in cmd.options:
+            desc = _zsh_escape(_first_line(_help(o)))
+            names = o.flags[0] if len(o.flags) == 1 else '{' + ','.join(o.flags) + '}'
+            exclusive = f"({' '.join(o.flags)})" if len(o.flags) > 1 else ''
+            if not o.takes_value:
+                specs.append(f"{_sq(exclusive) if exclusive else ''}{names}'[{desc}]'")
+                continue
+            if o.choices:
+                action = f"({' '.join(o.choices)})"
+            elif o.dynamic:
+                action = f'{{{fn}_dynamic {o.dynamic}}}'
+            else:
+                action = '_files' if o.path else ' '
+            specs.append(f"{_sq(exclusive) if exclusive else ''}{names}'[{desc}]:{o.metavar.lower()}:{action}'")
+        if cmd.subcommands:
+            specs += ["'1:command:->command'", "'*::arg:->args'"]
+        for i, p in enumerate(cmd.positionals, 1):
+            if p.choices:
+                action = f"({' '.join(p.choices)})"
+            elif p.dynamic:
+                action = f'{{{fn}_dynamic {p.dynamic}}}'
+            else:
+                action = '_files' if p.path else ' '
+            specs.append(f"'{'*' if p.many else i}:{p.metavar.lower()}:{action}'")
+
+        lines.append(f'{name}() {{')
+        if cmd.subcommands:
+            lines.append('    local state line')
+        lines.append('    _arguments -s ' + ' \\\n        '.join(specs or ["'*: :'"]))
+        if cmd.subcommands:
+            lines.append('    case $state in')
+            lines.append('        command)')
+            lines.append('            local -a commands=(')
+            for sub in cmd.subcommands:
+                lines.append(f"                {_sq(sub.path[-1] + ':' + _first_line(sub.help))}")
+            lines.append('            )')
+            lines.append("            _describe -t commands command commands ;;")
+            lines.append('        args)')
+            lines.append('            case $line[1] in')
+            for sub in cmd.subcommands:
+                sub_fn = fn + ''.join('_' + p.replace('-', '_') for p in sub.path)
+                lines.append(f'                {sub.path[-1]}) {sub_fn} ;;')
+            lines.append('            esac ;;')
+            lines.append('    esac')
+        lines.append('}')
+        lines.append('')
+    lines.append(f'{fn} "$@"')
+    lines.append('')
+    return '\n'.join(lines)
+
+
+def fish_script(parser: argparse.ArgumentParser, prog: str = 'spokedpy') -> str:
+    tree = command_tree(parser)
+    lines = [f'# fish completion for {prog} — generated by `{prog} completions fish`',
+             f'complete -c {prog} -f']
+
+    for cmd in tree.walk():
+        if not cmd.path:
+            here = '__fish_use_subcommand'
+        else:
+            here = '; and '.join(f'__fish_seen_subcommand_from {p}' for p in cmd.path)
+            if cmd.subcommands:
+                names = ' '.join(s.path[-1] for s in cmd.subcommands)
+                here += f'; and not __fish_seen_subcommand_from {names}'
+        cond = _sq(here)
+        for sub in cmd.subcommands:
+            lines.append(f'complete -c {prog} -n {cond} -a {sub.path[-1]} -d {_sq(_first_line(sub.help))}')
+        for o in cmd.options:
+            parts = [f'complete -c {prog} -n {cond}']
+            for flag in o.flags:
+                parts.append(f'-l {flag[2:]}' if flag.startswith('--') else f'-s {flag[1:]}')
+            if o.takes_value:
+                if o.choices:
+                    parts.append(f"-x -a {_sq(' '.join(o.choices))}")
+                elif o.dynamic:
+                    parts.append(f"-x -a {_sq(f'({prog} __complete {o.dynamic} (commandline -ct))')}")
+                elif o.path:
+                    parts.append('-r -F')
+                else:
+                    parts.append('-x')
+            parts.append(f'-d {_sq(_first_line(_help(o)))}')
+            lines.append(' '.join(parts))
+        for p in cmd.positionals:
+            if p.choices:
+                lines.append(f"complete -c {prog} -n {cond} -a {_sq(' '.join(p.choices))}")
+            elif p.dynamic:
+                lines.append(f"complete -c {prog} -n {cond} -a "
+                             f"{_sq(f'({prog} __complete {p.dynamic} (commandline -ct))')}")
+            elif p.path:
+                lines.append(f'complete -c {prog} -n {cond} -F')
+    lines.append('')
+    return '\n'.join(lines)
+
+
+SHELLS = {'bash': bash_script, 'zsh': zsh_script, 'fish': fish_script}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# MAN PAGE
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _roff(text: str) -> str:
+    text = text.replace('\\', '\\e')
+    return '\n'.join(('\\&' + line) if line[:1] in ('.', "'") else line
+                     for line in text.splitlines())
+
+
+def _synopsis(cmd: Command, prog: str) -> str:
+    words = [f'\\fB{prog}\\fR'] + [f'\\fB{p}\\fR' for p in cmd.path]
+    if cmd.options:
+        words.append('[\\fIoptions\\fR]')
+    if cmd.subcommands:
+        words.append('\\fIcommand\\fR')
+    for p in cmd.positionals:
+        name = '{' + '|'.join(p.choices) + '}' if p.choices else p.metavar.lower()
+        words.append(f'\\fI{name}\\fR' + ('…' if p.many else ''))
+    return ' '.join(words)
+
+
+def _option_items(options: List[Option]) -> List[str]:
+    out = []
+    for o in options:
+        flags = ', '.join(f"\\fB{f.replace('-', chr(92) + '-')}\\fR" for f in o.flags)
+        if o.takes_value:
+            value = '{' + ','.join(o.choices) + '}' if o.choices else o.metavar
+            flags += f' \\fI{value}\\fR'
+        out += ['.TP', flags, _roff(_help(o))]
+    return out
+
+
+def man_page(parser: argparse.ArgumentParser, prog: str = 'spokedpy', version: str = '',
+             environment: Iterable[Tuple[str, str]] = (), files: Iterable[Tuple[str, str]] = ()) -> str:
+    """roff source for ``man 1 spokedpy`` — ``environment`` and ``files`` are (name, description)."""
+    tree = command_tree(parser)
+    date = time.strftime('%Y-%m-%d', time.gmtime())
+    title = f'{prog} {version}'.strip()
+    lines = [
+        f'.\\" generated by `{prog} man`',
+        f'.TH {prog.upper()} 1 "{date}" "{title}" "SpokedPy Manual"',
+        '.SH NAME',
+        f'{prog} \\- {_roff(tree.description or "SpokedPy command-line interface.")}',
+        '.SH SYNOPSIS',
+    ]
+    for cmd in tree.walk():
+        if not cmd.subcommands:
+            lines += [_synopsis(cmd, prog), '.br']
+    lines += ['.SH OPTIONS', *_option_items(tree.options), '.SH COMMANDS']
+    for cmd in tree.walk():
+        if not cmd.path:
+            continue
+        lines += ['.SS ' + ' '.join(cmd.path), _synopsis(cmd, prog)]
+        if cmd.help:
+            lines += ['.PP', _roff(cmd.help[:1].upper() + cmd.help[1:] + '.')]
+        lines += _option_items(cmd.options)
+    environment, files = list(environment), list(files)
+    if environment:
+        lines.append('.SH ENVIRONMENT')
+        for name, desc in environment:
+            lines += ['.TP', f'\\fB{name}\\fR', _roff(desc)]
+    if files:
+        lines.append('.SH FILES')
+        for name, desc in files:
+            lines += ['.TP', f'\\fI{_roff(name)}\\fR', _roff(desc)]
+    lines += ['.SH EXIT STATUS', '.TP', '\\fB0\\fR', 'Success.']
+    for kind in ErrorKind:
+        lines += ['.TP', f'\\fB{kind.exit_code}\\fR ({kind.value})',
+                  _roff(DESCRIPTIONS[kind][:1].upper() + DESCRIPTIONS[kind][1:] + '.')]
+    lines += ['.PP', _roff('With --format json a failing command prints the error '
+                           '(kind, message, exit_code, context) as JSON on stdout.'), '']
+    return '\n'.join(lines)
@@ -0,0 +1,175 @@
+"""
+Coverage Gate — line coverage of a Rust snippet under its spec run.
+
+The snippet is compiled with ``rustc -C instrument-coverage`` and its spec
+cases are run — ``main`` and, when the snippet has ``#[test]`` functions,
+the ``--test`` harness — each writing an LLVM profile.  The profiles are
+merged and ``llvm-cov show`` reports per-line execution counts for the
+snippet's source:
+
+    rustc -C instrument-coverage main.rs          →  main      (+ main_test)
+    LLVM_PROFILE_FILE=… ./main                    →  *.profraw
+    llvm-profdata merge -sparse *.profraw         →  merged.profdata
+    llvm-cov show main -instr-profile=…           →  covered / executable lines
+
+Below ``[gates] coverage_min`` percent (``--gate coverage --min 80``) the
+gate DENIES with the uncovered line numbers.  Either way the percentage
+lands in the snippet's metrics and in the promoted file's ``coverage:``
+header field.
+
+llvm-profdata / llvm-cov come from ``rustup component add llvm-tools-preview``
+(found in the rustc sysroot) or a system LLVM on PATH; without them the gate
+denies with that hint.  Non-Rust snippets are skipped.
+"""
+
+import glob
+import os
+import re
+import shutil
+import subprocess
+import sys
+import tempfile
+from typing import Callable, List, Optional, Tuple
+
+from .promotion_gates import GateDecision, GateError, PromotionGate
+
+
+DEFAULT_MIN = 80.0
+COMPILE_TIMEOUT = 120
+RUN_TIMEOUT = 30
+
+_LINE = re.compile(r'^\s*(\d+)\|\s*([0-9.]+[kMGTPE]?)?\|')
+
+
+def _default_runner(*args, **kwargs):
+    from .execution_engine import _run_subprocess
+    return _run_subprocess(*args, **kwargs)
+
+
+def find_llvm_tool(name: str, rustc: Optional[str], runner: Callable = _default_runner) -> Optional[str]:
+    """``llvm-<name>`` from the Rust sysroot (llvm-tools-preview), else PATH."""
+    if rustc:
+        try:
+            sysroot = runner([rustc, '--print', 'sysroot'], capture_output=True, text=True,
+                             timeout=30).stdout.strip()
+        except (OSError, subprocess.SubprocessError):
+            sysroot = ''
+        exe = f"llvm-{name}{'.exe' if sys.platform == 'win32' else ''}"
+        matches = glob.glob(os.path.join(sysroot, 'lib', 'rustlib', '*', 'bin', exe)) if sysroot else []
+        if matches:
+            return matches[0]
+    return shutil.which(f'llvm-{name}')
+
+
+def parse_line_counts(show_output: str) -> Tuple[int, List[int]]:
+    """(executable line count, uncovered line numbers) from ``llvm-cov show`` text."""
+    executable, uncovered = 0, []
+    for line in show_output.splitlines():
+        match = _LINE.match(line)
+        if match is None or not match.group(2):
+            continue
+        executable += 1
+        if float(match.group(2).rstrip('kMGTPE')) == 0:
+            uncovered.append(int(match.group(1)))
+    return executable, uncovered
+
+
+def _ranges(lines: List[int]) -> str:
+    out, start = [], None
+    for i, n in enumerate(lines):
+        if start is None:
+            start = n
+        if i + 1 == len(lines) or lines[i + 1] != n + 1:
+            out.append(str(start) if start == n else f"{start}-{n}")
+            start = None
+    return ', '.join(out)
+
+
+class CoverageGate(PromotionGate):
+    """Built-in gate: minimum line coverage of a Rust snippet's spec run."""
+
+    kind = 'builtin'
+
+    def __init__(self, name: str = 'coverage', minimum: float = DEFAULT_MIN,
+                 runner: Callable = _default_runner, rustc: Optional[str] = None,
+                 profdata: Optional[str] = None, llvm_cov: Optional[str] = None):
+        super().__init__(name)
+        if not 0 <= minimum <= 100:
+            raise ValueError('coverage minimum must be a percentage between 0 and 100')
+        self.minimum = minimum
+        self._runner = runner
+        self._rustc, self._profdata, self._llvm_cov = rustc, profdata, llvm_cov
+
+    def _tools(self) -> Tuple[str, str, str]:
+        rustc = self._rustc or shutil.which('rustc')
+        if not rustc:
+            raise GateError('rustc not found on PATH — install the Rust toolchain')
+        profdata = self._profdata or find_llvm_tool('profdata', rustc, self._runner)
+        llvm_cov = self._llvm_cov or find_llvm_tool('cov', rustc, self._runner)
+        if not profdata or not llvm_cov:
+            raise GateError('llvm-profdata / llvm-cov not found — '
+                            '`rustup component add llvm-tools-preview`')
+        return rustc, profdata, llvm_cov
+
+    def _run(self, argv: List[str], what: str, timeout: float, **kwargs):
+        try:
+            proc = self._runner(argv, capture_output=True, text=True, timeout=timeout, **kwargs)
+        except subprocess.TimeoutExpired:
+            raise GateError(f"{what} timed out after {timeout}s")
+        except OSError as exc:
+            raise GateError(f"{what} could not start: {exc}")
+        if proc.returncode != 0:
+            tail = '\n'.join((proc.stderr or proc.stdout or '').strip().splitlines()[-6:])
+            raise GateError(f"{what} failed (exit {proc.returncode}): {tail}")
+        return proc
+
+    def measure(self, body: str) -> Tuple[float, int, List[int]]:
+        """(percent, executable lines, uncovered lines) for the snippet's spec run."""
+        rustc, profdata, llvm_cov = self._tools()
+        with tempfile.TemporaryDirectory(prefix='spokedpy_cov_') as work:
+            src = os.path.join(work, 'main.rs')
+            with open(src, 'w', encoding='utf-8') as f:
+                f.write(body)
+            cases = [('main', [])]
+            if '#[test]' in body:
+                cases.append(('main_test', ['--test']))
+
+            binaries = []
+            for name, extra in cases:
+                binary = os.path.join(work, name + ('.exe' if sys.platform == 'win32' else ''))
+                self._run([rustc, '-C', 'instrument-coverage', *extra, src, '-o', binary],
+                          f"rustc -C instrument-coverage{' --test' if extra else ''}",
+                          COMPILE_TIMEOUT, cwd=work)
+                env = {**os.environ, 'LLVM_PROFILE_FILE': os.path.join(work, f'{name}-%p.profraw')}
+                self._run([binary], f"spec case '{name}'", RUN_TIMEOUT, cwd=work, env=env)
+                binaries.append(binary)
+
+            raw = sorted(glob.glob(os.path.join(work, '*.profraw')))
+            if not raw:
+                raise GateError('the instrumented spec run wrote no coverage profile')
+            merged = os.path.join(work, 'merged.profdata')
+            self._run([profdata, 'merge', '-sparse', *raw, '-o', merged], 'llvm-profdata merge',
+                      COMPILE_TIMEOUT, cwd=work)
+            objects = [binaries[0]] + [a for b in binaries[1:] for a in ('-object', b)]
+            show = self._run([llvm_cov, 'show', *objects, f'-instr-profile={merged}',
+                              '-show-line-counts', src], 'llvm-cov show', COMPILE_TIMEOUT, cwd=work)
+
+        executable, uncovered = parse_line_counts(show.stdout or '')
+        if not executable:
+            raise GateError('llvm-cov reported no executable lines for the snippet')
+        return 100.0 * (executable - len(uncovered)) / executable, executable, uncovered
+
+    def check(self, header, body) -> GateDecision:
+        if header.get('language') != 'rust':
+            return GateDecision(self.name, True, ['skipped: not a Rust snippet'])
+        percent, executable, uncovered = self.measure(body)
+        summary = (f"line coverage {percent:.1f}% ({executable - len(uncovered)}/{executable} "
+                   f"lines, minimum {self.minimum:g}%)")
+        metrics = {'line_coverage': round(percent, 1)}
+        if percent >= self.minimum:
+            return GateDecision(self.name, True, [summary], metrics=metrics)
+        return GateDecision(self.name, False, [summary, f"uncovered lines: {_ranges(uncovered)}"],
+                            metrics=metrics)
+
+    def describe(self):
+        return {**super().describe(), 'minimum': self.minimum}
@@ -0,0 +1,453 @@
+"""
+Engine Doctor — toolchain, path, and credential diagnostics.
+
+Answers "why won't my snippet run?" *before* a snippet is staged, instead
+of letting a missing compiler surface as a spawn error halfway through
+speculative execution.
+
+Six families of checks are performed:
+
+    ┌──────────────┬───────────────────────────────────────────────────┐
+    │  engine      │  every registered engine's toolchain binary:      │
+    │              │  found on PATH?  which version?                   │
+    │  tool        │  auxiliary tools (cargo, docker) used by          │
+    │              │  optional features                                │
+    │  path        │  configured data paths exist and are writable     │
+    │  credential  │  secrets required by optional integrations        │
+    │  offline     │  network isolation + cargo vendor snapshot        │
+    │              │  (only when offline mode is on)                   │
+    │  gate        │  promotion gate plugins load (only when the       │
+    │              │  plugins dir holds any)                           │
+    └──────────────┴───────────────────────────────────────────────────┘
+
+Every failing or degraded check carries a ``fix`` string with the concrete
+action to take (install command, env var to set, directory to create).
+
+The module is UI-agnostic: ``spokedpy.py doctor`` renders the report as
+text/JSON and ``GET /api/engines/doctor`` serves it over HTTP.
+"""
+
+import os
+import sys
+import shutil
+import subprocess
+from enum import Enum
+from dataclasses import dataclass, field, asdict
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from . import offline
+from .execution_engine import _run_subprocess
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# TOOLCHAIN TABLE
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass(frozen=True)
+class ToolSpec:
+    """How to find, version-probe, and install one toolchain."""
+    binaries: Tuple[str, ...]                # candidates, in priority order
+    version_args: Tuple[str, ...] = ('--version',)
+    fix: str = ''                            # install hint shown when missing
+
+
+# Engine language → toolchain.  Candidate order mirrors the executors in
+# execution_engine.py so the doctor reports the binary that will actually run.
+ENGINE_TOOLCHAINS: Dict[str, ToolSpec] = {
+    'python':     ToolSpec(('python3', 'python'),
+                           fix='Python runs in-process; install Python 3.10+ for subprocess tooling'),
+    'javascript': ToolSpec(('node',),
+                           fix='Install Node.js 18+ (https://nodejs.org) or `nvm install --lts`'),
+    'typescript': ToolSpec(('tsx', 'ts-node', 'npx'),
+                           fix='`npm install -g tsx` (falls back to plain node when absent)'),
+    'rust':       ToolSpec(('rustc',),
+                           fix='Install via rustup: `curl https://sh.rustup.rs -sSf | sh`'),
+    'java':       ToolSpec(('javac',), ('-version',),
+                           fix='Install a JDK 17+ (e.g. `apt install openjdk-17-jdk`)'),
+    'swift':      ToolSpec(('swift',),
+                           fix='Install the Swift toolchain from https://swift.org/download'),
+    'cpp':        ToolSpec(('g++', 'c++', 'clang++'),
+                           fix='Install a C++ compiler (`apt install g++` / Xcode CLT)'),
+    'r':          ToolSpec(('Rscript',),
+                           fix='Install R from https://cran.r-project.org'),
+    'go':         ToolSpec(('go',), ('version',),
+                           fix='Install Go from https://go.dev/dl'),
+    'ruby':       ToolSpec(('ruby',),
+                           fix='Install Ruby 3.x (`apt install ruby` / rbenv)'),
+    'csharp':     ToolSpec(('dotnet-script', 'dotnet', 'csc', 'mcs'),
+                           fix='Install the .NET SDK, then `dotnet tool install -g dotnet-script`'),
+    'kotlin':     ToolSpec(('kotlinc', 'kotlin'), ('-version',),
+                           fix='Install Kotlin (`sdk install kotlin`)'),
+    'c':          ToolSpec(('gcc', 'cc'),
+                           fix='Install a C compiler (`apt install gcc` / Xcode CLT)'),
+    'bash':       ToolSpec(('bash', 'sh', 'pwsh', 'powershell'),
+                           fix='Install bash (Git Bash or WSL on Windows)'),
+    'perl':       ToolSpec(('perl',),
+                           fix='Install Perl (Strawberry Perl on Windows)'),
+}
+
+# Tools that are not engines themselves but back optional features.
+AUXILIARY_TOOLS: Dict[str, ToolSpec] = {
+    'cargo':  ToolSpec(('cargo',),
+                       fix='Installed alongside rustc by rustup: `rustup component add cargo`'),
+    'docker': ToolSpec(('docker',),
+                       fix='Install Docker Engine (https://docs.docker.com/engine/install)'),
+}
+
+if sys.platform == 'win32':
+    _WINDOWS_HINT = ' — or run install_toolchains.ps1'
+else:
+    _WINDOWS_HINT = ''
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# REPORT MODEL
+# ═══════════════════════════════════════════════════════════════════════════
+
+class CheckStatus(str, Enum):
+    """Outcome of a single diagnostic check."""
+    OK   = 'ok'       # Working as expected
+    WARN = 'warn'     # Degraded — an optional feature or engine is unavailable
+    FAIL = 'fail'     # Broken — staging will not work until fixed
+
+
+@dataclass
+class DoctorCheck:
+    """One line of the doctor report."""
+    category: str                            # engine | tool | path | credential | offline
+    name: str                                # e.g. 'rust', 'snippets_dir'
+    status: CheckStatus
+    detail: str = ''
+    path: Optional[str] = None               # binary or filesystem path
+    version: Optional[str] = None
+    fix: str = ''                            # actionable remedy (empty when OK)
+
+    def to_dict(self) -> Dict[str, Any]:
+        d = asdict(self)
+        d['status'] = self.status.value
+        return d
+
+
+@dataclass
+class DoctorReport:
+    """Aggregate of every check performed by run_doctor()."""
+    checks: List[DoctorCheck] = field(default_factory=list)
+
+    @property
+    def ok(self) -> bool:
+        return not any(c.status == CheckStatus.FAIL for c in self.checks)
+
+    def counts(self) -> Dict[str, int]:
+        out = {s.value: 0 for s in CheckStatus}
+        for c in self.checks:
+            out[c.status.value] += 1
+        return out
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            'ok': self.ok,
+            'counts': self.counts(),
+            'checks': [c.to_dict() for c in self.checks],
+        }
+
+    def format_text(self) -> str:
+        """Render a human-readable report grouped by category."""
+        marks = {CheckStatus.OK: '✔', CheckStatus.WARN: '!', CheckStatus.FAIL: '✘'}
+        lines: List[str] = []
+        current = None
+        for c in self.checks:
+            if c.category != current:
+                current = c.category
+                lines.append('')
+                lines.append(f"── {current} " + '─' * max(0, 50 - len(current)))
+            info = c.version or c.detail
+            lines.append(f"  {marks[c.status]} {c.name:<16} {info}".rstrip())
+            if c.path and c.status == CheckStatus.OK:
+                lines.append(f"      {c.path}")
+            if c.status != CheckStatus.OK:
+                if c.detail and c.detail != info:
+                    lines.append(f"      {c.detail}")
+                if c.fix:
+                    lines.append(f"      fix: {c.fix}")
+        counts = self.counts()
+        lines.append('')
+        lines.append(f"{counts['ok']} ok, {counts['warn']} warning(s), {counts['fail']} failure(s)")
+        return '\n'.join(lines).lstrip('\n')
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# TOOLCHAIN PROBES
+# ═══════════════════════════════════════════════════════════════════════════
+
+def find_toolchain(language: str) -> Optional[str]:
+    """Return the path of the first toolchain binary found for a language."""
+    language = language.lower().strip()
+    spec = ENGINE_TOOLCHAINS.get(language)
+    if spec is None:
+        return None
+    if language == 'python':
+        return sys.executable
+    for binary in spec.binaries:
+        found = shutil.which(binary)
+        if found:
+            return found
+    return None
+
+
+def toolchain_hint(language: str) -> str:
+    """One-line remedy for a missing toolchain, suitable for error messages."""
+    spec = ENGINE_TOOLCHAINS.get(language.lower().strip())
+    if spec is None:
+        return f"No engine is registered for '{language}'"
+    return f"{spec.fix}{_WINDOWS_HINT} (run `spokedpy doctor` for a full report)"
+
+
+def probe_version(binary_path: str, args: Iterable[str] = ('--version',),
+                  timeout: float = 5.0) -> Optional[str]:
+    """Run ``<binary> <args>`` and return the first meaningful output line."""
+    try:
+        proc = _run_subprocess([binary_path, *args], capture_output=True,
+                               text=True, timeout=timeout)
+    except (OSError, subprocess.SubprocessError):
+        return None
+    text = (proc.stdout or proc.stderr or '').strip()
+    for line in text.splitlines():
+        line = line.strip()
+        if line:
+            return line[:120]
+    return None
+
+
+def _check_tool(category: str, name: str, spec: ToolSpec,
+                required: bool) -> DoctorCheck:
+    if name == 'python':
+        path = sys.executable
+        version = f"Python {sys.version.split()[0]}"
+    else:
+        path = None
+        for binary in spec.binaries:
+            path = shutil.which(binary)
+            if path:
+                break
+        version = probe_version(path, spec.version_args) if path else None
+
+    if path is None:
+        return DoctorCheck(
+            category=category, name=name,
+            status=CheckStatus.FAIL if required else CheckStatus.WARN,
+            detail=f"not found on PATH (looked for: {', '.join(spec.binaries)})",
+            fix=spec.fix + _WINDOWS_HINT,
+        )
+    if version is None:
+        return DoctorCheck(
+            category=category, name=name, status=CheckStatus.WARN, path=path,
+            detail=f"found but `{os.path.basename(path)} {' '.join(spec.version_args)}` "
+                   f"did not report a version",
+            fix=f"Check that {path} is executable and not a broken shim",
+        )
+    return DoctorCheck(category=category, name=name, status=CheckStatus.OK,
+                       path=path, version=version)
+
+
+def check_engines(languages: Optional[Iterable[str]] = None,
+                  required: Iterable[str] = ()) -> List[DoctorCheck]:
+    """Check engine toolchains.
+
+    ``languages`` restricts the check to a subset (default: every engine).
+    Engines listed in ``required`` report FAIL instead of WARN when missing.
+    """
+    required = {r.lower() for r in required}
+    selected = list(languages) if languages else list(ENGINE_TOOLCHAINS)
+    checks = []
+    for lang in selected:
+        lang = lang.lower().strip()
+        spec = ENGINE_TOOLCHAINS.get(lang)
+        if spec is None:
+            checks.append(DoctorCheck(
+                category='engine', name=lang, status=CheckStatus.FAIL,
+                detail='unknown engine',
+                fix=f"Known engines: {', '.join(sorted(ENGINE_TOOLCHAINS))}",
+            ))
+            continue
+        checks.append(_check_tool('engine', lang, spec, lang in required))
+    return checks
+
+
+def check_auxiliary_tools() -> List[DoctorCheck]:
+    """Check optional tools (cargo, docker).  Missing tools only warn."""
+    return [_check_tool('tool', name, spec, required=False)
+            for name, spec in AUXILIARY_TOOLS.items()]
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# PATH & CREDENTIAL PROBES
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _nearest_existing(path: str) -> str:
+    """Walk up from ``path`` to the first ancestor that exists."""
+    current = os.path.abspath(path)
+    while not os.path.exists(current):
+        parent = os.path.dirname(current)
+        if parent == current:
+            break
+        current = parent
+    return current
+
+
+def check_path(name: str, path: str, kind: str = 'dir',
+               env_var: str = '') -> DoctorCheck:
+    """Validate a configured path.
+
+    kind='dir'  — must be a writable directory (or creatable)
+    kind='file' — parent must be writable; if present, must be a writable file
+    """
+    override = f"set {env_var} or " if env_var else ''
+    remedy = f"{override}override '{name}' via PUT /api/settings/{name}"
+
+    if not path:
+        return DoctorCheck(category='path', name=name, status=CheckStatus.FAIL,
+                           detail='no path configured', fix=remedy)
+
+    target_dir = path if kind == 'dir' else (os.path.dirname(path) or '.')
+
+    if os.path.exists(path):
+        if kind == 'dir' and not os.path.isdir(path):
+            return DoctorCheck(category='path', name=name, status=CheckStatus.FAIL,
+                               path=path, detail='exists but is not a directory', fix=remedy)
+        if kind == 'file' and os.path.isdir(path):
+            return DoctorCheck(category='path', name=name, status=CheckStatus.FAIL,
+                               path=path, detail='exists but is a directory', fix=remedy)
+        if not os.access(path, os.W_OK):
+            return DoctorCheck(category='path', name=name, status=CheckStatus.FAIL,
+                               path=path, detail='not writable by the current user',
+                               fix=f"chmod u+w {path} — or {remedy}")
+        return DoctorCheck(category='path', name=name, status=CheckStatus.OK,
+                           path=path, detail=kind)
+
+    anchor = _nearest_existing(target_dir)
+    if not os.path.isdir(anchor) or not os.access(anchor, os.W_OK):
+        return DoctorCheck(category='path', name=name, status=CheckStatus.FAIL,
+                           path=path, detail=f"cannot be created ({anchor} is not writable)",
+                           fix=f"mkdir -p {target_dir} with suitable permissions — or {remedy}")
+    return DoctorCheck(category='path', name=name, status=CheckStatus.WARN,
+                       path=path, detail='does not exist yet (will be created on first use)',
+                       fix=f"mkdir -p {target_dir}")
+
+
+def check_credentials(secrets: Dict[str, Tuple[str, str, str]]) -> List[DoctorCheck]:
+    """Check that optional secrets are configured.
+
+    ``secrets`` maps name → (value, env_var, feature description).  Values
+    are never echoed back — only whether they are set.
+    """
+    checks = []
+    for name, (value, env_var, feature) in secrets.items():
+        if value:
+            checks.append(DoctorCheck(category='credential', name=name,
+                                      status=CheckStatus.OK, detail='configured'))
+        else:
+            checks.append(DoctorCheck(
+                category='credential', name=name, status=CheckStatus.WARN,
+                detail=f"not set — {feature} is disabled",
+                fix=f"set {env_var} or PUT /api/hub/settings/bulk with '{name}'",
+            ))
+    return checks
+
+
+def check_offline() -> List[DoctorCheck]:
+    """Report how offline mode / the netns sandbox backend / the network policy is enforced."""
+    checks = []
+    prefix = offline.isolation_prefix()
+    strict = offline.sandbox_backend() == 'netns'
+    if prefix:
+        checks.append(DoctorCheck(category='offline', name='network_isolation',
+                                  status=CheckStatus.OK, path=prefix[0],
+                                  detail='engine subprocesses run in an empty network namespace'))
+    else:
+        checks.append(DoctorCheck(
+            category='offline', name='network_isolation',
+            status=CheckStatus.FAIL if strict else CheckStatus.WARN,
+            detail='no network namespace support — only package managers are forced offline '
+                   'and the network policy only binds in-process Python',
+            fix='run on Linux with unprivileged user namespaces (util-linux `unshare`) '
+                'or firewall the host',
+        ))
+    if not offline.is_offline():
+        return checks
+    root = offline.vendor_dir()
+    snapshot = offline.CargoVendor(root).status() if root else {'present': False}
+    if snapshot.get('present'):
+        checks.append(DoctorCheck(category='offline', name='cargo_vendor',
+                                  status=CheckStatus.OK, path=root,
+                                  detail=f"{snapshot.get('crate_count', 0)} vendored crate(s)"))
+    else:
+        checks.append(DoctorCheck(
+            category='offline', name='cargo_vendor', status=CheckStatus.WARN, path=root,
+            detail='no vendored registry snapshot — cargo can only use its local cache',
+            fix='spokedpy vendor path/to/Cargo.toml (run once while connected)',
+        ))
+    return checks
+
+
+def check_seccomp() -> List[DoctorCheck]:
+    """Report whether the seccomp profile of spec runs can be applied here."""
+    from . import seccomp
+    name = seccomp.profile()
+    if seccomp.supported():
+        return [DoctorCheck(category='sandbox', name='seccomp', status=CheckStatus.OK,
+                            detail=f"engine subprocesses of spec runs are filtered ({name}: "
+                                   f"{len(seccomp.PROFILES[name])} syscalls denied)")]
+    return [DoctorCheck(
+        category='sandbox', name='seccomp', status=CheckStatus.WARN,
+        detail=f"seccomp profile '{name}' is set but not applied — no seccomp user "
+               f"notifications here",
+        fix='run on Linux 5.0+ (x86_64 or aarch64), or set [sandbox] seccomp = "off"',
+    )]
+
+
+def check_gates(plugins_dir: Optional[str]) -> List[DoctorCheck]:
+    """Load every gate plugin the pipeline would run and report failures."""
+    from .promotion_gates import load_plugin_gates
+    checks = []
+    for gate in load_plugin_gates(plugins_dir):
+        if gate.kind == 'broken':
+            fix = ('pip install wasmtime' if 'wasmtime' in gate.error
+                   else 'rebuild the plugin against the spokedpy_alloc / spokedpy_check ABI')
+            checks.append(DoctorCheck(category='gate', name=gate.name, status=CheckStatus.FAIL,
+                                      path=gate.path, detail=gate.error, fix=fix))
+        else:
+            checks.append(DoctorCheck(category='gate', name=gate.name, status=CheckStatus.OK,
+                                      path=getattr(gate, 'path', ''), detail=f'{gate.kind} plugin loaded'))
+    return checks
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# ENTRY POINT
+# ═══════════════════════════════════════════════════════════════════════════
+
+def run_doctor(paths: Optional[Dict[str, Tuple[str, str, str]]] = None,
+               secrets: Optional[Dict[str, Tuple[str, str, str]]] = None,
+               languages: Optional[Iterable[str]] = None,
+               required: Iterable[str] = (),
+               plugins_dir: Optional[str] = None) -> DoctorReport:
+    """Run every check and return the aggregate report.
+
+    paths       — name → (path, kind, env_var)     (kind: 'dir' | 'file')
+    secrets     — name → (value, env_var, feature)
+    plugins_dir — promotion gate plugins to try loading
+    """
+    report = DoctorReport()
+    report.checks.extend(check_engines(languages, required))
+    if not languages:
+        report.checks.extend(check_auxiliary_tools())
+    for name, (path, kind, env_var) in (paths or {}).items():
+        report.checks.append(check_path(name, path, kind, env_var))
+    report.checks.extend(check_credentials(secrets or {}))
+    if offline.is_offline() or offline.sandbox_backend() == 'netns' \
+            or offline.network_policy() != ('full', {}):
+        report.checks.extend(check_offline())
+    from .seccomp import profile
+    if profile() != 'off':
+        report.checks.extend(check_seccomp())
+    report.checks.extend(check_gates(plugins_dir))
+    return report
@@ -0,0 +1,151 @@
+"""
+Errors — the failure kinds spokedpy reports, for callers that branch on them.
+
+Every refusal in the pipeline raises SpokedError (a ValueError, so existing
+``except ValueError`` handlers keep working) with an ErrorKind and the
+structured context of the failure — ``SpokedError(ErrorKind.SLOT_FULL,
+"…", engine='RUST', max_slots=16)``.  The server returns the kind and
+context next to the message (``{"success": false, "error": "…", "kind":
+"slot_full", "context": {…}}``), the CLI rebuilds the error from that body,
+and every spokedpy command exits with the kind's code:
+
+    ┌──────┬────────────────────┬────────────────────────────────────────────┐
+    │ code │ kind               │ meaning                                    │
+    ├──────┼────────────────────┼────────────────────────────────────────────┤
+    │  0   │                    │ success                                    │
+    │  1   │ failed             │ a check did not pass; unclassified errors  │
+    │  1   │ spec_failed        │ the dry-run or a spec case failed          │
+    │  2   │ usage              │ bad arguments, flags or config             │
+    │  3   │ server_unreachable │ no spokedpy server answered at --server    │
+    │  4   │ not_found          │ no such staging id, slot, file or report   │
+    │  5   │ invalid_state      │ the snippet is in the wrong phase for this │
+    │ 10   │ store_corruption   │ promoted file missing or not matching its  │
+    │      │                    │ code_hash; unreadable checkpoint           │
+    │ 11   │ store_read_only    │ [store] read_only refused a change         │
+    │ 12   │ slot_full          │ the engine row has no free slot            │
+    │ 13   │ slot_frozen        │ the slot's constraints refuse the snippet  │
+    │ 14   │ gate_denied        │ a gate, owner rule or policy said no       │
+    │ 15   │ spec_timeout       │ the dry-run or a spec case timed out       │
+    │ 16   │ engine_missing     │ no engine / toolchain for the language     │
+    │ 17   │ quota_exceeded     │ the author or namespace is at a [quota]    │
+    │      │                    │ limit                                      │
+    │ 18   │ slot_degraded      │ the snippet imports a slot whose snippet   │
+    │      │                    │ fails revalidation                         │
+    └──────┴────────────────────┴────────────────────────────────────────────┘
+
+With ``--format json`` a failing command prints the error as JSON on stdout
+(``{"success": false, "error": {"kind", "message", "exit_code",
+"context"}}``) instead of the ``✘`` line on stderr.
+"""
+
+from enum import Enum
+from typing import Any, Dict, Optional
+
+
+class ErrorKind(str, Enum):
+    """What went wrong; the value is what the API and ``--format json`` carry."""
+    FAILED             = 'failed'
+    SPEC_FAILED        = 'spec_failed'
+    USAGE              = 'usage'
+    SERVER_UNREACHABLE = 'server_unreachable'
+    NOT_FOUND          = 'not_found'
+    INVALID_STATE      = 'invalid_state'
+    STORE_CORRUPTION   = 'store_corruption'
+    STORE_READ_ONLY    = 'store_read_only'
+    SLOT_FULL          = 'slot_full'
+    SLOT_FROZEN        = 'slot_frozen'
+    GATE_DENIED        = 'gate_denied'
+    SPEC_TIMEOUT       = 'spec_timeout'
+    ENGINE_MISSING     = 'engine_missing'
+    QUOTA_EXCEEDED     = 'quota_exceeded'
+    SLOT_DEGRADED      = 'slot_degraded'
+
+    @property
+    def exit_code(self) -> int:
+        return EXIT_CODES[self]
+
+
+EXIT_CODES: Dict[ErrorKind, int] = {
+    ErrorKind.FAILED:             1,
+    ErrorKind.SPEC_FAILED:        1,
+    ErrorKind.USAGE:              2,
+    ErrorKind.SERVER_UNREACHABLE: 3,
+    ErrorKind.NOT_FOUND:          4,
+    ErrorKind.INVALID_STATE:      5,
+    ErrorKind.STORE_CORRUPTION:   10,
+    ErrorKind.STORE_READ_ONLY:    11,
+    ErrorKind.SLOT_FULL:          12,
+    ErrorKind.SLOT_FROZEN:        13,
+    ErrorKind.GATE_DENIED:        14,
+    ErrorKind.SPEC_TIMEOUT:       15,
+    ErrorKind.ENGINE_MISSING:     16,
+    ErrorKind.QUOTA_EXCEEDED:     17,
+    ErrorKind.SLOT_DEGRADED:      18,
+}
+
+# One line per kind, for the man page's EXIT STATUS section.
+DESCRIPTIONS: Dict[ErrorKind, str] = {
+    ErrorKind.FAILED:             'a check did not pass, or an unclassified error',
+    ErrorKind.SPEC_FAILED:        'the dry-run or a spec case failed',
+    ErrorKind.USAGE:              'bad arguments, flags or configuration',
+    ErrorKind.SERVER_UNREACHABLE: 'no spokedpy server answered at --server',
+    ErrorKind.NOT_FOUND:          'no such staging id, slot, file or report',
+    ErrorKind.INVALID_STATE:      'the snippet is in the wrong phase for the command',
+    ErrorKind.STORE_CORRUPTION:   'a promoted file is missing or no longer matches its code_hash',
+    ErrorKind.STORE_READ_ONLY:    'the store is read-only',
+    ErrorKind.SLOT_FULL:          'the engine row has no free slot',
+    ErrorKind.SLOT_FROZEN:        "the slot's constraints refuse the snippet",
+    ErrorKind.GATE_DENIED:        'a gate, owner rule or policy refused the snippet',
+    ErrorKind.SPEC_TIMEOUT:       'the dry-run or a spec case timed out',
+    ErrorKind.ENGINE_MISSING:     'no engine or toolchain for the language',
+    ErrorKind.QUOTA_EXCEEDED:     'the author or namespace has used up a staging quota',
+    ErrorKind.SLOT_DEGRADED:      'the snippet imports a degraded slot',
+}
+
+
+class SpokedError(ValueError):
+    """A refusal with a kind and the structured context of the failure."""
+
+    def __init__(self, kind: ErrorKind, message: str, **context: Any):
+        super().__init__(message)
+        self.kind = ErrorKind(kind)
+        self.message = message
+        self.context = context
+
+    @property
+    def exit_code(self) -> int:
+        return self.kind.exit_code
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {'kind': self.kind.value, 'message': self.message,
+                'exit_code': self.exit_code, 'context': self.context}
+
+    @classmethod
+    def from_response(cls, data: Dict[str, Any], status: int = 0) -> 'SpokedError':
+        """Rebuild the error a server response (``success: false``) describes."""
+        message = data.get('error') or (f"HTTP {status}" if status else 'request failed')
+        try:
+            kind = ErrorKind(data.get('kind', ''))
+        except ValueError:
+            kind = ErrorKind.NOT_FOUND if status == 404 else ErrorKind.FAILED
+        return cls(kind, message, **(data.get('context') or {}))
+
+
+def classify(exc: BaseException) -> SpokedError:
+    """``exc`` as a SpokedError; errors raised without a kind become FAILED."""
+    if isinstance(exc, SpokedError):
+        return exc
+    return SpokedError(ErrorKind.FAILED, str(exc))
+
+
+def spec_failure(error: Optional[str], **context: Any) -> SpokedError:
+    """The error of a failed dry-run, classified from the engine's message."""
+    text = error or 'dry-run failed'
+    lowered = text.lower()
+    if 'timed out' in lowered or 'timeout' in lowered:
+        kind = ErrorKind.SPEC_TIMEOUT
+    elif 'no executor' in lowered:
+        kind = ErrorKind.ENGINE_MISSING
+    else:
+        kind = ErrorKind.SPEC_FAILED
+    return SpokedError(kind, text, **context)
@@ -21,6 +21,7 @@ from typing import Dict, Any, Optional, List, Callable
 from contextlib import redirect_stdout, redirect_stderr
 from .models import VisualModel, VisualNode, NodeType, InputPort, OutputPort
 from .data_flow_visualizer import DataFlowVisualizer
+from . import accounting, offline, output_stream, scratch, seccomp, warm_pool
 
 
 def _run_subprocess(*args, **kwargs):
@@ -30,6 +31,35 @@ def _run_subprocess(*args, **kwargs):
     if kwargs.get('text', False) and 'encoding' not in kwargs:
         kwargs['encoding'] = 'utf-8'
         kwargs['errors'] = 'replace'  # Never crash on stray bytes
+    if (output_stream.current_sink() or output_stream.current_check()) is not None and args:
+        # A streaming client is listening (or a spec case checks stdout as
+        # it comes) — name the process before the offline wrapper
+        # (unshare …) can hide it.
+        kwargs.setdefault('process', output_stream.process_name(args[0]))
+        kwargs.setdefault('phase', output_stream.process_phase(args[0]))
+        streamed = True
+    else:
+        streamed = False
+    # Metered (a dry-run): its wait4() usage goes on the audit entry, under
+    # the engine's own name as well
+    popen = subprocess.Popen
+    if accounting.current() is not None and args:
+        popen = accounting.popen_for(output_stream.process_phase(args[0]),
+                                     output_stream.process_name(args[0]))
+    # A spec run: start in its scratch dir, HOME and TMPDIR inside it
+    args, kwargs = scratch.apply(args, kwargs)
+    # ...on a warm interpreter, when its engine has a pool (warm_pool)
+    served = warm_pool.serve(args, kwargs)
+    if served is not None:
+        return served
+    # ...under its seccomp profile (innermost, after the namespace is set up)
+    args, kwargs = seccomp.apply(args, kwargs)
+    # Offline mode: package managers offline + no network namespace
+    args, kwargs = offline.apply(args, kwargs)
+    if streamed:
+        return output_stream.run_streaming(*args, popen=popen, **kwargs)
+    if popen is not subprocess.Popen:
+        return accounting.run(popen, *args, **kwargs)
     return subprocess.run(*args, **kwargs)
 
 
@@ -273,14 +303,17 @@ class PythonExecutor:
         self.error_buffer = io.StringIO()
         
         try:
-            if capture_output:
-                # Capture stdout and stderr
-                with redirect_stdout(self.output_buffer), redirect_stderr(self.error_buffer):
-                    # Execute the code
+            # Offline mode: refuse non-loopback sockets opened by the snippet;
+            # in a spec run's scratch dir, refuse writes outside it
+            with offline.network_guard(), scratch.write_guard():
+                if capture_output:
+                    # Capture stdout and stderr
+                    with redirect_stdout(self.output_buffer), redirect_stderr(self.error_buffer):
+                        # Execute the code
+                        exec(code, self.global_namespace, self.local_namespace)
+                else:
+                    # Execute without capturing output
                     exec(code, self.global_namespace, self.local_namespace)
-            else:
-                # Execute without capturing output
-                exec(code, self.global_namespace, self.local_namespace)
             
             execution_time = time.time() - start_time
             output = self.output_buffer.getvalue()
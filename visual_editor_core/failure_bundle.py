@@ -0,0 +1,220 @@
+"""
+Failure Bundles — everything needed to debug a failed dry-run, kept on disk.
+
+When speculation (or a promotion gate) fails, the pipeline writes
+``<failures_dir>/<staging_id>/``:
+
+    ┌──────────────────┬──────────────────────────────────────────────────┐
+    │  bundle.json     │  snippet identity, exit status, error, spec      │
+    │                  │  cases, gate results, every engine process with  │
+    │                  │  its output, sandbox violations (writes refused  │
+    │                  │  outside the scratch dir, changed fixtures), the │
+    │                  │  syscalls seccomp denied and an environment      │
+    │                  │  summary                                         │
+    │  source.<ext>    │  the snippet exactly as it ran                   │
+    │  diagnostics.txt │  compiler output (compile-phase stderr/stdout)   │
+    │  stdout.txt      │  program stdout, per process                     │
+    │  stderr.txt      │  program stderr and the pipeline's error         │
+    │  fixtures.json   │  spec cases: args, stdin, env, expected output   │
+    └──────────────────┴──────────────────────────────────────────────────┘
+
+The exit status is the first failure's exit code, or ``stopped`` for a
+case killed at its first divergent stdout line (its stderr up to there
+is kept all the same).  ``spokedpy failures show <staging_id>`` reads the
+bundle back — no server needed.  Re-speculating a snippet replaces its
+bundle.
+"""
+
+import json
+import os
+import platform
+import shutil
+import sys
+import time
+from typing import Any, Dict, List, Optional, Union
+
+from .seccomp import spec_result
+
+BUNDLE_FILE = 'bundle.json'
+
+
+def bundle_dir(failures_dir: str, staging_id: str) -> str:
+    if not staging_id or os.sep in staging_id or (os.altsep and os.altsep in staging_id) \
+            or staging_id in ('.', '..'):
+        raise ValueError(f"Invalid staging id '{staging_id}'")
+    return os.path.join(failures_dir, staging_id)
+
+
+def environment_summary(language: str) -> Dict[str, Any]:
+    """Host, toolchain and sandbox facts that commonly explain a failure."""
+    from . import offline, output_stream, seccomp
+    from .engine_doctor import find_toolchain, probe_version
+
+    toolchain = find_toolchain(language)
+    with output_stream.streaming(lambda event: None):      # keep the probe out of transcripts
+        version = probe_version(toolchain) if toolchain else None
+    return {
+        'platform': platform.platform(),
+        'python': sys.version.split()[0],
+        'toolchain': toolchain or '',
+        'toolchain_version': version or '',
+        'offline': offline.is_offline(),
+        'sandbox_backend': offline.sandbox_backend(),
+        'seccomp': seccomp.profile(),
+        'cwd': os.getcwd(),
+    }
+
+
+def _exit_status(snippet, processes: List[Dict[str, Any]]) -> Union[int, str, None]:
+    """The first failure's exit code, or 'stopped' for a case killed at its
+    first divergent stdout line."""
+    failed = [c for c in snippet.spec_cases if not c.get('passed')]
+    for entry in failed + [p for p in processes if p['exit_code'] or p.get('stopped')]:
+        if entry.get('stopped'):
+            return 'stopped'
+        if entry.get('exit_code') is not None:
+            return entry['exit_code']
+    return None if snippet.spec_success else 1
+
+
+def _transcript(processes: List[Dict[str, Any]], stream: str, phase: str) -> str:
+    parts = []
+    for p in processes:
+        if p['phase'] != phase or not p[stream]:
+            continue
+        case = f" · case {p['case']}" if p['case'] else ''
+        status = 'stopped' if p.get('stopped') else f"exit {p['exit_code']}"
+        parts.append(f"── {p['process']}{case} ({status}) ──\n{p[stream]}"
+                     + ('' if p[stream].endswith('\n') else '\n'))
+    return ''.join(parts)
+
+
+def write_bundle(failures_dir: str, snippet, processes: List[Dict[str, Any]],
+                 extension: str = '.txt') -> str:
+    """Write the bundle for a FAILED snippet; returns its directory."""
+    path = bundle_dir(failures_dir, snippet.staging_id)
+    if os.path.isdir(path):
+        shutil.rmtree(path)
+    os.makedirs(path)
+
+    diagnostics = _transcript(processes, 'stderr', 'compile') + _transcript(processes, 'stdout', 'compile')
+    if not diagnostics and snippet.spec_error.startswith('Compilation failed'):
+        diagnostics = snippet.spec_error + '\n'
+    stdout = _transcript(processes, 'stdout', 'run') or snippet.spec_output
+    stderr = _transcript(processes, 'stderr', 'run')
+    if snippet.spec_error:
+        stderr += f"── pipeline ──\n{snippet.spec_error}\n"
+
+    bundle = {
+        'staging_id': snippet.staging_id,
+        'label': snippet.label,
+        'language': snippet.language,
+        'slot': snippet.reserved_address,
+        'author': snippet.author,
+        'parent': snippet.parent,
+        'code_hash': snippet.code_hash,
+        'spec_hash': snippet.spec_hash,
+        'phase': snippet.phase.value,
+        'failed_at': time.time(),
+        'exit_status': _exit_status(snippet, processes),
+        'error': snippet.spec_error,
+        'spec_time': snippet.spec_execution_time,
+        'cases': snippet.spec_cases,
+        'gates': snippet.gate_results,
+        'processes': processes,
+        'violations': snippet.sandbox_violations,
+        'denied': snippet.spec_denied,
+        'spec_result': spec_result(snippet.spec_success, snippet.spec_denied),
+        'environment': environment_summary(snippet.language),
+        'files': {},
+    }
+    files = {
+        f"source{extension}": snippet.code,
+        'diagnostics.txt': diagnostics,
+        'stdout.txt': stdout,
+        'stderr.txt': stderr,
+    }
+    if snippet.spec:
+        files['fixtures.json'] = json.dumps(snippet.spec.get('cases', []), indent=2) + '\n'
+    for name, text in files.items():
+        if not text:
+            continue
+        with open(os.path.join(path, name), 'w', encoding='utf-8') as f:
+            f.write(text)
+        bundle['files'][name] = len(text)
+    with open(os.path.join(path, BUNDLE_FILE), 'w', encoding='utf-8') as f:
+        json.dump(bundle, f, indent=2, default=str)
+        f.write('\n')
+    return path
+
+
+def read_bundle(failures_dir: str, staging_id: str) -> Dict[str, Any]:
+    """bundle.json for a staging id, with ``path``; ValueError if there is none."""
+    path = bundle_dir(failures_dir, staging_id)
+    try:
+        with open(os.path.join(path, BUNDLE_FILE), 'r', encoding='utf-8') as f:
+            bundle = json.load(f)
+    except FileNotFoundError:
+        raise ValueError(f"No failure bundle for '{staging_id}' under {failures_dir}") from None
+    except json.JSONDecodeError as exc:
+        raise ValueError(f"{os.path.join(path, BUNDLE_FILE)}: {exc}") from None
+    bundle['path'] = path
+    return bundle
+
+
+def read_file(bundle: Dict[str, Any], name: str) -> str:
+    try:
+        with open(os.path.join(bundle['path'], name), 'r', encoding='utf-8') as f:
+            return f.read()
+    except FileNotFoundError:
+        return ''
+
+
+def format_bundle(bundle: Dict[str, Any]) -> str:
+    """The bundle as ``spokedpy failures show`` prints it."""
+    env = bundle.get('environment', {})
+    failed_at = time.strftime('%Y-%m-%dT%H:%M:%SZ', time.gmtime(bundle.get('failed_at', 0)))
+    lines = [
+        f"  {bundle['staging_id']}  {bundle.get('label', '')} ({bundle.get('language', '')}, "
+        f"slot {bundle.get('slot') or '—'}) — {bundle.get('phase', '')}",
+        f"  failed:      {failed_at}",
+        f"  exit status: {bundle.get('exit_status')}",
+        *([f"  spec_result: {bundle['spec_result']}"] if bundle.get('denied') else []),
+        f"  code_hash:   {bundle.get('code_hash', '')[:16]}",
+        f"  bundle:      {bundle['path']}",
+    ]
+    if bundle.get('spec_hash'):
+        lines.append(f"  spec_hash:   {bundle['spec_hash']}")
+
+    def section(title, text):
+        if text and text.strip():
+            lines.append(f"  ── {title} " + '─' * max(3, 58 - len(title)))
+            lines.extend(f"    {line}" for line in text.rstrip('\n').split('\n'))
+
+    section('error', bundle.get('error', ''))
+    section('compiler diagnostics', read_file(bundle, 'diagnostics.txt'))
+    if bundle.get('cases'):
+        section('spec cases', '\n'.join(
+            f"{spec_result(c.get('passed'), c.get('denied'))}  {c.get('name', '')}"
+            + (f" — {c['first_diff']}" if c.get('first_diff') else '')
+            + (f" (exit {c['exit_code']})" if c.get('exit_code') not in (None, 0) else '')
+            + (' (stopped)' if c.get('stopped') else '')
+            for c in bundle['cases']))
+    denied = [g for g in bundle.get('gates', []) if not g.get('allow')]
+    if denied:
+        section('gates', '\n'.join(f"{g.get('gate')}: {'; '.join(g.get('reasons') or [])}"
+                                   for g in denied))
+    if bundle.get('violations'):
+        from .scratch import describe
+        section('sandbox violations', describe(bundle['violations']))
+    section('stdout', read_file(bundle, 'stdout.txt'))
+    section('stderr', read_file(bundle, 'stderr.txt'))
+    fixtures = read_file(bundle, 'fixtures.json')
+    if fixtures:
+        inputs = [c for c in json.loads(fixtures) if c.get('args') or c.get('stdin') or c.get('env')]
+        section('fixture inputs', '\n'.join(
+            f"{c.get('name', '')}: " + ', '.join(
+                f"{k}={json.dumps(c[k])}" for k in ('args', 'stdin', 'env') if c.get(k))
+            for c in inputs))
+    section('environment', '\n'.join(f"{k}: {v}" for k, v in env.items()))
+    return '\n'.join(lines)
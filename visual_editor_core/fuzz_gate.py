@@ -0,0 +1,214 @@
+"""
+Fuzz Gate — a short cargo-fuzz session before a Rust snippet is promoted.
+
+A Rust snippet opts in by naming the function to fuzz in a header hint:
+
+    // fuzz_target: parse_record            fn parse_record(data: &[u8]) …
+    // fuzz_target: parse_record(&str)      fn parse_record(s: &str) …
+
+The gate builds a throwaway crate around the snippet (the snippet becomes
+``src/lib.rs``; the target is made ``pub``) plus a libFuzzer harness, and
+runs ``cargo fuzz run`` for ``seconds`` inside the engine sandbox (offline
+env, network namespace — see offline.apply):
+
+    <tmp>/Cargo.toml                          lib crate  spokedpy_snippet
+    <tmp>/src/lib.rs                          the snippet
+    <tmp>/fuzz/Cargo.toml                     libfuzzer-sys harness crate
+    <tmp>/fuzz/fuzz_targets/snippet.rs        fuzz_target!(|data| target(data))
+
+Any crash, OOM, timeout or leak DENIES.  The input that triggered it is
+copied to the fixtures dir (``[gates] fuzz_fixtures_dir`` →
+data/fuzz_fixtures/<label>/) and replayed as seed corpus on every later run
+for that label, so a fix is only promotable once the old crash is gone.
+
+Snippets that are not Rust, or carry no ``fuzz_target:`` hint, are allowed
+with a "skipped" reason.  A harness that cannot be built (no cargo-fuzz, no
+nightly toolchain, target signature mismatch) denies — a check that cannot
+run never waves a snippet through.
+"""
+
+import os
+import re
+import shutil
+import subprocess
+import tempfile
+from dataclasses import dataclass
+from typing import Callable, Dict, List, Optional
+
+from .promotion_gates import GateDecision, GateError, PromotionGate
+
+
+DEFAULT_SECONDS = 30
+BUILD_TIMEOUT = 600                 # seconds allowed for compiling the harness
+TARGET_NAME = 'snippet'
+ARG_TYPES = ('&[u8]', '&str')
+
+_HINT = re.compile(r'^\s*(?://|#|/\*)\s*fuzz_target:\s*([A-Za-z_]\w*)\s*(?:\(([^)]*)\))?', re.M)
+_ARTIFACT_PREFIXES = ('crash-', 'oom-', 'timeout-', 'leak-', 'slow-unit-')
+
+
+@dataclass
+class FuzzTarget:
+    """The function a ``fuzz_target:`` hint names."""
+    function: str
+    arg_type: str = '&[u8]'
+
+
+def parse_fuzz_target(body: str) -> Optional[FuzzTarget]:
+    """The first ``fuzz_target:`` hint in the snippet, if any."""
+    match = _HINT.search(body)
+    if match is None:
+        return None
+    arg_type = (match.group(2) or '&[u8]').replace(' ', '')
+    if arg_type not in ARG_TYPES:
+        raise GateError(f"fuzz_target {match.group(1)}: unsupported argument type "
+                        f"'{match.group(2)}' (use {' or '.join(ARG_TYPES)})")
+    return FuzzTarget(match.group(1), arg_type)
+
+
+def fuzz_harness(body: str, target: FuzzTarget) -> Dict[str, str]:
+    """Relative path → contents of the crate cargo-fuzz builds."""
+    exported = re.sub(rf'(^|\n)(\s*)(?:pub(?:\([^)]*\))?\s+)?fn\s+{target.function}\b',
+                      rf'\1\2pub fn {target.function}', body, count=1)
+    if exported == body and not re.search(rf'\bpub\s+fn\s+{target.function}\b', body):
+        raise GateError(f"fuzz_target names '{target.function}', but the snippet "
+                        f"defines no fn {target.function}")
+    if target.arg_type == '&str':
+        call = (f"if let Ok(s) = std::str::from_utf8(data) "
+                f"{{ let _ = spokedpy_snippet::{target.function}(s); }}")
+    else:
+        call = f"let _ = spokedpy_snippet::{target.function}(data);"
+    return {
+        'Cargo.toml': (
+            '[package]\nname = "spokedpy_snippet"\nversion = "0.1.0"\nedition = "2021"\n\n'
+            '[lib]\npath = "src/lib.rs"\n\n[workspace]\n'
+        ),
+        'src/lib.rs': '#![allow(dead_code, unused)]\n' + exported + '\n',
+        'fuzz/Cargo.toml': (
+            '[package]\nname = "spokedpy_snippet-fuzz"\nversion = "0.0.0"\n'
+            'publish = false\nedition = "2021"\n\n'
+            '[package.metadata]\ncargo-fuzz = true\n\n'
+            '[dependencies]\nlibfuzzer-sys = "0.4"\n'
+            'spokedpy_snippet = { path = ".." }\n\n'
+            f'[[bin]]\nname = "{TARGET_NAME}"\npath = "fuzz_targets/{TARGET_NAME}.rs"\n'
+            'test = false\ndoc = false\nbench = false\n\n'
+            '[workspace]\nmembers = ["."]\n'
+        ),
+        f'fuzz/fuzz_targets/{TARGET_NAME}.rs': (
+            '#![no_main]\nuse libfuzzer_sys::fuzz_target;\n\n'
+            f'fuzz_target!(|data: &[u8]| {{\n    {call}\n}});\n'
+        ),
+    }
+
+
+def _slug(label: str) -> str:
+    return re.sub(r'[^A-Za-z0-9_.-]+', '_', label.strip()).strip('_') or 'unlabelled'
+
+
+def _default_runner(*args, **kwargs):
+    from .execution_engine import _run_subprocess
+    return _run_subprocess(*args, **kwargs)
+
+
+class FuzzGate(PromotionGate):
+    """Built-in gate: cargo-fuzz the snippet's ``fuzz_target:`` function."""
+
+    kind = 'builtin'
+
+    def __init__(self, name: str = 'fuzz', seconds: float = DEFAULT_SECONDS,
+                 fixtures_dir: str = '', toolchain: str = 'nightly',
+                 runner: Callable = _default_runner, cargo: Optional[str] = None):
+        super().__init__(name)
+        if seconds <= 0:
+            raise ValueError('fuzz seconds must be positive')
+        self.seconds = seconds
+        self.fixtures_dir = fixtures_dir
+        self.toolchain = toolchain
+        self._runner = runner
+        self._cargo = cargo
+
+    def fixtures_for(self, label: str) -> str:
+        return os.path.join(self.fixtures_dir, _slug(label)) if self.fixtures_dir else ''
+
+    def check(self, header, body) -> GateDecision:
+        if header.get('language') != 'rust':
+            return GateDecision(self.name, True, ['skipped: not a Rust snippet'])
+        target = parse_fuzz_target(body)
+        if target is None:
+            return GateDecision(self.name, True, ['skipped: no fuzz_target: hint in the snippet'])
+        cargo = self._cargo or shutil.which('cargo')
+        if not cargo:
+            raise GateError('cargo not found on PATH — install Rust and `cargo install cargo-fuzz`')
+
+        with tempfile.TemporaryDirectory(prefix='spokedpy_fuzz_') as crate:
+            for rel, text in fuzz_harness(body, target).items():
+                path = os.path.join(crate, rel)
+                os.makedirs(os.path.dirname(path), exist_ok=True)
+                with open(path, 'w', encoding='utf-8') as f:
+                    f.write(text)
+            corpus = os.path.join(crate, 'fuzz', 'corpus', TARGET_NAME)
+            os.makedirs(corpus)
+            argv = [cargo, 'fuzz', 'run', TARGET_NAME, corpus]
+            fixtures = self.fixtures_for(header.get('label', ''))
+            if fixtures and os.path.isdir(fixtures):
+                argv.append(fixtures)
+            argv += ['--', f'-max_total_time={max(1, int(self.seconds))}']
+            env = {**os.environ, 'RUSTUP_TOOLCHAIN': self.toolchain} if self.toolchain else None
+            try:
+                proc = self._runner(argv, cwd=crate, env=env, capture_output=True, text=True,
+                                    timeout=self.seconds + BUILD_TIMEOUT)
+            except subprocess.TimeoutExpired:
+                raise GateError(f"cargo fuzz did not finish within {self.seconds + BUILD_TIMEOUT:.0f}s")
+            except OSError as exc:
+                raise GateError(f"cannot run cargo fuzz: {exc}")
+
+            output = (proc.stderr or '') + (proc.stdout or '')
+            artifacts = self._artifacts(os.path.join(crate, 'fuzz', 'artifacts', TARGET_NAME))
+            if artifacts:
+                saved = self._persist(artifacts, fixtures)
+                return GateDecision(self.name, False, self._crash_reasons(target, artifacts,
+                                                                          saved, output))
+            if proc.returncode != 0:
+                tail = '\n'.join(output.strip().splitlines()[-8:])
+                raise GateError(f"fuzz harness did not build or run (exit {proc.returncode}): {tail}")
+
+        runs = re.findall(r'Done (\d+) runs', output)
+        detail = f", {int(runs[-1]):,} runs" if runs else ''
+        return GateDecision(self.name, True,
+                            [f"fuzzed {target.function} for {self.seconds:g}s{detail}, no crashes"])
+
+    @staticmethod
+    def _artifacts(directory: str) -> List[str]:
+        if not os.path.isdir(directory):
+            return []
+        return [os.path.join(directory, n) for n in sorted(os.listdir(directory))
+                if n.startswith(_ARTIFACT_PREFIXES)]
+
+    @staticmethod
+    def _persist(artifacts: List[str], fixtures: str) -> List[str]:
+        if not fixtures:
+            return []
+        os.makedirs(fixtures, exist_ok=True)
+        saved = []
+        for path in artifacts:
+            dest = os.path.join(fixtures, os.path.basename(path))
+            shutil.copyfile(path, dest)
+            saved.append(dest)
+        return saved
+
+    @staticmethod
+    def _crash_reasons(target: FuzzTarget, artifacts: List[str], saved: List[str],
+                       output: str) -> List[str]:
+        panic = next((line.strip() for line in output.splitlines()
+                      if 'panicked at' in line or 'ERROR: libFuzzer' in line), '')
+        reasons = [f"fuzzing {target.function} found {len(artifacts)} failing input(s): "
+                   f"{', '.join(os.path.basename(a) for a in artifacts)}"]
+        if panic:
+            reasons.append(panic[:300])
+        if saved:
+            reasons.append(f"saved as regression fixture(s) in {os.path.dirname(saved[0])}")
+        return reasons
+
+    def describe(self):
+        return {**super().describe(), 'seconds': self.seconds,
+                'fixtures_dir': self.fixtures_dir, 'toolchain': self.toolchain}
@@ -0,0 +1,142 @@
+"""
+Header Migration — rewrite promoted snippet files to the current banner layout.
+
+    spokedpy migrate-headers [--dry-run] [--backup-dir DIR]
+
+walks the snippets dir once and brings every promoted file whose
+``banner_version`` is older than BANNER_VERSION up to date, one step at a
+time (see MIGRATIONS):
+
+    1 → 2    add the ``banner_version:`` line; replace the 16-digit
+             ``code_hash`` with the full sha256 of the body
+
+A file whose body no longer matches its code_hash is skipped, not migrated:
+hashing the edited body would bless the edit.  Files with a banner_version
+this spokedpy does not know are skipped too.  Before the first write every
+file about to change is copied to the backup dir (relative paths kept);
+each rewrite is atomic and keeps the file's line endings and mtime.
+"""
+
+import hashlib
+import os
+import shutil
+import time
+from dataclasses import dataclass, field
+from typing import Callable, Dict, List, Tuple
+
+from .snippet_staging import BANNER_VERSION, SnippetFileHeader, parse_file_header
+
+
+def _v1_to_v2(header: SnippetFileHeader, lines: List[str]) -> List[str]:
+    expected = header.fields.get('code_hash', '').rstrip('…')
+    body = header.body
+    candidates = [body] + ([body[:-1]] if body.endswith('\n') else [])
+    full = next((h for h in (hashlib.sha256(c.encode('utf-8')).hexdigest() for c in candidates)
+                 if expected and h.startswith(expected)), None)
+    if full is None:
+        raise ValueError('body no longer matches code_hash (edited after promotion?)')
+    lines = list(lines)
+    lines[header.field_lines['code_hash']] = f"{header.prefix}  code_hash:   {full}"
+    lines.insert(2, f"{header.prefix}  banner_version: 2")
+    return lines
+
+
+# version → rewrite of a header in that layout into the next one
+MIGRATIONS: Dict[int, Callable[[SnippetFileHeader, List[str]], List[str]]] = {
+    1: _v1_to_v2,
+}
+
+
+def migrate_text(text: str) -> Tuple[str, int]:
+    """(text in the current layout, the version it was in); ValueError if it cannot be migrated."""
+    header = parse_file_header(text)
+    if header is None:
+        raise ValueError('not a promoted snippet file')
+    original = header.version
+    if original == 0 or original > BANNER_VERSION:
+        raise ValueError(f"unknown banner_version '{header.fields.get('banner_version')}'")
+    while header.version < BANNER_VERSION:
+        text = '\n'.join(MIGRATIONS[header.version](header, text.split('\n')))
+        header = parse_file_header(text)
+    return text, original
+
+
+@dataclass
+class MigrationReport:
+    snippets_dir: str
+    backup_dir: str = ''
+    dry_run: bool = False
+    migrated: List[Tuple[str, int]] = field(default_factory=list)    # (path, from version)
+    current: int = 0                                                  # already up to date
+    skipped: List[Tuple[str, str]] = field(default_factory=list)     # (path, reason)
+
+
+def _snippet_files(snippets_dir: str) -> List[str]:
+    found = []
+    for root, _dirs, names in os.walk(snippets_dir):
+        found.extend(os.path.join(root, name) for name in sorted(names))
+    return sorted(found)
+
+
+def migrate_headers(snippets_dir: str, backup_dir: str = '',
+                    dry_run: bool = False) -> MigrationReport:
+    """Migrate every promoted file under ``snippets_dir`` (see the module docstring)."""
+    snippets_dir = os.path.abspath(snippets_dir)
+    backup_dir = backup_dir or (snippets_dir.rstrip(os.sep) + '.backup-'
+                                + time.strftime('%Y%m%dT%H%M%SZ', time.gmtime()))
+    report = MigrationReport(snippets_dir, backup_dir, dry_run)
+    pending = []
+    for path in _snippet_files(snippets_dir):
+        try:
+            with open(path, 'rb') as f:
+                raw = f.read()
+            text = raw.decode('utf-8').replace('\r\n', '\n')
+        except (OSError, UnicodeDecodeError):
+            continue
+        header = parse_file_header(text)
+        if header is None:
+            continue                                  # spec sidecars, golden files
+        if header.version == BANNER_VERSION:
+            report.current += 1
+            continue
+        try:
+            migrated, version = migrate_text(text)
+        except ValueError as exc:
+            report.skipped.append((path, str(exc)))
+            continue
+        pending.append((path, migrated, version, b'\r\n' in raw))
+
+    if dry_run or not pending:
+        report.migrated = [(path, version) for path, _text, version, _crlf in pending]
+        if not pending:
+            report.backup_dir = ''
+        return report
+
+    for path, _text, _version, _crlf in pending:
+        target = os.path.join(backup_dir, os.path.relpath(path, snippets_dir))
+        os.makedirs(os.path.dirname(target), exist_ok=True)
+        shutil.copy2(path, target)
+    for path, text, version, crlf in pending:
+        stat = os.stat(path)
+        tmp_path = path + '.migrating'
+        with open(tmp_path, 'w', encoding='utf-8', newline='\r\n' if crlf else '\n') as f:
+            f.write(text)
+        os.replace(tmp_path, path)
+        os.utime(path, ns=(stat.st_atime_ns, stat.st_mtime_ns))
+        report.migrated.append((path, version))
+    return report
+
+
+def format_migration(report: MigrationReport) -> str:
+    verb = 'would migrate' if report.dry_run else 'migrated'
+    lines = [f"  {verb} {len(report.migrated)} file(s) to banner_version {BANNER_VERSION}, "
+             f"{report.current} already current, {len(report.skipped)} skipped "
+             f"({report.snippets_dir})"]
+    for path, version in report.migrated:
+        lines.append(f"    ✔ v{version} → v{BANNER_VERSION}  "
+                     f"{os.path.relpath(path, report.snippets_dir)}")
+    for path, reason in report.skipped:
+        lines.append(f"    ✘ {os.path.relpath(path, report.snippets_dir)} — {reason}")
+    if report.migrated and not report.dry_run:
+        lines.append(f"  backup: {report.backup_dir}")
+    return '\n'.join(lines)
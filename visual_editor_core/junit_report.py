@@ -0,0 +1,70 @@
+"""
+JUnit Report — staged snippets' spec results as JUnit XML for CI.
+
+One ``<testsuite>`` per snippet, one ``<testcase>`` per spec case (or a
+single ``dry-run`` case for snippets without a spec suite):
+
+    <testsuites tests="4" failures="1">
+      <testsuite name="etl (stg-1a2b3c4d5e6f)" tests="3" failures="1" time="0.062">
+        <testcase classname="python.etl" name="greets" time="0.021"/>
+        <testcase classname="python.etl" name="counts stdin" time="0.020">
+          <failure message="line 1: expected '3', got '4'">…</failure>
+          <system-out>4</system-out>
+        </testcase>
+        …
+
+Input is the snippet dicts the server returns (StagedSnippet.to_dict) or
+what ``spokedpy show`` reads back from a promoted file's header.
+"""
+
+import xml.etree.ElementTree as ET
+from typing import Any, Dict, Iterable, List
+
+
+def _cases(snippet: Dict[str, Any]) -> List[Dict[str, Any]]:
+    if snippet.get('spec_cases'):
+        return snippet['spec_cases']
+    error = snippet.get('spec_error', '')
+    return [{
+        'name': 'dry-run',
+        'passed': bool(snippet.get('spec_success')),
+        'duration': snippet.get('spec_execution_time', 0.0),
+        'first_diff': error.strip().splitlines()[0] if error.strip() else '',
+        'error': error,
+        'output': snippet.get('spec_output', ''),
+    }]
+
+
+def junit_xml(snippets: Iterable[Dict[str, Any]]) -> str:
+    """A ``<testsuites>`` document for the given snippets."""
+    root = ET.Element('testsuites')
+    total = failures = 0
+    for snippet in snippets:
+        cases = _cases(snippet)
+        failed = [c for c in cases if not c.get('passed')]
+        label = snippet.get('label', '')
+        suite = ET.SubElement(root, 'testsuite', {
+            'name': f"{label} ({snippet.get('staging_id', '')})",
+            'tests': str(len(cases)),
+            'failures': str(len(failed)),
+            'time': f"{sum(c.get('duration', 0.0) for c in cases):.3f}",
+        })
+        classname = f"{snippet.get('language', '')}.{label}".strip('.')
+        for case in cases:
+            testcase = ET.SubElement(suite, 'testcase', {
+                'classname': classname, 'name': case['name'],
+                'time': f"{case.get('duration', 0.0):.3f}",
+            })
+            if not case.get('passed'):
+                failure = ET.SubElement(testcase, 'failure',
+                                        {'message': case.get('first_diff') or 'failed'})
+                failure.text = case.get('error') or case.get('first_diff') or ''
+            if case.get('output'):
+                ET.SubElement(testcase, 'system-out').text = case['output']
+        total += len(cases)
+        failures += len(failed)
+    root.set('tests', str(total))
+    root.set('failures', str(failures))
+    if hasattr(ET, 'indent'):                   # Python 3.9+
+        ET.indent(root)
+    return '<?xml version="1.0" encoding="UTF-8"?>\n' + ET.tostring(root, encoding='unicode') + '\n'
@@ -0,0 +1,199 @@
+"""
+Jupyter Kernel — notebook cells run by SpokedPy engines, promotable to slots.
+
+An optional Jupyter kernel (needs ``pip install ipykernel``) that sends every
+cell to the running server's staging sandbox — the same path as
+``spokedpy repl`` — so a notebook can mix languages and hand a finished
+cell straight to the promotion workflow:
+
+    ┌──────────────────────────────────────────┬─────────────────────────────┐
+    │  <plain cell>                            │  run in the current language│
+    │  %%spoked run --lang rust                │  run this cell as Rust      │
+    │  %%spoked stage --label Fib --slot d3    │  queue + speculate the cell │
+    │  %%spoked lang go                        │  switch the default language│
+    └──────────────────────────────────────────┴─────────────────────────────┘
+
+``--slot`` is an engine row letter or address; without ``--lang`` the slot's
+row picks the language (``d3`` → rust).  Staged cells wait for review:
+``spokedpy promote --interactive <staging_id>``.
+
+Install the kernelspec with ``spokedpy jupyter install``; the kernel reads
+the server URL from ``SPOKEDPY_SERVER`` (written into the kernelspec).
+"""
+
+import argparse
+import io
+import json
+import os
+import shlex
+import sys
+import tempfile
+from contextlib import redirect_stderr
+from dataclasses import dataclass
+from typing import List, Optional, Tuple
+
+from .node_registry import LETTER_TO_ENGINE, resolve_language_string
+from .snippet_repl import ReplError, SnippetRepl, remote_backend
+
+
+KERNEL_NAME = 'spokedpy'
+MAGIC = '%%spoked'
+DEFAULT_SERVER = 'http://127.0.0.1:5002'
+
+
+class CellMagicError(ValueError):
+    """A ``%%spoked`` line could not be parsed."""
+
+
+@dataclass
+class CellRequest:
+    action: str                 # 'run' | 'stage' | 'lang'
+    body: str
+    language: str = ''
+    label: str = ''
+    slot: str = ''
+
+
+def _magic_parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(prog=MAGIC, add_help=False, exit_on_error=False)
+    parser.add_argument('action', nargs='?', default='run', choices=('run', 'stage', 'lang'))
+    parser.add_argument('language_arg', nargs='?', default='')
+    parser.add_argument('--lang', default='')
+    parser.add_argument('--label', default='')
+    parser.add_argument('--slot', default='')
+    return parser
+
+
+def parse_cell(code: str) -> CellRequest:
+    """Split a cell into its ``%%spoked`` options and body."""
+    first, _, body = code.partition('\n')
+    if not first.strip().startswith(MAGIC):
+        return CellRequest('run', code)
+    try:
+        words = shlex.split(first.strip()[len(MAGIC):])
+        with redirect_stderr(io.StringIO()):
+            args = _magic_parser().parse_args(words)
+    except (ValueError, argparse.ArgumentError, SystemExit) as exc:
+        raise CellMagicError(f"bad {MAGIC} line: {exc or 'invalid arguments'} — "
+                             f"usage: {MAGIC} [run|stage|lang] [--lang L] [--label X] [--slot S]")
+
+    language = args.lang or args.language_arg
+    if args.action != 'lang' and args.language_arg:
+        raise CellMagicError(f"unexpected argument '{args.language_arg}' — use --lang")
+    if args.action == 'lang' and not language:
+        raise CellMagicError(f"usage: {MAGIC} lang <language>")
+    if not language and args.slot:
+        engine = LETTER_TO_ENGINE.get(args.slot[:1].lower())
+        if engine is None:
+            raise CellMagicError(f"unknown slot '{args.slot}'")
+        language = resolve_language_string(engine.lang_id)
+    if args.action == 'stage' and not args.label:
+        raise CellMagicError(f"{MAGIC} stage needs --label")
+    return CellRequest(args.action, body, language.lower(), args.label, args.slot)
+
+
+class CellRunner:
+    """Executes notebook cells through a SnippetRepl; kernel-independent."""
+
+    def __init__(self, language: str, evaluate, stage):
+        self.language = language
+        self._evaluate, self._stage = evaluate, stage
+
+    def execute(self, code: str) -> Tuple[str, bool]:
+        """Run one cell; returns (text to show, ok)."""
+        out: List[str] = []
+        try:
+            request = parse_cell(code)
+            if request.action == 'lang':
+                SnippetRepl(request.language, self._evaluate, self._stage)   # validates
+                self.language = request.language
+                return f"  default language: {self.language}", True
+            repl = SnippetRepl(request.language or self.language,
+                               self._evaluate, self._stage, out=out.append)
+            if not request.body.strip():
+                raise ReplError('empty cell')
+            repl.set_buffer(request.body)
+            if request.action == 'stage':
+                snippet = repl.stage(request.label, request.slot)
+                return '\n'.join(out), snippet.get('phase') != 'failed'
+            result = repl.run()
+            return '\n'.join(out), bool(result.get('success'))
+        except ValueError as exc:
+            out.append(f"  ✘ {exc}")
+            return '\n'.join(out), False
+        except Exception as exc:
+            if type(exc).__module__.startswith('requests'):
+                out.append(f"  ✘ cannot reach the SpokedPy server ($SPOKEDPY_SERVER): {exc}")
+            else:
+                out.append(f"  ✘ {type(exc).__name__}: {exc}")
+            return '\n'.join(out), False
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# IPYKERNEL GLUE
+# ═══════════════════════════════════════════════════════════════════════════
+
+def make_kernel_class():
+    """The ipykernel Kernel subclass (imported lazily — ipykernel is optional)."""
+    try:
+        from ipykernel.kernelbase import Kernel
+    except ImportError:
+        raise ImportError("the Jupyter kernel needs ipykernel — pip install ipykernel")
+
+    class SpokedKernel(Kernel):
+        implementation = KERNEL_NAME
+        implementation_version = '1.0'
+        language = 'python'
+        language_info = {'name': 'python', 'mimetype': 'text/x-python', 'file_extension': '.py'}
+        banner = ('SpokedPy — cells run in the staging sandbox; '
+                  f'{MAGIC} stage --label NAME --slot d3 stages a cell')
+
+        def __init__(self, **kwargs):
+            super().__init__(**kwargs)
+            self.runner = CellRunner(
+                os.environ.get('SPOKEDPY_KERNEL_LANG', 'python'),
+                *remote_backend(os.environ.get('SPOKEDPY_SERVER', DEFAULT_SERVER).rstrip('/'),
+                                author=os.environ.get('USER', 'notebook')),
+            )
+
+        def do_execute(self, code, silent, store_history=True, user_expressions=None,
+                       allow_stdin=False, **kwargs):
+            text, ok = self.runner.execute(code)
+            if not silent and text:
+                self.send_response(self.iopub_socket, 'stream',
+                                   {'name': 'stdout' if ok else 'stderr', 'text': text + '\n'})
+            if ok:
+                return {'status': 'ok', 'execution_count': self.execution_count,
+                        'payload': [], 'user_expressions': {}}
+            return {'status': 'error', 'execution_count': self.execution_count,
+                    'ename': 'SpokedPyError', 'evalue': text.strip().splitlines()[-1] if text.strip() else '',
+                    'traceback': []}
+
+    return SpokedKernel
+
+
+def kernel_spec(server_url: str, python: str = sys.executable) -> dict:
+    root = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+    return {
+        'argv': [python, '-m', 'visual_editor_core.jupyter_kernel', '-f', '{connection_file}'],
+        'display_name': 'SpokedPy',
+        'language': 'python',
+        'env': {'SPOKEDPY_SERVER': server_url, 'PYTHONPATH': root},
+    }
+
+
+def install_kernelspec(server_url: str, user: bool = True, prefix: Optional[str] = None) -> str:
+    """Register the kernel with Jupyter; returns the kernelspec directory."""
+    try:
+        from jupyter_client.kernelspec import KernelSpecManager
+    except ImportError:
+        raise ImportError("installing the kernel needs jupyter_client — pip install ipykernel")
+    with tempfile.TemporaryDirectory() as tmp:
+        with open(os.path.join(tmp, 'kernel.json'), 'w', encoding='utf-8') as f:
+            json.dump(kernel_spec(server_url), f, indent=2)
+        return KernelSpecManager().install_kernel_spec(tmp, KERNEL_NAME, user=user, prefix=prefix)
+
+
+if __name__ == '__main__':
+    from ipykernel.kernelapp import IPKernelApp
+    IPKernelApp.launch_instance(kernel_class=make_kernel_class())
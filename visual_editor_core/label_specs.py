@@ -0,0 +1,105 @@
+"""
+Label Specs — a default spec every snippet of a label inherits.
+
+A label's contract lives in the specs dir (``[store] specs_dir`` →
+data/specs), one directory per label:
+
+    data/specs/Fibonacci/
+        default.spec.toml           # the cases every Fibonacci snippet must pass
+        fixtures/terms.txt          # its fixtures, relative to the directory
+        snapshots/default__first_ten.snap
+
+A label with a ``/`` (``--label-from path``) is a nested directory.  The
+file is a spec sidecar like any other (``default.spec.yaml`` works too):
+cases, fixtures and snapshot golden files as in spec_suite.
+
+Staging a snippet under that label — in any engine — runs the label's
+cases first, then the snippet's own sidecar cases, if it has one.  A
+snippet may add cases but not replace an inherited one: a case of the
+same name is refused, as is a fixture of the same path with other
+text.  The inherited cases count towards the snippet's ``spec_hash``,
+so an edit to the label spec changes every snippet held to it; the
+suite records ``inherits`` (the label) and which cases came from it,
+and the promoted header a ``spec_inherits:`` line.  Only the snippet's
+own sidecar is copied next to the promoted file — the label spec stays
+in the specs dir, where the LSP hashes it with the sidecar.  A label
+spec that does not parse refuses staging under its label rather than
+letting snippets through unchecked.
+"""
+
+import os
+from typing import List, Optional
+
+from .spec_suite import SPEC_SUFFIXES, SpecSuite, load_spec
+
+DEFAULT_STEM = 'default'
+
+
+def label_dir(specs_dir: str, label: str) -> Optional[str]:
+    """``<specs_dir>/<label>/``, or None for a label that is not a relative path."""
+    path = label.strip().replace('\\', '/')
+    parts = [p for p in path.split('/') if p not in ('', '.')]
+    if not parts or '..' in parts or path.startswith('/') or os.path.isabs(path):
+        return None
+    return os.path.join(specs_dir, *parts)
+
+
+def inherit(base: SpecSuite, own: Optional[SpecSuite], label: str) -> SpecSuite:
+    """``base``'s cases then ``own``'s; ValueError if ``own`` redefines one of them."""
+    inherited = [c.name for c in base.cases]
+    if own is None:                     # no sidecar of its own to travel with the file
+        return SpecSuite(list(base.cases), '', base.format, dict(base.fixtures), label, inherited)
+    clash = sorted(set(inherited) & {c.name for c in own.cases})
+    if clash:
+        raise ValueError(f"spec case(s) {', '.join(repr(n) for n in clash)} are inherited from "
+                         f"label '{label}' — a snippet's spec may add cases, not replace them")
+    fixtures = dict(base.fixtures)
+    for path, text in own.fixtures.items():
+        if text is not None and fixtures.get(path) not in (None, text):
+            raise ValueError(f"fixture '{path}' differs from the one label '{label}' provides")
+        if text is not None or path not in fixtures:
+            fixtures[path] = text
+    return SpecSuite(list(base.cases) + list(own.cases), own.source, own.format, fixtures,
+                     label, inherited)
+
+
+class LabelSpecs:
+    """The default specs under ``specs_dir``, read afresh on each use."""
+
+    def __init__(self, specs_dir: str):
+        self.specs_dir = specs_dir
+
+    def path_for(self, label: str) -> Optional[str]:
+        """The label's ``default.spec.*``, if it has one."""
+        folder = label_dir(self.specs_dir, label) if label else None
+        if folder is None:
+            return None
+        for suffix in SPEC_SUFFIXES:
+            path = os.path.join(folder, DEFAULT_STEM + suffix)
+            if os.path.isfile(path):
+                return path
+        return None
+
+    def default_for(self, label: str) -> Optional[SpecSuite]:
+        """The label's default spec, loaded; ValueError if it is malformed."""
+        path = self.path_for(label)
+        if path is None:
+            return None
+        try:
+            return load_spec(path)
+        except ValueError as exc:
+            raise ValueError(f"label spec {path}: {exc}") from None
+
+    def apply(self, label: str, spec: Optional[SpecSuite]) -> Optional[SpecSuite]:
+        """``spec`` extended with the label's default spec (``spec`` itself if it has none)."""
+        base = self.default_for(label)
+        return spec if base is None else inherit(base, spec, label)
+
+    def labels(self) -> List[str]:
+        """Every label with a default spec, sorted."""
+        found = []
+        for folder, dirs, names in os.walk(self.specs_dir):
+            dirs[:] = sorted(d for d in dirs if d != 'snapshots' and not d.startswith('.'))
+            if any(DEFAULT_STEM + suffix in names for suffix in SPEC_SUFFIXES):
+                found.append(os.path.relpath(folder, self.specs_dir).replace(os.sep, '/'))
+        return sorted(label for label in found if label != '.')
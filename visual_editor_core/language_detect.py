@@ -0,0 +1,206 @@
+"""
+Language Detection — which engine a source file is for, and how sure that is.
+
+A file carrying a spokedpy banner keeps the language it records.
+Otherwise the evidence is, strongest first:
+
+    ┌────────────┬───────────────────────────────────────────┬────────────┐
+    │  extension │  LANG_EXTENSIONS plus aliases (.mjs, .cc, │  0.90      │
+    │            │  .kts …); raised by probes that agree     │  – 1.00    │
+    │  #! line   │  the interpreter (env, flags and version  │  0.95      │
+    │            │  suffixes stripped)                       │            │
+    │  probes    │  syntax that only some languages have     │  its share │
+    │            │  (PROBES); a Python candidate must also   │  of the    │
+    │            │  compile                                  │  hits      │
+    └────────────┴───────────────────────────────────────────┴────────────┘
+
+An extension shared by several languages (``.h``: C or C++) narrows the
+probes to those.  Probes alone decide only with MIN_HITS hits and a share
+of at least MIN_CONFIDENCE, MIN_MARGIN ahead of the runner-up; anything
+less is ambiguous, and ``spokedpy stage`` / ``ingest`` refuse the file
+until ``--lang`` says.  A detected language is recorded on the snippet
+as ``lang-detected-by`` and ``lang-confidence`` metadata.
+"""
+
+import os
+import re
+from dataclasses import dataclass, field
+from typing import Dict, List, Tuple
+
+from .errors import ErrorKind, SpokedError
+from .snippet_staging import LANG_EXTENSIONS, parse_file_header
+
+EXTENSIONS: Dict[str, str] = {
+    **{ext: lang for lang, ext in LANG_EXTENSIONS.items()},
+    '.pyw': 'python', '.mjs': 'javascript', '.cjs': 'javascript', '.mts': 'typescript',
+    '.cc': 'cpp', '.cxx': 'cpp', '.hpp': 'cpp', '.hh': 'cpp', '.kts': 'kotlin',
+    '.bash': 'bash', '.pm': 'perl',
+}
+
+# Extensions several languages use: the probes pick among these
+SHARED_EXTENSIONS: Dict[str, Tuple[str, ...]] = {
+    '.h': ('c', 'cpp'),
+}
+
+# Interpreter of a #! line → language
+SHEBANGS: Dict[str, str] = {
+    'python': 'python', 'node': 'javascript', 'deno': 'typescript', 'ts-node': 'typescript',
+    'bash': 'bash', 'sh': 'bash', 'zsh': 'bash', 'perl': 'perl', 'ruby': 'ruby',
+    'rscript': 'r', 'kotlin': 'kotlin', 'swift': 'swift',
+}
+
+# language → (pattern, weight); each pattern counts once per file
+PROBES: Dict[str, List[Tuple[str, int]]] = {
+    'python':     [(r'^\s*def \w+\(.*\)\s*(->.*)?:\s*$', 2), (r'^\s*(from \w[\w.]* )?import \w', 1),
+                   (r'^\s*(elif|except)\b.*:\s*$', 2), (r'\bself\.\w', 1), (r'\bprint\(', 1),
+                   (r'^if __name__ == ', 3)],
+    'javascript': [(r'\bconsole\.log\(', 2), (r'\bfunction\s*\w*\s*\(', 1),
+                   (r'^\s*(const|let) \w+ =', 1), (r'\brequire\([\'"]', 2), (r'===|!==', 1),
+                   (r'\bmodule\.exports\b', 3)],
+    'typescript': [(r'^\s*(export )?interface \w+', 3), (r':\s*(string|number|boolean)\b', 2),
+                   (r'^\s*(export )?type \w+ =', 3), (r'\bconsole\.log\(', 1)],
+    'rust':       [(r'\bfn \w+\(', 2), (r'\blet mut\b', 3), (r'\bprintln!\(', 3),
+                   (r'^\s*use std::', 3), (r'^\s*impl\b', 2)],
+    'java':       [(r'\bpublic (final )?class\b', 2), (r'\bpublic static void main\(', 3),
+                   (r'\bSystem\.out\.print', 3)],
+    'kotlin':     [(r'^\s*fun \w+\(', 3), (r'^\s*val \w+', 1), (r'^\s*data class\b', 3)],
+    'swift':      [(r'^\s*func \w+\(', 3), (r'^\s*import (Foundation|UIKit)\b', 3),
+                   (r'\bguard let\b', 3)],
+    'cpp':        [(r'#include <(iostream|vector|string|map)>', 3), (r'\bstd::', 3),
+                   (r'^\s*template\s*<', 3), (r'^\s*namespace \w+', 2), (r'\bclass \w+\s*[:{]', 1)],
+    'c':          [(r'#include <(stdio|stdlib|string)\.h>', 3), (r'\bprintf\(', 1),
+                   (r'\bmalloc\(', 2), (r'^\s*int main\(', 1)],
+    'go':         [(r'^package \w+', 3), (r'^\s*func \w*\(', 1), (r'\bfmt\.\w+\(', 3), (r':=', 1)],
+    'ruby':       [(r'^\s*puts\b', 2), (r'^\s*def \w+[?!]?\s*(\(.*\))?\s*$', 2), (r'^\s*end\s*$', 1),
+                   (r'\.each do\s*\|', 3), (r'^\s*require [\'"]', 2)],
+    'csharp':     [(r'^\s*using System', 3), (r'\bConsole\.Write', 3), (r'\bstatic void Main\(', 3)],
+    'bash':       [(r'^\s*echo\b', 1), (r'^\s*(if|while) \[', 3), (r'^\s*(fi|done|esac)\s*$', 3),
+                   (r'\$\{?\w+\}?', 1)],
+    'perl':       [(r'^\s*use (strict|warnings);', 3), (r'^\s*my [$@%]', 3), (r'\$_\b', 1)],
+    'r':          [(r'\w+\s*<-\s*', 2), (r'\blibrary\(', 3), (r'\bfunction\(', 1), (r'\bcat\(', 1)],
+}
+
+MIN_HITS = 3                    # probe weight needed before probes alone decide
+MIN_CONFIDENCE = 0.6
+MIN_MARGIN = 0.2
+
+_COMPILED = {lang: [(re.compile(p, re.MULTILINE), w) for p, w in probes]
+             for lang, probes in PROBES.items()}
+_SHEBANG = re.compile(r'^#!\s*(\S+)(?:\s+(?:-\S+\s+)*(\S+))?')
+
+
+@dataclass
+class Detection:
+    language: str                       # '' when unknown or ambiguous
+    confidence: float = 0.0
+    how: str = ''                       # 'header', 'extension', 'shebang', 'probes'
+    candidates: List[Tuple[str, float]] = field(default_factory=list)   # probe shares, best first
+
+    @property
+    def ambiguous(self) -> bool:
+        return not self.language and bool(self.candidates)
+
+    def meta(self) -> Dict[str, str]:
+        """The snippet metadata recording how the language was told ({} for a banner / --lang)."""
+        if not self.language or self.how in ('header', 'flag'):
+            return {}
+        return {'lang-detected-by': self.how, 'lang-confidence': f"{self.confidence:.2f}"}
+
+    def describe(self) -> str:
+        if self.language:
+            return f"{self.language} ({self.how}, confidence {self.confidence:.2f})"
+        if self.candidates:
+            return 'ambiguous: ' + ', '.join(f"{lang} {share:.2f}"
+                                             for lang, share in self.candidates[:3])
+        return 'unknown'
+
+
+def shebang_language(text: str) -> str:
+    """The language of a ``#!`` first line ('' if none, or unknown)."""
+    match = _SHEBANG.match(text)
+    if match is None:
+        return ''
+    program = os.path.basename(match.group(1))
+    if program == 'env' and match.group(2):
+        program = os.path.basename(match.group(2))
+    program = re.sub(r'[\d.]+$', '', program.lower())         # python3.12 → python
+    return SHEBANGS.get(program, '')
+
+
+def _compiles(text: str) -> bool:
+    try:
+        compile(text, '<probe>', 'exec')
+    except (SyntaxError, ValueError):
+        return False
+    return True
+
+
+def probe_scores(text: str, among: Tuple[str, ...] = ()) -> Dict[str, int]:
+    """Probe weight each language scores on ``text`` (only ``among``, if given)."""
+    scores = {}
+    for lang, probes in _COMPILED.items():
+        if among and lang not in among:
+            continue
+        score = sum(weight for pattern, weight in probes if pattern.search(text))
+        if score:
+            scores[lang] = score
+    if 'python' in scores and not _compiles(text):
+        del scores['python']
+    return scores
+
+
+def _shares(scores: Dict[str, int]) -> List[Tuple[str, float]]:
+    total = sum(scores.values())
+    return sorted(((lang, round(score / total, 2)) for lang, score in scores.items()),
+                  key=lambda kv: (-kv[1], kv[0]))
+
+
+def detect(path: str, text: str) -> Detection:
+    """How sure we can be of ``path``'s language (see the module docstring)."""
+    header = parse_file_header(text)
+    if header is not None and header.fields.get('language'):
+        return Detection(header.fields['language'], 1.0, 'header')
+    ext = os.path.splitext(path)[1].lower()
+
+    language = EXTENSIONS.get(ext, '')
+    if language:
+        shares = dict(_shares(probe_scores(text)))
+        agree = shares.get(language, 0.0)
+        if shebang_language(text) == language:
+            agree = 1.0
+        return Detection(language, round(0.9 + 0.1 * agree, 2), 'extension')
+
+    language = shebang_language(text)
+    if language:
+        return Detection(language, 0.95, 'shebang')
+
+    among = SHARED_EXTENSIONS.get(ext, ())
+    scores = probe_scores(text, among)
+    candidates = _shares(scores)
+    if not candidates and among:
+        even = round(1 / len(among), 2)
+        return Detection('', how='extension', candidates=[(lang, even) for lang in among])
+    if not candidates:
+        return Detection('')
+    best, share = candidates[0]
+    runner_up = candidates[1][1] if len(candidates) > 1 else 0.0
+    if sum(scores.values()) >= (1 if among else MIN_HITS) and share >= MIN_CONFIDENCE \
+            and share - runner_up >= MIN_MARGIN:
+        return Detection(best, share, 'probes', candidates)
+    return Detection('', how='probes', candidates=candidates)
+
+
+def detect_language(path: str, text: str, explicit: str = '') -> Detection:
+    """
+    The language to stage ``path`` as: ``explicit`` (a --lang) if given,
+    else detect(); a usage SpokedError when that is unknown or ambiguous.
+    """
+    if explicit:
+        return Detection(explicit.lower().strip(), 1.0, 'flag')
+    found = detect(path, text)
+    if not found.language:
+        raise SpokedError(ErrorKind.USAGE, f"cannot tell the language of "
+                                           f"{os.path.basename(path)} ({found.describe()}) "
+                                           f"— pass --lang", path=path,
+                          candidates=[lang for lang, _share in found.candidates])
+    return found
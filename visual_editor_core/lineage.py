@@ -0,0 +1,114 @@
+"""
+Lineage — the chain of snippets a snippet was staged from.
+
+A snippet staged from an existing one records it as its ``parent``:
+
+    re-stage         the editor's "Re-stage this snippet" (snippet_lsp)
+    edit-and-stage   ``spokedpy stage`` of a promoted file, edited or not
+    import           ``spokedpy ingest`` of a tree holding promoted files
+
+or ``--parent`` / the ``parent`` body field when the caller knows better.
+The parent goes on the StagedSnippet and its SNIPPET_QUEUED audit event,
+and into the promoted file's header as a ``parent:`` line.
+``spokedpy lineage <staging_id>`` walks the parents back to the first
+version, newest first, each with the diff from the one it was staged
+from.
+
+An ancestor's code comes from wherever it is still kept: the store, its
+promoted file (which keeps the staging id in its name), or its failure
+bundle.  One whose code is gone — a run of an earlier server that was
+neither promoted nor bundled — is listed from the audit log, without a
+diff.  A chain stops at MAX_LINEAGE versions, and where it loops back.
+"""
+
+import difflib
+from dataclasses import asdict, dataclass, fields
+from typing import Any, Callable, Dict, List, Optional
+
+MAX_LINEAGE = 200
+
+SOURCE_STORE, SOURCE_FILE, SOURCE_BUNDLE, SOURCE_AUDIT = 'store', 'file', 'bundle', 'audit'
+
+
+@dataclass
+class Ancestor:
+    staging_id: str
+    parent: str = ''                        # '' → the first version
+    label: str = ''
+    language: str = ''
+    slot: str = ''
+    phase: str = ''                         # '' when only the audit log knows it
+    author: str = ''
+    code_hash: str = ''
+    created: str = ''
+    code: Optional[str] = None              # None → not kept anywhere
+    source: str = ''                        # where it was found ('' → nowhere)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'Ancestor':
+        known = {f.name for f in fields(cls)}
+        return cls(**{k: v for k, v in data.items() if k in known})
+
+
+def build_lineage(staging_id: str, find: Callable[[str], Optional[Ancestor]],
+                  limit: int = MAX_LINEAGE) -> List[Ancestor]:
+    """
+    ``staging_id`` and its ancestors, newest first.  ``find`` looks one up
+    (None: unknown); an unknown parent ends the chain as a bare entry.
+    """
+    chain: List[Ancestor] = []
+    seen = set()
+    current = staging_id
+    while current and current not in seen and len(chain) < limit:
+        seen.add(current)
+        found = find(current)
+        chain.append(found if found is not None else Ancestor(current))
+        current = chain[-1].parent
+    return chain
+
+
+def diff_from_parent(child: Ancestor, parent: Ancestor) -> str:
+    """Unified diff of ``child``'s code against ``parent``'s ('' if either is not kept)."""
+    if child.code is None or parent.code is None:
+        return ''
+    lines = difflib.unified_diff(parent.code.splitlines(), child.code.splitlines(),
+                                 fromfile=parent.staging_id, tofile=child.staging_id, lineterm='')
+    return ''.join(line + '\n' for line in lines)
+
+
+def with_diffs(chain: List[Ancestor]) -> List[Dict[str, Any]]:
+    """The chain as dicts, each with the ``diff`` from its parent."""
+    return [{**a.to_dict(), 'diff': diff_from_parent(a, chain[i + 1]) if i + 1 < len(chain)
+             else ''} for i, a in enumerate(chain)]
+
+
+def format_lineage(chain: List[Ancestor], diffs: bool = True) -> str:
+    """The chain as ``spokedpy lineage`` prints it."""
+    lines = []
+    for i, a in enumerate(chain):
+        parent = chain[i + 1] if i + 1 < len(chain) else None
+        if not a.source:
+            lines.append(f"  {a.staging_id}  (not found — no longer in the store or audit log)")
+            continue
+        who = f" by {a.author}" if a.author else ''
+        lines.append(f"  {a.staging_id}  {a.label} ({a.language}, {a.slot or '—'}) "
+                     f"{a.phase or '—'}  {a.created}{who}".rstrip())
+        if parent is None:
+            if not a.parent:
+                lines.append('    first version')
+            elif any(b.staging_id == a.parent for b in chain):
+                lines.append(f"    staged from {a.parent}, listed above — the chain loops")
+            else:
+                lines.append(f"    staged from {a.parent} — chain cut at {len(chain)} versions")
+        elif a.code is None or parent.code is None:
+            lines.append(f"    staged from {parent.staging_id} — code not kept, no diff")
+        elif a.code == parent.code:
+            lines.append(f"    staged from {parent.staging_id} — code unchanged")
+        else:
+            lines.append(f"    staged from {parent.staging_id}")
+            if diffs:
+                lines += ['      ' + line for line in diff_from_parent(a, parent).splitlines()]
+    return '\n'.join(lines)
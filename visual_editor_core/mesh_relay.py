@@ -246,6 +246,11 @@ class MeshRelay:
                 return True
             return False
 
+    def peers_for(self, local_addr: str) -> List[str]:
+        """The peers a local slot's output is relayed to."""
+        with self._lock:
+            return list(self._subscriptions.get(local_addr, []))
+
     # ─── Activation / Deactivation ───────────────────────────────────
 
     def activate(self) -> Dict[str, Any]:
@@ -742,6 +742,11 @@ class NodeRegistry:
                 return True
             return False
 
+    def subscribers(self, publisher_slot_id: str) -> List[str]:
+        """The slot_ids subscribed to a slot's output."""
+        with self._lock:
+            return list(self._channels.get(publisher_slot_id, []))
+
     def _broadcast_output(self, publisher_slot_id: str, output: str):
         """Push output to all subscribers of a slot."""
         subscribers = self._channels.get(publisher_slot_id, [])
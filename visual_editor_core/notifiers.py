@@ -0,0 +1,292 @@
+"""
+Notifiers — Slack and email messages for pipeline events.
+
+The staging pipeline emits six notifiable events:
+
+    ┌────────────────────┬──────────────────────────────────────────────────┐
+    │  spec_failure      │  dry-run failed or a promotion gate denied       │
+    │  spec_anomaly      │  spec_time far above the slot's rolling median   │
+    │  approval_request  │  snippet staged into a slot that has owners      │
+    │  promotion         │  snippet written to disk + committed to its slot │
+    │  rollback          │  promoted snippet pulled from production         │
+    │  degraded          │  promoted snippet fails revalidation: its slot   │
+    │                    │  is DEGRADED (see revalidation)                  │
+    └────────────────────┴──────────────────────────────────────────────────┘
+
+Each notifier subscribes to a subset (``[notify.slack] events = [...]``)
+and renders a ``string.Template`` per event; templates can be overridden
+in ``[notify.templates]``.  Placeholders:
+
+    $event $staging_id $slot $label $language $author $reason
+    $diff_link $code_hash $owners
+
+``$owners`` is set for approval_request and degraded (see
+snippet_owners); the email notifier also mails the owners that are email
+addresses.
+
+``$diff_link`` comes from ``[notify] diff_url`` with the same placeholders
+substituted, so it can point at whatever UI or code host the team uses.
+
+Delivery is best-effort and off the pipeline's thread: a Slack outage never
+blocks or fails a promotion.  Failures are kept in ``NotificationHub.errors``
+(and surfaced by ``GET /api/staging/notifications``).
+"""
+
+import json
+import smtplib
+import threading
+import time
+import urllib.request
+from abc import ABC, abstractmethod
+from collections import deque
+from dataclasses import dataclass, asdict
+from email.message import EmailMessage
+from string import Template
+from typing import Any, Callable, Dict, Iterable, List, Optional
+
+from . import offline
+
+
+NOTIFY_EVENTS = ('spec_failure', 'spec_anomaly', 'approval_request', 'promotion', 'rollback',
+                 'degraded')
+
+DEFAULT_TEMPLATES = {
+    'spec_failure': ("❌ Spec failure — $label ($language) for slot $slot\n"
+                     "Author: $author\nReason: $reason\nDiff: $diff_link"),
+    'spec_anomaly': ("🐢 Slow spec run — $label ($language) for slot $slot\n"
+                     "Author: $author\n$reason\nDiff: $diff_link"),
+    'approval_request': ("👀 Approval requested — $label ($language) for slot $slot\n"
+                         "Author: $author\nOwners: $owners\nDiff: $diff_link"),
+    'promotion':    ("✅ Promoted — $label ($language) to slot $slot\n"
+                     "Author: $author\nDiff: $diff_link"),
+    'rollback':     ("↩ Rolled back — $label ($language) from slot $slot\n"
+                     "Author: $author\nReason: $reason\nDiff: $diff_link"),
+    'degraded':     ("⚠ Slot degraded — $label ($language) in slot $slot fails revalidation\n"
+                     "Owners: $owners\nReason: $reason\nDiff: $diff_link"),
+}
+
+DEFAULT_DIFF_URL = 'http://localhost:5002/api/staging/snippet/$staging_id'
+
+SUBJECTS = {
+    'spec_failure': '[spokedpy] Spec failure: $label ($slot)',
+    'spec_anomaly': '[spokedpy] Slow spec run: $label ($slot)',
+    'approval_request': '[spokedpy] Approval requested: $label ($slot)',
+    'promotion':    '[spokedpy] Promoted: $label → $slot',
+    'rollback':     '[spokedpy] Rolled back: $label ($slot)',
+    'degraded':     '[spokedpy] Slot degraded: $label ($slot)',
+}
+
+
+class NotifyError(Exception):
+    """Raised when a notifier cannot deliver a message."""
+
+
+@dataclass
+class Notification:
+    """Everything a template can reference about one event."""
+    event: str
+    staging_id: str
+    slot: str
+    label: str
+    language: str
+    author: str = ''
+    reason: str = ''
+    diff_link: str = ''
+    code_hash: str = ''
+    owners: str = ''                         # space-separated (approval_request, degraded)
+
+    @classmethod
+    def from_snippet(cls, event: str, snippet, reason: str = '',
+                     diff_url: str = '', owners: Iterable[str] = ()) -> 'Notification':
+        note = cls(event=event, staging_id=snippet.staging_id,
+                   slot=snippet.reserved_address, label=snippet.label,
+                   language=snippet.language, author=snippet.author or 'unknown',
+                   reason=reason[:500], code_hash=snippet.code_hash[:12],
+                   owners=' '.join(owners))
+        if diff_url:
+            note.diff_link = Template(diff_url).safe_substitute(note.context())
+        return note
+
+    def context(self) -> Dict[str, str]:
+        return asdict(self)
+
+
+def render(template: str, note: Notification) -> str:
+    return Template(template).safe_substitute(note.context())
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# NOTIFIERS
+# ═══════════════════════════════════════════════════════════════════════════
+
+class Notifier(ABC):
+    """Base class: event filter + templates; subclasses deliver."""
+
+    kind = 'notifier'
+
+    def __init__(self, events: Iterable[str] = NOTIFY_EVENTS,
+                 templates: Optional[Dict[str, str]] = None):
+        self.events = [e for e in events if e]
+        unknown = set(self.events) - set(NOTIFY_EVENTS)
+        if unknown:
+            raise ValueError(f"Unknown notification event(s): {', '.join(sorted(unknown))} "
+                             f"(choose from: {', '.join(NOTIFY_EVENTS)})")
+        self.templates = {k: v for k, v in (templates or {}).items() if v}
+
+    def wants(self, event: str) -> bool:
+        return event in self.events
+
+    def text(self, note: Notification) -> str:
+        return render(self.templates.get(note.event) or DEFAULT_TEMPLATES[note.event], note)
+
+    @abstractmethod
+    def send(self, note: Notification):
+        """Deliver one notification; raise NotifyError on failure."""
+
+    def describe(self) -> Dict[str, Any]:
+        return {'kind': self.kind, 'events': self.events}
+
+
+class SlackNotifier(Notifier):
+    """Posts to a Slack incoming webhook."""
+
+    kind = 'slack'
+
+    def __init__(self, webhook_url: str, timeout: float = 10.0, **kwargs):
+        super().__init__(**kwargs)
+        self.webhook_url = webhook_url
+        self.timeout = timeout
+
+    def send(self, note: Notification):
+        offline.require_host(self.webhook_url, 'Slack notification')
+        body = json.dumps({'text': self.text(note)}).encode('utf-8')
+        req = urllib.request.Request(self.webhook_url, data=body,
+                                     headers={'Content-Type': 'application/json'})
+        try:
+            with urllib.request.urlopen(req, timeout=self.timeout) as resp:
+                if resp.status >= 300:
+                    raise NotifyError(f"Slack webhook returned HTTP {resp.status}")
+        except OSError as exc:
+            raise NotifyError(f"Slack webhook failed: {exc}") from exc
+
+
+class EmailNotifier(Notifier):
+    """Sends plain-text mail over SMTP (STARTTLS by default)."""
+
+    kind = 'email'
+
+    def __init__(self, host: str, port: int, sender: str, recipients: List[str],
+                 username: str = '', password: str = '', starttls: bool = True,
+                 timeout: float = 20.0, smtp_factory: Callable = smtplib.SMTP, **kwargs):
+        super().__init__(**kwargs)
+        self.host, self.port = host, port
+        self.sender, self.recipients = sender, [r for r in recipients if r]
+        self.username, self.password = username, password
+        self.starttls = starttls
+        self.timeout = timeout
+        self._smtp_factory = smtp_factory
+
+    def send(self, note: Notification):
+        offline.require_host(f"smtp://{self.host}:{self.port}", 'Email notification')
+        msg = EmailMessage()
+        msg['Subject'] = render(SUBJECTS[note.event], note)
+        msg['From'] = self.sender
+        # Owners that are addresses hear about their own approval requests.
+        owners = [o for o in note.owners.split() if '@' in o and o not in self.recipients]
+        msg['To'] = ', '.join(self.recipients + owners)
+        msg.set_content(self.text(note))
+        try:
+            with self._smtp_factory(self.host, self.port, timeout=self.timeout) as smtp:
+                if self.starttls:
+                    smtp.starttls()
+                if self.username:
+                    smtp.login(self.username, self.password)
+                smtp.send_message(msg)
+        except (OSError, smtplib.SMTPException) as exc:
+            raise NotifyError(f"SMTP delivery to {self.host}:{self.port} failed: {exc}") from exc
+
+    def describe(self) -> Dict[str, Any]:
+        return {**super().describe(), 'host': self.host, 'port': self.port,
+                'recipients': self.recipients}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# HUB — pipeline listener that fans out to notifiers
+# ═══════════════════════════════════════════════════════════════════════════
+
+class NotificationHub:
+    """Pipeline listener: ``pipeline.add_listener(hub)``."""
+
+    def __init__(self, notifiers: List[Notifier], diff_url: str = '',
+                 background: bool = True):
+        self.notifiers = list(notifiers)
+        self.diff_url = diff_url
+        self.background = background
+        self.errors: deque = deque(maxlen=50)
+
+    def __call__(self, event: str, snippet, details: Dict[str, Any]):
+        if not any(n.wants(event) for n in self.notifiers):
+            return
+        # Snapshot the snippet now — it keeps changing after we return.
+        note = Notification.from_snippet(event, snippet, details.get('reason', ''),
+                                         self.diff_url, details.get('owners', ()))
+        self.dispatch(note)
+
+    def dispatch(self, note: Notification):
+        targets = [n for n in self.notifiers if n.wants(note.event)]
+        if self.background:
+            threading.Thread(target=self._deliver, args=(targets, note),
+                             name='spokedpy-notify', daemon=True).start()
+        else:
+            self._deliver(targets, note)
+
+    def _deliver(self, targets: List[Notifier], note: Notification):
+        for notifier in targets:
+            try:
+                notifier.send(note)
+            except Exception as exc:
+                self.errors.append({'time': time.time(), 'notifier': notifier.kind,
+                                    'event': note.event, 'staging_id': note.staging_id,
+                                    'error': str(exc)})
+                print(f"  [NOTIFY] {notifier.kind} {note.event} {note.staging_id}: {exc}")
+
+    def describe(self) -> Dict[str, Any]:
+        return {'notifiers': [n.describe() for n in self.notifiers],
+                'diff_url': self.diff_url, 'errors': list(self.errors)}
+
+
+def _csv(value: str) -> List[str]:
+    return [v.strip() for v in (value or '').split(',') if v.strip()]
+
+
+def hub_from_settings(setting: Callable[[str, str, str], str]) -> NotificationHub:
+    """Build the hub from ``[notify]`` settings; ``setting`` is resolve_setting."""
+    templates = {event: setting(f'template_{event}', f'SPOKEDPY_TEMPLATE_{event.upper()}', '')
+                 for event in NOTIFY_EVENTS}
+    notifiers: List[Notifier] = []
+
+    webhook = setting('slack_webhook', 'SPOKEDPY_SLACK_WEBHOOK', '')
+    if webhook:
+        notifiers.append(SlackNotifier(
+            webhook,
+            events=_csv(setting('slack_events', 'SPOKEDPY_SLACK_EVENTS', ','.join(NOTIFY_EVENTS))),
+            templates=templates,
+        ))
+
+    smtp_host = setting('smtp_host', 'SPOKEDPY_SMTP_HOST', '')
+    recipients = _csv(setting('email_to', 'SPOKEDPY_EMAIL_TO', ''))
+    if smtp_host and recipients:
+        notifiers.append(EmailNotifier(
+            smtp_host,
+            int(setting('smtp_port', 'SPOKEDPY_SMTP_PORT', '587')),
+            sender=setting('email_from', 'SPOKEDPY_EMAIL_FROM', 'spokedpy@localhost'),
+            recipients=recipients,
+            username=setting('smtp_username', 'SPOKEDPY_SMTP_USERNAME', ''),
+            password=setting('smtp_password', 'SPOKEDPY_SMTP_PASSWORD', ''),
+            starttls=setting('smtp_starttls', 'SPOKEDPY_SMTP_STARTTLS', '1').strip().lower()
+                     in ('1', 'true', 'yes', 'on'),
+            events=_csv(setting('email_events', 'SPOKEDPY_EMAIL_EVENTS', ','.join(NOTIFY_EVENTS))),
+            templates=templates,
+        ))
+
+    return NotificationHub(notifiers, diff_url=setting('diff_url', 'SPOKEDPY_DIFF_URL', DEFAULT_DIFF_URL))
@@ -0,0 +1,487 @@
+"""
+Offline Mode — air-gapped enforcement across every engine.
+
+When offline mode is on, SpokedPy must never reach past the local machine.
+Enforcement happens at three layers:
+
+    ┌──────────────────┬───────────────────────────────────────────────────┐
+    │  engine spawns   │  every ``_run_subprocess`` call gets offline env  │
+    │                  │  (CARGO_NET_OFFLINE, GOPROXY=off, npm/pip offline)│
+    │                  │  and, on Linux, runs inside a fresh network       │
+    │                  │  namespace (``unshare --net``) — no interfaces    │
+    │                  │  but loopback.  The ``netns`` sandbox backend     │
+    │                  │  applies the namespace even when online.          │
+    │  in-process      │  the Python engine's exec() runs under an audit-  │
+    │  Python          │  hook guard that refuses non-loopback sockets     │
+    │  host operations │  toolchain installs, AI chat to remote endpoints, │
+    │                  │  … call ``require_network()`` and fail with a     │
+    │                  │  clear OfflineError instead of hanging on DNS     │
+    └──────────────────┴───────────────────────────────────────────────────┘
+
+Cargo resolves crates from a vendored registry snapshot managed by
+``spokedpy vendor``.  The snapshot lives under the vendor dir:
+
+    <vendor_dir>/crates/             cargo vendor output (directory source)
+    <vendor_dir>/cargo-home/         CARGO_HOME used by engines while offline;
+                 config.toml         replaces crates-io with the snapshot
+    <vendor_dir>/vendor.json         snapshot metadata (manifests, crate count)
+
+Enabled via:  DB setting → SPOKEDPY_OFFLINE env → off
+              (``spokedpy --offline <command>`` for one CLI invocation)
+
+Spec runs also follow a network policy, online or not (``[sandbox]
+network``, default ``none``; per engine as ``"none, javascript=loopback"``):
+
+    ┌────────────┬─────────────────────────────────────────────────────────┐
+    │  none      │  an empty network namespace — not even loopback         │
+    │  loopback  │  a namespace with only ``lo`` up: local servers the     │
+    │            │  snippet starts itself, nothing past the machine        │
+    │  full      │  the host's network                                     │
+    └────────────┴─────────────────────────────────────────────────────────┘
+
+A snippet that needs more than its engine's level declares it in its
+leading comments (``# network: loopback``, see declared_network); the
+promoted file's header keeps the declaration, and slot constraints
+(``max_network``) can keep such snippets out of protected slots.
+Namespaces are Linux only; elsewhere only the in-process Python guard
+applies the policy.  A spawn of a spec run at none or loopback that gets
+the host's network anyway (no namespaces here, or ``shell=True``) is not
+hidden: the snippet lists it (``network_not_enforced``), and so does the
+audit entry of its dry-run.
+"""
+
+import os
+import re
+import sys
+import json
+import time
+import shutil
+import ipaddress
+import threading
+import subprocess
+from urllib.parse import urlparse
+from typing import Callable, Dict, Iterable, List, Optional, Tuple
+
+
+class OfflineError(RuntimeError):
+    """Raised when an operation needs the network while offline mode is on."""
+
+
+_TRUTHY = ('1', 'true', 'yes', 'on')
+
+# Sandbox backends for engine subprocesses:
+#   process — plain child process (network isolation only in offline mode)
+#   netns   — always run in an empty network namespace; refuse to spawn
+#             when namespaces are unavailable (locked-down profiles)
+SANDBOX_BACKENDS = ('process', 'netns')
+
+# Network levels of a spec run, least access first (see the table above).
+NETWORK_LEVELS = ('none', 'loopback', 'full')
+
+_state = {
+    'enabled': os.environ.get('SPOKEDPY_OFFLINE', '').strip().lower() in _TRUTHY,
+    'vendor_dir': os.environ.get('SPOKEDPY_VENDOR_DIR', '').strip() or None,
+    'backend': 'process',
+    'network': 'none',
+    'network_engines': {},
+}
+_isolation_probe: Dict[str, Optional[List[str]]] = {}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# MODE
+# ═══════════════════════════════════════════════════════════════════════════
+
+def configure(enabled: bool, vendor_dir: Optional[str] = None):
+    """Switch offline mode on/off for this process and its children."""
+    _state['enabled'] = bool(enabled)
+    if vendor_dir is not None:
+        _state['vendor_dir'] = vendor_dir
+    # Children (and CLI helpers spawned by engines) inherit the mode.
+    if enabled:
+        os.environ['SPOKEDPY_OFFLINE'] = '1'
+    else:
+        os.environ.pop('SPOKEDPY_OFFLINE', None)
+
+
+def is_offline() -> bool:
+    return _state['enabled']
+
+
+def set_sandbox_backend(name: str):
+    """Select the sandbox backend (``[sandbox] backend`` / SPOKEDPY_SANDBOX_BACKEND)."""
+    name = (name or 'process').strip().lower()
+    if name not in SANDBOX_BACKENDS:
+        raise ValueError(f"Unknown sandbox backend '{name}' "
+                         f"(choose from: {', '.join(SANDBOX_BACKENDS)})")
+    _state['backend'] = name
+
+
+def sandbox_backend() -> str:
+    return _state['backend']
+
+
+def vendor_dir() -> Optional[str]:
+    return _state['vendor_dir']
+
+
+def _network_level(value: str, where: str) -> str:
+    level = value.strip().lower()
+    if level not in NETWORK_LEVELS:
+        raise ValueError(f"{where}: unknown network level '{value.strip()}' "
+                         f"(choose from: {', '.join(NETWORK_LEVELS)})")
+    return level
+
+
+def parse_network_policy(value: str) -> Tuple[str, Dict[str, str]]:
+    """``"none, javascript=loopback"`` → ('none', {'javascript': 'loopback'}); ValueError if bad."""
+    default, engines = 'none', {}
+    for part in (value or '').split(','):
+        if not part.strip():
+            continue
+        engine, sep, level = part.partition('=')
+        if sep:
+            if not engine.strip():
+                raise ValueError(f"network policy: '{part.strip()}' names no engine")
+            engine = engine.strip().lower()
+            engines[engine] = _network_level(level, f"network policy ({engine})")
+        else:
+            default = _network_level(part, 'network policy')
+    return default, engines
+
+
+def set_network_policy(value: str):
+    """Set the spec runs' network policy (``[sandbox] network`` / SPOKEDPY_NETWORK)."""
+    _state['network'], _state['network_engines'] = parse_network_policy(value)
+
+
+def network_policy() -> Tuple[str, Dict[str, str]]:
+    return _state['network'], dict(_state['network_engines'])
+
+
+_DECLARATION = re.compile(r'^(?:#|//|--)\s*network:\s*(\S*)\s*$')
+
+
+def declared_network(code: str) -> str:
+    """
+    The ``network:`` level a snippet declares in its leading comments
+    (``# network: loopback``, ``// network: full``), '' if none; a
+    ValueError for an unknown level.
+    """
+    for line in code.split('\n'):
+        line = line.strip()
+        if not line or line.startswith('#!'):
+            continue
+        if not line.startswith(('#', '//', '--')):
+            break
+        match = _DECLARATION.match(line)
+        if match:
+            return _network_level(match.group(1), 'network declaration')
+    return ''
+
+
+def network_for(language: str, declared: str = '') -> str:
+    """The level a spec run of a ``language`` snippet gets: its declaration, else its engine's."""
+    return declared or _state['network_engines'].get(language.lower(), _state['network'])
+
+
+def require_network(operation: str):
+    """Raise OfflineError if offline mode forbids ``operation``."""
+    if _state['enabled']:
+        raise OfflineError(
+            f"{operation} needs network access, but SpokedPy is in offline mode "
+            f"(unset SPOKEDPY_OFFLINE or the 'offline' setting to allow it)"
+        )
+
+
+def is_loopback_host(host: str) -> bool:
+    host = (host or '').strip('[]').lower()
+    if host in ('localhost', 'localhost.localdomain') or host.endswith('.localhost'):
+        return True
+    try:
+        return ipaddress.ip_address(host).is_loopback
+    except ValueError:
+        return False
+
+
+def require_host(url: str, operation: str):
+    """Like ``require_network`` but lets loopback URLs (local servers) through."""
+    if _state['enabled'] and not is_loopback_host(urlparse(url).hostname or ''):
+        require_network(f"{operation} ({url})")
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SUBPROCESS ENFORCEMENT
+# ═══════════════════════════════════════════════════════════════════════════
+
+def subprocess_env() -> Dict[str, str]:
+    """Environment overrides that put each toolchain's package manager offline."""
+    env = {
+        'SPOKEDPY_OFFLINE': '1',
+        'CARGO_NET_OFFLINE': 'true',
+        'GOPROXY': 'off',
+        'GOTOOLCHAIN': 'local',          # never auto-download a newer go
+        'NPM_CONFIG_OFFLINE': 'true',
+        'PIP_NO_INDEX': '1',
+        'DOTNET_CLI_TELEMETRY_OPTOUT': '1',
+        'DOTNET_NOLOGO': '1',
+    }
+    snapshot = CargoVendor(_state['vendor_dir']) if _state['vendor_dir'] else None
+    if snapshot is not None and snapshot.exists():
+        env['CARGO_HOME'] = snapshot.cargo_home
+    return env
+
+
+def isolation_prefix() -> Optional[List[str]]:
+    """Command prefix that runs a child without network access, if supported.
+
+    Linux only: an unprivileged user + network namespace.  Probed once per
+    process because some container runtimes forbid user namespaces.
+    """
+    if 'prefix' in _isolation_probe:
+        return _isolation_probe['prefix']
+    prefix = None
+    unshare = shutil.which('unshare') if sys.platform.startswith('linux') else None
+    if unshare:
+        candidate = [unshare, '--net', '--map-root-user', '--']
+        try:
+            probe = subprocess.run(candidate + ['true'], capture_output=True, timeout=5)
+            if probe.returncode == 0:
+                prefix = candidate
+        except (OSError, subprocess.SubprocessError):
+            pass
+    _isolation_probe['prefix'] = prefix
+    return prefix
+
+
+# Run inside the namespace before the engine: bring ``lo`` up (SIOCGIFFLAGS /
+# SIOCSIFFLAGS), then exec the command.  If that is refused, the run
+# simply has no loopback either.
+_LOOPBACK_UP = '''
+import fcntl, os, socket, struct, sys
+try:
+    s = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    flags = struct.unpack('16sH', fcntl.ioctl(s, 0x8913, struct.pack('16sH14x', b'lo', 0))[:18])[1]
+    fcntl.ioctl(s, 0x8914, struct.pack('16sH14x', b'lo', flags | 1))
+    s.close()
+except OSError:
+    pass
+os.execvp(sys.argv[1], sys.argv[1:])
+'''
+
+
+def apply(args: tuple, kwargs: dict) -> Tuple[tuple, dict]:
+    """Rewrite a ``subprocess.run`` call for offline mode / the netns backend /
+    the network policy of the spec run on this thread.
+
+    No-op when online with the ``process`` backend, outside a spec run.
+    """
+    strict = _state['backend'] == 'netns'
+    level = current_network()
+    if not (_state['enabled'] or strict or level in ('none', 'loopback')) or not args:
+        return args, kwargs
+    kwargs = dict(kwargs)
+    if _state['enabled']:
+        kwargs['env'] = {**(kwargs.get('env') or os.environ), **subprocess_env()}
+    argv = args[0]
+    prefix = isolation_prefix()
+    if prefix is None and strict:
+        raise OfflineError("sandbox backend 'netns' needs Linux network namespaces "
+                           "(util-linux `unshare`), which are unavailable here")
+    if prefix and isinstance(argv, (list, tuple)) and not kwargs.get('shell'):
+        if level == 'loopback':
+            prefix = prefix + [sys.executable, '-I', '-c', _LOOPBACK_UP]
+        args = (prefix + list(argv),) + tuple(args[1:])
+    elif level in ('none', 'loopback'):
+        _not_enforced(argv)
+    return args, kwargs
+
+
+def _not_enforced(argv):
+    """Note on this thread's spec run that ``argv`` runs with the host's network."""
+    scope = getattr(_guard, 'scope', None)
+    if scope is None:
+        return
+    command = argv[0] if isinstance(argv, (list, tuple)) and argv else \
+        str(argv).split(' ', 1)[0]
+    name = os.path.basename(os.fsdecode(command) if isinstance(command, bytes) else str(command))
+    if name not in scope.not_enforced:
+        scope.not_enforced.append(name)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# IN-PROCESS GUARD (Python engine)
+# ═══════════════════════════════════════════════════════════════════════════
+
+_guard = threading.local()
+_hook_installed = threading.Lock()
+_hook_state = {'installed': False}
+
+
+class spec_network:
+    """Context manager: this thread is running a spec run with network ``level``.
+
+    ``not_enforced`` names the commands it spawned with the host's network
+    all the same (see apply).
+    """
+
+    def __init__(self, level: str):
+        self.level = _network_level(level, 'network level')
+        self.not_enforced: List[str] = []
+
+    def __enter__(self):
+        self._previous = getattr(_guard, 'network', None), getattr(_guard, 'scope', None)
+        _guard.network, _guard.scope = self.level, self
+        return self
+
+    def __exit__(self, *exc):
+        _guard.network, _guard.scope = self._previous
+        return False
+
+
+def current_network() -> Optional[str]:
+    """The network level of the spec run on this thread (None outside one)."""
+    return getattr(_guard, 'network', None)
+
+
+def _audit_hook(event: str, args: tuple):
+    mode = getattr(_guard, 'active', None)
+    if not mode:
+        return
+    # 'offline' and 'loopback' let loopback through; 'none' refuses everything
+    why = 'in offline mode' if mode == 'offline' else f"by the network policy ({mode})"
+    if event == 'socket.connect':
+        address = args[1] if len(args) > 1 else None
+        if isinstance(address, tuple) and (mode == 'none' or not is_loopback_host(str(address[0]))):
+            raise OfflineError(f"Network access to {address[0]} is blocked {why}")
+    elif event == 'socket.getaddrinfo':
+        host = args[0] if args else None
+        if isinstance(host, bytes):
+            host = host.decode('ascii', 'replace')
+        if host and (mode == 'none' or not is_loopback_host(str(host))):
+            raise OfflineError(f"DNS lookup for {host} is blocked {why}")
+
+
+class network_guard:
+    """Context manager: refuse non-loopback sockets on this thread while offline,
+    and what the network policy refuses during a spec run."""
+
+    def __enter__(self):
+        self._previous = getattr(_guard, 'active', None)
+        level = current_network()
+        mode = 'none' if level == 'none' else 'offline' if _state['enabled'] else \
+            'loopback' if level == 'loopback' else None
+        if mode:
+            with _hook_installed:
+                if not _hook_state['installed']:
+                    sys.addaudithook(_audit_hook)
+                    _hook_state['installed'] = True
+            _guard.active = mode
+        return self
+
+    def __exit__(self, *exc):
+        _guard.active = self._previous
+        return False
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# CARGO VENDOR SNAPSHOT
+# ═══════════════════════════════════════════════════════════════════════════
+
+_CARGO_CONFIG = """\
+# Generated by `spokedpy vendor` — engines use this CARGO_HOME while offline.
+[source.crates-io]
+replace-with = "spokedpy-vendor"
+
+[source.spokedpy-vendor]
+directory = {crates}
+
+[net]
+offline = true
+"""
+
+
+class CargoVendor:
+    """A vendored crates.io snapshot that cargo can build from with no network."""
+
+    def __init__(self, root: str,
+                 runner: Optional[Callable[[List[str]], Tuple[int, str]]] = None):
+        self.root = root
+        self._runner = runner or self._run
+
+    @property
+    def crates_dir(self) -> str:
+        return os.path.join(self.root, 'crates')
+
+    @property
+    def cargo_home(self) -> str:
+        return os.path.join(self.root, 'cargo-home')
+
+    @property
+    def metadata_path(self) -> str:
+        return os.path.join(self.root, 'vendor.json')
+
+    def exists(self) -> bool:
+        return os.path.exists(os.path.join(self.cargo_home, 'config.toml'))
+
+    def status(self) -> Dict[str, object]:
+        """Return snapshot metadata (``present: False`` if never vendored)."""
+        if not os.path.exists(self.metadata_path):
+            return {'present': False, 'root': self.root}
+        with open(self.metadata_path, 'r', encoding='utf-8') as f:
+            meta = json.load(f)
+        meta.update(present=self.exists(), root=self.root)
+        return meta
+
+    def command(self, manifests: Iterable[str]) -> List[str]:
+        manifests = [os.path.abspath(m) for m in manifests]
+        if not manifests:
+            raise ValueError('vendor needs at least one Cargo.toml')
+        cargo = shutil.which('cargo')
+        if cargo is None:
+            raise OfflineError('cargo not found on PATH — install Rust (rustup) to vendor crates')
+        argv = [cargo, 'vendor', '--versioned-dirs', '--manifest-path', manifests[0]]
+        for extra in manifests[1:]:
+            argv += ['--sync', extra]
+        if _state['enabled']:
+            # Air-gapped: snapshot from the local registry cache only.
+            argv.append('--offline')
+        argv.append(self.crates_dir)
+        return argv
+
+    def sync(self, manifests: Iterable[str]) -> Dict[str, object]:
+        """Vendor every dependency of ``manifests`` and write the cargo config."""
+        manifests = list(manifests)
+        argv = self.command(manifests)
+        os.makedirs(self.root, exist_ok=True)
+        code, output = self._runner(argv)
+        if code != 0:
+            tail = '\n'.join(output.strip().splitlines()[-15:])
+            raise OfflineError(f"cargo vendor failed (exit {code})\n{tail}")
+        os.makedirs(self.crates_dir, exist_ok=True)     # cargo skips it when there are no deps
+        os.makedirs(self.cargo_home, exist_ok=True)
+        with open(os.path.join(self.cargo_home, 'config.toml'), 'w', encoding='utf-8') as f:
+            f.write(_CARGO_CONFIG.format(crates=json.dumps(self.crates_dir)))
+        crates = sorted(d for d in os.listdir(self.crates_dir)
+                        if os.path.isdir(os.path.join(self.crates_dir, d))) \
+            if os.path.isdir(self.crates_dir) else []
+        meta = {
+            'created': time.time(),
+            'manifests': [os.path.abspath(m) for m in manifests],
+            'crate_count': len(crates),
+            'crates': crates,
+        }
+        tmp = self.metadata_path + '.tmp'
+        with open(tmp, 'w', encoding='utf-8') as f:
+            json.dump(meta, f, indent=2)
+        os.replace(tmp, self.metadata_path)
+        return self.status()
+
+    @staticmethod
+    def _run(argv: List[str]) -> Tuple[int, str]:
+        try:
+            proc = subprocess.run(argv, capture_output=True, text=True,
+                                  encoding='utf-8', errors='replace', timeout=1800)
+        except (OSError, subprocess.SubprocessError) as exc:
+            return 127, str(exc)
+        return proc.returncode, (proc.stdout or '') + (proc.stderr or '')
@@ -0,0 +1,258 @@
+"""
+Output Stream — live compiler / spec output for streaming clients.
+
+A speculative run normally reports only when it finishes.  With a sink
+installed for the current thread (``streaming(sink)``), every engine
+subprocess started through ``execution_engine._run_subprocess`` streams
+its stdout / stderr line by line, and the pipeline adds phase markers:
+
+    ┌────────────────────┬─────────────────────────────────────────────────┐
+    │  spec_started      │  staging_id, language, cases                    │
+    │  case_started      │  name                      (spec suites only)   │
+    │  process_started   │  process, phase ('compile' | 'run')             │
+    │  output            │  process, stream ('stdout' | 'stderr'), text    │
+    │  process_finished  │  process, phase, exit_code, duration, stopped   │
+    │  case_finished     │  name, passed, duration, first_diff, denied     │
+    │  gate              │  gate, allow, reasons                           │
+    │  spec_finished     │  staging_id, success, phase, spec_time          │
+    └────────────────────┴─────────────────────────────────────────────────┘
+
+Every event is a dict ``{'event': name, 'at': unix_time, ...}``.  POST
+/api/staging/stage-and-spec sends them to the client as NDJSON.  Without a
+sink nothing changes: subprocesses run through subprocess.run as before.
+
+A stdout check (``checking(check)``) streams the same way, sink or not:
+every complete stdout line of a 'run' process goes to ``check(line)``,
+and a reason back stops the process there: run_streaming gives it
+STOP_GRACE seconds to exit by itself (its later stdout is read and
+dropped), kills it if it has not, and raises OutputDiverged with the
+output so far, its stderr and its exit code (None: killed, and
+``process_finished`` says ``stopped``).  Spec suites use it to end a case
+at its first line that differs from the expected stdout.
+"""
+
+import os
+import subprocess
+import threading
+import time
+from contextlib import contextmanager
+from typing import Any, Callable, Dict, Optional
+
+Sink = Callable[[Dict[str, Any]], None]
+
+# Programs whose run is the compile step of an engine (everything else is 'run').
+COMPILERS = frozenset({
+    'rustc', 'cargo', 'gcc', 'g++', 'cc', 'c++', 'clang', 'clang++', 'javac',
+    'kotlinc', 'swiftc', 'tsc', 'csc', 'mcs',
+})
+
+# Seconds a process whose stdout diverged gets to exit by itself before it is killed.
+STOP_GRACE = 0.5
+
+_local = threading.local()
+
+
+@contextmanager
+def streaming(sink: Optional[Sink]):
+    """Send this thread's events to ``sink`` for the duration (None: leave as is)."""
+    if sink is None:
+        yield
+        return
+    previous = getattr(_local, 'sink', None)
+    _local.sink = sink
+    try:
+        yield
+    finally:
+        _local.sink = previous
+
+
+def current_sink() -> Optional[Sink]:
+    return getattr(_local, 'sink', None)
+
+
+class OutputDiverged(subprocess.SubprocessError):
+    """A stdout check stopped the process; ``reason`` is what it returned,
+    ``exit_code`` None when it had to be killed."""
+
+    def __init__(self, cmd, reason: str, output: str = '', stderr: str = '',
+                 exit_code: Optional[int] = None):
+        super().__init__(reason)
+        self.cmd, self.reason, self.output, self.stderr = cmd, reason, output, stderr
+        self.exit_code = exit_code
+
+    def __str__(self) -> str:
+        return f"stopped: {self.reason}"
+
+
+@contextmanager
+def checking(check: Optional[Callable[[str], Optional[str]]]):
+    """Check this thread's 'run' stdout line by line for the duration (None: leave as is)."""
+    if check is None:
+        yield
+        return
+    previous = getattr(_local, 'check', None)
+    _local.check = check
+    try:
+        yield
+    finally:
+        _local.check = previous
+
+
+def current_check() -> Optional[Callable[[str], Optional[str]]]:
+    return getattr(_local, 'check', None)
+
+
+def emit(event: str, **data):
+    """Send one event to the current thread's sink, if any."""
+    sink = current_sink()
+    if sink is None:
+        return
+    try:
+        sink({'event': event, 'at': time.time(), **data})
+    except Exception:
+        # A slow or vanished client must never fail the run itself.
+        pass
+
+
+class Transcript:
+    """A sink that keeps every process's output (for failure bundles) and
+    passes each event on to ``forward``."""
+
+    def __init__(self, forward: Optional[Sink] = None):
+        self.forward = forward
+        self.processes = []              # dicts: process, phase, case, stdout, stderr, exit_code, duration
+        self._case = ''
+        self._lock = threading.Lock()
+
+    def __call__(self, event: Dict[str, Any]):
+        kind = event['event']
+        with self._lock:
+            if kind == 'case_started':
+                self._case = event['name']
+            elif kind == 'process_started':
+                self.processes.append({'process': event['process'], 'phase': event['phase'],
+                                       'case': self._case, 'stdout': '', 'stderr': '',
+                                       'exit_code': None, 'duration': 0.0})
+            elif kind == 'output':
+                running = self._running(event['process'])
+                if running is not None:
+                    running[event['stream']] += event['text']
+            elif kind == 'process_finished':
+                running = self._running(event['process'])
+                if running is not None:
+                    running.update(exit_code=event['exit_code'], duration=event['duration'],
+                                   finished=True)
+                    if event.get('stopped'):
+                        running['stopped'] = True
+        if self.forward is not None:
+            self.forward(event)
+
+    def _running(self, process: str):
+        for entry in reversed(self.processes):
+            if entry['process'] == process and not entry.get('finished'):
+                return entry
+        return None
+
+    def records(self):
+        return [{k: v for k, v in p.items() if k != 'finished'} for p in self.processes]
+
+
+def process_name(argv) -> str:
+    first = argv.split()[0] if isinstance(argv, str) else argv[0]
+    return os.path.basename(str(first))
+
+
+def process_phase(argv) -> str:
+    """'compile' for compilers and ``<tool> build``, else 'run'."""
+    args = argv.split() if isinstance(argv, str) else [str(a) for a in argv]
+    name = os.path.splitext(process_name(args))[0]
+    if name in COMPILERS or (len(args) > 1 and args[1] == 'build'):
+        return 'compile'
+    return 'run'
+
+
+def run_streaming(argv, process: str = '', phase: str = '', popen=subprocess.Popen,
+                  **kwargs) -> subprocess.CompletedProcess:
+    """subprocess.run(argv, capture_output=True, text=True, ...) that emits
+    each output line as it arrives.  Raises subprocess.TimeoutExpired like
+    subprocess.run, and OutputDiverged when the stdout check stops it;
+    other keyword arguments go to ``popen`` (Popen, or accounting's
+    metered one)."""
+    sink, check = current_sink(), current_check()
+    if ((sink is None and check is None) or not kwargs.get('capture_output')
+            or not (kwargs.get('text') or kwargs.get('encoding'))):
+        if popen is not subprocess.Popen:
+            from .accounting import run
+            return run(popen, argv, **kwargs)
+        return subprocess.run(argv, **kwargs)
+
+    kwargs.pop('capture_output')
+    kwargs.pop('check', None)
+    stdin_text = kwargs.pop('input', None)
+    timeout = kwargs.pop('timeout', None)
+    process = process or process_name(argv)
+    phase = phase or process_phase(argv)
+    if stdin_text is not None:
+        kwargs['stdin'] = subprocess.PIPE
+
+    if phase != 'run':
+        check = None
+    emit('process_started', process=process, phase=phase)
+    started = time.time()
+    proc = popen(argv, stdout=subprocess.PIPE, stderr=subprocess.PIPE, **kwargs)
+    captured = {'stdout': [], 'stderr': []}
+    stopped, killed = [], []
+
+    def kill():
+        if proc.poll() is None:
+            killed.append(True)
+            proc.kill()
+
+    def pump(stream_name, pipe):
+        def forward():
+            for line in iter(pipe.readline, ''):
+                if stopped and stream_name == 'stdout':
+                    continue                    # what follows the divergent line is not kept
+                captured[stream_name].append(line)
+                with streaming(sink):
+                    emit('output', process=process, stream=stream_name, text=line)
+                if check is not None and stream_name == 'stdout':
+                    reason = check(line)
+                    if reason:
+                        stopped.append(reason)
+                        grace = threading.Timer(STOP_GRACE, kill)
+                        grace.daemon = True
+                        grace.start()
+            pipe.close()
+        thread = threading.Thread(target=forward, daemon=True)
+        thread.start()
+        return thread
+
+    readers = [pump('stdout', proc.stdout), pump('stderr', proc.stderr)]
+    if stdin_text is not None:
+        try:
+            proc.stdin.write(stdin_text)
+            proc.stdin.close()
+        except (BrokenPipeError, OSError):
+            pass
+    try:
+        proc.wait(timeout=timeout)
+    except subprocess.TimeoutExpired:
+        proc.kill()
+        proc.wait()
+        for reader in readers:
+            reader.join()
+        emit('process_finished', process=process, phase=phase, exit_code=None,
+             duration=time.time() - started)
+        raise subprocess.TimeoutExpired(argv, timeout, output=''.join(captured['stdout']),
+                                        stderr=''.join(captured['stderr']))
+    for reader in readers:
+        reader.join()
+    exit_code = None if killed else proc.returncode
+    emit('process_finished', process=process, phase=phase, exit_code=exit_code,
+         duration=time.time() - started, **({'stopped': True} if killed else {}))
+    if stopped:
+        raise OutputDiverged(argv, stopped[0], ''.join(captured['stdout']),
+                             ''.join(captured['stderr']), exit_code)
+    return subprocess.CompletedProcess(argv, proc.returncode,
+                                       ''.join(captured['stdout']), ''.join(captured['stderr']))
@@ -0,0 +1,282 @@
+"""
+Promotion Gates — pluggable allow/deny checks between dry-run and verdict.
+
+After a snippet passes speculative execution, every configured gate sees the
+snippet's header (metadata) and body (source) and votes allow or deny.  Any
+deny turns the snippet FAILED with the gates' reasons; only an explicit
+'approve' verdict (when ``[gates] allow_override`` permits it) can force it
+through.
+
+Teams ship bespoke checks as WebAssembly modules dropped into the plugins
+dir (``[gates] plugins_dir`` / SPOKEDPY_PLUGINS_DIR → data/plugins/) — no
+changes to SpokedPy itself.  Each ``<name>.wasm`` becomes gate ``<name>``.
+
+Plugin ABI (no imports; the module gets no host access at all):
+
+    ┌──────────────────────────────────────────────────────────────────────┐
+    │  export memory                        linear memory                  │
+    │  export spokedpy_alloc(len: i32) -> i32                              │
+    │      return a pointer to `len` writable bytes for the request        │
+    │  export spokedpy_check(ptr: i32, len: i32) -> i64                    │
+    │      request  = UTF-8 JSON {"header": {...}, "body": "<source>"}     │
+    │      return   = (reply_ptr << 32) | reply_len                        │
+    │      reply    = UTF-8 JSON {"allow": bool, "reasons": ["..."]}       │
+    └──────────────────────────────────────────────────────────────────────┘
+
+Every check runs in a fresh instance with a fuel budget, so a plugin cannot
+keep state between snippets or spin forever.  Plugins that fail to load,
+trap, run out of fuel, or reply with malformed JSON DENY — a configured
+check that cannot run never waves a snippet through.
+
+The WASM runtime is the optional ``wasmtime`` package; without it every
+plugin found denies with an install hint.
+
+Built-in gates ship with SpokedPy and are enabled by name — for every
+dry-run via ``[gates] builtin = "fuzz"``, or for one promotion with
+``spokedpy promote stg-… --gate fuzz --seconds 30``:
+
+    fuzz      cargo-fuzz a Rust snippet's ``fuzz_target:`` function (fuzz_gate)
+    mutation  deny when the dry-run kills too few mutants (spec_mutation)
+    coverage  deny below a line-coverage percentage of a Rust snippet (coverage_gate)
+"""
+
+import os
+import json
+import time
+from abc import ABC, abstractmethod
+from dataclasses import dataclass, field, asdict
+from typing import Any, Dict, List, Optional
+
+
+WASM_FUEL = 50_000_000          # instructions (roughly) per check
+
+
+class GateError(Exception):
+    """Raised when a gate plugin cannot be loaded or replies incorrectly."""
+
+
+@dataclass
+class GateDecision:
+    """One gate's vote on one snippet."""
+    gate: str
+    allow: bool
+    reasons: List[str] = field(default_factory=list)
+    duration: float = 0.0
+    metrics: Dict[str, Any] = field(default_factory=dict)   # e.g. line_coverage
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def snippet_header(snippet) -> Dict[str, Any]:
+    """The metadata a gate sees alongside the source."""
+    return {
+        'staging_id': snippet.staging_id,
+        'language': snippet.language,
+        'engine_letter': snippet.engine_letter,
+        'label': snippet.label,
+        'code_hash': snippet.code_hash,
+        'reserved_address': snippet.reserved_address,
+        'spec_success': snippet.spec_success,
+        'spec_execution_time': snippet.spec_execution_time,
+        'spec_output': snippet.spec_output[:5000],
+    }
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# GATE TYPES
+# ═══════════════════════════════════════════════════════════════════════════
+
+class PromotionGate(ABC):
+    """Base class for promotion gates."""
+
+    kind = 'builtin'
+
+    def __init__(self, name: str):
+        self.name = name
+
+    @abstractmethod
+    def check(self, header: Dict[str, Any], body: str) -> GateDecision:
+        """Vote on one snippet; raise GateError if the check itself broke."""
+
+    def describe(self) -> Dict[str, Any]:
+        return {'name': self.name, 'kind': self.kind}
+
+
+class BrokenGate(PromotionGate):
+    """Stand-in for a plugin that failed to load — always denies."""
+
+    kind = 'broken'
+
+    def __init__(self, name: str, error: str, path: str = ''):
+        super().__init__(name)
+        self.error = error
+        self.path = path
+
+    def check(self, header, body) -> GateDecision:
+        return GateDecision(self.name, False, [f"gate unavailable: {self.error}"])
+
+    def describe(self) -> Dict[str, Any]:
+        return {**super().describe(), 'path': self.path, 'error': self.error}
+
+
+class WasmGate(PromotionGate):
+    """A gate implemented as a WebAssembly module (see module docstring)."""
+
+    kind = 'wasm'
+
+    def __init__(self, name: str, source, fuel: int = WASM_FUEL, path: str = ''):
+        """``source`` is a path to a .wasm file, or the module bytes / WAT text."""
+        super().__init__(name)
+        try:
+            import wasmtime
+        except ImportError:
+            raise GateError("the wasmtime package is not installed — pip install wasmtime")
+        self._wasmtime = wasmtime
+        self.fuel = fuel
+        self.path = path or (source if isinstance(source, str) and os.path.isfile(source) else '')
+
+        config = wasmtime.Config()
+        config.consume_fuel = True
+        self._engine = wasmtime.Engine(config)
+        try:
+            if self.path:
+                self._module = wasmtime.Module.from_file(self._engine, self.path)
+            else:
+                self._module = wasmtime.Module(self._engine, source)
+        except Exception as exc:
+            raise GateError(f"invalid WASM module: {exc}") from exc
+
+        exports = {e.name for e in self._module.exports}
+        missing = {'memory', 'spokedpy_alloc', 'spokedpy_check'} - exports
+        if missing:
+            raise GateError(f"module does not export {', '.join(sorted(missing))}")
+        if self._module.imports:
+            names = ', '.join(f"{i.module}.{i.name}" for i in self._module.imports)
+            raise GateError(f"module imports host functions ({names}); gate plugins get none")
+
+    def check(self, header, body) -> GateDecision:
+        wasmtime = self._wasmtime
+        store = wasmtime.Store(self._engine)
+        # wasmtime >= 17 replaced add_fuel() with set_fuel()
+        if hasattr(store, 'set_fuel'):
+            store.set_fuel(self.fuel)
+        else:
+            store.add_fuel(self.fuel)
+        request = json.dumps({'header': header, 'body': body}).encode('utf-8')
+        try:
+            instance = wasmtime.Instance(store, self._module, [])
+            exports = instance.exports(store)
+            memory = exports['memory']
+            ptr = exports['spokedpy_alloc'](store, len(request))
+            memory.write(store, request, ptr)
+            packed = exports['spokedpy_check'](store, ptr, len(request)) & 0xFFFFFFFFFFFFFFFF
+            reply_ptr, reply_len = packed >> 32, packed & 0xFFFFFFFF
+            raw = memory.read(store, reply_ptr, reply_ptr + reply_len)
+        except Exception as exc:
+            message = str(exc).strip().splitlines()[0] if str(exc).strip() else type(exc).__name__
+            if 'fuel' in str(exc).lower():
+                message = f"exceeded its fuel budget ({self.fuel:,})"
+            raise GateError(f"plugin trapped: {message}") from exc
+        try:
+            reply = json.loads(bytes(raw).decode('utf-8'))
+            allow = reply['allow']
+            reasons = reply.get('reasons', [])
+            if not isinstance(allow, bool) or not isinstance(reasons, list):
+                raise TypeError('allow must be a bool and reasons a list')
+        except (ValueError, KeyError, TypeError) as exc:
+            raise GateError(f"malformed reply: {exc}") from exc
+        return GateDecision(self.name, allow, [str(r) for r in reasons])
+
+    def describe(self) -> Dict[str, Any]:
+        return {**super().describe(), 'path': self.path, 'fuel': self.fuel}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# BUILT-IN GATES
+# ═══════════════════════════════════════════════════════════════════════════
+
+BUILTIN_GATES = ('fuzz', 'mutation', 'coverage')
+
+
+def builtin_gate(name: str, seconds: Optional[float] = None, fuzz_seconds: Optional[float] = None,
+                 fixtures_dir: str = '', evaluate=None, min_kill_rate: Optional[float] = None,
+                 mutation_limit: Optional[int] = None,
+                 coverage_min: Optional[float] = None) -> PromotionGate:
+    """Construct a built-in gate by name.
+
+    ``seconds`` is an explicit time budget (``--seconds``); without it the
+    fuzz gate uses ``fuzz_seconds`` and the mutation gate runs every mutant.
+    ``evaluate`` is the pipeline's sandbox (StagingPipeline.evaluate), which
+    the mutation gate runs mutants through.
+    """
+    if name == 'fuzz':
+        from .fuzz_gate import DEFAULT_SECONDS, FuzzGate
+        return FuzzGate(seconds=seconds or fuzz_seconds or DEFAULT_SECONDS,
+                        fixtures_dir=fixtures_dir)
+    if name == 'mutation':
+        from .spec_mutation import DEFAULT_LIMIT, DEFAULT_MIN_KILL_RATE, MutationGate, isolated_runner
+        if evaluate is None:
+            raise ValueError("the mutation gate needs the pipeline's sandbox (evaluate=)")
+        return MutationGate(isolated_runner(evaluate),
+                            min_kill_rate=DEFAULT_MIN_KILL_RATE if min_kill_rate is None else min_kill_rate,
+                            limit=mutation_limit or DEFAULT_LIMIT, seconds=seconds)
+    if name == 'coverage':
+        from .coverage_gate import DEFAULT_MIN, CoverageGate
+        return CoverageGate(minimum=DEFAULT_MIN if coverage_min is None else coverage_min)
+    raise ValueError(f"Unknown built-in gate '{name}' (choose from: {', '.join(BUILTIN_GATES)})")
+
+
+def load_builtin_gates(names: str, **options) -> List[PromotionGate]:
+    """Gates for a comma-separated ``[gates] builtin`` list (unknown names deny)."""
+    gates: List[PromotionGate] = []
+    for name in (n.strip() for n in (names or '').split(',')):
+        if not name:
+            continue
+        try:
+            gates.append(builtin_gate(name, **options))
+        except ValueError as exc:
+            gates.append(BrokenGate(name, str(exc)))
+    return gates
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# DISCOVERY + EVALUATION
+# ═══════════════════════════════════════════════════════════════════════════
+
+def load_plugin_gates(plugins_dir: Optional[str], fuel: int = WASM_FUEL) -> List[PromotionGate]:
+    """One gate per ``*.wasm`` in ``plugins_dir``, in filename order.
+
+    A plugin that cannot be loaded comes back as a BrokenGate (which denies)
+    rather than disappearing from the pipeline.
+    """
+    if not plugins_dir or not os.path.isdir(plugins_dir):
+        return []
+    gates: List[PromotionGate] = []
+    for filename in sorted(os.listdir(plugins_dir)):
+        if not filename.endswith('.wasm'):
+            continue
+        name = filename[:-len('.wasm')]
+        path = os.path.join(plugins_dir, filename)
+        try:
+            gates.append(WasmGate(name, path, fuel=fuel, path=path))
+        except GateError as exc:
+            gates.append(BrokenGate(name, str(exc), path))
+    return gates
+
+
+def evaluate_gates(gates: List[PromotionGate], header: Dict[str, Any],
+                   body: str) -> List[GateDecision]:
+    """Run every gate (no short-circuit, so the audit trail shows them all)."""
+    decisions = []
+    for gate in gates:
+        started = time.time()
+        try:
+            decision = gate.check(header, body)
+        except GateError as exc:
+            decision = GateDecision(gate.name, False, [str(exc)])
+        except Exception as exc:
+            decision = GateDecision(gate.name, False, [f"gate crashed: {exc}"])
+        decision.duration = time.time() - started
+        decisions.append(decision)
+    return decisions
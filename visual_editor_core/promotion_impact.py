@@ -0,0 +1,153 @@
+"""
+Promotion Impact — what else references the slot a promotion lands in.
+
+``spokedpy promote --impact`` asks the server before it promotes:
+
+    GET /api/staging/impact/<staging_id>
+
+and shows the blast radius of the slot the snippet was reserved:
+
+    dependents   promoted snippets that import the slot — they declare it
+                 in their leading comments, like a network level:
+                 ``# imports: d3, a1`` (``// imports:``, ``-- imports:``)
+    consumers    slots subscribed to its output on the registry, and mesh
+                 peers its output is relayed to
+    scheduled    held promotions (``promote --at`` / ``--window``) that
+                 import the slot — they land against the new snippet later
+
+Dependents run against the new snippet without having been staged with
+it, so ``promote --impact`` refuses while there are any unless
+``--force`` is given; consumers and scheduled promotions are shown but
+do not block.  A snippet's own slot in its ``imports:`` line is ignored.
+"""
+
+import re
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, List
+
+from .errors import ErrorKind, SpokedError
+
+_DECLARATION = re.compile(r'^(?:#|//|--)\s*imports:\s*(.*?)\s*$')
+_ADDRESS = re.compile(r'^[a-z]\d+$')
+
+
+def declared_imports(code: str) -> List[str]:
+    """
+    The slot addresses a snippet declares in its leading comments
+    (``# imports: d3, a1``), in order, [] if none; a ValueError for a
+    malformed address.
+    """
+    for line in code.split('\n'):
+        line = line.strip()
+        if not line or line.startswith('#!'):
+            continue
+        if not line.startswith(('#', '//', '--')):
+            break
+        match = _DECLARATION.match(line)
+        if match:
+            found = []
+            for address in re.split(r'[,\s]+', match.group(1).lower()):
+                if not address:
+                    continue
+                if not _ADDRESS.match(address):
+                    raise ValueError(f"imports declaration: '{address}' is not a slot "
+                                     f"address (e.g. d3)")
+                if address not in found:
+                    found.append(address)
+            return found
+    return []
+
+
+def _imports(snippet) -> List[str]:
+    try:
+        return declared_imports(snippet.code)
+    except ValueError:                  # staged before the declaration was checked
+        return []
+
+
+def _ref(snippet) -> Dict[str, str]:
+    return {'staging_id': snippet.staging_id, 'slot': snippet.reserved_address,
+            'label': snippet.label}
+
+
+@dataclass
+class Impact:
+    staging_id: str
+    slot: str
+    label: str = ''
+    replaces: str = ''                  # the staging id the slot holds now ('' → vacant)
+    dependents: List[Dict[str, str]] = field(default_factory=list)
+    consumers: List[str] = field(default_factory=list)      # subscribed slot addresses
+    peers: List[str] = field(default_factory=list)          # mesh peers relayed to
+    scheduled: List[Dict[str, Any]] = field(default_factory=list)
+
+    @property
+    def has_dependents(self) -> bool:
+        return bool(self.dependents)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {**asdict(self), 'has_dependents': self.has_dependents}
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'Impact':
+        return cls(data['staging_id'], data['slot'], data.get('label', ''),
+                   data.get('replaces', ''), list(data.get('dependents', [])),
+                   list(data.get('consumers', [])), list(data.get('peers', [])),
+                   list(data.get('scheduled', [])))
+
+
+def analyze(pipeline, staging_id: str, registry=None, relay=None) -> Impact:
+    """
+    The impact of promoting ``staging_id``: ``pipeline``'s promoted and held
+    snippets, ``registry``'s (a NodeRegistry) subscribers and ``relay``'s (a
+    MeshRelay) peers of its slot.  A NOT_FOUND SpokedError for an unknown id.
+    """
+    snippet = pipeline.get_snippet(staging_id)
+    if snippet is None:
+        raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                          staging_id=staging_id)
+    slot = snippet.reserved_address
+    impact = Impact(staging_id, slot, snippet.label)
+    current = pipeline.current_promoted()
+    if slot in current and current[slot].staging_id != staging_id:
+        impact.replaces = current[slot].staging_id
+    impact.dependents = [_ref(s) for address, s in sorted(current.items())
+                         if address != slot and slot in _imports(s)]
+    from .promotion_schedule import format_at
+    impact.scheduled = [{**_ref(s), 'iso_at': format_at(s.scheduled_for)}
+                        for s in pipeline.scheduled()
+                        if s.staging_id != staging_id and s.reserved_address != slot
+                        and slot in _imports(s)]
+    if registry is not None:
+        letter, position = slot[:1], int(slot[1:] or 0)
+        published = registry.get_slot_by_address(letter, position)
+        if published is not None:
+            for slot_id in registry.subscribers(published.slot_id):
+                subscriber = registry.get_slot(slot_id)
+                impact.consumers.append(subscriber.address if subscriber else slot_id)
+    if relay is not None:
+        impact.peers = relay.peers_for(slot)
+    return impact
+
+
+def format_impact(impact: Impact) -> str:
+    """The ``promote --impact`` report."""
+    target = f"replaces {impact.replaces}" if impact.replaces else 'vacant'
+    lines = [f"{impact.staging_id} → {impact.slot} ({impact.label}; {target})"]
+    if not (impact.dependents or impact.consumers or impact.peers or impact.scheduled):
+        lines.append('  nothing else references the slot')
+        return '\n'.join(lines)
+    if impact.dependents:
+        lines.append(f"  dependents ({len(impact.dependents)}) — import {impact.slot}:")
+        lines.extend(f"    {d['slot']:<5} {d['staging_id']}  {d['label']}" for d in impact.dependents)
+    if impact.consumers:
+        lines.append(f"  consumers ({len(impact.consumers)}) — subscribed to its output: "
+                     + ', '.join(impact.consumers))
+    if impact.peers:
+        lines.append(f"  mesh peers ({len(impact.peers)}) — relayed its output: "
+                     + ', '.join(impact.peers))
+    if impact.scheduled:
+        lines.append(f"  scheduled ({len(impact.scheduled)}) — held promotions importing it:")
+        lines.extend(f"    {s['slot']:<5} {s['staging_id']}  {s['label']}  at {s['iso_at']}"
+                     for s in impact.scheduled)
+    return '\n'.join(lines)
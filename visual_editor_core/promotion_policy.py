@@ -0,0 +1,187 @@
+"""
+Promotion Policy — per-slot auto-promote rules.
+
+``[gates] auto_promote`` is one switch for every slot.  A policy file
+(``[gates] policy_file`` → data/promotion_policy.toml) refines it per slot,
+so low-risk slots promote without a human while protected ones always wait
+in the approvals queue:
+
+    [[rule]]
+    name = "scratch"
+    slots = ["d*", "e*"]            # fnmatch on the slot address
+    auto_promote = true
+    max_spec_time = 2.0             # seconds
+    authors = ["alice", "ci-bot"]   # trusted submitters
+    gates = ["fuzz"]                # these gates must have run and allowed
+    require_spec = true             # a spec suite, not a bare dry-run
+
+    [[rule]]
+    name = "protected"
+    slots = ["a1", "a2"]
+    auto_promote = false            # always a human
+
+The first rule whose ``slots`` (and optional ``languages``) match decides.
+A snippet it covers is auto-promoted only if every condition holds — a
+failed condition holds it for review, with the reasons in the audit trail.
+Slots no rule covers fall back to ``[gates] auto_promote``.  A request
+that explicitly says ``auto_promote: false`` is never promoted.
+
+Every snippet dry-run already had to pass every configured gate; ``gates``
+additionally insists on particular ones (e.g. a built-in that is not on for
+every dry-run).  The file is re-read when it changes.
+"""
+
+import fnmatch
+import os
+import threading
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, List, Optional
+
+try:
+    import tomllib                      # Python 3.11+
+except ImportError:                     # pragma: no cover
+    import tomli as tomllib
+
+_RULE_KEYS = {'name', 'slots', 'languages', 'auto_promote', 'max_spec_time',
+              'authors', 'gates', 'require_spec'}
+
+
+@dataclass
+class PolicyRule:
+    name: str
+    slots: List[str]
+    auto_promote: bool = True
+    languages: List[str] = field(default_factory=list)
+    max_spec_time: Optional[float] = None
+    authors: List[str] = field(default_factory=list)
+    gates: List[str] = field(default_factory=list)
+    require_spec: bool = False
+
+    def matches(self, snippet) -> bool:
+        if self.languages and snippet.language not in self.languages:
+            return False
+        return any(fnmatch.fnmatchcase(snippet.reserved_address, p) for p in self.slots)
+
+    def check(self, snippet) -> List[str]:
+        """Why this rule will not auto-promote the snippet ([] → it will)."""
+        if not self.auto_promote:
+            return [f"slot {snippet.reserved_address} is protected (rule '{self.name}')"]
+        reasons = []
+        if self.max_spec_time is not None and snippet.spec_execution_time > self.max_spec_time:
+            reasons.append(f"spec_time {snippet.spec_execution_time:.4f}s > {self.max_spec_time:g}s")
+        if self.authors and snippet.author not in self.authors:
+            reasons.append(f"author '{snippet.author or 'unknown'}' is not trusted")
+        allowed = {g.get('gate') for g in snippet.gate_results if g.get('allow')}
+        for gate in self.gates:
+            if gate not in allowed:
+                reasons.append(f"gate '{gate}' has not passed")
+        if self.require_spec and not snippet.spec:
+            reasons.append('no spec suite')
+        return reasons
+
+
+@dataclass
+class PolicyDecision:
+    promote: bool
+    rule: str = ''                       # '' → no rule matched
+    reasons: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def _strings(value, where: str) -> List[str]:
+    if isinstance(value, str):
+        value = [value]
+    if not isinstance(value, list) or not all(isinstance(v, str) for v in value):
+        raise ValueError(f"{where} must be a string or a list of strings")
+    return value
+
+
+def parse_policy(text: str) -> List[PolicyRule]:
+    """Parse policy TOML into rules; ValueError on anything malformed."""
+    try:
+        data = tomllib.loads(text)
+    except tomllib.TOMLDecodeError as exc:
+        raise ValueError(f"invalid policy TOML: {exc}")
+    unknown = sorted(set(data) - {'rule'})
+    if unknown:
+        raise ValueError(f"unknown policy key(s) {', '.join(unknown)} (expected [[rule]] tables)")
+    rules = []
+    for i, raw in enumerate(data.get('rule', [])):
+        where = f"rule {raw.get('name') or i + 1}"
+        bad = sorted(set(raw) - _RULE_KEYS)
+        if bad:
+            raise ValueError(f"{where}: unknown key(s) {', '.join(bad)}")
+        if 'slots' not in raw:
+            raise ValueError(f"{where}: 'slots' is required")
+        max_spec_time = raw.get('max_spec_time')
+        if max_spec_time is not None and (isinstance(max_spec_time, bool)
+                                          or not isinstance(max_spec_time, (int, float))):
+            raise ValueError(f"{where}: max_spec_time must be a number of seconds")
+        for key in ('auto_promote', 'require_spec'):
+            if key in raw and not isinstance(raw[key], bool):
+                raise ValueError(f"{where}: {key} must be true or false")
+        rules.append(PolicyRule(
+            name=str(raw.get('name') or f'rule-{i + 1}'),
+            slots=_strings(raw['slots'], f"{where}: slots"),
+            auto_promote=raw.get('auto_promote', True),
+            languages=_strings(raw.get('languages', []), f"{where}: languages"),
+            max_spec_time=None if max_spec_time is None else float(max_spec_time),
+            authors=_strings(raw.get('authors', []), f"{where}: authors"),
+            gates=_strings(raw.get('gates', []), f"{where}: gates"),
+            require_spec=raw.get('require_spec', False),
+        ))
+    return rules
+
+
+class PromotionPolicy:
+    """The rules in ``path``, re-read whenever the file changes."""
+
+    def __init__(self, path: str):
+        self.path = path
+        self.error = ''
+        self._rules: List[PolicyRule] = []
+        self._mtime: Optional[float] = None
+        self._lock = threading.Lock()
+
+    @property
+    def rules(self) -> List[PolicyRule]:
+        try:
+            mtime = os.path.getmtime(self.path)
+        except OSError:
+            mtime = -1.0
+        with self._lock:
+            if mtime != self._mtime:
+                self._mtime = mtime
+                self._rules, self.error = [], ''
+                if mtime >= 0:
+                    try:
+                        with open(self.path, 'r', encoding='utf-8') as f:
+                            self._rules = parse_policy(f.read())
+                    except (OSError, ValueError) as exc:
+                        # A broken policy protects everything rather than nothing.
+                        self.error = f"{self.path}: {exc}"
+            return list(self._rules)
+
+    def decide(self, snippet, requested: Optional[bool], default: bool) -> PolicyDecision:
+        """
+        Should a PASSED snippet be auto-promoted?  ``requested`` is the
+        caller's explicit auto_promote (None if unspecified), ``default``
+        the [gates] auto_promote setting.
+        """
+        rules = self.rules
+        if requested is False:
+            return PolicyDecision(False, reasons=['auto_promote: false in the request'])
+        if self.error:
+            return PolicyDecision(False, 'invalid-policy', [self.error])
+        for rule in rules:
+            if rule.matches(snippet):
+                reasons = rule.check(snippet)
+                return PolicyDecision(not reasons, rule.name, reasons)
+        promote = default if requested is None else requested
+        return PolicyDecision(promote, reasons=[] if promote else ['[gates] auto_promote is off'])
+
+    def describe(self) -> Dict[str, Any]:
+        rules = self.rules
+        return {'path': self.path, 'error': self.error, 'rules': [asdict(r) for r in rules]}
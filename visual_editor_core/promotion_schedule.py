@@ -0,0 +1,131 @@
+"""
+Promotion Schedule — hold approved promotions for a maintenance window.
+
+``spokedpy promote stg-… --at 2026-02-11T02:00Z`` (or ``--window nightly``)
+marks a PASSED snippet as scheduled instead of promoting it.  The server's
+PromotionScheduler wakes every few seconds and promotes whatever is due in
+one pass, so a window's slot changes land together; ``promote --cancel``
+releases the hold and leaves the snippet PASSED.
+
+Named windows come from ``[gates] windows`` (``SPOKEDPY_WINDOWS``), a
+comma-separated list of ``name=[weekday ]HH:MM`` in UTC:
+
+    windows = "nightly=02:00, weekly=sun 03:30"
+
+A window resolves to its next occurrence after now.
+"""
+
+import calendar
+import re
+import threading
+import time
+from typing import Callable, Dict, Optional, Tuple
+
+DEFAULT_WINDOWS = 'nightly=02:00,weekly=sun 02:00'
+
+WEEKDAYS = ('mon', 'tue', 'wed', 'thu', 'fri', 'sat', 'sun')
+
+_ISO = re.compile(r'^(\d{4})-(\d{2})-(\d{2})[T ](\d{2}):(\d{2})(?::(\d{2}))?(Z|[+-]\d{2}:?\d{2})?$')
+_WINDOW = re.compile(r'^(?:(mon|tue|wed|thu|fri|sat|sun)\w*\s+)?(\d{1,2}):(\d{2})Z?$')
+
+
+def parse_at(text: str) -> float:
+    """ISO-8601 time (``2026-02-11T02:00Z``; no offset means UTC) → unix time."""
+    m = _ISO.match(text.strip())
+    if not m:
+        raise ValueError(f"Cannot parse time '{text}' (expected e.g. 2026-02-11T02:00Z)")
+    year, month, day, hour, minute = (int(g) for g in m.groups()[:5])
+    second = int(m.group(6) or 0)
+    at = calendar.timegm((year, month, day, hour, minute, second, 0, 0, 0))
+    offset = m.group(7)
+    if offset and offset != 'Z':
+        sign = -1 if offset[0] == '-' else 1
+        digits = offset[1:].replace(':', '')
+        at -= sign * (int(digits[:2]) * 3600 + int(digits[2:]) * 60)
+    return float(at)
+
+
+def format_at(at: float) -> str:
+    return time.strftime('%Y-%m-%dT%H:%MZ', time.gmtime(at))
+
+
+def parse_windows(spec: str) -> Dict[str, Tuple[Optional[int], int, int]]:
+    """``name=[weekday ]HH:MM`` list → name → (weekday or None, hour, minute)."""
+    windows = {}
+    for item in (spec or '').split(','):
+        if not item.strip():
+            continue
+        name, sep, when = item.partition('=')
+        m = _WINDOW.match(when.strip().lower())
+        if not sep or not name.strip() or not m:
+            raise ValueError(f"Bad window '{item.strip()}' (expected name=[weekday ]HH:MM)")
+        day, hour, minute = m.group(1), int(m.group(2)), int(m.group(3))
+        if hour > 23 or minute > 59:
+            raise ValueError(f"Bad window time in '{item.strip()}'")
+        windows[name.strip()] = (WEEKDAYS.index(day) if day else None, hour, minute)
+    return windows
+
+
+def next_window(window: Tuple[Optional[int], int, int], now: Optional[float] = None) -> float:
+    """The next occurrence of ``window`` strictly after ``now`` (UTC)."""
+    now = time.time() if now is None else now
+    weekday, hour, minute = window
+    t = time.gmtime(now)
+    today = calendar.timegm((t.tm_year, t.tm_mon, t.tm_mday, hour, minute, 0, 0, 0, 0))
+    days = 0 if weekday is None else (weekday - t.tm_wday) % 7
+    at = today + days * 86400
+    if at <= now:
+        at += 86400 if weekday is None else 7 * 86400
+    return float(at)
+
+
+def resolve_when(at: str = '', window: str = '', windows: str = DEFAULT_WINDOWS,
+                 now: Optional[float] = None) -> float:
+    """The promotion time for ``--at`` or ``--window`` (exactly one of them)."""
+    if bool(at) == bool(window):
+        raise ValueError("Give exactly one of 'at' (ISO time) or 'window' (a named window)")
+    if at:
+        return parse_at(at)
+    known = parse_windows(windows)
+    if window not in known:
+        raise ValueError(f"Unknown window '{window}' "
+                         f"(configured: {', '.join(sorted(known)) or 'none'})")
+    return next_window(known[window], now)
+
+
+class PromotionScheduler:
+    """Background thread: ``promote_due()`` every ``interval`` seconds."""
+
+    def __init__(self, promote_due: Callable[[], list], interval: float = 5.0,
+                 on_promoted: Optional[Callable[[list], None]] = None):
+        self._promote_due = promote_due
+        self.interval = interval
+        self._on_promoted = on_promoted
+        self._stop = threading.Event()
+        self._thread: Optional[threading.Thread] = None
+
+    def start(self):
+        if self._thread is not None and self._thread.is_alive():
+            return
+        self._stop.clear()
+        self._thread = threading.Thread(target=self._loop, daemon=True,
+                                        name='promotion-scheduler')
+        self._thread.start()
+
+    def stop(self):
+        self._stop.set()
+        if self._thread is not None:
+            self._thread.join(timeout=self.interval + 1)
+
+    def tick(self) -> list:
+        promoted = self._promote_due()
+        if promoted and self._on_promoted is not None:
+            self._on_promoted(promoted)
+        return promoted
+
+    def _loop(self):
+        while not self._stop.wait(self.interval):
+            try:
+                self.tick()
+            except Exception as exc:
+                print(f"  [SCHEDULE] tick failed: {exc}")
@@ -0,0 +1,128 @@
+"""
+Respec — re-run the specs of promoted snippets under the current toolchain.
+
+After a toolchain bump (a new rustc, say) every promoted snippet of that
+engine needs to be checked again:
+
+    spokedpy respec --engine rust --all-promoted [--quarantine]
+
+runs each one's spec suite (or plain dry-run, if it has none) in the
+speculation sandbox, several at a time, and reports the ones that no
+longer pass:
+
+    respec rust — rustc 1.86.0 (05f9846f8 2025-03-31): 14 snippets, 2 now failing
+      ✘ d3   parse-csv     stg-1a2b3c4d5e6f  0.2130s  (was 0.1904s)  error[E0658]: …
+      ✘ d7   fib           stg-9f8e7d6c5b4a  0.0871s  (was 0.0702s)  1/3 spec case(s) failed …
+
+With ``quarantine`` the newly failing snippets are rolled back (their
+files stay on disk); without it their slots are marked DEGRADED until
+they pass again (see revalidation).  Only the slot's current snippet is checked, and the
+promoted records are left as they were; each run is recorded in the
+audit trail as ``respec``.
+"""
+
+import sys
+import time
+from concurrent.futures import ThreadPoolExecutor
+from dataclasses import asdict, dataclass, field
+from typing import Any, Callable, Dict, List
+
+
+def toolchain_version(language: str) -> str:
+    """The toolchain a re-run will use, e.g. 'rustc 1.86.0 (…)'; '' if none is found."""
+    from .engine_doctor import ENGINE_TOOLCHAINS, find_toolchain, probe_version
+
+    if language == 'python':
+        return f"python {sys.version.split()[0]}"
+    spec = ENGINE_TOOLCHAINS.get(language)
+    binary = find_toolchain(language)
+    if spec is None or binary is None:
+        return ''
+    return probe_version(binary, spec.version_args) or binary
+
+
+@dataclass
+class RespecResult:
+    staging_id: str
+    slot: str
+    label: str
+    language: str
+    passed: bool
+    spec_time: float
+    previous_spec_time: float
+    error: str = ''
+    quarantined: bool = False
+    degraded: bool = False                   # its slot is now DEGRADED (see revalidation)
+    resources: Dict[str, Any] = field(default_factory=dict)     # see accounting
+
+
+@dataclass
+class RespecReport:
+    engine: str                              # language filter ('' → every engine)
+    toolchains: Dict[str, str]               # language → toolchain_version()
+    results: List[RespecResult] = field(default_factory=list)
+    duration: float = 0.0
+
+    @property
+    def failing(self) -> List[RespecResult]:
+        return [r for r in self.results if not r.passed]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {'engine': self.engine, 'toolchains': self.toolchains,
+                'results': [asdict(r) for r in self.results],
+                'checked': len(self.results), 'failing': len(self.failing),
+                'quarantined': sum(r.quarantined for r in self.results),
+                'degraded': sum(r.degraded for r in self.results),
+                'duration': self.duration}
+
+
+def respec(snippets: List, run: Callable[[Any], Dict[str, Any]], workers: int = 4,
+           engine: str = '') -> RespecReport:
+    """Re-run every snippet with ``run(snippet)`` (a dry-run result dict).
+
+    Python executes in-process with redirected stdout, so Python snippets
+    run one at a time; the other engines run up to ``workers`` at once.
+    """
+    started = time.time()
+    languages = sorted({s.language for s in snippets})
+    report = RespecReport(engine, {lang: toolchain_version(lang) for lang in languages})
+
+    def one(snippet) -> RespecResult:
+        try:
+            result = run(snippet)
+        except Exception as exc:
+            result = {'success': False, 'error': str(exc), 'execution_time': 0.0}
+        return RespecResult(snippet.staging_id, snippet.reserved_address, snippet.label,
+                            snippet.language, bool(result.get('success')),
+                            result.get('execution_time', 0.0), snippet.spec_execution_time,
+                            '' if result.get('success') else (result.get('error') or '')[:2000],
+                            resources=result.get('resources', {}))
+
+    in_process = [s for s in snippets if s.language == 'python']
+    parallel = [s for s in snippets if s.language != 'python']
+    by_id = {r.staging_id: r for r in map(one, in_process)}
+    with ThreadPoolExecutor(max_workers=max(1, int(workers))) as pool:
+        by_id.update((r.staging_id, r) for r in pool.map(one, parallel))
+    report.results = [by_id[s.staging_id] for s in snippets]
+    report.duration = time.time() - started
+    return report
+
+
+def format_respec(report: Dict[str, Any]) -> str:
+    toolchains = '; '.join(v or f"{k}: no toolchain found" for k, v in report['toolchains'].items())
+    lines = [f"  respec {report['engine'] or 'all engines'} — {toolchains or 'nothing promoted'}: "
+             f"{report['checked']} snippet(s), {report['failing']} now failing "
+             f"({report['duration']:.1f}s)"]
+    failing = [r for r in report['results'] if not r['passed']]
+    width = max([len(r['label']) for r in failing] or [0])
+    for r in failing:
+        error = (r['error'].strip().splitlines() or [''])[0][:120]
+        lines.append(f"    ✘ {r['slot']:<4} {r['label']:<{width}}  {r['staging_id']}  "
+                     f"{r['spec_time']:.4f}s  (was {r['previous_spec_time']:.4f}s)  {error}"
+                     + ('  [quarantined]' if r['quarantined'] else '')
+                     + ('  [degraded]' if r.get('degraded') else ''))
+    if failing and not report['quarantined']:
+        lines.append('  → rerun with --quarantine to roll the failing snippets back')
+    elif not failing and report['checked']:
+        lines.append('  ✔ every promoted snippet still passes')
+    return '\n'.join(lines)
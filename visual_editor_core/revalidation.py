@@ -0,0 +1,116 @@
+"""
+Revalidation — slots whose promoted snippet stopped passing its spec.
+
+A store can re-run the spec of every slot's current promoted snippet on a
+schedule, the way ``spokedpy respec`` does by hand (see respec):
+
+    [revalidate]
+    interval_minutes = 60       # 0: never (the default)
+
+A snippet that fails — on the schedule, or in a ``respec`` without
+``--quarantine`` — marks its slot DEGRADED: a ``slot_degraded`` audit
+event and a ``degraded`` notification to the slot's owners (see
+snippet_owners), once, not on every failing run.  While the slot is
+degraded
+
+    promote()            refuses every snippet that imports it (``# imports:
+                         d3``, see promotion_impact) with a SLOT_DEGRADED
+                         SpokedError — exit 18, HTTP 409
+    run_full_pipeline    leaves those snippets PASSED instead of promoting
+                         them, and scheduled promotions of them are dropped
+    the slot's snippet   carries ``degraded`` (slot, reason, since, toolchain),
+                         shown by ``spokedpy list`` and the staging API;
+                         GET /api/staging/degraded lists every degraded slot
+
+Promoting into the degraded slot itself is not blocked — that is how it is
+fixed.  The slot recovers (``slot_recovered``) when its snippet passes a
+revalidation again, when it is rolled back, or when another snippet takes
+it.  The state is rebuilt from the audit log when the store opens, so a
+restart keeps it; a snippet re-promoted from the checkpoint with the same
+code_hash is still degraded.
+"""
+
+import threading
+import time
+from dataclasses import asdict, dataclass
+from typing import Any, Callable, Dict, Optional
+
+
+@dataclass
+class Degradation:
+    slot: str                               # e.g. 'd3'
+    staging_id: str                         # the snippet that failed
+    code_hash: str                          # …by content, across re-promotions
+    label: str = ''
+    reason: str = ''                        # first lines of the failure
+    toolchain: str = ''                     # what it failed under (see respec)
+    since: float = 0.0                      # Unix time of the first failing run
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def load_degraded(audit) -> Dict[str, Degradation]:
+    """Slot → its Degradation, replayed from ``audit`` (an AuditLogger)."""
+    from .snippet_staging import AuditEventType
+    events = (audit.read_events(AuditEventType.SLOT_DEGRADED)
+              + audit.read_events(AuditEventType.SLOT_RECOVERED))
+    found: Dict[str, Degradation] = {}
+    for event in sorted(events, key=lambda e: e.get('seq', 0)):
+        data = event.get('data', {})
+        slot = data.get('slot', '')
+        if not slot:
+            continue
+        if event['event'] == AuditEventType.SLOT_DEGRADED.value:
+            found[slot] = Degradation(slot, event['staging_id'], data.get('code_hash', ''),
+                                      data.get('label', ''), data.get('reason', ''),
+                                      data.get('toolchain', ''),
+                                      float(data.get('since') or event.get('timestamp', 0)))
+        else:
+            found.pop(slot, None)
+    return found
+
+
+def failure_reason(error: str, limit: int = 300) -> str:
+    """The first non-blank lines of a failing run's error, up to ``limit`` chars."""
+    lines = [line.strip() for line in (error or '').splitlines() if line.strip()]
+    return ' — '.join(lines)[:limit] or 'spec failed'
+
+
+class Revalidator:
+    """Background thread: ``revalidate()`` every ``interval`` seconds (0: never)."""
+
+    def __init__(self, revalidate: Callable[[], Dict[str, Any]], interval: float = 0.0,
+                 on_report: Optional[Callable[[Dict[str, Any]], None]] = None):
+        self._revalidate = revalidate
+        self.interval = float(interval)
+        self._on_report = on_report
+        self.last_run = 0.0
+        self._stop = threading.Event()
+        self._thread: Optional[threading.Thread] = None
+
+    def start(self):
+        if self.interval <= 0 or (self._thread is not None and self._thread.is_alive()):
+            return
+        self._stop.clear()
+        self._thread = threading.Thread(target=self._loop, daemon=True, name='revalidator')
+        self._thread.start()
+
+    def stop(self):
+        self._stop.set()
+        if self._thread is not None:
+            self._thread.join(timeout=5)
+
+    def tick(self) -> Dict[str, Any]:
+        report = self._revalidate()
+        self.last_run = time.time()
+        if self._on_report is not None:
+            self._on_report(report)
+        return report
+
+    def _loop(self):
+        while not self._stop.wait(self.interval):
+            try:
+                self.tick()
+            except Exception as exc:
+                print(f"  [REVALIDATE] run failed: {exc}")
@@ -0,0 +1,384 @@
+"""
+Scratch Dirs — every spec run gets a working directory of its own, then loses it.
+
+A dry-run no longer runs in the server's cwd with the user's HOME: each
+spec case (or the single run of a snippet without a suite) gets a fresh
+``spokedpy-scratch-*`` directory, removed as soon as the run ends:
+
+    ┌──────────────────┬───────────────────────────────────────────────────┐
+    │  engine spawns   │  ``_run_subprocess`` starts the child in the      │
+    │                  │  scratch dir, with HOME and TMPDIR (TMP, TEMP,    │
+    │                  │  USERPROFILE) inside it; toolchain homes under    │
+    │                  │  the real HOME (~/.cargo, ~/.rustup, ~/go, …)     │
+    │                  │  stay pinned so the engines still find them       │
+    │  Python          │  an audit-hook write guard refuses opening a file │
+    │                  │  for writing, removing, renaming, mkdir or chmod  │
+    │                  │  outside scratch — in the ``python -I`` child of  │
+    │                  │  a spec case, and around the in-process exec() of │
+    │                  │  a plain dry-run (which has no scratch cwd, so it │
+    │                  │  may not write files at all: give it a suite);    │
+    │                  │  it refuses starting a process (os.system,        │
+    │                  │  subprocess, os.exec*, posix_spawn) and loading   │
+    │                  │  native code (ctypes) outright: the guard could   │
+    │                  │  not see what those write                         │
+    │  fixtures        │  the sidecar's ``fixtures`` are copied in at the  │
+    │                  │  same relative paths, read-only; a write to one   │
+    │                  │  is refused (Python) or found afterwards (any     │
+    │                  │  engine: the fixture no longer matches)           │
+    └──────────────────┴───────────────────────────────────────────────────┘
+
+Whatever the guard refused or found is a violation: the run FAILS, and
+the violations are kept on the snippet (``sandbox_violations``) and in its
+failure bundle.  Engines other than Python are confined by cwd and HOME
+only — a write to an absolute path elsewhere is not detectable.
+
+Enabled via:  DB setting → SPOKEDPY_SCRATCH env → on  (``[sandbox] scratch``)
+
+This module imports nothing from the package: the Python child of a spec
+case loads it by path to install the guard (see child_command).
+"""
+
+import hashlib
+import json
+import os
+import shutil
+import stat
+import sys
+import tempfile
+import threading
+import traceback
+from typing import Dict, List, Optional, Tuple
+
+SCRATCH_PREFIX = 'spokedpy-scratch-'
+VIOLATIONS_FILE = '.violations'
+CODE_ENV = 'SPOKEDPY_SNIPPET_CODE'
+SCRATCH_ENV = 'SPOKEDPY_SCRATCH'
+FIXTURES_ENV = 'SPOKEDPY_SCRATCH_FIXTURES'
+
+# Toolchain state that lives under the real HOME: pinned there for engine
+# children (when it exists and the caller did not set the variable).
+TOOLCHAIN_HOMES = (
+    ('CARGO_HOME', '.cargo'),
+    ('RUSTUP_HOME', '.rustup'),
+    ('GOPATH', 'go'),
+    ('GOCACHE', os.path.join('.cache', 'go-build')),
+    ('NPM_CONFIG_CACHE', '.npm'),
+    ('NUGET_PACKAGES', os.path.join('.nuget', 'packages')),
+    ('DOTNET_CLI_HOME', ''),
+    ('GRADLE_USER_HOME', '.gradle'),
+    ('KONAN_DATA_DIR', '.konan'),
+    ('R_LIBS_USER', 'R'),
+)
+
+_WRITE_FLAGS = os.O_WRONLY | os.O_RDWR | os.O_CREAT | os.O_APPEND | os.O_TRUNC
+# audit event → indexes of its path arguments
+_PATH_EVENTS = {
+    'os.remove': (0,), 'os.rmdir': (0,), 'os.mkdir': (0,), 'os.rename': (0, 1),
+    'os.truncate': (0,), 'os.chmod': (0,), 'os.chown': (0,), 'os.symlink': (1,),
+    'os.link': (1,), 'os.utime': (0,), 'shutil.rmtree': (0,),
+}
+# audit event → (op, index of the argument that names it): code the guard cannot follow
+_ESCAPE_EVENTS = {
+    'os.system': ('system', 0), 'os.exec': ('exec', 0), 'os.posix_spawn': ('posix_spawn', 0),
+    'os.spawn': ('spawn', 1), 'os.startfile': ('startfile', 0),
+    'subprocess.Popen': ('popen', 0), 'ctypes.dlopen': ('dlopen', 0),
+}
+_DEVICES = ('/dev/null', '/dev/stdout', '/dev/stderr', '/dev/tty', os.devnull)
+
+_active = threading.local()
+_hook_lock = threading.Lock()
+_hook_state = {'installed': False}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# THE WRITE CHECK (shared by the in-process guard and the Python child)
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _within(path: str, root: str) -> bool:
+    return path == root or path.startswith(root + os.sep)
+
+
+def write_target(event: str, args: tuple) -> List[Tuple[str, str]]:
+    """``(op, path)`` for each path an audit event would write to ([] if none)."""
+    if event == 'open':
+        path, mode, flags = (tuple(args) + (None, None, 0))[:3]
+        writes = any(c in mode for c in 'wax+') if isinstance(mode, str) \
+            else bool((flags or 0) & _WRITE_FLAGS)
+        targets = [('open', path)] if writes else []
+    else:
+        targets = [(event.split('.')[-1], args[i]) for i in _PATH_EVENTS.get(event, ())
+                   if i < len(args)]
+    found = []
+    for op, path in targets:
+        if isinstance(path, int) or path is None:
+            continue                        # an fd: it was checked when it was opened
+        path = os.fsdecode(path)
+        found.append((op, os.path.realpath(path)))
+    return found
+
+
+def escape(event: str, args: tuple) -> Optional[Tuple[str, str, str]]:
+    """``(op, target, reason)`` if an audit event starts a process or loads native code."""
+    if event not in _ESCAPE_EVENTS:
+        return None
+    op, index = _ESCAPE_EVENTS[event]
+    target = args[index] if index < len(args) else None
+    if target is None and event == 'subprocess.Popen' and len(args) > 1:
+        target = args[1]                    # no executable: argv[0] (or the shell command)
+    if isinstance(target, (list, tuple)):
+        target = ' '.join(os.fsdecode(a) if isinstance(a, (str, bytes)) else str(a)
+                          for a in target)
+    target = os.fsdecode(target) if isinstance(target, (str, bytes)) else str(target or '')
+    what = 'loading native code' if op == 'dlopen' else 'starting a process'
+    return op, target, f"{op} refused: {what} escapes the write guard: {target}"
+
+
+def refused(op: str, path: str, root: str, fixtures: Dict[str, str]) -> Optional[str]:
+    """Why a write to ``path`` is refused, or None if the snippet may make it."""
+    if path in _DEVICES:
+        return None
+    if os.path.basename(os.path.dirname(path)) == '__pycache__':
+        return None                         # the interpreter caching bytecode, not the snippet
+    if not _within(path, root):
+        return f"{op} outside the scratch dir: {path}"
+    relative = os.path.relpath(path, root).replace(os.sep, '/')
+    if relative in fixtures:
+        return f"{op} of read-only fixture {relative}"
+    return None
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SCRATCH DIR
+# ═══════════════════════════════════════════════════════════════════════════
+
+class Scratch:
+    """
+    Context manager: one run's scratch dir, active on this thread until it
+    exits (and is removed).  ``fixtures`` maps relative paths to their text
+    (SpecSuite.fixtures); ``case`` names the spec case in violations.
+    """
+
+    def __init__(self, fixtures: Optional[Dict[str, Optional[str]]] = None, case: str = ''):
+        self.fixtures = dict(fixtures or {})
+        self.case = case
+        self.root = ''
+        self.violations: List[Dict[str, str]] = []
+        self._hashes: Dict[str, str] = {}
+        self._previous = None
+
+    def __enter__(self) -> 'Scratch':
+        self.root = os.path.realpath(tempfile.mkdtemp(prefix=SCRATCH_PREFIX))
+        try:
+            os.makedirs(os.path.join(self.root, '.home'))
+            os.makedirs(os.path.join(self.root, '.tmp'))
+            for relative, text in sorted(self.fixtures.items()):
+                self._write_fixture(relative, text)
+        except BaseException:
+            _remove(self.root)
+            raise
+        self._previous = getattr(_active, 'scratch', None)
+        _active.scratch = self
+        return self
+
+    def __exit__(self, *exc):
+        _active.scratch = self._previous
+        self.violations.extend(self._reported())
+        self.violations.extend(self._changed_fixtures())
+        _remove(self.root)
+        return False
+
+    def _write_fixture(self, relative: str, text: Optional[str]):
+        if text is None:
+            raise ValueError(f"fixture '{relative}' was declared but not read — stage the "
+                             f"snippet from its file so the sidecar's fixtures are loaded")
+        path = os.path.join(self.root, *relative.split('/'))
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+        with open(path, 'w', encoding='utf-8', newline='') as f:
+            f.write(text)
+        os.chmod(path, stat.S_IRUSR | stat.S_IRGRP | stat.S_IROTH)
+        self._hashes[relative] = hashlib.sha256(text.encode('utf-8')).hexdigest()
+
+    def env(self, base: Optional[Dict[str, str]] = None) -> Dict[str, str]:
+        """``base`` (the process env) with HOME and the temp dirs moved into scratch."""
+        env = dict(os.environ if base is None else base)
+        real_home = os.path.expanduser('~')
+        for name, relative in TOOLCHAIN_HOMES:
+            path = os.path.join(real_home, relative) if relative else real_home
+            if name not in env and os.path.isdir(path):
+                env[name] = path
+        home, tmp = os.path.join(self.root, '.home'), os.path.join(self.root, '.tmp')
+        env.update({'HOME': home, 'USERPROFILE': home, 'TMPDIR': tmp, 'TMP': tmp,
+                    'TEMP': tmp, SCRATCH_ENV: self.root})
+        return env
+
+    def record(self, reason: str, op: str, path: str):
+        self.violations.append({'case': self.case, 'op': op, 'path': path, 'reason': reason})
+
+    def _reported(self) -> List[Dict[str, str]]:
+        """What the Python child's guard refused (one JSON object per line)."""
+        found = []
+        try:
+            with open(os.path.join(self.root, VIOLATIONS_FILE), 'r', encoding='utf-8') as f:
+                for line in f:
+                    try:
+                        entry = json.loads(line)
+                    except json.JSONDecodeError:
+                        continue
+                    if isinstance(entry, dict):
+                        found.append({'case': self.case, 'op': str(entry.get('op', '')),
+                                      'path': str(entry.get('path', '')),
+                                      'reason': str(entry.get('reason', ''))})
+        except OSError:
+            pass
+        return found
+
+    def _changed_fixtures(self) -> List[Dict[str, str]]:
+        found = []
+        for relative, digest in sorted(self._hashes.items()):
+            path = os.path.join(self.root, *relative.split('/'))
+            try:
+                with open(path, 'rb') as f:
+                    changed = hashlib.sha256(f.read()).hexdigest() != digest
+                op = 'modify'
+            except OSError:
+                changed, op = True, 'remove'
+            if changed and not any(v['path'].endswith(relative) for v in self.violations):
+                found.append({'case': self.case, 'op': op, 'path': relative,
+                              'reason': f"{op} of read-only fixture {relative}"})
+        return found
+
+
+def _remove(root: str):
+    for directory, _dirs, files in os.walk(root):
+        for name in files:
+            try:
+                os.chmod(os.path.join(directory, name), stat.S_IRUSR | stat.S_IWUSR)
+            except OSError:
+                pass
+    shutil.rmtree(root, ignore_errors=True)
+
+
+def current() -> Optional[Scratch]:
+    """The scratch dir active on this thread, if any."""
+    return getattr(_active, 'scratch', None)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# ENGINE SPAWNS
+# ═══════════════════════════════════════════════════════════════════════════
+
+def apply(args: tuple, kwargs: dict) -> Tuple[tuple, dict]:
+    """Rewrite a ``subprocess.run`` call to start in this thread's scratch dir.
+
+    No-op without one; a spawn that sets its own cwd keeps it.
+    """
+    active = current()
+    if active is None:
+        return args, kwargs
+    kwargs = dict(kwargs)
+    kwargs.setdefault('cwd', active.root)
+    kwargs['env'] = active.env(kwargs.get('env'))
+    return args, kwargs
+
+
+def child_command(python: str, code: str, args: List[str]) -> Tuple[List[str], Dict[str, str]]:
+    """
+    ``python -I -u -c`` for a spec case inside this thread's scratch dir:
+    a bootstrap that loads this file, installs the write guard and runs
+    ``code`` (passed in the environment) as ``__main__``.  Returns the
+    argv and the env entries to add.
+    """
+    bootstrap = (
+        "import importlib.util as _u\n"
+        f"_s = _u.spec_from_file_location('_spokedpy_scratch', {os.path.abspath(__file__)!r})\n"
+        "_m = _u.module_from_spec(_s)\n"
+        "_s.loader.exec_module(_m)\n"
+        "del _u, _s\n"
+        "_m.run_child()\n"
+    )
+    fixtures = sorted(current()._hashes) if current() is not None else []
+    return [python, '-I', '-u', '-c', bootstrap, *args], {CODE_ENV: code,
+                                                          FIXTURES_ENV: json.dumps(fixtures)}
+
+
+def run_child():
+    """Inside the Python child: guard writes, then run the snippet as ``__main__``."""
+    code = os.environ.pop(CODE_ENV, '')
+    root = os.path.realpath(os.environ.get(SCRATCH_ENV) or os.getcwd())
+    fixtures = dict.fromkeys(json.loads(os.environ.pop(FIXTURES_ENV, '') or '[]'), '')
+    log = os.path.join(root, VIOLATIONS_FILE)
+
+    def report(op, path, reason):
+        fd = os.open(log, os.O_WRONLY | os.O_CREAT | os.O_APPEND, 0o600)
+        with os.fdopen(fd, 'a', encoding='utf-8') as f:
+            f.write(json.dumps({'op': op, 'path': path, 'reason': reason}) + '\n')
+        raise PermissionError(f"spokedpy sandbox: {reason}")
+
+    def hook(event, args):
+        escaped = escape(event, args)
+        if escaped:
+            report(*escaped)
+        for op, path in write_target(event, args):
+            if path == log:
+                continue
+            reason = refused(op, path, root, fixtures)
+            if reason:
+                report(op, path, reason)
+
+    main = sys.modules['__main__']
+    main.__dict__.pop('_m', None)                   # the bootstrap's handle on this module
+    compiled = compile(code, '<string>', 'exec')
+    sys.addaudithook(hook)
+    try:
+        exec(compiled, main.__dict__)
+    except SystemExit:
+        raise
+    except BaseException as exc:
+        # As python -c prints it: without this function's frame.
+        traceback.print_exception(type(exc), exc, exc.__traceback__.tb_next)
+        sys.exit(1)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# IN-PROCESS GUARD (Python engine)
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _audit_hook(event: str, args: tuple):
+    active = getattr(_active, 'guarded', None)
+    if active is None:
+        return
+    escaped = escape(event, args)
+    if escaped:
+        op, target, reason = escaped
+        active.record(reason, op, target)
+        raise PermissionError(f"spokedpy sandbox: {reason}")
+    for op, path in write_target(event, args):
+        reason = refused(op, path, active.root, active._hashes)
+        if reason:
+            active.record(reason, op, path)
+            raise PermissionError(f"spokedpy sandbox: {reason}")
+
+
+class write_guard:
+    """Context manager: refuse writes outside this thread's scratch dir (if it has one)."""
+
+    def __enter__(self):
+        self._previous = getattr(_active, 'guarded', None)
+        active = current()
+        if active is not None:
+            with _hook_lock:
+                if not _hook_state['installed']:
+                    sys.addaudithook(_audit_hook)
+                    _hook_state['installed'] = True
+            _active.guarded = active
+        return self
+
+    def __exit__(self, *exc):
+        _active.guarded = self._previous
+        return False
+
+
+def describe(violations: List[Dict[str, str]]) -> str:
+    """One line per violation, as the dry-run's error and the failure bundle show them."""
+    return '\n'.join((f"{v['case']}: " if v.get('case') else '') + v.get('reason', '')
+                     for v in violations)
@@ -0,0 +1,289 @@
+"""
+Scripting API — stage, spec, promote and list from Python, without the CLI.
+
+For orchestration scripts and notebooks that drive a store in-process
+instead of shelling out to ``spokedpy``:
+
+    from visual_editor_core.scripting import Spoked
+
+    spoked = Spoked('data')                  # data/snippets, data/staging_audit.jsonl
+    snippet = spoked.stage('etl.py')         # queue + dry-run; etl.spec.toml is used
+    if snippet.spec_success:
+        spoked.promote(snippet.staging_id)
+    for s in spoked.list(phase='promoted', meta={'team': 'payments'}):
+        print(s.staging_id, s.reserved_address)
+    header = spoked.header('a1')             # SnippetHeader of the file a1 holds
+    print(header.promoted, header.spec_time, header.meta)
+
+Spoked owns a StagingPipeline with an executor for every engine
+(``engines`` narrows them, as ``[engines] enabled`` does for the server),
+so the same gates, spec suites and promoted-file headers apply.  list()
+covers what this Spoked staged; slots() and header() read the promoted
+files on disk (SlotResolver), whoever promoted them.  Slot reservations
+live in the pipeline, so two writers on one store can pick the same
+slot: point a writing Spoked at a store no server is using, or open it
+with ``read_only=True`` to only read.  Failures raise SpokedError (see
+errors.py) just as the pipeline does.
+"""
+
+import os
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from .errors import ErrorKind, SpokedError
+from .lineage import Ancestor
+from .slot_resolver import SlotResolver
+from .snippet_staging import (
+    DEFAULT_SHARD_WIDTH,
+    LANG_EXTENSIONS,
+    SnippetFileHeader,
+    StagedSnippet,
+    StagingPipeline,
+    StoreMode,
+    parse_file_header,
+    parse_timestamp,
+)
+
+
+def _when(value: str) -> Optional[datetime]:
+    try:
+        return datetime.fromtimestamp(parse_timestamp(value), timezone.utc) if value else None
+    except ValueError:
+        return None
+
+
+def _number(value: str) -> Optional[float]:
+    try:
+        return float(value.rstrip('s%'))
+    except ValueError:
+        return None
+
+
+@dataclass(frozen=True)
+class SnippetHeader:
+    """The banner of a promoted file, typed (SnippetFileHeader keeps the raw fields)."""
+    path: str
+    staging_id: str
+    language: str
+    engine: str                             # 'RUST'
+    slot: str                               # 'd3'
+    position: int
+    label: str
+    code_hash: str
+    created: Optional[datetime]
+    promoted: Optional[datetime]
+    promoted_seq: int                       # 0: older banner
+    spec_time: Optional[float]              # seconds
+    spec_passed: bool
+    banner_version: int
+    spec_hash: str = ''
+    coverage: Optional[float] = None        # line coverage, percent
+    approved_by: str = ''
+    meta: Dict[str, str] = field(default_factory=dict)
+    spec_cases: Tuple[Dict[str, Any], ...] = ()
+    verified: bool = False                  # the body still matches code_hash
+    network: str = ''                       # declared network level ('' → none declared)
+    parent: str = ''                        # staging_id it was staged from (see lineage)
+
+    @classmethod
+    def from_header(cls, header: SnippetFileHeader, path: str = '') -> 'SnippetHeader':
+        f = header.fields
+        slot = f.get('slot', '').split()
+        position = slot[2].rstrip(')') if len(slot) > 2 else ''
+        coverage = f.get('coverage', '')
+        return cls(
+            path=path, staging_id=f.get('staging_id', ''), language=f.get('language', ''),
+            engine=(f.get('engine', '').split() or [''])[0], slot=(slot or [''])[0],
+            position=int(position) if position.isdigit() else 0,
+            label=f.get('label', ''), code_hash=f.get('code_hash', ''),
+            created=_when(f.get('created', '')), promoted=_when(f.get('promoted', '')),
+            promoted_seq=header.promoted_order[1], spec_time=_number(f.get('spec_time', '')),
+            spec_passed=f.get('spec_result', '') == 'PASS', banner_version=header.version,
+            spec_hash=f.get('spec_hash', ''),
+            coverage=_number(coverage) if coverage else None,
+            approved_by=f.get('approved_by', ''), meta=header.meta,
+            spec_cases=tuple(header.spec_cases), verified=header.body_matches_hash(),
+            network=f.get('network', ''), parent=f.get('parent', ''),
+        )
+
+    @classmethod
+    def from_file(cls, path: str) -> 'SnippetHeader':
+        """ValueError if ``path`` is not a promoted snippet file."""
+        with open(path, 'r', encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        if header is None:
+            raise ValueError(f"{path} is not a promoted snippet file")
+        return cls.from_header(header, path)
+
+    def read_code(self, verify: bool = True) -> str:
+        """The body; ValueError if it was edited since promotion (as PromotedSnippet.read_code)."""
+        with open(self.path, 'r', encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        if header is None:
+            raise ValueError(f"{self.path} is no longer a promoted snippet file")
+        if verify and not header.body_matches_hash():
+            raise SpokedError(ErrorKind.STORE_CORRUPTION,
+                              f"{self.path} was edited after promotion (code_hash mismatch)",
+                              path=self.path)
+        return header.body
+
+
+def default_executors(engines: Optional[Iterable[str]] = None) -> Dict[str, Any]:
+    """A fresh executor per engine language, or only for ``engines`` (python always)."""
+    from .execution_engine import EXECUTOR_CLASSES
+    wanted = {e.strip().lower() for e in engines or () if e.strip()}
+    return {lang: cls() for lang, cls in EXECUTOR_CLASSES.items()
+            if not wanted or lang in wanted or lang == 'python'}
+
+
+class Spoked:
+    """A store driven in-process: stage, spec, promote, roll back, list."""
+
+    def __init__(self, data_dir: str = 'data', *, snippets_dir: str = '', audit_log: str = '',
+                 engines: Optional[Iterable[str]] = None, read_only: bool = False,
+                 shard_width: int = DEFAULT_SHARD_WIDTH,
+                 pipeline: Optional[StagingPipeline] = None, **options):
+        """``options`` go to StagingPipeline (gates, policy, constraints, failures_dir, …).
+
+        Label specs come from ``<data_dir>/specs`` unless ``label_specs`` says otherwise.
+        """
+        from .label_specs import LabelSpecs
+        from .node_registry import NodeRegistry
+        from .session_ledger import SessionLedger
+
+        self.snippets_dir = snippets_dir or os.path.join(data_dir, 'snippets')
+        self.audit_log = audit_log or os.path.join(data_dir, 'staging_audit.jsonl')
+        if pipeline is None:
+            options.setdefault('label_specs', LabelSpecs(os.path.join(data_dir, 'specs')))
+            ledger = SessionLedger()                # shared, as in init_runtime
+            pipeline = StagingPipeline(
+                executors=default_executors(engines), node_registry=NodeRegistry(ledger),
+                session_ledger=ledger, snippets_dir=self.snippets_dir,
+                audit_log_path=self.audit_log,
+                mode=StoreMode.READ_ONLY if read_only else StoreMode.READ_WRITE,
+                shard_width=shard_width, **options,
+            )
+        self.pipeline = pipeline
+        self._resolver: Optional[SlotResolver] = None
+
+    # ─────────────────────────────────────────────────────────────────────
+    # WRITE PATH
+    # ─────────────────────────────────────────────────────────────────────
+
+    def stage(self, path: str = '', *, code: Optional[str] = None, language: str = '',
+              label: str = '', spec=None, meta: Optional[Dict[str, Any]] = None,
+              author: str = '', parent: Optional[str] = None) -> StagedSnippet:
+        """
+        Queue a snippet and dry-run it (``spokedpy stage``): a file (a
+        promoted file's body, its language from the extension, the
+        ``.spec.*`` sidecar next to it) or ``code`` with a ``language``.
+        A promoted file's staging_id becomes the parent, unless ``parent``
+        says otherwise (see lineage).  The snippet comes back PASSED or
+        FAILED; a failed dry-run is not raised (see spec_success / spec_error).
+        """
+        language, code, spec, label, found = self._source(path, code, language, spec, label)
+        snippet = self.pipeline.queue_snippet(_engine_letter(language), language, code,
+                                              label, author, spec, meta,
+                                              found if parent is None else parent)
+        return self.pipeline.speculate(snippet.staging_id)
+
+    def spec(self, path: str = '', *, code: Optional[str] = None, language: str = '',
+             spec=None) -> List[Dict[str, Any]]:
+        """Run a spec suite without staging (``spokedpy spec run``); one dict per case."""
+        language, code, spec, _label, _parent = self._source(path, code, language, spec, '')
+        if spec is None:
+            raise SpokedError(ErrorKind.USAGE, 'no spec to run — pass spec= or add a '
+                              '.spec.toml / .spec.yaml sidecar', path=path)
+        return self.pipeline.run_spec(language, spec, code)
+
+    def promote(self, staging_id: str) -> StagedSnippet:
+        return self.pipeline.promote(staging_id)
+
+    def verdict(self, staging_id: str, action: str = 'auto', reason: str = '') -> StagedSnippet:
+        return self.pipeline.verdict(staging_id, action, reason)
+
+    def rollback(self, staging_id: str, reason: str = '') -> StagedSnippet:
+        return self.pipeline.rollback(staging_id, reason)
+
+    def lineage(self, staging_id: str) -> List[Ancestor]:
+        """The snippet and the ones it was staged from, newest first (``spokedpy lineage``)."""
+        return self.pipeline.lineage(staging_id)
+
+    # ─────────────────────────────────────────────────────────────────────
+    # READ PATH
+    # ─────────────────────────────────────────────────────────────────────
+
+    def get(self, staging_id: str) -> StagedSnippet:
+        snippet = self.pipeline.get_snippet(staging_id)
+        if snippet is None:
+            raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet with id '{staging_id}'",
+                              staging_id=staging_id)
+        return snippet
+
+    def list(self, phase: str = '', meta: Optional[Dict[str, Optional[str]]] = None,
+             query: str = '', limit: int = 100) -> List[StagedSnippet]:
+        """Staged and recent snippets, newest first (``spokedpy list``)."""
+        found = [s for s in self.pipeline.get_active() + self.pipeline.get_history(limit)
+                 if s.matches(meta, query) and (not phase or s.phase.value == phase)]
+        return sorted(found, key=lambda s: s.seq, reverse=True)
+
+    def slots(self) -> Dict[str, SnippetHeader]:
+        """Every occupied slot of the store on disk, by address."""
+        return {slot: SnippetHeader.from_file(p.path)
+                for slot, p in self._slot_resolver().slots().items()}
+
+    def header(self, slot_or_path: str) -> SnippetHeader:
+        """The header of the file a slot holds (``spokedpy resolve``), or of a file."""
+        if os.path.isfile(slot_or_path):
+            return SnippetHeader.from_file(slot_or_path)
+        promoted = self._slot_resolver().resolve_slot(slot_or_path)
+        if promoted is None:
+            raise SpokedError(ErrorKind.NOT_FOUND,
+                              f"no promoted snippet in slot {slot_or_path} under "
+                              f"{self.snippets_dir}", slot=slot_or_path)
+        return SnippetHeader.from_file(promoted.path)
+
+    # ─────────────────────────────────────────────────────────────────────
+
+    def _slot_resolver(self) -> SlotResolver:
+        if self._resolver is None:
+            self._resolver = SlotResolver(self.snippets_dir, self.audit_log)
+        return self._resolver
+
+    @staticmethod
+    def _source(path: str, code: Optional[str], language: str, spec, label: str):
+        parent = ''
+        if code is None:
+            if not path:
+                raise SpokedError(ErrorKind.USAGE, 'pass a file path or code=')
+            from .spec_suite import load_spec, sidecar_for
+            with open(path, 'r', encoding='utf-8') as f:
+                code = f.read()
+            header = parse_file_header(code)
+            if header is not None:
+                language, code = language or header.fields.get('language', ''), header.body
+                parent = header.fields.get('staging_id', '')
+            ext = os.path.splitext(path)[1]
+            language = language or next((lang for lang, e in LANG_EXTENSIONS.items()
+                                         if e == ext), '')
+            if spec is None and sidecar_for(path) is not None:
+                spec = load_spec(sidecar_for(path))
+            label = label or os.path.splitext(os.path.basename(path))[0]
+        if not language:
+            raise SpokedError(ErrorKind.USAGE, f"cannot tell the language of {path or 'code'} "
+                              f"— pass language=", path=path)
+        if isinstance(spec, str):
+            from .spec_suite import parse_spec
+            spec = parse_spec(spec)
+        return language.lower().strip(), code, spec, label, parent
+
+
+def _engine_letter(language: str) -> str:
+    from .node_registry import LANGUAGE_STRING_TO_ENGINE
+    engine = LANGUAGE_STRING_TO_ENGINE.get(language)
+    if engine is None:
+        known = ', '.join(sorted(LANGUAGE_STRING_TO_ENGINE))
+        raise SpokedError(ErrorKind.USAGE, f"Unknown language '{language}' (choose from: {known})",
+                          language=language)
+    return engine.letter
@@ -0,0 +1,356 @@
+"""
+Seccomp Profiles — syscall filtering for the children of spec runs (Linux).
+
+With ``[sandbox] seccomp`` set to a profile, every engine subprocess of a
+spec run starts under a seccomp-bpf filter that denies the syscalls a
+snippet has no business making:
+
+    ┌──────────┬─────────────────────────────────────────────────────────┐
+    │  off     │  no filter                                              │
+    │  dev     │  ptrace and process_vm_*, mount / umount / pivot_root   │
+    │          │  and the new mount API, reboot, kexec, kernel modules,  │
+    │          │  swap, setting the clock or hostname, acct, quotactl,   │
+    │          │  iopl / ioperm                                          │
+    │  prod    │  dev, plus unshare / setns, bpf, perf_event_open, the   │
+    │          │  kernel keyring, userfaultfd, chroot, open_by_handle_at,│
+    │          │  syslog, kcmp                                           │
+    └──────────┴─────────────────────────────────────────────────────────┘
+
+Config profiles pick one each — a lenient laptop, a strict server:
+
+    [sandbox]
+    seccomp = "dev"
+
+    [profiles.prod.sandbox]
+    seccomp = "prod"
+
+A denied syscall fails with EPERM instead of killing the child: the
+filter hands it to a supervisor (this file, run as the command's parent —
+see main), which answers it and records its name.  The run's outcome is
+then DENIED rather than FAIL — ``spec_result: DENIED(ptrace)`` — on its
+case lines, in its audit trail and in its failure bundle.
+
+Only engine subprocesses are filtered, not the in-process exec() of a
+plain Python dry-run (give the snippet a suite).  x86_64 and aarch64
+only, on kernels with seccomp user notifications (5.0+); elsewhere no
+filter is applied and ``spokedpy doctor`` says so.
+
+Enabled via:  DB setting → SPOKEDPY_SECCOMP env → off
+
+This module imports nothing from the package: engine spawns run it by
+path as the supervisor of the real command.
+"""
+
+import ctypes
+import errno
+import json
+import os
+import platform
+import shutil
+import signal
+import socket
+import struct
+import subprocess
+import sys
+import tempfile
+import threading
+from typing import Dict, List, Optional, Tuple
+
+SECCOMP_PROFILES = ('off', 'dev', 'prod')
+
+_DEV = (
+    'ptrace', 'process_vm_readv', 'process_vm_writev',
+    'mount', 'umount2', 'pivot_root', 'fsopen', 'fsconfig', 'fsmount', 'fspick',
+    'move_mount', 'open_tree', 'mount_setattr',
+    'reboot', 'kexec_load', 'kexec_file_load',
+    'init_module', 'finit_module', 'delete_module',
+    'swapon', 'swapoff', 'settimeofday', 'clock_settime', 'clock_adjtime', 'adjtimex',
+    'sethostname', 'setdomainname', 'acct', 'quotactl', 'iopl', 'ioperm',
+)
+_PROD = _DEV + (
+    'unshare', 'setns', 'bpf', 'perf_event_open', 'keyctl', 'add_key', 'request_key',
+    'userfaultfd', 'chroot', 'open_by_handle_at', 'name_to_handle_at', 'syslog', 'kcmp',
+)
+PROFILES: Dict[str, Tuple[str, ...]] = {'off': (), 'dev': _DEV, 'prod': _PROD}
+
+# Syscall numbers and AUDIT_ARCH_* per machine (syscalls a machine lacks
+# are left out of its filter).
+_SYSCALLS = {
+    'x86_64': (0xc000003e, 317, {
+        'ptrace': 101, 'process_vm_readv': 310, 'process_vm_writev': 311,
+        'mount': 165, 'umount2': 166, 'pivot_root': 155, 'reboot': 169,
+        'kexec_load': 246, 'kexec_file_load': 320, 'init_module': 175, 'finit_module': 313,
+        'delete_module': 176, 'swapon': 167, 'swapoff': 168, 'settimeofday': 164,
+        'clock_settime': 227, 'clock_adjtime': 305, 'adjtimex': 159, 'sethostname': 170,
+        'setdomainname': 171, 'acct': 163, 'quotactl': 179, 'iopl': 172, 'ioperm': 173,
+        'unshare': 272, 'setns': 308, 'bpf': 321, 'perf_event_open': 298, 'keyctl': 250,
+        'add_key': 248, 'request_key': 249, 'userfaultfd': 323, 'chroot': 161,
+        'open_by_handle_at': 304, 'name_to_handle_at': 303, 'syslog': 103, 'kcmp': 312,
+        'open_tree': 428, 'move_mount': 429, 'fsopen': 430, 'fsconfig': 431,
+        'fsmount': 432, 'fspick': 433, 'mount_setattr': 442,
+    }),
+    'aarch64': (0xc00000b7, 277, {
+        'ptrace': 117, 'process_vm_readv': 270, 'process_vm_writev': 271,
+        'mount': 40, 'umount2': 39, 'pivot_root': 41, 'reboot': 142,
+        'kexec_load': 104, 'kexec_file_load': 294, 'init_module': 105, 'finit_module': 273,
+        'delete_module': 106, 'swapon': 224, 'swapoff': 225, 'settimeofday': 170,
+        'clock_settime': 112, 'clock_adjtime': 266, 'adjtimex': 171, 'sethostname': 161,
+        'setdomainname': 162, 'acct': 89, 'quotactl': 60,
+        'unshare': 97, 'setns': 268, 'bpf': 280, 'perf_event_open': 241, 'keyctl': 219,
+        'add_key': 217, 'request_key': 218, 'userfaultfd': 282, 'chroot': 51,
+        'open_by_handle_at': 265, 'name_to_handle_at': 264, 'syslog': 116, 'kcmp': 272,
+        'open_tree': 428, 'move_mount': 429, 'fsopen': 430, 'fsconfig': 431,
+        'fsmount': 432, 'fspick': 433, 'mount_setattr': 442,
+    }),
+}
+
+_PR_SET_PDEATHSIG = 1
+_PR_SET_NO_NEW_PRIVS = 38
+_SECCOMP_SET_MODE_FILTER = 1
+_SECCOMP_FILTER_FLAG_NEW_LISTENER = 1 << 3
+_RET_ALLOW = 0x7fff0000
+_RET_USER_NOTIF = 0x7fc00000
+_X32_BIT = 0x40000000
+_NOTIF_SIZE = 80                            # struct seccomp_notif
+_IOCTL_NOTIF_RECV = 0xc0502100              # _IOWR('!', 0, struct seccomp_notif)
+_IOCTL_NOTIF_SEND = 0xc0182101              # _IOWR('!', 1, struct seccomp_notif_resp)
+
+_state = {'profile': 'off'}
+_probe: Dict[str, bool] = {}
+_active = threading.local()
+
+
+def _profile_name(value: str) -> str:
+    name = (value or 'off').strip().lower()
+    if name not in SECCOMP_PROFILES:
+        raise ValueError(f"unknown seccomp profile '{value.strip()}' "
+                         f"(choose from: {', '.join(SECCOMP_PROFILES)})")
+    return name
+
+
+def set_profile(value: str):
+    """Set the profile spec runs are filtered with (``[sandbox] seccomp`` / SPOKEDPY_SECCOMP)."""
+    _state['profile'] = _profile_name(value)
+
+
+def profile() -> str:
+    return _state['profile']
+
+
+def supported() -> bool:
+    """Whether this machine can filter children (probed once per process)."""
+    if 'ok' not in _probe:
+        ok = sys.platform.startswith('linux') and platform.machine() in _SYSCALLS
+        if ok:
+            try:
+                probe = subprocess.run([sys.executable, '-I', os.path.abspath(__file__), 'dev',
+                                        '', sys.executable, '-I', '-c', 'pass'],
+                                       capture_output=True, timeout=10)
+                ok = probe.returncode == 0
+            except (OSError, subprocess.SubprocessError):
+                ok = False
+        _probe['ok'] = ok
+    return _probe['ok']
+
+
+def spec_result(passed: bool, denied: Optional[List[str]] = None) -> str:
+    """``PASS``, ``FAIL`` or ``DENIED(ptrace,mount)`` — a run's outcome."""
+    if denied:
+        return f"DENIED({','.join(denied)})"
+    return 'PASS' if passed else 'FAIL'
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# ENGINE SPAWNS
+# ═══════════════════════════════════════════════════════════════════════════
+
+class Denials:
+    """
+    Context manager: engine spawns on this thread run under the seccomp
+    profile while it is active; ``syscalls`` holds what was denied to
+    them (in order, once each) after it exits.
+    """
+
+    def __init__(self):
+        self.syscalls: List[str] = []
+        self.dir = ''
+        self._spawns = 0
+        self._previous = None
+
+    def __enter__(self) -> 'Denials':
+        self._previous = getattr(_active, 'denials', None)
+        _active.denials = self
+        return self
+
+    def __exit__(self, *exc):
+        _active.denials = self._previous
+        if not self.dir:
+            return False
+        for name in sorted(os.listdir(self.dir)):
+            try:
+                with open(os.path.join(self.dir, name), 'r', encoding='utf-8') as f:
+                    entries = [json.loads(line) for line in f if line.strip()]
+            except (OSError, ValueError):
+                continue
+            for entry in entries:
+                syscall = str(entry.get('syscall', '')) if isinstance(entry, dict) else ''
+                if syscall and syscall not in self.syscalls:
+                    self.syscalls.append(syscall)
+        shutil.rmtree(self.dir, ignore_errors=True)
+        return False
+
+    def report_path(self) -> str:
+        if not self.dir:
+            self.dir = tempfile.mkdtemp(prefix='spokedpy-seccomp-')
+        self._spawns += 1
+        return os.path.join(self.dir, f"{self._spawns:04d}.jsonl")
+
+
+def current() -> Optional[Denials]:
+    return getattr(_active, 'denials', None)
+
+
+def apply(args: tuple, kwargs: dict) -> Tuple[tuple, dict]:
+    """Rewrite a ``subprocess.run`` call to start under the seccomp supervisor.
+
+    No-op outside a spec run (no Denials on this thread), with the ``off``
+    profile, for shell commands, or where filtering is unsupported.
+    """
+    active = current()
+    if active is None or _state['profile'] == 'off' or not args:
+        return args, kwargs
+    argv = args[0]
+    if not isinstance(argv, (list, tuple)) or kwargs.get('shell') or not supported():
+        return args, kwargs
+    command = [sys.executable, '-I', os.path.abspath(__file__), _state['profile'],
+               active.report_path(), *argv]
+    return (command,) + tuple(args[1:]), kwargs
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# THE SUPERVISOR (python seccomp.py <profile> <report> <command…>)
+# ═══════════════════════════════════════════════════════════════════════════
+
+class _SockFilter(ctypes.Structure):
+    _fields_ = [('code', ctypes.c_ushort), ('jt', ctypes.c_ubyte), ('jf', ctypes.c_ubyte),
+                ('k', ctypes.c_uint)]
+
+
+class _SockFprog(ctypes.Structure):
+    _fields_ = [('len', ctypes.c_ushort), ('filter', ctypes.POINTER(_SockFilter))]
+
+
+def program(machine: str, names) -> List[Tuple[int, int, int, int]]:
+    """The BPF filter: notify on the ``names`` syscalls (and any foreign ABI), allow the rest."""
+    arch, _nr, table = _SYSCALLS[machine]
+    numbers = sorted({table[n] for n in names if n in table})
+    ld_word, jeq, jge, ret = 0x20, 0x15, 0x35, 0x06
+    checks = len(numbers) + (1 if machine == 'x86_64' else 0)
+    code = [(ld_word, 0, 0, 4),                            # seccomp_data.arch
+            (jeq, 1, 0, arch),
+            (ret, 0, 0, _RET_USER_NOTIF),                   # i386 or other foreign ABIs
+            (ld_word, 0, 0, 0)]                             # seccomp_data.nr
+    if machine == 'x86_64':
+        code.append((jge, checks, 0, _X32_BIT))             # x32 syscalls
+    for i, number in enumerate(numbers):
+        code.append((jeq, len(numbers) - i, 0, number))
+    code += [(ret, 0, 0, _RET_ALLOW), (ret, 0, 0, _RET_USER_NOTIF)]
+    return code
+
+
+def syscall_name(machine: str, arch: int, number: int) -> str:
+    """``ptrace``; ``x32_syscall_101`` / ``compat_syscall_26`` for the other ABIs."""
+    native, _nr, table = _SYSCALLS[machine]
+    if arch != native:
+        return f"compat_syscall_{number}"
+    if number & _X32_BIT:
+        return f"x32_syscall_{number & ~_X32_BIT}"
+    return next((name for name, value in table.items() if value == number),
+                f"syscall_{number}")
+
+
+def _install(libc, machine: str, names) -> int:
+    """In the child: no new privileges, then the filter; returns its listener fd."""
+    code = program(machine, names)
+    filters = (_SockFilter * len(code))(*[_SockFilter(*insn) for insn in code])
+    prog = _SockFprog(len(code), filters)
+    if libc.prctl(_PR_SET_NO_NEW_PRIVS, ctypes.c_ulong(1), ctypes.c_ulong(0),
+                  ctypes.c_ulong(0), ctypes.c_ulong(0)) != 0:
+        raise OSError(ctypes.get_errno(), 'prctl(PR_SET_NO_NEW_PRIVS)')
+    fd = libc.syscall(ctypes.c_long(_SYSCALLS[machine][1]),
+                      ctypes.c_long(_SECCOMP_SET_MODE_FILTER),
+                      ctypes.c_long(_SECCOMP_FILTER_FLAG_NEW_LISTENER), ctypes.byref(prog))
+    if fd < 0:
+        raise OSError(ctypes.get_errno(), 'seccomp(SECCOMP_SET_MODE_FILTER)')
+    return fd
+
+
+def _supervise(libc, listener: int, machine: str, denied: List[Dict[str, object]]):
+    """Answer every notification with EPERM, recording the syscall."""
+    while True:
+        notif = ctypes.create_string_buffer(_NOTIF_SIZE)
+        if libc.ioctl(listener, ctypes.c_ulong(_IOCTL_NOTIF_RECV), notif) != 0:
+            if ctypes.get_errno() in (errno.EINTR, errno.ENOENT):
+                continue                    # interrupted, or the caller died meanwhile
+            return
+        ident, pid, _flags, number, arch = struct.unpack_from('=QIIiI', notif.raw)
+        denied.append({'syscall': syscall_name(machine, arch, number), 'pid': pid})
+        response = ctypes.create_string_buffer(struct.pack('=QqiI', ident, 0, -errno.EPERM, 0))
+        libc.ioctl(listener, ctypes.c_ulong(_IOCTL_NOTIF_SEND), response)
+
+
+def _exit_as(status: int):
+    if os.WIFSIGNALED(status):
+        sig = os.WTERMSIG(status)
+        try:
+            signal.signal(sig, signal.SIG_DFL)      # SIGKILL / SIGSTOP cannot be, need not be
+        except (OSError, ValueError):
+            pass
+        os.kill(os.getpid(), sig)
+        os._exit(128 + sig)
+    os._exit(os.waitstatus_to_exitcode(status))
+
+
+def main(argv: List[str]):
+    """Fork the command under the filter, answer its denied syscalls, exit as it did."""
+    name, report, command = argv[0], argv[1], argv[2:]
+    machine = platform.machine()
+    libc = ctypes.CDLL(None, use_errno=True)
+    ours, theirs = socket.socketpair(socket.AF_UNIX, socket.SOCK_STREAM)
+    parent = os.getpid()
+    pid = os.fork()
+    if pid == 0:
+        ours.close()
+        try:
+            libc.prctl(_PR_SET_PDEATHSIG, ctypes.c_ulong(signal.SIGKILL), ctypes.c_ulong(0),
+                       ctypes.c_ulong(0), ctypes.c_ulong(0))
+            if os.getppid() != parent:
+                os._exit(137)
+            listener = _install(libc, machine, PROFILES[_profile_name(name)])
+            socket.send_fds(theirs, [b'1'], [listener])
+            os.close(listener)
+            theirs.close()
+            os.execvp(command[0], command)
+        except (OSError, ValueError) as exc:
+            sys.stderr.write(f"spokedpy seccomp: {exc}\n")
+            sys.stderr.flush()
+        os._exit(127)
+    theirs.close()
+    try:
+        _msg, fds, _flags, _addr = socket.recv_fds(ours, 1, 1)
+    except OSError:
+        fds = []
+    ours.close()
+    denied: List[Dict[str, object]] = []
+    if fds:
+        threading.Thread(target=_supervise, args=(libc, fds[0], machine, denied),
+                         daemon=True).start()
+    _pid, status = os.waitpid(pid, 0)
+    if denied and report:
+        with open(report, 'a', encoding='utf-8') as f:
+            f.writelines(json.dumps(entry) + '\n' for entry in denied)
+    _exit_as(status)
+
+
+if __name__ == '__main__':
+    main(sys.argv[1:])
@@ -0,0 +1,202 @@
+"""
+Slot Constraints — what a slot accepts, checked before every promotion.
+
+A constraints file (``[gates] constraints_file`` → data/slot_constraints.toml)
+declares per-slot requirements; a promotion into a slot that does not meet
+them is refused, whoever asks (auto-promote, approve, a scheduled window):
+
+    [slot.d2]
+    engine = "rust"                  # language the candidate must be in
+    max_spec_time = 2.0              # seconds
+    gates = ["clippy", "miri"]       # must have run and allowed
+    capabilities = ["compiled"]      # see ENGINE_CAPABILITIES
+
+    [slot."a*"]                      # fnmatch patterns; every match applies
+    require_spec = true
+    max_network = "none"             # refuse snippets that declare more (see offline)
+
+Unlike the auto-promotion policy (which only decides whether a human has to
+look), a constraint is a hard requirement — even a forced approval cannot
+promote past it.  The file is re-read when it changes.
+"""
+
+import fnmatch
+import os
+import threading
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, FrozenSet, List, Optional
+
+try:
+    import tomllib                      # Python 3.11+
+except ImportError:                     # pragma: no cover
+    import tomli as tomllib
+
+from .node_registry import LANGUAGE_STRING_TO_ENGINE
+from .offline import NETWORK_LEVELS
+
+
+# What each engine's sandbox can do, as the pipeline uses it:
+#   spec_io      spec cases may pass args, stdin and env (see spec_suite)
+#   peak_memory  compare / spec runs report peak RSS (see spec_compare)
+#   compiled     the snippet is compiled before it runs
+#   fuzz         the built-in fuzz gate applies
+#   coverage     the built-in coverage gate applies
+_COMPILED = frozenset({'compiled'})
+ENGINE_CAPABILITIES: Dict[str, FrozenSet[str]] = {
+    'python':     frozenset({'spec_io', 'peak_memory'}),
+    'rust':       frozenset({'compiled', 'fuzz', 'coverage'}),
+    'c':          _COMPILED,
+    'cpp':        _COMPILED,
+    'go':         _COMPILED,
+    'java':       _COMPILED,
+    'kotlin':     _COMPILED,
+    'swift':      _COMPILED,
+    'csharp':     _COMPILED,
+}
+
+KNOWN_CAPABILITIES = frozenset().union(*ENGINE_CAPABILITIES.values())
+
+_CONSTRAINT_KEYS = {'engine', 'max_spec_time', 'gates', 'capabilities', 'require_spec',
+                    'max_network'}
+
+
+def capabilities(language: str) -> FrozenSet[str]:
+    return ENGINE_CAPABILITIES.get(language.lower(), frozenset())
+
+
+def engine_language(name: str) -> str:
+    """'rust' or 'RUST' → 'rust'; ValueError for an unknown engine."""
+    key = name.strip().lower()
+    for language, engine in LANGUAGE_STRING_TO_ENGINE.items():
+        if key in (language, engine.name.lower()):
+            return language
+    raise ValueError(f"unknown engine '{name}'")
+
+
+@dataclass
+class SlotConstraint:
+    slot: str                                # fnmatch pattern on the slot address
+    engine: str = ''                         # language ('' → any)
+    max_spec_time: Optional[float] = None
+    gates: List[str] = field(default_factory=list)
+    capabilities: List[str] = field(default_factory=list)
+    require_spec: bool = False
+    max_network: str = ''                    # NETWORK_LEVELS entry ('' → any)
+
+    def matches(self, address: str) -> bool:
+        return fnmatch.fnmatchcase(address, self.slot)
+
+    def violations(self, snippet) -> List[str]:
+        """Why ``snippet`` may not be promoted into this slot ([] → it may)."""
+        out = []
+        if self.engine and snippet.language != self.engine:
+            out.append(f"engine {snippet.language}, slot requires {self.engine}")
+        if self.max_spec_time is not None and snippet.spec_execution_time > self.max_spec_time:
+            out.append(f"spec_time {snippet.spec_execution_time:.4f}s > {self.max_spec_time:g}s")
+        allowed = {g.get('gate') for g in snippet.gate_results if g.get('allow')}
+        out.extend(f"gate '{g}' has not passed" for g in self.gates if g not in allowed)
+        missing = [c for c in self.capabilities if c not in capabilities(snippet.language)]
+        if missing:
+            out.append(f"{snippet.language} lacks {', '.join(missing)}")
+        if self.require_spec and not snippet.spec:
+            out.append('no spec suite')
+        declared = snippet.network or 'none'
+        if self.max_network and \
+                NETWORK_LEVELS.index(declared) > NETWORK_LEVELS.index(self.max_network):
+            out.append(f"declares network: {declared}, slot allows {self.max_network}")
+        return out
+
+
+def parse_constraints(text: str) -> List[SlotConstraint]:
+    """Parse constraints TOML; ValueError on anything malformed."""
+    try:
+        data = tomllib.loads(text)
+    except tomllib.TOMLDecodeError as exc:
+        raise ValueError(f"invalid constraints TOML: {exc}")
+    unknown = sorted(set(data) - {'slot'})
+    if unknown:
+        raise ValueError(f"unknown key(s) {', '.join(unknown)} (expected [slot.<address>] tables)")
+    out = []
+    for pattern, raw in data.get('slot', {}).items():
+        where = f"[slot.{pattern}]"
+        if not isinstance(raw, dict):
+            raise ValueError(f"{where} must be a table")
+        bad = sorted(set(raw) - _CONSTRAINT_KEYS)
+        if bad:
+            raise ValueError(f"{where}: unknown key(s) {', '.join(bad)}")
+        max_spec_time = raw.get('max_spec_time')
+        if max_spec_time is not None and (isinstance(max_spec_time, bool)
+                                          or not isinstance(max_spec_time, (int, float))):
+            raise ValueError(f"{where}: max_spec_time must be a number of seconds")
+        lists = {}
+        for key in ('gates', 'capabilities'):
+            value = raw.get(key, [])
+            if not isinstance(value, list) or not all(isinstance(v, str) for v in value):
+                raise ValueError(f"{where}: {key} must be a list of strings")
+            lists[key] = value
+        strange = [c for c in lists['capabilities'] if c not in KNOWN_CAPABILITIES]
+        if strange:
+            raise ValueError(f"{where}: unknown capability {', '.join(strange)} "
+                             f"(known: {', '.join(sorted(KNOWN_CAPABILITIES))})")
+        if not isinstance(raw.get('require_spec', False), bool):
+            raise ValueError(f"{where}: require_spec must be true or false")
+        max_network = raw.get('max_network', '')
+        if max_network not in ('',) + NETWORK_LEVELS:
+            raise ValueError(f"{where}: max_network must be one of {', '.join(NETWORK_LEVELS)}")
+        try:
+            engine = engine_language(raw['engine']) if raw.get('engine') else ''
+        except ValueError as exc:
+            raise ValueError(f"{where}: {exc}")
+        out.append(SlotConstraint(
+            slot=pattern, engine=engine,
+            max_spec_time=None if max_spec_time is None else float(max_spec_time),
+            gates=lists['gates'], capabilities=lists['capabilities'],
+            require_spec=raw.get('require_spec', False), max_network=max_network,
+        ))
+    return out
+
+
+class SlotConstraints:
+    """The constraints in ``path``, re-read whenever the file changes."""
+
+    def __init__(self, path: str):
+        self.path = path
+        self.error = ''
+        self._constraints: List[SlotConstraint] = []
+        self._mtime: Optional[float] = None
+        self._lock = threading.Lock()
+
+    @property
+    def constraints(self) -> List[SlotConstraint]:
+        try:
+            mtime = os.path.getmtime(self.path)
+        except OSError:
+            mtime = -1.0
+        with self._lock:
+            if mtime != self._mtime:
+                self._mtime = mtime
+                self._constraints, self.error = [], ''
+                if mtime >= 0:
+                    try:
+                        with open(self.path, 'r', encoding='utf-8') as f:
+                            self._constraints = parse_constraints(f.read())
+                    except (OSError, ValueError) as exc:
+                        self.error = f"{self.path}: {exc}"
+            return list(self._constraints)
+
+    def for_slot(self, address: str) -> List[SlotConstraint]:
+        return [c for c in self.constraints if c.matches(address)]
+
+    def violations(self, snippet) -> List[str]:
+        """Every unmet requirement of the snippet's target slot."""
+        matching = self.for_slot(snippet.reserved_address)
+        if self.error:
+            # A broken file must not silently lift the constraints.
+            return [f"slot constraints unreadable — {self.error}"]
+        return [v for c in matching for v in c.violations(snippet)]
+
+    def describe(self) -> Dict[str, Any]:
+        constraints = self.constraints
+        return {'path': self.path, 'error': self.error,
+                'constraints': [asdict(c) for c in constraints],
+                'capabilities': {lang: sorted(caps) for lang, caps in ENGINE_CAPABILITIES.items()}}
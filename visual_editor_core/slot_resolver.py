@@ -0,0 +1,378 @@
+"""
+Slot Resolver — which promoted file a slot holds, for services that embed
+promoted snippets:
+
+    from visual_editor_core.slot_resolver import SlotResolver
+
+    store = SlotResolver('data/snippets', audit_log='data/staging_audit.jsonl',
+                         index_path='data/store_index.sqlite')
+    snippet = store.resolve_slot('d3')     # PromotedSnippet(path, code_hash, label, engine, …)
+    code = snippet.read_code()
+
+No server, pipeline or ledger is involved: the index is built from the
+headers of the promoted files under the snippets dir.  When several files
+claim a slot the latest ``promoted`` one wins (``promoted_seq``, then the
+file's mtime, break ties); with an audit log, snippets
+that were rolled back (their files stay on disk) are left out.
+
+The index is a SQLite database (``index_path``; in memory without one) that
+keeps a row per file, the mtime of every directory it has read, how far
+into the audit log it got, and the winner of every slot.  Opening a store
+is lazy: nothing is read until the first lookup, which only compares the
+directory mtimes and loads the slot bindings — a header is re-read only
+when its directory changed, and a snippet's metadata is paged in from its
+row when it is resolved.  Afterwards the index is rebuilt only where
+something changed — a promotion adds a file (its shard directory's mtime
+moves), the audit log grows, or the resolved file itself was edited — so a
+lookup on a warm index costs a stat per directory.
+"""
+
+import json
+import os
+import sqlite3
+import threading
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Set, Tuple
+
+from .snippet_staging import parse_file_header, parse_timestamp
+
+INDEX_SCHEMA = 1
+
+_SCHEMA = """
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY, dir TEXT NOT NULL, mtime_ns INTEGER NOT NULL,
+    slot TEXT NOT NULL, code_hash TEXT, label TEXT, engine TEXT, language TEXT,
+    staging_id TEXT, promoted TEXT, promoted_at REAL, promoted_seq INTEGER, meta TEXT);
+CREATE INDEX IF NOT EXISTS files_dir ON files (dir);
+CREATE INDEX IF NOT EXISTS files_staging_id ON files (staging_id);
+CREATE INDEX IF NOT EXISTS files_order ON files (slot, promoted_at, promoted_seq, mtime_ns);
+CREATE TABLE IF NOT EXISTS dirs (path TEXT PRIMARY KEY, mtime_ns INTEGER NOT NULL);
+CREATE TABLE IF NOT EXISTS rolled_back (staging_id TEXT PRIMARY KEY);
+CREATE TABLE IF NOT EXISTS bindings (slot TEXT PRIMARY KEY, path TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS state (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+"""
+
+_COLUMNS = ('slot', 'path', 'code_hash', 'label', 'engine', 'language', 'staging_id',
+            'promoted', 'mtime_ns', 'promoted_seq', 'meta')
+
+
+@dataclass(frozen=True)
+class PromotedSnippet:
+    slot: str
+    path: str
+    code_hash: str                          # as in the header (banner v1: 16 hex digits + '…')
+    label: str
+    engine: str                             # 'RUST'
+    language: str                           # 'rust'
+    staging_id: str
+    promoted: str                           # RFC 3339 UTC, from the header
+    mtime_ns: int = 0
+    promoted_seq: int = 0                   # store order of the promotion (0: older banner)
+    meta: Dict[str, str] = field(default_factory=dict)    # the header's x- fields
+
+    def read_code(self, verify: bool = True) -> str:
+        """The snippet body (without the header); ValueError if it no longer matches code_hash."""
+        with open(self.path, 'r', encoding='utf-8') as f:
+            header = parse_file_header(f.read())
+        if header is None:
+            raise ValueError(f"{self.path} is no longer a promoted snippet file")
+        if verify and not header.body_matches_hash():
+            raise ValueError(f"{self.path} was edited after promotion (code_hash mismatch)")
+        return header.body
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def _read_entry(path: str) -> Optional[PromotedSnippet]:
+    try:
+        mtime_ns = os.stat(path).st_mtime_ns
+        with open(path, 'r', encoding='utf-8', errors='replace') as f:
+            header = parse_file_header(f.read())
+    except OSError:
+        return None
+    if header is None:
+        return None
+    fields = header.fields
+    slot = (fields.get('slot', '').split() or [''])[0]          # 'd3 (position 3)'
+    if not slot:
+        return None
+    return PromotedSnippet(
+        slot=slot, path=path, code_hash=fields.get('code_hash', ''),
+        label=fields.get('label', ''),
+        engine=(fields.get('engine', '').split() or [''])[0],
+        language=fields.get('language', ''),
+        staging_id=fields.get('staging_id', ''),
+        promoted=fields.get('promoted', ''),
+        mtime_ns=mtime_ns,
+        promoted_seq=header.promoted_order[1],
+        meta=header.meta,
+    )
+
+
+def _order(entry: PromotedSnippet) -> Tuple[float, int, int]:
+    try:
+        when = parse_timestamp(entry.promoted)
+    except ValueError:
+        when = 0.0
+    return when, entry.promoted_seq, entry.mtime_ns
+
+
+class SlotResolver:
+    """Slot → current PromotedSnippet, backed by a SQLite index invalidated on file changes."""
+
+    def __init__(self, snippets_dir: str, audit_log: str = '', index_path: str = ''):
+        self.snippets_dir = os.path.abspath(snippets_dir)
+        self.audit_log = audit_log
+        self.index_path = index_path
+        self._db: Optional[sqlite3.Connection] = None           # opened on first lookup
+        self._bindings: Dict[str, str] = {}                     # slot → path
+        self._entries: Dict[str, PromotedSnippet] = {}          # path → paged-in entry
+        self._fingerprint: Optional[Tuple] = None
+        self._lock = threading.Lock()
+
+    def resolve_slot(self, slot: str) -> Optional[PromotedSnippet]:
+        """The snippet promoted into ``slot`` (e.g. 'd3'); None if the slot is empty."""
+        key = slot.strip().lower()
+        with self._lock:
+            self._refresh()
+            entry = self._entry(key)
+            if entry is not None and self._mtime_ns(entry.path) != entry.mtime_ns:
+                # Edited, replaced or deleted in place — the directory mtime
+                # does not move for that.
+                with self._db:
+                    self._rebind(self._sync_file(entry.path))
+                self._load_bindings()
+                entry = self._entry(key)
+            return entry
+
+    def slots(self) -> Dict[str, PromotedSnippet]:
+        """Every occupied slot, by address."""
+        with self._lock:
+            self._refresh()
+            found = {slot: self._entry(slot) for slot in sorted(self._bindings)}
+            return {slot: entry for slot, entry in found.items() if entry is not None}
+
+    def invalidate(self):
+        """Forget the index: the next lookup re-reads every header."""
+        with self._lock:
+            if self._db is not None:
+                with self._db:
+                    for table in ('files', 'dirs', 'rolled_back', 'bindings'):
+                        self._db.execute(f'DELETE FROM {table}')
+                    self._db.execute("UPDATE state SET value = '0' WHERE key = 'audit_offset'")
+            self._entries.clear()
+            self._fingerprint = None
+
+    def close(self):
+        with self._lock:
+            if self._db is not None:
+                self._db.close()
+            self._db, self._fingerprint = None, None
+
+    # ── index ─────────────────────────────────────────────────────────
+
+    @staticmethod
+    def _mtime_ns(path: str) -> int:
+        try:
+            return os.stat(path).st_mtime_ns
+        except OSError:
+            return -1
+
+    @staticmethod
+    def _subdirs(directory: str) -> List[str]:
+        try:
+            with os.scandir(directory) as it:
+                return sorted(e.path for e in it if e.is_dir())
+        except OSError:
+            return []
+
+    def _directories(self) -> List[str]:
+        """The language dirs and their code_hash shard dirs (promoted_dir)."""
+        found = []
+        for lang_dir in self._subdirs(self.snippets_dir):
+            found.append(lang_dir)
+            found.extend(self._subdirs(lang_dir))
+        return found
+
+    def _audit_stat(self) -> Tuple[int, int]:
+        if self.audit_log:
+            try:
+                st = os.stat(self.audit_log)
+                return st.st_mtime_ns, st.st_size
+            except OSError:
+                pass
+        return -1, -1
+
+    def _current_fingerprint(self) -> Tuple:
+        dirs = [self.snippets_dir] + self._directories()
+        return tuple((d, self._mtime_ns(d)) for d in dirs) + (self._audit_stat(),)
+
+    def _open(self) -> sqlite3.Connection:
+        """The index; an unusable index_path (read-only store, corrupt file) falls back to memory."""
+        for target in ([self.index_path] if self.index_path else []) + [':memory:']:
+            db = None
+            try:
+                if target != ':memory:':
+                    os.makedirs(os.path.dirname(os.path.abspath(target)), exist_ok=True)
+                db = sqlite3.connect(target, timeout=5.0, check_same_thread=False)
+                state = {}
+                if db.execute("SELECT 1 FROM sqlite_master WHERE name = 'state'").fetchone():
+                    state = dict(db.execute('SELECT key, value FROM state'))
+                if state and (state.get('schema') != str(INDEX_SCHEMA)
+                              or state.get('snippets_dir') != self.snippets_dir
+                              or state.get('audit_log', '') != self.audit_log):
+                    for table in ('files', 'dirs', 'rolled_back', 'bindings', 'state'):
+                        db.execute(f'DROP TABLE IF EXISTS {table}')
+                    state = {}
+                db.executescript(_SCHEMA)
+                if not state:
+                    with db:
+                        db.executemany('INSERT OR REPLACE INTO state VALUES (?, ?)', [
+                            ('schema', str(INDEX_SCHEMA)), ('snippets_dir', self.snippets_dir),
+                            ('audit_log', self.audit_log), ('audit_offset', '0')])
+                return db
+            except (sqlite3.Error, OSError):
+                if db is not None:
+                    db.close()
+        raise sqlite3.Error('cannot open an in-memory index')
+
+    def _refresh(self):
+        fingerprint = self._current_fingerprint()
+        if fingerprint == self._fingerprint:
+            return
+        if self._db is None:
+            self._db = self._open()
+        db = self._db
+        with db:
+            touched: Set[str] = set()
+            known = dict(db.execute('SELECT path, mtime_ns FROM dirs'))
+            current = dict(fingerprint[1:-1])
+            for directory in set(known) - set(current):
+                touched.update(s for (s,) in db.execute(
+                    "SELECT slot FROM files WHERE dir = ? AND slot != ''", (directory,)))
+                db.execute('DELETE FROM files WHERE dir = ?', (directory,))
+                db.execute('DELETE FROM dirs WHERE path = ?', (directory,))
+            for directory, mtime_ns in current.items():
+                if known.get(directory) != mtime_ns:
+                    touched |= self._sync_dir(directory)
+                    db.execute('INSERT OR REPLACE INTO dirs VALUES (?, ?)', (directory, mtime_ns))
+            touched |= self._sync_audit()
+            self._rebind(touched)
+        self._load_bindings()
+        self._fingerprint = fingerprint
+
+    def _sync_dir(self, directory: str) -> Set[str]:
+        """Re-read the files of one directory whose mtime moved; the slots that may have changed."""
+        db = self._db
+        stored = dict(db.execute('SELECT path, mtime_ns FROM files WHERE dir = ?', (directory,)))
+        try:
+            with os.scandir(directory) as it:
+                present = {e.path: e.stat().st_mtime_ns for e in it if e.is_file()}
+        except OSError:
+            present = {}
+        touched: Set[str] = set()
+        for path in set(stored) - set(present):
+            touched |= self._sync_file(path)
+        for path, mtime_ns in present.items():
+            if stored.get(path) != mtime_ns:
+                touched |= self._sync_file(path)
+        return touched
+
+    def _sync_file(self, path: str) -> Set[str]:
+        """Re-read one file into its row (no row for a vanished file); the slots it left or joined."""
+        db = self._db
+        touched = {s for (s,) in db.execute(
+            "SELECT slot FROM files WHERE path = ? AND slot != ''", (path,))}
+        self._entries.pop(path, None)
+        mtime_ns = self._mtime_ns(path)
+        if mtime_ns < 0:
+            db.execute('DELETE FROM files WHERE path = ?', (path,))
+            return touched
+        entry = _read_entry(path)
+        if entry is None:
+            # Spec sidecars and golden files have no banner: a row with no
+            # slot, so they are not read again until they change.
+            row = (path, os.path.dirname(path), mtime_ns, '') + (None,) * 9
+        else:
+            touched.add(entry.slot)
+            row = (path, os.path.dirname(path), entry.mtime_ns, entry.slot, entry.code_hash,
+                   entry.label, entry.engine, entry.language, entry.staging_id,
+                   entry.promoted, _order(entry)[0], entry.promoted_seq,
+                   json.dumps(entry.meta))
+        db.execute('INSERT OR REPLACE INTO files VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)',
+                   row)
+        return touched
+
+    def _sync_audit(self) -> Set[str]:
+        """Read the audit log from where the index left off; the slots of new rollbacks."""
+        if not self.audit_log:
+            return set()
+        db = self._db
+        offset = int(db.execute("SELECT value FROM state WHERE key = 'audit_offset'").fetchone()[0])
+        _mtime, size = self._audit_stat()
+        if size < offset:                                   # rotated or truncated: start over
+            db.execute('DELETE FROM rolled_back')
+            offset = 0
+        rolled_back: List[str] = []
+        try:
+            with open(self.audit_log, 'rb') as f:
+                f.seek(offset)
+                chunk = f.read()
+        except OSError:
+            chunk = b''
+        complete = chunk[:chunk.rfind(b'\n') + 1]           # a line still being written waits
+        for line in complete.splitlines():
+            if b'"rollback"' not in line:
+                continue
+            try:
+                event = json.loads(line)
+            except ValueError:
+                continue
+            if event.get('event') == 'rollback' and event.get('staging_id'):
+                rolled_back.append(event['staging_id'])
+        db.executemany('INSERT OR IGNORE INTO rolled_back VALUES (?)',
+                       [(s,) for s in rolled_back])
+        db.execute("UPDATE state SET value = ? WHERE key = 'audit_offset'",
+                   (str(offset + len(complete)),))
+        return self._slots_of(rolled_back)
+
+    def _slots_of(self, staging_ids: Iterable[str]) -> Set[str]:
+        slots: Set[str] = set()
+        for staging_id in staging_ids:
+            slots.update(s for (s,) in self._db.execute(
+                "SELECT slot FROM files WHERE staging_id = ? AND slot != ''", (staging_id,)))
+        return slots
+
+    def _rebind(self, slots: Iterable[str]):
+        """Recompute the winner of each slot in ``slots``."""
+        db = self._db
+        for slot in slots:
+            row = db.execute(
+                'SELECT path FROM files WHERE slot = ? AND staging_id NOT IN '
+                '(SELECT staging_id FROM rolled_back) '
+                'ORDER BY promoted_at DESC, promoted_seq DESC, mtime_ns DESC, path DESC LIMIT 1',
+                (slot,)).fetchone()
+            if row is None:
+                db.execute('DELETE FROM bindings WHERE slot = ?', (slot,))
+            else:
+                db.execute('INSERT OR REPLACE INTO bindings VALUES (?, ?)', (slot, row[0]))
+
+    def _load_bindings(self):
+        self._bindings = dict(self._db.execute('SELECT slot, path FROM bindings'))
+
+    def _entry(self, slot: str) -> Optional[PromotedSnippet]:
+        """The bound snippet, its metadata paged in from the index on first use."""
+        path = self._bindings.get(slot)
+        if path is None:
+            return None
+        entry = self._entries.get(path)
+        if entry is None:
+            row = self._db.execute(f"SELECT {', '.join(_COLUMNS)} FROM files WHERE path = ?",
+                                   (path,)).fetchone()
+            if row is None:
+                return None
+            values = dict(zip(_COLUMNS, row))
+            values['meta'] = json.loads(values['meta'] or '{}')
+            entry = self._entries[path] = PromotedSnippet(**values)
+        return entry
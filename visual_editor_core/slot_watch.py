@@ -0,0 +1,106 @@
+"""
+Slot Watch — long-poll notifications when a slot's promoted snippet changes.
+
+A consumer that hot-reloads promoted code registers the slots it cares about
+and waits, instead of polling the snippets dir:
+
+    GET /api/slots/watch?slots=d3,a*&since=41&timeout=30
+
+returns as soon as one of those slots changes (or after ``timeout`` seconds
+with no events).  Every change carries a sequence number; passing the last
+``cursor`` back as ``since`` picks up exactly where the previous call
+stopped, so nothing is lost between two polls:
+
+    {"cursor": 42, "missed": false, "events": [
+        {"seq": 42, "slot": "d3", "event": "promoted", "staging_id": "stg-…",
+         "previous": "stg-…", "label": "parse-csv", "code_hash": "…",
+         "path": "data/snippets/rust/d3_stg-…_….rs", "timestamp": 1760400000.0}]}
+
+A rollback reports the snippet the slot falls back to (``staging_id`` is
+empty when the slot is now vacant).  Only the last ``keep`` events are held;
+``missed`` is true when ``since`` is older than that (or from before a
+server restart) and the consumer should re-resolve its slots (see
+slot_resolver).  ``spokedpy watch d3``
+follows the same stream from the shell.
+"""
+
+import fnmatch
+import threading
+import time
+from collections import deque
+from dataclasses import dataclass
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+MAX_TIMEOUT = 60.0                          # seconds a single poll may hold a request
+
+_CHANGES = {'promotion': 'promoted', 'rollback': 'rolled_back'}
+
+
+@dataclass
+class SlotEvent:
+    seq: int
+    slot: str
+    event: str                              # 'promoted' | 'rolled_back'
+    staging_id: str                         # what the slot holds now ('' → vacant)
+    previous: str                           # the snippet that was promoted / rolled back
+    label: str = ''
+    code_hash: str = ''
+    path: str = ''
+    timestamp: float = 0.0
+
+
+class SlotWatch:
+    """Pipeline listener recording slot changes; ``wait`` long-polls on them."""
+
+    def __init__(self, current: Callable[[], Dict[str, Any]], keep: int = 1000):
+        self._current = current               # StagingPipeline.current_promoted
+        self._events: deque = deque(maxlen=keep)
+        self._seq = 0
+        self._changed = threading.Condition()
+
+    @property
+    def cursor(self) -> int:
+        with self._changed:
+            return self._seq
+
+    def __call__(self, event: str, snippet, details: Dict[str, Any]):
+        kind = _CHANGES.get(event)
+        if kind is None or not snippet.reserved_address:
+            return
+        holder = self._current().get(snippet.reserved_address)
+        with self._changed:
+            self._seq += 1
+            self._events.append(SlotEvent(
+                seq=self._seq, slot=snippet.reserved_address, event=kind,
+                staging_id=holder.staging_id if holder else '',
+                previous=snippet.staging_id,
+                label=holder.label if holder else snippet.label,
+                code_hash=holder.code_hash if holder else '',
+                path=holder.saved_file_path if holder else '',
+                timestamp=time.time(),
+            ))
+            self._changed.notify_all()
+
+    def wait(self, slots: Iterable[str], since: Optional[int] = None,
+             timeout: float = 30.0) -> Tuple[List[SlotEvent], int, bool]:
+        """
+        Events after ``since`` for slots matching ``slots`` (fnmatch patterns;
+        empty → every slot), waiting up to ``timeout`` seconds for the first
+        one.  ``since=None`` means "from now".  Returns (events, cursor,
+        missed).
+        """
+        patterns = [p.strip().lower() for p in slots if p.strip()]
+        deadline = time.monotonic() + max(0.0, min(float(timeout), MAX_TIMEOUT))
+        with self._changed:
+            since = self._seq if since is None else int(since)
+            while True:
+                oldest = self._events[0].seq if self._events else self._seq + 1
+                # Older than what is kept, or from before a server restart.
+                missed = since < oldest - 1 or since > self._seq
+                matching = [e for e in self._events if e.seq > since
+                            and (not patterns or any(fnmatch.fnmatchcase(e.slot, p)
+                                                     for p in patterns))]
+                remaining = deadline - time.monotonic()
+                if matching or missed or remaining <= 0:
+                    return matching, self._seq, missed
+                self._changed.wait(remaining)
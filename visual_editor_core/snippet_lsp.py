@@ -0,0 +1,519 @@
+"""
+Snippet Language Server — pipeline state inline in the editor.
+
+``spokedpy lsp`` speaks the Language Server Protocol over stdio for the
+promoted snippet files under the snippets dir (``data/snippets/<lang>/<hh>/``):
+
+    ┌──────────────────┬─────────────────────────────────────────────────────┐
+    │  banner          │  header banner present, closed, and titled; an old  │
+    │                  │  banner_version is noted (spokedpy migrate-headers) │
+    │  fields          │  required fields present and well-formed; unknown   │
+    │                  │  fields flagged (``x-`` prefixed ones are allowed)  │
+    │  stale-hash      │  body no longer matches code_hash — re-stage it     │
+    │  stale-spec      │  the <stem>.spec.toml sidecar no longer matches     │
+    │                  │  spec_hash (or was added / removed); with           │
+    │                  │  spec_inherits, hashed with the label's spec        │
+    │  slot-conflict   │  another live snippet claims the same slot          │
+    │  superseded      │  rolled back, or the slot now holds someone else    │
+    └──────────────────┴─────────────────────────────────────────────────────┘
+
+Spec sidecars themselves are checked for parse errors (``spec``).
+
+Code actions:
+
+    Re-stage this snippet        queue + speculate the edited body (and its
+                                 spec sidecar) on the running server, the
+                                 file's staging_id as its parent
+                                 (``spokedpy.restage``)
+    Show promoted predecessor    open the previous promoted version with the
+                                 same label (``spokedpy.showPredecessor``)
+
+Which snippets are live comes from the local index (audit log + state
+checkpoint, see cli_completion.local_index), so diagnostics work without
+the server; only re-staging needs it.
+"""
+
+import json
+import os
+import re
+import sys
+import urllib.parse
+import urllib.request
+from dataclasses import dataclass
+from typing import Any, BinaryIO, Callable, Dict, List, Optional, Tuple
+
+from .cli_completion import local_index
+from .label_specs import LabelSpecs, inherit
+from .spec_suite import load_spec, parse_spec, sidecar_for, spec_format
+from .snippet_staging import (
+    BANNER_VERSION, HEADER_FIELDS, HEADER_FIELDS_BY_VERSION, LANG_EXTENSIONS, LANG_TO_LETTER,
+    OPTIONAL_HEADER_FIELDS, SnippetFileHeader, parse_file_header,
+)
+
+
+SEVERITY_ERROR, SEVERITY_WARNING, SEVERITY_INFO = 1, 2, 3
+
+BANNER_TITLE = 'VPyD Staged Snippet — PROMOTED TO PRODUCTION'
+CMD_RESTAGE = 'spokedpy.restage'
+CMD_PREDECESSOR = 'spokedpy.showPredecessor'
+
+_STAGING_ID = re.compile(r'^stg-(?:[0-9a-f]{12}|[0-7][0-9A-HJKMNP-TV-Z]{25})$')  # hex or ULID
+_SLOT = re.compile(r'^([a-z])(\d+)\b')
+_HASH = re.compile(r'^[0-9a-f]{16}…?$')
+_CODE_HASH = {1: _HASH, 2: re.compile(r'^[0-9a-f]{64}$')}     # by banner_version
+_ISO = re.compile(r'^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d{1,9})?Z$')     # banner v1: no fraction
+_PERCENT = re.compile(r'^\d{1,3}(\.\d+)?%$')
+_SPEC_RESULT = re.compile(r'^(PASS|FAIL|DENIED\([\w,]+\))$')
+
+
+@dataclass
+class Diagnostic:
+    line: int
+    message: str
+    severity: int = SEVERITY_ERROR
+    code: str = ''
+
+    def to_lsp(self) -> Dict[str, Any]:
+        return {
+            'range': {'start': {'line': self.line, 'character': 0},
+                      'end': {'line': self.line, 'character': 10_000}},
+            'severity': self.severity,
+            'code': self.code,
+            'source': 'spokedpy',
+            'message': self.message,
+        }
+
+
+def uri_to_path(uri: str) -> str:
+    parsed = urllib.parse.urlparse(uri)
+    return os.path.abspath(urllib.request.url2pathname(urllib.parse.unquote(parsed.path)))
+
+
+def path_to_uri(path: str) -> str:
+    return 'file://' + urllib.request.pathname2url(os.path.abspath(path))
+
+
+def slot_of(header: SnippetFileHeader) -> str:
+    match = _SLOT.match(header.fields.get('slot', ''))
+    return match.group(0) if match else ''
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# WORKSPACE — snippet files + local index
+# ═══════════════════════════════════════════════════════════════════════════
+
+class SnippetWorkspace:
+    """Everything diagnostics need to know beyond the open document."""
+
+    def __init__(self, snippets_dir: str, audit_log: str = '', state_checkpoint: str = '',
+                 specs_dir: str = ''):
+        self.snippets_dir = os.path.abspath(snippets_dir)
+        self.audit_log = audit_log
+        self.state_checkpoint = state_checkpoint
+        # Label specs (see label_specs); by default the specs dir next to the snippets dir
+        self.specs_dir = specs_dir or os.path.join(os.path.dirname(self.snippets_dir), 'specs')
+
+    def contains(self, path: str) -> bool:
+        return os.path.abspath(path).startswith(self.snippets_dir + os.sep)
+
+    def files(self) -> List[Tuple[str, SnippetFileHeader]]:
+        found = []
+        for root, _dirs, names in os.walk(self.snippets_dir):
+            for name in sorted(names):
+                path = os.path.join(root, name)
+                try:
+                    with open(path, 'r', encoding='utf-8', errors='replace') as f:
+                        header = parse_file_header(f.read())
+                except OSError:
+                    continue
+                if header is not None:
+                    found.append((path, header))
+        return found
+
+    def states(self) -> Dict[str, str]:
+        if not self.audit_log and not self.state_checkpoint:
+            return {}
+        return {e.staging_id: e.state for e in local_index(self.audit_log, self.state_checkpoint)}
+
+    def predecessor(self, path: str, header: SnippetFileHeader) -> Optional[str]:
+        """The latest other promoted file with this label + language, older than ``header``."""
+        mine = header.promoted_order
+        best: Optional[Tuple[Tuple[float, int], str]] = None
+        for other_path, other in self.files():
+            if os.path.abspath(other_path) == os.path.abspath(path):
+                continue
+            if other.fields.get('staging_id') == header.fields.get('staging_id'):
+                continue
+            if (other.fields.get('label'), other.fields.get('language')) != \
+               (header.fields.get('label'), header.fields.get('language')):
+                continue
+            when = other.promoted_order
+            if mine[0] and when > mine:
+                continue
+            if best is None or when > best[0]:
+                best = (when, other_path)
+        return best[1] if best else None
+
+    # ── diagnostics ───────────────────────────────────────────────────
+
+    def diagnose(self, path: str, text: str) -> List[Diagnostic]:
+        if spec_format(path):
+            return self._spec_errors(path, text)
+        header = parse_file_header(text)
+        if header is None:
+            if self.contains(path):
+                return [Diagnostic(0, 'Missing VPyD snippet banner — files in the snippets '
+                                      'dir are written by promote()', code='banner')]
+            return []
+
+        out: List[Diagnostic] = []
+        fields, lines = header.fields, header.field_lines
+        if header.title != BANNER_TITLE:
+            out.append(Diagnostic(1, f"Unexpected banner title (expected '{BANNER_TITLE}')",
+                                  SEVERITY_WARNING, 'banner'))
+        if not header.closed:
+            out.append(Diagnostic(header.end_line, 'Header banner is not closed',
+                                  code='banner'))
+        if 0 < header.version < BANNER_VERSION:
+            out.append(Diagnostic(1, f"Banner layout version {header.version} (current: "
+                                     f"{BANNER_VERSION}) — spokedpy migrate-headers rewrites it",
+                                  SEVERITY_INFO, 'banner'))
+
+        missing = [f for f in header.required_fields if f not in fields]
+        if missing:
+            out.append(Diagnostic(header.end_line, f"Missing header field(s): {', '.join(missing)}",
+                                  code='fields'))
+        for name, line in lines.items():
+            if name not in HEADER_FIELDS + OPTIONAL_HEADER_FIELDS and not name.startswith('x-'):
+                out.append(Diagnostic(line, f"Unknown header field '{name}' "
+                                            f"(prefix custom fields with x-)",
+                                      SEVERITY_WARNING, 'fields'))
+        out += self._field_errors(path, header)
+
+        code_hash = _CODE_HASH.get(header.version, _HASH)
+        if 'code_hash' in fields and code_hash.match(fields['code_hash']) and not header.body_matches_hash():
+            out.append(Diagnostic(lines['code_hash'],
+                                  'Code changed since promotion — the body no longer matches '
+                                  'code_hash; re-stage to promote the edit',
+                                  SEVERITY_WARNING, 'stale-hash'))
+        out += self._spec_state(path, header)
+        out += self._slot_state(path, header)
+        return out
+
+    @staticmethod
+    def _spec_errors(path: str, text: str) -> List[Diagnostic]:
+        try:
+            parse_spec(text, spec_format(path))
+        except ValueError as exc:
+            match = re.search(r'line (\d+)', str(exc))
+            return [Diagnostic(int(match.group(1)) - 1 if match else 0, str(exc), code='spec')]
+        return []
+
+    def _spec_state(self, path: str, header: SnippetFileHeader) -> List[Diagnostic]:
+        recorded = header.fields.get('spec_hash', '')
+        label = header.fields.get('spec_inherits', '')
+        line = header.field_lines.get('spec_hash', header.field_lines.get('code_hash', 0))
+        sidecar = sidecar_for(path)
+        if sidecar is None and not label:
+            if not recorded:
+                return []
+            return [Diagnostic(line, f"Promoted with spec {recorded}, but its .spec.toml "
+                                     f"sidecar is missing", SEVERITY_WARNING, 'stale-spec')]
+        try:
+            suite = load_spec(sidecar) if sidecar is not None else None
+        except (OSError, ValueError) as exc:
+            return [Diagnostic(line, f"{os.path.basename(sidecar)}: {exc}", code='stale-spec')]
+        what = os.path.basename(sidecar) if sidecar is not None else ''
+        if label:
+            try:
+                base = LabelSpecs(self.specs_dir).default_for(label)
+            except (OSError, ValueError) as exc:
+                return [Diagnostic(line, str(exc), code='stale-spec')]
+            if base is None:
+                return [Diagnostic(line, f"Promoted against the default spec of label '{label}', "
+                                         f"which is gone from {self.specs_dir}",
+                                   SEVERITY_WARNING, 'stale-spec')]
+            try:
+                suite = inherit(base, suite, label)
+            except ValueError as exc:
+                return [Diagnostic(line, str(exc), code='stale-spec')]
+            what = ' with '.join(filter(None, (what, f"label '{label}'s default spec")))
+        current = suite.spec_hash
+        if current == recorded:
+            return []
+        status = 'added since promotion' if not recorded else 'changed since promotion'
+        return [Diagnostic(line, f"Spec {status} — {what} hashes to {current}; "
+                                 f"re-stage to promote against it", SEVERITY_WARNING, 'stale-spec')]
+
+    def _field_errors(self, path: str, header: SnippetFileHeader) -> List[Diagnostic]:
+        fields, lines, out = header.fields, header.field_lines, []
+
+        def bad(name: str, message: str, severity: int = SEVERITY_ERROR):
+            out.append(Diagnostic(lines[name], message, severity, 'fields'))
+
+        for name in ('staging_id', 'parent'):
+            if name in fields and not _STAGING_ID.match(fields[name]):
+                bad(name, f"Malformed {name} '{fields[name]}' (expected stg-<12 hex> or stg-<ULID>)")
+        language = fields.get('language', '')
+        if 'language' in fields:
+            if language not in LANG_EXTENSIONS:
+                bad('language', f"Unknown language '{language}'")
+            elif not path.endswith(LANG_EXTENSIONS[language]):
+                bad('language', f"{language} snippets use {LANG_EXTENSIONS[language]} files",
+                    SEVERITY_WARNING)
+        if 'slot' in fields:
+            match = _SLOT.match(fields['slot'])
+            if not match:
+                bad('slot', f"Malformed slot '{fields['slot']}' (expected <row letter><position>)")
+            elif language in LANG_TO_LETTER and match.group(1) != LANG_TO_LETTER[language]:
+                bad('slot', f"Slot {match.group(0)} is not on the {language} row "
+                            f"('{LANG_TO_LETTER[language]}')")
+        if 'banner_version' in fields and header.version not in HEADER_FIELDS_BY_VERSION:
+            bad('banner_version', f"Unknown banner_version '{fields['banner_version']}' "
+                                  f"(this spokedpy reads 1–{BANNER_VERSION})")
+        if 'code_hash' in fields and header.version in _CODE_HASH \
+                and not _CODE_HASH[header.version].match(fields['code_hash']):
+            digits = 16 if header.version == 1 else 64
+            bad('code_hash', f"Malformed code_hash (expected {digits} hex digits)")
+        if 'spec_hash' in fields and not _HASH.match(fields['spec_hash']):
+            bad('spec_hash', 'Malformed spec_hash (expected 16 hex digits)')
+        if 'spec_result' in fields and not _SPEC_RESULT.match(fields['spec_result']):
+            bad('spec_result', f"spec_result must be PASS, FAIL or DENIED(<syscalls>), "
+                               f"not '{fields['spec_result']}'")
+        if 'coverage' in fields and not _PERCENT.match(fields['coverage']):
+            bad('coverage', f"coverage must be a percentage, not '{fields['coverage']}'")
+        for name in ('created', 'promoted'):
+            if name in fields and not _ISO.match(fields[name]):
+                bad(name, f"{name} is not an RFC 3339 UTC timestamp", SEVERITY_WARNING)
+        if 'promoted_seq' in fields and not fields['promoted_seq'].isdigit():
+            bad('promoted_seq', f"promoted_seq must be a number, not '{fields['promoted_seq']}'")
+        return out
+
+    def _slot_state(self, path: str, header: SnippetFileHeader) -> List[Diagnostic]:
+        staging_id, slot = header.fields.get('staging_id', ''), slot_of(header)
+        states = self.states()
+        if not staging_id or not slot or not states:
+            return []
+        line = header.field_lines.get('slot', 0)
+        mine = states.get(staging_id, '')
+        if mine == 'rolled back':
+            return [Diagnostic(line, f"{staging_id} was rolled back from {slot}",
+                               SEVERITY_INFO, 'superseded')]
+
+        holders = [(p, h.fields.get('staging_id')) for p, h in self.files()
+                   if slot_of(h) == slot and h.fields.get('staging_id') != staging_id
+                   and states.get(h.fields.get('staging_id', '')) == 'promoted']
+        if not holders:
+            return []
+        other_path, other_id = holders[-1]
+        where = os.path.relpath(other_path, self.snippets_dir)
+        if mine == 'promoted':
+            return [Diagnostic(line, f"Slot conflict: {slot} is also held by {other_id} ({where})",
+                               SEVERITY_WARNING, 'slot-conflict')]
+        return [Diagnostic(line, f"Slot {slot} is now held by {other_id} ({where})",
+                           SEVERITY_INFO, 'superseded')]
+
+    # ── code actions ──────────────────────────────────────────────────
+
+    def code_actions(self, uri: str, text: str,
+                     diagnostics: List[Dict[str, Any]] = ()) -> List[Dict[str, Any]]:
+        path = uri_to_path(uri)
+        header = parse_file_header(text)
+        if header is None or header.fields.get('language') not in LANG_TO_LETTER:
+            return []
+        stale = [d for d in diagnostics if d.get('code') in ('stale-hash', 'stale-spec')]
+        actions = [{
+            'title': 'Re-stage this snippet',
+            'kind': 'quickfix' if stale else 'source',
+            'diagnostics': stale,
+            'command': {'title': 'Re-stage this snippet', 'command': CMD_RESTAGE,
+                        'arguments': [uri]},
+        }]
+        if self.predecessor(path, header):
+            actions.append({
+                'title': 'Show promoted predecessor',
+                'kind': 'source',
+                'command': {'title': 'Show promoted predecessor', 'command': CMD_PREDECESSOR,
+                            'arguments': [uri]},
+            })
+        return actions
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SERVER — JSON-RPC over stdio
+# ═══════════════════════════════════════════════════════════════════════════
+
+Stage = Callable[..., Dict[str, Any]]       # snippet_repl.remote_backend
+
+
+class SnippetLanguageServer:
+    """Minimal LSP server: full-text sync, diagnostics, code actions, commands."""
+
+    def __init__(self, workspace: SnippetWorkspace, stage: Optional[Stage] = None,
+                 reader: Optional[BinaryIO] = None, writer: Optional[BinaryIO] = None):
+        self.workspace = workspace
+        self._stage = stage
+        self._reader = reader or sys.stdin.buffer
+        self._writer = writer or sys.stdout.buffer
+        self.documents: Dict[str, str] = {}
+        self._next_id = 0
+        self._shutdown = False
+
+    # ── transport ─────────────────────────────────────────────────────
+
+    def read_message(self) -> Optional[Dict[str, Any]]:
+        length = None
+        while True:
+            line = self._reader.readline()
+            if not line:
+                return None
+            line = line.strip()
+            if not line:
+                break
+            name, _, value = line.decode('ascii', 'replace').partition(':')
+            if name.lower() == 'content-length':
+                length = int(value.strip())
+        if length is None:
+            return None
+        return json.loads(self._reader.read(length).decode('utf-8'))
+
+    def send(self, message: Dict[str, Any]):
+        body = json.dumps({'jsonrpc': '2.0', **message}).encode('utf-8')
+        self._writer.write(f"Content-Length: {len(body)}\r\n\r\n".encode('ascii') + body)
+        self._writer.flush()
+
+    def notify(self, method: str, params: Dict[str, Any]):
+        self.send({'method': method, 'params': params})
+
+    def request(self, method: str, params: Dict[str, Any]):
+        self._next_id += 1
+        self.send({'id': f"spokedpy-{self._next_id}", 'method': method, 'params': params})
+
+    def serve(self) -> int:
+        while True:
+            message = self.read_message()
+            if message is None:
+                return 0 if self._shutdown else 1
+            if message.get('method') == 'exit':
+                return 0 if self._shutdown else 1
+            self.handle(message)
+
+    # ── dispatch ──────────────────────────────────────────────────────
+
+    def handle(self, message: Dict[str, Any]):
+        method, params = message.get('method'), message.get('params') or {}
+        if method is None:
+            return                              # a reply to one of our requests
+        handler = getattr(self, '_on_' + method.replace('/', '_').replace('$', 'S'), None)
+        is_request = 'id' in message
+        try:
+            result = handler(params) if handler else None
+        except Exception as exc:
+            if is_request:
+                self.send({'id': message['id'], 'error': {'code': -32603, 'message': str(exc)}})
+            return
+        if not is_request:
+            return
+        if handler is None:
+            self.send({'id': message['id'],
+                       'error': {'code': -32601, 'message': f"Method not found: {method}"}})
+        else:
+            self.send({'id': message['id'], 'result': result})
+
+    def _on_initialize(self, params):
+        return {
+            'capabilities': {
+                'textDocumentSync': {'openClose': True, 'change': 1, 'save': True},
+                'codeActionProvider': True,
+                'executeCommandProvider': {'commands': [CMD_RESTAGE, CMD_PREDECESSOR]},
+            },
+            'serverInfo': {'name': 'spokedpy-lsp'},
+        }
+
+    def _on_initialized(self, params):
+        pass
+
+    def _on_shutdown(self, params):
+        self._shutdown = True
+
+    def _on_textDocument_didOpen(self, params):
+        doc = params['textDocument']
+        self.documents[doc['uri']] = doc['text']
+        self.publish(doc['uri'])
+
+    def _on_textDocument_didChange(self, params):
+        uri = params['textDocument']['uri']
+        changes = params.get('contentChanges') or []
+        if changes:
+            self.documents[uri] = changes[-1]['text']
+        self.publish(uri)
+
+    def _on_textDocument_didSave(self, params):
+        uri = params['textDocument']['uri']
+        if params.get('text') is not None:
+            self.documents[uri] = params['text']
+        self.publish(uri)
+
+    def _on_textDocument_didClose(self, params):
+        uri = params['textDocument']['uri']
+        self.documents.pop(uri, None)
+        self.notify('textDocument/publishDiagnostics', {'uri': uri, 'diagnostics': []})
+
+    def _on_textDocument_codeAction(self, params):
+        uri = params['textDocument']['uri']
+        diagnostics = (params.get('context') or {}).get('diagnostics') or []
+        return self.workspace.code_actions(uri, self._text(uri), diagnostics)
+
+    def _on_workspace_executeCommand(self, params):
+        command, args = params.get('command'), params.get('arguments') or []
+        if not args:
+            raise ValueError(f"{command} needs the document URI")
+        uri = args[0]
+        header = parse_file_header(self._text(uri))
+        if header is None:
+            raise ValueError('not a snippet file')
+
+        if command == CMD_PREDECESSOR:
+            previous = self.workspace.predecessor(uri_to_path(uri), header)
+            if previous is None:
+                self.notify('window/showMessage', {'type': 3, 'message': 'No promoted predecessor'})
+                return None
+            self.request('window/showDocument', {'uri': path_to_uri(previous), 'takeFocus': True})
+            return path_to_uri(previous)
+
+        if command == CMD_RESTAGE:
+            if self._stage is None:
+                raise ValueError('re-staging needs a running SpokedPy server')
+            language = header.fields.get('language', '')
+            sidecar = sidecar_for(uri_to_path(uri))
+            try:
+                # load_spec fills snapshot cases from their golden files
+                spec = {'spec': load_spec(sidecar).to_dict()} if sidecar else {}
+                snippet = self._stage(LANG_TO_LETTER[language], language, header.body,
+                                      header.fields.get('label', ''), **spec,
+                                      parent=header.fields.get('staging_id', ''))
+            except Exception as exc:
+                self.notify('window/showMessage', {'type': 1, 'message': f"Re-stage failed: {exc}"})
+                return None
+            phase = snippet.get('phase')
+            self.notify('window/showMessage', {
+                'type': 1 if phase == 'failed' else 3,
+                'message': f"Staged {snippet.get('staging_id')} → {snippet.get('reserved_address')} "
+                           f"({phase}) — review with spokedpy promote --interactive",
+            })
+            return snippet.get('staging_id')
+
+        raise ValueError(f"unknown command {command}")
+
+    # ── helpers ───────────────────────────────────────────────────────
+
+    def _text(self, uri: str) -> str:
+        if uri in self.documents:
+            return self.documents[uri]
+        with open(uri_to_path(uri), 'r', encoding='utf-8', errors='replace') as f:
+            return f.read()
+
+    def publish(self, uri: str):
+        diagnostics = self.workspace.diagnose(uri_to_path(uri), self.documents.get(uri, ''))
+        self.notify('textDocument/publishDiagnostics',
+                    {'uri': uri, 'diagnostics': [d.to_lsp() for d in diagnostics]})
@@ -0,0 +1,94 @@
+"""
+Snippet Owners — CODEOWNERS-style routing of approvals.
+
+An owners file (``[gates] owners_file`` → data/OWNERS) maps slots and labels
+to the people who must approve changes to them, one rule per line:
+
+    # pattern            owners
+    d*                   alice bob           # the whole Rust row
+    a1                   carol
+    label:Fibonacci      dave  eve@example.com
+
+A bare pattern matches the slot address, ``label:`` the snippet label; both
+are fnmatch patterns.  As in CODEOWNERS the LAST matching line wins, so put
+broad rules first; a line with a pattern and no owners un-owns what it
+matches.
+
+Staging a snippet into an owned slot records the owners as its
+``required_approvers``, sends an ``approval_request`` notification (owners
+that look like email addresses are added to the mail's recipients), and
+keeps it out of auto-promotion: one of the owners has to approve it.
+"""
+
+import fnmatch
+import os
+import threading
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, List, Optional, Tuple
+
+
+@dataclass
+class OwnerRule:
+    pattern: str                             # fnmatch on the slot, or 'label:<fnmatch>'
+    owners: List[str] = field(default_factory=list)
+    line: int = 0
+
+    def matches(self, slot: str, label: str) -> bool:
+        if self.pattern.startswith('label:'):
+            return fnmatch.fnmatchcase(label, self.pattern[len('label:'):])
+        return fnmatch.fnmatchcase(slot, self.pattern)
+
+
+def parse_owners(text: str) -> List[OwnerRule]:
+    """Parse an owners file; ValueError on a malformed line."""
+    rules = []
+    for number, raw in enumerate(text.splitlines(), 1):
+        line = raw.split('#', 1)[0].strip()
+        if not line:
+            continue
+        pattern, *owners = line.split()
+        if pattern == 'label:':
+            raise ValueError(f"line {number}: 'label:' needs a pattern, e.g. label:etl*")
+        rules.append(OwnerRule(pattern, owners, number))
+    return rules
+
+
+class SnippetOwners:
+    """The rules in ``path``, re-read whenever the file changes."""
+
+    def __init__(self, path: str):
+        self.path = path
+        self.error = ''
+        self._rules: List[OwnerRule] = []
+        self._mtime: Optional[float] = None
+        self._lock = threading.Lock()
+
+    @property
+    def rules(self) -> List[OwnerRule]:
+        try:
+            mtime = os.path.getmtime(self.path)
+        except OSError:
+            mtime = -1.0
+        with self._lock:
+            if mtime != self._mtime:
+                self._mtime = mtime
+                self._rules, self.error = [], ''
+                if mtime >= 0:
+                    try:
+                        with open(self.path, 'r', encoding='utf-8') as f:
+                            self._rules = parse_owners(f.read())
+                    except (OSError, ValueError) as exc:
+                        self.error = f"{self.path}: {exc}"
+            return list(self._rules)
+
+    def match(self, slot: str, label: str) -> Tuple[List[str], Optional[OwnerRule]]:
+        """(owners, the rule that decided) — ([], None) when nobody owns it."""
+        decided = None
+        for rule in self.rules:
+            if rule.matches(slot, label):
+                decided = rule
+        return (list(decided.owners), decided) if decided else ([], None)
+
+    def describe(self) -> Dict[str, Any]:
+        rules = self.rules
+        return {'path': self.path, 'error': self.error, 'rules': [asdict(r) for r in rules]}
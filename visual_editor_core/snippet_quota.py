@@ -0,0 +1,196 @@
+"""
+Snippet Quotas — how much one author or namespace may stage.
+
+A store may cap what each author and each namespace holds in the
+pipeline, so a runaway script cannot fill it:
+
+    [quota]
+    max_staged = 50             # snippets in the pipeline at once (0: no limit)
+    max_spec_minutes = 120      # dry-run minutes per UTC day (0: no limit)
+
+Both limits apply to every author (a snippet's ``author``; "unknown"
+when blank) and every namespace (the first path segment of a label with
+a ``/``: ``billing`` for ``billing/invoice``; other labels have none) on
+its own.  A snippet is *staged* from queueing until it is promoted,
+rejected or deleted — a failed dry-run still counts.  Its *spec
+minutes* are the wall time of its dry-runs, counted on the UTC day they
+start; a store re-reads the day's from its audit log on opening, so a
+restart does not reset them.  Runs outside the pipeline count as well —
+``spec run`` (its label), ``compare`` and ``spec mutate`` (the snippets'
+author and label), the REPL ("unknown") — but leave no audit entry, so
+they are counted until the next restart only.
+
+queue_snippet refuses a snippet whose author or namespace is at either
+limit with a QUOTA_EXCEEDED SpokedError naming the scope, the limit and
+the use (HTTP 429 from the API), and releases the slot it reserved.
+``spokedpy quota show`` (GET /api/staging/quota) lists the use per
+author and namespace.
+"""
+
+import threading
+import time
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from .errors import ErrorKind, SpokedError
+
+SCOPES = ('author', 'namespace')
+UNKNOWN_AUTHOR = 'unknown'
+
+
+def namespace_of(label: str) -> str:
+    """``billing`` for ``billing/invoice``; '' for a label without a ``/``."""
+    parts = [p for p in (label or '').strip().replace('\\', '/').split('/') if p]
+    return parts[0] if len(parts) > 1 else ''
+
+
+def scopes_of(author: str, label: str) -> List[Tuple[str, str]]:
+    """The (scope, name) pairs a snippet of ``author`` and ``label`` counts towards."""
+    found = [('author', (author or '').strip() or UNKNOWN_AUTHOR)]
+    namespace = namespace_of(label)
+    if namespace:
+        found.append(('namespace', namespace))
+    return found
+
+
+def utc_day(ts: Optional[float] = None) -> str:
+    return time.strftime('%Y-%m-%d', time.gmtime(time.time() if ts is None else ts))
+
+
+@dataclass
+class QuotaUsage:
+    scope: str                              # 'author' or 'namespace'
+    name: str
+    staged: int = 0
+    spec_seconds: float = 0.0
+
+    @property
+    def spec_minutes(self) -> float:
+        return self.spec_seconds / 60.0
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {**asdict(self), 'spec_minutes': round(self.spec_minutes, 3)}
+
+
+class Quotas:
+    """The ``[quota]`` limits of one store and its spec minutes today."""
+
+    def __init__(self, max_staged: int = 0, max_spec_minutes: float = 0.0):
+        if int(max_staged) < 0 or float(max_spec_minutes) < 0:
+            raise ValueError(f"quota limits cannot be negative (max_staged={max_staged}, "
+                             f"max_spec_minutes={max_spec_minutes})")
+        self.max_staged = int(max_staged)
+        self.max_spec_minutes = float(max_spec_minutes)
+        self._lock = threading.Lock()
+        self._day = utc_day()
+        self._spent: Dict[Tuple[str, str], float] = {}
+
+    @property
+    def enabled(self) -> bool:
+        return bool(self.max_staged or self.max_spec_minutes)
+
+    def _roll(self, now: Optional[float] = None):
+        day = utc_day(now)
+        if day != self._day:
+            self._day, self._spent = day, {}
+
+    def record(self, author: str, label: str, seconds: float, at: Optional[float] = None):
+        """Count a dry-run of ``seconds`` started at ``at`` (today's only)."""
+        with self._lock:
+            self._roll()
+            if utc_day(at) != self._day:
+                return
+            for scope in scopes_of(author, label):
+                self._spent[scope] = self._spent.get(scope, 0.0) + max(0.0, float(seconds))
+
+    def load(self, audit):
+        """Today's dry-runs from ``audit`` (an AuditLogger), e.g. after a restart."""
+        from .snippet_staging import AuditEventType
+        today = utc_day()
+        started = {e['staging_id']: e['timestamp']
+                   for e in audit.read_events(AuditEventType.SPEC_EXEC_STARTED)
+                   if utc_day(e.get('timestamp', 0)) == today}
+        if not started:
+            return
+        queued = {e['staging_id']: e.get('data', {})
+                  for e in audit.read_events(AuditEventType.SNIPPET_QUEUED)}
+        runs: List[Tuple[str, float, float]] = []
+        for event_type in (AuditEventType.SPEC_EXEC_COMPLETED, AuditEventType.SPEC_EXEC_FAILED):
+            for e in audit.read_events(event_type):
+                begun = started.get(e['staging_id'])
+                if begun is not None and e.get('timestamp', 0) >= begun:
+                    runs.append((e['staging_id'], begun, e['timestamp']))
+        with self._lock:
+            self._day, self._spent = today, {}
+        for staging_id, begun, ended in runs:
+            data = queued.get(staging_id, {})
+            self.record(data.get('author', ''), data.get('label', ''), ended - begun, begun)
+
+    def spent(self, scope: str, name: str) -> float:
+        """Spec seconds ``name`` of ``scope`` used today."""
+        with self._lock:
+            self._roll()
+            return self._spent.get((scope, name), 0.0)
+
+    def usage(self, active: Iterable) -> List[QuotaUsage]:
+        """Per author and namespace with a staged snippet or spec time today, sorted."""
+        rows: Dict[Tuple[str, str], QuotaUsage] = {}
+        for snippet in active:
+            for scope in scopes_of(snippet.author, snippet.label):
+                rows.setdefault(scope, QuotaUsage(*scope)).staged += 1
+        with self._lock:
+            self._roll()
+            for scope, seconds in self._spent.items():
+                rows.setdefault(scope, QuotaUsage(*scope)).spec_seconds = seconds
+        return [rows[k] for k in sorted(rows, key=lambda k: (SCOPES.index(k[0]), k[1]))]
+
+    def check(self, active: Iterable, author: str, label: str):
+        """QUOTA_EXCEEDED if staging one more snippet would pass a limit."""
+        if not self.enabled:
+            return
+        scopes = scopes_of(author, label)
+        staged = {scope: 0 for scope in scopes}
+        for snippet in active:
+            for scope in scopes_of(snippet.author, snippet.label):
+                if scope in staged:
+                    staged[scope] += 1
+        for scope, name in scopes:
+            count = staged[(scope, name)]
+            if self.max_staged and count >= self.max_staged:
+                raise SpokedError(
+                    ErrorKind.QUOTA_EXCEEDED,
+                    f"Quota: {scope} '{name}' has {count} snippet(s) staged, the limit is "
+                    f"{self.max_staged} ([quota] max_staged) — promote, reject or delete some",
+                    scope=scope, name=name, limit='max_staged', used=count,
+                    allowed=self.max_staged)
+            minutes = self.spent(scope, name) / 60.0
+            if self.max_spec_minutes and minutes >= self.max_spec_minutes:
+                raise SpokedError(
+                    ErrorKind.QUOTA_EXCEEDED,
+                    f"Quota: {scope} '{name}' has used {minutes:.1f} spec minute(s) today, the "
+                    f"limit is {self.max_spec_minutes:g} ([quota] max_spec_minutes; resets at "
+                    f"00:00 UTC)",
+                    scope=scope, name=name, limit='max_spec_minutes', used=round(minutes, 3),
+                    allowed=self.max_spec_minutes)
+
+    def report(self, active: Iterable) -> Dict[str, Any]:
+        """The limits, the UTC day and usage() — what GET /api/staging/quota returns."""
+        return {'max_staged': self.max_staged, 'max_spec_minutes': self.max_spec_minutes,
+                'day': utc_day(), 'usage': [u.to_dict() for u in self.usage(active)]}
+
+
+def format_quota(report: Dict[str, Any]) -> str:
+    """The ``spokedpy quota show`` table, from Quotas.report()."""
+    def limit(value) -> str:
+        return f"{value:g}" if value else '∞'
+
+    lines = [f"Quotas for {report['day']} (UTC): max_staged {limit(report['max_staged'])}, "
+             f"max_spec_minutes {limit(report['max_spec_minutes'])}"]
+    if not report['usage']:
+        lines.append('  nothing staged and no spec minutes used today')
+        return '\n'.join(lines)
+    for row in report['usage']:
+        lines.append(f"  {row['scope']:<10} {row['name']:<20} "
+                     f"staged {row['staged']:>4}/{limit(report['max_staged']):<5} "
+                     f"spec {row['spec_minutes']:>7.1f}/{limit(report['max_spec_minutes'])} min")
+    return '\n'.join(lines)
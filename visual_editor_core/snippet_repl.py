@@ -0,0 +1,223 @@
+"""
+Snippet REPL — type a snippet body, run it in the staging sandbox, stage it.
+
+``spokedpy repl --lang rust`` drives this loop.  Lines accumulate in a
+buffer; a blank line runs the whole buffer through the same isolated
+executor staging uses for speculation (fresh Python namespace, one child
+process per run for everything else) and prints output and timing.
+
+    ┌──────────────────────────┬──────────────────────────────────────────┐
+    │  <blank line> / :run     │  run the buffer                          │
+    │  :stage <label> [slot]   │  queue + speculate the buffer            │
+    │  :show                   │  print the buffer                        │
+    │  :undo                   │  drop the last block (run or not)        │
+    │  :clear                  │  empty the buffer                        │
+    │  :lang <language>        │  switch language (clears the buffer)     │
+    │  :load <file>            │  replace the buffer with a file          │
+    │  :help / :quit           │                                          │
+    └──────────────────────────┴──────────────────────────────────────────┘
+
+``slot`` is an engine row letter (``c``) or address (``c4``); rows must
+match the language, and the pipeline reserves the next free position.
+
+The loop only needs two callables — ``remote_backend()`` points them at a
+running server (the CLI and the Jupyter kernel), tests at a local
+StagingPipeline.
+"""
+
+import os
+import shlex
+from typing import Any, Callable, Dict, List, Optional
+
+from .node_registry import LANGUAGE_STRING_TO_ENGINE
+
+
+Evaluate = Callable[[str, str], Dict[str, Any]]                 # (language, code) → result
+Stage = Callable[[str, str, str, str], Dict[str, Any]]         # (letter, language, code, label) → snippet
+
+HELP = """\
+  <blank line>  run the buffer          :stage <label> [slot]  stage the buffer
+  :run          run the buffer          :show                  print the buffer
+  :undo         drop the last block     :clear                 empty the buffer
+  :lang <name>  switch language         :load <file>           load a file
+  :help         this help               :quit                  leave"""
+
+
+class ReplError(ValueError):
+    """A REPL command was used incorrectly."""
+
+
+def engine_letter(language: str) -> str:
+    engine = LANGUAGE_STRING_TO_ENGINE.get(language.lower().strip())
+    if engine is None:
+        known = ', '.join(sorted(LANGUAGE_STRING_TO_ENGINE))
+        raise ReplError(f"Unknown language '{language}' (choose from: {known})")
+    return engine.letter
+
+
+class SnippetRepl:
+    """Line-at-a-time REPL state machine; ``feed()`` returns False to quit."""
+
+    def __init__(self, language: str, evaluate: Evaluate, stage: Stage,
+                 out: Callable[[str], None] = print):
+        self.letter = engine_letter(language)
+        self.language = language.lower().strip()
+        self._evaluate = evaluate
+        self._stage = stage
+        self._out = out
+        self.lines: List[str] = []
+        self._block_start = 0               # first line not yet run
+        self._ran_from = 0                  # first line of the last block run
+        self.last_result: Optional[Dict[str, Any]] = None
+
+    @property
+    def prompt(self) -> str:
+        return f"{self.language}{'…' if self._block_start < len(self.lines) else '>'} "
+
+    @property
+    def buffer(self) -> str:
+        return '\n'.join(self.lines)
+
+    def feed(self, line: str) -> bool:
+        if line.startswith(':'):
+            try:
+                return self._command(line[1:])
+            except ReplError as exc:
+                self._out(f"  ✘ {exc}")
+                return True
+        if line.strip():
+            self.lines.append(line.rstrip('\n'))
+        elif self._block_start < len(self.lines):
+            self.run()
+        return True
+
+    # ── commands ──────────────────────────────────────────────────────
+
+    def _command(self, text: str) -> bool:
+        try:
+            words = shlex.split(text)
+        except ValueError as exc:
+            raise ReplError(str(exc))
+        if not words:
+            raise ReplError('empty command — :help lists them')
+        name, args = words[0].lower(), words[1:]
+
+        if name in ('q', 'quit', 'exit'):
+            return False
+        if name in ('h', 'help', '?'):
+            self._out(HELP)
+        elif name == 'run':
+            self.run()
+        elif name == 'show':
+            self._out(self.buffer or '  (empty buffer)')
+        elif name == 'undo':
+            if self._block_start < len(self.lines):
+                del self.lines[self._block_start:]
+            elif self.lines:
+                del self.lines[self._ran_from:]
+                self._block_start = self._ran_from = len(self.lines)
+            else:
+                raise ReplError('empty buffer')
+        elif name == 'clear':
+            self._reset()
+        elif name == 'lang':
+            if len(args) != 1:
+                raise ReplError('usage: :lang <language>')
+            self.letter = engine_letter(args[0])
+            self.language = args[0].lower()
+            self._reset()
+        elif name == 'load':
+            if len(args) != 1:
+                raise ReplError('usage: :load <file>')
+            try:
+                with open(args[0], 'r', encoding='utf-8') as f:
+                    self.set_buffer(f.read())
+            except OSError as exc:
+                raise ReplError(f"cannot read {args[0]}: {exc.strerror or exc}")
+            self._out(f"  loaded {len(self.lines)} lines")
+        elif name == 'stage':
+            if not args or len(args) > 2:
+                raise ReplError('usage: :stage <label> [slot]')
+            self.stage(args[0], args[1] if len(args) == 2 else '')
+        else:
+            raise ReplError(f"unknown command ':{name}' — :help lists them")
+        return True
+
+    def set_buffer(self, code: str):
+        """Replace the buffer, e.g. with a notebook cell."""
+        self.lines = code.splitlines()
+        self._block_start = self._ran_from = 0
+
+    def _reset(self):
+        self.lines, self.last_result = [], None
+        self._block_start = self._ran_from = 0
+
+    # ── run / stage ───────────────────────────────────────────────────
+
+    def run(self) -> Optional[Dict[str, Any]]:
+        if not self.lines:
+            raise ReplError('empty buffer')
+        result = self._evaluate(self.language, self.buffer)
+        if self._block_start < len(self.lines):
+            self._ran_from = self._block_start
+        self._block_start = len(self.lines)
+        self.last_result = result
+        output = (result.get('output') or '').rstrip()
+        if output:
+            self._out(output)
+        if not result.get('success'):
+            self._out(f"  ✘ {(result.get('error') or 'failed').strip()}")
+            self._out('  (:undo drops the last block)')
+        self._out(f"  [{'ok' if result.get('success') else 'error'} · "
+                  f"{result.get('execution_time', 0) * 1000:.1f} ms]")
+        return result
+
+    def stage(self, label: str, slot: str = '') -> Dict[str, Any]:
+        if not self.lines:
+            raise ReplError('empty buffer')
+        letter = slot[:1].lower() if slot else self.letter
+        if letter != self.letter:
+            raise ReplError(f"slot '{slot}' is not on the {self.language} row "
+                            f"(letter '{self.letter}')")
+        snippet = self._stage(letter, self.language, self.buffer, label)
+        address = snippet.get('reserved_address', '?')
+        note = f" (requested {slot})" if len(slot) > 1 and slot.lower() != address else ''
+        self._out(f"  ✔ staged {snippet.get('staging_id')} → {address}{note} "
+                  f"— {snippet.get('phase')}")
+        if snippet.get('spec_error'):
+            self._out(f"  ✘ {snippet['spec_error'].strip()}")
+        return snippet
+
+
+def remote_backend(base_url: str, author: str = '', timeout: float = 120):
+    """(evaluate, stage) callables backed by a running server's pipeline.
+
+    Failures raise ValueError with the server's error message, or
+    requests.RequestException when the server cannot be reached.  A
+    tenant's token comes from SPOKEDPY_TOKEN.
+    """
+    import requests
+    token = os.environ.get('SPOKEDPY_TOKEN', '').strip()
+    headers = {'X-Spokedpy-Token': token} if token else {}
+
+    def call(method: str, path: str, body: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        resp = requests.request(method, f"{base_url}{path}", json=body, headers=headers,
+                                timeout=timeout)
+        data = resp.json()
+        if not data.get('success'):
+            raise ValueError(data.get('error', f"HTTP {resp.status_code}"))
+        return data
+
+    def evaluate(language: str, code: str) -> Dict[str, Any]:
+        return call('POST', '/api/staging/evaluate', {'language': language, 'code': code})['result']
+
+    def stage(letter: str, language: str, code: str, label: str,
+              spec: str = '', spec_format: str = 'toml', parent: str = '') -> Dict[str, Any]:
+        snippet = call('POST', '/api/staging/queue',
+                       {'engine_letter': letter, 'language': language, 'code': code,
+                        'label': label, 'author': author,
+                        **({'spec': spec, 'spec_format': spec_format} if spec else {}),
+                        **({'parent': parent} if parent else {})})['snippet']
+        return call('POST', f"/api/staging/speculate/{snippet['staging_id']}")['snippet']
+
+    return evaluate, stage
@@ -10,17 +10,22 @@ the engine tab system:
     │    on the target engine row (position held, not yet live).  │
     │                                                              │
     │  Phase 2: SPECULATIVE EXECUTION (dry-run)                   │
-    │    Executor runs the code in an ISOLATED sandbox.           │
+    │    Executor runs the code in an ISOLATED sandbox — once, or │
+    │    once per case of its spec suite (see spec_suite).        │
     │    Output, errors, timing, and variables are captured.      │
     │    The production namespace is NOT touched.                 │
     │                                                              │
+    │  Phase 2b: PROMOTION GATES                                  │
+    │    Plugin gates (WASM modules in the plugins dir) vote       │
+    │    allow/deny on the header + source; any deny → FAILED.    │
+    │                                                              │
     │  Phase 3: VERDICT (pass / fail / manual)                    │
     │    If pass  → auto-promote to Phase 4                       │
     │    If fail  → reject, release reserved slot, log reason     │
     │    If manual → hold for human approval                      │
     │                                                              │
     │  Phase 4: PROMOTE TO PRODUCTION                             │
-    │    • Code written to `snippets/<lang>/<staging_id>.ext`     │
+    │    • Code written to `snippets/<lang>/<hh>/<staging_id>.ext`│
     │    • Synthetic node created in the SessionLedger            │
     │    • Node committed to the NodeRegistry (reserved slot)     │
     │    • Full audit trail: staging log, speculative output,     │
@@ -41,21 +46,75 @@ The staging folder structure (configurable via DB setting → SPOKEDPY_SNIPPETS_
         bash/      → .sh files
         rust/      → .rs files
         ...
+
+A pipeline opened with ``mode=StoreMode.READ_ONLY`` never writes to the
+store: every operation that would stage, speculate, promote or roll back
+raises ReadOnlyStoreError, nothing is appended to the audit log, and the
+promoted snippets are loaded from the state checkpoint (load_promoted)
+instead of being re-run.  This is how consumers that only resolve promoted
+snippets by slot mount a production store (``spokedpy serve --read-only``).
+
+Custom metadata: ``spokedpy stage --meta team=payments`` attaches key/value
+pairs to a snippet (StagedSnippet.meta).  They are written to the promoted
+file's header as ``x-team: payments`` lines, kept in the audit log and the
+state checkpoint, and can be filtered on (``spokedpy list --meta team=payments``).
+
+Timestamps the store keeps (header ``created`` / ``promoted``, the audit
+log's ``iso_time``) are UTC RFC 3339 with microseconds (utc_timestamp).
+Every audit entry also carries ``seq``, a number that only goes up within
+one store: a snippet's ``seq`` is that of its queue event and
+``promoted_seq`` that of its promotion, so two promotions in the same
+instant still have an order.
+
+Deleting a snippet (``delete``) is soft: it moves to the trash with a
+tombstone (see snippet_trash), from which ``restore`` brings it back until
+gc_trash purges it ``trash_days`` after the delete.
+
+Each dry-run — each spec case — happens in a scratch dir of its own (see
+scratch), which holds the suite's read-only fixtures and is removed
+afterwards.  A write refused outside it fails the run; the violations
+are kept as ``sandbox_violations`` and in the failure bundle.  Its engine
+spawns run under the ``[sandbox] seccomp`` profile (see seccomp): a
+denied syscall makes the run DENIED, kept as ``spec_denied``.
+
+Dry-runs also get a network level (see offline): the engine's from the
+``[sandbox] network`` policy, or the one the snippet declares in its
+leading comments (``# network: loopback``), which its promoted file's
+header then records.
+
+A promoted snippet that fails a revalidation (``respec``, or the
+``[revalidate]`` schedule) marks its slot DEGRADED: snippets importing the
+slot are not promoted until it passes again or is replaced (see
+revalidation).
+
+A snippet staged from an existing one (re-staged, edited and staged
+again, imported) records it as its ``parent``, written to the header as
+a ``parent:`` line; ``lineage()`` walks the chain back (see lineage).
 """
 
 import os
+import re
 import json
 import time
 import uuid
+import difflib
+import fnmatch
 import hashlib
 import shutil
 import threading
 import traceback
+from contextlib import contextmanager
 from enum import Enum
-from dataclasses import dataclass, field, asdict
-from typing import Any, Dict, List, Optional, Tuple
+from dataclasses import dataclass, field, asdict, fields as dataclass_fields
+from datetime import datetime, timezone
+from typing import Any, Callable, Dict, List, Optional, Tuple
 from pathlib import Path
 
+from .errors import ErrorKind, SpokedError
+from .lineage import (
+    SOURCE_AUDIT, SOURCE_BUNDLE, SOURCE_FILE, SOURCE_STORE, Ancestor, build_lineage,
+)
+
 
 # ── File extensions per language ────────────────────────────────────────────
 LANG_EXTENSIONS = {
@@ -86,11 +145,92 @@ LETTER_TO_LANG = {
 
 LANG_TO_LETTER = {v: k for k, v in LETTER_TO_LANG.items()}
 
+# ── Store layout ────────────────────────────────────────────────────────────
+# Promoted files are sharded by code_hash prefix, <lang>/<hash[:2]>/<file>,
+# so parallel promotions do not all create entries in one directory.  Files
+# written flat into <lang>/ before sharding stay where they are; readers
+# (slot_resolver, snippet_lsp, header_migration) look at both levels.
+DEFAULT_SHARD_WIDTH = 2
+
+
+def promoted_dir(snippets_dir: str, language: str, code_hash: str,
+                 shard_width: int = DEFAULT_SHARD_WIDTH) -> str:
+    """The directory a promoted file with this code_hash goes to."""
+    lang_dir = os.path.join(snippets_dir, language)
+    return os.path.join(lang_dir, code_hash[:shard_width]) if shard_width else lang_dir
+
 
 # ═══════════════════════════════════════════════════════════════════════════
 # STAGING LIFECYCLE
 # ═══════════════════════════════════════════════════════════════════════════
 
+class StoreMode(str, Enum):
+    """How the pipeline may use its store ([store] read_only)."""
+    READ_WRITE   = 'read_write'
+    READ_ONLY    = 'read_only'       # Resolve promoted snippets; change nothing
+
+
+class IdMode(str, Enum):
+    """How queue_snippet names snippets ([store] id_mode)."""
+    RANDOM        = 'random'         # stg-<12 random hex digits>
+    DETERMINISTIC = 'deterministic'  # stg-<12 hex digits of the content> (see deterministic_id)
+    ULID          = 'ulid'           # stg-<26-char ULID>: sorts in the order it was staged
+
+
+def deterministic_id(code_hash: str, label: str, address: str, counter: int = 0) -> str:
+    """
+    The staging_id of the ``counter``-th staging of this code, label and slot
+    in a store.  Re-staging identical content in another environment
+    yields the same ids in the same order, so exports, imports and mirrors
+    can recognise a snippet they already hold.
+    """
+    key = '\0'.join((code_hash, label, address, str(counter)))
+    return f"stg-{hashlib.sha256(key.encode('utf-8')).hexdigest()[:12]}"
+
+
+_CROCKFORD = '0123456789ABCDEFGHJKMNPQRSTVWXYZ'
+
+
+class UlidGenerator:
+    """
+    Monotonic ULIDs: 48 bits of milliseconds, then 80 random bits, in
+    Crockford base32.  Within one millisecond (or if the clock steps back)
+    the previous ULID is incremented instead, so each one sorts after the
+    last — as text too.
+    """
+
+    def __init__(self):
+        self._last = 0
+        self._lock = threading.Lock()
+
+    def next(self, now: Optional[float] = None) -> str:
+        ms = int((time.time() if now is None else now) * 1000) & ((1 << 48) - 1)
+        with self._lock:
+            value = (ms << 80) | int.from_bytes(os.urandom(10), 'big')
+            if value >> 80 <= self._last >> 80:
+                value = self._last + 1
+            self._last = value
+        return ''.join(_CROCKFORD[(value >> shift) & 31] for shift in range(125, -1, -5))
+
+
+def ulid_time(staging_id: str) -> Optional[float]:
+    """When a ULID staging_id was made (epoch seconds); None for other ids."""
+    text = staging_id[4:] if staging_id.startswith('stg-') else staging_id
+    if len(text) != 26 or any(c not in _CROCKFORD for c in text.upper()):
+        return None
+    ms = 0
+    for c in text.upper()[:10]:
+        ms = ms * 32 + _CROCKFORD.index(c)
+    return ms / 1000
+
+
+class ReadOnlyStoreError(SpokedError):
+    """Raised by every mutating operation on a READ_ONLY pipeline."""
+
+    def __init__(self, message: str, **context: Any):
+        super().__init__(ErrorKind.STORE_READ_ONLY, message, **context)
+
+
 class StagingPhase(str, Enum):
     """Lifecycle phase of a staged snippet."""
     QUEUED       = 'queued'          # Received, slot reserved
@@ -110,6 +250,8 @@ class AuditEventType(str, Enum):
     SPEC_EXEC_STARTED      = 'spec_exec_started'
     SPEC_EXEC_COMPLETED    = 'spec_exec_completed'
     SPEC_EXEC_FAILED       = 'spec_exec_failed'
+    GATE_PASSED            = 'gate_passed'
+    GATE_DENIED            = 'gate_denied'
     VERDICT_PASS           = 'verdict_pass'
     VERDICT_FAIL           = 'verdict_fail'
     VERDICT_MANUAL_HOLD    = 'verdict_manual_hold'
@@ -119,11 +261,112 @@ class AuditEventType(str, Enum):
     REGISTRY_SLOT_COMMITTED = 'registry_slot_committed'
     PROMOTION_COMPLETED    = 'promotion_completed'
     REJECTION              = 'rejection'
+    LABEL_CHANGED          = 'label_changed'
+    APPROVAL_RECORDED      = 'approval_recorded'
+    FAILURE_RECORDED       = 'failure_recorded'
+    SPEC_ANOMALY           = 'spec_anomaly'
+    PROMOTION_SCHEDULED    = 'promotion_scheduled'
+    SCHEDULE_CANCELLED     = 'schedule_cancelled'
+    POLICY_EVALUATED       = 'policy_evaluated'
+    CONSTRAINT_VIOLATED    = 'constraint_violated'
+    APPROVAL_REQUESTED     = 'approval_requested'
+    MUTATION_TESTED        = 'mutation_tested'
+    COMPARED               = 'compared'
+    RESPEC                 = 'respec'
+    SLOT_DEGRADED          = 'slot_degraded'
+    SLOT_RECOVERED         = 'slot_recovered'
     ROLLBACK               = 'rollback'
     SLOT_RELEASED          = 'slot_released'
+    SNIPPET_DELETED        = 'snippet_deleted'
+    SNIPPET_RESTORED       = 'snippet_restored'
+    TRASH_PURGED           = 'trash_purged'
     ERROR                  = 'error'
 
 
+# ═══════════════════════════════════════════════════════════════════════════
+# TIMESTAMPS — UTC RFC 3339
+# ═══════════════════════════════════════════════════════════════════════════
+
+def utc_timestamp(ts: Optional[float] = None) -> str:
+    """``ts`` (default: now) as RFC 3339 UTC with microseconds, e.g. 2026-02-10T11:57:14.031250Z."""
+    moment = datetime.fromtimestamp(time.time() if ts is None else ts, timezone.utc)
+    return moment.strftime('%Y-%m-%dT%H:%M:%S.%fZ')
+
+
+def parse_timestamp(text: str) -> float:
+    """
+    Unix time of an RFC 3339 / ISO 8601 timestamp — any UTC offset, with or
+    without a fraction (older headers have whole seconds); no offset means
+    UTC.  Raises ValueError if malformed.
+    """
+    value = text.strip()
+    if value[-1:] in ('Z', 'z'):
+        value = value[:-1] + '+00:00'
+    moment = datetime.fromisoformat(value)
+    if moment.tzinfo is None:
+        moment = moment.replace(tzinfo=timezone.utc)
+    return moment.timestamp()
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# CUSTOM METADATA — user-defined x- fields
+# ═══════════════════════════════════════════════════════════════════════════
+
+META_PREFIX = 'x-'                          # header spelling of a meta key
+META_VALUE_LIMIT = 200                      # characters per value
+_META_KEY = re.compile(r'^[a-z0-9][a-z0-9_.-]{0,63}$')
+
+
+def normalize_meta(meta: Optional[Dict[str, Any]]) -> Dict[str, str]:
+    """
+    Validated metadata: keys lower-cased (a leading ``x-`` dropped), values
+    single-line strings.  Raises ValueError on a bad key or value.
+    """
+    if not meta:
+        return {}
+    if not isinstance(meta, dict):
+        raise ValueError('meta must be an object of key → value')
+    out: Dict[str, str] = {}
+    for key, value in meta.items():
+        name = str(key).strip().lower()
+        if name.startswith(META_PREFIX):
+            name = name[len(META_PREFIX):]
+        if not _META_KEY.match(name):
+            raise ValueError(f"Bad meta key '{key}' (letters, digits, '_', '.', '-')")
+        text = '' if value is None else str(value).strip()
+        if '\n' in text or '\r' in text or len(text) > META_VALUE_LIMIT:
+            raise ValueError(f"Meta '{name}' must be one line of at most "
+                             f"{META_VALUE_LIMIT} characters")
+        out[name] = text
+    return dict(sorted(out.items()))
+
+
+def parse_meta_args(pairs: List[str], filters: bool = False) -> Dict[str, Optional[str]]:
+    """
+    ``['team=payments', 'ticket=PAY-12']`` → ``{'team': 'payments', …}``.
+    With ``filters`` a bare ``KEY`` is allowed and means "has the key"
+    (value None); values may then be fnmatch patterns.
+    """
+    meta: Dict[str, Optional[str]] = {}
+    for pair in pairs:
+        key, sep, value = pair.partition('=')
+        if not sep and not filters:
+            raise ValueError(f"--meta expects KEY=VALUE, not '{pair}'")
+        name = next(iter(normalize_meta({key: ''})))
+        meta[name] = value if sep else None
+    return meta if filters else normalize_meta(meta)
+
+
+def meta_matches(meta: Dict[str, str], wanted: Dict[str, Optional[str]]) -> bool:
+    """True if ``meta`` has every key of ``wanted`` with a matching value."""
+    for key, pattern in wanted.items():
+        if key not in meta:
+            return False
+        if pattern is not None and not fnmatch.fnmatchcase(meta[key], pattern):
+            return False
+    return True
+
+
 # ═══════════════════════════════════════════════════════════════════════════
 # STAGED SNIPPET — One entry in the pipeline
 # ═══════════════════════════════════════════════════════════════════════════
@@ -139,11 +382,18 @@ class StagedSnippet:
     label: str                               # Human-readable name
     code: str                                # The snippet source code
     code_hash: str                           # SHA-256 of the code
+    author: str = ''                         # Who submitted it (user / agent)
+    spec: Dict[str, Any] = field(default_factory=dict)      # SpecSuite.to_dict(), if any
+    spec_hash: str = ''                      # SpecSuite.spec_hash ('' → single run)
+    meta: Dict[str, str] = field(default_factory=dict)      # custom x- fields (normalize_meta)
+    network: str = ''                        # declared network level ('' → its engine's)
+    parent: str = ''                         # staging_id it was staged from (see lineage)
 
     # ── Lifecycle ─────────────────────────────────────────────────────────
     phase: StagingPhase = StagingPhase.QUEUED
     created_at: float = 0.0                  # Unix timestamp
     updated_at: float = 0.0
+    seq: int = 0                             # store sequence number of its queue event
 
     # ── Slot reservation ──────────────────────────────────────────────────
     reserved_engine: str = ''                # EngineID name (e.g. 'PYTHON')
@@ -158,12 +408,35 @@ class StagedSnippet:
     spec_variables: Dict[str, Any] = field(default_factory=dict)
     spec_started_at: float = 0.0
     spec_completed_at: float = 0.0
+    spec_cases: List[Dict[str, Any]] = field(default_factory=list)   # CaseResult.to_dict() per case
+    sandbox_violations: List[Dict[str, str]] = field(default_factory=list)  # see scratch
+    spec_denied: List[str] = field(default_factory=list)    # syscalls refused (see seccomp)
+    network_not_enforced: List[str] = field(default_factory=list)   # see offline.apply
+    failure_bundle: str = ''                 # Directory of the last failure bundle, if any
+
+    # ── Promotion gates ───────────────────────────────────────────────────
+    gate_results: List[Dict[str, Any]] = field(default_factory=list)
+    metrics: Dict[str, Any] = field(default_factory=dict)   # gate measurements
 
     # ── Promotion details ─────────────────────────────────────────────────
     saved_file_path: str = ''                # Path where snippet was saved
     ledger_node_id: str = ''                 # Node ID in the SessionLedger
     registry_slot_id: str = ''               # Slot ID in the NodeRegistry (nra##)
     promoted_at: float = 0.0
+    promoted_seq: int = 0                    # store sequence number of its promotion
+
+    degraded: Dict[str, Any] = field(default_factory=dict)  # failing revalidation (see revalidation)
+
+    # ── Review ────────────────────────────────────────────────────────────
+    reviewed_by: str = ''                    # Principal who approved / rejected it
+    required_approvers: List[str] = field(default_factory=list)  # slot owners (see snippet_owners)
+
+    # ── Scheduled promotion ───────────────────────────────────────────────
+    scheduled_for: float = 0.0               # Unix time of a held promotion (0: none)
+    scheduled_by: str = ''                   # Who scheduled it
+
+    # ── Auto-promotion policy ─────────────────────────────────────────────
+    policy_decision: Dict[str, Any] = field(default_factory=dict)  # {promote, rule, reasons}
 
     # ── Rejection / rollback ──────────────────────────────────────────────
     rejection_reason: str = ''
@@ -172,6 +445,8 @@ class StagedSnippet:
     def to_dict(self) -> Dict[str, Any]:
         d = asdict(self)
         d['phase'] = self.phase.value
+        d['created'] = utc_timestamp(self.created_at)
+        d['promoted'] = utc_timestamp(self.promoted_at) if self.promoted_at else ''
         # Truncate large fields for API responses
         if len(d.get('spec_output', '')) > 5000:
             d['spec_output'] = d['spec_output'][:5000] + '\n…(truncated)'
@@ -179,6 +454,19 @@ class StagedSnippet:
             d['code'] = d['code'][:10000] + '\n…(truncated)'
         return d
 
+    def matches(self, meta: Optional[Dict[str, Optional[str]]] = None, query: str = '') -> bool:
+        """
+        True if the snippet carries ``meta`` (see meta_matches) and ``query``
+        occurs, case-insensitively, in its id, label, slot, author or meta.
+        """
+        if meta and not meta_matches(self.meta, meta):
+            return False
+        if not query:
+            return True
+        haystack = ' '.join([self.staging_id, self.label, self.reserved_address, self.author,
+                             *(f"{k}={v}" for k, v in self.meta.items())])
+        return query.lower() in haystack.lower()
+
 
 # ═══════════════════════════════════════════════════════════════════════════
 # AUDIT LOGGER — append-only forensic event trail
@@ -189,33 +477,91 @@ class AuditLogger:
 
     Every event — queue, reserve, spec-exec, verdict, promote, reject —
     gets an immutable line in the log. These are never modified or deleted.
+    Each line is numbered (``seq``), continuing from the last number in the
+    log, so events order correctly even when their timestamps tie.
+
+    Writes are group-committed: log() numbers its entry and queues it under
+    a short lock, then whichever caller holds the write lock appends every
+    queued line in one write.  Under contention one open + write serves many
+    events; log() still returns only once its own line is in the file.
     """
 
-    def __init__(self, log_path: str):
+    def __init__(self, log_path: str, read_only: bool = False):
         self._path = log_path
-        self._lock = threading.Lock()
-        # Ensure directory exists
-        os.makedirs(os.path.dirname(log_path) or '.', exist_ok=True)
+        self._lock = threading.Lock()           # seq + pending queue
+        self._write_lock = threading.Lock()     # the file
+        self._pending: List[str] = []
+        self._events = 0
+        self._batches = 0
+        # Read-only: the trail can be read, but log() drops new events
+        self._read_only = read_only
+        if not read_only:
+            # Ensure directory exists
+            os.makedirs(os.path.dirname(log_path) or '.', exist_ok=True)
+        self._seq = self._last_seq()
+
+    def _last_seq(self) -> int:
+        """Highest ``seq`` in the log (logs from before seq: their line count)."""
+        try:
+            with open(self._path, 'r', encoding='utf-8', errors='replace') as f:
+                lines = f.readlines()
+        except OSError:
+            return 0
+        last = len(lines)
+        for line in reversed(lines):
+            try:
+                return max(last, int(json.loads(line)['seq']))
+            except (ValueError, KeyError, TypeError):
+                continue
+        return last
+
+    @property
+    def seq(self) -> int:
+        """The number of the last event logged."""
+        return self._seq
 
     def log(self, event_type: AuditEventType, staging_id: str,
-            data: Optional[Dict[str, Any]] = None):
-        """Append a single audit event."""
+            data: Optional[Dict[str, Any]] = None) -> Dict[str, Any]:
+        """Append a single audit event; returns it (with its ``seq``)."""
+        now = time.time()
         entry = {
-            'timestamp': time.time(),
-            'iso_time': time.strftime('%Y-%m-%dT%H:%M:%S', time.gmtime()),
+            'timestamp': now,
+            'iso_time': utc_timestamp(now),
+            'seq': 0,
             'event': event_type.value,
             'staging_id': staging_id,
             'data': data or {},
         }
+        if self._read_only:
+            return entry
         with self._lock:
+            self._seq += 1
+            entry['seq'] = self._seq
+            self._pending.append(json.dumps(entry, default=str) + '\n')
+        self._flush()
+        return entry
+
+    def _flush(self):
+        with self._write_lock:
+            with self._lock:
+                batch, self._pending = self._pending, []
+            if not batch:
+                return                          # another caller's batch carried it
             with open(self._path, 'a', encoding='utf-8') as f:
-                f.write(json.dumps(entry, default=str) + '\n')
+                f.write(''.join(batch))
+            self._events += len(batch)
+            self._batches += 1
+
+    def stats(self) -> Dict[str, int]:
+        """Events written and the writes that carried them, since opening."""
+        with self._write_lock:
+            return {'events': self._events, 'batches': self._batches}
 
     def read_all(self, limit: int = 500) -> List[Dict]:
         """Read the last N audit entries (newest first)."""
         if not os.path.exists(self._path):
             return []
-        with self._lock:
+        with self._write_lock:
             with open(self._path, 'r', encoding='utf-8') as f:
                 lines = f.readlines()
         entries = []
@@ -233,6 +579,26 @@ class AuditLogger:
         all_entries = self.read_all(limit=10000)
         return [e for e in all_entries if e.get('staging_id') == staging_id]
 
+    def read_events(self, *event_types: AuditEventType) -> List[Dict]:
+        """Every entry of these types in the whole log, oldest first (one pass)."""
+        values = {t.value for t in event_types}
+        needles = [json.dumps(v) for v in values]
+        entries = []
+        try:
+            with self._write_lock, open(self._path, 'r', encoding='utf-8', errors='replace') as f:
+                for line in f:
+                    if not any(n in line for n in needles):
+                        continue
+                    try:
+                        entry = json.loads(line)
+                    except json.JSONDecodeError:
+                        continue
+                    if entry.get('event') in values:
+                        entries.append(entry)
+        except OSError:
+            pass
+        return entries
+
 
 # ═══════════════════════════════════════════════════════════════════════════
 # STAGING PIPELINE — The main engine
@@ -250,17 +616,65 @@ class StagingPipeline:
         - node_registry: NodeRegistry       — for slot reservation & commit
         - session_ledger: SessionLedger     — for creating synthetic nodes
         - snippets_dir: str                 — where promoted snippets are saved
+        - shard_width: int                  — code_hash hex digits per subdirectory (0: flat)
         - audit_log_path: str               — path to the JSONL audit file
+        - history_limit: int                — finished snippets kept in memory
+        - allow_override: bool              — may 'approve' force a FAILED snippet
+        - gates: List[PromotionGate]        — allow/deny checks after the dry-run
+        - failures_dir: str                 — where failure bundles are kept (None: off)
+        - trends: TrendIndex                — spec_time history + anomaly checks (None: off)
+        - policy: PromotionPolicy           — per-slot auto-promote rules (None: off)
+        - constraints: SlotConstraints      — what each slot accepts (None: anything)
+        - owners: SnippetOwners             — who must approve each slot (None: anyone)
+        - label_specs: LabelSpecs           — default spec per label (None: off)
+        - quotas: Quotas                    — per author / namespace staging limits (None: off)
+        - mode: StoreMode                   — READ_ONLY refuses every mutation
+        - id_mode: IdMode                   — random or content-derived staging_ids
+        - trash_dir: str                    — where deleted snippets go (None: no delete)
+        - trash_days: float                 — how long the trash keeps them (0: forever)
+        - scratch: bool                     — dry-run in a throwaway scratch dir (see scratch)
     """
 
     def __init__(self, executors: Dict, node_registry, session_ledger,
                  snippets_dir: str = 'web_interface/snippets',
-                 audit_log_path: str = 'web_interface/staging_audit.jsonl'):
+                 audit_log_path: str = 'web_interface/staging_audit.jsonl',
+                 history_limit: int = 1000,
+                 allow_override: bool = True,
+                 gates: Optional[List] = None,
+                 failures_dir: Optional[str] = None,
+                 trends=None,
+                 policy=None,
+                 constraints=None,
+                 owners=None,
+                 label_specs=None,
+                 quotas=None,
+                 mode: StoreMode = StoreMode.READ_WRITE,
+                 shard_width: int = DEFAULT_SHARD_WIDTH,
+                 id_mode: IdMode = IdMode.RANDOM,
+                 trash_dir: Optional[str] = None,
+                 trash_days: float = 30.0,
+                 scratch: bool = True):
         self._executors = executors
         self._registry = node_registry
         self._ledger = session_ledger
         self._snippets_dir = snippets_dir
-        self._audit = AuditLogger(audit_log_path)
+        if not 0 <= int(shard_width) <= 8:
+            raise ValueError(f"shard_width must be 0-8 hex digits, not {shard_width}")
+        # Promoted files go to <lang>/<code_hash[:shard_width]>/ (see promoted_dir)
+        self._shard_width = int(shard_width)
+        self._mode = StoreMode(mode)
+        try:
+            self._id_mode = IdMode(id_mode)
+        except ValueError:
+            raise SpokedError(ErrorKind.USAGE, f"id_mode must be one of "
+                              f"{', '.join(m.value for m in IdMode)}, not '{id_mode}'",
+                              id_mode=id_mode) from None
+        # Every staging_id the store has used (read from the audit log once,
+        # on the first deterministic id)
+        self._used_ids: Optional[set] = None
+        self._ulids = UlidGenerator()
+        self._id_collisions = 0
+        self._audit = AuditLogger(audit_log_path, read_only=self.read_only)
         self._lock = threading.RLock()
 
         # Active staging entries: staging_id → StagedSnippet
@@ -268,32 +682,100 @@ class StagingPipeline:
 
         # History of completed (promoted / rejected / rolled-back) snippets
         self._history: List[StagedSnippet] = []
+        self._history_limit = max(1, history_limit)
+        # False → 'approve' cannot force through a failed speculative run
+        self._allow_override = allow_override
+        # Promotion gates, run after a passing dry-run (see promotion_gates)
+        self._gates: List = list(gates or [])
+        # Failure bundles: <failures_dir>/<staging_id>/ (see failure_bundle)
+        self._failures_dir = failures_dir
+        # Deleted snippets: <trash_dir>/<staging_id>/ (see snippet_trash)
+        self._trash_dir = trash_dir
+        self._trash_days = float(trash_days)
+        # Each dry-run (each spec case) in its own scratch dir, writes outside it refused
+        self._scratch = scratch
+        # spec_time per (label, slot, engine) — see spec_trends
+        self._trends = trends
+        # Per-slot auto-promote rules for run_full_pipeline (see promotion_policy)
+        self._policy = policy
+        # Hard per-slot requirements, checked by promote() (see slot_constraints)
+        self._constraints = constraints
+        # Slot / label owners whose approval a staged snippet needs (see snippet_owners)
+        self._owners = owners
+        # Default spec per label, inherited by every snippet of it (see label_specs)
+        self._label_specs = label_specs
+        # Staged snippets and spec minutes per author / namespace (see snippet_quota)
+        self._quotas = quotas
+        if quotas is not None and quotas.max_spec_minutes:
+            quotas.load(self._audit)
+        # Slot → its Degradation while its snippet fails revalidation (see revalidation)
+        from .revalidation import load_degraded
+        self._degraded = load_degraded(self._audit)
+        # Event listeners (notifiers): fn(event, snippet, details)
+        self._listeners: List[Callable] = []
 
         # Reserved positions: engine_name → set of positions held
         self._reserved_positions: Dict[str, set] = {}
 
         # Ensure snippet directories exist
-        for lang in LANG_EXTENSIONS:
-            os.makedirs(os.path.join(self._snippets_dir, lang), exist_ok=True)
+        if not self.read_only:
+            for lang in LANG_EXTENSIONS:
+                os.makedirs(os.path.join(self._snippets_dir, lang), exist_ok=True)
+
+    @property
+    def mode(self) -> StoreMode:
+        return self._mode
+
+    @property
+    def read_only(self) -> bool:
+        return self._mode == StoreMode.READ_ONLY
+
+    @property
+    def id_mode(self) -> IdMode:
+        return self._id_mode
+
+    @property
+    def scratch(self) -> bool:
+        return self._scratch
+
+    def _require_writable(self, action: str):
+        if self.read_only:
+            raise ReadOnlyStoreError(f"The store is read-only: cannot {action}")
 
     # ─────────────────────────────────────────────────────────────────────
     # PHASE 1: QUEUE — receive snippet, reserve a slot
     # ─────────────────────────────────────────────────────────────────────
 
     def queue_snippet(self, engine_letter: str, language: str, code: str,
-                      label: str = '') -> StagedSnippet:
+                      label: str = '', author: str = '', spec=None,
+                      meta: Optional[Dict[str, Any]] = None,
+                      parent: str = '') -> StagedSnippet:
         """
         Accept a snippet into the staging pipeline.
 
         1. Generates a staging_id
-        2. Computes a SHA-256 hash of the code
+        2. Computes a SHA-256 hash of the code (and of ``spec``, a SpecSuite)
         3. Reserves the next free slot on the target engine row
         4. Returns the StagedSnippet in QUEUED phase
 
-        Raises ValueError if the engine row is full.
+        ``meta`` is custom key/value metadata (see normalize_meta).  ``spec``
+        is extended with the label's default spec, if it has one.
+        ``parent`` is the staging_id of the snippet this one was staged
+        from, if any (see lineage).
+        Raises ValueError if the engine row is full, ``meta`` is malformed
+        or the author or namespace is at a quota (see snippet_quota).
         """
+        self._require_writable('stage snippets')
+        meta = normalize_meta(meta)
+        spec = self.inherit_spec(label, spec)
+        from .offline import declared_network
+        from .promotion_impact import declared_imports
+        try:
+            network = declared_network(code)
+            declared_imports(code)
+        except ValueError as exc:
+            raise SpokedError(ErrorKind.USAGE, str(exc)) from None
         now = time.time()
-        staging_id = f"stg-{uuid.uuid4().hex[:12]}"
         code_hash = hashlib.sha256(code.encode('utf-8')).hexdigest()
         lang = language.lower().strip()
 
@@ -303,13 +785,20 @@ class StagingPipeline:
         if engine is None:
             engine = LANGUAGE_STRING_TO_ENGINE.get(lang)
         if engine is None:
-            raise ValueError(f"Unknown engine for letter='{engine_letter}' language='{lang}'")
+            raise SpokedError(ErrorKind.ENGINE_MISSING,
+                              f"Unknown engine for letter='{engine_letter}' language='{lang}'",
+                              engine_letter=engine_letter, language=lang)
 
         engine_name = engine.name
 
         # Reserve a slot position (don't actually commit yet)
         reserved_pos = self._reserve_position(engine_name)
         address = f"{engine_letter}{reserved_pos}"
+        try:
+            staging_id = self._new_staging_id(code_hash, label, address)
+        except SpokedError:
+            self._release_position(engine_name, reserved_pos)
+            raise
 
         snippet = StagedSnippet(
             staging_id=staging_id,
@@ -318,32 +807,90 @@ class StagingPipeline:
             label=label or f"snippet-{staging_id[:8]}",
             code=code,
             code_hash=code_hash,
+            author=author,
             phase=StagingPhase.QUEUED,
             created_at=now,
             updated_at=now,
             reserved_engine=engine_name,
             reserved_position=reserved_pos,
             reserved_address=address,
+            spec=spec.to_dict() if spec is not None else {},
+            spec_hash=spec.spec_hash if spec is not None else '',
+            meta=meta,
+            network=network,
+            parent=parent,
         )
 
         with self._lock:
+            try:
+                if self._quotas is not None:       # checked with the insert: bursts count too
+                    self._quotas.check(self._staged.values(), author, snippet.label)
+            except SpokedError:
+                self._release_position(engine_name, reserved_pos)
+                if self._used_ids is not None:     # a refused snippet does not use up its id
+                    self._used_ids.discard(staging_id)
+                raise
             self._staged[staging_id] = snippet
 
-        self._audit.log(AuditEventType.SNIPPET_QUEUED, staging_id, {
+        snippet.seq = self._audit.log(AuditEventType.SNIPPET_QUEUED, staging_id, {
             'language': lang,
             'engine_letter': engine_letter,
             'label': snippet.label,
+            'author': author,
             'code_hash': code_hash,
             'code_length': len(code),
-        })
+            'spec_hash': snippet.spec_hash,
+            'spec_cases': len(snippet.spec.get('cases', [])),
+            **({'spec_inherits': spec.inherits} if spec is not None and spec.inherits else {}),
+            'meta': meta,
+            **({'network': network} if network else {}),
+            **({'parent': parent} if parent else {}),
+        })['seq']
         self._audit.log(AuditEventType.SLOT_RESERVED, staging_id, {
             'engine': engine_name,
             'position': reserved_pos,
             'address': address,
         })
+        self._request_approval(snippet)
 
         return snippet
 
+    def _new_staging_id(self, code_hash: str, label: str, address: str) -> str:
+        """
+        A staging_id no snippet in this pipeline holds.  A random or ULID id
+        that collides anyway is drawn again (and counted, see
+        id_collisions); giving up after a few draws means the id source is
+        broken, not unlucky.
+        """
+        with self._lock:
+            used = set(self._staged) | {h.staging_id for h in self._history}
+            if self._id_mode == IdMode.DETERMINISTIC:
+                if self._used_ids is None:
+                    self._used_ids = {e.get('staging_id') for e in
+                                      self._audit.read_events(AuditEventType.SNIPPET_QUEUED)}
+                used |= self._used_ids
+                counter = 0
+                while deterministic_id(code_hash, label, address, counter) in used:
+                    counter += 1
+                staging_id = deterministic_id(code_hash, label, address, counter)
+                self._used_ids.add(staging_id)
+                return staging_id
+            for _attempt in range(8):
+                staging_id = (f"stg-{self._ulids.next()}" if self._id_mode == IdMode.ULID
+                              else f"stg-{uuid.uuid4().hex[:12]}")
+                if staging_id not in used:
+                    return staging_id
+                self._id_collisions += 1
+            raise SpokedError(ErrorKind.INVALID_STATE,
+                              f"could not draw an unused staging_id ({self._id_mode.value} "
+                              f"mode, {self._id_collisions} collision(s))",
+                              id_mode=self._id_mode.value)
+
+    @property
+    def id_collisions(self) -> int:
+        """How many freshly drawn staging_ids were already in use."""
+        return self._id_collisions
+
     def _reserve_position(self, engine_name: str) -> int:
         """
         Find and reserve the next free slot position on an engine row.
@@ -353,7 +900,8 @@ class StagingPipeline:
         with self._lock:
             row = self._registry.get_engine_row(engine_name)
             if row is None:
-                raise ValueError(f"Engine row '{engine_name}' not found")
+                raise SpokedError(ErrorKind.ENGINE_MISSING,
+                                  f"Engine row '{engine_name}' not found", engine=engine_name)
 
             reserved = self._reserved_positions.get(engine_name, set())
 
@@ -368,10 +916,13 @@ class StagingPipeline:
                 self._reserved_positions.setdefault(engine_name, set()).add(pos)
                 return pos
 
-            raise ValueError(
+            raise SpokedError(
+                ErrorKind.SLOT_FULL,
                 f"Engine '{engine_name}' has no free slots "
                 f"(max={row.max_slots}, occupied={len(row.occupied_positions())}, "
-                f"reserved={len(reserved)})"
+                f"reserved={len(reserved)})",
+                engine=engine_name, max_slots=row.max_slots,
+                occupied=len(row.occupied_positions()), reserved=len(reserved),
             )
 
     def _release_position(self, engine_name: str, position: int):
@@ -384,36 +935,72 @@ class StagingPipeline:
     # PHASE 2: SPECULATIVE EXECUTION — isolated dry-run
     # ─────────────────────────────────────────────────────────────────────
 
-    def speculate(self, staging_id: str) -> StagedSnippet:
+    def speculate(self, staging_id: str,
+                  on_event: Optional[Callable[[Dict[str, Any]], None]] = None) -> StagedSnippet:
         """
         Run the snippet in an ISOLATED executor (not the production one).
 
         For Python: creates a fresh PythonExecutor with an empty namespace.
         For subprocess languages: they're already isolated by design.
 
+        ``on_event`` receives progress events as they happen — phase
+        markers and live compiler / program output (see output_stream).
+        With a failures_dir, a failed run leaves a failure bundle there.
+
         Returns the snippet with spec_* fields populated.
         """
+        self._require_writable('speculate')
+        from . import output_stream
+        transcript = None
+        if self._failures_dir:
+            transcript = output_stream.Transcript(forward=on_event)
+        with output_stream.streaming(transcript or on_event):
+            return self._speculate(staging_id, transcript)
+
+    def _speculate(self, staging_id: str, transcript=None) -> StagedSnippet:
+        from .output_stream import emit
         with self._lock:
             snippet = self._staged.get(staging_id)
             if snippet is None:
-                raise ValueError(f"No staged snippet with id '{staging_id}'")
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet with id '{staging_id}'",
+                                  staging_id=staging_id)
             if snippet.phase not in (StagingPhase.QUEUED, StagingPhase.FAILED):
-                raise ValueError(
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
                     f"Snippet {staging_id} is in phase '{snippet.phase.value}', "
-                    f"cannot speculate (must be QUEUED or FAILED)"
+                    f"cannot speculate (must be QUEUED or FAILED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
                 )
             snippet.phase = StagingPhase.SPECULATING
             snippet.updated_at = time.time()
             snippet.spec_started_at = time.time()
-
+            snippet.gate_results = []
+            snippet.metrics = {}
+            snippet.spec_cases = []
+            snippet.sandbox_violations = []
+            snippet.spec_denied = []
+            snippet.network_not_enforced = []
+
+        from .accounting import Meter
+        from .offline import network_for, spec_network
+        from .seccomp import spec_result
+        network = network_for(snippet.language, snippet.network)
+        meter = Meter()
+        emit('spec_started', staging_id=staging_id, language=snippet.language,
+             cases=len(snippet.spec.get('cases', [])))
         self._audit.log(AuditEventType.SPEC_EXEC_STARTED, staging_id, {
             'language': snippet.language,
             'code_hash': snippet.code_hash,
             'reserved_address': snippet.reserved_address,
+            'network': network,
         })
 
         try:
-            result = self._run_isolated(snippet.language, snippet.code)
+            with spec_network(network) as scope, meter:
+                if snippet.spec:
+                    result = self._run_spec_suite(snippet)
+                else:
+                    result = self._run_scratch(snippet.language, snippet.code)
 
             with self._lock:
                 snippet.spec_output = result.get('output', '')
@@ -421,24 +1008,38 @@ class StagingPipeline:
                 snippet.spec_execution_time = result.get('execution_time', 0.0)
                 snippet.spec_success = result.get('success', False)
                 snippet.spec_variables = result.get('variables', {})
+                snippet.spec_cases = result.get('cases', [])
+                snippet.sandbox_violations = result.get('violations', [])
+                snippet.spec_denied = result.get('denied', [])
+                snippet.network_not_enforced = list(scope.not_enforced)
                 snippet.spec_completed_at = time.time()
                 snippet.updated_at = time.time()
-
-                if snippet.spec_success:
-                    snippet.phase = StagingPhase.PASSED
-                    self._audit.log(AuditEventType.SPEC_EXEC_COMPLETED, staging_id, {
-                        'success': True,
-                        'execution_time': snippet.spec_execution_time,
-                        'output_length': len(snippet.spec_output),
-                        'variables_count': len(snippet.spec_variables),
-                    })
-                else:
-                    snippet.phase = StagingPhase.FAILED
-                    self._audit.log(AuditEventType.SPEC_EXEC_FAILED, staging_id, {
-                        'success': False,
-                        'error': snippet.spec_error[:2000],
-                        'execution_time': snippet.spec_execution_time,
-                    })
+                snippet.phase = StagingPhase.PASSED if snippet.spec_success else StagingPhase.FAILED
+
+            # Logged outside the pipeline lock: other jobs need not wait on the write.
+            if snippet.spec_success:
+                self._audit.log(AuditEventType.SPEC_EXEC_COMPLETED, staging_id, {
+                    'success': True,
+                    'execution_time': snippet.spec_execution_time,
+                    'output_length': len(snippet.spec_output),
+                    'variables_count': len(snippet.spec_variables),
+                    **self._case_summary(snippet),
+                    **self._network_summary(snippet, network),
+                    'resources': meter.summary(),
+                })
+            else:
+                self._audit.log(AuditEventType.SPEC_EXEC_FAILED, staging_id, {
+                    'success': False,
+                    'error': snippet.spec_error[:2000],
+                    'execution_time': snippet.spec_execution_time,
+                    **self._case_summary(snippet),
+                    **({'violations': snippet.sandbox_violations[:20]}
+                       if snippet.sandbox_violations else {}),
+                    **({'spec_result': spec_result(False, snippet.spec_denied),
+                        'denied': snippet.spec_denied} if snippet.spec_denied else {}),
+                    **self._network_summary(snippet, network),
+                    'resources': meter.summary(),
+                })
 
         except Exception as exc:
             with self._lock:
@@ -452,10 +1053,494 @@ class StagingPipeline:
                 'success': False,
                 'error': str(exc),
                 'traceback': traceback.format_exc()[:3000],
+                'resources': meter.summary(),
             })
 
+        if self._quotas is not None:
+            self._quotas.record(snippet.author, snippet.label,
+                                snippet.spec_completed_at - snippet.spec_started_at,
+                                snippet.spec_started_at)
+        if snippet.phase == StagingPhase.PASSED and self._gates:
+            with spec_network(network):             # gates that re-run the snippet, too
+                self._run_gates(snippet)
+        if self._trends is not None:
+            self._record_trend(snippet)
+        if snippet.phase == StagingPhase.FAILED:
+            if transcript is not None:
+                self._record_failure(snippet, transcript.records())
+            self._emit('spec_failure', snippet, reason=snippet.spec_error)
+
+        emit('spec_finished', staging_id=staging_id, success=snippet.phase == StagingPhase.PASSED,
+             phase=snippet.phase.value, spec_time=snippet.spec_execution_time,
+             error=snippet.spec_error[:2000])
         return snippet
 
+    # ─────────────────────────────────────────────────────────────────────
+    # PHASE 2b: PROMOTION GATES — plugin allow/deny checks
+    # ─────────────────────────────────────────────────────────────────────
+
+    @property
+    def gates(self) -> List:
+        return list(self._gates)
+
+    @property
+    def constraints(self):
+        """The slot constraints (None: every slot accepts any snippet)."""
+        return self._constraints
+
+    @property
+    def owners(self):
+        """The owners file (None: any reviewer may approve any snippet)."""
+        return self._owners
+
+    @property
+    def policy(self):
+        """The auto-promotion policy (None: [gates] auto_promote alone decides)."""
+        return self._policy
+
+    def set_gates(self, gates: List):
+        """Replace the gate list (e.g. after reloading the plugins dir)."""
+        with self._lock:
+            self._gates = list(gates)
+
+    def _run_gates(self, snippet: StagedSnippet, gates: Optional[List] = None):
+        """Evaluate every gate; any deny turns a PASSED snippet FAILED.
+
+        With ``gates`` (an on-demand run) the decisions are appended to the
+        snippet's existing gate results instead of replacing them.
+        """
+        from .output_stream import emit
+        from .promotion_gates import evaluate_gates, snippet_header
+
+        decisions = evaluate_gates(self._gates if gates is None else gates,
+                                   snippet_header(snippet), snippet.code)
+        denied = [d for d in decisions if not d.allow]
+        for d in decisions:
+            emit('gate', gate=d.gate, allow=d.allow, reasons=d.reasons)
+            self._audit.log(
+                AuditEventType.GATE_PASSED if d.allow else AuditEventType.GATE_DENIED,
+                snippet.staging_id,
+                {'gate': d.gate, 'reasons': d.reasons, 'duration': d.duration},
+            )
+        with self._lock:
+            results = [d.to_dict() for d in decisions]
+            snippet.gate_results = results if gates is None else snippet.gate_results + results
+            for d in decisions:
+                snippet.metrics.update(d.metrics)
+            if denied:
+                snippet.phase = StagingPhase.FAILED
+                snippet.spec_error = '\n'.join(
+                    f"Gate '{d.gate}' denied: {'; '.join(d.reasons) or 'no reason given'}"
+                    for d in denied
+                )
+                snippet.updated_at = time.time()
+        return decisions
+
+    def run_gate(self, staging_id: str, gate) -> StagedSnippet:
+        """Run one extra gate on a PASSED snippet (``promote --gate fuzz``)."""
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase != StagingPhase.PASSED:
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot run gate '{gate.name}' on snippet in phase "
+                    f"'{snippet.phase.value}' (must be PASSED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+        self._run_gates(snippet, [gate])
+        if snippet.phase == StagingPhase.FAILED:
+            if self._failures_dir:
+                self._record_failure(snippet, [])
+            self._emit('spec_failure', snippet, reason=snippet.spec_error)
+        return snippet
+
+    def mutate(self, staging_id: str, limit: int = 40,
+               budget: Optional[float] = None) -> Dict[str, Any]:
+        """Mutation-test a speculated snippet's spec (``spokedpy spec mutate``)."""
+        from .spec_mutation import isolated_runner, run_mutation
+
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase not in (StagingPhase.PASSED, StagingPhase.FAILED):
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot mutation-test snippet in phase '{snippet.phase.value}' "
+                    f"(must be PASSED or FAILED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+        def evaluate(language: str, code: str, timeout: Optional[float] = None):
+            return self.evaluate(language, code, timeout, snippet.author, snippet.label)
+
+        report = run_mutation(staging_id, snippet.language, snippet.code,
+                              isolated_runner(evaluate), limit, budget)
+        self._audit.log(AuditEventType.MUTATION_TESTED, staging_id, {
+            'run': report.run, 'killed': report.killed,
+            'kill_rate': round(report.kill_rate, 4), 'deterministic': report.deterministic,
+        })
+        return report.to_dict()
+
+    def compare(self, a_id: str, b_id: str, spec=None, label: str = '',
+                repeat: int = 1) -> Dict[str, Any]:
+        """
+        Run two candidates on the same spec cases (``spokedpy compare``).
+
+        Either may be staged or in history (e.g. the promoted incumbent).
+        Without ``spec`` the cases come from A's spec suite, else B's.  With
+        ``label`` both must carry it, so an A/B run never compares unrelated
+        snippets by a mistyped id.
+        """
+        from .offline import network_for
+        from .spec_compare import compare
+        from .spec_suite import SpecSuite
+
+        snippets = []
+        for staging_id in (a_id, b_id):
+            snippet = self.get_snippet(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if label and snippet.label != label:
+                raise SpokedError(ErrorKind.USAGE,
+                                  f"{staging_id} is labelled '{snippet.label}', not '{label}'",
+                                  staging_id=staging_id, label=snippet.label, expected=label)
+            snippets.append(snippet)
+        a, b = snippets
+        if a.staging_id == b.staging_id:
+            raise SpokedError(ErrorKind.USAGE, 'Compare needs two different snippets',
+                              staging_id=a.staging_id)
+        if a.language != b.language:
+            raise SpokedError(ErrorKind.USAGE, f"Cannot compare {a.language} with {b.language}",
+                              languages=[a.language, b.language])
+        if spec is None:
+            spec = SpecSuite.from_dict(a.spec or b.spec) if (a.spec or b.spec) else None
+        if spec is None or not spec.cases:
+            raise SpokedError(ErrorKind.USAGE, 'No spec cases to compare on (pass a cases '
+                              'file, or stage one of the snippets with a spec)',
+                              a=a.staging_id, b=b.staging_id)
+        report = compare(spec, a, b, self._run_isolated, label, repeat, sandbox=self._scratch,
+                         dry_run=lambda s: self._dry_run(network_for(s.language, s.network),
+                                                         s.author, s.label)).to_dict()
+        for side, snippet, other in (('a', a, b), ('b', b, a)):
+            self._audit.log(AuditEventType.COMPARED, snippet.staging_id, {
+                'against': other.staging_id, 'side': side,
+                'passed': report['totals'][side]['passed'], 'cases': len(report['cases']),
+                'duration': round(report['totals'][side]['duration'], 6),
+                'peak_kb': report['totals'][side]['peak_kb'],
+                'winner': report['recommendation']['winner'] == side,
+            })
+        return report
+
+    def respec(self, engine: str = '', staging_ids: Optional[List[str]] = None,
+               workers: int = 4, quarantine: bool = False) -> Dict[str, Any]:
+        """
+        Re-run the spec of every slot's current promoted snippet (``spokedpy
+        respec``), e.g. after a toolchain upgrade.  ``engine`` limits it to
+        one engine, ``staging_ids`` to those snippets.  With ``quarantine``
+        the snippets that no longer pass are rolled back; without it their
+        slots are marked DEGRADED until they pass again (see revalidation).
+        """
+        self._require_writable('re-spec promoted snippets')
+        from .respec import respec
+        from .slot_constraints import engine_language
+
+        language = engine_language(engine) if engine else ''
+        snippets = [s for s in self.current_promoted().values()
+                    if not language or s.language == language]
+        if staging_ids:
+            known = {s.staging_id for s in snippets}
+            missing = [i for i in staging_ids if i not in known]
+            if missing:
+                raise SpokedError(ErrorKind.INVALID_STATE,
+                                  f"Not the current promoted snippet of its slot"
+                                  f"{' on ' + language if language else ''}: {', '.join(missing)}",
+                                  staging_ids=missing)
+            snippets = [s for s in snippets if s.staging_id in staging_ids]
+
+        from .accounting import Meter
+        from .offline import network_for, spec_network
+
+        def run(snippet: StagedSnippet) -> Dict[str, Any]:
+            with spec_network(network_for(snippet.language, snippet.network)), Meter() as meter:
+                if snippet.spec:
+                    result = self._run_spec_suite(snippet)
+                else:
+                    result = self._run_scratch(snippet.language, snippet.code)
+            return {**result, 'resources': meter.summary()}
+
+        report = respec(snippets, run, workers, language)
+        by_id = {s.staging_id: s for s in snippets}
+        for result in report.results:
+            toolchain = report.toolchains.get(result.language, '')
+            if not result.passed and quarantine:
+                self.rollback(result.staging_id, f"Quarantined: spec fails under "
+                                                 f"{toolchain or result.language} — "
+                                                 f"{result.error[:300]}")
+                result.quarantined = True
+            elif not result.passed:
+                self._degrade(by_id[result.staging_id], result.error, toolchain)
+                result.degraded = True
+            else:
+                self._recover(by_id[result.staging_id], 'passed revalidation')
+            self._audit.log(AuditEventType.RESPEC, result.staging_id, {
+                'passed': result.passed,
+                'spec_time': result.spec_time,
+                'previous_spec_time': result.previous_spec_time,
+                'toolchain': toolchain,
+                'error': result.error[:2000],
+                'quarantined': result.quarantined,
+                'degraded': result.degraded,
+                'resources': result.resources,
+            })
+        return report.to_dict()
+
+    def revalidate(self, workers: int = 4) -> Dict[str, Any]:
+        """The ``[revalidate]`` run: respec() every slot; failing ones become DEGRADED."""
+        return self.respec(workers=workers)
+
+    def degraded(self) -> Dict[str, Any]:
+        """Slot → Degradation of every slot whose current snippet fails revalidation."""
+        current = self.current_promoted()
+        with self._lock:
+            return {slot: d for slot, d in sorted(self._degraded.items())
+                    if slot in current and current[slot].code_hash == d.code_hash}
+
+    def _degraded_imports(self, snippet: StagedSnippet) -> List:
+        """The Degradations of the slots ``snippet`` imports (its own slot aside)."""
+        if not self._degraded:
+            return []
+        from .promotion_impact import declared_imports
+        try:
+            imports = declared_imports(snippet.code)
+        except ValueError:
+            return []
+        degraded = self.degraded()
+        return [degraded[a] for a in imports if a in degraded and a != snippet.reserved_address]
+
+    def _degrade(self, snippet: StagedSnippet, error: str, toolchain: str = ''):
+        """Mark the snippet's slot DEGRADED — audited and its owners notified once."""
+        from .revalidation import Degradation, failure_reason
+        slot = snippet.reserved_address
+        with self._lock:
+            known = self._degraded.get(slot)
+            if known is not None and known.code_hash == snippet.code_hash:
+                snippet.degraded = known.to_dict()
+                return
+            record = self._degraded[slot] = Degradation(
+                slot, snippet.staging_id, snippet.code_hash, snippet.label,
+                failure_reason(error), toolchain, time.time())
+            snippet.degraded = record.to_dict()
+        self._audit.log(AuditEventType.SLOT_DEGRADED, snippet.staging_id, record.to_dict())
+        owners = self._owners.match(slot, snippet.label)[0] if self._owners is not None else []
+        self._emit('degraded', snippet, owners=owners,
+                   reason=f"{record.reason} (under {toolchain or snippet.language})")
+
+    def _recover(self, snippet: StagedSnippet, reason: str):
+        """Clear the snippet's slot of its DEGRADED state, if it has one."""
+        slot = snippet.reserved_address
+        with self._lock:
+            record = self._degraded.pop(slot, None)
+            if record is None:
+                return
+            for h in self._history:
+                if h.reserved_address == slot:
+                    h.degraded = {}
+            snippet.degraded = {}
+        self._audit.log(AuditEventType.SLOT_RECOVERED, snippet.staging_id, {
+            'slot': slot,
+            'reason': reason,
+            'degraded_staging_id': record.staging_id,
+            'degraded_since': record.since,
+        })
+
+    def _settle_degraded(self, snippet: StagedSnippet):
+        """A snippet now holds its slot: still DEGRADED if it is the same code, else recovered."""
+        with self._lock:
+            record = self._degraded.get(snippet.reserved_address)
+        if record is None:
+            return
+        if record.code_hash == snippet.code_hash:
+            snippet.degraded = record.to_dict()
+        else:
+            self._recover(snippet, f"replaced by {snippet.staging_id}")
+
+    def evaluate(self, language: str, code: str, timeout: Optional[float] = None,
+                 author: str = '', label: str = '') -> Dict[str, Any]:
+        """
+        Run code in the speculation sandbox without staging it (REPL,
+        mutants): in a scratch dir, under the seccomp profile and the
+        network level the code declares (or its engine's), as a dry-run.
+        With ``timeout``, Python runs as its own process, killed after it.
+        Its time counts towards the spec quota of ``author`` and ``label``.
+        """
+        from .offline import declared_network, network_for
+        try:
+            network = network_for(language.lower().strip(), declared_network(code))
+        except ValueError as exc:
+            raise SpokedError(ErrorKind.USAGE, str(exc)) from None
+        with self._dry_run(network, author, label) as scope:
+            result = self._run_scratch(language, code, timeout)
+        if scope.not_enforced:
+            result = {**result, 'network_not_enforced': scope.not_enforced}
+        return result
+
+    @property
+    def label_specs(self):
+        return self._label_specs
+
+    @property
+    def quotas(self):
+        return self._quotas
+
+    def inherit_spec(self, label: str, spec):
+        """``spec`` extended with ``label``'s default spec (see label_specs); a usage error if that clashes."""
+        if self._label_specs is None or not label:
+            return spec
+        try:
+            return self._label_specs.apply(label, spec)
+        except ValueError as exc:
+            raise SpokedError(ErrorKind.USAGE, str(exc), label=label) from None
+
+    def run_spec(self, language: str, spec, code: str,
+                 on_event: Optional[Callable[[Dict[str, Any]], None]] = None,
+                 label: str = '') -> List[Dict[str, Any]]:
+        """Run a SpecSuite against code without staging it (``spokedpy spec run``).
+
+        ``on_event`` receives the same progress events as speculate(), and
+        the run gets the network level the code declares (or its engine's).
+        With ``label`` the label's default spec runs too, as on staging
+        (``spec`` may then be None).
+        """
+        spec = self.inherit_spec(label, spec)
+        if spec is None:
+            raise SpokedError(ErrorKind.USAGE, f"no spec to run: no sidecar, and label '{label}' "
+                                               f"has no default spec", label=label)
+        from . import output_stream
+        from .offline import declared_network, network_for
+        from .spec_suite import run_suite
+        language = language.lower().strip()
+        try:
+            network = network_for(language, declared_network(code))
+        except ValueError as exc:
+            raise SpokedError(ErrorKind.USAGE, str(exc)) from None
+        with output_stream.streaming(on_event), self._dry_run(network, label=label):
+            output_stream.emit('spec_started', language=language, cases=len(spec.cases))
+            results = run_suite(spec, language, code, self._run_isolated,
+                                sandbox=self._scratch)
+            output_stream.emit('spec_finished', success=all(r.passed for r in results),
+                               spec_time=sum(r.duration for r in results))
+        return [asdict(r) for r in results]
+
+    @contextmanager
+    def _dry_run(self, network: str, author: str = '', label: str = ''):
+        """
+        A dry-run outside speculate() (evaluate, run_spec, compare, mutants):
+        this thread at ``network`` (yields its offline.spec_network), its
+        wall time counted towards the spec quota of ``author`` and ``label``.
+        """
+        from .offline import spec_network
+        started = time.time()
+        try:
+            with spec_network(network) as scope:
+                yield scope
+        finally:
+            if self._quotas is not None:
+                self._quotas.record(author, label, time.time() - started, started)
+
+    def _run_spec_suite(self, snippet: StagedSnippet) -> Dict[str, Any]:
+        """Run every case of the snippet's spec suite; the dry-run passes only if all do."""
+        from .spec_suite import SpecSuite, run_suite
+
+        results = run_suite(SpecSuite.from_dict(snippet.spec), snippet.language,
+                            snippet.code, self._run_isolated, sandbox=self._scratch)
+        failed = [r for r in results if not r.passed]
+        output = ''.join(f"── {r.name}: {'PASS' if r.passed else 'FAIL'} ──\n{r.output}"
+                         + ('' if not r.output or r.output.endswith('\n') else '\n')
+                         for r in results)
+        error = ''
+        if failed:
+            error = f"{len(failed)}/{len(results)} spec case(s) failed: " + \
+                    '; '.join(f"{r.name}: {r.error}" for r in failed)
+        return {
+            'success': not failed,
+            'output': output,
+            'error': error,
+            'execution_time': sum(r.duration for r in results),
+            'variables': {},
+            'cases': [r.to_dict() for r in results],
+            'violations': [v for r in results for v in r.violations],
+            'denied': list(dict.fromkeys(s for r in results for s in r.denied)),
+        }
+
+    @staticmethod
+    def _case_summary(snippet: StagedSnippet) -> Dict[str, Any]:
+        """Per-case outcome (without output) for the audit trail."""
+        if not snippet.spec_cases:
+            return {}
+        return {'cases': [{k: c[k] for k in ('name', 'passed', 'duration', 'first_diff')}
+                          for c in snippet.spec_cases]}
+
+    @staticmethod
+    def _network_summary(snippet: StagedSnippet, network: str) -> Dict[str, Any]:
+        """The spawns that ran past the network policy, for the audit trail."""
+        if not snippet.network_not_enforced:
+            return {}
+        return {'network_not_enforced': snippet.network_not_enforced,
+                'warning': f"network not enforced: {', '.join(snippet.network_not_enforced)} "
+                           f"ran with the host's network under policy '{network}'"}
+
+    def _run_scratch(self, language: str, code: str,
+                     timeout: Optional[float] = None) -> Dict[str, Any]:
+        """
+        _run_isolated (_run_child with a ``timeout``, for Python) in a
+        scratch dir of its own (see scratch), its engine spawns under the
+        seccomp profile; a violation or a denied syscall fails the run.
+        """
+        from .seccomp import Denials
+
+        def run() -> Dict[str, Any]:
+            if timeout is not None and language.lower().strip() == 'python':
+                return self._run_child(code, timeout)
+            return self._run_isolated(language, code)
+
+        if not self._scratch:
+            with Denials() as denials:
+                return self._denied(run(), denials.syscalls)
+        from .scratch import Scratch, describe
+        with Scratch() as box, Denials() as denials:
+            result = run()
+        result = self._denied(result, denials.syscalls)
+        if not box.violations:
+            return result
+        error = result.get('error', '')
+        unmentioned = [v for v in box.violations if v['reason'] not in error]
+        if unmentioned:
+            error = '\n'.join(filter(None, [error, 'sandbox: ' + describe(unmentioned)]))
+        return {**result, 'success': False, 'error': error, 'violations': box.violations}
+
+    @staticmethod
+    def _denied(result: Dict[str, Any], syscalls: List[str]) -> Dict[str, Any]:
+        if not syscalls:
+            return result
+        error = '\n'.join(filter(None, [result.get('error', ''),
+                                        'seccomp: denied ' + ', '.join(syscalls)]))
+        return {**result, 'success': False, 'error': error, 'denied': syscalls}
+
+    @staticmethod
+    def _run_child(code: str, timeout: float) -> Dict[str, Any]:
+        """Python as its own ``python -I`` process: killable, write-guarded in scratch."""
+        from .spec_suite import SpecCase, run_python_case
+        started = time.time()
+        result = run_python_case(code, SpecCase('run', timeout=timeout))
+        error = result.get('error', '')
+        return {**result, 'error': error.splitlines()[-1] if error else '',
+                'execution_time': time.time() - started, 'variables': {}}
+
     def _run_isolated(self, language: str, code: str) -> Dict[str, Any]:
         """
         Execute code in an ISOLATED environment.
@@ -473,8 +1558,20 @@ class StagingPipeline:
         if lang == 'python':
             # Create a disposable executor with a clean namespace
             from .execution_engine import PythonExecutor
+            from .output_stream import emit
+            from .accounting import record
             sandbox = PythonExecutor()
+            emit('process_started', process='python', phase='run')
+            cpu = time.thread_time()
             result = sandbox.execute(code)
+            # In-process: wall and CPU time of this thread; memory is the server's
+            record('run', 'python', result.execution_time, cpu=time.thread_time() - cpu,
+                   exit_code=0 if result.success else 1)
+            # In-process: the output arrives in one piece once exec() returns.
+            if result.output:
+                emit('output', process='python', stream='stdout', text=result.output)
+            emit('process_finished', process='python', phase='run',
+                 exit_code=0 if result.success else 1, duration=result.execution_time)
             variables = {}
             if result.variables:
                 for k, v in result.variables.items():
@@ -494,20 +1591,26 @@ class StagingPipeline:
             }
         else:
             # Subprocess-based: already isolated
+            from .engine_doctor import find_toolchain, toolchain_hint
             executor = self._executors.get(lang)
             if executor is None:
                 return {
                     'success': False,
                     'output': '',
-                    'error': f'No executor for language "{lang}"',
+                    'error': f'No executor for language "{lang}" — {toolchain_hint(lang)}',
                     'execution_time': 0,
                     'variables': {},
                 }
             result = executor.execute(code)
+            error = str(result.error) if result.error else ''
+            # A missing toolchain otherwise reads like a spawn failure —
+            # attach the concrete install step.
+            if not result.success and find_toolchain(lang) is None:
+                error = f'{error}\n{toolchain_hint(lang)}'.strip()
             return {
                 'success': result.success,
                 'output': result.output or '',
-                'error': str(result.error) if result.error else '',
+                'error': error,
                 'execution_time': result.execution_time,
                 'variables': {},
             }
@@ -523,16 +1626,19 @@ class StagingPipeline:
 
         action:
             'auto'    — use the speculative result (pass → promote, fail → reject)
-            'approve' — force-approve (human override, even if spec failed)
+            'approve' — force-approve (human override, even if spec failed,
+                        unless the pipeline disallows overrides)
             'reject'  — force-reject (human override, even if spec passed)
             'hold'    — mark for manual review (no auto-action)
 
         Returns the updated snippet.
         """
+        self._require_writable('record verdicts')
         with self._lock:
             snippet = self._staged.get(staging_id)
             if snippet is None:
-                raise ValueError(f"No staged snippet '{staging_id}'")
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
 
             if action == 'auto':
                 if snippet.phase == StagingPhase.PASSED:
@@ -555,12 +1661,21 @@ class StagingPipeline:
                     self._archive_snippet(snippet)
                     return snippet
                 else:
-                    raise ValueError(
+                    raise SpokedError(
+                        ErrorKind.INVALID_STATE,
                         f"Cannot auto-verdict snippet in phase '{snippet.phase.value}' "
-                        f"(must be PASSED or FAILED)"
+                        f"(must be PASSED or FAILED)",
+                        staging_id=staging_id, phase=snippet.phase.value,
                     )
 
             elif action == 'approve':
+                if snippet.phase == StagingPhase.FAILED and not self._allow_override:
+                    raise SpokedError(
+                        ErrorKind.GATE_DENIED,
+                        f"Snippet '{staging_id}' failed speculation or a promotion gate and "
+                        f"gate overrides are disabled ([gates] allow_override = false)",
+                        staging_id=staging_id,
+                    )
                 snippet.phase = StagingPhase.PASSED
                 snippet.updated_at = time.time()
                 self._audit.log(AuditEventType.VERDICT_PASS, staging_id, {
@@ -589,41 +1704,71 @@ class StagingPipeline:
                 return snippet
 
             else:
-                raise ValueError(f"Unknown verdict action: '{action}'")
+                raise SpokedError(ErrorKind.USAGE, f"Unknown verdict action: '{action}'",
+                                  action=action)
 
     # ─────────────────────────────────────────────────────────────────────
     # PHASE 4: PROMOTE — write to disk, ledger, registry
     # ─────────────────────────────────────────────────────────────────────
 
-    def promote(self, staging_id: str) -> StagedSnippet:
+    def promote(self, staging_id: str, allow_degraded: bool = False,
+                approved_by: str = '') -> StagedSnippet:
         """
         Promote a PASSED snippet to production.
 
         Steps:
-            1. Write the code to `snippets/<lang>/<staging_id>.<ext>`
+            1. Write the code to `snippets/<lang>/<hh>/<staging_id>.<ext>`,
+               where <hh> is the code_hash prefix (see promoted_dir)
             2. Create a synthetic node in the SessionLedger
             3. Commit the node to the reserved slot in the NodeRegistry
             4. Log every step to the audit trail
 
         Returns the snippet in PROMOTED phase.
-        Raises ValueError if the snippet is not in PASSED phase.
+        Raises ValueError if the snippet is not in PASSED phase, a
+        GATE_DENIED SpokedError if its slot is owned and ``approved_by`` is
+        not one of its ``required_approvers``, and a SLOT_DEGRADED
+        SpokedError if it imports a degraded slot (unless
+        ``allow_degraded``: a snippet restored from the checkpoint).
         """
+        self._require_writable('promote')
         with self._lock:
             snippet = self._staged.get(staging_id)
             if snippet is None:
-                raise ValueError(f"No staged snippet '{staging_id}'")
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
             if snippet.phase != StagingPhase.PASSED:
-                raise ValueError(
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
                     f"Cannot promote snippet in phase '{snippet.phase.value}' "
-                    f"(must be PASSED)"
+                    f"(must be PASSED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
                 )
+            self._check_approver(snippet, approved_by)
+            violations = self._slot_violations(snippet)
+            if violations:
+                raise SpokedError(ErrorKind.SLOT_FROZEN,
+                                  f"Slot {snippet.reserved_address} does not accept "
+                                  f"{staging_id}: {'; '.join(violations)}",
+                                  staging_id=staging_id, slot=snippet.reserved_address,
+                                  violations=violations)
+            blocked = [] if allow_degraded else self._degraded_imports(snippet)
+            if blocked:
+                slots = [d.slot for d in blocked]
+                raise SpokedError(ErrorKind.SLOT_DEGRADED,
+                                  f"{staging_id} imports degraded slot(s) {', '.join(slots)} — "
+                                  f"{blocked[0].slot}: {blocked[0].reason}; fix or roll back "
+                                  f"its snippet first",
+                                  staging_id=staging_id, slots=slots,
+                                  degraded=[d.to_dict() for d in blocked])
             snippet.phase = StagingPhase.PROMOTING
             snippet.updated_at = time.time()
 
-        self._audit.log(AuditEventType.PROMOTION_STARTED, staging_id, {
+        started = self._audit.log(AuditEventType.PROMOTION_STARTED, staging_id, {
             'reserved_address': snippet.reserved_address,
             'code_hash': snippet.code_hash,
         })
+        # The header records when promotion started and its place in the order.
+        snippet.promoted_at, snippet.promoted_seq = started['timestamp'], started['seq']
 
         try:
             # ── Step 1: Write code to disk ────────────────────────────────
@@ -632,7 +1777,8 @@ class StagingPipeline:
             ts = time.strftime('%Y%m%dT%H%M%S', time.gmtime(time.time()))
             addr = snippet.reserved_address or 'x0'
             filename = f"{addr}_{snippet.staging_id}_{ts}{ext}"
-            lang_dir = os.path.join(self._snippets_dir, snippet.language)
+            lang_dir = promoted_dir(self._snippets_dir, snippet.language,
+                                    snippet.code_hash, self._shard_width)
             os.makedirs(lang_dir, exist_ok=True)
             file_path = os.path.join(lang_dir, filename)
 
@@ -640,6 +1786,19 @@ class StagingPipeline:
             header = self._make_file_header(snippet)
             with open(file_path, 'w', encoding='utf-8') as f:
                 f.write(header + snippet.code)
+            if snippet.spec.get('source'):
+                # The suite travels with the file as its <stem>.spec.* sidecar
+                # (plus the golden files of its snapshot cases); inherited
+                # cases stay with their label spec
+                from .spec_suite import write_snapshots
+                suffix = '.spec.yaml' if snippet.spec.get('format') == 'yaml' else '.spec.toml'
+                sidecar = os.path.splitext(file_path)[0] + suffix
+                with open(sidecar, 'w', encoding='utf-8') as f:
+                    f.write(snippet.spec['source'])
+                inherited = set(snippet.spec.get('inherits', {}).get('cases', []))
+                write_snapshots(sidecar, {c['name']: c['stdout'] for c in snippet.spec['cases']
+                                          if c.get('snapshot') and c.get('stdout') is not None
+                                          and c['name'] not in inherited})
 
             snippet.saved_file_path = file_path
             self._audit.log(AuditEventType.FILE_WRITTEN, staging_id, {
@@ -692,7 +1851,6 @@ class StagingPipeline:
             # ── Step 4: Mark promoted ─────────────────────────────────────
             with self._lock:
                 snippet.phase = StagingPhase.PROMOTED
-                snippet.promoted_at = time.time()
                 snippet.updated_at = time.time()
                 # Release the reservation (the real slot is now committed)
                 self._release_position(snippet.reserved_engine, snippet.reserved_position)
@@ -703,16 +1861,20 @@ class StagingPipeline:
                 'slot_id': snippet.registry_slot_id,
                 'address': snippet.reserved_address,
                 'promoted_at': snippet.promoted_at,
+                'promoted_seq': snippet.promoted_seq,
                 'total_staging_time': snippet.promoted_at - snippet.created_at,
             })
 
             self._archive_snippet(snippet)
+            self._settle_degraded(snippet)
+            self._emit('promotion', snippet)
             return snippet
 
         except Exception as exc:
             with self._lock:
                 snippet.phase = StagingPhase.FAILED
                 snippet.spec_error = f"Promotion failed: {exc}"
+                snippet.promoted_at, snippet.promoted_seq = 0.0, 0
                 snippet.updated_at = time.time()
             self._audit.log(AuditEventType.ERROR, staging_id, {
                 'step': 'promote',
@@ -723,6 +1885,7 @@ class StagingPipeline:
 
     def _make_file_header(self, snippet: StagedSnippet) -> str:
         """Generate a metadata header comment for saved snippet files."""
+        from .seccomp import spec_result
         lang = snippet.language
         # Pick comment style
         if lang in ('python', 'ruby', 'r', 'bash'):
@@ -736,16 +1899,33 @@ class StagingPipeline:
         lines = [
             f"{prefix} ═══════════════════════════════════════════════════════",
             f"{prefix}  VPyD Staged Snippet — PROMOTED TO PRODUCTION",
+            f"{prefix}  banner_version: {BANNER_VERSION}",
             f"{prefix}  staging_id:  {snippet.staging_id}",
             f"{prefix}  language:    {snippet.language}",
             f"{prefix}  engine:      {snippet.reserved_engine} ({snippet.engine_letter})",
             f"{prefix}  slot:        {snippet.reserved_address} (position {snippet.reserved_position})",
             f"{prefix}  label:       {snippet.label}",
-            f"{prefix}  code_hash:   {snippet.code_hash[:16]}…",
-            f"{prefix}  created:     {time.strftime('%Y-%m-%dT%H:%M:%SZ', time.gmtime(snippet.created_at))}",
-            f"{prefix}  promoted:    {time.strftime('%Y-%m-%dT%H:%M:%SZ', time.gmtime(time.time()))}",
+            *([f"{prefix}  parent:      {snippet.parent}"] if snippet.parent else []),
+            f"{prefix}  code_hash:   {snippet.code_hash}",
+            *([f"{prefix}  spec_hash:   {snippet.spec_hash}"] if snippet.spec_hash else []),
+            *([f"{prefix}  spec_inherits: {snippet.spec['inherits']['label']}"]
+              if snippet.spec.get('inherits') else []),
+            f"{prefix}  created:     {utc_timestamp(snippet.created_at)}",
+            f"{prefix}  promoted:    {utc_timestamp(snippet.promoted_at or None)}",
+            *([f"{prefix}  promoted_seq: {snippet.promoted_seq}"] if snippet.promoted_seq else []),
             f"{prefix}  spec_time:   {snippet.spec_execution_time:.4f}s",
-            f"{prefix}  spec_result: {'PASS' if snippet.spec_success else 'FAIL'}",
+            f"{prefix}  spec_result: {spec_result(snippet.spec_success, snippet.spec_denied)}",
+            *([f"{prefix}  network:     {snippet.network}"] if snippet.network else []),
+        ]
+        if snippet.spec_cases:
+            from .spec_suite import case_line
+            lines += [f"{prefix}  spec_case:   {case_line(c)}" for c in snippet.spec_cases]
+        if 'line_coverage' in snippet.metrics:
+            lines.append(f"{prefix}  coverage:    {snippet.metrics['line_coverage']:g}%")
+        if snippet.reviewed_by:
+            lines.append(f"{prefix}  approved_by: {snippet.reviewed_by}")
+        lines += [f"{prefix}  {META_PREFIX}{key}: {value}" for key, value in snippet.meta.items()]
+        lines += [
             f"{prefix} ═══════════════════════════════════════════════════════",
             f"",
         ]
@@ -814,6 +1994,7 @@ class StagingPipeline:
         3. Does NOT delete the saved file (forensics)
         4. Logs everything
         """
+        self._require_writable('roll back')
         with self._lock:
             # Check active staged first, then history
             snippet = self._staged.get(staging_id)
@@ -823,11 +2004,14 @@ class StagingPipeline:
                         snippet = h
                         break
             if snippet is None:
-                raise ValueError(f"No snippet with staging_id '{staging_id}'")
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No snippet with staging_id '{staging_id}'",
+                                  staging_id=staging_id)
             if snippet.phase != StagingPhase.PROMOTED:
-                raise ValueError(
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
                     f"Cannot rollback snippet in phase '{snippet.phase.value}' "
-                    f"(must be PROMOTED)"
+                    f"(must be PROMOTED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
                 )
 
         # Clear the registry slot
@@ -852,28 +2036,411 @@ class StagingPipeline:
             'was_promoted_at': snippet.promoted_at,
             'time_in_production': time.time() - snippet.promoted_at,
         })
+        degraded = self._degraded.get(snippet.reserved_address)
+        if degraded is not None and degraded.code_hash == snippet.code_hash:
+            self._recover(snippet, 'rolled back')
+        self._emit('rollback', snippet, reason=snippet.rejection_reason)
+
+        return snippet
+
+    # ─────────────────────────────────────────────────────────────────────
+    # TRASH — soft-delete, restore, purge
+    # ─────────────────────────────────────────────────────────────────────
+
+    def _require_trash(self, action: str) -> str:
+        self._require_writable(action)
+        if not self._trash_dir:
+            raise SpokedError(ErrorKind.USAGE, f"Cannot {action}: the pipeline has no trash_dir")
+        return self._trash_dir
+
+    def delete(self, staging_id: str, reason: str = '', deleted_by: str = '') -> Dict[str, Any]:
+        """
+        Move a snippet out of the pipeline into the trash, freeing the slot
+        it reserved; returns its tombstone.  A promoted snippet has to be
+        rolled back first, and one that is running cannot be deleted.
+        """
+        trash_dir = self._require_trash('delete snippets')
+        from .snippet_trash import write_entry
+        with self._lock:
+            snippet = self.get_snippet(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No snippet with staging_id '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase in (StagingPhase.PROMOTED, StagingPhase.SPECULATING,
+                                 StagingPhase.PROMOTING):
+                hint = 'roll it back first' if snippet.phase == StagingPhase.PROMOTED \
+                    else 'wait for it to finish'
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot delete snippet in phase '{snippet.phase.value}' ({hint})",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+            record = asdict(snippet)
+            record['phase'] = snippet.phase.value
+            tombstone = write_entry(trash_dir, record, reason, deleted_by)
+            if self._staged.pop(staging_id, None) is not None:
+                self._release_position(snippet.reserved_engine, snippet.reserved_position)
+            else:
+                self._history.remove(snippet)
+
+        self._audit.log(AuditEventType.SNIPPET_DELETED, staging_id, {
+            'reason': reason,
+            'deleted_by': deleted_by,
+            'phase': snippet.phase.value,
+            'address': snippet.reserved_address,
+            'trash': tombstone['path'],
+            'file': tombstone['file'],
+        })
+        return tombstone
+
+    def restore(self, staging_id: str) -> StagedSnippet:
+        """
+        Take a snippet back out of the trash.  A staged one reserves a slot
+        on its row again (the same one, if it is still free); a finished
+        one returns to the history, its promoted file to where it was.
+        """
+        trash_dir = self._require_trash('restore snippets')
+        from .snippet_trash import read_entry, remove_entry, restore_file
+        with self._lock:
+            if self.get_snippet(staging_id) is not None:
+                raise SpokedError(ErrorKind.INVALID_STATE,
+                                  f"'{staging_id}' is in the pipeline, not the trash",
+                                  staging_id=staging_id)
+            try:
+                tombstone = read_entry(trash_dir, staging_id)
+            except ValueError as exc:
+                raise SpokedError(ErrorKind.NOT_FOUND, str(exc), staging_id=staging_id) from None
+            record = dict(tombstone['snippet'])
+            record['phase'] = StagingPhase(record['phase'])
+            known = {f.name for f in dataclass_fields(StagedSnippet)}
+            snippet = StagedSnippet(**{k: v for k, v in record.items() if k in known})
+            staged = snippet.phase in (StagingPhase.QUEUED, StagingPhase.PASSED,
+                                       StagingPhase.FAILED)
+            if staged:
+                position = self._reserve_position(snippet.reserved_engine)
+                snippet.reserved_position = position
+                snippet.reserved_address = f"{snippet.engine_letter}{position}"
+            try:
+                restore_file(tombstone)
+            except ValueError as exc:
+                if staged:
+                    self._release_position(snippet.reserved_engine, snippet.reserved_position)
+                raise SpokedError(ErrorKind.INVALID_STATE, str(exc), staging_id=staging_id,
+                                  path=tombstone['file']) from None
+            snippet.updated_at = time.time()
+            if staged:
+                self._staged[staging_id] = snippet
+            else:
+                self._archive_snippet(snippet)
+            remove_entry(trash_dir, staging_id)
 
+        self._audit.log(AuditEventType.SNIPPET_RESTORED, staging_id, {
+            'phase': snippet.phase.value,
+            'address': snippet.reserved_address,
+            'deleted_at': tombstone.get('deleted_at'),
+        })
         return snippet
 
+    def trash(self) -> List[Dict[str, Any]]:
+        """The tombstones in the trash, most recently deleted first."""
+        from .snippet_trash import list_entries
+        return list_entries(self._trash_dir) if self._trash_dir else []
+
+    def purge_trash(self, staging_ids: Optional[List[str]] = None,
+                    expired_only: bool = False) -> List[str]:
+        """
+        Delete trash entries for good: ``staging_ids``, or every entry (with
+        ``expired_only``: those older than trash_days).  Returns the ids purged.
+        """
+        trash_dir = self._require_trash('purge the trash')
+        from .snippet_trash import expired, read_entry, remove_entry
+        with self._lock:
+            if staging_ids:
+                entries = []
+                for staging_id in staging_ids:
+                    try:
+                        entries.append(read_entry(trash_dir, staging_id))
+                    except ValueError as exc:
+                        raise SpokedError(ErrorKind.NOT_FOUND, str(exc),
+                                          staging_id=staging_id) from None
+            else:
+                entries = self.trash()
+            if expired_only:
+                entries = expired(entries, self._trash_days)
+            for entry in entries:
+                remove_entry(trash_dir, entry['staging_id'])
+        for entry in entries:
+            self._audit.log(AuditEventType.TRASH_PURGED, entry['staging_id'], {
+                'deleted_at': entry.get('deleted_at'),
+                'expired': expired_only,
+            })
+        return [e['staging_id'] for e in entries]
+
+    def gc_trash(self) -> List[str]:
+        """Purge what has been in the trash longer than trash_days (the server does at start)."""
+        if self.read_only or not self._trash_dir:
+            return []
+        return self.purge_trash(expired_only=True)
+
+    # ─────────────────────────────────────────────────────────────────────
+    # REVIEW — what promoting a snippet would change
+    # ─────────────────────────────────────────────────────────────────────
+
+    def review(self, staging_id: str) -> Dict[str, Any]:
+        """
+        Everything a reviewer needs before promoting a speculated snippet:
+        the file header that will be written, gate results and spec output
+        (in ``snippet``), and a unified diff against the slot's current
+        snippet — the promoted snippet at the same address, else the latest
+        promoted snippet with the same label and language.
+        """
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase not in (StagingPhase.PASSED, StagingPhase.FAILED):
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot review snippet in phase '{snippet.phase.value}' "
+                    f"(must be PASSED or FAILED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+            current = self._current_for(snippet)
+
+        new_name = f"{snippet.reserved_address} ({snippet.staging_id})"
+        if current is None:
+            old_code, old_name = '', '/dev/null'
+        else:
+            old_code, old_name = current.code, f"{current.reserved_address} ({current.staging_id})"
+        diff = difflib.unified_diff(old_code.splitlines(keepends=True),
+                                    snippet.code.splitlines(keepends=True),
+                                    fromfile=old_name, tofile=new_name)
+        return {
+            'snippet': snippet.to_dict(),
+            'header': self._make_file_header(snippet),
+            'current': None if current is None else {
+                'staging_id': current.staging_id,
+                'address': current.reserved_address,
+                'label': current.label,
+                'promoted_at': current.promoted_at,
+            },
+            'diff': ''.join(line if line.endswith('\n') else line + '\n' for line in diff),
+            'constraints': [] if self._constraints is None
+                           else self._constraints.violations(snippet),
+        }
+
+    def _current_for(self, snippet: StagedSnippet) -> Optional[StagedSnippet]:
+        promoted = [h for h in reversed(self._history) if h.phase == StagingPhase.PROMOTED]
+        for h in promoted:
+            if h.reserved_address == snippet.reserved_address:
+                return h
+        for h in promoted:
+            if h.label == snippet.label and h.language == snippet.language:
+                return h
+        return None
+
+    def relabel(self, staging_id: str, label: str) -> StagedSnippet:
+        """Rename a snippet that has not been promoted yet."""
+        self._require_writable('relabel snippets')
+        label = label.strip()
+        if not label:
+            raise SpokedError(ErrorKind.USAGE, 'Label cannot be empty', staging_id=staging_id)
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            old = snippet.label
+            snippet.label = label
+            snippet.updated_at = time.time()
+        self._audit.log(AuditEventType.LABEL_CHANGED, staging_id, {'from': old, 'to': label})
+        # label: rules may hand the snippet to different owners.
+        self._request_approval(snippet)
+        return snippet
+
+    # ─────────────────────────────────────────────────────────────────────
+    # APPROVALS — the review queue behind /api/approvals
+    # ─────────────────────────────────────────────────────────────────────
+
+    def pending_approvals(self) -> List[Dict[str, Any]]:
+        """
+        review() for every snippet waiting on a human decision: PASSED
+        snippets that were not auto-promoted, plus FAILED ones while gate
+        overrides are allowed.  Oldest first.
+        """
+        waiting = (StagingPhase.PASSED, StagingPhase.FAILED) if self._allow_override \
+            else (StagingPhase.PASSED,)
+        with self._lock:
+            ids = [s.staging_id for s in sorted(self._staged.values(), key=lambda s: s.created_at)
+                   if s.phase in waiting and not s.scheduled_for]
+        return [self.review(staging_id) for staging_id in ids]
+
+    def decide(self, staging_id: str, approve: bool, principal: str,
+               reason: str = '') -> StagedSnippet:
+        """
+        Record a reviewer's decision and act on it: approve promotes the
+        snippet (overriding a failure if allowed), reject archives it.
+        The principal lands in the audit trail, ``reviewed_by``, and the
+        promoted file's ``approved_by:`` header line.  Only one of the
+        snippet's ``required_approvers`` (if any) may approve.
+        """
+        self._require_writable('record approvals')
+        if not principal:
+            raise ValueError('A decision needs a principal')
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase not in (StagingPhase.PASSED, StagingPhase.FAILED):
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot decide on snippet in phase '{snippet.phase.value}' "
+                    f"(must be PASSED or FAILED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+            if approve and snippet.phase == StagingPhase.FAILED and not self._allow_override:
+                raise SpokedError(
+                    ErrorKind.GATE_DENIED,
+                    f"Snippet '{staging_id}' failed speculation or a promotion gate and "
+                    f"gate overrides are disabled ([gates] allow_override = false)",
+                    staging_id=staging_id,
+                )
+            if approve:
+                self._check_approver(snippet, principal)
+            snippet.reviewed_by = principal
+        self._audit.log(AuditEventType.APPROVAL_RECORDED, staging_id, {
+            'decision': 'approve' if approve else 'reject',
+            'principal': principal,
+            'reason': reason,
+        })
+        if not approve:
+            return self.verdict(staging_id, 'reject', reason or f'Rejected by {principal}')
+        if snippet.phase == StagingPhase.FAILED:
+            self.verdict(staging_id, 'approve', reason or f'Approved by {principal}')
+        return self.promote(staging_id, approved_by=principal)
+
+    # ─────────────────────────────────────────────────────────────────────
+    # SCHEDULED PROMOTION — hold a PASSED snippet for a maintenance window
+    # ─────────────────────────────────────────────────────────────────────
+
+    def schedule_promotion(self, staging_id: str, at: float,
+                           principal: str = '') -> StagedSnippet:
+        """Hold a PASSED snippet and promote it at ``at`` (see promote_due)."""
+        self._require_writable('schedule promotions')
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No staged snippet '{staging_id}'",
+                                  staging_id=staging_id)
+            if snippet.phase != StagingPhase.PASSED:
+                raise SpokedError(
+                    ErrorKind.INVALID_STATE,
+                    f"Cannot schedule snippet in phase '{snippet.phase.value}' "
+                    f"(must be PASSED)",
+                    staging_id=staging_id, phase=snippet.phase.value,
+                )
+            if at <= time.time():
+                raise ValueError('The scheduled time is in the past')
+            self._check_approver(snippet, principal)
+            violations = self._slot_violations(snippet)
+            if violations:
+                raise SpokedError(ErrorKind.SLOT_FROZEN,
+                                  f"Slot {snippet.reserved_address} does not accept "
+                                  f"{staging_id}: {'; '.join(violations)}",
+                                  staging_id=staging_id, slot=snippet.reserved_address,
+                                  violations=violations)
+            snippet.scheduled_for = at
+            snippet.scheduled_by = principal
+            snippet.updated_at = time.time()
+        self._audit.log(AuditEventType.PROMOTION_SCHEDULED, staging_id, {
+            'at': at,
+            'iso_at': time.strftime('%Y-%m-%dT%H:%M:%SZ', time.gmtime(at)),
+            'principal': principal,
+            'reserved_address': snippet.reserved_address,
+        })
+        return snippet
+
+    def cancel_schedule(self, staging_id: str, reason: str = '') -> StagedSnippet:
+        """Release a held promotion; the snippet stays PASSED."""
+        self._require_writable('cancel scheduled promotions')
+        with self._lock:
+            snippet = self._staged.get(staging_id)
+            if snippet is None or not snippet.scheduled_for:
+                raise SpokedError(ErrorKind.NOT_FOUND, f"No scheduled promotion for '{staging_id}'",
+                                  staging_id=staging_id)
+            at = snippet.scheduled_for
+            snippet.scheduled_for = 0.0
+            snippet.scheduled_by = ''
+            snippet.updated_at = time.time()
+        self._audit.log(AuditEventType.SCHEDULE_CANCELLED, staging_id, {
+            'at': at,
+            'reason': reason,
+        })
+        return snippet
+
+    def scheduled(self) -> List[StagedSnippet]:
+        """Held promotions, soonest first."""
+        with self._lock:
+            return sorted((s for s in self._staged.values() if s.scheduled_for),
+                          key=lambda s: s.scheduled_for)
+
+    def promote_due(self, now: Optional[float] = None) -> List[StagedSnippet]:
+        """
+        Promote every held snippet whose time has come, in one pass under the
+        pipeline lock so a window's changes land together.  A promotion that
+        fails is logged and dropped from the schedule.
+        """
+        self._require_writable('promote scheduled snippets')
+        now = time.time() if now is None else now
+        promoted = []
+        with self._lock:
+            due = [s for s in self.scheduled() if s.scheduled_for <= now]
+            for snippet in due:
+                if snippet.phase != StagingPhase.PASSED:
+                    snippet.scheduled_for = 0.0
+                    continue
+                try:
+                    promoted.append(self.promote(snippet.staging_id,
+                                                 approved_by=snippet.scheduled_by))
+                except Exception as exc:
+                    snippet.scheduled_for = 0.0
+                    self._audit.log(AuditEventType.ERROR, snippet.staging_id, {
+                        'step': 'scheduled_promotion',
+                        'error': str(exc),
+                    })
+        return promoted
+
     # ─────────────────────────────────────────────────────────────────────
     # FULL PIPELINE — queue → speculate → verdict → promote (one call)
     # ─────────────────────────────────────────────────────────────────────
 
     def run_full_pipeline(self, engine_letter: str, language: str,
                           code: str, label: str = '',
-                          auto_promote: bool = True) -> StagedSnippet:
+                          auto_promote: Optional[bool] = True,
+                          author: str = '', spec=None,
+                          default_auto_promote: bool = True,
+                          meta: Optional[Dict[str, Any]] = None,
+                          parent: str = '') -> StagedSnippet:
         """
         Run the complete staging pipeline in one call:
 
             queue → speculate → verdict → promote (if pass)
 
         If auto_promote is False, stops after verdict (returns PASSED
-        or FAILED — caller must call promote() separately).
+        or FAILED — caller must call promote() separately).  None means
+        the caller did not say: default_auto_promote applies.  With a
+        policy, a rule covering the slot decides instead — a protected
+        slot waits for a human even when auto_promote is True, and so
+        does a snippet whose slot has owners, or that imports a DEGRADED
+        slot (see revalidation).
 
         Returns the final StagedSnippet.
         """
         # Phase 1: Queue
-        snippet = self.queue_snippet(engine_letter, language, code, label)
+        snippet = self.queue_snippet(engine_letter, language, code, label, author, spec, meta,
+                                     parent)
 
         # Phase 2: Speculate
         snippet = self.speculate(snippet.staging_id)
@@ -881,9 +2448,15 @@ class StagingPipeline:
         # Phase 3: Verdict
         snippet = self.verdict(snippet.staging_id, action='auto')
 
-        # Phase 4: Promote (if passed and auto_promote)
-        if snippet.phase == StagingPhase.PASSED and auto_promote:
-            snippet = self.promote(snippet.staging_id)
+        # Phase 4: Promote (if passed and auto_promote / the policy allows)
+        if snippet.phase == StagingPhase.PASSED:
+            if self._policy is None:
+                promote = default_auto_promote if auto_promote is None else auto_promote
+            else:
+                promote = self._evaluate_policy(snippet, auto_promote, default_auto_promote)
+            if (promote and not snippet.required_approvers and not self._slot_violations(snippet)
+                    and not self._degraded_imports(snippet)):
+                snippet = self.promote(snippet.staging_id)
 
         return snippet
 
@@ -909,6 +2482,108 @@ class StagingPipeline:
         """Get completed/rejected snippets (most recent first)."""
         return list(reversed(self._history[-limit:]))
 
+    def list_snippets(self, limit: int = 100, after: str = '', phase: str = '',
+                      meta: Optional[Dict[str, Optional[str]]] = None, query: str = '',
+                      include_history: bool = True) -> Dict[str, Any]:
+        """
+        One page of the staged (and archived) snippets, newest first by seq.
+
+        ``after`` is the ``next_cursor`` of the page before: the seq of its
+        last snippet, so the pages stay put while new snippets are staged
+        in front of them.  ``total`` counts every match, not just the page;
+        ``next_cursor`` is None on the last page.  The history covers the
+        whole store: snippets trimmed from memory (history_limit) or from
+        before a restart come from the audit log, without their code.
+        """
+        before = None
+        if after:
+            try:
+                before = int(after)
+            except ValueError:
+                raise SpokedError(ErrorKind.USAGE, f"Malformed cursor '{after}'",
+                                  cursor=after) from None
+        limit = max(1, int(limit))
+        with self._lock:
+            found = list(self._staged.values()) + (list(self._history) if include_history else [])
+        if include_history:
+            found += self._logged_snippets({s.staging_id for s in found})
+        found = sorted((s for s in found
+                        if (not phase or s.phase.value == phase) and s.matches(meta, query)),
+                       key=lambda s: s.seq, reverse=True)
+        start = 0 if before is None else next(
+            (i for i, s in enumerate(found) if s.seq < before), len(found))
+        page = found[start:start + limit]
+        more = start + limit < len(found)
+        return {'snippets': page, 'total': len(found),
+                'next_cursor': str(page[-1].seq) if more else None}
+
+    _PHASE_EVENTS = {
+        AuditEventType.SPEC_EXEC_STARTED.value: StagingPhase.SPECULATING,
+        AuditEventType.SPEC_EXEC_COMPLETED.value: StagingPhase.PASSED,
+        AuditEventType.SPEC_EXEC_FAILED.value: StagingPhase.FAILED,
+        AuditEventType.PROMOTION_COMPLETED.value: StagingPhase.PROMOTED,
+        AuditEventType.REJECTION.value: StagingPhase.REJECTED,
+        AuditEventType.ROLLBACK.value: StagingPhase.ROLLED_BACK,
+    }
+
+    def _logged_snippets(self, known: set) -> List[StagedSnippet]:
+        """
+        The snippets the audit log knows and ``known`` does not, rebuilt
+        from their events: phase, slot and label as last logged, code ''.
+        Deleted ones stay out until they are restored.
+        """
+        events = self._audit.read_events(
+            AuditEventType.SNIPPET_QUEUED, AuditEventType.SLOT_RESERVED,
+            AuditEventType.LABEL_CHANGED, AuditEventType.SNIPPET_DELETED,
+            AuditEventType.SNIPPET_RESTORED, *(AuditEventType(e) for e in self._PHASE_EVENTS))
+        rebuilt: Dict[str, StagedSnippet] = {}
+        deleted = set()
+        for e in events:
+            staging_id, data, kind = e.get('staging_id', ''), e.get('data', {}), e.get('event')
+            if staging_id in known:
+                continue
+            if kind == AuditEventType.SNIPPET_QUEUED.value:
+                rebuilt[staging_id] = StagedSnippet(
+                    staging_id=staging_id, language=data.get('language', ''),
+                    engine_letter=data.get('engine_letter', ''), label=data.get('label', ''),
+                    code='', code_hash=data.get('code_hash', ''), author=data.get('author', ''),
+                    spec_hash=data.get('spec_hash', ''), meta=dict(data.get('meta') or {}),
+                    network=data.get('network', ''), parent=data.get('parent', ''),
+                    created_at=e.get('timestamp', 0.0), updated_at=e.get('timestamp', 0.0),
+                    seq=e.get('seq', 0))
+                continue
+            snippet = rebuilt.get(staging_id)
+            if snippet is None:
+                continue
+            snippet.updated_at = e.get('timestamp', snippet.updated_at)
+            if kind == AuditEventType.SLOT_RESERVED.value:
+                snippet.reserved_engine = data.get('engine', '')
+                snippet.reserved_position = data.get('position', 0)
+                snippet.reserved_address = data.get('address', '')
+            elif kind == AuditEventType.LABEL_CHANGED.value:
+                snippet.label = data.get('to', snippet.label)
+            elif kind == AuditEventType.SNIPPET_DELETED.value:
+                deleted.add(staging_id)
+            elif kind == AuditEventType.SNIPPET_RESTORED.value:
+                deleted.discard(staging_id)
+                snippet.reserved_address = data.get('address', snippet.reserved_address)
+            else:
+                snippet.phase = self._PHASE_EVENTS[kind]
+                if kind == AuditEventType.PROMOTION_COMPLETED.value:
+                    snippet.saved_file_path = data.get('file_path', '')
+                    snippet.promoted_at = data.get('promoted_at', 0.0)
+                    snippet.promoted_seq = data.get('promoted_seq', 0)
+        return [s for sid, s in rebuilt.items() if sid not in deleted]
+
+    def current_promoted(self) -> Dict[str, StagedSnippet]:
+        """Slot address → the promoted snippet it holds now."""
+        with self._lock:
+            current: Dict[str, StagedSnippet] = {}
+            for h in self._history:                   # oldest first: the last promotion wins
+                if h.phase == StagingPhase.PROMOTED:
+                    current[h.reserved_address] = h
+        return current
+
     def get_audit_trail(self, staging_id: Optional[str] = None,
                         limit: int = 500) -> List[Dict]:
         """Get audit log entries, optionally filtered by staging_id."""
@@ -916,6 +2591,95 @@ class StagingPipeline:
             return self._audit.read_for_staging_id(staging_id)
         return self._audit.read_all(limit=limit)
 
+    def lineage(self, staging_id: str) -> List[Ancestor]:
+        """
+        ``staging_id`` and the snippets it was staged from, newest first,
+        each with its code where it is still kept (see lineage).  Raises
+        NOT_FOUND if neither the store, its files nor the audit log know it.
+        """
+        events = self._audit.read_events
+        queued = {e['staging_id']: e for e in events(AuditEventType.SNIPPET_QUEUED)}
+        written = {e['staging_id']: e['data'].get('path', '')
+                   for e in events(AuditEventType.FILE_WRITTEN)}
+        rolled_back = {e['staging_id'] for e in events(AuditEventType.ROLLBACK)}
+        chain = build_lineage(staging_id, lambda sid: self._ancestor(
+            sid, queued.get(sid), written.get(sid, ''), sid in rolled_back))
+        if not chain[0].source:
+            raise SpokedError(ErrorKind.NOT_FOUND, f"No snippet '{staging_id}' in the store, "
+                              f"its files or the audit log", staging_id=staging_id)
+        return chain
+
+    def _ancestor(self, staging_id: str, queued: Optional[Dict[str, Any]], path: str,
+                  rolled_back: bool = False) -> Optional[Ancestor]:
+        """One link of a lineage: from the store, else its promoted file, bundle or queue event."""
+        snippet = self.get_snippet(staging_id)
+        if snippet is not None:
+            return Ancestor(staging_id, snippet.parent, snippet.label, snippet.language,
+                            snippet.reserved_address, snippet.phase.value, snippet.author,
+                            snippet.code_hash, utc_timestamp(snippet.created_at), snippet.code,
+                            SOURCE_STORE)
+        if queued is None and not path:
+            return None
+        data = (queued or {}).get('data', {})
+        found = Ancestor(staging_id, data.get('parent', ''), data.get('label', ''),
+                         data.get('language', ''), author=data.get('author', ''),
+                         code_hash=data.get('code_hash', ''),
+                         created=utc_timestamp(queued['timestamp']) if queued else '',
+                         source=SOURCE_AUDIT)
+
+        header_path = path if path and os.path.isfile(path) else self._promoted_file(found)
+        if header_path:
+            with open(header_path, 'r', encoding='utf-8', errors='replace') as f:
+                header = parse_file_header(f.read())
+            if header is not None:
+                fields = header.fields
+                found.parent = fields.get('parent', found.parent)
+                found.label = fields.get('label', found.label)
+                found.language = fields.get('language', found.language)
+                found.slot = fields.get('slot', '').split(' ')[0]
+                found.created = fields.get('created', found.created)
+                found.phase = (StagingPhase.ROLLED_BACK if rolled_back
+                               else StagingPhase.PROMOTED).value
+                found.code, found.source = header.body, SOURCE_FILE
+                return found
+
+        if self._failures_dir:
+            from .failure_bundle import read_bundle, read_file
+            try:
+                bundle = read_bundle(self._failures_dir, staging_id)
+            except ValueError:
+                bundle = None
+            if bundle is not None:
+                found.parent = bundle.get('parent') or found.parent
+                found.slot, found.phase = bundle.get('slot', ''), bundle.get('phase', '')
+                source = next((n for n in bundle.get('files', {}) if n.startswith('source')), '')
+                if source:
+                    found.code = read_file(bundle, source)
+                found.source = SOURCE_BUNDLE
+        return found
+
+    def _promoted_file(self, found: Ancestor) -> str:
+        """The promoted file of ``found`` in its shard (or flat) dir, '' if there is none."""
+        if not (found.language and found.code_hash):
+            return ''
+        sharded = promoted_dir(self._snippets_dir, found.language, found.code_hash,
+                               self._shard_width)
+        ext = LANG_EXTENSIONS.get(found.language, '.txt')
+        flat = os.path.join(self._snippets_dir, found.language)
+        for directory in dict.fromkeys([sharded, flat]):
+            try:
+                names = sorted(os.listdir(directory))
+            except OSError:
+                continue
+            for name in names:
+                if f"_{found.staging_id}_" in name and name.endswith(ext):
+                    return os.path.join(directory, name)
+        return ''
+
+    def audit_stats(self) -> Dict[str, int]:
+        """Audit events written and the (group-committed) writes that carried them."""
+        return self._audit.stats()
+
     def get_reserved_positions(self) -> Dict[str, List[int]]:
         """Get currently reserved (but not yet committed) positions."""
         with self._lock:
@@ -946,17 +2710,320 @@ class StagingPipeline:
             'rejected_total': rejected_count,
             'rolled_back_total': rolled_back,
             'reserved_positions': self.get_reserved_positions(),
+            'gates': [g.name for g in self._gates],
+            'id_mode': self._id_mode.value,
+            'id_collisions': self._id_collisions,
+            'degraded': list(self.degraded()),
         }
 
+    # ─────────────────────────────────────────────────────────────────────
+    # EVENT LISTENERS — spec_failure / spec_anomaly / promotion / rollback
+    # ─────────────────────────────────────────────────────────────────────
+
+    def add_listener(self, listener: Callable):
+        """Call ``listener(event, snippet, details)`` on notifiable events."""
+        with self._lock:
+            self._listeners.append(listener)
+
+    def _emit(self, event: str, snippet: StagedSnippet, **details):
+        for listener in list(self._listeners):
+            try:
+                listener(event, snippet, details)
+            except Exception as exc:
+                # A broken notifier must never fail the pipeline itself.
+                self._audit.log(AuditEventType.ERROR, snippet.staging_id, {
+                    'step': f'listener:{event}',
+                    'error': str(exc),
+                })
+
+    def _slot_violations(self, snippet: StagedSnippet) -> List[str]:
+        """Unmet constraints of the snippet's slot ([] → it may be promoted); audited."""
+        if self._constraints is None:
+            return []
+        violations = self._constraints.violations(snippet)
+        if violations:
+            self._audit.log(AuditEventType.CONSTRAINT_VIOLATED, snippet.staging_id, {
+                'reserved_address': snippet.reserved_address,
+                'violations': violations,
+            })
+        return violations
+
+    def _request_approval(self, snippet: StagedSnippet):
+        """Look up the owners of the snippet's slot / label; notify them if they changed."""
+        if self._owners is None:
+            return
+        owners, rule = self._owners.match(snippet.reserved_address, snippet.label)
+        if self._owners.error:
+            self._audit.log(AuditEventType.ERROR, snippet.staging_id, {
+                'step': 'owners',
+                'error': self._owners.error,
+            })
+        if owners == snippet.required_approvers:
+            return
+        snippet.required_approvers = owners
+        if not owners:
+            return
+        self._audit.log(AuditEventType.APPROVAL_REQUESTED, snippet.staging_id, {
+            'owners': owners,
+            'rule': rule.pattern,
+            'line': rule.line,
+            'reserved_address': snippet.reserved_address,
+        })
+        self._emit('approval_request', snippet, owners=owners,
+                   reason=f"{rule.pattern} is owned by {', '.join(owners)}")
+
+    def _check_approver(self, snippet: StagedSnippet, principal: str):
+        if snippet.required_approvers and principal not in snippet.required_approvers:
+            raise SpokedError(
+                ErrorKind.GATE_DENIED,
+                f"Slot {snippet.reserved_address} ({snippet.label}) is owned by "
+                f"{', '.join(snippet.required_approvers)}; '{principal or 'anonymous'}' "
+                f"cannot approve {snippet.staging_id}",
+                staging_id=snippet.staging_id, slot=snippet.reserved_address,
+                approvers=list(snippet.required_approvers), principal=principal,
+            )
+
+    def _evaluate_policy(self, snippet: StagedSnippet, requested: Optional[bool],
+                         default: bool) -> bool:
+        """Ask the policy whether a PASSED snippet promotes now; audited."""
+        decision = self._policy.decide(snippet, requested, default)
+        snippet.policy_decision = decision.to_dict()
+        self._audit.log(AuditEventType.POLICY_EVALUATED, snippet.staging_id,
+                        dict(snippet.policy_decision))
+        return decision.promote
+
+    def _record_trend(self, snippet: StagedSnippet):
+        """Add the run's spec_time to the trend index; alert on an outlier."""
+        try:
+            anomaly = self._trends.record(snippet)
+        except OSError as exc:
+            self._audit.log(AuditEventType.ERROR, snippet.staging_id, {
+                'step': 'trends',
+                'error': str(exc),
+            })
+            return
+        if anomaly is None:
+            return
+        self._audit.log(AuditEventType.SPEC_ANOMALY, snippet.staging_id, {
+            'spec_time': anomaly.spec_time,
+            'median': anomaly.median,
+            'stdev': anomaly.stdev,
+            'samples': anomaly.samples,
+        })
+        self._emit('spec_anomaly', snippet, reason=anomaly.describe())
+
+    def _record_failure(self, snippet: StagedSnippet, processes: List[Dict[str, Any]]):
+        """Write the failure bundle for a FAILED snippet (never raises)."""
+        from .failure_bundle import write_bundle
+        try:
+            path = write_bundle(self._failures_dir, snippet, processes,
+                                LANG_EXTENSIONS.get(snippet.language, '.txt'))
+        except (OSError, ValueError) as exc:
+            self._audit.log(AuditEventType.ERROR, snippet.staging_id, {
+                'step': 'failure_bundle',
+                'error': str(exc),
+            })
+            return
+        with self._lock:
+            snippet.failure_bundle = path
+        self._audit.log(AuditEventType.FAILURE_RECORDED, snippet.staging_id, {'path': path})
+
     # ─────────────────────────────────────────────────────────────────────
     # INTERNAL HELPERS
     # ─────────────────────────────────────────────────────────────────────
 
+    def load_promoted(self, snap: Dict[str, Any]) -> StagedSnippet:
+        """
+        Put a snippet that was promoted before back in its slot, from a
+        state checkpoint entry (see state_persistence), without re-running
+        it or writing anything: the file it was promoted to must still be
+        there and match ``code_hash``.  A read-only server fills its slots
+        this way; the others re-run the checkpoint through the pipeline.
+        """
+        staging_id = snap.get('staging_id', '')
+        code = snap.get('code', '')
+        path = snap.get('saved_file_path', '')
+        if not path or not os.path.isfile(path):
+            raise SpokedError(ErrorKind.STORE_CORRUPTION,
+                              f"Promoted file of {staging_id} is missing: {path or '(none)'}",
+                              staging_id=staging_id, path=path)
+        code_hash = hashlib.sha256(code.encode('utf-8')).hexdigest()
+        if snap.get('code_hash') and snap['code_hash'] != code_hash:
+            raise SpokedError(ErrorKind.STORE_CORRUPTION,
+                              f"Checkpointed code of {staging_id} does not match its code_hash",
+                              staging_id=staging_id, path=path, code_hash=snap['code_hash'])
+        from .offline import declared_network
+        try:
+            network = declared_network(code)
+        except ValueError:
+            network = ''                    # promoted before declarations were checked
+        snippet = StagedSnippet(
+            staging_id=staging_id,
+            language=snap.get('language', ''),
+            engine_letter=snap.get('engine_letter', ''),
+            label=snap.get('label', ''),
+            code=code,
+            code_hash=code_hash,
+            author=snap.get('submitter', ''),
+            meta=normalize_meta(snap.get('meta')),
+            network=network,
+            phase=StagingPhase.PROMOTED,
+            created_at=snap.get('created_at', 0.0),
+            updated_at=time.time(),
+            reserved_engine=snap.get('engine_name', ''),
+            reserved_position=snap.get('position', 0),
+            reserved_address=snap.get('address', ''),
+            spec_output=snap.get('spec_output', ''),
+            spec_error=snap.get('spec_error', ''),
+            spec_execution_time=snap.get('spec_execution_time', 0.0),
+            spec_success=snap.get('spec_success', True),
+            saved_file_path=path,
+            promoted_at=snap.get('promoted_at', 0.0),
+            promoted_seq=snap.get('promoted_seq', 0),
+            parent=snap.get('parent', ''),
+        )
+        # The ledger and registry are in memory — filling them changes no file.
+        snippet.ledger_node_id = self._create_ledger_node(snippet)
+        from .node_registry import SlotPermissionSet
+        slot = self._registry.commit_node(
+            node_id=snippet.ledger_node_id,
+            engine_name=snippet.reserved_engine,
+            position=snippet.reserved_position,
+            permissions=SlotPermissionSet(get=True, push=False, post=False, delete=False),  # resolve only
+        )
+        if slot is None:
+            raise SpokedError(ErrorKind.SLOT_FULL,
+                              f"Cannot place {staging_id} in slot {snippet.reserved_address}",
+                              staging_id=staging_id, slot=snippet.reserved_address)
+        snippet.registry_slot_id = slot.slot_id
+        self._archive_snippet(snippet)
+        self._settle_degraded(snippet)
+        return snippet
+
     def _archive_snippet(self, snippet: StagedSnippet):
         """Move a snippet from active to history."""
         with self._lock:
             self._staged.pop(snippet.staging_id, None)
             self._history.append(snippet)
-            # Cap history at 1000
-            if len(self._history) > 1000:
-                self._history = self._history[-1000:]
+            # Cap history ([retention] history_limit)
+            if len(self._history) > self._history_limit:
+                self._history = self._history[-self._history_limit:]
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SNIPPET FILES — read back the header promote() writes
+# ═══════════════════════════════════════════════════════════════════════════
+
+# The banner layout promote() writes.  Older files stay readable: a header
+# without a banner_version line is version 1, whose code_hash is cut to 16
+# hex digits + '…'; version 2 numbers the layout and keeps the full sha256.
+# ``spokedpy migrate-headers`` rewrites old files to the current layout.
+BANNER_VERSION = 2
+
+_V1_FIELDS = ('staging_id', 'language', 'engine', 'slot', 'label', 'code_hash',
+              'created', 'promoted', 'spec_time', 'spec_result')
+HEADER_FIELDS_BY_VERSION = {
+    1: _V1_FIELDS,
+    2: ('banner_version',) + _V1_FIELDS,
+}
+HEADER_FIELDS = HEADER_FIELDS_BY_VERSION[BANNER_VERSION]
+OPTIONAL_HEADER_FIELDS = ('spec_hash', 'spec_inherits', 'spec_case', 'coverage', 'approved_by',
+                          'promoted_seq', 'network', 'parent')
+REPEATED_HEADER_FIELDS = ('spec_case',)             # one line per spec case
+
+_BANNER = '═══'
+
+
+@dataclass
+class SnippetFileHeader:
+    """A promoted snippet file split into header fields and body."""
+    prefix: str                                 # '#' or '//'
+    title: str
+    fields: Dict[str, str]
+    field_lines: Dict[str, int]                 # field → 0-based line number
+    end_line: int                               # closing banner line
+    closed: bool                                # closing banner present
+    body_line: int                              # first line of the code
+    body: str
+    repeated: Dict[str, List[str]] = field(default_factory=dict)   # REPEATED_HEADER_FIELDS values
+    version: int = 1                            # banner_version (0 → malformed)
+
+    @property
+    def required_fields(self) -> Tuple[str, ...]:
+        """The fields this header's layout must have (the current one's if unknown)."""
+        return HEADER_FIELDS_BY_VERSION.get(self.version, HEADER_FIELDS)
+
+    @property
+    def promoted_order(self) -> Tuple[float, int]:
+        """(promoted time, promoted_seq) — sorts promotions, older files (0 seq) included."""
+        try:
+            when = parse_timestamp(self.fields.get('promoted', ''))
+        except ValueError:
+            when = 0.0
+        seq = self.fields.get('promoted_seq', '')
+        return when, int(seq) if seq.isdigit() else 0
+
+    @property
+    def meta(self) -> Dict[str, str]:
+        """The custom ``x-`` fields, without the prefix."""
+        return {k[len(META_PREFIX):]: v for k, v in self.fields.items() if k.startswith(META_PREFIX)}
+
+    @property
+    def spec_cases(self) -> List[Dict[str, Any]]:
+        """The ``spec_case:`` lines, parsed (malformed ones skipped)."""
+        from .spec_suite import parse_case_line
+        parsed = (parse_case_line(v) for v in self.repeated.get('spec_case', []))
+        return [c for c in parsed if c is not None]
+
+    def body_matches_hash(self) -> bool:
+        """True if the body still hashes to the header's (truncated) code_hash."""
+        expected = self.fields.get('code_hash', '').rstrip('…')
+        if not expected:
+            return False
+        # Editors often add a trailing newline on save.
+        candidates = {self.body, self.body[:-1] if self.body.endswith('\n') else self.body}
+        return any(hashlib.sha256(c.encode('utf-8')).hexdigest().startswith(expected)
+                   for c in candidates)
+
+
+def parse_file_header(text: str) -> Optional[SnippetFileHeader]:
+    """Parse a promoted snippet file; None if it does not start with a banner."""
+    lines = text.split('\n')
+    first = lines[0].strip() if lines else ''
+    prefix = next((p for p in ('//', '#') if first.startswith(p)), None)
+    if prefix is None or _BANNER not in first:
+        return None
+
+    fields: Dict[str, str] = {}
+    field_lines: Dict[str, int] = {}
+    repeated: Dict[str, List[str]] = {}
+    title = ''
+    end_line = -1
+    for i in range(1, len(lines)):
+        line = lines[i].strip()
+        if not line.startswith(prefix):
+            break
+        content = line[len(prefix):].strip()
+        if content.startswith(_BANNER):
+            end_line = i
+            break
+        if i == 1 and ':' not in content:
+            title = content
+            continue
+        key, sep, value = content.partition(':')
+        if sep:
+            fields[key.strip()] = value.strip()
+            field_lines[key.strip()] = i
+            if key.strip() in REPEATED_HEADER_FIELDS:
+                repeated.setdefault(key.strip(), []).append(value.strip())
+    closed = end_line >= 0
+    if not closed:
+        end_line = max(field_lines.values(), default=0)
+
+    body_line = end_line + 1
+    if body_line < len(lines) and not lines[body_line].strip():
+        body_line += 1
+    raw_version = fields.get('banner_version')
+    version = 1 if raw_version is None else int(raw_version) if raw_version.isdigit() else 0
+    return SnippetFileHeader(prefix, title, fields, field_lines, end_line, closed, body_line,
+                             '\n'.join(lines[body_line:]), repeated, version)
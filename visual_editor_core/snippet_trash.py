@@ -0,0 +1,138 @@
+"""
+Snippet Trash — deleted snippets, kept for a while so a delete can be undone.
+
+StagingPipeline.delete() moves a snippet out of the pipeline into
+``<trash_dir>/<staging_id>/``:
+
+    ┌──────────────────┬──────────────────────────────────────────────────┐
+    │  tombstone.json  │  when, why and by whom it was deleted, the phase │
+    │                  │  it was in, and the whole snippet (code, spec,   │
+    │                  │  results) as the pipeline held it                │
+    │  <file name>     │  its promoted file, if it had one (a rolled-back │
+    │                  │  snippet keeps it for forensics)                 │
+    └──────────────────┴──────────────────────────────────────────────────┘
+
+``spokedpy trash list`` reads the trash back (no server needed); ``spokedpy
+trash restore`` puts a snippet back where it was, ``spokedpy trash purge``
+deletes entries for good.  Entries older than ``[retention] trash_days``
+are purged by gc_trash — on ``purge --expired`` and when the server starts.
+"""
+
+import json
+import os
+import shutil
+import time
+from typing import Any, Dict, List, Optional
+
+TOMBSTONE_FILE = 'tombstone.json'
+DEFAULT_TRASH_DAYS = 30.0
+
+
+def entry_dir(trash_dir: str, staging_id: str) -> str:
+    if not staging_id or os.sep in staging_id or (os.altsep and os.altsep in staging_id) \
+            or staging_id in ('.', '..'):
+        raise ValueError(f"Invalid staging id '{staging_id}'")
+    return os.path.join(trash_dir, staging_id)
+
+
+def write_entry(trash_dir: str, record: Dict[str, Any], reason: str = '',
+                deleted_by: str = '') -> Dict[str, Any]:
+    """
+    Put a snippet (``record``: its fields, as StagedSnippet holds them) in the
+    trash, moving its promoted file along; returns the tombstone.
+    """
+    path = entry_dir(trash_dir, record['staging_id'])
+    if os.path.isdir(path):
+        shutil.rmtree(path)
+    os.makedirs(path)
+    tombstone = {
+        'staging_id': record['staging_id'],
+        'label': record.get('label', ''),
+        'language': record.get('language', ''),
+        'slot': record.get('reserved_address', ''),
+        'phase': record.get('phase', ''),
+        'deleted_at': time.time(),
+        'deleted_by': deleted_by,
+        'reason': reason,
+        'file': '',
+        'snippet': record,
+    }
+    saved = record.get('saved_file_path', '')
+    if saved and os.path.isfile(saved):
+        shutil.move(saved, os.path.join(path, os.path.basename(saved)))
+        tombstone['file'] = saved
+    with open(os.path.join(path, TOMBSTONE_FILE), 'w', encoding='utf-8') as f:
+        json.dump(tombstone, f, indent=2, default=str)
+        f.write('\n')
+    tombstone['path'] = path
+    return tombstone
+
+
+def read_entry(trash_dir: str, staging_id: str) -> Dict[str, Any]:
+    """tombstone.json for a staging id, with ``path``; ValueError if it is not in the trash."""
+    path = entry_dir(trash_dir, staging_id)
+    try:
+        with open(os.path.join(path, TOMBSTONE_FILE), 'r', encoding='utf-8') as f:
+            tombstone = json.load(f)
+    except FileNotFoundError:
+        raise ValueError(f"'{staging_id}' is not in the trash ({trash_dir})") from None
+    except json.JSONDecodeError as exc:
+        raise ValueError(f"{os.path.join(path, TOMBSTONE_FILE)}: {exc}") from None
+    tombstone['path'] = path
+    return tombstone
+
+
+def list_entries(trash_dir: str) -> List[Dict[str, Any]]:
+    """Every tombstone in the trash, most recently deleted first."""
+    try:
+        names = os.listdir(trash_dir)
+    except FileNotFoundError:
+        return []
+    entries = []
+    for name in names:
+        try:
+            entries.append(read_entry(trash_dir, name))
+        except ValueError:
+            continue                        # not an entry (or a broken one): leave it be
+    return sorted(entries, key=lambda e: e.get('deleted_at', 0), reverse=True)
+
+
+def restore_file(tombstone: Dict[str, Any]):
+    """Move the promoted file back where it was; ValueError if that path is taken."""
+    original = tombstone.get('file', '')
+    if not original:
+        return
+    if os.path.exists(original):
+        raise ValueError(f"Cannot restore {tombstone['staging_id']}: {original} exists")
+    os.makedirs(os.path.dirname(original) or '.', exist_ok=True)
+    shutil.move(os.path.join(tombstone['path'], os.path.basename(original)), original)
+
+
+def remove_entry(trash_dir: str, staging_id: str):
+    shutil.rmtree(entry_dir(trash_dir, staging_id), ignore_errors=True)
+
+
+def expired(entries: List[Dict[str, Any]], trash_days: float,
+            now: Optional[float] = None) -> List[Dict[str, Any]]:
+    """The entries deleted more than ``trash_days`` ago (0: keep everything)."""
+    if trash_days <= 0:
+        return []
+    cutoff = (time.time() if now is None else now) - trash_days * 86400
+    return [e for e in entries if e.get('deleted_at', 0) < cutoff]
+
+
+def format_entries(entries: List[Dict[str, Any]], trash_days: float = 0) -> str:
+    """The trash as ``spokedpy trash list`` prints it."""
+    if not entries:
+        return '  the trash is empty'
+    width = max(len(e.get('label', '')) for e in entries)
+    lines = []
+    for e in entries:
+        deleted = time.strftime('%Y-%m-%dT%H:%M:%SZ', time.gmtime(e.get('deleted_at', 0)))
+        by = f" by {e['deleted_by']}" if e.get('deleted_by') else ''
+        reason = f" — {e['reason']}" if e.get('reason') else ''
+        lines.append(f"  {e['staging_id']}  {e.get('slot') or '—':<4} {e.get('phase', ''):<11} "
+                     f"{e.get('label', ''):<{width}}  deleted {deleted}{by}{reason}")
+    if trash_days > 0:
+        lines.append(f"  entries are purged {trash_days:g} day(s) after they were deleted")
+    return '\n'.join(lines)
@@ -0,0 +1,245 @@
+"""
+Source Ingest — stage a whole directory of pre-spokedpy source files.
+
+    spokedpy ingest ./legacy-snippets [--lang auto] [--label-from filename]
+
+walks the directory (hidden dirs and build / dependency dirs left out),
+works out each file's language (language_detect, or --lang for all of
+them) and label, and stages every file on the running server with a
+dry-run, its ``<stem>.spec.*`` sidecar attached as ``spokedpy stage``
+would.  Each snippet gets ``ingested-from`` metadata — the file's path
+under the directory — which promotion writes into the generated banner
+as an ``x-ingested-from:`` line.  A file that already has a spokedpy
+banner is staged with its body and recorded language, its staging_id as
+the parent (see lineage).
+
+Files whose language is unknown or ambiguous, that are not UTF-8 text, are empty
+or bigger than MAX_FILE_BYTES are skipped and listed in the report with
+the reason; so are spec sidecars and golden files, which travel with
+their snippet.
+"""
+
+import os
+from dataclasses import dataclass, field
+from typing import Any, Callable, Dict, List, Optional
+
+from .language_detect import Detection, detect
+from .snippet_staging import parse_file_header
+from .spec_suite import SPEC_SUFFIXES, sidecar_for, spec_format
+
+MAX_FILE_BYTES = 1024 * 1024
+LABEL_FROM = ('filename', 'dir', 'path')
+META_KEY = 'ingested-from'
+
+# Directories never walked into (besides hidden ones)
+SKIP_DIRS = frozenset({'node_modules', 'target', 'build', 'dist', 'out', 'bin', 'obj',
+                       '__pycache__', 'venv', 'snapshots'})
+
+
+@dataclass
+class IngestItem:
+    path: str                            # relative to the ingested dir, '/'-separated
+    language: str = ''
+    detected_by: str = ''                # 'flag', 'header', 'extension', 'shebang', 'probes'
+    confidence: float = 0.0
+    label: str = ''
+    spec: str = ''                       # sidecar path, relative
+    skipped: str = ''                    # why it was not staged
+    staging_id: str = ''
+    slot: str = ''
+    phase: str = ''
+    spec_success: Optional[bool] = None
+    error: str = ''                      # staging refused, or the dry-run's error
+
+    @property
+    def failed(self) -> bool:
+        return bool(self.error) or self.spec_success is False
+
+    def to_dict(self) -> Dict[str, Any]:
+        return dict(self.__dict__)
+
+
+@dataclass
+class IngestReport:
+    root: str
+    dry_run: bool = False
+    items: List[IngestItem] = field(default_factory=list)
+
+    @property
+    def staged(self) -> List[IngestItem]:
+        return [i for i in self.items if i.staging_id]
+
+    @property
+    def skipped(self) -> List[IngestItem]:
+        return [i for i in self.items if i.skipped]
+
+    @property
+    def failed(self) -> List[IngestItem]:
+        return [i for i in self.items if i.failed]
+
+    def languages(self) -> Dict[str, int]:
+        counts: Dict[str, int] = {}
+        for item in self.items:
+            if not item.skipped:
+                counts[item.language] = counts.get(item.language, 0) + 1
+        return dict(sorted(counts.items(), key=lambda kv: (-kv[1], kv[0])))
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {'root': self.root, 'dry_run': self.dry_run, 'files': len(self.items),
+                'staged': len(self.staged), 'failed': len(self.failed),
+                'skipped': len(self.skipped), 'languages': self.languages(),
+                'items': [i.to_dict() for i in self.items]}
+
+
+def _is_spec_file(name: str) -> bool:
+    return any(name.endswith(suffix) for suffix in SPEC_SUFFIXES) or name.endswith('.snap')
+
+
+def source_files(root: str) -> List[str]:
+    """The files under ``root`` an ingest looks at, sorted."""
+    found = []
+    for folder, dirs, names in os.walk(root):
+        dirs[:] = sorted(d for d in dirs if not d.startswith('.') and d not in SKIP_DIRS)
+        found.extend(os.path.join(folder, name) for name in sorted(names)
+                     if not name.startswith('.'))
+    return found
+
+
+def label_for(rel_path: str, label_from: str, root: str) -> str:
+    stem = os.path.splitext(rel_path)[0]
+    if label_from == 'filename':
+        return os.path.basename(stem)
+    if label_from == 'dir':
+        parent = os.path.dirname(rel_path)
+        return os.path.basename(parent) if parent else os.path.basename(os.path.abspath(root))
+    if label_from == 'path':
+        return stem
+    raise ValueError(f"--label-from '{label_from}': choose from {', '.join(LABEL_FROM)}")
+
+
+def plan(root: str, lang: str = 'auto', label_from: str = 'filename') -> List[IngestItem]:
+    """What ingesting ``root`` would stage: one IngestItem per file (skipped ones say why)."""
+    if not os.path.isdir(root):
+        raise FileNotFoundError(2, 'No such directory', root)
+    from .snippet_repl import engine_letter
+
+    label_for('', label_from, root)                                 # refuse a bad choice early
+    lang = '' if lang in ('', 'auto') else lang.lower().strip()
+    if lang:
+        engine_letter(lang)
+    items = []
+    for full in source_files(root):
+        rel = os.path.relpath(full, root).replace(os.sep, '/')
+        item = IngestItem(rel)
+        items.append(item)
+        if _is_spec_file(rel):
+            item.skipped = 'spec sidecar or golden file (staged with its snippet)'
+            continue
+        size = os.path.getsize(full)
+        if not size or size > MAX_FILE_BYTES:
+            item.skipped = 'empty' if not size else f"bigger than {MAX_FILE_BYTES // 1024} KiB"
+            continue
+        try:
+            with open(full, 'r', encoding='utf-8') as f:
+                text = f.read()
+        except UnicodeDecodeError:
+            item.skipped = 'not UTF-8 text'
+            continue
+        if lang:
+            item.language, item.detected_by, item.confidence = lang, 'flag', 1.0
+        else:
+            found = detect(full, text)
+            if not found.language:
+                item.skipped = f"cannot tell the language ({found.describe()}; pass --lang)"
+                continue
+            item.language, item.detected_by, item.confidence = \
+                found.language, found.how, found.confidence
+            try:
+                engine_letter(item.language)
+            except ValueError:
+                item.skipped = f"no engine for the recorded language '{item.language}'"
+                continue
+        item.label = label_for(rel, label_from, root)
+        sidecar = sidecar_for(full)
+        if sidecar is not None:
+            item.spec = os.path.relpath(sidecar, root).replace(os.sep, '/')
+    return items
+
+
+def stage_body(root: str, item: IngestItem, author: str = '',
+               meta: Optional[Dict[str, str]] = None) -> Dict[str, Any]:
+    """The /api/staging/queue body for an item (engine_letter, code, spec, meta …)."""
+    from .snippet_repl import engine_letter
+
+    with open(os.path.join(root, item.path), 'r', encoding='utf-8') as f:
+        code = f.read()
+    header = parse_file_header(code)
+    parent = header.fields.get('staging_id', '') if header is not None else ''
+    if header is not None:
+        code = header.body
+    detected = Detection(item.language, item.confidence, item.detected_by).meta()
+    body = {'engine_letter': engine_letter(item.language), 'language': item.language,
+            'code': code, 'label': item.label, 'author': author,
+            'meta': {**(meta or {}), **detected, META_KEY: item.path}}
+    if parent:
+        body['parent'] = parent
+    if item.spec:
+        spec_path = os.path.join(root, item.spec)
+        with open(spec_path, 'r', encoding='utf-8') as f:
+            body.update(spec=f.read(), spec_format=spec_format(spec_path))
+    return body
+
+
+def ingest(root: str, stage: Callable[[Dict[str, Any]], Dict[str, Any]], lang: str = 'auto',
+           label_from: str = 'filename', author: str = '',
+           meta: Optional[Dict[str, str]] = None, dry_run: bool = False) -> IngestReport:
+    """
+    Stage every file of ``root`` with ``stage`` (queue body → the dry-run
+    snippet).  A ValueError from ``stage`` (staging refused, slot full) is
+    recorded on that file and the ingest goes on; anything else — the
+    server went away — aborts it.
+    """
+    report = IngestReport(os.path.abspath(root), dry_run, plan(root, lang, label_from))
+    if dry_run:
+        return report
+    for item in report.items:
+        if item.skipped:
+            continue
+        try:
+            snippet = stage(stage_body(root, item, author, meta))
+        except ValueError as exc:
+            item.error = str(exc)
+            continue
+        item.staging_id = snippet.get('staging_id', '')
+        item.slot = snippet.get('reserved_address', '')
+        item.phase = snippet.get('phase', '')
+        item.spec_success = bool(snippet.get('spec_success'))
+        if not item.spec_success:
+            item.error = (snippet.get('spec_error') or 'dry-run failed').strip().splitlines()[0]
+    return report
+
+
+def format_ingest(report: IngestReport) -> str:
+    verb = 'would stage' if report.dry_run else 'staged'
+    count = len(report.items) - len(report.skipped) if report.dry_run else len(report.staged)
+    lines = [f"  ingest {report.root}: {len(report.items)} file(s) — {verb} {count}"
+             + ('' if report.dry_run else f" ({len(report.failed)} failed)")
+             + f", {len(report.skipped)} skipped"]
+    width = max((len(i.path) for i in report.items), default=0)
+    for item in report.items:
+        if item.skipped:
+            lines.append(f"    – {item.path:<{width}}  skipped: {item.skipped}")
+            continue
+        mark = '✘' if item.failed else '✔'
+        line = f"    {mark} {item.path:<{width}}  {item.language:<10} {item.label}"
+        if item.staging_id:
+            line += f"  {item.staging_id} {item.slot} {item.phase}"
+        elif report.dry_run:
+            line += f"  ({item.detected_by}{', spec ' + item.spec if item.spec else ''})"
+        if item.error:
+            line += f" — {item.error}"
+        lines.append(line)
+    languages = report.languages()
+    if languages:
+        lines.append('  by language: ' + ', '.join(f"{k} {v}" for k, v in languages.items()))
+    return '\n'.join(lines)
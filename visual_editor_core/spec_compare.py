@@ -0,0 +1,268 @@
+"""
+Spec Compare — A/B runs of two candidates on the same spec cases.
+
+``spokedpy compare --label Fibonacci stg-A stg-B --cases cases.toml`` runs
+both snippets in the speculation sandbox, case by case and alternating
+between them, so neither gets a warmer cache.  The report puts them side
+by side:
+
+    case     A                         B                         output
+    fib(10)  ✔   0.0213s   9.8 MB      ✔   0.0190s   9.6 MB      same
+    fib(30)  ✔   0.4102s   9.9 MB      ✔   0.0201s   9.7 MB      same
+
+and recommends the candidate that passes more cases, then the faster one,
+then the leaner one.  Python cases run as their own process and report
+peak RSS; other engines go through their executor, which reports timing
+only.  With ``repeat`` above 1 each case runs that many times per
+candidate and the median duration counts.  Every run is a dry-run like
+any other: in a scratch dir of its own under the write guard and the
+seccomp profile, at the network level of its candidate.
+"""
+
+import os
+import statistics
+import subprocess
+import sys
+import threading
+from dataclasses import asdict, dataclass, field
+from typing import Any, Callable, ContextManager, Dict, List, Optional
+
+from .spec_suite import SpecSuite, run_case
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# MEASURING
+# ═══════════════════════════════════════════════════════════════════════════
+
+class MeasuredRunner:
+    """``subprocess.run`` stand-in that records the child's peak RSS (kB).
+
+    The child starts as _run_subprocess would start it: in this thread's
+    scratch dir, under the seccomp profile and the network policy.  Needs
+    ``os.wait4`` (POSIX); elsewhere it runs normally and records None.
+    """
+
+    def __init__(self):
+        self.peak_kb: Optional[int] = None
+
+    def __call__(self, argv, input=None, timeout=None, env=None, **kwargs):
+        self.peak_kb = None
+        if not hasattr(os, 'wait4'):
+            from .execution_engine import _run_subprocess
+            return _run_subprocess(argv, input=input, timeout=timeout, env=env,
+                                   capture_output=True, text=True)
+        from . import offline, scratch, seccomp
+        args, kwargs = scratch.apply((argv,), {**kwargs, 'env': env})
+        args, kwargs = seccomp.apply(args, kwargs)
+        (argv,), kwargs = offline.apply(args, kwargs)
+        proc = subprocess.Popen(argv, stdin=subprocess.PIPE, stdout=subprocess.PIPE,
+                                stderr=subprocess.PIPE, text=True, encoding='utf-8',
+                                errors='replace', env=kwargs.get('env'), cwd=kwargs.get('cwd'))
+        streams: Dict[str, str] = {}
+
+        def feed():
+            try:
+                proc.stdin.write(input or '')
+                proc.stdin.close()
+            except OSError:
+                pass                                    # child exited without reading
+
+        def drain(name, pipe):
+            streams[name] = pipe.read()
+
+        threads = [threading.Thread(target=feed, daemon=True),
+                   threading.Thread(target=drain, args=('stdout', proc.stdout), daemon=True),
+                   threading.Thread(target=drain, args=('stderr', proc.stderr), daemon=True)]
+        for t in threads:
+            t.start()
+        timed_out = threading.Event()
+
+        def kill():
+            timed_out.set()
+            proc.kill()
+
+        timer = threading.Timer(timeout, kill) if timeout else None
+        if timer:
+            timer.start()
+        try:
+            _, status, usage = os.wait4(proc.pid, 0)
+        finally:
+            if timer:
+                timer.cancel()
+        proc.returncode = os.waitstatus_to_exitcode(status)
+        for t in threads:
+            t.join()
+        if timed_out.is_set():
+            raise subprocess.TimeoutExpired(argv, timeout)
+        # ru_maxrss is kB on Linux, bytes on macOS.
+        self.peak_kb = usage.ru_maxrss // 1024 if sys.platform == 'darwin' else usage.ru_maxrss
+        return subprocess.CompletedProcess(argv, proc.returncode, streams.get('stdout', ''),
+                                           streams.get('stderr', ''))
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# COMPARING
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class Side:
+    """One candidate's result for one case."""
+    passed: bool
+    duration: float                          # median over ``repeat`` runs
+    peak_kb: Optional[int] = None            # max over the runs (None: not measured)
+    output: str = ''
+    error: str = ''
+
+
+@dataclass
+class CaseComparison:
+    name: str
+    a: Side
+    b: Side
+
+    @property
+    def same_output(self) -> bool:
+        return self.a.output.replace('\r\n', '\n') == self.b.output.replace('\r\n', '\n')
+
+
+@dataclass
+class Comparison:
+    label: str
+    a: Dict[str, Any]                        # staging_id, slot, language, code_hash
+    b: Dict[str, Any]
+    repeat: int
+    cases: List[CaseComparison] = field(default_factory=list)
+
+    def totals(self, side: str) -> Dict[str, Any]:
+        sides = [getattr(c, side) for c in self.cases]
+        peaks = [s.peak_kb for s in sides if s.peak_kb is not None]
+        return {'passed': sum(s.passed for s in sides), 'cases': len(sides),
+                'duration': sum(s.duration for s in sides),
+                'peak_kb': max(peaks) if peaks else None}
+
+    def recommendation(self) -> Dict[str, str]:
+        """``{'winner': 'a' | 'b' | '', 'reason': …}`` — passes, then time, then memory."""
+        ta, tb = self.totals('a'), self.totals('b')
+        if ta['passed'] != tb['passed']:
+            side = 'a' if ta['passed'] > tb['passed'] else 'b'
+            return {'winner': side, 'reason': f"passes {max(ta['passed'], tb['passed'])}"
+                                              f"/{ta['cases']} cases vs {min(ta['passed'], tb['passed'])}"}
+        fast, slow = sorted((ta['duration'], tb['duration']))
+        # Under 5 % apart is noise for a handful of short runs.
+        if slow > 0 and (slow - fast) / slow >= 0.05:
+            side = 'a' if ta['duration'] < tb['duration'] else 'b'
+            return {'winner': side, 'reason': f"{slow / max(fast, 1e-9):.1f}× faster "
+                                              f"({fast:.4f}s vs {slow:.4f}s)"}
+        if ta['peak_kb'] and tb['peak_kb'] and ta['peak_kb'] != tb['peak_kb']:
+            side = 'a' if ta['peak_kb'] < tb['peak_kb'] else 'b'
+            low, high = sorted((ta['peak_kb'], tb['peak_kb']))
+            return {'winner': side, 'reason': f"similar speed, less memory "
+                                              f"({low / 1024:.1f} MB vs {high / 1024:.1f} MB)"}
+        return {'winner': '', 'reason': 'no measurable difference'}
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            'label': self.label, 'a': self.a, 'b': self.b, 'repeat': self.repeat,
+            'cases': [{'name': c.name, 'a': asdict(c.a), 'b': asdict(c.b),
+                       'same_output': c.same_output} for c in self.cases],
+            'totals': {'a': self.totals('a'), 'b': self.totals('b'),
+                       'same_output': sum(c.same_output for c in self.cases)},
+            'recommendation': self.recommendation(),
+        }
+
+
+def _candidate(snippet) -> Dict[str, Any]:
+    return {'staging_id': snippet.staging_id, 'slot': snippet.reserved_address,
+            'language': snippet.language, 'code_hash': snippet.code_hash,
+            'phase': snippet.phase.value}
+
+
+def _run_side(snippet, case, run: Callable[[str, str], Dict[str, Any]],
+              runner_factory: Callable[[], MeasuredRunner], repeat: int,
+              sandbox: bool, fixtures: Dict[str, Optional[str]],
+              dry_run: Callable[[Any], ContextManager]) -> Side:
+    if snippet.language != 'python' and case.needs_process():
+        reason = f"the {snippet.language} executor cannot pass args, stdin or env"
+        return Side(False, 0.0, error=reason)
+    durations, peaks, checked = [], [], None
+    for _ in range(repeat):
+        runner = runner_factory()
+        with dry_run(snippet):
+            checked = run_case(case, snippet.language, snippet.code, run, runner, sandbox,
+                               fixtures)
+        if runner.peak_kb is not None:
+            peaks.append(runner.peak_kb)
+        durations.append(checked.duration)
+    return Side(checked.passed, statistics.median(durations), max(peaks) if peaks else None,
+                checked.output, checked.error)
+
+
+def compare(suite: SpecSuite, a, b, run: Callable[[str, str], Dict[str, Any]],
+            label: str = '', repeat: int = 1,
+            runner_factory: Callable[[], MeasuredRunner] = MeasuredRunner,
+            sandbox: bool = False,
+            dry_run: Optional[Callable[[Any], ContextManager]] = None) -> Comparison:
+    """Run every case of ``suite`` against snippets ``a`` and ``b``, interleaved.
+
+    ``run(language, code)`` is the engine dry-run for non-Python snippets;
+    with ``sandbox`` each run gets a scratch dir (see run_case).  Each run
+    happens inside ``dry_run(snippet)`` (default: the snippet's network
+    level, see offline.spec_network).
+    """
+    from .offline import network_for, spec_network
+    from .output_stream import emit
+    if dry_run is None:
+        def dry_run(snippet):
+            return spec_network(network_for(snippet.language, snippet.network))
+    repeat = max(1, int(repeat))
+    report = Comparison(label or a.label, _candidate(a), _candidate(b), repeat)
+    for case in suite.cases:
+        emit('case_started', name=case.name)
+        side_a = _run_side(a, case, run, runner_factory, repeat, sandbox, suite.fixtures, dry_run)
+        side_b = _run_side(b, case, run, runner_factory, repeat, sandbox, suite.fixtures, dry_run)
+        report.cases.append(CaseComparison(case.name, side_a, side_b))
+        emit('case_finished', name=case.name, passed=side_a.passed and side_b.passed,
+             duration=side_a.duration + side_b.duration, first_diff='')
+    return report
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# REPORTING — spokedpy compare
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _memory(peak_kb: Optional[int]) -> str:
+    return '     —' if peak_kb is None else f"{peak_kb / 1024:>5.1f} MB"
+
+
+def _side(side: Dict[str, Any]) -> str:
+    return f"{'✔' if side['passed'] else '✘'} {side['duration']:>8.4f}s {_memory(side['peak_kb'])}"
+
+
+def format_comparison(report: Dict[str, Any]) -> str:
+    a, b = report['a'], report['b']
+    width = max([len('total')] + [len(c['name']) for c in report['cases']])
+    lines = [
+        f"  {report['label']}: A = {a['staging_id']} ({a['slot']}, {a['phase']})  "
+        f"B = {b['staging_id']} ({b['slot']}, {b['phase']})"
+        + (f"  — median of {report['repeat']} runs" if report['repeat'] > 1 else ''),
+        f"    {'case':<{width}}  {'A':<24}  {'B':<24}  output",
+    ]
+    for c in report['cases']:
+        lines.append(f"    {c['name']:<{width}}  {_side(c['a']):<24}  {_side(c['b']):<24}  "
+                     f"{'same' if c['same_output'] else 'DIFFERS'}")
+    ta, tb = (report['totals'][side] for side in ('a', 'b'))
+    total_a, total_b = (f"{t['passed']}/{t['cases']} {t['duration']:>7.4f}s {_memory(t['peak_kb'])}"
+                        for t in (ta, tb))
+    lines.append(f"    {'total':<{width}}  {total_a:<24}  {total_b:<24}  "
+                 f"{report['totals']['same_output']}/{len(report['cases'])} same")
+    for c in report['cases']:
+        for side in ('a', 'b'):
+            if c[side]['error'] and not c[side]['passed']:
+                lines.append(f"    {side.upper()} ✘ {c['name']}: {c[side]['error']}")
+    rec = report['recommendation']
+    if rec['winner']:
+        lines.append(f"  → promote {rec['winner'].upper()} "
+                     f"({report[rec['winner']]['staging_id']}): {rec['reason']}")
+    else:
+        lines.append(f"  → {rec['reason']}")
+    return '\n'.join(lines)
@@ -0,0 +1,318 @@
+"""
+Spec Mutation — does the dry-run actually constrain the snippet?
+
+A snippet's spec is its speculative run: it must succeed and print what it
+printed.  A spec like ``print(fib(20))`` only pins one value; a spec that
+prints nothing pins none.  ``spokedpy spec mutate <staging_id>`` measures
+that by re-running small single-point mutations of the snippet:
+
+    ┌──────────────┬──────────────────────────────────────────────────────┐
+    │  operator    │  + ↔ -   * ↔ /   < ↔ <=   > ↔ >=   == ↔ !=         │
+    │              │  && ↔ ||   and ↔ or   += ↔ -=                        │
+    │  constant    │  integer n → n + 1;  true ↔ false                    │
+    └──────────────┴──────────────────────────────────────────────────────┘
+
+A mutant is KILLED when its run fails, times out, or prints different
+output than the original; otherwise it SURVIVED — the spec could not tell
+the mutated code apart.  kill rate = killed / mutants run.
+
+Mutations skip string literals and comments and only touch operators in
+binary position, so most mutants still compile.  Runs are child processes
+with a timeout (mutants easily loop forever); a snippet whose own output
+changes between two runs is compared on success/failure only.
+
+The ``mutation`` built-in gate denies promotion below
+``[gates] mutation_min_kill_rate`` (default 0.6).
+"""
+
+import re
+import time
+from dataclasses import dataclass, field, asdict
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+from .promotion_gates import GateDecision, GateError, PromotionGate
+
+
+DEFAULT_LIMIT = 40
+DEFAULT_MIN_KILL_RATE = 0.6
+MIN_TIMEOUT = 5.0               # seconds per mutant, at least
+TIMEOUT_FACTOR = 10             # … or this many times the original's run time
+
+_HASH_COMMENT = ('python', 'ruby', 'r', 'bash', 'perl')
+# String delimiters per language (Rust: ' also starts lifetimes, so only ")
+_QUOTES = {'javascript': '"\'`', 'typescript': '"\'`', 'go': '"\'`', 'rust': '"'}
+
+_OPERATORS = ('**=', '<<=', '>>=', '...', '==', '!=', '<=', '>=', '&&', '||', '->', '=>', '::',
+              '+=', '-=', '*=', '/=', '++', '--', '**', '//', '<<', '>>', '<-', '|>')
+_SWAPS = {
+    '+': '-', '-': '+', '*': '/', '/': '*',
+    '<': '<=', '<=': '<', '>': '>=', '>=': '>',
+    '==': '!=', '!=': '==', '&&': '||', '||': '&&',
+    '+=': '-=', '-=': '+=',
+}
+_SPACED_ONLY = ('<', '>')       # unspaced < > are usually generics
+_WORD_SWAPS = {
+    'python': {'and': 'or', 'or': 'and', 'True': 'False', 'False': 'True'},
+    'r':      {'TRUE': 'FALSE', 'FALSE': 'TRUE'},
+}
+_DEFAULT_WORD_SWAPS = {'true': 'false', 'false': 'true'}
+_OPERAND_END = re.compile(r'[\w)\]]')
+_OPERAND_START = re.compile(r'[\w(\[]')
+
+
+@dataclass
+class Mutant:
+    """One single-point mutation of the snippet."""
+    index: int
+    line: int                   # 1-based
+    original: str
+    replacement: str
+    code: str = field(repr=False, default='')
+    status: str = ''            # 'killed' | 'survived' | 'timeout'
+    detail: str = ''
+
+    @property
+    def description(self) -> str:
+        return f"line {self.line}: {self.original} → {self.replacement}"
+
+    def to_dict(self) -> Dict[str, Any]:
+        d = asdict(self)
+        d.pop('code')
+        d['description'] = self.description
+        d['mutated_line'] = self.code.splitlines()[self.line - 1].strip() if self.code else ''
+        return d
+
+
+@dataclass
+class MutationReport:
+    staging_id: str
+    language: str
+    total: int = 0                           # mutation sites found
+    run: int = 0                             # mutants executed
+    killed: int = 0
+    deterministic: bool = True
+    duration: float = 0.0
+    mutants: List[Mutant] = field(default_factory=list)
+
+    @property
+    def kill_rate(self) -> float:
+        return self.killed / self.run if self.run else 0.0
+
+    @property
+    def survivors(self) -> List[Mutant]:
+        return [m for m in self.mutants if m.status == 'survived']
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            'staging_id': self.staging_id, 'language': self.language,
+            'total': self.total, 'run': self.run, 'killed': self.killed,
+            'kill_rate': round(self.kill_rate, 4), 'deterministic': self.deterministic,
+            'duration': self.duration, 'mutants': [m.to_dict() for m in self.mutants],
+        }
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# MUTANT GENERATION
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _code_spans(code: str, language: str) -> List[Tuple[int, int]]:
+    """(start, end) ranges of ``code`` outside strings and comments."""
+    hash_comment = language in _HASH_COMMENT
+    quotes = _QUOTES.get(language, '"\'')
+    spans, start, i, n = [], 0, 0, len(code)
+    while i < n:
+        ch = code[i]
+        end = None
+        if hash_comment and ch == '#' or not hash_comment and code.startswith('//', i):
+            end = code.find('\n', i)
+            end = n if end < 0 else end
+        elif not hash_comment and code.startswith('/*', i):
+            end = code.find('*/', i + 2)
+            end = n if end < 0 else end + 2
+        elif ch in quotes:
+            delim = code[i:i + 3] if language == 'python' and code[i:i + 3] in ('"""', "'''") else ch
+            j = i + len(delim)
+            while j < n and not code.startswith(delim, j):
+                j += 2 if code[j] == '\\' else 1
+            end = min(n, j + len(delim))
+        if end is None:
+            i += 1
+            continue
+        if i > start:
+            spans.append((start, i))
+        start = i = end
+    if start < n:
+        spans.append((start, n))
+    return spans
+
+
+def _neighbour(text: str, pos: int, step: int) -> str:
+    while 0 <= pos < len(text) and text[pos] in ' \t':
+        pos += step
+    return text[pos] if 0 <= pos < len(text) else ''
+
+
+def _sites(code: str, language: str) -> List[Tuple[int, int, str]]:
+    """Mutation sites as (offset, length, replacement)."""
+    words = _WORD_SWAPS.get(language, _DEFAULT_WORD_SWAPS)
+    sites = []
+    for start, end in _code_spans(code, language):
+        i = start
+        while i < end:
+            ch = code[i]
+            if ch.isalpha() or ch == '_':
+                j = i
+                while j < end and (code[j].isalnum() or code[j] == '_'):
+                    j += 1
+                if code[i:j] in words:
+                    sites.append((i, j - i, words[code[i:j]]))
+                i = j
+                continue
+            if ch.isdigit():
+                j = i
+                while j < end and (code[j].isalnum() or code[j] in '._'):
+                    j += 1
+                token = code[i:j]
+                prev = code[i - 1] if i else ''
+                if token.isdigit() and prev != '.' and len(token) < 10:
+                    sites.append((i, j - i, str(int(token) + 1)))
+                i = j
+                continue
+            op = next((o for o in _OPERATORS if code.startswith(o, i) and i + len(o) <= end), ch)
+            if op in _SWAPS and _binary(code, i, len(op), op in _SPACED_ONLY):
+                sites.append((i, len(op), _SWAPS[op]))
+            i += len(op)
+    return sites
+
+
+def _binary(code: str, i: int, length: int, spaced_only: bool) -> bool:
+    """True if the operator at ``i`` sits between two operands."""
+    if spaced_only:
+        before = code[i - 1] if i else '\n'
+        after = code[i + length] if i + length < len(code) else '\n'
+        if before not in (' ', '\t') or after not in (' ', '\t'):
+            return False
+    left, right = _neighbour(code, i - 1, -1), _neighbour(code, i + length, 1)
+    return bool(left and right and _OPERAND_END.match(left) and
+                (_OPERAND_START.match(right) or right in '-!"\''))
+
+
+def generate_mutants(code: str, language: str, limit: int = DEFAULT_LIMIT) -> Tuple[List[Mutant], int]:
+    """Up to ``limit`` mutants spread evenly over the snippet, and the site count."""
+    sites = _sites(code, language)
+    picked = sites
+    if limit and len(sites) > limit:
+        step = len(sites) / limit
+        picked = [sites[int(k * step)] for k in range(limit)]
+    mutants = []
+    for index, (offset, length, replacement) in enumerate(picked, 1):
+        mutated = code[:offset] + replacement + code[offset + length:]
+        mutants.append(Mutant(index, code.count('\n', 0, offset) + 1,
+                              code[offset:offset + length], replacement, mutated))
+    return mutants, len(sites)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# EXECUTION
+# ═══════════════════════════════════════════════════════════════════════════
+
+Run = Callable[[str, str, float], Dict[str, Any]]        # (language, code, timeout) → result
+
+
+def isolated_runner(evaluate: Callable[..., Dict[str, Any]]) -> Run:
+    """A Run for mutants through ``evaluate(language, code, timeout=)``.
+
+    ``evaluate`` is the pipeline's sandbox (StagingPipeline.evaluate): each
+    mutant gets a scratch dir, the seccomp profile and the network policy,
+    and Python runs as a child process killed at the timeout; the
+    subprocess engines already enforce their own limits.
+    """
+    def run(language: str, code: str, timeout: float) -> Dict[str, Any]:
+        result = evaluate(language, code, timeout=timeout)
+        if result.get('timed_out'):
+            return {**result, 'error': 'timeout', 'execution_time': timeout}
+        return result
+    return run
+
+
+def run_mutation(staging_id: str, language: str, code: str, run: Run,
+                 limit: int = DEFAULT_LIMIT, budget: Optional[float] = None) -> MutationReport:
+    """Run the original twice (baseline + determinism check), then each mutant.
+
+    ``budget`` (seconds) stops launching mutants once exceeded; mutants not
+    run are left out of the kill rate.
+    """
+    started = time.time()
+    first = run(language, code, MIN_TIMEOUT * 6)
+    if not first.get('success'):
+        raise ValueError(f"Snippet {staging_id} fails its own spec run "
+                         f"({(first.get('error') or 'no error message').strip()[:200]}); "
+                         f"fix it before mutation testing")
+    second = run(language, code, MIN_TIMEOUT * 6)
+    report = MutationReport(staging_id, language,
+                            deterministic=first.get('output') == second.get('output'))
+    timeout = max(MIN_TIMEOUT, TIMEOUT_FACTOR * max(first.get('execution_time', 0),
+                                                    second.get('execution_time', 0)))
+
+    report.mutants, report.total = generate_mutants(code, language, limit)
+    for mutant in report.mutants:
+        if budget is not None and time.time() - started > budget:
+            break
+        result = run(language, mutant.code, timeout)
+        if result.get('timed_out'):
+            mutant.status, mutant.detail = 'timeout', f"no result within {timeout:.0f}s"
+        elif not result.get('success'):
+            mutant.status = 'killed'
+            mutant.detail = (result.get('error') or 'failed').strip().splitlines()[-1][:200] \
+                if (result.get('error') or '').strip() else 'failed'
+        elif report.deterministic and result.get('output') != first.get('output'):
+            mutant.status, mutant.detail = 'killed', 'output differs'
+        else:
+            mutant.status = 'survived'
+        report.run += 1
+        report.killed += mutant.status != 'survived'
+    report.mutants = [m for m in report.mutants if m.status]
+    report.duration = time.time() - started
+    return report
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# GATE
+# ═══════════════════════════════════════════════════════════════════════════
+
+class MutationGate(PromotionGate):
+    """Built-in gate: deny when the spec kills too few mutants."""
+
+    kind = 'builtin'
+
+    def __init__(self, run: Run, name: str = 'mutation',
+                 min_kill_rate: float = DEFAULT_MIN_KILL_RATE,
+                 limit: int = DEFAULT_LIMIT, seconds: Optional[float] = None):
+        super().__init__(name)
+        if not 0 <= min_kill_rate <= 1:
+            raise ValueError('mutation_min_kill_rate must be between 0 and 1')
+        self._run = run
+        self.min_kill_rate = min_kill_rate
+        self.limit = limit
+        self.seconds = seconds
+
+    def check(self, header, body) -> GateDecision:
+        try:
+            report = run_mutation(header.get('staging_id', ''), header.get('language', ''),
+                                  body, self._run, self.limit, self.seconds)
+        except ValueError as exc:
+            raise GateError(str(exc))
+        if not report.run:
+            return GateDecision(self.name, True, ['skipped: nothing to mutate'])
+        summary = (f"spec killed {report.killed}/{report.run} mutants "
+                   f"({report.kill_rate:.0%}, minimum {self.min_kill_rate:.0%})")
+        if report.kill_rate >= self.min_kill_rate:
+            return GateDecision(self.name, True, [summary])
+        reasons = [summary] + [f"survived {m.description}" for m in report.survivors[:5]]
+        if not report.deterministic:
+            reasons.append('output varies between runs, so only crashes count as kills')
+        return GateDecision(self.name, False, reasons)
+
+    def describe(self):
+        return {**super().describe(), 'min_kill_rate': self.min_kill_rate,
+                'limit': self.limit, 'seconds': self.seconds}
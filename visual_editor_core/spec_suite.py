@@ -0,0 +1,687 @@
+"""
+Spec Suites — declarative spec cases in a ``*.spec.toml`` sidecar.
+
+Next to a snippet file ``etl.py`` an ``etl.spec.toml`` (or ``.spec.yaml`` /
+``.spec.yml``, which needs PyYAML) lists the cases the dry-run must pass:
+
+    timeout = 10                    # default for every case (seconds)
+    env = { TZ = "UTC" }            # merged under each case's env
+    fixtures = ["data/in.csv", "testdata/"]     # read-only in each case's scratch dir
+
+    [[case]]
+    name = "two fields"
+    args = ["--sep", ","]
+    stdin = "a,b\\n"
+    stdout = "2\\n"                  # omitted → output is not compared
+    exit_code = 0
+
+    [[case]]
+    name = "rejects empty input"
+    exit_code = 1
+
+    [[case]]
+    name = "report"
+    snapshot = true                 # expected stdout lives in a golden file
+
+A case with a ``[case.generate]`` table is a matrix: it expands into one
+case per combination of its variables, substituted as ``$n`` / ``${n}``
+into the name, args, stdin, stdout and env values (``$$`` is a ``$``):
+
+    [[case]]
+    name = "fib($n)"                # without a variable: "fib [n=0]", …
+    args = ["$n"]
+    snapshot = true                 # one golden file per generated case
+    [case.generate]
+    n = { range = [0, 40] }         # stop included; [start, stop, step] too
+
+A variable is a list of values (``sep = [",", ";"]``), a ``range`` or
+``{ random = [low, high], count = 5 }`` — distinct values drawn from
+``seed`` (default 0), so reproducible.  Several variables make their cartesian product, or
+with ``mode = "zip"`` are paired up in order (``n = [1, 2, 3]`` next to
+``fib = ["1", "1", "2"]``).  parse_spec expands the matrix — at most
+MAX_GENERATED_CASES cases a suite — so the runner, spec_hash, snapshots
+and label specs all see plain cases.
+
+A case that expects stdout (given, or from its golden file) is diffed as
+it runs: each complete line is compared on arrival, and the first one
+that differs — or one past the expected end — stops the run, so a
+long-running spec fails in the divergent line's time instead of its
+timeout, and keeps only the output up to that line:
+
+    #  spec_case:   FAIL  0.0512s  fib(40) — line 3: expected '1', got '2'
+
+A run that times out keeps the output it printed, so the failure still
+names the first missing or differing line.  Runs on a warm worker
+(warm_pool) are diffed when they return.
+
+Snapshot cases compare stdout against ``snapshots/<stem>__<case>.snap`` next
+to the sidecar (``etl.spec.toml`` → ``snapshots/etl__report.snap``).  load_spec
+reads the golden files into the cases, so they count towards ``spec_hash``;
+``spokedpy spec run --update-snapshots`` rewrites them from the current
+output and prints the diff for review.  A snapshot case without a golden
+file yet only checks the exit code.
+
+Every case runs in a scratch dir of its own (see scratch).  ``fixtures``
+are files next to the sidecar (a directory means every file under it);
+load_spec reads them — UTF-8 text, at most 1 MiB in all — and each case
+finds them read-only at the same relative paths in its scratch dir.
+Like golden files they count towards ``spec_hash``.
+
+Without a suite the dry-run is one run of the snippet, as before.  With one,
+every case runs in isolation and the snippet only PASSES when all of them
+do.  The normalized cases are hashed into ``spec_hash``, written to the
+promoted file's header next to ``code_hash`` — an edit to the spec is a
+change to the snippet just like an edit to its body.
+
+Each case's outcome (name, duration, pass/fail, first differing line) is kept
+on the snippet as ``spec_cases`` and written as one ``spec_case:`` header line
+per case:
+
+    #  spec_case:   PASS  0.0213s  greets
+    #  spec_case:   FAIL  0.0208s  counts stdin — line 1: expected '3', got '4'
+    #  spec_case:   DENIED(ptrace)  0.0311s  attaches — seccomp: denied ptrace
+
+    ┌────────────┬──────────────────────────────────────────────────────────┐
+    │  python    │  python -I -u -c <code> <args…> — args, stdin, env, exit │
+    │            │  code and timeout all honoured; writes outside the       │
+    │            │  scratch dir are refused                                 │
+    │  others    │  the engine's executor; cases may only check stdout and  │
+    │            │  pass / fail (exit_code 0 vs non-zero)                   │
+    └────────────┴──────────────────────────────────────────────────────────┘
+"""
+
+import hashlib
+import itertools
+import json
+import os
+import random
+import re
+import subprocess
+import sys
+import time
+from contextlib import nullcontext
+from string import Template
+from dataclasses import asdict, dataclass, field
+from typing import Any, Callable, Dict, List, Optional, Tuple
+
+from . import accounting, scratch, seccomp
+
+try:
+    import tomllib                      # Python 3.11+
+except ModuleNotFoundError:             # pragma: no cover
+    import tomli as tomllib
+
+
+SPEC_SUFFIXES = {'.spec.toml': 'toml', '.spec.yaml': 'yaml', '.spec.yml': 'yaml'}
+DEFAULT_TIMEOUT = 30.0
+MAX_FIXTURE_BYTES = 1 << 20
+MAX_GENERATED_CASES = 1000
+
+_SUITE_KEYS = {'timeout', 'env', 'fixtures', 'case'}
+_CASE_KEYS = {'name', 'args', 'stdin', 'stdout', 'exit_code', 'timeout', 'env', 'snapshot',
+              'generate'}
+_GENERATE_KEYS = {'seed', 'mode'}
+_GENERATORS = ('range', 'values', 'random')
+
+
+@dataclass
+class SpecCase:
+    """One declarative spec case."""
+    name: str
+    args: List[str] = field(default_factory=list)
+    stdin: str = ''
+    stdout: Optional[str] = None            # None → not compared
+    exit_code: int = 0
+    timeout: float = DEFAULT_TIMEOUT
+    env: Dict[str, str] = field(default_factory=dict)
+    snapshot: bool = False                  # stdout comes from the golden file
+
+    def needs_process(self) -> bool:
+        """True if the case feeds the snippet args, stdin or env."""
+        return bool(self.args or self.stdin or self.env)
+
+
+@dataclass
+class SpecSuite:
+    """The parsed cases of a spec sidecar, plus its original text."""
+    cases: List[SpecCase]
+    source: str = ''
+    format: str = 'toml'
+    fixtures: Dict[str, Optional[str]] = field(default_factory=dict)   # path → text (None: not read)
+    inherits: str = ''                      # the label whose default spec it extends (label_specs)
+    inherited: List[str] = field(default_factory=list)    # names of the cases that came from it
+
+    @property
+    def spec_hash(self) -> str:
+        """SHA-256 of the normalized cases (16 hex) — formatting and comments don't count."""
+        data: Any = [asdict(c) for c in self.cases]
+        if self.fixtures:
+            data = {'cases': data, 'fixtures': self.fixtures}
+        canonical = json.dumps(data, sort_keys=True, separators=(',', ':'))
+        return hashlib.sha256(canonical.encode('utf-8')).hexdigest()[:16]
+
+    def to_dict(self) -> Dict[str, Any]:
+        d = {'cases': [asdict(c) for c in self.cases], 'source': self.source,
+             'format': self.format, 'spec_hash': self.spec_hash}
+        if self.fixtures:
+            d['fixtures'] = dict(self.fixtures)
+        if self.inherits:
+            d['inherits'] = {'label': self.inherits, 'cases': list(self.inherited)}
+        return d
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'SpecSuite':
+        try:
+            cases = [SpecCase(**c) for c in data.get('cases', [])]
+        except TypeError as exc:
+            raise ValueError(f"malformed spec case: {exc}")
+        if not cases:
+            raise ValueError('a spec needs at least one case')
+        fixtures = data.get('fixtures') or {}
+        if not isinstance(fixtures, dict):
+            raise ValueError('spec fixtures must map paths to their text')
+        inherits = data.get('inherits') or {}
+        if not isinstance(inherits, dict):
+            raise ValueError("spec inherits must be {label, cases}")
+        return cls(cases, data.get('source', ''), data.get('format', 'toml'), dict(fixtures),
+                   inherits.get('label', ''), list(inherits.get('cases', [])))
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# PARSING
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _string_map(value, where: str) -> Dict[str, str]:
+    if not isinstance(value, dict):
+        raise ValueError(f"{where}: env must be a table of strings")
+    return {str(k): str(v) for k, v in value.items()}
+
+
+def _timeout(value, where: str) -> float:
+    if isinstance(value, bool) or not isinstance(value, (int, float)) or value <= 0:
+        raise ValueError(f"{where}: timeout must be a positive number of seconds")
+    return float(value)
+
+
+def _fixture_paths(value) -> List[str]:
+    if not isinstance(value, list) or not all(isinstance(p, str) and p.strip() for p in value):
+        raise ValueError('spec: fixtures must be a list of paths')
+    paths = []
+    for raw in value:
+        path = raw.strip().replace('\\', '/')
+        parts = [p for p in path.split('/') if p not in ('', '.')]
+        if os.path.isabs(path) or path.startswith('/') or '..' in parts or not parts:
+            raise ValueError(f"spec: fixture '{raw}' must be a path inside the sidecar's directory")
+        paths.append('/'.join(parts) + ('/' if path.endswith('/') else ''))
+    return paths
+
+
+def _case(raw, index: int, timeout: float, env: Dict[str, str]) -> SpecCase:
+    where = f"case {index + 1}"
+    if not isinstance(raw, dict):
+        raise ValueError(f"{where}: expected a table")
+    unknown = sorted(set(raw) - _CASE_KEYS)
+    if unknown:
+        raise ValueError(f"{where}: unknown key(s) {', '.join(unknown)}")
+    name = str(raw.get('name') or f"case {index + 1}")
+    where = f"case '{name}'"
+    args = raw.get('args', [])
+    if isinstance(args, str) or not isinstance(args, list):
+        raise ValueError(f"{where}: args must be a list of strings")
+    for key in ('stdin', 'stdout'):
+        if key in raw and not isinstance(raw[key], str):
+            raise ValueError(f"{where}: {key} must be a string")
+    exit_code = raw.get('exit_code', 0)
+    if isinstance(exit_code, bool) or not isinstance(exit_code, int):
+        raise ValueError(f"{where}: exit_code must be an integer")
+    snapshot = raw.get('snapshot', False)
+    if not isinstance(snapshot, bool):
+        raise ValueError(f"{where}: snapshot must be true or false")
+    if snapshot and 'stdout' in raw:
+        raise ValueError(f"{where}: use stdout or snapshot, not both")
+    return SpecCase(
+        name=name,
+        args=[str(a) for a in args],
+        stdin=raw.get('stdin', ''),
+        stdout=raw.get('stdout'),
+        exit_code=exit_code,
+        timeout=_timeout(raw['timeout'], where) if 'timeout' in raw else timeout,
+        env={**env, **_string_map(raw.get('env', {}), where)},
+        snapshot=snapshot,
+    )
+
+
+def _scalar(value) -> bool:
+    return isinstance(value, (str, int, float)) and not isinstance(value, bool)
+
+
+def _integers(value, size: Tuple[int, ...], what: str, where: str) -> List[int]:
+    if (not isinstance(value, list) or len(value) not in size
+            or not all(isinstance(v, int) and not isinstance(v, bool) for v in value)):
+        raise ValueError(f"{where}: {what}")
+    return value
+
+
+def _generator(name: str, spec, rng: random.Random, where: str) -> List[Any]:
+    """The values of one ``[case.generate]`` variable."""
+    where = f"{where}: generate.{name}"
+    if isinstance(spec, list):
+        spec = {'values': spec}
+    if not isinstance(spec, dict) or len(set(spec) & set(_GENERATORS)) != 1:
+        raise ValueError(f"{where} must be a list, or a table with one of "
+                         f"{', '.join(_GENERATORS)}")
+    kind = next(k for k in _GENERATORS if k in spec)
+    unknown = sorted(set(spec) - {kind} - ({'count'} if kind == 'random' else set()))
+    if unknown:
+        raise ValueError(f"{where}: unknown key(s) {', '.join(unknown)}")
+    if kind == 'values':
+        values = spec['values']
+        if not isinstance(values, list) or not values or not all(_scalar(v) for v in values):
+            raise ValueError(f"{where}: values must be a non-empty list of strings or numbers")
+        return list(values)
+    if kind == 'range':
+        bounds = _integers(spec['range'], (2, 3), 'range must be [start, stop] or '
+                           '[start, stop, step] (integers, stop included)', where)
+        start, stop, step = (bounds + [1 if bounds[1] >= bounds[0] else -1])[:3]
+        if step == 0 or (stop - start) * step < 0:
+            raise ValueError(f"{where}: range step {step} never reaches {stop} from {start}")
+        return list(range(start, stop + (1 if step > 0 else -1), step))
+    low_high, count = spec['random'], spec.get('count')
+    if (not isinstance(low_high, list) or len(low_high) != 2
+            or not all(_scalar(v) and not isinstance(v, str) for v in low_high)
+            or low_high[0] > low_high[1]):
+        raise ValueError(f"{where}: random must be [low, high] (numbers, high included)")
+    if isinstance(count, bool) or not isinstance(count, int) or count < 1:
+        raise ValueError(f"{where}: random needs a positive integer count")
+    if all(isinstance(v, int) for v in low_high):
+        span = low_high[1] - low_high[0] + 1
+        if count > span:
+            raise ValueError(f"{where}: cannot draw {count} distinct integers from {low_high}")
+        return rng.sample(range(low_high[0], low_high[1] + 1), count)
+    return [round(rng.uniform(*low_high), 6) for _ in range(count)]
+
+
+def _combinations(generate, where: str) -> List[Dict[str, str]]:
+    """Variable → value (as text) for each case ``[case.generate]`` expands into."""
+    if not isinstance(generate, dict) or not set(generate) - _GENERATE_KEYS:
+        raise ValueError(f"{where}: generate must be a table with at least one variable")
+    seed, mode = generate.get('seed', 0), generate.get('mode', 'product')
+    if isinstance(seed, bool) or not isinstance(seed, int):
+        raise ValueError(f"{where}: generate.seed must be an integer")
+    if mode not in ('product', 'zip'):
+        raise ValueError(f"{where}: generate.mode must be \"product\" or \"zip\"")
+    rng = random.Random(seed)
+    names = [k for k in generate if k not in _GENERATE_KEYS]
+    for name in names:
+        if not name.isidentifier():
+            raise ValueError(f"{where}: generate variable '{name}' is not a name ($name)")
+    columns = [_generator(name, generate[name], rng, where) for name in names]
+    if mode == 'zip':
+        if len({len(c) for c in columns}) > 1:
+            raise ValueError(f"{where}: generate.mode = \"zip\" needs lists of one length "
+                             f"({', '.join(f'{n}: {len(c)}' for n, c in zip(names, columns))})")
+        rows = zip(*columns)
+    else:
+        total = 1
+        for column in columns:
+            total *= len(column)
+        if total > MAX_GENERATED_CASES:
+            raise ValueError(f"{where}: generate makes {total} cases, at most "
+                             f"{MAX_GENERATED_CASES}")
+        rows = itertools.product(*columns)
+    return [{n: str(v) for n, v in zip(names, row)} for row in rows]
+
+
+def _expand(case: SpecCase, combinations: List[Dict[str, str]]) -> List[SpecCase]:
+    """One copy of a matrix case per combination, its variables substituted."""
+    def fill(text: str, values: Dict[str, str]) -> str:
+        return Template(text).safe_substitute(values)
+
+    bare = fill(case.name, {})
+    named = bare != fill(case.name, combinations[0])
+    expanded = []
+    for values in combinations:
+        name = fill(case.name, values) if named else (
+            f"{bare} [{', '.join(f'{k}={v}' for k, v in values.items())}]")
+        expanded.append(SpecCase(
+            name=name, args=[fill(a, values) for a in case.args],
+            stdin=fill(case.stdin, values),
+            stdout=None if case.stdout is None else fill(case.stdout, values),
+            exit_code=case.exit_code, timeout=case.timeout,
+            env={k: fill(v, values) for k, v in case.env.items()}, snapshot=case.snapshot,
+        ))
+    return expanded
+
+
+def parse_spec(text: str, fmt: str = 'toml') -> SpecSuite:
+    """Parse sidecar text into a SpecSuite; ValueError on anything malformed."""
+    if fmt == 'toml':
+        try:
+            data = tomllib.loads(text)
+        except tomllib.TOMLDecodeError as exc:
+            raise ValueError(f"invalid spec TOML: {exc}")
+    elif fmt == 'yaml':
+        try:
+            import yaml
+        except ImportError:
+            raise ValueError('YAML specs need PyYAML (pip install pyyaml) — or use .spec.toml')
+        try:
+            data = yaml.safe_load(text) or {}
+        except yaml.YAMLError as exc:
+            raise ValueError(f"invalid spec YAML: {exc}")
+    else:
+        raise ValueError(f"unknown spec format '{fmt}' (expected toml or yaml)")
+    if not isinstance(data, dict):
+        raise ValueError('a spec file is a table with [[case]] entries')
+
+    unknown = sorted(set(data) - _SUITE_KEYS)
+    if unknown:
+        raise ValueError(f"unknown spec key(s) {', '.join(unknown)}")
+    timeout = _timeout(data['timeout'], 'spec') if 'timeout' in data else DEFAULT_TIMEOUT
+    env = _string_map(data.get('env', {}), 'spec')
+    raw_cases = data.get('case', [])
+    if not isinstance(raw_cases, list) or not raw_cases:
+        raise ValueError('a spec file needs at least one [[case]]')
+    cases = []
+    for i, raw in enumerate(raw_cases):
+        case = _case(raw, i, timeout, env)
+        if 'generate' in raw:
+            case_where = f"case '{case.name}'"
+            cases.extend(_expand(case, _combinations(raw['generate'], case_where)))
+        else:
+            cases.append(case)
+    if len(cases) > MAX_GENERATED_CASES:
+        raise ValueError(f"the spec has {len(cases)} cases, at most {MAX_GENERATED_CASES}")
+    names = [c.name for c in cases]
+    duplicates = sorted({n for n in names if names.count(n) > 1})
+    if duplicates:
+        raise ValueError(f"duplicate case name(s): {', '.join(duplicates)}")
+    fixtures = dict.fromkeys(_fixture_paths(data['fixtures'])) if 'fixtures' in data else {}
+    return SpecSuite(cases, text, fmt, fixtures)
+
+
+def spec_format(path: str) -> Optional[str]:
+    """'toml' / 'yaml' for a spec sidecar path, None for anything else."""
+    return next((fmt for suffix, fmt in SPEC_SUFFIXES.items() if path.endswith(suffix)), None)
+
+
+def load_spec(path: str) -> SpecSuite:
+    """Parse a sidecar, fill its snapshot cases from their golden files and read its fixtures."""
+    fmt = spec_format(path)
+    if fmt is None:
+        raise ValueError(f"{path} is not a spec file ({', '.join(SPEC_SUFFIXES)})")
+    with open(path, 'r', encoding='utf-8') as f:
+        suite = parse_spec(f.read(), fmt)
+    for case in suite.cases:
+        golden = snapshot_path(path, case.name)
+        if case.snapshot and os.path.isfile(golden):
+            with open(golden, 'r', encoding='utf-8', newline='') as f:
+                case.stdout = f.read()
+    if suite.fixtures:
+        suite.fixtures = read_fixtures(os.path.dirname(path), list(suite.fixtures))
+    return suite
+
+
+def read_fixtures(base: str, paths: List[str]) -> Dict[str, str]:
+    """The text of each fixture under ``base`` (a directory: every file in it), by relative path."""
+    found: Dict[str, str] = {}
+    for relative in paths:
+        path = os.path.join(base, *relative.rstrip('/').split('/'))
+        if os.path.isdir(path):
+            files = sorted(os.path.join(d, n) for d, _dirs, names in os.walk(path) for n in names)
+        elif os.path.isfile(path):
+            files = [path]
+        else:
+            raise ValueError(f"fixture '{relative}' not found (looked for {path})")
+        for file in files:
+            name = os.path.relpath(file, base).replace(os.sep, '/')
+            try:
+                with open(file, 'r', encoding='utf-8', newline='') as f:
+                    found[name] = f.read()
+            except UnicodeDecodeError:
+                raise ValueError(f"fixture '{name}' is not UTF-8 text") from None
+    if sum(len(t.encode('utf-8')) for t in found.values()) > MAX_FIXTURE_BYTES:
+        raise ValueError(f"fixtures are {MAX_FIXTURE_BYTES // 1024} KiB at most in all")
+    return found
+
+
+def snapshot_path(spec_path: str, case_name: str) -> str:
+    """``snapshots/<stem>__<case>.snap`` next to the sidecar."""
+    suffix = next((s for s in SPEC_SUFFIXES if spec_path.endswith(s)), os.path.splitext(spec_path)[1])
+    stem = os.path.basename(spec_path)[:-len(suffix) or None]
+    slug = re.sub(r'[^A-Za-z0-9_.-]+', '_', case_name.strip()).strip('_') or 'case'
+    return os.path.join(os.path.dirname(spec_path), 'snapshots', f"{stem}__{slug}.snap")
+
+
+def write_snapshots(spec_path: str, outputs: Dict[str, str]) -> List[str]:
+    """Write case name → stdout as golden files; returns the paths written."""
+    written = []
+    for name, output in outputs.items():
+        golden = snapshot_path(spec_path, name)
+        os.makedirs(os.path.dirname(golden), exist_ok=True)
+        with open(golden, 'w', encoding='utf-8', newline='') as f:
+            f.write(output)
+        written.append(golden)
+    return written
+
+
+def sidecar_for(snippet_path: str) -> Optional[str]:
+    """The existing ``<stem>.spec.*`` next to a snippet file, if any."""
+    stem = os.path.splitext(snippet_path)[0]
+    for suffix in SPEC_SUFFIXES:
+        if os.path.isfile(stem + suffix):
+            return stem + suffix
+    return None
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# RUNNING
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class CaseResult:
+    """Outcome of one spec case."""
+    name: str
+    passed: bool
+    output: str = ''
+    error: str = ''
+    exit_code: Optional[int] = None
+    duration: float = 0.0
+    first_diff: str = ''                    # first failing check, e.g. the first differing line
+    stopped: bool = False                   # killed at its first differing stdout line
+    violations: List[Dict[str, str]] = field(default_factory=list)  # refused writes (see scratch)
+    denied: List[str] = field(default_factory=list)         # syscalls refused (see seccomp)
+
+    def to_dict(self) -> Dict[str, Any]:
+        d = asdict(self)
+        if len(d['output']) > 2000:
+            d['output'] = d['output'][:2000] + '\n…(truncated)'
+        return d
+
+
+def first_diff_line(expected: str, actual: str) -> str:
+    """``line N: expected '…', got '…'`` for the first line where two outputs differ."""
+    def shown(line: Optional[str]) -> str:
+        return 'end of output' if line is None else repr(line[:120])
+
+    want, got = expected.split('\n'), actual.split('\n')
+    for i in range(max(len(want), len(got))):
+        a = want[i] if i < len(want) else None
+        b = got[i] if i < len(got) else None
+        if a != b:
+            return f"line {i + 1}: expected {shown(a)}, got {shown(b)}"
+    return ''
+
+
+def case_line(case: Dict[str, Any]) -> str:
+    """The ``spec_case:`` header value for a CaseResult dict."""
+    outcome = seccomp.spec_result(case['passed'], case.get('denied'))
+    line = f"{outcome}  {case['duration']:.4f}s  {case['name']}"
+    return line + (f" — {case['first_diff']}" if case.get('first_diff') else '')
+
+
+_CASE_LINE = re.compile(r'^(PASS|FAIL|DENIED\(([^)]*)\))\s+([\d.]+)s\s+(.*?)(?:\s+—\s+(.*))?$')
+
+
+def parse_case_line(value: str) -> Optional[Dict[str, Any]]:
+    """Inverse of case_line(); None if the value is malformed."""
+    match = _CASE_LINE.match(value.strip())
+    if match is None:
+        return None
+    case = {'name': match.group(4), 'passed': match.group(1) == 'PASS',
+            'duration': float(match.group(3)), 'first_diff': match.group(5) or ''}
+    if match.group(2):
+        case['denied'] = match.group(2).split(',')
+    return case
+
+
+class StdoutCheck:
+    """output_stream check: None while each stdout line matches ``expected``, else the diff."""
+
+    def __init__(self, expected: str):
+        self._want = _normalize(expected).split('\n')
+        if self._want[-1] == '':
+            self._want.pop()                # complete lines only; the end is checked after
+        self._got: List[str] = []
+        self.first_diff = ''
+
+    @property
+    def output(self) -> str:
+        return ''.join(line + '\n' for line in self._got)
+
+    def __call__(self, line: str) -> Optional[str]:
+        line = _normalize(line)
+        if self.first_diff or not line.endswith('\n'):
+            return None
+        self._got.append(line[:-1])
+        i = len(self._got) - 1
+        if i >= len(self._want) or self._got[i] != self._want[i]:
+            self.first_diff = first_diff_line('\n'.join(self._want[:i + 1]),
+                                              '\n'.join(self._got))
+            return self.first_diff
+        return None
+
+
+def _default_runner(*args, **kwargs):
+    from .execution_engine import _run_subprocess
+    return _run_subprocess(*args, **kwargs)
+
+
+def run_python_case(code: str, case: SpecCase, runner: Callable = _default_runner) -> Dict[str, Any]:
+    """Run a Python snippet as ``python -I -u -c`` with the case's args, stdin and env
+    (unbuffered, so streaming clients see output as it is printed) — under the
+    write guard when a scratch dir is active."""
+    argv, env = [sys.executable, '-I', '-u', '-c', code, *case.args], {**os.environ, **case.env}
+    if scratch.current() is not None:
+        argv, extra = scratch.child_command(sys.executable, code, case.args)
+        env.update(extra)
+    from .output_stream import OutputDiverged
+    try:
+        proc = runner(argv, input=case.stdin, capture_output=True, text=True,
+                      timeout=case.timeout, env=env)
+    except subprocess.TimeoutExpired as exc:
+        output = exc.output or ''
+        if isinstance(output, bytes):
+            output = output.decode('utf-8', 'replace')
+        return {'success': False, 'output': output, 'timed_out': True,
+                'error': f"timed out after {case.timeout:g}s"}
+    except OutputDiverged as exc:
+        result = {'success': False, 'output': exc.output, 'error': (exc.stderr or '').strip()}
+        return result if exc.exit_code is None else {**result, 'exit_code': exc.exit_code}
+    return {'success': proc.returncode == 0, 'output': proc.stdout or '',
+            'error': (proc.stderr or '').strip(), 'exit_code': proc.returncode}
+
+
+def _normalize(text: str) -> str:
+    return text.replace('\r\n', '\n')
+
+
+def check_case(case: SpecCase, result: Dict[str, Any], duration: float) -> CaseResult:
+    """Compare one run against the case's expectations."""
+    output = result.get('output', '')
+    exit_code = result.get('exit_code')
+    diverged = result.get('diverged', '')
+    stopped = bool(diverged) and exit_code is None          # killed there: no exit code
+    if exit_code is None and not result.get('timed_out') and not stopped:
+        exit_code = 0 if result.get('success') else 1       # executor: pass / fail only
+    problems = []
+    if stopped:
+        pass
+    elif result.get('timed_out'):
+        problems.append(result.get('error') or 'timed out')
+    elif 'exit_code' in result and exit_code != case.exit_code:
+        problems.append(f"exit code {exit_code}, expected {case.exit_code}")
+    elif 'exit_code' not in result and (exit_code == 0) != (case.exit_code == 0):
+        problems.append('failed, expected success' if case.exit_code == 0
+                        else f"succeeded, expected exit code {case.exit_code}")
+    if problems and result.get('error') and not result.get('timed_out'):
+        problems.append(result['error'].splitlines()[-1][:300])
+    violations = result.get('violations') or []
+    if violations:
+        problems.insert(0, 'sandbox: ' + '; '.join(v['reason'] for v in violations))
+    denied = result.get('denied') or []
+    if denied:
+        problems.insert(0, 'seccomp: denied ' + ', '.join(denied))
+    if stopped:
+        problems += [f"stdout differs — {diverged}", 'stopped there, before the timeout']
+        error = result.get('error') or ''
+        if error and not error.startswith('stopped: '):    # an executor's error is the stop
+            problems.append(error.splitlines()[-1][:300])
+    elif diverged:
+        problems.append(f"stdout differs — {diverged}")
+    elif case.stdout is not None and _normalize(output) != _normalize(case.stdout):
+        problems.append('stdout differs — ' + first_diff_line(_normalize(case.stdout),
+                                                             _normalize(output)))
+    first = problems[0].replace('stdout differs — ', '', 1) if problems else ''
+    return CaseResult(case.name, not problems, output, '; '.join(problems), exit_code, duration,
+                      first, stopped, list(violations), list(denied))
+
+
+def run_suite(suite: SpecSuite, language: str, code: str,
+              run: Callable[[str, str], Dict[str, Any]],
+              runner: Callable = _default_runner, sandbox: bool = False) -> List[CaseResult]:
+    """
+    Run every case; ``run(language, code)`` is the engine dry-run for
+    non-Python cases.  With ``sandbox``, each case runs in a scratch dir
+    of its own, with the suite's fixtures, and a violation fails it.
+    Engine spawns run under the seccomp profile; a denied syscall makes
+    the case DENIED.  Inside a Meter, each case's stages carry its name.
+    A case's expected stdout is diffed line by line as it runs
+    (StdoutCheck); the first differing line stops it.
+    """
+    from .output_stream import emit
+    results = []
+    for case in suite.cases:
+        emit('case_started', name=case.name)
+        results.append(run_case(case, language, code, run, runner, sandbox, suite.fixtures))
+        emit('case_finished', name=case.name, passed=results[-1].passed,
+             duration=results[-1].duration, first_diff=results[-1].first_diff,
+             denied=results[-1].denied)
+    return results
+
+
+def run_case(case: SpecCase, language: str, code: str,
+             run: Callable[[str, str], Dict[str, Any]], runner: Callable = _default_runner,
+             sandbox: bool = False, fixtures: Optional[Dict[str, Optional[str]]] = None
+             ) -> CaseResult:
+    """One case of run_suite (without its case events), e.g. one side of a compare."""
+    from .output_stream import checking
+    started = time.time()
+    box = scratch.Scratch(fixtures or {}, case.name) if sandbox else nullcontext()
+    check = StdoutCheck(case.stdout) if case.stdout is not None else None
+    with box, seccomp.Denials() as denials, accounting.Meter(case.name), checking(check):
+        if language == 'python':
+            result = run_python_case(code, case, runner)
+        elif case.needs_process():
+            reason = (f"the {language} executor cannot pass args, stdin or env — "
+                      f"only Python cases may use them")
+            return CaseResult(case.name, False, error=reason, first_diff=reason)
+        else:
+            result = run(language, code)
+    if check is not None and check.first_diff:
+        result = {**result, 'diverged': check.first_diff, 'output': check.output}
+    if sandbox:
+        result = {**result, 'violations': box.violations}
+    if denials.syscalls:
+        result = {**result, 'denied': denials.syscalls}
+    return check_case(case, result, time.time() - started)
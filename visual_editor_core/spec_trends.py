@@ -0,0 +1,178 @@
+"""
+Spec Trends — spec_time history per (label, slot, engine) and anomaly alerts.
+
+Every speculative run appends one measurement to the trend index, a JSONL
+file next to the audit log (``[store] trends_log``):
+
+    {"at": 1760000000.0, "staging_id": "stg-…", "label": "etl", "slot": "d3",
+     "engine": "RUST", "language": "rust", "spec_time": 1.82, "success": true}
+
+A run is anomalous when its spec_time lies more than ``sigmas`` standard
+deviations above the median of the previous ``window`` passing runs of the
+same series (and at least ``min_samples`` of them exist).  The pipeline then
+emits a ``spec_anomaly`` notification; ``spokedpy trends d3`` prints the
+series with a sparkline and marks the outliers.
+"""
+
+import json
+import os
+import statistics
+import threading
+import time
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, List, Optional, Tuple
+
+SPARKS = '▁▂▃▄▅▆▇█'
+
+
+@dataclass
+class Measurement:
+    at: float
+    staging_id: str
+    label: str
+    slot: str
+    engine: str
+    language: str
+    spec_time: float
+    success: bool
+
+    @property
+    def key(self) -> Tuple[str, str, str]:
+        return (self.label, self.slot, self.engine)
+
+
+@dataclass
+class Anomaly:
+    spec_time: float
+    median: float
+    stdev: float
+    sigmas: float
+    samples: int
+
+    @property
+    def deviation(self) -> float:
+        return (self.spec_time - self.median) / self.stdev
+
+    def describe(self) -> str:
+        return (f"spec_time {self.spec_time:.4f}s is {self.deviation:.1f}σ above the rolling "
+                f"median {self.median:.4f}s (σ {self.stdev:.4f}s, {self.samples} runs; "
+                f"threshold {self.sigmas:g}σ)")
+
+
+def detect_anomaly(previous: List[float], spec_time: float, sigmas: float = 3.0,
+                   window: int = 20, min_samples: int = 5) -> Optional[Anomaly]:
+    """An Anomaly if ``spec_time`` is an outlier against ``previous`` (oldest first)."""
+    recent = previous[-window:] if window > 0 else list(previous)
+    if len(recent) < max(2, min_samples):
+        return None
+    median = statistics.median(recent)
+    stdev = statistics.pstdev(recent)
+    if stdev <= 0 or spec_time <= median + sigmas * stdev:
+        return None
+    return Anomaly(spec_time, median, stdev, sigmas, len(recent))
+
+
+class TrendIndex:
+    """Append-only spec_time measurements, thread-safe."""
+
+    def __init__(self, path: str, sigmas: float = 3.0, window: int = 20,
+                 min_samples: int = 5):
+        self.path = path
+        self.sigmas = sigmas
+        self.window = window
+        self.min_samples = min_samples
+        self._lock = threading.Lock()
+        os.makedirs(os.path.dirname(path) or '.', exist_ok=True)
+
+    def measurements(self) -> List[Measurement]:
+        """Every measurement, oldest first (unreadable lines are skipped)."""
+        try:
+            with open(self.path, 'r', encoding='utf-8') as f:
+                lines = f.readlines()
+        except OSError:
+            return []
+        out = []
+        for line in lines:
+            try:
+                out.append(Measurement(**json.loads(line)))
+            except (ValueError, TypeError):
+                continue
+        return out
+
+    def series(self, slot: str = '', label: str = '',
+               engine: str = '') -> Dict[Tuple[str, str, str], List[Measurement]]:
+        """Measurements grouped by (label, slot, engine), filtered by any given part."""
+        grouped: Dict[Tuple[str, str, str], List[Measurement]] = {}
+        for m in self.measurements():
+            if (slot and m.slot != slot) or (label and m.label != label) \
+                    or (engine and m.engine.lower() != engine.lower()):
+                continue
+            grouped.setdefault(m.key, []).append(m)
+        return grouped
+
+    def record(self, snippet) -> Optional[Anomaly]:
+        """Store the snippet's spec_time; returns an Anomaly for an outlier pass."""
+        m = Measurement(at=time.time(), staging_id=snippet.staging_id, label=snippet.label,
+                        slot=snippet.reserved_address, engine=snippet.reserved_engine,
+                        language=snippet.language, spec_time=snippet.spec_execution_time,
+                        success=bool(snippet.spec_success))
+        with self._lock:
+            previous = [p.spec_time for p in self.series(m.slot, m.label, m.engine).get(m.key, [])
+                        if p.success]
+            with open(self.path, 'a', encoding='utf-8') as f:
+                f.write(json.dumps(asdict(m)) + '\n')
+        if not m.success:
+            return None
+        return detect_anomaly(previous, m.spec_time, self.sigmas, self.window, self.min_samples)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# REPORTING — spokedpy trends
+# ═══════════════════════════════════════════════════════════════════════════
+
+def sparkline(values: List[float]) -> str:
+    if not values:
+        return ''
+    low, high = min(values), max(values)
+    span = (high - low) or 1.0
+    return ''.join(SPARKS[round((v - low) / span * (len(SPARKS) - 1))] for v in values)
+
+
+def trend_report(measurements: List[Measurement], sigmas: float = 3.0, window: int = 20,
+                 min_samples: int = 5) -> Dict[str, Any]:
+    """One series with summary statistics and a per-run anomaly flag."""
+    runs, passing = [], []
+    for m in measurements:
+        anomaly = detect_anomaly(passing, m.spec_time, sigmas, window, min_samples) \
+            if m.success else None
+        runs.append({**asdict(m), 'anomaly': anomaly.describe() if anomaly else ''})
+        if m.success:
+            passing.append(m.spec_time)
+    first = measurements[0]
+    return {
+        'label': first.label, 'slot': first.slot, 'engine': first.engine,
+        'runs': runs,
+        'count': len(runs),
+        'median': statistics.median(passing) if passing else 0.0,
+        'stdev': statistics.pstdev(passing) if passing else 0.0,
+        'min': min(passing) if passing else 0.0,
+        'max': max(passing) if passing else 0.0,
+        'anomalies': sum(1 for r in runs if r['anomaly']),
+    }
+
+
+def format_report(report: Dict[str, Any], limit: int = 20) -> str:
+    times = [r['spec_time'] for r in report['runs'] if r['success']]
+    lines = [
+        f"  {report['label']} @ {report['slot']} ({report['engine'].lower()}) — "
+        f"{report['count']} run(s), {report['anomalies']} anomal{'y' if report['anomalies'] == 1 else 'ies'}",
+        f"    {sparkline(times)}",
+        f"    median {report['median']:.4f}s · σ {report['stdev']:.4f}s · "
+        f"min {report['min']:.4f}s · max {report['max']:.4f}s",
+    ]
+    for r in report['runs'][-limit:]:
+        when = time.strftime('%Y-%m-%d %H:%M', time.localtime(r['at']))
+        mark = '✘' if not r['success'] else ('⚠' if r['anomaly'] else '✔')
+        lines.append(f"    {mark} {when}  {r['spec_time']:>9.4f}s  {r['staging_id']}"
+                     + (f"  {r['anomaly']}" if r['anomaly'] else ''))
+    return '\n'.join(lines)
@@ -0,0 +1,402 @@
+"""
+Store Backup — incremental, content-addressed copies of the store, optionally encrypted.
+
+    spokedpy backup --to /mnt/backup [--encrypt age:age1…]
+    spokedpy backup verify /mnt/backup [--identity key.txt] [--snapshot ID]
+
+Each backup is a snapshot of the store's files (STORE_PARTS: the snippets
+dir, audit log, state checkpoint, failure bundles, trends log, trash, the
+label specs and the policy / constraints / OWNERS files).  Files are cut into CHUNK_SIZE
+chunks, and every chunk is kept once, under the sha256 of its contents,
+so a backup writes only the chunks that no earlier one has — for the
+append-only audit log, its last one:
+
+    ┌──────────────────────────────┬──────────────────────────────────────────┐
+    │  backup.json                 │  format, chunk size, encryption — fixed   │
+    │                              │  by the first backup                      │
+    │  objects/<ab>/<sha256>[.age] │  one chunk                                │
+    │  index.jsonl                 │  per chunk: its size and the sha256 of    │
+    │                              │  the bytes stored (ciphertext, encrypted) │
+    │  snapshots/<id>.json[.age]   │  the manifest: every file with its part,  │
+    │                              │  relative path, mode, mtime and chunks    │
+    │  snapshots/<id>.seal.json    │  when, the manifest's sha256 and every    │
+    │                              │  chunk the snapshot needs                 │
+    └──────────────────────────────┴──────────────────────────────────────────┘
+
+With ``--encrypt age:<recipient>`` chunks and manifests are encrypted with
+the ``age`` tool (a recipient, or a file of recipients) before they are
+written; the seal and index stay readable, so chunk names (the sha256 of
+the plain chunk) are visible.  Every write is atomic, and a snapshot
+exists only once its seal is written — an interrupted backup leaves
+chunks the next one reuses, never a half snapshot.
+
+verify checks that every chunk a snapshot needs is there and unchanged
+(its stored sha256).  For a plain backup, or an encrypted one given the
+``age`` identity, it also reads every manifest and chunk back and checks
+the contents and file sizes.
+"""
+
+import hashlib
+import json
+import os
+import shutil
+import subprocess
+import time
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterator, List, Optional, Tuple
+
+from .errors import ErrorKind, SpokedError
+
+FORMAT = 1
+CHUNK_SIZE = 1 << 20
+LAYOUT_FILE = 'backup.json'
+INDEX_FILE = 'index.jsonl'
+AGE_SUFFIX = '.age'
+
+# The settings whose files make up a store (each a file or a dir)
+STORE_PARTS = ('snippets_dir', 'audit_log', 'state_checkpoint', 'failures_dir', 'trends_log',
+               'trash_dir', 'specs_dir', 'policy_file', 'constraints_file', 'owners_file')
+
+
+def parse_encrypt(spec: str) -> str:
+    """The age recipient of ``--encrypt age:<recipient>`` ('' for none)."""
+    spec = (spec or '').strip()
+    if not spec:
+        return ''
+    scheme, _, recipient = spec.partition(':')
+    if scheme != 'age' or not recipient.strip():
+        raise SpokedError(ErrorKind.USAGE, f"--encrypt '{spec}': use age:<recipient> "
+                                           f"(an age public key, or a file of recipients)")
+    return recipient.strip()
+
+
+def _age(args: List[str], data: bytes) -> bytes:
+    binary = shutil.which('age')
+    if binary is None:
+        raise ValueError('age is not installed (https://age-encryption.org); encrypted '
+                         'backups need it to write and to check their contents')
+    proc = subprocess.run([binary, *args], input=data, capture_output=True)
+    if proc.returncode:
+        detail = proc.stderr.decode('utf-8', errors='replace').strip()
+        raise ValueError(f"age: {detail or f'exit status {proc.returncode}'}")
+    return proc.stdout
+
+
+def _encrypt(recipient: str, data: bytes) -> bytes:
+    return _age(['-R' if os.path.isfile(recipient) else '-r', recipient], data)
+
+
+def _decrypt(identity: str, data: bytes) -> bytes:
+    return _age(['-d', '-i', identity], data)
+
+
+def _sha256(data: bytes) -> str:
+    return hashlib.sha256(data).hexdigest()
+
+
+def _write_atomic(path: str, data: bytes):
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    tmp_path = f"{path}.{os.getpid()}.tmp"
+    with open(tmp_path, 'wb') as f:
+        f.write(data)
+        f.flush()
+        os.fsync(f.fileno())
+    os.replace(tmp_path, path)
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# BACKUP DIR
+# ═══════════════════════════════════════════════════════════════════════════
+
+class _Repo:
+    """A backup dir: its layout, chunk index and snapshots."""
+
+    def __init__(self, root: str):
+        self.root = os.path.abspath(root)
+        self.layout: Optional[Dict[str, Any]] = None
+        self.index: Dict[str, Dict[str, Any]] = {}
+        layout_path = os.path.join(self.root, LAYOUT_FILE)
+        if os.path.isfile(layout_path):
+            with open(layout_path, encoding='utf-8') as f:
+                self.layout = json.load(f)
+        index_path = os.path.join(self.root, INDEX_FILE)
+        if os.path.isfile(index_path):
+            with open(index_path, encoding='utf-8') as f:
+                for line in f:
+                    try:
+                        entry = json.loads(line)
+                    except json.JSONDecodeError:
+                        continue                    # a line cut short by a crash
+                    self.index[entry['object']] = entry
+
+    @property
+    def encrypt(self) -> str:
+        return (self.layout or {}).get('encrypt', '')
+
+    @property
+    def suffix(self) -> str:
+        return AGE_SUFFIX if self.encrypt else ''
+
+    def object_path(self, name: str) -> str:
+        return os.path.join(self.root, 'objects', name[:2], name + self.suffix)
+
+    def manifest_path(self, snapshot: str) -> str:
+        return os.path.join(self.root, 'snapshots', f"{snapshot}.json{self.suffix}")
+
+    def seal_path(self, snapshot: str) -> str:
+        return os.path.join(self.root, 'snapshots', f"{snapshot}.seal.json")
+
+    def seals(self) -> List[Dict[str, Any]]:
+        """Every complete snapshot's seal, oldest first."""
+        snapshots_dir = os.path.join(self.root, 'snapshots')
+        if not os.path.isdir(snapshots_dir):
+            return []
+        seals = []
+        for name in sorted(os.listdir(snapshots_dir)):
+            if name.endswith('.seal.json'):
+                with open(os.path.join(snapshots_dir, name), encoding='utf-8') as f:
+                    seals.append(json.load(f))
+        return sorted(seals, key=lambda s: (s['created'], s['snapshot']))
+
+
+def _store_files(path: str) -> Iterator[Tuple[str, str]]:
+    """(relative path, full path) of the regular files of a part ('' for a lone file)."""
+    if os.path.isfile(path):
+        yield '', path
+        return
+    for root, dirs, names in os.walk(path):
+        dirs.sort()
+        for name in sorted(names):
+            full = os.path.join(root, name)
+            if os.path.isfile(full) and not os.path.islink(full):
+                yield os.path.relpath(full, path).replace(os.sep, '/'), full
+
+
+@dataclass
+class BackupReport:
+    dest: str
+    snapshot: str
+    encrypt: str = ''
+    files: int = 0
+    bytes: int = 0                      # plain size of every file backed up
+    chunks: int = 0
+    new_chunks: int = 0
+    new_bytes: int = 0                  # bytes written to objects/ (stored size)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return dict(self.__dict__)
+
+
+def backup(parts: Dict[str, str], dest: str, encrypt: str = '') -> BackupReport:
+    """
+    Write a snapshot of ``parts`` (setting name → file or dir; see
+    STORE_PARTS) into the backup dir ``dest``; parts that do not exist are
+    left out.  ``encrypt`` is ``age:<recipient>`` or ''; it must be what the
+    dir's first backup used.
+    """
+    recipient = parse_encrypt(encrypt)
+    encrypt = f"age:{recipient}" if recipient else ''
+    repo = _Repo(dest)
+    if repo.layout is None:
+        repo.layout = {'format': FORMAT, 'chunk_size': CHUNK_SIZE, 'encrypt': encrypt,
+                       'created': time.time()}
+        _write_atomic(os.path.join(repo.root, LAYOUT_FILE),
+                      json.dumps(repo.layout, indent=2).encode('utf-8'))
+    elif repo.encrypt != encrypt:
+        had = repo.encrypt or 'no encryption'
+        raise SpokedError(ErrorKind.USAGE, f"{repo.root} holds backups with {had}; back up "
+                                           f"with the same --encrypt, or into a new dir",
+                          path=repo.root)
+    chunk_size = int(repo.layout.get('chunk_size', CHUNK_SIZE))
+
+    stamp = time.strftime('%Y%m%dT%H%M%SZ', time.gmtime())
+    snapshot, n = stamp, 1
+    while os.path.exists(repo.seal_path(snapshot)):
+        n += 1
+        snapshot = f"{stamp}-{n}"
+    report = BackupReport(repo.root, snapshot, encrypt)
+
+    files, needed = [], set()
+    with open(os.path.join(repo.root, INDEX_FILE), 'a', encoding='utf-8') as index:
+        for part, path in parts.items():
+            if not path or not os.path.exists(path):
+                continue
+            for rel, full in _store_files(path):
+                try:
+                    stat = os.stat(full)
+                    chunks, size = [], 0
+                    with open(full, 'rb') as f:
+                        while True:
+                            data = f.read(chunk_size)
+                            if not data:
+                                break
+                            name = _sha256(data)
+                            chunks.append(name)
+                            size += len(data)
+                            if name not in repo.index or not os.path.exists(repo.object_path(name)):
+                                stored = _encrypt(recipient, data) if recipient else data
+                                _write_atomic(repo.object_path(name), stored)
+                                repo.index[name] = {'object': name, 'size': len(data),
+                                                    'stored_sha256': _sha256(stored),
+                                                    'stored_size': len(stored)}
+                                index.write(json.dumps(repo.index[name]) + '\n')
+                                report.new_chunks += 1
+                                report.new_bytes += len(stored)
+                except FileNotFoundError:
+                    continue                        # removed while we walked (gc, purge)
+                files.append({'part': part, 'path': rel, 'size': size,
+                              'mode': stat.st_mode & 0o7777, 'mtime': stat.st_mtime,
+                              'chunks': chunks})
+                needed.update(chunks)
+                report.files += 1
+                report.bytes += size
+                report.chunks += len(chunks)
+        index.flush()
+        os.fsync(index.fileno())
+
+    created = time.time()
+    manifest = json.dumps({'format': FORMAT, 'snapshot': snapshot, 'created': created,
+                           'parts': {part: os.path.abspath(path) for part, path in parts.items()},
+                           'files': files}, indent=1).encode('utf-8')
+    if recipient:
+        manifest = _encrypt(recipient, manifest)
+    _write_atomic(repo.manifest_path(snapshot), manifest)
+    seal = {'snapshot': snapshot, 'created': created, 'encrypt': encrypt,
+            'manifest_sha256': _sha256(manifest), 'files': report.files,
+            'bytes': report.bytes, 'objects': sorted(needed)}
+    _write_atomic(repo.seal_path(snapshot), json.dumps(seal, indent=1).encode('utf-8'))
+    return report
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# VERIFY
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class VerifyReport:
+    dest: str
+    encrypt: str = ''
+    snapshots: List[str] = field(default_factory=list)
+    objects: int = 0
+    contents_checked: bool = False      # manifests and chunks read back (plain, or --identity)
+    problems: List[Tuple[str, str]] = field(default_factory=list)     # (snapshot, what)
+
+    @property
+    def ok(self) -> bool:
+        return not self.problems
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {**self.__dict__, 'problems': [{'snapshot': s, 'problem': p}
+                                              for s, p in self.problems], 'ok': self.ok}
+
+
+def verify(dest: str, snapshot: str = '', identity: str = '') -> VerifyReport:
+    """Check the snapshots of a backup dir (all, or ``snapshot``); see the module docstring."""
+    repo = _Repo(dest)
+    if repo.layout is None:
+        raise SpokedError(ErrorKind.NOT_FOUND, f"{repo.root} is not a spokedpy backup "
+                                               f"(no {LAYOUT_FILE})", path=repo.root)
+    seals = repo.seals()
+    if snapshot:
+        seals = [s for s in seals if s['snapshot'] == snapshot]
+        if not seals:
+            raise SpokedError(ErrorKind.NOT_FOUND, f"no snapshot '{snapshot}' in {repo.root}",
+                              path=repo.root, snapshot=snapshot)
+    report = VerifyReport(repo.root, repo.encrypt,
+                          contents_checked=not repo.encrypt or bool(identity))
+
+    def read(path: str) -> bytes:
+        with open(path, 'rb') as f:
+            return f.read()
+
+    def plain(stored: bytes) -> bytes:
+        return _decrypt(identity, stored) if repo.encrypt else stored
+
+    checked: Dict[str, str] = {}        # chunk → problem ('' when intact)
+
+    def check_object(name: str) -> str:
+        if name in checked:
+            return checked[name]
+        entry = repo.index.get(name)
+        path = repo.object_path(name)
+        if entry is None:
+            problem = f"chunk {name[:12]} is not in {INDEX_FILE}"
+        elif not os.path.isfile(path):
+            problem = f"chunk {name[:12]} is missing"
+        else:
+            stored = read(path)
+            problem = ''
+            if _sha256(stored) != entry['stored_sha256']:
+                problem = f"chunk {name[:12]} is corrupt (stored sha256 differs)"
+            elif report.contents_checked:
+                try:
+                    if _sha256(plain(stored)) != name:
+                        problem = f"chunk {name[:12]} is corrupt (contents differ)"
+                except ValueError as exc:
+                    problem = f"chunk {name[:12]}: {exc}"
+        checked[name] = problem
+        return problem
+
+    for seal in seals:
+        sid = seal['snapshot']
+        report.snapshots.append(sid)
+        problems = [check_object(name) for name in seal['objects']]
+        report.problems.extend((sid, p) for p in problems if p)
+        path = repo.manifest_path(sid)
+        if not os.path.isfile(path):
+            report.problems.append((sid, 'manifest is missing'))
+            continue
+        stored = read(path)
+        if _sha256(stored) != seal['manifest_sha256']:
+            report.problems.append((sid, 'manifest is corrupt (sha256 differs from the seal)'))
+            continue
+        if not report.contents_checked:
+            continue
+        try:
+            manifest = json.loads(plain(stored))
+        except ValueError as exc:
+            report.problems.append((sid, f"manifest: {exc}"))
+            continue
+        sealed = set(seal['objects'])
+        for entry in manifest['files']:
+            where = f"{entry['part']}/{entry['path']}".rstrip('/')
+            if not sealed.issuperset(entry['chunks']):
+                report.problems.append((sid, f"{where}: chunks missing from the seal"))
+            elif sum(repo.index.get(c, {}).get('size', 0) for c in entry['chunks']) != entry['size']:
+                report.problems.append((sid, f"{where}: chunk sizes do not add up to "
+                                             f"{entry['size']} bytes"))
+    report.objects = len(checked)
+    return report
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# FORMATTING
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _size(n: int) -> str:
+    for unit in ('B', 'KiB', 'MiB', 'GiB'):
+        if n < 1024 or unit == 'GiB':
+            return f"{n} {unit}" if unit == 'B' else f"{n:.1f} {unit}"
+        n /= 1024
+    return f"{n} B"
+
+
+def format_backup(report: BackupReport) -> str:
+    how = ' (age-encrypted)' if report.encrypt else ''
+    return '\n'.join([
+        f"  ✔ snapshot {report.snapshot} → {report.dest}{how}",
+        f"    {report.files} file(s), {_size(report.bytes)} in {report.chunks} chunk(s); "
+        f"{report.new_chunks} new ({_size(report.new_bytes)} written)",
+    ])
+
+
+def format_verify(report: VerifyReport) -> str:
+    how = 'contents checked' if report.contents_checked else \
+        'stored bytes checked; pass --identity to check the contents'
+    if report.ok:
+        return (f"  ✔ {len(report.snapshots)} snapshot(s), {report.objects} chunk(s) intact "
+                f"({how}) — {report.dest}")
+    bad = len({sid for sid, _ in report.problems})
+    lines = [f"  ✘ {bad} of {len(report.snapshots)} snapshot(s) damaged ({how}) — {report.dest}"]
+    lines.extend(f"    {sid}: {problem}" for sid, problem in report.problems)
+    return '\n'.join(lines)
@@ -0,0 +1,177 @@
+"""
+Store Bench — throughput of the store write path under parallel staging.
+
+    spokedpy bench-store [--jobs 50] [--per-job 4] [--shard-width 2] [--min-rate 100]
+
+runs ``jobs`` threads against a throwaway store (a temp dir), each staging,
+dry-running, promoting and rolling back ``per_job`` snippets through one
+StagingPipeline — the path the server takes, minus the engines: a stub
+executor answers every dry-run at once, so what is measured is the store
+itself (slot reservation, promoted files, audit log, ledger and registry).
+The jobs are spread over the subprocess engine rows, 16 slots each, so 50
+of them can hold a slot at the same time.
+
+The report gives promotions per second, per-snippet latency (p50 / p95),
+how many shard directories the promoted files landed in, and how many
+audit-log writes carried the events: under contention the log group-
+commits, so there are fewer writes than events.
+"""
+
+import os
+import shutil
+import tempfile
+import threading
+import time
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from .snippet_staging import DEFAULT_SHARD_WIDTH, StagingPipeline
+
+BENCH_LANGUAGES = ('javascript', 'typescript', 'rust', 'go', 'java', 'bash')
+DEFAULT_MIN_RATE = 100.0                    # promotions per second
+
+
+@dataclass
+class _Result:
+    success: bool = True
+    output: str = ''
+    error: Optional[str] = None
+    execution_time: float = 0.0
+
+
+class _InstantExecutor:
+    """Stands in for an engine: every dry-run passes immediately."""
+
+    def execute(self, code: str) -> _Result:
+        return _Result()
+
+
+@dataclass
+class BenchReport:
+    jobs: int
+    per_job: int
+    shard_width: int
+    seconds: float = 0.0
+    latencies: List[float] = field(default_factory=list)     # seconds per snippet
+    errors: List[str] = field(default_factory=list)
+    shards: int = 0                         # directories holding promoted files
+    audit_events: int = 0
+    audit_batches: int = 0
+
+    @property
+    def promoted(self) -> int:
+        return len(self.latencies)
+
+    @property
+    def rate(self) -> float:
+        return self.promoted / self.seconds if self.seconds else 0.0
+
+    def percentile(self, p: float) -> float:
+        if not self.latencies:
+            return 0.0
+        ordered = sorted(self.latencies)
+        return ordered[min(len(ordered) - 1, int(round(p / 100 * (len(ordered) - 1))))]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            'jobs': self.jobs, 'per_job': self.per_job, 'shard_width': self.shard_width,
+            'promoted': self.promoted, 'seconds': round(self.seconds, 4),
+            'rate': round(self.rate, 2), 'p50': round(self.percentile(50), 4),
+            'p95': round(self.percentile(95), 4), 'shards': self.shards,
+            'audit_events': self.audit_events, 'audit_batches': self.audit_batches,
+            'errors': self.errors,
+        }
+
+
+def _count_shards(snippets_dir: str) -> int:
+    return sum(1 for root, _dirs, names in os.walk(snippets_dir)
+               if any(not n.endswith('.spec.toml') for n in names))
+
+
+def bench_store(jobs: int = 50, per_job: int = 4,
+                shard_width: int = DEFAULT_SHARD_WIDTH, root: str = '') -> BenchReport:
+    """Run the benchmark (see the module docstring); ``root`` is kept, a temp dir is not."""
+    from .node_registry import LANGUAGE_STRING_TO_ENGINE, NodeRegistry
+    from .session_ledger import SessionLedger
+
+    keep = bool(root)
+    root = root or tempfile.mkdtemp(prefix='spokedpy-bench-')
+    report = BenchReport(jobs, per_job, shard_width)
+    try:
+        ledger = SessionLedger()                # shared, as in init_runtime
+        pipeline = StagingPipeline(
+            executors={lang: _InstantExecutor() for lang in BENCH_LANGUAGES},
+            node_registry=NodeRegistry(ledger), session_ledger=ledger,
+            snippets_dir=os.path.join(root, 'snippets'),
+            audit_log_path=os.path.join(root, 'staging_audit.jsonl'),
+            shard_width=shard_width,
+        )
+        lock = threading.Lock()
+        start = threading.Barrier(jobs + 1)
+
+        def job(n: int):
+            language = BENCH_LANGUAGES[n % len(BENCH_LANGUAGES)]
+            letter = LANGUAGE_STRING_TO_ENGINE[language].letter
+            start.wait()
+            for i in range(per_job):
+                began = time.perf_counter()
+                try:
+                    snippet = pipeline.queue_snippet(letter, language,
+                                                     f"// bench-store job {n} snippet {i}\n",
+                                                     f"bench-{n}-{i}", 'bench-store')
+                    pipeline.speculate(snippet.staging_id)
+                    pipeline.verdict(snippet.staging_id)
+                    pipeline.promote(snippet.staging_id)
+                    pipeline.rollback(snippet.staging_id, 'bench-store')   # frees the slot
+                except Exception as exc:
+                    with lock:
+                        report.errors.append(f"job {n} snippet {i}: {exc}")
+                    continue
+                with lock:
+                    report.latencies.append(time.perf_counter() - began)
+
+        threads = [threading.Thread(target=job, args=(n,), daemon=True, name=f"bench-{n}")
+                   for n in range(jobs)]
+        for thread in threads:
+            thread.start()
+        start.wait()
+        started = time.perf_counter()
+        for thread in threads:
+            thread.join()
+        report.seconds = time.perf_counter() - started
+
+        stats = pipeline.audit_stats()
+        report.audit_events, report.audit_batches = stats['events'], stats['batches']
+        report.shards = _count_shards(os.path.join(root, 'snippets'))
+    finally:
+        if not keep:
+            shutil.rmtree(root, ignore_errors=True)
+    return report
+
+
+def format_bench(report: BenchReport, min_rate: float = 0.0,
+                 max_p95: Optional[float] = None) -> str:
+    layout = (f"sharded by {report.shard_width} hex digit(s)" if report.shard_width
+              else 'flat')
+    lines = [
+        f"  {report.promoted}/{report.jobs * report.per_job} snippet(s) staged → promoted → "
+        f"rolled back by {report.jobs} parallel job(s) in {report.seconds:.2f}s",
+        f"    throughput  {report.rate:.1f} promotions/s"
+        + (f"  (target ≥ {min_rate:g})" if min_rate else ''),
+        f"    latency     p50 {report.percentile(50) * 1000:.1f} ms, "
+        f"p95 {report.percentile(95) * 1000:.1f} ms"
+        + (f"  (target ≤ {max_p95 * 1000:g} ms)" if max_p95 is not None else ''),
+        f"    files       {layout}, {report.shards} director(ies)",
+        f"    audit log   {report.audit_events} event(s) in {report.audit_batches} write(s)",
+    ]
+    lines += [f"    ✘ {e}" for e in report.errors[:10]]
+    if len(report.errors) > 10:
+        lines.append(f"    … {len(report.errors) - 10} more error(s)")
+    return '\n'.join(lines)
+
+
+def meets_targets(report: BenchReport, min_rate: float = 0.0,
+                  max_p95: Optional[float] = None) -> bool:
+    if report.errors or report.rate < min_rate:
+        return False
+    return max_p95 is None or report.percentile(95) <= max_p95
@@ -0,0 +1,264 @@
+"""
+Store Lint — dead slots, orphans, duplicate labels and abandoned stagings.
+
+    spokedpy lint-store [--fix [CLASS,…]] [--stale-days 7] [--allow-label GLOB]
+
+reads the store from disk (no server needed) — the promoted files'
+headers and rollbacks, as SlotResolver sees them; the state checkpoint
+the server restores its slots from; the audit log — and reports four
+classes of finding:
+
+    dead-slot        the checkpoint binds (or locks) a slot no promoted
+                     file holds: its file was removed or rolled back
+    orphan           a promoted file holds a slot the checkpoint does not
+                     bind: resolvers serve it, a restarted server does not
+    duplicate-label  one label holds more than one slot of the same engine
+                     row (across engines is a port, not a duplicate;
+                     --allow-label GLOB accepts more)
+    stale            a snippet staged and never referenced since — not
+                     promoted, rejected, rolled back or deleted, and no
+                     event for --stale-days days
+
+``--fix`` (all classes, or the ones named) repairs them: a dead slot's
+binding or lock is dropped from the checkpoint; an orphan and every
+duplicate but the latest promotion are rolled back (a ``rollback`` audit
+event, files kept — and their bindings dropped); a stale snippet gets a
+``rejection`` event.  Fixes write the checkpoint and audit log the
+server keeps, so run them with the server stopped; a read-only store
+refuses them.  Dead slots and orphans need a checkpoint to compare with.
+"""
+
+import fnmatch
+import json
+import os
+import time
+from dataclasses import asdict, dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from .slot_resolver import PromotedSnippet, SlotResolver, _order, _read_entry
+
+KINDS = ('dead-slot', 'orphan', 'duplicate-label', 'stale')
+
+# Events after which a staged snippet is accounted for (until a snippet_restored)
+_CLOSING = frozenset({'promotion_completed', 'rejection', 'rollback', 'snippet_deleted',
+                      'trash_purged'})
+
+
+@dataclass
+class Finding:
+    kind: str                               # one of KINDS
+    slot: str = ''
+    staging_id: str = ''
+    label: str = ''
+    detail: str = ''
+    path: str = ''
+    fix: str = ''                           # what --fix does about it
+    fixed: bool = False
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+@dataclass
+class LintReport:
+    snippets_dir: str
+    checkpoint: str = ''                    # '' → none found (dead slots / orphans unchecked)
+    findings: List[Finding] = field(default_factory=list)
+
+    def counts(self) -> Dict[str, int]:
+        return {kind: sum(1 for f in self.findings if f.kind == kind) for kind in KINDS}
+
+    @property
+    def unfixed(self) -> List[Finding]:
+        return [f for f in self.findings if not f.fixed]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {'snippets_dir': self.snippets_dir, 'checkpoint': self.checkpoint,
+                'counts': self.counts(), 'findings': [f.to_dict() for f in self.findings]}
+
+
+def parse_kinds(value: str) -> List[str]:
+    """'all' or 'orphan, stale' → the classes; ValueError for an unknown one."""
+    names = [n.strip() for n in value.replace(',', ' ').split() if n.strip()]
+    if not names or names == ['all']:
+        return list(KINDS)
+    unknown = sorted(set(names) - set(KINDS))
+    if unknown:
+        raise ValueError(f"unknown lint class(es) {', '.join(unknown)} — "
+                         f"expected {', '.join(KINDS)} or all")
+    return [k for k in KINDS if k in names]
+
+
+def _read_checkpoint(path: str) -> Optional[Dict[str, Any]]:
+    if not path or not os.path.isfile(path):
+        return None
+    try:
+        with open(path, 'r', encoding='utf-8') as f:
+            state = json.load(f)
+    except (OSError, ValueError) as exc:
+        raise ValueError(f"checkpoint {path}: {exc}") from None
+    if not isinstance(state, dict):
+        raise ValueError(f"checkpoint {path}: not a checkpoint object")
+    return state
+
+
+def _events(audit_log: str) -> Iterable[Dict[str, Any]]:
+    try:
+        with open(audit_log, 'r', encoding='utf-8', errors='replace') as f:
+            for line in f:
+                try:
+                    event = json.loads(line)
+                except ValueError:
+                    continue
+                if isinstance(event, dict) and event.get('staging_id'):
+                    yield event
+    except OSError:
+        return
+
+
+def _files(snippets_dir: str) -> List[PromotedSnippet]:
+    found = []
+    for folder, dirs, names in os.walk(snippets_dir):
+        dirs.sort()
+        for name in sorted(names):
+            entry = _read_entry(os.path.join(folder, name))
+            if entry is not None:
+                found.append(entry)
+    return found
+
+
+def lint_store(snippets_dir: str, audit_log: str = '', checkpoint: str = '',
+               stale_days: float = 7.0, allow_labels: Iterable[str] = (),
+               now: Optional[float] = None) -> LintReport:
+    """Every finding in the store; ValueError for an unreadable checkpoint."""
+    now = time.time() if now is None else now
+    state = _read_checkpoint(checkpoint)
+    report = LintReport(snippets_dir, checkpoint if state is not None else '')
+    held = SlotResolver(snippets_dir, audit_log).slots()       # slot → its promoted file
+
+    if state is not None:
+        bound = {str(s.get('address', '')).lower(): s for s in state.get('promoted_snippets', [])
+                 if s.get('address')}
+        by_id = {e.staging_id: e for e in _files(snippets_dir)}
+        for slot, snap in sorted(bound.items()):
+            if slot not in held:
+                gone = 'was rolled back' if snap.get('staging_id') in by_id else 'is missing'
+                report.findings.append(Finding(
+                    'dead-slot', slot, snap.get('staging_id', ''), snap.get('label', ''),
+                    f"the checkpoint binds it, but its promoted file {gone}",
+                    snap.get('saved_file_path', ''), 'drop the checkpoint binding'))
+        for slot in sorted(state.get('locked_slots', {})):
+            if slot.lower() not in held and slot.lower() not in bound:
+                report.findings.append(Finding(
+                    'dead-slot', slot.lower(), detail='locked, but nothing is promoted into it',
+                    fix='drop the lock'))
+        for slot, entry in sorted(held.items()):
+            if slot not in bound:
+                report.findings.append(Finding(
+                    'orphan', slot, entry.staging_id, entry.label,
+                    'a promoted file holds the slot, but the checkpoint does not bind it',
+                    entry.path, 'roll it back'))
+
+    rows: Dict[Tuple[str, str], List[PromotedSnippet]] = {}
+    for entry in held.values():
+        if entry.label and not any(fnmatch.fnmatchcase(entry.label, g) for g in allow_labels):
+            rows.setdefault((entry.label, entry.engine), []).append(entry)
+    for (label, engine), entries in sorted(rows.items()):
+        if len(entries) < 2:
+            continue
+        entries.sort(key=_order)
+        keep = entries[-1]
+        for entry in entries[:-1]:
+            report.findings.append(Finding(
+                'duplicate-label', entry.slot, entry.staging_id, label,
+                f"{engine or 'the engine'} row holds label '{label}' in "
+                f"{', '.join(e.slot for e in entries)}; {keep.slot} is the latest",
+                entry.path, f"roll it back (keep {keep.slot})"))
+
+    if audit_log:
+        trails: Dict[str, Dict[str, Any]] = {}
+        for event in _events(audit_log):
+            trail = trails.setdefault(event['staging_id'], {'open': False, 'last': 0.0})
+            kind = event.get('event', '')
+            if kind == 'snippet_queued':
+                trail['queued'], trail['open'] = event, True
+            elif kind in _CLOSING:
+                trail['open'] = False
+            elif kind == 'snippet_restored':
+                trail['open'] = True
+            trail['last'] = max(trail['last'], float(event.get('timestamp', 0) or 0))
+        cutoff = now - float(stale_days) * 86400
+        for staging_id, trail in sorted(trails.items()):
+            queued = trail.get('queued')
+            if queued is None or not trail['open'] or trail['last'] >= cutoff:
+                continue
+            data = queued.get('data', {})
+            idle = (now - trail['last']) / 86400
+            report.findings.append(Finding(
+                'stale', '', staging_id, data.get('label', ''),
+                f"staged {queued.get('iso_time', '')} by {data.get('author') or 'unknown'}, "
+                f"nothing since for {idle:.0f} day(s)", fix='reject it'))
+    return report
+
+
+def _write_checkpoint(path: str, state: Dict[str, Any]):
+    tmp = path + '.tmp'
+    with open(tmp, 'w', encoding='utf-8') as f:
+        json.dump(state, f, indent=2, default=str)
+    os.replace(tmp, path)
+
+
+def fix_store(report: LintReport, audit_log: str, kinds: Iterable[str]) -> LintReport:
+    """Apply the fixes of ``kinds`` to ``report``'s findings (marked ``fixed``)."""
+    from .snippet_staging import AuditEventType, AuditLogger
+    kinds = set(kinds)
+    chosen = [f for f in report.findings if f.kind in kinds and not f.fixed]
+    if not chosen:
+        return report
+    audit = AuditLogger(audit_log) if audit_log else None
+    state = _read_checkpoint(report.checkpoint)
+    drop_bindings, drop_locks = set(), set()
+    for finding in chosen:
+        reason = f"lint-store --fix: {finding.kind}"
+        if finding.kind == 'dead-slot':
+            (drop_bindings if finding.staging_id else drop_locks).add(finding.slot)
+        elif audit is None:
+            continue                        # rollbacks and rejections are audit events
+        elif finding.kind == 'stale':
+            audit.log(AuditEventType.REJECTION, finding.staging_id,
+                      {'reason': reason, 'by': 'lint-store'})
+        else:
+            audit.log(AuditEventType.ROLLBACK, finding.staging_id,
+                      {'reason': reason, 'address': finding.slot, 'by': 'lint-store'})
+            drop_bindings.add(finding.slot)
+        finding.fixed = True
+    if state is not None and (drop_bindings or drop_locks):
+        state['promoted_snippets'] = [
+            s for s in state.get('promoted_snippets', [])
+            if str(s.get('address', '')).lower() not in drop_bindings]
+        state['locked_slots'] = {a: m for a, m in state.get('locked_slots', {}).items()
+                                 if a.lower() not in drop_locks | drop_bindings}
+        _write_checkpoint(report.checkpoint, state)
+    return report
+
+
+def format_lint(report: LintReport) -> str:
+    """The ``spokedpy lint-store`` report."""
+    where = report.checkpoint or 'no checkpoint — dead slots and orphans not checked'
+    lines = [f"Store lint: {report.snippets_dir} ({where})"]
+    for f in report.findings:
+        what = f.staging_id or '-'
+        label = f" '{f.label}'" if f.label else ''
+        state = '✔ fixed' if f.fixed else f"--fix: {f.fix}"
+        lines.append(f"  {f.kind:<16} {f.slot or '-':<5} {what}{label} — {f.detail}  [{state}]")
+        if f.path:
+            lines.append(f"  {'':<16} {'':<5} {f.path}")
+    counts = report.counts()
+    if not report.findings:
+        lines.append('  ✔ no dead slots, orphans, duplicate labels or stale snippets')
+    else:
+        fixed = len(report.findings) - len(report.unfixed)
+        lines.append(f"{len(report.findings)} finding(s): "
+                     + ', '.join(f"{n} {k}" for k, n in counts.items() if n)
+                     + (f"; {fixed} fixed" if fixed else ''))
+    return '\n'.join(lines)
@@ -0,0 +1,346 @@
+"""
+Toolchain Manager — provision pinned engine toolchains into a managed prefix.
+
+Fresh CI machines rarely have the exact compiler a snippet was promoted
+against.  ``spokedpy toolchain install rust@1.84`` drives the language's own
+version manager to install a pinned toolchain into a SpokedPy-owned prefix,
+leaving the machine's global installs untouched:
+
+    ┌────────────┬────────────┬───────────────────────────────────────────┐
+    │  engine    │  manager   │  managed location                         │
+    ├────────────┼────────────┼───────────────────────────────────────────┤
+    │  rust      │  rustup    │  <prefix>/rustup  (RUSTUP_HOME)           │
+    │            │            │  <prefix>/cargo   (CARGO_HOME)            │
+    │  python    │  pyenv     │  <prefix>/pyenv   (PYENV_ROOT)            │
+    │  javascript│  nvm       │  <prefix>/nvm     (NVM_DIR)               │
+    └────────────┴────────────┴───────────────────────────────────────────┘
+
+Installed toolchains are recorded in ``<prefix>/toolchains.json``.
+``activate()`` prepends their bin directories to PATH so every executor
+(and the engine doctor) resolves the pinned binaries first.
+
+Prefix is configurable via:  DB setting → SPOKEDPY_TOOLCHAINS_DIR env → data/toolchains
+"""
+
+import os
+import json
+import time
+import shutil
+import tempfile
+import threading
+import subprocess
+from dataclasses import dataclass, field, asdict
+from typing import Callable, Dict, List, Optional, Tuple
+
+from . import offline
+from .execution_engine import _run_subprocess
+
+
+# Language aliases accepted on the command line → canonical engine language.
+_LANGUAGE_ALIASES = {
+    'rust': 'rust', 'rustc': 'rust',
+    'python': 'python', 'py': 'python',
+    'node': 'javascript', 'nodejs': 'javascript', 'javascript': 'javascript', 'js': 'javascript',
+}
+
+RUSTUP_INIT_URL = 'https://sh.rustup.rs'
+
+INSTALL_TIMEOUT = 1800          # seconds — compiling CPython via pyenv is slow
+
+
+class ToolchainError(RuntimeError):
+    """Raised when a toolchain cannot be provisioned."""
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# SPECS & MANIFEST
+# ═══════════════════════════════════════════════════════════════════════════
+
+def parse_toolchain_spec(spec: str) -> Tuple[str, str]:
+    """Parse ``lang@version`` into (language, version).
+
+    >>> parse_toolchain_spec('rust@1.84')
+    ('rust', '1.84')
+    """
+    name, sep, version = spec.strip().partition('@')
+    language = _LANGUAGE_ALIASES.get(name.lower())
+    if language is None:
+        raise ToolchainError(
+            f"No toolchain provider for '{name}' "
+            f"(supported: {', '.join(sorted(set(_LANGUAGE_ALIASES)))})"
+        )
+    if not sep or not version.strip():
+        raise ToolchainError(f"Toolchain spec '{spec}' must pin a version, e.g. {name}@1.84")
+    return language, version.strip()
+
+
+@dataclass
+class InstalledToolchain:
+    """One provisioned toolchain, as recorded in the manifest."""
+    language: str
+    version: str
+    manager: str
+    bin_dir: str
+    installed_at: float = 0.0
+
+    @property
+    def spec(self) -> str:
+        return f"{self.language}@{self.version}"
+
+
+@dataclass
+class InstallStep:
+    """A single command in an install plan."""
+    argv: List[str]
+    env: Dict[str, str] = field(default_factory=dict)
+    description: str = ''
+
+    def render(self) -> str:
+        env = ' '.join(f"{k}={v}" for k, v in sorted(self.env.items()))
+        return f"{env} {' '.join(self.argv)}".strip()
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# PROVIDERS — one per version manager
+# ═══════════════════════════════════════════════════════════════════════════
+
+class RustupProvider:
+    """Installs Rust toolchains with rustup into an isolated RUSTUP_HOME."""
+
+    manager = 'rustup'
+
+    def __init__(self, prefix: str):
+        self.rustup_home = os.path.join(prefix, 'rustup')
+        self.cargo_home = os.path.join(prefix, 'cargo')
+
+    def env(self) -> Dict[str, str]:
+        return {'RUSTUP_HOME': self.rustup_home, 'CARGO_HOME': self.cargo_home}
+
+    def _rustup(self) -> Optional[str]:
+        managed = os.path.join(self.cargo_home, 'bin', 'rustup')
+        if os.path.exists(managed):
+            return managed
+        return shutil.which('rustup')
+
+    def plan(self, version: str) -> List[InstallStep]:
+        steps = []
+        rustup = self._rustup()
+        if rustup is None:
+            curl = shutil.which('curl')
+            if curl is None:
+                raise ToolchainError('Neither rustup nor curl is available to bootstrap Rust')
+            script = os.path.join(tempfile.gettempdir(), 'spokedpy-rustup-init.sh')
+            steps.append(InstallStep(
+                [curl, '--proto', '=https', '--tlsv1.2', '-sSf', RUSTUP_INIT_URL, '-o', script],
+                description='download rustup-init',
+            ))
+            steps.append(InstallStep(
+                ['sh', script, '-y', '--no-modify-path', '--default-toolchain', 'none',
+                 '--profile', 'minimal'],
+                env=self.env(), description='bootstrap rustup into the managed prefix',
+            ))
+            rustup = os.path.join(self.cargo_home, 'bin', 'rustup')
+        steps.append(InstallStep(
+            [rustup, 'toolchain', 'install', version, '--profile', 'minimal'],
+            env=self.env(), description=f'install rust {version}',
+        ))
+        return steps
+
+    def bin_dir(self, version: str) -> str:
+        rustup = self._rustup()
+        if rustup is None:
+            raise ToolchainError('rustup disappeared after install')
+        proc = _run_subprocess([rustup, 'which', 'rustc', '--toolchain', version],
+                               capture_output=True, text=True, timeout=30,
+                               env={**os.environ, **self.env()})
+        path = (proc.stdout or '').strip()
+        if proc.returncode != 0 or not path:
+            raise ToolchainError(f"rustup cannot locate rustc {version}: {proc.stderr.strip()}")
+        return os.path.dirname(path)
+
+
+class PyenvProvider:
+    """Installs CPython versions with pyenv into an isolated PYENV_ROOT."""
+
+    manager = 'pyenv'
+
+    def __init__(self, prefix: str):
+        self.root = os.path.join(prefix, 'pyenv')
+
+    def env(self) -> Dict[str, str]:
+        return {'PYENV_ROOT': self.root}
+
+    def plan(self, version: str) -> List[InstallStep]:
+        pyenv = shutil.which('pyenv')
+        if pyenv is None:
+            raise ToolchainError('pyenv is not installed — see https://github.com/pyenv/pyenv#installation')
+        return [InstallStep([pyenv, 'install', '--skip-existing', version],
+                            env=self.env(), description=f'install python {version}')]
+
+    def bin_dir(self, version: str) -> str:
+        path = os.path.join(self.root, 'versions', version, 'bin')
+        if not os.path.isdir(path):
+            raise ToolchainError(f"pyenv reported success but {path} does not exist")
+        return path
+
+
+class NvmProvider:
+    """Installs Node.js versions with nvm into an isolated NVM_DIR."""
+
+    manager = 'nvm'
+
+    def __init__(self, prefix: str):
+        self.nvm_dir = os.path.join(prefix, 'nvm')
+
+    def env(self) -> Dict[str, str]:
+        return {'NVM_DIR': self.nvm_dir}
+
+    def _nvm_script(self) -> Optional[str]:
+        # nvm is a shell function, not a binary — source it from the user's
+        # install while pointing NVM_DIR at the managed prefix.
+        candidates = [
+            os.path.join(os.environ.get('NVM_DIR', ''), 'nvm.sh'),
+            os.path.expanduser('~/.nvm/nvm.sh'),
+            os.path.join(self.nvm_dir, 'nvm.sh'),
+        ]
+        for c in candidates:
+            if c and os.path.isfile(c):
+                return c
+        return None
+
+    def plan(self, version: str) -> List[InstallStep]:
+        script = self._nvm_script()
+        bash = shutil.which('bash')
+        if script is None or bash is None:
+            raise ToolchainError('nvm is not installed — see https://github.com/nvm-sh/nvm#installing-and-updating')
+        return [InstallStep([bash, '-c', f'. "{script}" && nvm install "{version}"'],
+                            env=self.env(), description=f'install node {version}')]
+
+    def bin_dir(self, version: str) -> str:
+        root = os.path.join(self.nvm_dir, 'versions', 'node')
+        wanted = version.lstrip('v')
+        matches = sorted(
+            d for d in (os.listdir(root) if os.path.isdir(root) else [])
+            if d.lstrip('v') == wanted or d.lstrip('v').startswith(wanted + '.')
+        )
+        if not matches:
+            raise ToolchainError(f"nvm reported success but no node {version} under {root}")
+        return os.path.join(root, matches[-1], 'bin')
+
+
+_PROVIDERS = {
+    'rust': RustupProvider,
+    'python': PyenvProvider,
+    'javascript': NvmProvider,
+}
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# TOOLCHAIN MANAGER
+# ═══════════════════════════════════════════════════════════════════════════
+
+class ToolchainManager:
+    """Plans, runs, and records toolchain installs under one prefix.
+
+    ``runner`` executes an InstallStep and returns (returncode, output); it
+    defaults to a subprocess call and exists so callers can stream output.
+    """
+
+    MANIFEST = 'toolchains.json'
+
+    def __init__(self, prefix: str,
+                 runner: Optional[Callable[[InstallStep], Tuple[int, str]]] = None):
+        self.prefix = prefix
+        self._runner = runner or self._run_step
+        self._lock = threading.Lock()
+
+    # ── Manifest ──────────────────────────────────────────────────────────
+
+    @property
+    def manifest_path(self) -> str:
+        return os.path.join(self.prefix, self.MANIFEST)
+
+    def installed(self) -> List[InstalledToolchain]:
+        """Return every toolchain recorded in the manifest."""
+        if not os.path.exists(self.manifest_path):
+            return []
+        with open(self.manifest_path, 'r', encoding='utf-8') as f:
+            data = json.load(f)
+        return [InstalledToolchain(**entry) for entry in data.get('toolchains', [])]
+
+    def _record(self, tc: InstalledToolchain):
+        with self._lock:
+            entries = [e for e in self.installed() if e.spec != tc.spec]
+            entries.append(tc)
+            os.makedirs(self.prefix, exist_ok=True)
+            tmp = self.manifest_path + '.tmp'
+            with open(tmp, 'w', encoding='utf-8') as f:
+                json.dump({'toolchains': [asdict(e) for e in entries]}, f, indent=2)
+            os.replace(tmp, self.manifest_path)
+
+    # ── Install ───────────────────────────────────────────────────────────
+
+    def provider(self, language: str):
+        return _PROVIDERS[language](self.prefix)
+
+    def plan(self, spec: str) -> List[InstallStep]:
+        """Return the commands ``install(spec)`` would run, without running them."""
+        language, version = parse_toolchain_spec(spec)
+        return self.provider(language).plan(version)
+
+    def install(self, spec: str) -> InstalledToolchain:
+        """Provision ``lang@version`` and record it in the manifest."""
+        language, version = parse_toolchain_spec(spec)
+        provider = self.provider(language)
+        offline.require_network(f"Installing {language}@{version}")
+        os.makedirs(self.prefix, exist_ok=True)
+        for step in provider.plan(version):
+            code, output = self._runner(step)
+            if code != 0:
+                tail = '\n'.join(output.strip().splitlines()[-15:])
+                raise ToolchainError(
+                    f"{step.description or step.argv[0]} failed (exit {code})\n{tail}"
+                )
+        tc = InstalledToolchain(
+            language=language,
+            version=version,
+            manager=provider.manager,
+            bin_dir=provider.bin_dir(version),
+            installed_at=time.time(),
+        )
+        self._record(tc)
+        return tc
+
+    @staticmethod
+    def _run_step(step: InstallStep) -> Tuple[int, str]:
+        try:
+            proc = _run_subprocess(step.argv, capture_output=True, text=True,
+                                   timeout=INSTALL_TIMEOUT,
+                                   env={**os.environ, **step.env})
+        except (OSError, subprocess.SubprocessError) as exc:
+            return 127, str(exc)
+        return proc.returncode, (proc.stdout or '') + (proc.stderr or '')
+
+    # ── Activation ────────────────────────────────────────────────────────
+
+    def activate(self, environ: Optional[Dict[str, str]] = None) -> List[str]:
+        """Prepend every managed bin dir to PATH (newest install wins per language).
+
+        The recorded bin dirs hold the real binaries (not rustup/pyenv shims),
+        so no manager-specific environment is needed at run time.
+        Mutates ``environ`` (default ``os.environ``) and returns the dirs added.
+        """
+        environ = os.environ if environ is None else environ
+        latest: Dict[str, InstalledToolchain] = {}
+        for tc in self.installed():
+            if not os.path.isdir(tc.bin_dir):
+                continue
+            current = latest.get(tc.language)
+            if current is None or tc.installed_at >= current.installed_at:
+                latest[tc.language] = tc
+        dirs = [tc.bin_dir for tc in latest.values()]
+        if not dirs:
+            return []
+        existing = [p for p in environ.get('PATH', '').split(os.pathsep) if p and p not in dirs]
+        environ['PATH'] = os.pathsep.join(dirs + existing)
+        return dirs
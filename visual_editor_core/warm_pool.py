@@ -0,0 +1,497 @@
+"""
+Warm Pools — pre-spawned interpreters for Python and Node spec runs.
+
+A spec case costs an interpreter start before the snippet runs a line.
+With ``[engines] warm_pool`` set, the daemon keeps workers booted and
+waiting, and ``_run_subprocess`` hands them the run instead:
+
+    ┌────────────┬──────────────────────────────────────────────────────────┐
+    │  python    │  a fork server (``python -I -u``, common stdlib modules  │
+    │            │  imported): each run is a fork of it with the case's     │
+    │            │  stdin / stdout / stderr, argv, env and cwd, so no run   │
+    │            │  sees another's state; the server is recycled after      │
+    │            │  ``warm_recycle`` runs                                   │
+    │  javascript│  ``node`` booted and blocked on its first stdin line:    │
+    │            │  it runs one file, then exits (Node cannot fork a clean  │
+    │            │  copy of itself), and a fresh one is booted behind it    │
+    └────────────┴──────────────────────────────────────────────────────────┘
+
+Workers are sandboxed the way a cold spawn of the same run would be: the
+network namespace of its level (offline.apply) is entered when the
+worker starts, so there is a pool per level; the scratch dir, HOME and
+TMPDIR, the write guard and the fixtures are the run's own, applied in
+the fork.  A run the pool cannot serve starts cold as before: one under
+a seccomp profile (the supervisor reports per run), a streaming client
+(it sees output as it is printed), or a command the pool does not know.
+
+A worker that dies, answers garbage or is killed on a timeout is
+replaced, never reused.  Python runs report wall and CPU time and peak
+RSS to an active Meter, Node runs their wall time.
+
+    [engines]
+    warm_pool = "python=4, javascript=2"     # workers kept ready per engine
+    warm_recycle = 100                       # runs per Python worker
+
+Enabled via:  DB setting → SPOKEDPY_WARM_POOL env → off
+"""
+
+import atexit
+import json
+import os
+import select
+import shutil
+import subprocess
+import sys
+import tempfile
+import threading
+import time
+from collections import deque
+from typing import Any, Dict, List, Optional, Tuple
+
+WARM_ENGINES = ('python', 'javascript')
+_ALIASES = {'node': 'javascript', 'js': 'javascript'}
+
+# Imported once by the Python fork server, so its forks start with them
+PRELOAD = ('json', 're', 'collections', 'itertools', 'functools', 'math', 'datetime',
+           'random', 'string', 'typing', 'dataclasses', 'traceback')
+
+_PYTHON_WORKER = '''
+import atexit, builtins, json, os, sys, threading, traceback, types
+for _name in %(preload)r:
+    __import__(_name)
+_proto = os.fdopen(os.dup(1), 'w', buffering=1)
+_proto.write('{"ready": true}\\n')
+for _line in sys.stdin.buffer:
+    _job = json.loads(_line)
+    _pid = os.fork()
+    if _pid == 0:
+        _status = 0
+        try:
+            for _fd, (_path, _flags) in enumerate(((_job['stdin'], os.O_RDONLY),
+                                                    (_job['stdout'], os.O_WRONLY),
+                                                    (_job['stderr'], os.O_WRONLY))):
+                _new = os.open(_path, _flags)
+                os.dup2(_new, _fd)
+                os.close(_new)
+            os.close(_proto.fileno())
+            os.chdir(_job['cwd'])
+            os.environ.clear()
+            os.environ.update(_job['env'])
+            sys.argv = ['-c'] + _job['args']
+            _main = types.ModuleType('__main__')
+            _main.__builtins__ = builtins
+            sys.modules['__main__'] = _main
+            _code = compile(_job['source'], '<string>', 'exec')
+            exec(_code, _main.__dict__)
+            for _t in threading.enumerate():
+                if _t is not threading.main_thread() and not _t.daemon:
+                    _t.join()
+        except SystemExit as _exit:
+            if _exit.code is None or isinstance(_exit.code, int):
+                _status = _exit.code or 0
+            else:
+                print(_exit.code, file=sys.stderr)
+                _status = 1
+        except BaseException:
+            traceback.print_exc()
+            _status = 1
+        try:
+            atexit._run_exitfuncs()
+            sys.stdout.flush()
+            sys.stderr.flush()
+        finally:
+            os._exit(_status & 0xff)
+    _proto.write(json.dumps({'pid': _pid}) + '\\n')
+    _, _wait, _usage = os.wait4(_pid, 0)
+    _proto.write(json.dumps({'exit_code': os.waitstatus_to_exitcode(_wait),
+                             'cpu': _usage.ru_utime + _usage.ru_stime,
+                             'peak_rss_kb': _usage.ru_maxrss // (1024 if sys.platform == 'darwin'
+                                                                 else 1)}) + '\\n')
+''' % {'preload': PRELOAD}
+
+_NODE_WORKER = '''
+const fs = require('fs');
+const bytes = [];
+const one = Buffer.alloc(1);
+while (fs.readSync(0, one, 0, 1, null) === 1 && one[0] !== 10) bytes.push(one[0]);
+if (bytes.length) {
+  const job = JSON.parse(Buffer.from(bytes).toString('utf8'));
+  process.chdir(job.cwd);
+  for (const key of Object.keys(process.env)) delete process.env[key];
+  Object.assign(process.env, job.env);
+  process.argv = [process.argv[0], job.file, ...job.args];
+  require('module').runMain();
+}
+'''
+
+_state: Dict[str, Any] = {'sizes': {}, 'recycle': 100, 'generation': 0}
+_lock = threading.Lock()
+_idle: Dict[Tuple[str, Tuple[str, ...]], deque] = {}
+_stats: Dict[str, Dict[str, int]] = {}
+
+
+def parse_sizes(value: str) -> Dict[str, int]:
+    """``python=4, javascript=2`` (or a bare number for both) → engine → workers."""
+    sizes: Dict[str, int] = {}
+    for item in (part.strip() for part in (value or '').split(',')):
+        if not item:
+            continue
+        engine, _, count = item.rpartition('=')
+        engines = [_ALIASES.get(engine.strip().lower(), engine.strip().lower())] if engine \
+            else list(WARM_ENGINES)
+        if any(e not in WARM_ENGINES for e in engines):
+            raise ValueError(f"warm_pool: no warm pool for engine '{engine.strip()}' "
+                             f"(only {', '.join(WARM_ENGINES)})")
+        try:
+            workers = int(count)
+        except ValueError:
+            raise ValueError(f"warm_pool: '{item}' is not ENGINE=WORKERS") from None
+        if workers < 0:
+            raise ValueError(f"warm_pool: '{item}' — the worker count cannot be negative")
+        for e in engines:
+            sizes[e] = workers
+    return {e: n for e, n in sizes.items() if n}
+
+
+def configure(sizes: str, recycle: int = 100):
+    """Set the pool sizes (``[engines] warm_pool``) and runs per Python worker; boots them."""
+    if int(recycle) < 1:
+        raise ValueError(f"warm_recycle must be at least 1 (got {recycle})")
+    parsed = parse_sizes(sizes)
+    shutdown()
+    with _lock:
+        _state['sizes'], _state['recycle'] = parsed, int(recycle)
+        _state['generation'] += 1           # workers still booting for the old sizes are dropped
+        _stats.clear()
+        for engine in parsed:
+            _stats[engine] = {'served': 0, 'cold': 0, 'booted': 0, 'recycled': 0}
+    from .offline import network_for, spec_network
+    for engine in parsed:
+        with spec_network(network_for(engine)):
+            key = _key(engine)
+        if key is not None:
+            _refill(engine, key)
+
+
+def sizes() -> Dict[str, int]:
+    return dict(_state['sizes'])
+
+
+def recycle() -> int:
+    return _state['recycle']
+
+
+def stats() -> Dict[str, Dict[str, int]]:
+    """Per engine: runs served warm, runs started cold, workers booted and recycled, idle now."""
+    with _lock:
+        out = {e: dict(s) for e, s in _stats.items()}
+        for (engine, _key_), workers in _idle.items():
+            out.setdefault(engine, {})['idle'] = out[engine].get('idle', 0) + len(workers)
+    return out
+
+
+def _count(engine: str, what: str, n: int = 1):
+    with _lock:
+        if engine in _stats:                # not if the pool was reconfigured meanwhile
+            _stats[engine][what] += n
+
+
+def shutdown():
+    """Stop every idle worker (at exit, and before reconfiguring)."""
+    with _lock:
+        workers = [w for q in _idle.values() for w in q]
+        _idle.clear()
+    for worker in workers:
+        worker.stop()
+
+
+atexit.register(shutdown)
+
+
+# ─────────────────────────────────────────────────────────────────────────
+# WORKERS
+# ─────────────────────────────────────────────────────────────────────────
+
+def _node() -> Optional[str]:
+    return shutil.which('node')
+
+
+def _command(engine: str) -> Optional[List[str]]:
+    if engine == 'python':
+        return [sys.executable, '-I', '-u', '-c', _PYTHON_WORKER]
+    node = _node()
+    return [node, '-e', _NODE_WORKER] if node else None
+
+
+def _key(engine: str) -> Optional[Tuple[str, ...]]:
+    """The worker command as this thread's spec run would spawn it (its sandbox prefix included)."""
+    from . import offline
+    command = _command(engine)
+    if command is None:
+        return None
+    try:
+        (wrapped, *_rest), _kwargs = offline.apply((command,), {})
+    except offline.OfflineError:
+        return None
+    return tuple(wrapped)
+
+
+class _Worker:
+    """One booted interpreter, started from ``command`` (a pool key)."""
+
+    def __init__(self, engine: str, command: Tuple[str, ...]):
+        self.engine, self.command, self.runs = engine, command, 0
+        self.replaced = False               # its successor is booting already
+        # A fork server answers on stdout only; a Node worker's stdio is its run's
+        self.proc = subprocess.Popen(list(command), stdin=subprocess.PIPE, stdout=subprocess.PIPE,
+                                     stderr=subprocess.DEVNULL if engine == 'python'
+                                     else subprocess.PIPE, env=dict(os.environ))
+        self._ready = engine != 'python'
+        self._pending = b''                 # read past the last protocol line
+
+    def alive(self) -> bool:
+        return self.proc.poll() is None
+
+    def read(self, deadline: Optional[float] = None) -> Optional[Dict[str, Any]]:
+        """The worker's next protocol line (None past ``deadline``); ValueError if it broke."""
+        fd = self.proc.stdout.fileno()
+        while b'\n' not in self._pending:
+            if deadline is not None:
+                ready, _, _ = select.select([fd], [], [], max(0.0, deadline - time.time()))
+                if not ready:
+                    return None
+            chunk = os.read(fd, 65536)
+            if not chunk:
+                raise ValueError('warm worker exited')
+            self._pending += chunk
+        line, self._pending = self._pending.split(b'\n', 1)
+        return json.loads(line)
+
+    def wait_ready(self):
+        if not self._ready:
+            if self.read().get('ready') is not True:
+                raise ValueError('warm worker did not start')
+            self._ready = True
+
+    def stop(self):
+        if self.alive():
+            self.proc.kill()
+        try:
+            self.proc.wait(timeout=5)
+        except subprocess.TimeoutExpired:
+            pass
+        for stream in (self.proc.stdin, self.proc.stdout):
+            try:
+                stream.close()
+            except OSError:
+                pass
+
+
+def _boot(engine: str, key: Tuple[str, ...], generation: int):
+    try:
+        worker = _Worker(engine, key)
+    except OSError:
+        return
+    with _lock:
+        if generation == _state['generation']:
+            _stats[engine]['booted'] += 1
+            workers = _idle.setdefault((engine, key), deque())
+            if len(workers) < _state['sizes'].get(engine, 0):
+                workers.append(worker)
+                return
+    worker.stop()                                   # full already, or the pool was reconfigured
+
+
+def _refill(engine: str, key: Tuple[str, ...]):
+    """Boot workers (in the background) until ``engine``'s pool for ``key`` is full."""
+    with _lock:
+        missing = _state['sizes'].get(engine, 0) - len(_idle.get((engine, key), ()))
+        generation = _state['generation']
+    for _ in range(max(0, missing)):
+        threading.Thread(target=_boot, args=(engine, key, generation), daemon=True,
+                         name=f"warm-{engine}").start()
+
+
+def _checkout(engine: str, key: Tuple[str, ...]) -> Optional[_Worker]:
+    """An idle worker for ``key``, if one is alive; dead ones found on the way are replaced."""
+    found, dead = None, []
+    with _lock:
+        workers = _idle.get((engine, key)) or deque()
+        while workers and found is None:
+            worker = workers.popleft()
+            if worker.alive():
+                found = worker
+            else:
+                dead.append(worker)
+    _count(engine, 'recycled', len(dead))
+    for worker in dead:
+        worker.stop()
+    if found is not None and engine == 'python' and found.runs + 1 >= _state['recycle']:
+        found.replaced = True               # its last run: boot the successor meanwhile
+    if dead or found is None or engine != 'python' or found.replaced:
+        _refill(engine, key)                # a Node worker runs once: boot the next now
+    return found
+
+
+def _checkin(worker: _Worker):
+    """Back to the idle pool, or — worn out, dead or one too many — stopped (and replaced)."""
+    engine, key = worker.engine, worker.command
+    if engine == 'python' and worker.alive() and worker.runs < _state['recycle']:
+        with _lock:
+            workers = _idle.setdefault((engine, key), deque())
+            if len(workers) < _state['sizes'].get(engine, 0):
+                workers.append(worker)
+                return
+        worker.stop()
+        return
+    worker.stop()
+    if engine == 'python':
+        _count(engine, 'recycled')
+        if not worker.replaced:
+            _refill(engine, key)
+
+
+# ─────────────────────────────────────────────────────────────────────────
+# SERVING A RUN
+# ─────────────────────────────────────────────────────────────────────────
+
+def _engine_of(argv) -> Optional[str]:
+    if not isinstance(argv, (list, tuple)) or len(argv) < 2:
+        return None
+    if argv[0] == sys.executable and list(argv[1:4]) == ['-I', '-u', '-c'] and len(argv) >= 5:
+        return 'python'
+    node = _node()
+    if node and argv[0] in (node, 'node') and not argv[1].startswith('-'):
+        return 'javascript'
+    return None
+
+
+def serve(args: tuple, kwargs: dict) -> Optional[subprocess.CompletedProcess]:
+    """
+    Run a ``_run_subprocess`` call (after scratch.apply) on a warm worker;
+    None if the pool cannot serve it and it should start cold.
+    """
+    if not _state['sizes'] or not args or kwargs.get('shell') or not kwargs.get('capture_output'):
+        return None
+    engine = _engine_of(args[0])
+    if engine is None or engine not in _state['sizes']:
+        return None
+    from . import offline, output_stream, seccomp
+    if output_stream.current_sink() is not None or seccomp.apply(args, kwargs)[0] is not args:
+        return None
+    key = _key(engine)
+    if key is None:
+        return None
+    worker = _checkout(engine, key)
+    if worker is None:
+        _count(engine, 'cold')
+        return None
+    _args, applied = offline.apply(args, kwargs)        # offline mode's env for package managers
+    env = applied.get('env')
+    job = {'args': [str(a) for a in args[0][(5 if engine == 'python' else 2):]],
+           'env': dict(os.environ if env is None else env),
+           'cwd': kwargs.get('cwd') or os.getcwd()}
+    try:
+        result = (_run_python if engine == 'python' else _run_node)(worker, args[0], job, kwargs)
+    except ValueError:                                  # broke before the run started
+        _checkin(worker)
+        _count(engine, 'cold')
+        return None
+    except subprocess.TimeoutExpired:
+        _count(engine, 'served')
+        raise
+    _count(engine, 'served')
+    return result
+
+
+def _decode(data: bytes, kwargs: dict):
+    if not (kwargs.get('text') or kwargs.get('encoding') or kwargs.get('universal_newlines')):
+        return data
+    return data.decode(kwargs.get('encoding') or 'utf-8', kwargs.get('errors') or 'replace')
+
+
+def _encode(data, kwargs: dict) -> bytes:
+    if data is None:
+        return b''
+    if isinstance(data, str):
+        return data.encode(kwargs.get('encoding') or 'utf-8', kwargs.get('errors') or 'replace')
+    return data
+
+
+def _record(engine: str, started: float, exit_code: int, reply: Optional[Dict[str, Any]] = None):
+    from . import accounting, output_stream
+    measured = {} if reply is None else {'cpu': reply.get('cpu'),
+                                         'peak_rss_kb': reply.get('peak_rss_kb')}
+    process = 'python' if engine == 'python' else 'node'
+    accounting.record(output_stream.process_phase(process), process, time.time() - started,
+                      exit_code=exit_code, **measured)
+
+
+def _run_python(worker: _Worker, argv, job: Dict[str, Any], kwargs: dict):
+    """A fork of the Python server, with the run's stdio in private temp files."""
+    timeout = kwargs.get('timeout')
+    folder = tempfile.mkdtemp(prefix='spokedpy-warm-')
+    try:
+        paths = {name: os.path.join(folder, name) for name in ('stdin', 'stdout', 'stderr')}
+        for name, path in paths.items():
+            with open(path, 'wb') as f:
+                if name == 'stdin':
+                    f.write(_encode(kwargs.get('input'), kwargs))
+        worker.wait_ready()
+        started = time.time()
+        try:
+            worker.proc.stdin.write((json.dumps({**job, 'source': argv[4], **paths}) + '\n')
+                                    .encode('utf-8'))
+            worker.proc.stdin.flush()
+        except OSError:
+            raise ValueError('warm worker exited') from None
+        pid = worker.read()['pid']
+        worker.runs += 1
+        try:
+            reply = worker.read(None if timeout is None else started + timeout)
+        except ValueError:                              # the server died mid-run
+            reply = {'exit_code': -9}
+        timed_out = reply is None
+        if timed_out:
+            try:
+                os.kill(pid, 9)
+            except OSError:
+                pass
+            reply = worker.read()
+        with open(paths['stdout'], 'rb') as f:
+            stdout = _decode(f.read(), kwargs)
+        with open(paths['stderr'], 'rb') as f:
+            stderr = _decode(f.read(), kwargs)
+        _record('python', started, reply['exit_code'], reply)
+    finally:
+        shutil.rmtree(folder, ignore_errors=True)
+    _checkin(worker)
+    if timed_out:
+        raise subprocess.TimeoutExpired(list(argv), timeout, output=stdout, stderr=stderr)
+    return subprocess.CompletedProcess(list(argv), reply['exit_code'], stdout, stderr)
+
+
+def _run_node(worker: _Worker, argv, job: Dict[str, Any], kwargs: dict):
+    """The booted Node runs the file; its own stdio is the run's."""
+    timeout = kwargs.get('timeout')
+    line = (json.dumps({**job, 'file': os.path.abspath(str(argv[1]))}) + '\n').encode('utf-8')
+    if not worker.alive():
+        raise ValueError('warm worker exited')
+    started = time.time()
+    try:
+        stdout, stderr = worker.proc.communicate(line + _encode(kwargs.get('input'), kwargs),
+                                                 timeout=timeout)
+    except subprocess.TimeoutExpired:
+        worker.proc.kill()
+        stdout, stderr = worker.proc.communicate()
+        worker.stop()
+        _record('javascript', started, -9)
+        raise subprocess.TimeoutExpired(list(argv), timeout, output=_decode(stdout, kwargs),
+                                        stderr=_decode(stderr or b'', kwargs)) from None
+    except OSError:
+        raise ValueError('warm worker exited') from None
+    code = worker.proc.returncode
+    worker.stop()
+    _record('javascript', started, code)
+    return subprocess.CompletedProcess(list(argv), code, _decode(stdout, kwargs),
+                                       _decode(stderr or b'', kwargs))
@@ -28,6 +28,7 @@ from visual_editor_core.session_ledger import (
     SessionLedger, LanguageID, resolve_language_id, resolve_language_string,
     LedgerEventType, NodeSnapshot, DependencyStrategy, resolve_dependency_strategy
 )
+from visual_editor_core import offline
 
 app = Flask(__name__)
 app.config['SECRET_KEY'] = 'visual-editor-secret-key'
@@ -2679,6 +2680,11 @@ def ai_chat_proxy():
         if not endpoint:
             return jsonify({'error': 'No endpoint configured'}), 400
 
+        try:
+            offline.require_host(endpoint, 'AI chat')
+        except offline.OfflineError as e:
+            return jsonify({'error': str(e)}), 503
+
         headers = {'Content-Type': 'application/json'}
         if api_key:
             headers['Authorization'] = f'Bearer {api_key}'
@@ -3629,22 +3635,20 @@ init_runtime(app, session_ledger, socketio)
 import atexit as _atexit
 
 def _shutdown_checkpoint():
-    """Write a final state checkpoint before the process exits."""
+    """Write a final state checkpoint (one per store) before the process exits."""
     try:
-        from web_interface.runtime import (
-            _state_persistence, staging_pipeline,
-            _marshal_tokens, _marshal_lock,
-            _locked_slots, _locked_slots_lock,
-        )
+        from web_interface import tenants
+        from web_interface.runtime import _marshal_tokens, _marshal_lock, _locked_slots_lock
         from web_interface.state_persistence import build_promoted_snapshots
-        if _state_persistence is not None and staging_pipeline is not None:
+        for store in tenants.stores():
             with _marshal_lock:
                 tokens_copy = dict(_marshal_tokens)
             with _locked_slots_lock:
-                locks_copy = dict(_locked_slots)
-            snapshots = build_promoted_snapshots(staging_pipeline, tokens_copy, locks_copy)
-            _state_persistence.checkpoint_now(locks_copy, tokens_copy, snapshots)
-            print("  [STATE] Shutdown checkpoint written.")
+                locks_copy = dict(store.locked_slots)
+            snapshots = build_promoted_snapshots(store.pipeline, tokens_copy, locks_copy)
+            store.state_persistence.checkpoint_now(locks_copy, tokens_copy, snapshots)
+            print(f"  [STATE] Shutdown checkpoint written"
+                  f"{' (tenant ' + store.name + ')' if store.name else ''}.")
     except Exception as exc:
         print(f"  [STATE] Shutdown checkpoint FAILED: {exc}")
 
@@ -3662,7 +3666,12 @@ from web_interface.swagger import register_swagger
 register_swagger(app)
 
 
-if __name__ == '__main__':
+def run_server(host=None, port=None, ui=None):
+    """Run the web interface until interrupted (``spokedpy serve``).
+
+    ``host`` / ``port`` / ``ui`` override the [server] settings; ``ui``
+    also mounts the store browser at /ui (see store_ui).
+    """
     # Create templates and static directories
     os.makedirs('templates', exist_ok=True)
     os.makedirs('static', exist_ok=True)
@@ -3694,11 +3703,19 @@ if __name__ == '__main__':
     # SPOKEDPY_RELOADER=1 env var to enable during active development
     # on runtime.py / app.py only.
     import os as _os
-    use_reloader = _os.environ.get('SPOKEDPY_RELOADER', '0') == '1'
-    host = _os.environ.get('SPOKEDPY_HOST', '0.0.0.0')
-    port = int(_os.environ.get('SPOKEDPY_PORT', '5002'))
+    from web_interface.project_db import resolve_setting as _resolve_setting
+    use_reloader = _resolve_setting('reloader', 'SPOKEDPY_RELOADER', '0') == '1'
+    host = host or _resolve_setting('host', 'SPOKEDPY_HOST', '0.0.0.0')
+    port = int(port or _resolve_setting('port', 'SPOKEDPY_PORT', '5002'))
+    if ui is None:
+        ui = _resolve_setting('ui', 'SPOKEDPY_UI', '0').strip().lower() in ('1', 'true', 'yes', 'on')
+    if ui:
+        from web_interface.store_ui import register_store_ui
+        register_store_ui(app)
 
     print(f"Access the interface at: http://localhost:{port}")
+    if ui:
+        print(f"Store browser at:        http://localhost:{port}/ui")
 
     socketio.run(
         app,
@@ -3707,3 +3724,7 @@ if __name__ == '__main__':
         port=port,
         use_reloader=use_reloader,
     )
+
+
+if __name__ == '__main__':
+    run_server()
@@ -0,0 +1,525 @@
+"""
+Config Layers — ``spokedpy.toml`` files layered under env vars and DB overrides.
+
+Every setting resolves through the same stack, highest priority first:
+
+    ┌────────────┬──────────────────────────────────────────────────────────┐
+    │  database  │  web-UI override (Settings Hub / PUT /api/settings/<k>)   │
+    │  env       │  SPOKEDPY_* environment variable                         │
+    │  profile   │  [profiles.<name>] tables of the active profile          │
+    │            │  (--profile / SPOKEDPY_PROFILE), from any file below     │
+    │  project   │  $SPOKEDPY_CONFIG, else nearest ./spokedpy.toml walking   │
+    │            │  up from the cwd, else <repo>/spokedpy.toml               │
+    │  user      │  $XDG_CONFIG_HOME/spokedpy/spokedpy.toml                  │
+    │            │  (%APPDATA%\\SpokedPy\\spokedpy.toml on Windows)          │
+    │  system    │  /etc/spokedpy/spokedpy.toml                              │
+    │            │  (%PROGRAMDATA%\\SpokedPy\\spokedpy.toml on Windows)      │
+    │  default   │  hard-coded fallback                                     │
+    └────────────┴──────────────────────────────────────────────────────────┘
+
+File layout (every key optional; relative paths resolve against the file):
+
+    [store]      snippets_dir, audit_log, state_checkpoint, db_path,
+                 toolchains_dir, vendor_dir, failures_dir, trends_log, trash_dir,
+                 specs_dir = "data/specs"  (a default spec per label, <label>/default.spec.toml),
+                 index_path = "data/store_index.sqlite"  (SlotResolver's index),
+                 read_only = false   (serve without ever writing the store),
+                 shard_width = 2   (code_hash hex digits per promoted-file subdir),
+                 id_mode = "random" | "deterministic" | "ulid"   (how staging_ids are made)
+    [engines]    enabled = ["python", "rust"],
+                 warm_pool = "python=4, javascript=2"   (pre-booted workers for spec runs),
+                 warm_recycle = 100   (runs before a Python worker is replaced)
+    [gates]      auto_promote = true, allow_override = true,
+                 plugins_dir = "data/plugins"   (WASM gate plugins),
+                 policy_file = "data/promotion_policy.toml"  (per-slot rules),
+                 constraints_file = "data/slot_constraints.toml",
+                 owners_file = "data/OWNERS"  (approvers per slot / label),
+                 windows = "nightly=02:00, weekly=sun 02:00"  (UTC)
+    [retention]  history_limit = 1000,
+                 trash_days = 30   (deleted snippets are purged after; 0: never)
+    [quota]      max_staged = 0   (snippets staged at once per author / namespace; 0: no limit),
+                 max_spec_minutes = 0   (dry-run minutes per UTC day, likewise)
+    [revalidate] interval_minutes = 0   (re-spec promoted snippets this often; failing
+                 slots turn DEGRADED; 0: never)
+    [sandbox]    offline = false, backend = "process" | "netns",
+                 scratch = true   (dry-runs in a throwaway dir, writes outside it refused),
+                 network = "none" | "loopback" | "full"   (spec runs; per engine:
+                 "none, javascript=loopback"),
+                 seccomp = "off" | "dev" | "prod"   (syscalls denied to spec runs)
+    [server]     host, port, reloader, ui, marshal_ttl,
+                 reviewers = ["name:token", …]   (approvals API)
+    [notify]     diff_url, anomaly_sigmas = 3, anomaly_window = 20;
+                 [notify.slack] webhook_url, events;
+                 [notify.email] smtp_host, smtp_port, username, password,
+                 starttls, from, to, events;
+                 [notify.templates] spec_failure, spec_anomaly,
+                 approval_request, promotion, rollback, degraded
+    [profiles.<name>.<section>]   any of the above, applied with --profile
+    [tenants.<name>]   token, data_dir = "data/tenants/<name>";
+                 [tenants.<name>.<section>]   a store's own [store] paths, [gates]
+                 auto_promote / allow_override / windows / *_file, [retention], [quota],
+                 [revalidate], [server] reviewers  (serve: /t/<name>/… or X-Spokedpy-Token)
+    [ai]         endpoint, api_key, model, temperature, system_prompt
+    [canvas]     parallax_factor, grid_size, zoom_min, zoom_max, snap_to_grid
+
+``spokedpy config show --resolved`` prints which layer set each value.
+
+This module must not import project_db (project_db imports it to resolve
+``store.db_path`` before the database exists).
+"""
+
+import os
+import re
+import sys
+import threading
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional, Tuple
+
+try:
+    import tomllib
+except ModuleNotFoundError:          # Python < 3.11
+    import tomli as tomllib
+
+
+CONFIG_FILENAME = 'spokedpy.toml'
+
+_ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+_DATA_DIR = os.path.join(_ROOT, 'data')
+
+
+class ConfigError(ValueError):
+    """Raised when a spokedpy.toml file cannot be parsed."""
+
+
+@dataclass(frozen=True)
+class ConfigKey:
+    """One setting as it appears in spokedpy.toml."""
+    path: str                   # dotted TOML path, e.g. 'store.snippets_dir'
+    env: str                    # SPOKEDPY_* override
+    default: str
+    is_path: bool = False       # relative values resolve against the file
+    secret: bool = False        # masked by `config show`
+
+
+# setting key → where it lives in spokedpy.toml
+CONFIG_SCHEMA: Dict[str, ConfigKey] = {
+    # ── store ──────────────────────────────────────────────────────
+    'snippets_dir':     ConfigKey('store.snippets_dir', 'SPOKEDPY_SNIPPETS_DIR',
+                                  os.path.join(_DATA_DIR, 'snippets'), is_path=True),
+    'audit_log':        ConfigKey('store.audit_log', 'SPOKEDPY_AUDIT_LOG',
+                                  os.path.join(_DATA_DIR, 'staging_audit.jsonl'), is_path=True),
+    'state_checkpoint': ConfigKey('store.state_checkpoint', 'SPOKEDPY_STATE_CHECKPOINT',
+                                  os.path.join(_DATA_DIR, 'runtime_state.json'), is_path=True),
+    'db_path':          ConfigKey('store.db_path', 'SPOKEDPY_DB_PATH',
+                                  os.path.join(_ROOT, 'web_interface', 'projects.db'), is_path=True),
+    'toolchains_dir':   ConfigKey('store.toolchains_dir', 'SPOKEDPY_TOOLCHAINS_DIR',
+                                  os.path.join(_DATA_DIR, 'toolchains'), is_path=True),
+    'vendor_dir':       ConfigKey('store.vendor_dir', 'SPOKEDPY_VENDOR_DIR',
+                                  os.path.join(_DATA_DIR, 'vendor'), is_path=True),
+    'failures_dir':     ConfigKey('store.failures_dir', 'SPOKEDPY_FAILURES_DIR',
+                                  os.path.join(_DATA_DIR, 'failures'), is_path=True),
+    'trends_log':       ConfigKey('store.trends_log', 'SPOKEDPY_TRENDS_LOG',
+                                  os.path.join(_DATA_DIR, 'spec_trends.jsonl'), is_path=True),
+    'trash_dir':        ConfigKey('store.trash_dir', 'SPOKEDPY_TRASH_DIR',
+                                  os.path.join(_DATA_DIR, 'trash'), is_path=True),
+    'specs_dir':        ConfigKey('store.specs_dir', 'SPOKEDPY_SPECS_DIR',
+                                  os.path.join(_DATA_DIR, 'specs'), is_path=True),
+    'index_path':       ConfigKey('store.index_path', 'SPOKEDPY_INDEX_PATH',
+                                  os.path.join(_DATA_DIR, 'store_index.sqlite'), is_path=True),
+    'read_only':        ConfigKey('store.read_only', 'SPOKEDPY_READ_ONLY', '0'),
+    'shard_width':      ConfigKey('store.shard_width', 'SPOKEDPY_SHARD_WIDTH', '2'),
+    'id_mode':          ConfigKey('store.id_mode', 'SPOKEDPY_ID_MODE', 'random'),
+    # ── engines / gates / retention / sandbox ──────────────────────
+    'engines':          ConfigKey('engines.enabled', 'SPOKEDPY_ENGINES', ''),
+    'warm_pool':        ConfigKey('engines.warm_pool', 'SPOKEDPY_WARM_POOL', ''),
+    'warm_recycle':     ConfigKey('engines.warm_recycle', 'SPOKEDPY_WARM_RECYCLE', '100'),
+    'auto_promote':     ConfigKey('gates.auto_promote', 'SPOKEDPY_AUTO_PROMOTE', '1'),
+    'gate_override':    ConfigKey('gates.allow_override', 'SPOKEDPY_GATE_OVERRIDE', '1'),
+    'windows':          ConfigKey('gates.windows', 'SPOKEDPY_WINDOWS', 'nightly=02:00,weekly=sun 02:00'),
+    'plugins_dir':      ConfigKey('gates.plugins_dir', 'SPOKEDPY_PLUGINS_DIR',
+                                  os.path.join(_DATA_DIR, 'plugins'), is_path=True),
+    'policy_file':      ConfigKey('gates.policy_file', 'SPOKEDPY_POLICY_FILE',
+                                  os.path.join(_DATA_DIR, 'promotion_policy.toml'), is_path=True),
+    'constraints_file': ConfigKey('gates.constraints_file', 'SPOKEDPY_CONSTRAINTS_FILE',
+                                  os.path.join(_DATA_DIR, 'slot_constraints.toml'), is_path=True),
+    'owners_file':      ConfigKey('gates.owners_file', 'SPOKEDPY_OWNERS_FILE',
+                                  os.path.join(_DATA_DIR, 'OWNERS'), is_path=True),
+    'builtin_gates':    ConfigKey('gates.builtin', 'SPOKEDPY_GATES_BUILTIN', ''),
+    'fuzz_seconds':     ConfigKey('gates.fuzz_seconds', 'SPOKEDPY_FUZZ_SECONDS', '30'),
+    'mutation_min_kill_rate': ConfigKey('gates.mutation_min_kill_rate',
+                                        'SPOKEDPY_MUTATION_MIN_KILL_RATE', '0.6'),
+    'mutation_limit':   ConfigKey('gates.mutation_limit', 'SPOKEDPY_MUTATION_LIMIT', '40'),
+    'coverage_min':     ConfigKey('gates.coverage_min', 'SPOKEDPY_COVERAGE_MIN', '80'),
+    'fuzz_fixtures_dir': ConfigKey('gates.fuzz_fixtures_dir', 'SPOKEDPY_FUZZ_FIXTURES_DIR',
+                                   os.path.join(_DATA_DIR, 'fuzz_fixtures'), is_path=True),
+    'history_limit':    ConfigKey('retention.history_limit', 'SPOKEDPY_HISTORY_LIMIT', '1000'),
+    'trash_days':       ConfigKey('retention.trash_days', 'SPOKEDPY_TRASH_DAYS', '30'),
+    'quota_max_staged': ConfigKey('quota.max_staged', 'SPOKEDPY_QUOTA_MAX_STAGED', '0'),
+    'quota_max_spec_minutes': ConfigKey('quota.max_spec_minutes',
+                                        'SPOKEDPY_QUOTA_MAX_SPEC_MINUTES', '0'),
+    'revalidate_minutes': ConfigKey('revalidate.interval_minutes',
+                                    'SPOKEDPY_REVALIDATE_MINUTES', '0'),
+    'offline':          ConfigKey('sandbox.offline', 'SPOKEDPY_OFFLINE', '0'),
+    'sandbox_backend':  ConfigKey('sandbox.backend', 'SPOKEDPY_SANDBOX_BACKEND', 'process'),
+    'scratch':          ConfigKey('sandbox.scratch', 'SPOKEDPY_SCRATCH', '1'),
+    'network':          ConfigKey('sandbox.network', 'SPOKEDPY_NETWORK', 'none'),
+    'seccomp':          ConfigKey('sandbox.seccomp', 'SPOKEDPY_SECCOMP', 'off'),
+    # ── server ─────────────────────────────────────────────────────
+    'host':             ConfigKey('server.host', 'SPOKEDPY_HOST', '0.0.0.0'),
+    'port':             ConfigKey('server.port', 'SPOKEDPY_PORT', '5002'),
+    'reloader':         ConfigKey('server.reloader', 'SPOKEDPY_RELOADER', '0'),
+    'ui':               ConfigKey('server.ui', 'SPOKEDPY_UI', '0'),
+    'marshal_ttl':      ConfigKey('server.marshal_ttl', 'SPOKEDPY_MARSHAL_TTL', '4000'),
+    'reviewers':        ConfigKey('server.reviewers', 'SPOKEDPY_REVIEWERS', '', secret=True),
+    # ── notify ─────────────────────────────────────────────────────
+    'diff_url':         ConfigKey('notify.diff_url', 'SPOKEDPY_DIFF_URL',
+                                  'http://localhost:5002/api/staging/snippet/$staging_id'),
+    'anomaly_sigmas':   ConfigKey('notify.anomaly_sigmas', 'SPOKEDPY_ANOMALY_SIGMAS', '3'),
+    'anomaly_window':   ConfigKey('notify.anomaly_window', 'SPOKEDPY_ANOMALY_WINDOW', '20'),
+    'slack_webhook':    ConfigKey('notify.slack.webhook_url', 'SPOKEDPY_SLACK_WEBHOOK', '', secret=True),
+    'slack_events':     ConfigKey('notify.slack.events', 'SPOKEDPY_SLACK_EVENTS',
+                                  'spec_failure,spec_anomaly,approval_request,promotion,rollback,'
+                                  'degraded'),
+    'smtp_host':        ConfigKey('notify.email.smtp_host', 'SPOKEDPY_SMTP_HOST', ''),
+    'smtp_port':        ConfigKey('notify.email.smtp_port', 'SPOKEDPY_SMTP_PORT', '587'),
+    'smtp_username':    ConfigKey('notify.email.username', 'SPOKEDPY_SMTP_USERNAME', ''),
+    'smtp_password':    ConfigKey('notify.email.password', 'SPOKEDPY_SMTP_PASSWORD', '', secret=True),
+    'smtp_starttls':    ConfigKey('notify.email.starttls', 'SPOKEDPY_SMTP_STARTTLS', '1'),
+    'email_from':       ConfigKey('notify.email.from', 'SPOKEDPY_EMAIL_FROM', 'spokedpy@localhost'),
+    'email_to':         ConfigKey('notify.email.to', 'SPOKEDPY_EMAIL_TO', ''),
+    'email_events':     ConfigKey('notify.email.events', 'SPOKEDPY_EMAIL_EVENTS',
+                                  'spec_failure,spec_anomaly,approval_request,promotion,rollback,'
+                                  'degraded'),
+    'template_spec_failure': ConfigKey('notify.templates.spec_failure',
+                                       'SPOKEDPY_TEMPLATE_SPEC_FAILURE', ''),
+    'template_spec_anomaly': ConfigKey('notify.templates.spec_anomaly',
+                                       'SPOKEDPY_TEMPLATE_SPEC_ANOMALY', ''),
+    'template_approval_request': ConfigKey('notify.templates.approval_request',
+                                           'SPOKEDPY_TEMPLATE_APPROVAL_REQUEST', ''),
+    'template_promotion':    ConfigKey('notify.templates.promotion',
+                                       'SPOKEDPY_TEMPLATE_PROMOTION', ''),
+    'template_rollback':     ConfigKey('notify.templates.rollback',
+                                       'SPOKEDPY_TEMPLATE_ROLLBACK', ''),
+    'template_degraded':     ConfigKey('notify.templates.degraded',
+                                       'SPOKEDPY_TEMPLATE_DEGRADED', ''),
+    # ── ai ─────────────────────────────────────────────────────────
+    'ai_endpoint':      ConfigKey('ai.endpoint', 'SPOKEDPY_AI_ENDPOINT', 'https://api.openai.com/v1'),
+    'ai_api_key':       ConfigKey('ai.api_key', 'SPOKEDPY_AI_API_KEY', '', secret=True),
+    'ai_model':         ConfigKey('ai.model', 'SPOKEDPY_AI_MODEL', 'gpt-4o'),
+    'ai_temperature':   ConfigKey('ai.temperature', 'SPOKEDPY_AI_TEMPERATURE', '0.7'),
+    'ai_system_prompt': ConfigKey('ai.system_prompt', 'SPOKEDPY_AI_SYSTEM_PROMPT', ''),
+    # ── canvas ─────────────────────────────────────────────────────
+    'parallax_factor':  ConfigKey('canvas.parallax_factor', 'SPOKEDPY_PARALLAX_FACTOR', '6'),
+    'grid_size':        ConfigKey('canvas.grid_size', 'SPOKEDPY_GRID_SIZE', '20'),
+    'zoom_min':         ConfigKey('canvas.zoom_min', 'SPOKEDPY_ZOOM_MIN', '0.1'),
+    'zoom_max':         ConfigKey('canvas.zoom_max', 'SPOKEDPY_ZOOM_MAX', '5.0'),
+    'snap_to_grid':     ConfigKey('canvas.snap_to_grid', 'SPOKEDPY_SNAP_TO_GRID', '1'),
+}
+
+_BY_PATH = {meta.path: key for key, meta in CONFIG_SCHEMA.items()}
+
+# What a [tenants.<name>] table may set: the settings of one store.  The
+# rest (engines, sandbox, notify, …) is the server's, shared by every tenant.
+TENANT_KEYS = frozenset({
+    'snippets_dir', 'audit_log', 'state_checkpoint', 'failures_dir', 'trends_log',
+    'trash_dir', 'specs_dir', 'read_only', 'shard_width', 'id_mode',
+    'auto_promote', 'gate_override', 'windows', 'policy_file', 'constraints_file',
+    'owners_file', 'history_limit', 'trash_days', 'reviewers',
+    'quota_max_staged', 'quota_max_spec_minutes', 'revalidate_minutes',
+})
+
+# ═══════════════════════════════════════════════════════════════════════════
+# LAYER DISCOVERY
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _system_path() -> str:
+    if sys.platform == 'win32':
+        base = os.environ.get('PROGRAMDATA', r'C:\ProgramData')
+        return os.path.join(base, 'SpokedPy', CONFIG_FILENAME)
+    return os.path.join('/etc', 'spokedpy', CONFIG_FILENAME)
+
+
+def _user_path() -> str:
+    if sys.platform == 'win32':
+        base = os.environ.get('APPDATA') or os.path.expanduser('~')
+        return os.path.join(base, 'SpokedPy', CONFIG_FILENAME)
+    base = os.environ.get('XDG_CONFIG_HOME') or os.path.join(os.path.expanduser('~'), '.config')
+    return os.path.join(base, 'spokedpy', CONFIG_FILENAME)
+
+
+def _project_path(cwd: Optional[str] = None) -> str:
+    explicit = os.environ.get('SPOKEDPY_CONFIG', '').strip()
+    if explicit:
+        return os.path.abspath(explicit)
+    here = os.path.abspath(cwd or os.getcwd())
+    while True:
+        candidate = os.path.join(here, CONFIG_FILENAME)
+        if os.path.isfile(candidate):
+            return candidate
+        parent = os.path.dirname(here)
+        if parent == here:
+            break
+        here = parent
+    return os.path.join(_ROOT, CONFIG_FILENAME)
+
+
+def layer_paths(cwd: Optional[str] = None) -> List[Tuple[str, str]]:
+    """Return ``(layer, path)`` for every file layer, lowest priority first."""
+    return [('system', _system_path()), ('user', _user_path()),
+            ('project', _project_path(cwd))]
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# PARSING
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _flatten(table: Dict[str, Any], prefix: str = '') -> Dict[str, Any]:
+    out = {}
+    for name, value in table.items():
+        dotted = f"{prefix}{name}"
+        if isinstance(value, dict):
+            out.update(_flatten(value, dotted + '.'))
+        else:
+            out[dotted] = value
+    return out
+
+
+def _to_setting(value: Any) -> str:
+    """Convert a TOML value into the string form resolve_setting returns."""
+    if isinstance(value, bool):
+        return '1' if value else '0'
+    if isinstance(value, (list, tuple)):
+        return ','.join(str(v) for v in value)
+    return str(value)
+
+
+@dataclass
+class ConfigFile:
+    """One parsed spokedpy.toml layer (or one ``[profiles.<name>]`` table in it)."""
+    layer: str                      # system | user | project | profile
+    path: str
+    values: Dict[str, str]          # setting key → string value
+    raw: Dict[str, Any]             # setting key → TOML value (paths resolved)
+    unknown: List[str]              # dotted paths not in CONFIG_SCHEMA
+    profile: Optional[str] = None   # set on profile tables
+    profiles: Dict[str, 'ConfigFile'] = field(default_factory=dict)
+    tenants: Dict[str, 'TenantConfig'] = field(default_factory=dict)
+
+    @property
+    def origin(self) -> str:
+        return f"{self.path} [profiles.{self.profile}]" if self.profile else self.path
+
+
+@dataclass
+class TenantConfig:
+    """One ``[tenants.<name>]`` table: a store the server hosts besides its own."""
+    name: str
+    path: str                       # the file that defines it
+    data_dir: str                   # default home of its store paths
+    token: str = ''                 # X-Spokedpy-Token that selects it ('' → prefix only)
+    values: Dict[str, str] = field(default_factory=dict)    # TENANT_KEYS it sets
+
+
+def _parse_table(data: Dict[str, Any], base: str, prefix: str = ''):
+    values, raw_values, unknown = {}, {}, []
+    for dotted, raw in _flatten(data).items():
+        key = _BY_PATH.get(dotted)
+        if key is None:
+            unknown.append(prefix + dotted)
+            continue
+        value = _to_setting(raw)
+        if CONFIG_SCHEMA[key].is_path and value:
+            value = raw = os.path.normpath(os.path.join(base, os.path.expanduser(value)))
+        values[key] = value
+        raw_values[key] = raw
+    return values, raw_values, unknown
+
+
+def load_file(layer: str, path: str) -> Optional[ConfigFile]:
+    """Parse one layer; ``None`` if the file does not exist."""
+    if not os.path.isfile(path):
+        return None
+    try:
+        with open(path, 'rb') as f:
+            data = tomllib.load(f)
+    except (tomllib.TOMLDecodeError, UnicodeDecodeError) as exc:
+        raise ConfigError(f"{path}: {exc}") from exc
+    base = os.path.dirname(os.path.abspath(path))
+    profile_tables = data.pop('profiles', {})
+    if not isinstance(profile_tables, dict):
+        raise ConfigError(f"{path}: 'profiles' must be a table of [profiles.<name>] tables")
+    tenant_tables = data.pop('tenants', {})
+    if not isinstance(tenant_tables, dict):
+        raise ConfigError(f"{path}: 'tenants' must be a table of [tenants.<name>] tables")
+    cf = ConfigFile(layer, path, *_parse_table(data, base))
+    for name, table in profile_tables.items():
+        if not isinstance(table, dict):
+            raise ConfigError(f"{path}: [profiles.{name}] must be a table")
+        values, raw_values, unknown = _parse_table(table, base, f"profiles.{name}.")
+        cf.profiles[name] = ConfigFile('profile', path, values, raw_values, [], profile=name)
+        cf.unknown.extend(unknown)
+    for name, table in tenant_tables.items():
+        cf.tenants[name] = _parse_tenant(path, base, name, table)
+    return cf
+
+
+_TENANT_NAME = re.compile(r'^[a-z0-9][a-z0-9_-]{0,62}$')
+
+
+def _parse_tenant(path: str, base: str, name: str, table: Any) -> TenantConfig:
+    where = f"{path}: [tenants.{name}]"
+    if not isinstance(table, dict):
+        raise ConfigError(f"{where} must be a table")
+    if not _TENANT_NAME.match(name):
+        raise ConfigError(f"{where}: a tenant name is lowercase letters, digits, '-' and '_' "
+                          f"(it is the /t/<name>/ URL prefix)")
+    table = dict(table)
+    token = _to_setting(table.pop('token', '')).strip()
+    data_dir = _to_setting(table.pop('data_dir', '')).strip()
+    data_dir = os.path.normpath(os.path.join(base, os.path.expanduser(data_dir))) \
+        if data_dir else os.path.join(_DATA_DIR, 'tenants', name)
+    values, _raw, unknown = _parse_table(table, base, f"tenants.{name}.")
+    if unknown:
+        raise ConfigError(f"{path}: unknown key '{unknown[0]}'")
+    shared = sorted(key for key in values if key not in TENANT_KEYS)
+    if shared:
+        raise ConfigError(f"{where}: {CONFIG_SCHEMA[shared[0]].path} is a server setting, "
+                          f"shared by every tenant")
+    return TenantConfig(name, path, data_dir, token, values)
+
+
+_cache_lock = threading.Lock()
+_cache: Dict[str, Tuple[float, Optional[ConfigFile]]] = {}
+
+
+def _cached(layer: str, path: str) -> Optional[ConfigFile]:
+    try:
+        mtime = os.path.getmtime(path)
+    except OSError:
+        mtime = -1.0
+    with _cache_lock:
+        hit = _cache.get(path)
+        if hit is not None and hit[0] == mtime:
+            return hit[1]
+    loaded = load_file(layer, path) if mtime >= 0 else None
+    with _cache_lock:
+        _cache[path] = (mtime, loaded)
+    return loaded
+
+
+def load_layers(cwd: Optional[str] = None) -> List[ConfigFile]:
+    """Return every existing file layer, lowest priority first."""
+    return [cf for layer, path in layer_paths(cwd)
+            if (cf := _cached(layer, path)) is not None]
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# PROFILES
+# ═══════════════════════════════════════════════════════════════════════════
+#
+#   [profiles.prod.gates]
+#   auto_promote = false
+#
+# ``spokedpy --profile prod …`` (or SPOKEDPY_PROFILE=prod for the server)
+# overlays the named table on top of every base file layer; env and DB
+# overrides still win.  The same profile may be defined in several files —
+# project beats user beats system, as for base values.
+
+_profile_state = {'name': os.environ.get('SPOKEDPY_PROFILE', '').strip() or None}
+
+
+def set_profile(name: Optional[str]):
+    """Select the active profile for this process and the engines it spawns."""
+    _profile_state['name'] = name or None
+    if name:
+        os.environ['SPOKEDPY_PROFILE'] = name
+    else:
+        os.environ.pop('SPOKEDPY_PROFILE', None)
+
+
+def active_profile() -> Optional[str]:
+    return _profile_state['name']
+
+
+def available_profiles(layers: Optional[List[ConfigFile]] = None) -> List[str]:
+    names = set()
+    for cf in layers if layers is not None else load_layers():
+        names.update(cf.profiles)
+    return sorted(names)
+
+
+def effective_layers() -> List[ConfigFile]:
+    """Base file layers followed by the active profile's tables, lowest first.
+
+    Raises ConfigError if the active profile is not defined in any file.
+    """
+    layers = load_layers()
+    name = _profile_state['name']
+    if not name:
+        return layers
+    tables = [cf.profiles[name] for cf in layers if name in cf.profiles]
+    if not tables:
+        known = ', '.join(available_profiles(layers)) or 'none defined'
+        raise ConfigError(f"profile '{name}' is not defined in any {CONFIG_FILENAME} "
+                          f"(available: {known})")
+    return layers + tables
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# TENANTS
+# ═══════════════════════════════════════════════════════════════════════════
+#
+#   [tenants.payments]
+#   token = "…"
+#   [tenants.payments.retention]
+#   history_limit = 200
+#
+# ``spokedpy serve`` hosts each tenant's store next to its own (see
+# web_interface/tenants.py).  A tenant defined in several files takes its
+# table from the highest one — project beats user beats system, whole.
+
+def tenant_configs(layers: Optional[List[ConfigFile]] = None) -> Dict[str, TenantConfig]:
+    """Every ``[tenants.<name>]`` table, by name."""
+    tenants: Dict[str, TenantConfig] = {}
+    for cf in layers if layers is not None else load_layers():
+        tenants.update(cf.tenants)
+    return dict(sorted(tenants.items()))
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# RESOLUTION
+# ═══════════════════════════════════════════════════════════════════════════
+
+def file_value(key: str) -> Optional[Tuple[str, ConfigFile]]:
+    """Return ``(value, layer)`` from the highest file layer that sets ``key``."""
+    for cf in reversed(effective_layers()):
+        if key in cf.values:
+            return cf.values[key], cf
+    return None
+
+
+def explain(key: str, env_var: str, default: str,
+            db_value: Optional[str] = None) -> Dict[str, Any]:
+    """Resolve ``key`` and report every layer that had a value.
+
+    ``layers`` lists (highest priority first) each layer that set the key;
+    ``source`` names the winner.
+    """
+    layers = []
+    if db_value is not None:
+        layers.append({'layer': 'database', 'value': db_value})
+    env_val = os.environ.get(env_var, '').strip() if env_var else ''
+    if env_val:
+        layers.append({'layer': 'environment', 'value': env_val, 'origin': env_var})
+    for cf in reversed(effective_layers()):
+        if key in cf.values:
+            layers.append({'layer': cf.layer, 'value': cf.values[key], 'origin': cf.origin})
+    layers.append({'layer': 'default', 'value': default})
+    winner = layers[0]
+    return {
+        'key': key,
+        'value': winner['value'],
+        'source': winner['layer'],
+        'origin': winner.get('origin'),
+        'layers': layers,
+    }
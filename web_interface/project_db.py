@@ -16,15 +16,21 @@ import time
 import uuid
 from typing import Dict, List, Optional, Any
 
+from web_interface import config_layers
+
 
 def _resolve_db_path() -> str:
-    """Resolve the database path from env → default.
+    """Resolve the database path from env → spokedpy.toml → default.
 
     Priority:
         1. SPOKEDPY_DB_PATH environment variable
-        2. ``<web_interface>/projects.db``  (legacy default)
+        2. ``store.db_path`` in a spokedpy.toml layer (project → user → system)
+        3. ``<web_interface>/projects.db``  (legacy default)
     """
     env_path = os.environ.get('SPOKEDPY_DB_PATH', '').strip()
+    if not env_path:
+        from_file = config_layers.file_value('db_path')
+        env_path = from_file[0] if from_file else ''
     if env_path:
         os.makedirs(os.path.dirname(env_path) or '.', exist_ok=True)
         return env_path
@@ -259,7 +265,7 @@ def delete_setting(key: str) -> bool:
 
 
 def resolve_setting(key: str, env_var: str, default: str) -> str:
-    """Three-tier resolution: DB → env → default.
+    """Layered resolution: DB → env → spokedpy.toml (project → user → system) → default.
 
     This is the canonical function every subsystem should call to
     determine the effective value of a configurable path or parameter.
@@ -272,5 +278,24 @@ def resolve_setting(key: str, env_var: str, default: str) -> str:
     env_val = os.environ.get(env_var, '').strip()
     if env_val:
         return env_val
-    # 3. Hard-coded default
+    # 3. spokedpy.toml layers
+    from_file = config_layers.file_value(key)
+    if from_file is not None:
+        return from_file[0]
+    # 4. Hard-coded default
     return default
+
+
+_UNSET = object()
+
+
+def explain_setting(key: str, env_var: str, default: str,
+                    db_value: Any = _UNSET) -> Dict[str, Any]:
+    """Like resolve_setting, but report which layer won and what each layer held.
+
+    Pass ``db_value`` (None = no override) when the caller already fetched
+    all settings in bulk.
+    """
+    if db_value is _UNSET:
+        db_value = get_setting(key)
+    return config_layers.explain(key, env_var, default, db_value)
@@ -11,7 +11,9 @@ Contains:
 
 Call  init_runtime(app, session_ledger, socketio)  from app.py to wire everything up.
 """
-from flask import Blueprint, request, jsonify
+from flask import Blueprint, Response, request, jsonify
+from dataclasses import asdict
+import contextvars
 import json
 import os
 import uuid
@@ -51,12 +53,21 @@ from visual_editor_core.execution_engine import (
 from visual_editor_core.session_ledger import (
     LanguageID, resolve_language_string,
 )
+from visual_editor_core.errors import ErrorKind, SpokedError
 from visual_editor_core.snippet_staging import (
     StagingPipeline,
     StagingPhase,
     StagedSnippet,
+    StoreMode,
 )
+from visual_editor_core.spec_trends import TrendIndex
+from visual_editor_core.promotion_policy import PromotionPolicy
+from visual_editor_core.slot_constraints import SlotConstraints
+from visual_editor_core.snippet_owners import SnippetOwners
+from visual_editor_core.label_specs import LabelSpecs
+from visual_editor_core.snippet_quota import Quotas
 from web_interface.project_db import resolve_setting
+from web_interface import tenants
 from web_interface.state_persistence import (
     StatePersistence, build_promoted_snapshots,
 )
@@ -72,6 +83,51 @@ import secrets as _secrets
 # ---------------------------------------------------------------------------
 runtime_bp = Blueprint('runtime', __name__)
 
+
+# Non-GET routes a read-only consumer still needs: running a promoted slot.
+_READ_ONLY_ENDPOINTS = {'runtime.execute_registry_slot'}
+
+# serve --read-only: set before web_interface.app is imported (the import
+# opens the stores).  It wins over every read_only layer, a DB row included.
+read_only_forced = False
+
+
+@runtime_bp.before_request
+def _refuse_writes_when_read_only():
+    """[store] read_only / serve --read-only: only GET (and HEAD / OPTIONS) get through."""
+    if request.method not in ('GET', 'HEAD', 'OPTIONS') \
+            and request.endpoint not in _READ_ONLY_ENDPOINTS:
+        return read_only_refusal()
+    return None
+
+
+def read_only_refusal():
+    """The 403 reply to the current request when the store is read-only, else None.
+
+    Other blueprints call it from routes that change server state outside the
+    store (the settings hub), so a read-only server refuses those too.
+    """
+    if staging_pipeline is None or not staging_pipeline.read_only:
+        return None
+    return _error_response(SpokedError(
+        ErrorKind.STORE_READ_ONLY,
+        'The store is read-only (serve --read-only / [store] read_only); '
+        f'{request.method} {request.path} is refused',
+        method=request.method, path=request.path), 403)
+
+
+def _error_response(exc: ValueError, status: int = 400):
+    """The JSON reply to a refused request; a SpokedError adds its kind and context."""
+    body = {'success': False, 'error': str(exc)}
+    if isinstance(exc, SpokedError):
+        body['kind'] = exc.kind.value
+        body['context'] = exc.context
+        if exc.kind is ErrorKind.QUOTA_EXCEEDED and status == 400:
+            status = 429
+        elif exc.kind is ErrorKind.SLOT_DEGRADED and status == 400:
+            status = 409
+    return jsonify(body), status
+
 # ---------------------------------------------------------------------------
 # Module-level state — populated by init_runtime()
 # ---------------------------------------------------------------------------
@@ -82,6 +138,10 @@ _live_executor = None    # PythonExecutor  (shared REPL namespace — kept for P
 _executors = {}          # language string → executor instance  (all engines)
 multi_debugger = None    # MultiDebuggerManager
 staging_pipeline = None  # StagingPipeline — speculative execution & promotion
+notification_hub = None  # NotificationHub — Slack / email on pipeline events
+promotion_scheduler = None  # PromotionScheduler — applies held promotions when due
+revalidator = None  # Revalidator — re-specs promoted snippets on [revalidate] interval_minutes
+slot_watch = None  # SlotWatch — long-poll slot change events for hot-reloading consumers
 active_debug_sessions = {}
 mesh_relay = None        # MeshRelay — distributed instance interconnect
 
@@ -184,7 +244,7 @@ def _trigger_checkpoint():
     Called after every state-mutating operation (promote, lock, unlock,
     evict, token mint).  The actual write is coalesced via StatePersistence.
     """
-    if _state_persistence is None or staging_pipeline is None:
+    if _state_persistence is None or staging_pipeline is None or staging_pipeline.read_only:
         return
     try:
         with _marshal_lock:
@@ -244,10 +304,23 @@ def _restore_state():
             continue
 
         try:
-            # Re-run through the full pipeline (queue → speculate → verdict → promote)
-            snippet = staging_pipeline.run_full_pipeline(
-                engine_letter, language, code, label, auto_promote=True
-            )
+            if staging_pipeline.read_only:
+                # Read-only: the promoted files are the truth; re-running
+                # would stage, write and audit.
+                snippet = staging_pipeline.load_promoted(snap)
+            else:
+                # Re-run through the full pipeline (queue → speculate → verdict),
+                # then promote: these were promoted before, so the auto-promotion
+                # policy does not hold them for review again, nor a degraded
+                # slot they import.
+                snippet = staging_pipeline.run_full_pipeline(
+                    engine_letter, language, code, label, auto_promote=False,
+                    author=snap.get('submitter', ''), meta=snap.get('meta'),
+                    parent=snap.get('parent', ''),
+                )
+                if snippet.phase.value == 'passed':
+                    snippet = staging_pipeline.promote(snippet.staging_id, allow_degraded=True,
+                                                       approved_by=snap.get('approved_by', ''))
 
             if snippet.phase.value == 'promoted':
                 restored_count += 1
@@ -341,13 +414,68 @@ def init_runtime(app, session_ledger, socketio):
     Must be called once, after the app and session_ledger are ready.
     """
     global _session_ledger, _socketio, node_registry, _live_executor, multi_debugger, _executors, staging_pipeline
-    global _state_persistence, mesh_relay
+    global _state_persistence, mesh_relay, notification_hub, promotion_scheduler, slot_watch
+    global revalidator
+    global _locked_slots
 
     _session_ledger = session_ledger
     _socketio = socketio
 
-    # Execution matrix on top of the ledger
-    node_registry = NodeRegistry(session_ledger)
+    # Named config profile (SPOKEDPY_PROFILE) — overlays [profiles.<name>]
+    from web_interface.config_layers import active_profile
+    if active_profile():
+        print(f"  Profile:       {active_profile()}")
+
+    # Managed toolchains (spokedpy toolchain install …) take precedence on
+    # PATH — must run before the executors probe for their binaries.
+    from visual_editor_core.toolchain_manager import ToolchainManager
+    toolchains_dir = resolve_setting(
+        'toolchains_dir',
+        'SPOKEDPY_TOOLCHAINS_DIR',
+        os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'toolchains'),
+    )
+    for bin_dir in ToolchainManager(toolchains_dir).activate():
+        print(f"  Toolchain:     {bin_dir}")
+
+    # Offline / air-gapped mode — engines spawn with package managers
+    # offline and (on Linux) inside an empty network namespace.
+    from visual_editor_core import offline
+    offline.configure(
+        resolve_setting('offline', 'SPOKEDPY_OFFLINE', '0').strip().lower() in ('1', 'true', 'yes', 'on'),
+        vendor_dir=resolve_setting(
+            'vendor_dir',
+            'SPOKEDPY_VENDOR_DIR',
+            os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'vendor'),
+        ),
+    )
+    offline.set_sandbox_backend(resolve_setting('sandbox_backend', 'SPOKEDPY_SANDBOX_BACKEND', 'process'))
+    if offline.is_offline():
+        isolation = 'network namespace' if offline.isolation_prefix() else 'env only'
+        print(f"  Offline mode:  ON ({isolation})")
+    if offline.sandbox_backend() != 'process':
+        print(f"  Sandbox:       {offline.sandbox_backend()}")
+    offline.set_network_policy(resolve_setting('network', 'SPOKEDPY_NETWORK', 'none'))
+    network, per_engine = offline.network_policy()
+    if network != 'full' or per_engine:
+        levels = ', '.join([network] + [f"{e}={level}" for e, level in sorted(per_engine.items())])
+        enforced = '' if offline.isolation_prefix() else ' — not enforced for engine subprocesses ' \
+                                                         '(no network namespaces)'
+        print(f"  Network:       spec runs {levels}{enforced}")
+    from visual_editor_core import seccomp
+    seccomp.set_profile(resolve_setting('seccomp', 'SPOKEDPY_SECCOMP', 'off'))
+    if seccomp.profile() != 'off':
+        enforced = '' if seccomp.supported() else ' — not enforced (needs Linux x86_64 / ' \
+                                                  'aarch64 with seccomp user notifications)'
+        print(f"  Seccomp:       {seccomp.profile()}{enforced}")
+    # Warm pools — pre-booted Python / Node workers take over spec runs' spawns
+    from visual_editor_core import warm_pool
+    warm_pool.configure(resolve_setting('warm_pool', 'SPOKEDPY_WARM_POOL', ''),
+                        int(resolve_setting('warm_recycle', 'SPOKEDPY_WARM_RECYCLE', '100')))
+    if warm_pool.sizes():
+        pools = ', '.join(f"{engine}={n}" for engine, n in warm_pool.sizes().items())
+        bypassed = ' — not for seccomp-filtered runs' if seccomp.profile() != 'off' else ''
+        print(f"  Warm pool:     {pools} (Python workers recycled after "
+              f"{warm_pool.recycle()} runs){bypassed}")
 
     # Persistent Python executor — holds variables across runs (REPL-style)
     _live_executor = _PythonExecutor()
@@ -371,54 +499,235 @@ def init_runtime(app, session_ledger, socketio):
         'perl':       _PerlExecutor(),            # perl subprocess (Strawberry Perl)
     }
 
+    # [engines] enabled = [...] — restrict the pool (python is the live
+    # REPL and always stays on).
+    enabled_engines = [e.strip().lower() for e in
+                       resolve_setting('engines', 'SPOKEDPY_ENGINES', '').split(',') if e.strip()]
+    if enabled_engines:
+        _executors = {lang: ex for lang, ex in _executors.items()
+                      if lang in enabled_engines or lang == 'python'}
+        print(f"  Engines:       {', '.join(_executors)}")
+
     # Multi-debugger manager (ensure global is initialized)
     multi_debugger = MultiDebuggerManager()
 
-    # ── Resolve data paths: DB setting → env var → legacy default ────
-    _legacy_dir = os.path.dirname(__file__)           # web_interface/
-    _data_dir   = os.path.join(os.path.dirname(_legacy_dir), 'data')
+    # ── Stores: the server's own, then one per [tenants.<name>] table ──
+    from web_interface.config_layers import tenant_configs
+
+    # The routes read these per request: the store of the request's tenant
+    # (the server's own outside one).
+    staging_pipeline = tenants.Routed('pipeline')
+    node_registry = tenants.Routed('node_registry')
+    slot_watch = tenants.Routed('slot_watch')
+    promotion_scheduler = tenants.Routed('promotion_scheduler')
+    revalidator = tenants.Routed('revalidator')
+    _state_persistence = tenants.Routed('state_persistence')
+    _locked_slots = tenants.Routed('locked_slots')
+
+    _data_dir = os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data')
+    own = _open_store(tenants.Store('', resolve_setting, data_dir=_data_dir))
+    for config in tenant_configs().values():
+        print(f"  Tenant:        {config.name} → /t/{config.name}/"
+              f"{' (token)' if config.token else ''}, {config.data_dir}")
+        _open_store(tenants.Store(config.name, tenants.tenant_setting(config, resolve_setting),
+                                  token=config.token, data_dir=config.data_dir))
+    if tenants.hosted():
+        tenants.install(app)
+
+    # ── Notifications — attached after restore so re-promoting the
+    #    checkpoint on startup does not page anyone ──────────────────
+    from visual_editor_core.notifiers import hub_from_settings
+    notification_hub = hub_from_settings(resolve_setting)
+    for store in tenants.stores():
+        store.pipeline.add_listener(notification_hub)
+    for notifier in notification_hub.notifiers:
+        print(f"  Notify:        {notifier.kind} ({', '.join(notifier.events)})")
+
+    # ── Mesh Relay — distributed instance interconnect ──────────────
+    instance_name = os.environ.get('SPOKEDPY_INSTANCE_NAME', '')
+    mesh_relay = MeshRelay(own.node_registry, session_ledger, instance_name)
+    print(f"  Mesh relay:    {mesh_relay.instance_id} ({mesh_relay.instance_name})")
+
+    # Register the blueprint (all routes become active)
+    app.register_blueprint(runtime_bp)
+
+    # Register WebSocket handlers (they need the socketio reference)
+    _register_websocket_handlers(socketio)
+
+
+def _store_read_only(setting) -> bool:
+    """serve --read-only, or else the store's [store] read_only setting."""
+    value = setting('read_only', 'SPOKEDPY_READ_ONLY', '0')
+    return read_only_forced or value.strip().lower() in ('1', 'true', 'yes', 'on')
 
-    snippets_dir = resolve_setting(
+
+def _open_store(store):
+    """Open one store — the server's own or a tenant's — and restore its promoted slots.
+
+    Paths and store settings come from ``store.setting``; the engines,
+    sandbox and gate plugins are the server's.
+    """
+    setting, _data_dir = store.setting, store.data_dir
+
+    # ── Resolve data paths: DB setting → env var → legacy default ────
+    snippets_dir = setting(
         'snippets_dir',
         'SPOKEDPY_SNIPPETS_DIR',
         os.path.join(_data_dir, 'snippets'),
     )
-    audit_log_path = resolve_setting(
+    audit_log_path = setting(
         'audit_log',
         'SPOKEDPY_AUDIT_LOG',
         os.path.join(_data_dir, 'staging_audit.jsonl'),
     )
-    # Ensure the resolved directories exist
-    os.makedirs(snippets_dir, exist_ok=True)
-    os.makedirs(os.path.dirname(audit_log_path) or '.', exist_ok=True)
+    failures_dir = setting(
+        'failures_dir',
+        'SPOKEDPY_FAILURES_DIR',
+        os.path.join(_data_dir, 'failures'),
+    )
+    trends_log = setting(
+        'trends_log',
+        'SPOKEDPY_TRENDS_LOG',
+        os.path.join(_data_dir, 'spec_trends.jsonl'),
+    )
+    policy_file = setting(
+        'policy_file',
+        'SPOKEDPY_POLICY_FILE',
+        os.path.join(_data_dir, 'promotion_policy.toml'),
+    )
+    constraints_file = setting(
+        'constraints_file',
+        'SPOKEDPY_CONSTRAINTS_FILE',
+        os.path.join(_data_dir, 'slot_constraints.toml'),
+    )
+    owners_file = setting(
+        'owners_file',
+        'SPOKEDPY_OWNERS_FILE',
+        os.path.join(_data_dir, 'OWNERS'),
+    )
+    trash_dir = setting(
+        'trash_dir',
+        'SPOKEDPY_TRASH_DIR',
+        os.path.join(_data_dir, 'trash'),
+    )
+    specs_dir = setting(
+        'specs_dir',
+        'SPOKEDPY_SPECS_DIR',
+        os.path.join(_data_dir, 'specs'),
+    )
+    checkpoint = setting(
+        'state_checkpoint',
+        'SPOKEDPY_STATE_CHECKPOINT',
+        os.path.join(_data_dir, 'runtime_state.json'),
+    )
+    read_only = _store_read_only(setting)
+    if not read_only:
+        # Ensure the resolved directories exist
+        os.makedirs(snippets_dir, exist_ok=True)
+        os.makedirs(os.path.dirname(audit_log_path) or '.', exist_ok=True)
 
     print(f"  Snippets dir:  {snippets_dir}")
     print(f"  Audit log:     {audit_log_path}")
+    if read_only:
+        print("  Store mode:    READ-ONLY (staging, promotion and rollback refused)")
+
+    # Execution matrix on top of the ledger — a store's slots are its own
+    store.node_registry = NodeRegistry(_session_ledger)
 
     # Staging pipeline — speculative execution & promotion to production
-    staging_pipeline = StagingPipeline(
+    pipeline = store.pipeline = StagingPipeline(
         executors=_executors,
-        node_registry=node_registry,
-        session_ledger=session_ledger,
+        node_registry=store.node_registry,
+        session_ledger=_session_ledger,
         snippets_dir=snippets_dir,
         audit_log_path=audit_log_path,
+        history_limit=int(setting('history_limit', 'SPOKEDPY_HISTORY_LIMIT', '1000')),
+        allow_override=setting('gate_override', 'SPOKEDPY_GATE_OVERRIDE', '1').strip().lower() in ('1', 'true', 'yes', 'on'),
+        gates=_load_gates(),
+        failures_dir=failures_dir,
+        trends=TrendIndex(
+            trends_log,
+            sigmas=float(resolve_setting('anomaly_sigmas', 'SPOKEDPY_ANOMALY_SIGMAS', '3')),
+            window=int(resolve_setting('anomaly_window', 'SPOKEDPY_ANOMALY_WINDOW', '20')),
+        ),
+        policy=PromotionPolicy(policy_file),
+        constraints=SlotConstraints(constraints_file),
+        owners=SnippetOwners(owners_file),
+        label_specs=LabelSpecs(specs_dir),
+        quotas=Quotas(int(setting('quota_max_staged', 'SPOKEDPY_QUOTA_MAX_STAGED', '0')),
+                      float(setting('quota_max_spec_minutes', 'SPOKEDPY_QUOTA_MAX_SPEC_MINUTES',
+                                    '0'))),
+        mode=StoreMode.READ_ONLY if read_only else StoreMode.READ_WRITE,
+        shard_width=int(setting('shard_width', 'SPOKEDPY_SHARD_WIDTH', '2')),
+        id_mode=setting('id_mode', 'SPOKEDPY_ID_MODE', 'random').strip().lower(),
+        trash_dir=trash_dir,
+        trash_days=float(setting('trash_days', 'SPOKEDPY_TRASH_DAYS', '30')),
+        scratch=resolve_setting('scratch', 'SPOKEDPY_SCRATCH', '1').strip().lower()
+        in ('1', 'true', 'yes', 'on'),
     )
+    if not pipeline.scratch:
+        print("  Scratch dirs:  OFF (dry-runs share the server's cwd and HOME)")
+    if pipeline.quotas.enabled:
+        print(f"  Quota:         max_staged={pipeline.quotas.max_staged or '-'}, "
+              f"max_spec_minutes={pipeline.quotas.max_spec_minutes or '-'} "
+              f"(per author and namespace)")
+    purged = pipeline.gc_trash()
+    if purged:
+        print(f"  Trash:         purged {len(purged)} expired snippet(s)")
+    for gate in pipeline.gates:
+        status = f"BROKEN — {gate.error}" if gate.kind == 'broken' else gate.kind
+        print(f"  Gate:          {gate.name} ({status})")
+    for rule in pipeline.policy.rules:
+        print(f"  Policy:        {rule.name} → {', '.join(rule.slots)} "
+              f"({'auto' if rule.auto_promote else 'protected'})")
+    if pipeline.policy.error:
+        print(f"  Policy:        BROKEN — {pipeline.policy.error} (nothing auto-promotes)")
+    for c in pipeline.constraints.constraints:
+        print(f"  Constraint:    {c.slot} → engine={c.engine or 'any'}, "
+              f"gates={','.join(c.gates) or '-'}, max_spec_time={c.max_spec_time or '-'}")
+    if pipeline.constraints.error:
+        print(f"  Constraint:    BROKEN — {pipeline.constraints.error} (nothing promotes)")
+    for rule in pipeline.owners.rules:
+        print(f"  Owners:        {rule.pattern} → {' '.join(rule.owners) or '(unowned)'}")
+    if pipeline.owners.error:
+        print(f"  Owners:        BROKEN — {pipeline.owners.error} (no slot is owned)")
+    for label in pipeline.label_specs.labels():
+        try:
+            suite = pipeline.label_specs.default_for(label)
+            print(f"  Label spec:    {label} → {len(suite.cases)} case(s)")
+        except ValueError as exc:
+            print(f"  Label spec:    {label} BROKEN — {exc} (its snippets cannot be staged)")
 
     # ── State persistence — restore promoted slots from last checkpoint ──
-    _state_persistence = StatePersistence()
-    print(f"  State file:    {_state_persistence.path}")
-    _restore_state()
-
-    # ── Mesh Relay — distributed instance interconnect ──────────────
-    instance_name = os.environ.get('SPOKEDPY_INSTANCE_NAME', '')
-    mesh_relay = MeshRelay(node_registry, session_ledger, instance_name)
-    print(f"  Mesh relay:    {mesh_relay.instance_id} ({mesh_relay.instance_name})")
-
-    # Register the blueprint (all routes become active)
-    app.register_blueprint(runtime_bp)
-
-    # Register WebSocket handlers (they need the socketio reference)
-    _register_websocket_handlers(socketio)
+    store.state_persistence = StatePersistence(checkpoint, read_only=read_only)
+    print(f"  State file:    {store.state_persistence.path}")
+    tenants.register(store)
+    with tenants.using(store.name):
+        _restore_state()
+
+    # ── Slot watch — GET /api/slots/watch long-polls on slot changes ──
+    from visual_editor_core.slot_watch import SlotWatch
+    store.slot_watch = SlotWatch(pipeline.current_promoted)
+    pipeline.add_listener(store.slot_watch)
+
+    # ── Scheduled promotions — promote --at / --window ──────────────
+    from visual_editor_core.promotion_schedule import PromotionScheduler
+    store.promotion_scheduler = PromotionScheduler(pipeline.promote_due,
+                                                   on_promoted=_report_scheduled)
+    if not read_only:
+        store.promotion_scheduler.start()
+
+    # ── Revalidation — failing promoted snippets turn their slot DEGRADED ──
+    from visual_editor_core.revalidation import Revalidator
+    minutes = float(setting('revalidate_minutes', 'SPOKEDPY_REVALIDATE_MINUTES', '0'))
+    store.revalidator = Revalidator(pipeline.revalidate, max(0.0, minutes) * 60,
+                                    on_report=_report_revalidation)
+    if minutes > 0 and not read_only:
+        print(f"  Revalidate:    every {minutes:g} min (failing slots turn DEGRADED)")
+        store.revalidator.start()
+    for slot, degraded in pipeline.degraded().items():
+        print(f"  Degraded:      {slot} {degraded.label} — {degraded.reason}")
+    return store
 
 
 # ==================== ENGINE MANIFEST (SINGLE SOURCE OF TRUTH) ====================
@@ -442,6 +751,8 @@ def get_engine_manifest():
         capabilities      — dict of feature flags this engine supports
         parser            — which parser/executor class handles this language
         tier              — priority tier: 'primary', 'tier-1', 'tier-2'
+
+    ``warm_pool`` has the warm pools' counters per engine (see warm_pool).
     """
     import subprocess as _sp
 
@@ -611,12 +922,14 @@ def get_engine_manifest():
                 'tier':             _TIERS.get(lang_str, 'tier-2'),
             })
 
+        from visual_editor_core import warm_pool
         return jsonify({
             'success': True,
             'engines': engines,
             'total':   len(engines),
             'enabled': sum(1 for e in engines if e['platform_enabled']),
             'disabled': sum(1 for e in engines if not e['platform_enabled']),
+            'warm_pool': warm_pool.stats(),
         })
     except Exception as e:
         import traceback
@@ -2179,7 +2492,8 @@ def run_all_registry_slots():
         results = []
         max_w = min(len(slots_to_run), 8)  # cap concurrency
         with concurrent.futures.ThreadPoolExecutor(max_workers=max_w) as pool:
-            future_map = {pool.submit(execute_slot, s): s for s in slots_to_run}
+            future_map = {pool.submit(contextvars.copy_context().run, execute_slot, s): s
+                          for s in slots_to_run}
             for future in concurrent.futures.as_completed(future_map, timeout=120):
                 try:
                     results.append(future.result())
@@ -2395,13 +2709,65 @@ def api_engines_available():
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
+@runtime_bp.route('/api/engines/doctor', methods=['GET'])
+def api_engines_doctor():
+    """Run the engine doctor: toolchains, versions, paths, credentials.
+
+    Query: ?engine=rust,python  (optional — restricts and requires engines)
+    Every non-OK check carries an actionable ``fix`` string.
+    """
+    try:
+        from visual_editor_core.engine_doctor import run_doctor
+        languages = [l for l in request.args.get('engine', '').split(',') if l.strip()]
+        paths = {}
+        for key in ('snippets_dir', 'audit_log'):
+            meta = _KNOWN_SETTINGS[key]
+            kind = 'dir' if key == 'snippets_dir' else 'file'
+            paths[key] = (resolve_setting(key, meta['env'], meta['default']), kind, meta['env'])
+        if _state_persistence is not None:
+            paths['state_checkpoint'] = (_state_persistence.path, 'file', 'SPOKEDPY_STATE_CHECKPOINT')
+        secrets = {
+            'ai_api_key': (resolve_setting('ai_api_key', 'SPOKEDPY_AI_API_KEY', ''),
+                           'SPOKEDPY_AI_API_KEY', 'the AI chat agent'),
+        }
+        plugins = _KNOWN_SETTINGS['plugins_dir']
+        report = run_doctor(paths=paths, secrets=secrets,
+                            languages=languages or None, required=languages,
+                            plugins_dir=resolve_setting('plugins_dir', plugins['env'], plugins['default']))
+        return jsonify({'success': True, **report.to_dict()})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
 # ==================== STAGING PIPELINE ====================
 
+def _default_auto_promote() -> bool:
+    """[gates] auto_promote (the tenant's, under one) — used when a request does not say."""
+    return tenants.setting('auto_promote', 'SPOKEDPY_AUTO_PROMOTE', '1').strip().lower() in ('1', 'true', 'yes', 'on')
+
+
+def _request_spec(data):
+    """The SpecSuite in a request's ``spec``, or None; ValueError if malformed.
+
+    ``spec`` is sidecar text, or SpecSuite.to_dict() when the client already
+    resolved snapshot files (spokedpy spec run, the LSP).
+    """
+    if not data.get('spec'):
+        return None
+    from visual_editor_core.spec_suite import SpecSuite, parse_spec
+    if isinstance(data['spec'], dict):
+        return SpecSuite.from_dict(data['spec'])
+    return parse_spec(data['spec'], data.get('spec_format', 'toml'))
+
+
 @runtime_bp.route('/api/staging/queue', methods=['POST'])
 def staging_queue():
     """Queue a snippet into the staging pipeline.
 
-    Body: { engine_letter, language, code, label? }
+    Body: { engine_letter, language, code, label?, author?, spec?, spec_format?, meta?, parent? }
+    ``spec`` is the text of a *.spec.toml (or spec_format 'yaml') sidecar;
+    ``meta`` custom key/value fields, written to the header as ``x-`` lines;
+    ``parent`` the staging_id of the snippet this one was staged from.
     Returns the staged snippet with reserved slot address.
     """
     try:
@@ -2418,10 +2784,134 @@ def staging_queue():
         if not engine_letter and not language:
             return jsonify({'success': False, 'error': 'engine_letter or language required'}), 400
 
-        snippet = staging_pipeline.queue_snippet(engine_letter, language, code, label)
+        snippet = staging_pipeline.queue_snippet(engine_letter, language, code, label,
+                                                 data.get('author', ''), _request_spec(data),
+                                                 data.get('meta'), data.get('parent', ''))
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+def _ndjson_stream(work, *head):
+    """Stream ``work(emit)``'s progress events as NDJSON while it runs.
+
+    ``head`` events go out first; the last line is ``{"event": "result",
+    …}`` with work's return value (a dict), or ``{"event": "error"}``.
+    """
+    import queue
+    events = queue.Queue()
+    done = object()
+
+    def run():
+        try:
+            result = work(events.put)
+            events.put({'event': 'result', 'at': _time.time(), **result})
+        except Exception as exc:
+            error = {'event': 'error', 'at': _time.time(), 'error': str(exc)}
+            if isinstance(exc, SpokedError):
+                error.update(kind=exc.kind.value, context=exc.context)
+            events.put(error)
+        events.put(done)
+
+    # copy_context: the work runs against this request's tenant store
+    _threading.Thread(target=contextvars.copy_context().run, args=(run,), daemon=True,
+                      name='ndjson-stream').start()
+
+    def lines():
+        for event in head:
+            yield json.dumps(event, default=str) + '\n'
+        while True:
+            event = events.get()
+            if event is done:
+                return
+            yield json.dumps(event, default=str) + '\n'
+
+    return Response(lines(), mimetype='application/x-ndjson',
+                    headers={'Cache-Control': 'no-cache', 'X-Accel-Buffering': 'no'})
+
+
+@runtime_bp.route('/api/staging/stage-and-spec', methods=['POST'])
+def staging_stage_and_spec():
+    """Queue a snippet and speculate it, streaming progress live (StageAndSpec).
+
+    Body: same as /api/staging/queue.
+    Response: application/x-ndjson, one event per line — ``queued``, then
+    the output_stream events (phase markers, compiler and program output
+    as it is printed), then ``result`` with the speculated snippet.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        if not data.get('code', '').strip():
+            return jsonify({'success': False, 'error': 'No code provided'}), 400
+        if not data.get('engine_letter') and not data.get('language'):
+            return jsonify({'success': False, 'error': 'engine_letter or language required'}), 400
+        snippet = staging_pipeline.queue_snippet(data.get('engine_letter', ''), data.get('language', ''),
+                                                 data['code'], data.get('label', ''),
+                                                 data.get('author', ''), _request_spec(data),
+                                                 data.get('meta'), data.get('parent', ''))
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+    def work(emit):
+        result = staging_pipeline.speculate(snippet.staging_id, on_event=emit)
+        return {'success': True, 'snippet': result.to_dict()}
+
+    return _ndjson_stream(work, {'event': 'queued', 'at': _time.time(), 'snippet': snippet.to_dict()})
+
+
+@runtime_bp.route('/api/staging/evaluate', methods=['POST'])
+def staging_evaluate():
+    """Run code in the speculation sandbox without staging it.
+
+    Body: { language, code }   — used by ``spokedpy repl``.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        if not data.get('code', '').strip():
+            return jsonify({'success': False, 'error': 'No code provided'}), 400
+        result = staging_pipeline.evaluate(data.get('language', ''), data['code'])
+        return jsonify({'success': True, 'result': result})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/spec-run', methods=['POST'])
+def staging_spec_run():
+    """Run a spec suite against code in the sandbox without staging it.
+
+    Body: { language, code, spec, spec_format?, label?, stream? }   — used by ``spokedpy spec run``.
+    With ``label`` its default spec's cases run first (spec may then be
+    left out).  Returns { cases: [CaseResult…], inherited: [case names] }
+    with each case's full output.  With ``stream: true`` the response is
+    NDJSON progress events (as for /api/staging/stage-and-spec) ending
+    in ``result`` with the cases.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        if not data.get('code', '').strip():
+            return jsonify({'success': False, 'error': 'No code provided'}), 400
+        label = data.get('label', '')
+        spec = staging_pipeline.inherit_spec(label, _request_spec(data))
+        if spec is None:
+            return jsonify({'success': False, 'error': 'No spec provided'}), 400
+        if data.get('stream'):
+            return _ndjson_stream(lambda emit: {'success': True, 'inherited': spec.inherited,
+                                                'cases': staging_pipeline.run_spec(
+                data.get('language', ''), spec, data['code'], on_event=emit)})
+        cases = staging_pipeline.run_spec(data.get('language', ''), spec, data['code'])
+        return jsonify({'success': True, 'cases': cases, 'inherited': spec.inherited})
+    except ValueError as ve:
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
@@ -2439,44 +2929,93 @@ def staging_speculate(staging_id):
         snippet = staging_pipeline.speculate(staging_id)
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
 @runtime_bp.route('/api/staging/verdict/<staging_id>', methods=['POST'])
 def staging_verdict(staging_id):
-    """Issue a verdict on a speculated snippet.
+    """Issue a verdict on a speculated snippet, as an authenticated reviewer.
 
     Body: { action: 'auto'|'approve'|'reject'|'hold', reason? }
     """
     try:
         if staging_pipeline is None:
             return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        if _approval_principal() is None:
+            return _unauthorized()
         data = request.get_json() or {}
         action = data.get('action', 'auto')
         reason = data.get('reason', '')
         snippet = staging_pipeline.verdict(staging_id, action, reason)
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
 @runtime_bp.route('/api/staging/promote/<staging_id>', methods=['POST'])
 def staging_promote(staging_id):
-    """Promote a PASSED snippet to production.
+    """Promote a PASSED snippet to production, as an authenticated reviewer.
 
     Writes code to disk, creates ledger node, commits to registry slot.
+    An owned slot also needs the reviewer to be one of its owners.
     """
     try:
         if staging_pipeline is None:
             return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
-        snippet = staging_pipeline.promote(staging_id)
+        principal = _approval_principal()
+        if principal is None:
+            return _unauthorized()
+        snippet = staging_pipeline.promote(staging_id, approved_by=principal)
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/impact/<staging_id>', methods=['GET'])
+def staging_impact(staging_id):
+    """What references the slot a snippet would be promoted into (``promote --impact``).
+
+    Dependents (promoted snippets declaring ``# imports: <slot>``), registry
+    subscribers and mesh peers of the slot, and held promotions importing it.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.promotion_impact import analyze
+        store = tenants.current()
+        relay = mesh_relay if store is None or not store.name else None     # relays the own store
+        impact = analyze(staging_pipeline, staging_id, node_registry, relay)
+        return jsonify({'success': True, 'impact': impact.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/quota', methods=['GET'])
+def staging_quota():
+    """The store's [quota] limits and use per author and namespace (``quota show``).
+
+    Query: ?author=NAME and / or ?namespace=NAME keep only those rows.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        quotas = staging_pipeline.quotas or Quotas()
+        report = quotas.report(staging_pipeline.get_active())
+        wanted = {(scope, request.args[scope]) for scope in ('author', 'namespace')
+                  if request.args.get(scope)}
+        if wanted:
+            report['usage'] = [u for u in report['usage'] if (u['scope'], u['name']) in wanted]
+        return jsonify({'success': True, 'quota': report})
+    except ValueError as ve:
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
@@ -2485,7 +3024,7 @@ def staging_promote(staging_id):
 def staging_run_full():
     """Run the FULL staging pipeline in one call.
 
-    Body: { engine_letter, language, code, label?, auto_promote? }
+    Body: { engine_letter, language, code, label?, auto_promote?, author?, spec?, spec_format?, meta?, parent? }
 
     queue → speculate → verdict → promote (if pass & auto_promote=true)
     """
@@ -2497,17 +3036,20 @@ def staging_run_full():
         language = data.get('language', '')
         code = data.get('code', '')
         label = data.get('label', '')
-        auto_promote = data.get('auto_promote', True)
+        auto_promote = data.get('auto_promote')     # None → [gates] auto_promote / the policy
 
         if not code.strip():
             return jsonify({'success': False, 'error': 'No code provided'}), 400
 
         snippet = staging_pipeline.run_full_pipeline(
-            engine_letter, language, code, label, auto_promote
+            engine_letter, language, code, label, auto_promote,
+            author=data.get('author', ''), spec=_request_spec(data),
+            default_auto_promote=_default_auto_promote(), meta=data.get('meta'),
+            parent=data.get('parent', ''),
         )
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
@@ -2526,7 +3068,103 @@ def staging_rollback(staging_id):
         snippet = staging_pipeline.rollback(staging_id, reason)
         return jsonify({'success': True, 'snippet': snippet.to_dict()})
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/snippet/<staging_id>', methods=['DELETE'])
+def staging_delete(staging_id):
+    """Move a snippet that is not promoted to the trash.
+
+    Body: { reason?, deleted_by? }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json(silent=True) or {}
+        tombstone = staging_pipeline.delete(staging_id, data.get('reason', ''),
+                                            data.get('deleted_by', ''))
+        return jsonify({'success': True, 'tombstone': tombstone})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/trash', methods=['GET'])
+def staging_trash():
+    """The deleted snippets, most recently deleted first."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        return jsonify({'success': True, 'trash': staging_pipeline.trash()})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/trash/<staging_id>/restore', methods=['POST'])
+def staging_trash_restore(staging_id):
+    """Put a deleted snippet back where it was."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        snippet = staging_pipeline.restore(staging_id)
+        return jsonify({'success': True, 'snippet': snippet.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/trash/purge', methods=['POST'])
+def staging_trash_purge():
+    """Delete trash entries for good.
+
+    Body: { staging_ids?: [..], expired?: bool }  — without ids, the whole
+    trash; with ``expired``, only what is past [retention] trash_days.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json(silent=True) or {}
+        purged = staging_pipeline.purge_trash(data.get('staging_ids') or None,
+                                              bool(data.get('expired')))
+        return jsonify({'success': True, 'purged': purged})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/review/<staging_id>', methods=['GET'])
+def staging_review(staging_id):
+    """What promoting a snippet would change: header, gate results, spec
+    output, and a unified diff against the slot's current snippet."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        return jsonify({'success': True, **staging_pipeline.review(staging_id)})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/label/<staging_id>', methods=['POST'])
+def staging_relabel(staging_id):
+    """Rename a staged snippet before it is promoted.
+
+    Body: { label }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        snippet = staging_pipeline.relabel(staging_id, data.get('label', ''))
+        return jsonify({'success': True, 'snippet': snippet.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
@@ -2535,22 +3173,43 @@ def staging_rollback(staging_id):
 def staging_list():
     """List active staged snippets + optional history.
 
-    Query: ?include_history=1&limit=100
+    Query: ?include_history=1&limit=100&meta=team=payments&q=csv
+    ``meta`` (repeatable) keeps snippets whose custom field matches (a bare
+    key: has the field; the value may be a glob); ``q`` searches ids,
+    labels, slots, authors and meta.
+
+    Paged: with ``after`` (empty for the first page) the answer is
+    ``{snippets, total, next_cursor}`` instead — ``limit`` snippets, newest
+    first by seq, optionally of one ``phase``; pass ``next_cursor`` back as
+    ``after`` for the next page.
     """
     try:
         if staging_pipeline is None:
             return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.snippet_staging import parse_meta_args
         include_history = request.args.get('include_history', '0') == '1'
         limit = int(request.args.get('limit', '100'))
+        wanted = parse_meta_args(request.args.getlist('meta'), filters=True)
+        query = request.args.get('q', '').strip()
 
-        active = [s.to_dict() for s in staging_pipeline.get_active()]
+        if 'after' in request.args:
+            page = staging_pipeline.list_snippets(
+                limit, request.args.get('after', '').strip(),
+                request.args.get('phase', '').strip(), wanted, query, include_history)
+            return jsonify({'success': True, **page,
+                            'snippets': [s.to_dict() for s in page['snippets']]})
+
+        active = [s.to_dict() for s in staging_pipeline.get_active() if s.matches(wanted, query)]
         result = {'success': True, 'active': active}
 
         if include_history:
-            result['history'] = [s.to_dict() for s in staging_pipeline.get_history(limit)]
+            result['history'] = [s.to_dict() for s in staging_pipeline.get_history(limit)
+                                 if s.matches(wanted, query)]
 
         result['summary'] = staging_pipeline.get_pipeline_summary()
         return jsonify(result)
+    except ValueError as ve:
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
@@ -2563,7 +3222,8 @@ def staging_get_snippet(staging_id):
             return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
         snippet = staging_pipeline.get_snippet(staging_id)
         if snippet is None:
-            return jsonify({'success': False, 'error': 'Not found'}), 404
+            return _error_response(SpokedError(ErrorKind.NOT_FOUND, 'Not found',
+                                               staging_id=staging_id), 404)
 
         audit = staging_pipeline.get_audit_trail(staging_id)
         return jsonify({
@@ -2575,6 +3235,26 @@ def staging_get_snippet(staging_id):
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
+@runtime_bp.route('/api/staging/lineage/<staging_id>', methods=['GET'])
+def staging_lineage(staging_id):
+    """The snippets a snippet was staged from, newest first (see lineage).
+
+    Each entry: staging_id, parent, label, language, slot, phase, author,
+    code_hash, created, code (null when it is not kept), source (store,
+    file, bundle or audit) and diff, the unified diff from its parent.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.lineage import with_diffs
+        chain = staging_pipeline.lineage(staging_id)
+        return jsonify({'success': True, 'staging_id': staging_id, 'lineage': with_diffs(chain)})
+    except SpokedError as se:
+        return _error_response(se, 404 if se.kind is ErrorKind.NOT_FOUND else 400)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
 @runtime_bp.route('/api/staging/audit', methods=['GET'])
 def staging_audit():
     """Get the full audit trail (most recent first).
@@ -2592,6 +3272,209 @@ def staging_audit():
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
+def _load_gates():
+    """Promotion gates from the plugins dir (one per *.wasm file) + [gates] builtin."""
+    from visual_editor_core.promotion_gates import load_builtin_gates, load_plugin_gates
+    gates = load_plugin_gates(resolve_setting(
+        'plugins_dir',
+        'SPOKEDPY_PLUGINS_DIR',
+        os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'plugins'),
+    ))
+    meta = _KNOWN_SETTINGS['builtin_gates']
+    return gates + load_builtin_gates(resolve_setting('builtin_gates', meta['env'], meta['default']),
+                                      **_builtin_gate_options())
+
+
+def _builtin_gate_options(seconds=None, minimum=None):
+    """Settings for the built-in gates; ``seconds`` / ``minimum`` override the configured ones."""
+    def setting(key):
+        return resolve_setting(key, _KNOWN_SETTINGS[key]['env'], _KNOWN_SETTINGS[key]['default'])
+
+    return {
+        'seconds': float(seconds) if seconds else None,
+        'fuzz_seconds': float(setting('fuzz_seconds')),
+        'fixtures_dir': setting('fuzz_fixtures_dir'),
+        # Resolved per call: the pipeline may not exist yet when gates load.
+        'evaluate': lambda language, code: staging_pipeline.evaluate(language, code),
+        'min_kill_rate': float(setting('mutation_min_kill_rate')),
+        'mutation_limit': int(setting('mutation_limit')),
+        'coverage_min': float(setting('coverage_min') if minimum is None else minimum),
+    }
+
+
+@runtime_bp.route('/api/staging/gates', methods=['GET'])
+def staging_gates():
+    """List the promotion gates the pipeline runs after each dry-run."""
+    if staging_pipeline is None:
+        return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+    return jsonify({'success': True, 'gates': [g.describe() for g in staging_pipeline.gates]})
+
+
+@runtime_bp.route('/api/staging/gate/<staging_id>', methods=['POST'])
+def staging_run_gate(staging_id):
+    """Run one built-in gate on demand before promoting (``promote --gate fuzz``).
+
+    Body: { gate: 'fuzz'|'mutation'|'coverage', seconds?: number, min?: number }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.promotion_gates import builtin_gate
+        data = request.get_json(silent=True) or {}
+        gate = builtin_gate(data.get('gate', ''),
+                            **_builtin_gate_options(data.get('seconds'), data.get('min')))
+        snippet = staging_pipeline.run_gate(staging_id, gate)
+        return jsonify({'success': True, 'decision': snippet.gate_results[-1],
+                        'snippet': snippet.to_dict()})
+    except ValueError as e:
+        return _error_response(e)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/mutate/<staging_id>', methods=['POST'])
+def staging_mutate(staging_id):
+    """Mutation-test a speculated snippet's spec (``spokedpy spec mutate``).
+
+    Body: { limit?: int, seconds?: number }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json(silent=True) or {}
+        meta = _KNOWN_SETTINGS['mutation_limit']
+        limit = int(data.get('limit') or resolve_setting('mutation_limit', meta['env'], meta['default']))
+        report = staging_pipeline.mutate(staging_id, limit,
+                                         float(data['seconds']) if data.get('seconds') else None)
+        meta = _KNOWN_SETTINGS['mutation_min_kill_rate']
+        return jsonify({'success': True, 'report': report,
+                        'min_kill_rate': float(resolve_setting('mutation_min_kill_rate',
+                                                               meta['env'], meta['default']))})
+    except ValueError as e:
+        return _error_response(e)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/compare', methods=['POST'])
+def staging_compare():
+    """A/B-run two snippets on identical spec cases (``spokedpy compare``).
+
+    Body: { a, b, label?, spec?, spec_format?, repeat? }  — without ``spec``
+    the cases come from the snippets' own spec suite.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        if not data.get('a') or not data.get('b'):
+            return jsonify({'success': False, 'error': 'Both a and b staging ids are required'}), 400
+        report = staging_pipeline.compare(data['a'], data['b'], _request_spec(data),
+                                          data.get('label', ''), int(data.get('repeat') or 1))
+        return jsonify({'success': True, 'report': report})
+    except ValueError as e:
+        return _error_response(e)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/respec', methods=['POST'])
+def staging_respec():
+    """Re-run the specs of promoted snippets under the current toolchain (``spokedpy respec``).
+
+    Body: { engine?, staging_ids?, workers?, quarantine? }  — without
+    ``staging_ids`` every slot's current promoted snippet (of ``engine``) runs.
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        data = request.get_json() or {}
+        report = staging_pipeline.respec(data.get('engine', ''), data.get('staging_ids') or None,
+                                         int(data.get('workers') or 4),
+                                         bool(data.get('quarantine')))
+        return jsonify({'success': True, 'report': report})
+    except ValueError as e:
+        return _error_response(e)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+def _report_revalidation(report):
+    if report['failing']:
+        print(f"  [REVALIDATE] {report['checked']} snippet(s), {report['failing']} failing, "
+              f"{report['degraded']} slot(s) degraded")
+
+
+@runtime_bp.route('/api/staging/degraded', methods=['GET'])
+def staging_degraded():
+    """Slots whose promoted snippet fails revalidation, and the revalidation schedule.
+
+    Each entry: slot, staging_id, code_hash, label, reason, toolchain, since.
+    Promoting a snippet that imports one of them answers 409 (slot_degraded).
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        return jsonify({'success': True,
+                        'degraded': [d.to_dict() for d in staging_pipeline.degraded().values()],
+                        'interval_minutes': revalidator.interval / 60,
+                        'last_run': revalidator.last_run})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/gates/reload', methods=['POST'])
+def staging_gates_reload():
+    """Re-scan the plugins dir — pick up new or changed gate plugins without a restart."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        staging_pipeline.set_gates(_load_gates())
+        return jsonify({'success': True, 'gates': [g.describe() for g in staging_pipeline.gates]})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/notifications', methods=['GET'])
+def staging_notifications():
+    """Configured notifiers (Slack / email), their events, and recent delivery errors."""
+    if notification_hub is None:
+        return jsonify({'success': False, 'error': 'Notifications not initialized'}), 500
+    return jsonify({'success': True, **notification_hub.describe()})
+
+
+@runtime_bp.route('/api/staging/notifications/test', methods=['POST'])
+def staging_notifications_test():
+    """Send a sample notification synchronously to check the configuration.
+
+    Body: { event?: 'spec_failure'|'promotion'|'rollback' }
+    """
+    try:
+        if notification_hub is None:
+            return jsonify({'success': False, 'error': 'Notifications not initialized'}), 500
+        from visual_editor_core.notifiers import NOTIFY_EVENTS, Notification
+        event = (request.get_json(silent=True) or {}).get('event', 'promotion')
+        if event not in NOTIFY_EVENTS:
+            raise ValueError(f"Unknown event '{event}' (choose from: {', '.join(NOTIFY_EVENTS)})")
+        note = Notification(event=event, staging_id='stg-test00000000', slot='a1',
+                            label='notification test', language='python',
+                            author='spokedpy', reason='Test message', owners='spokedpy')
+        results = []
+        for notifier in notification_hub.notifiers:
+            if not notifier.wants(event):
+                continue
+            try:
+                notifier.send(note)
+                results.append({'notifier': notifier.kind, 'sent': True})
+            except Exception as exc:
+                results.append({'notifier': notifier.kind, 'sent': False, 'error': str(exc)})
+        return jsonify({'success': all(r['sent'] for r in results), 'results': results})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
 @runtime_bp.route('/api/staging/summary', methods=['GET'])
 def staging_summary():
     """Get pipeline summary: active counts, history stats, reserved slots."""
@@ -2603,16 +3486,252 @@ def staging_summary():
         return jsonify({'success': False, 'error': str(e)}), 500
 
 
+# ==================== APPROVALS ====================
+# A review queue for snippets that stopped short of promotion.  Callers
+# authenticate as a reviewer with ``Authorization: Bearer <token>`` against
+# [server] reviewers ("name:token" entries), or arrive with REMOTE_USER set
+# by an authenticating proxy / WSGI server.  The principal is recorded with
+# every decision.  /api/staging/verdict and /api/staging/promote need one too.
+
+def _reviewer_tokens():
+    """token → principal from the [server] reviewers setting (a tenant has its own)."""
+    raw = tenants.setting('reviewers', 'SPOKEDPY_REVIEWERS', '')
+    tokens = {}
+    for entry in raw.split(','):
+        name, sep, token = entry.strip().partition(':')
+        if sep and name.strip() and token.strip():
+            tokens[token.strip()] = name.strip()
+    return tokens
+
+
+def _approval_principal():
+    """The authenticated reviewer for this request, or None."""
+    import hmac
+    auth = request.headers.get('Authorization', '')
+    if auth.startswith('Bearer '):
+        presented = auth[len('Bearer '):].strip()
+        for token, name in _reviewer_tokens().items():
+            if hmac.compare_digest(presented.encode(), token.encode()):
+                return name
+        return None
+    return request.remote_user or None
+
+
+def _unauthorized():
+    return jsonify({'success': False,
+                    'error': 'Reviewer authentication required '
+                             '(Authorization: Bearer <token> from [server] reviewers)'}), 401
+
+
+@runtime_bp.route('/api/approvals', methods=['GET'])
+def approvals_list():
+    """Snippets waiting on a reviewer, each with its header and diff."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        if _approval_principal() is None:
+            return _unauthorized()
+        pending = staging_pipeline.pending_approvals()
+        return jsonify({'success': True, 'approvals': pending, 'count': len(pending)})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/approvals/<staging_id>/<decision>', methods=['POST'])
+def approvals_decide(staging_id, decision):
+    """Approve (promote) or reject a pending snippet as the authenticated reviewer.
+
+    Body: { reason? }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        if decision not in ('approve', 'reject'):
+            return jsonify({'success': False, 'error': f"Unknown decision '{decision}'"}), 404
+        principal = _approval_principal()
+        if principal is None:
+            return _unauthorized()
+        reason = (request.get_json(silent=True) or {}).get('reason', '')
+        snippet = staging_pipeline.decide(staging_id, decision == 'approve', principal, reason)
+        return jsonify({'success': True, 'principal': principal, 'snippet': snippet.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+# ==================== SCHEDULED PROMOTIONS ====================
+# ``promote --at`` / ``--window``: the snippet stays PASSED, held until the
+# PromotionScheduler thread promotes it.  Windows come from [gates] windows.
+
+def _report_scheduled(promoted):
+    for snippet in promoted:
+        print(f"  [SCHEDULE] promoted {snippet.staging_id} → {snippet.reserved_address}")
+
+
+def _schedule_dict(snippet):
+    from visual_editor_core.promotion_schedule import format_at
+    return {'staging_id': snippet.staging_id, 'label': snippet.label,
+            'slot': snippet.reserved_address, 'language': snippet.language,
+            'at': snippet.scheduled_for, 'iso_at': format_at(snippet.scheduled_for),
+            'scheduled_by': snippet.scheduled_by}
+
+
+@runtime_bp.route('/api/staging/schedule', methods=['GET'])
+def staging_schedule_list():
+    """Held promotions, soonest first, and the configured windows."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.promotion_schedule import format_at, next_window, parse_windows
+        meta = _KNOWN_SETTINGS['windows']
+        windows = parse_windows(tenants.setting('windows', meta['env'], meta['default']))
+        return jsonify({'success': True,
+                        'scheduled': [_schedule_dict(s) for s in staging_pipeline.scheduled()],
+                        'windows': {name: format_at(next_window(w)) for name, w in windows.items()}})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/schedule/<staging_id>', methods=['POST'])
+def staging_schedule(staging_id):
+    """Hold a PASSED snippet and promote it at a time or in a named window.
+
+    Body: { at?: '2026-02-11T02:00Z', window?: 'nightly' }  (exactly one)
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        from visual_editor_core.promotion_schedule import resolve_when
+        data = request.get_json() or {}
+        meta = _KNOWN_SETTINGS['windows']
+        at = resolve_when(data.get('at', ''), data.get('window', ''),
+                          tenants.setting('windows', meta['env'], meta['default']))
+        snippet = staging_pipeline.schedule_promotion(staging_id, at, _approval_principal() or '')
+        return jsonify({'success': True, 'schedule': _schedule_dict(snippet),
+                        'snippet': snippet.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/schedule/<staging_id>', methods=['DELETE'])
+def staging_schedule_cancel(staging_id):
+    """Cancel a held promotion; the snippet stays PASSED.
+
+    Body: { reason? }
+    """
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        reason = (request.get_json(silent=True) or {}).get('reason', '')
+        snippet = staging_pipeline.cancel_schedule(staging_id, reason)
+        return jsonify({'success': True, 'snippet': snippet.to_dict()})
+    except ValueError as ve:
+        return _error_response(ve)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+# ==================== PROMOTION POLICY ====================
+# Per-slot auto-promote rules ([gates] policy_file, see promotion_policy),
+# applied by run-full and marshal when a snippet passes, the slot
+# constraints every promotion must meet ([gates] constraints_file), and
+# who must approve what ([gates] owners_file).
+
+@runtime_bp.route('/api/staging/policy', methods=['GET'])
+def staging_policy():
+    """The auto-promotion rules in effect (re-read if the file changed)."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        policy = staging_pipeline.policy
+        if policy is None:
+            return jsonify({'success': True, 'policy': None})
+        return jsonify({'success': True, 'policy': policy.describe(),
+                        'default_auto_promote': _default_auto_promote()})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/constraints', methods=['GET'])
+def staging_constraints():
+    """Slot constraints and engine capabilities; ``?slot=d2`` → that slot's only."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        constraints = staging_pipeline.constraints
+        if constraints is None:
+            return jsonify({'success': True, 'constraints': None})
+        described = constraints.describe()
+        slot = request.args.get('slot', '')
+        if slot:
+            patterns = {c.slot for c in constraints.for_slot(slot)}
+            described['constraints'] = [c for c in described['constraints'] if c['slot'] in patterns]
+        return jsonify({'success': True, 'constraints': described})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+@runtime_bp.route('/api/staging/owners', methods=['GET'])
+def staging_owners():
+    """The owners rules; ``?slot=d2&label=etl`` → who must approve that snippet."""
+    try:
+        if staging_pipeline is None:
+            return jsonify({'success': False, 'error': 'Staging pipeline not initialized'}), 500
+        owners = staging_pipeline.owners
+        if owners is None:
+            return jsonify({'success': True, 'owners': None})
+        described = owners.describe()
+        slot, label = request.args.get('slot', ''), request.args.get('label', '')
+        if slot or label:
+            approvers, rule = owners.match(slot, label)
+            described['match'] = {'owners': approvers, 'rule': rule.pattern if rule else None}
+        return jsonify({'success': True, 'owners': described})
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
+# ==================== SLOT WATCH ====================
+# Long-poll for consumers that hot-reload promoted snippets (see slot_watch):
+# the request is held until a watched slot is promoted into or rolled back.
+
+@runtime_bp.route('/api/slots/watch', methods=['GET'])
+def slots_watch():
+    """``?slots=d3,a*&since=<cursor>&timeout=30`` → the slot changes after ``since``."""
+    try:
+        if slot_watch is None:
+            return jsonify({'success': False, 'error': 'Slot watch not initialized'}), 500
+        slots = [s for s in request.args.get('slots', '').split(',') if s.strip()]
+        since = request.args.get('since', '')
+        try:
+            since = int(since) if since != '' else None
+            timeout = float(request.args.get('timeout', 30))
+        except ValueError:
+            raise ValueError('since must be an integer cursor and timeout a number of seconds')
+        events, cursor, missed = slot_watch.wait(slots, since, timeout)
+        return jsonify({'success': True, 'cursor': cursor, 'missed': missed,
+                        'events': [asdict(e) for e in events]})
+    except ValueError as e:
+        return _error_response(e)
+    except Exception as e:
+        return jsonify({'success': False, 'error': str(e)}), 500
+
+
 # ==================== SETTINGS API ====================
-# Three-tier configuration: DB (web UI) → .env → hard-coded default.
-# The web interface can override any setting; deleting it reverts to
-# the env / default value.
+# Layered configuration: DB (web UI) → .env → spokedpy.toml
+# (project → user → system) → hard-coded default.  The web interface
+# can override any setting; deleting it reverts to the next layer.
 
 from web_interface.project_db import (
     get_setting as _db_get_setting,
     set_setting as _db_set_setting,
     get_all_settings as _db_get_all_settings,
     delete_setting as _db_delete_setting,
+    explain_setting as _explain_setting,
 )
 
 # Keys that can be managed through the API, with their env-var name
@@ -2630,6 +3749,222 @@ _KNOWN_SETTINGS = {
         'label': 'Staging audit log path',
         'restart_required': True,
     },
+    'toolchains_dir': {
+        'env': 'SPOKEDPY_TOOLCHAINS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'toolchains'),
+        'label': 'Managed toolchain prefix',
+        'restart_required': True,
+    },
+    'vendor_dir': {
+        'env': 'SPOKEDPY_VENDOR_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'vendor'),
+        'label': 'Vendored registry snapshot (offline mode)',
+        'restart_required': True,
+    },
+    'failures_dir': {
+        'env': 'SPOKEDPY_FAILURES_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'failures'),
+        'label': 'Failure bundles (spokedpy failures show)',
+        'restart_required': True,
+    },
+    'trash_dir': {
+        'env': 'SPOKEDPY_TRASH_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'trash'),
+        'label': 'Deleted snippets (spokedpy trash list / restore)',
+        'restart_required': True,
+    },
+    'trends_log': {
+        'env': 'SPOKEDPY_TRENDS_LOG',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'spec_trends.jsonl'),
+        'label': 'spec_time trend index (spokedpy trends)',
+        'restart_required': True,
+    },
+    'specs_dir': {
+        'env': 'SPOKEDPY_SPECS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'specs'),
+        'label': 'Default spec per label (<label>/default.spec.toml), inherited on staging',
+        'restart_required': True,
+    },
+    'index_path': {
+        'env': 'SPOKEDPY_INDEX_PATH',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'store_index.sqlite'),
+        'label': 'Slot index of the promoted files (spokedpy resolve, SlotResolver)',
+        'restart_required': False,
+    },
+    'read_only': {
+        'env': 'SPOKEDPY_READ_ONLY',
+        'default': '0',
+        'label': 'Read-only store: resolve promoted snippets, refuse every change (0/1)',
+        'restart_required': True,
+    },
+    'plugins_dir': {
+        'env': 'SPOKEDPY_PLUGINS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'plugins'),
+        'label': 'Promotion gate plugins (*.wasm)',
+        'restart_required': False,
+    },
+    'policy_file': {
+        'env': 'SPOKEDPY_POLICY_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'promotion_policy.toml'),
+        'label': 'Per-slot auto-promotion rules (TOML, re-read on change)',
+        'restart_required': True,
+    },
+    'constraints_file': {
+        'env': 'SPOKEDPY_CONSTRAINTS_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'slot_constraints.toml'),
+        'label': 'Slot constraints checked before promotion (TOML, re-read on change)',
+        'restart_required': True,
+    },
+    'owners_file': {
+        'env': 'SPOKEDPY_OWNERS_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'OWNERS'),
+        'label': 'Slot / label owners who must approve (CODEOWNERS-style, re-read on change)',
+        'restart_required': True,
+    },
+    'builtin_gates': {
+        'env': 'SPOKEDPY_GATES_BUILTIN',
+        'default': '',
+        'label': 'Built-in gates run after every dry-run (comma-separated, e.g. fuzz)',
+        'restart_required': False,
+    },
+    'fuzz_seconds': {
+        'env': 'SPOKEDPY_FUZZ_SECONDS',
+        'default': '30',
+        'label': 'Fuzz gate session length (seconds)',
+        'restart_required': False,
+    },
+    'mutation_min_kill_rate': {
+        'env': 'SPOKEDPY_MUTATION_MIN_KILL_RATE',
+        'default': '0.6',
+        'label': 'Mutation gate: minimum share of mutants the spec must kill (0-1)',
+        'restart_required': False,
+    },
+    'mutation_limit': {
+        'env': 'SPOKEDPY_MUTATION_LIMIT',
+        'default': '40',
+        'label': 'Mutation gate: mutants run per snippet',
+        'restart_required': False,
+    },
+    'coverage_min': {
+        'env': 'SPOKEDPY_COVERAGE_MIN',
+        'default': '80',
+        'label': 'Coverage gate: minimum line coverage of Rust snippets (%)',
+        'restart_required': False,
+    },
+    'fuzz_fixtures_dir': {
+        'env': 'SPOKEDPY_FUZZ_FIXTURES_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'fuzz_fixtures'),
+        'label': 'Fuzz gate crash inputs (regression fixtures)',
+        'restart_required': False,
+    },
+    'offline': {
+        'env': 'SPOKEDPY_OFFLINE',
+        'default': '0',
+        'label': 'Offline / air-gapped mode (0/1)',
+        'restart_required': True,
+    },
+    'engines': {
+        'env': 'SPOKEDPY_ENGINES',
+        'default': '',
+        'label': 'Enabled engines (comma-separated, empty = all)',
+        'restart_required': True,
+    },
+    'warm_pool': {
+        'env': 'SPOKEDPY_WARM_POOL',
+        'default': '',
+        'label': 'Pre-booted workers for spec runs, as "python=4, javascript=2" (empty = off)',
+        'restart_required': True,
+    },
+    'warm_recycle': {
+        'env': 'SPOKEDPY_WARM_RECYCLE',
+        'default': '100',
+        'label': 'Runs before a warm Python worker is replaced',
+        'restart_required': True,
+    },
+    'auto_promote': {
+        'env': 'SPOKEDPY_AUTO_PROMOTE',
+        'default': '1',
+        'label': 'Auto-promote snippets that pass (0/1)',
+        'restart_required': False,
+    },
+    'history_limit': {
+        'env': 'SPOKEDPY_HISTORY_LIMIT',
+        'default': '1000',
+        'label': 'Finished snippets kept in pipeline history',
+        'restart_required': True,
+    },
+    'trash_days': {
+        'env': 'SPOKEDPY_TRASH_DAYS',
+        'default': '30',
+        'label': 'Days deleted snippets stay in the trash (0 = until purged)',
+        'restart_required': True,
+    },
+    'quota_max_staged': {
+        'env': 'SPOKEDPY_QUOTA_MAX_STAGED',
+        'default': '0',
+        'label': 'Quota: snippets staged at once per author and per namespace (0 = no limit)',
+        'restart_required': True,
+    },
+    'quota_max_spec_minutes': {
+        'env': 'SPOKEDPY_QUOTA_MAX_SPEC_MINUTES',
+        'default': '0',
+        'label': 'Quota: dry-run minutes per UTC day per author and per namespace (0 = no limit)',
+        'restart_required': True,
+    },
+    'revalidate_minutes': {
+        'env': 'SPOKEDPY_REVALIDATE_MINUTES',
+        'default': '0',
+        'label': 'Re-spec promoted snippets every N minutes; failing slots turn DEGRADED (0 = never)',
+        'restart_required': True,
+    },
+    'shard_width': {
+        'env': 'SPOKEDPY_SHARD_WIDTH',
+        'default': '2',
+        'label': 'Promoted files: code_hash hex digits per subdirectory (0 = flat)',
+        'restart_required': True,
+    },
+    'id_mode': {
+        'env': 'SPOKEDPY_ID_MODE',
+        'default': 'random',
+        'label': 'Staging ids: random, deterministic (from code, label and slot) or ulid (time-sortable)',
+        'restart_required': True,
+    },
+    'gate_override': {
+        'env': 'SPOKEDPY_GATE_OVERRIDE',
+        'default': '1',
+        'label': 'Allow approving snippets that failed speculation (0/1)',
+        'restart_required': True,
+    },
+    'windows': {
+        'env': 'SPOKEDPY_WINDOWS',
+        'default': 'nightly=02:00,weekly=sun 02:00',
+        'label': 'Promotion windows for promote --window (name=[weekday ]HH:MM UTC, comma-separated)',
+        'restart_required': False,
+    },
+    'sandbox_backend': {
+        'env': 'SPOKEDPY_SANDBOX_BACKEND',
+        'default': 'process',
+        'label': 'Sandbox backend (process / netns)',
+        'restart_required': True,
+    },
+    'scratch': {
+        'env': 'SPOKEDPY_SCRATCH',
+        'default': '1',
+        'label': 'Dry-run each spec case in a scratch dir, refusing writes outside it (0/1)',
+        'restart_required': True,
+    },
+    'network': {
+        'env': 'SPOKEDPY_NETWORK',
+        'default': 'none',
+        'label': 'Network of spec runs: none / loopback / full, per engine as "none, rust=full"',
+        'restart_required': True,
+    },
+    'seccomp': {
+        'env': 'SPOKEDPY_SECCOMP',
+        'default': 'off',
+        'label': 'Seccomp profile of spec runs\' engine processes: off / dev / prod (Linux)',
+        'restart_required': True,
+    },
     'marshal_ttl': {
         'env': 'SPOKEDPY_MARSHAL_TTL',
         'default': '4000',
@@ -2647,11 +3982,11 @@ def settings_list():
     for key, meta in _KNOWN_SETTINGS.items():
         db_val = db_settings.get(key)
         env_val = os.environ.get(meta['env'], '').strip() or None
-        effective = db_val or env_val or meta['default']
-        source = 'database' if db_val else ('environment' if env_val else 'default')
+        resolved = _explain_setting(key, meta['env'], meta['default'], db_val)
         result[key] = {
-            'value': effective,
-            'source': source,
+            'value': resolved['value'],
+            'source': resolved['source'],
+            'origin': resolved['origin'],
             'db_override': db_val,
             'env_value': env_val,
             'default': meta['default'],
@@ -2670,13 +4005,14 @@ def settings_get(key: str):
         return jsonify({'success': False, 'error': f'Unknown setting: {key}'}), 404
     db_val = _db_get_setting(key)
     env_val = os.environ.get(meta['env'], '').strip() or None
-    effective = db_val or env_val or meta['default']
-    source = 'database' if db_val else ('environment' if env_val else 'default')
+    resolved = _explain_setting(key, meta['env'], meta['default'], db_val)
     return jsonify({
         'success': True,
         'key': key,
-        'value': effective,
-        'source': source,
+        'value': resolved['value'],
+        'source': resolved['source'],
+        'origin': resolved['origin'],
+        'layers': resolved['layers'],
         'db_override': db_val,
         'env_value': env_val,
         'default': meta['default'],
@@ -2710,7 +4046,7 @@ def settings_set(key: str):
 
 @runtime_bp.route('/api/settings/<key>', methods=['DELETE'])
 def settings_delete(key: str):
-    """Remove a database override (reverts to env / default)."""
+    """Remove a database override (reverts to env / spokedpy.toml / default)."""
     key = key.lower()
     meta = _KNOWN_SETTINGS.get(key)
     if not meta:
@@ -2718,10 +4054,11 @@ def settings_delete(key: str):
     deleted = _db_delete_setting(key)
     if not deleted:
         return jsonify({'success': False, 'error': 'Setting was not overridden'}), 404
+    resolved = _explain_setting(key, meta['env'], meta['default'])
     return jsonify({
         'success': True,
-        'reverted_to': os.environ.get(meta['env'], '').strip() or meta['default'],
-        'source': 'environment' if os.environ.get(meta['env'], '').strip() else 'default',
+        'reverted_to': resolved['value'],
+        'source': resolved['source'],
     })
 
 
@@ -2767,7 +4104,7 @@ def marshal_submit():
         language = data.get('language', '')
         code = data.get('code', '')
         label = data.get('label', '')
-        auto_promote = data.get('auto_promote', True)
+        auto_promote = data.get('auto_promote')     # None → [gates] auto_promote / the policy
         ttl = int(data.get('ttl', _MARSHAL_DEFAULT_TTL))
         # Provenance — who/what submitted this
         origin = data.get('origin', 'api')          # 'api' | 'live-exec' | 'canvas'
@@ -2784,7 +4121,9 @@ def marshal_submit():
 
         # Run the full pipeline synchronously
         snippet = staging_pipeline.run_full_pipeline(
-            engine_letter, language, code, label, auto_promote
+            engine_letter, language, code, label, auto_promote,
+            author=submitter or agent_id or ('Human' if origin == 'live-exec' else 'API Agent'),
+            default_auto_promote=_default_auto_promote(), meta=data.get('meta'),
         )
 
         # Mint an opaque token bound to this snippet
@@ -2851,7 +4190,7 @@ def marshal_submit():
 
         return jsonify(resp)
     except ValueError as ve:
-        return jsonify({'success': False, 'error': str(ve)}), 400
+        return _error_response(ve)
     except Exception as e:
         return jsonify({'success': False, 'error': str(e)}), 500
 
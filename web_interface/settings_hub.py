@@ -13,6 +13,10 @@ Provides:
     GET /api/hub/tests/<run_id>    — single test run detail
     GET /api/hub/history           — change-history feed
     POST /api/hub/settings/bulk    — batch-update settings
+    DELETE /api/hub/settings/<key> — drop a DB override
+
+  The settings writes, log writes and clears, and test runs are refused
+  (403) while the store is read-only.
 """
 
 import json
@@ -36,6 +40,7 @@ from web_interface.project_db import (
     set_setting,
     delete_setting,
     resolve_setting,
+    explain_setting,
 )
 
 hub_bp = Blueprint('settings_hub', __name__)
@@ -147,6 +152,110 @@ SETTINGS_MANIFEST: Dict[str, Dict[str, Any]] = {
         'type': 'path',
         'restart': True,
     },
+    'toolchains_dir': {
+        'env': 'SPOKEDPY_TOOLCHAINS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'toolchains'),
+        'label': 'Managed toolchain prefix',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'vendor_dir': {
+        'env': 'SPOKEDPY_VENDOR_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'vendor'),
+        'label': 'Vendored registry snapshot (offline mode)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'failures_dir': {
+        'env': 'SPOKEDPY_FAILURES_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'failures'),
+        'label': 'Failure bundles (spokedpy failures show)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'trash_dir': {
+        'env': 'SPOKEDPY_TRASH_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'trash'),
+        'label': 'Deleted snippets (spokedpy trash list / restore)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'trends_log': {
+        'env': 'SPOKEDPY_TRENDS_LOG',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'spec_trends.jsonl'),
+        'label': 'spec_time trend index (spokedpy trends)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'specs_dir': {
+        'env': 'SPOKEDPY_SPECS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'specs'),
+        'label': 'Default spec per label (<label>/default.spec.toml), inherited on staging',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'index_path': {
+        'env': 'SPOKEDPY_INDEX_PATH',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'store_index.sqlite'),
+        'label': 'Slot index of the promoted files (spokedpy resolve, SlotResolver)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': False,
+    },
+    'read_only': {
+        'env': 'SPOKEDPY_READ_ONLY',
+        'default': '0',
+        'label': 'Read-only store: resolve promoted snippets, refuse every change (0/1)',
+        'group': 'server',
+        'type': 'boolean',
+        'restart': True,
+    },
+    'plugins_dir': {
+        'env': 'SPOKEDPY_PLUGINS_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'plugins'),
+        'label': 'Promotion gate plugins (*.wasm)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': False,
+    },
+    'policy_file': {
+        'env': 'SPOKEDPY_POLICY_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'promotion_policy.toml'),
+        'label': 'Per-slot auto-promotion rules (TOML, re-read on change)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'constraints_file': {
+        'env': 'SPOKEDPY_CONSTRAINTS_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'slot_constraints.toml'),
+        'label': 'Slot constraints checked before promotion (TOML, re-read on change)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'owners_file': {
+        'env': 'SPOKEDPY_OWNERS_FILE',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'OWNERS'),
+        'label': 'Slot / label owners who must approve (CODEOWNERS-style, re-read on change)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': True,
+    },
+    'fuzz_fixtures_dir': {
+        'env': 'SPOKEDPY_FUZZ_FIXTURES_DIR',
+        'default': os.path.join(os.path.dirname(os.path.dirname(__file__)), 'data', 'fuzz_fixtures'),
+        'label': 'Fuzz gate crash inputs (regression fixtures)',
+        'group': 'paths',
+        'type': 'path',
+        'restart': False,
+    },
     'db_path': {
         'env': 'SPOKEDPY_DB_PATH',
         'default': os.path.join(os.path.dirname(os.path.abspath(__file__)), 'projects.db'),
@@ -172,6 +281,14 @@ SETTINGS_MANIFEST: Dict[str, Dict[str, Any]] = {
         'type': 'number',
         'restart': True,
     },
+    'offline': {
+        'env': 'SPOKEDPY_OFFLINE',
+        'default': '0',
+        'label': 'Offline / air-gapped mode (0/1)',
+        'group': 'server',
+        'type': 'boolean',
+        'restart': True,
+    },
     'reloader': {
         'env': 'SPOKEDPY_RELOADER',
         'default': '0',
@@ -180,6 +297,199 @@ SETTINGS_MANIFEST: Dict[str, Dict[str, Any]] = {
         'type': 'boolean',
         'restart': True,
     },
+    'ui': {
+        'env': 'SPOKEDPY_UI',
+        'default': '0',
+        'label': 'Serve the store browser at /ui (0/1)',
+        'group': 'server',
+        'type': 'boolean',
+        'restart': True,
+    },
+    'reviewers': {
+        'env': 'SPOKEDPY_REVIEWERS',
+        'default': '',
+        'label': 'Approval reviewers (comma-separated name:token)',
+        'group': 'server',
+        'type': 'secret',
+        'restart': False,
+    },
+    # ── Pipeline ─────────────────────────────────────────────────────
+    'engines': {
+        'env': 'SPOKEDPY_ENGINES',
+        'default': '',
+        'label': 'Enabled engines (comma-separated, empty = all)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
+    'warm_pool': {
+        'env': 'SPOKEDPY_WARM_POOL',
+        'default': '',
+        'label': 'Pre-booted workers for spec runs, as "python=4, javascript=2" (empty = off)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
+    'warm_recycle': {
+        'env': 'SPOKEDPY_WARM_RECYCLE',
+        'default': '100',
+        'label': 'Runs before a warm Python worker is replaced',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'auto_promote': {
+        'env': 'SPOKEDPY_AUTO_PROMOTE',
+        'default': '1',
+        'label': 'Auto-promote snippets that pass (0/1)',
+        'group': 'pipeline',
+        'type': 'boolean',
+        'restart': False,
+    },
+    'history_limit': {
+        'env': 'SPOKEDPY_HISTORY_LIMIT',
+        'default': '1000',
+        'label': 'Finished snippets kept in pipeline history',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'trash_days': {
+        'env': 'SPOKEDPY_TRASH_DAYS',
+        'default': '30',
+        'label': 'Days deleted snippets stay in the trash (0 = until purged)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'quota_max_staged': {
+        'env': 'SPOKEDPY_QUOTA_MAX_STAGED',
+        'default': '0',
+        'label': 'Quota: snippets staged at once per author and per namespace (0 = no limit)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'quota_max_spec_minutes': {
+        'env': 'SPOKEDPY_QUOTA_MAX_SPEC_MINUTES',
+        'default': '0',
+        'label': 'Quota: dry-run minutes per UTC day per author and per namespace (0 = no limit)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'revalidate_minutes': {
+        'env': 'SPOKEDPY_REVALIDATE_MINUTES',
+        'default': '0',
+        'label': 'Re-spec promoted snippets every N minutes; failing slots turn DEGRADED (0 = never)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'shard_width': {
+        'env': 'SPOKEDPY_SHARD_WIDTH',
+        'default': '2',
+        'label': 'Promoted files: code_hash hex digits per subdirectory (0 = flat)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': True,
+    },
+    'id_mode': {
+        'env': 'SPOKEDPY_ID_MODE',
+        'default': 'random',
+        'label': 'Staging ids: random, deterministic (from code, label and slot) or ulid (time-sortable)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
+    'gate_override': {
+        'env': 'SPOKEDPY_GATE_OVERRIDE',
+        'default': '1',
+        'label': 'Allow approving snippets that failed speculation (0/1)',
+        'group': 'pipeline',
+        'type': 'boolean',
+        'restart': True,
+    },
+    'windows': {
+        'env': 'SPOKEDPY_WINDOWS',
+        'default': 'nightly=02:00,weekly=sun 02:00',
+        'label': 'Promotion windows for promote --window (name=[weekday ]HH:MM UTC, comma-separated)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': False,
+    },
+    'builtin_gates': {
+        'env': 'SPOKEDPY_GATES_BUILTIN',
+        'default': '',
+        'label': 'Built-in gates run after every dry-run (comma-separated, e.g. fuzz)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': False,
+    },
+    'fuzz_seconds': {
+        'env': 'SPOKEDPY_FUZZ_SECONDS',
+        'default': '30',
+        'label': 'Fuzz gate session length (seconds)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': False,
+    },
+    'mutation_min_kill_rate': {
+        'env': 'SPOKEDPY_MUTATION_MIN_KILL_RATE',
+        'default': '0.6',
+        'label': 'Mutation gate: minimum share of mutants the spec must kill (0-1)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': False,
+    },
+    'mutation_limit': {
+        'env': 'SPOKEDPY_MUTATION_LIMIT',
+        'default': '40',
+        'label': 'Mutation gate: mutants run per snippet',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': False,
+    },
+    'coverage_min': {
+        'env': 'SPOKEDPY_COVERAGE_MIN',
+        'default': '80',
+        'label': 'Coverage gate: minimum line coverage of Rust snippets (%)',
+        'group': 'pipeline',
+        'type': 'number',
+        'restart': False,
+    },
+    'sandbox_backend': {
+        'env': 'SPOKEDPY_SANDBOX_BACKEND',
+        'default': 'process',
+        'label': 'Sandbox backend (process / netns)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
+    'scratch': {
+        'env': 'SPOKEDPY_SCRATCH',
+        'default': '1',
+        'label': 'Dry-run each spec case in a scratch dir, refusing writes outside it (0/1)',
+        'group': 'pipeline',
+        'type': 'boolean',
+        'restart': True,
+    },
+    'network': {
+        'env': 'SPOKEDPY_NETWORK',
+        'default': 'none',
+        'label': 'Network of spec runs: none / loopback / full, per engine as "none, rust=full"',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
+    'seccomp': {
+        'env': 'SPOKEDPY_SECCOMP',
+        'default': 'off',
+        'label': 'Seccomp profile of spec runs\' engine processes: off / dev / prod (Linux)',
+        'group': 'pipeline',
+        'type': 'string',
+        'restart': True,
+    },
     # ── Marshal ──────────────────────────────────────────────────────
     'marshal_ttl': {
         'env': 'SPOKEDPY_MARSHAL_TTL',
@@ -189,6 +499,161 @@ SETTINGS_MANIFEST: Dict[str, Dict[str, Any]] = {
         'type': 'number',
         'restart': False,
     },
+    # ── Notifications ────────────────────────────────────────────────
+    'diff_url': {
+        'env': 'SPOKEDPY_DIFF_URL',
+        'default': 'http://localhost:5002/api/staging/snippet/$staging_id',
+        'label': 'Diff link in messages ($staging_id, $slot, …)',
+        'group': 'notify',
+        'type': 'url',
+        'restart': True,
+    },
+    'anomaly_sigmas': {
+        'env': 'SPOKEDPY_ANOMALY_SIGMAS',
+        'default': '3',
+        'label': 'spec_anomaly: standard deviations above the rolling median',
+        'group': 'notify',
+        'type': 'number',
+        'restart': True,
+    },
+    'anomaly_window': {
+        'env': 'SPOKEDPY_ANOMALY_WINDOW',
+        'default': '20',
+        'label': 'spec_anomaly: runs in the rolling window',
+        'group': 'notify',
+        'type': 'number',
+        'restart': True,
+    },
+    'slack_webhook': {
+        'env': 'SPOKEDPY_SLACK_WEBHOOK',
+        'default': '',
+        'label': 'Slack incoming webhook URL',
+        'group': 'notify',
+        'type': 'secret',
+        'restart': True,
+    },
+    'slack_events': {
+        'env': 'SPOKEDPY_SLACK_EVENTS',
+        'default': 'spec_failure,spec_anomaly,approval_request,promotion,rollback,degraded',
+        'label': 'Slack events (spec_failure, spec_anomaly, approval_request, promotion, rollback, '
+                 'degraded)',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'smtp_host': {
+        'env': 'SPOKEDPY_SMTP_HOST',
+        'default': '',
+        'label': 'SMTP server',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'smtp_port': {
+        'env': 'SPOKEDPY_SMTP_PORT',
+        'default': '587',
+        'label': 'SMTP port',
+        'group': 'notify',
+        'type': 'number',
+        'restart': True,
+    },
+    'smtp_username': {
+        'env': 'SPOKEDPY_SMTP_USERNAME',
+        'default': '',
+        'label': 'SMTP username',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'smtp_password': {
+        'env': 'SPOKEDPY_SMTP_PASSWORD',
+        'default': '',
+        'label': 'SMTP password',
+        'group': 'notify',
+        'type': 'secret',
+        'restart': True,
+    },
+    'smtp_starttls': {
+        'env': 'SPOKEDPY_SMTP_STARTTLS',
+        'default': '1',
+        'label': 'Use STARTTLS (0/1)',
+        'group': 'notify',
+        'type': 'boolean',
+        'restart': True,
+    },
+    'email_from': {
+        'env': 'SPOKEDPY_EMAIL_FROM',
+        'default': 'spokedpy@localhost',
+        'label': 'Email sender address',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'email_to': {
+        'env': 'SPOKEDPY_EMAIL_TO',
+        'default': '',
+        'label': 'Email recipients (comma-separated)',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'email_events': {
+        'env': 'SPOKEDPY_EMAIL_EVENTS',
+        'default': 'spec_failure,spec_anomaly,approval_request,promotion,rollback,degraded',
+        'label': 'Email events (spec_failure, spec_anomaly, approval_request, promotion, rollback, '
+                 'degraded)',
+        'group': 'notify',
+        'type': 'string',
+        'restart': True,
+    },
+    'template_spec_failure': {
+        'env': 'SPOKEDPY_TEMPLATE_SPEC_FAILURE',
+        'default': '',
+        'label': 'Spec-failure message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
+    'template_spec_anomaly': {
+        'env': 'SPOKEDPY_TEMPLATE_SPEC_ANOMALY',
+        'default': '',
+        'label': 'Slow-spec-run message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
+    'template_approval_request': {
+        'env': 'SPOKEDPY_TEMPLATE_APPROVAL_REQUEST',
+        'default': '',
+        'label': 'Approval-request message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
+    'template_promotion': {
+        'env': 'SPOKEDPY_TEMPLATE_PROMOTION',
+        'default': '',
+        'label': 'Promotion message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
+    'template_rollback': {
+        'env': 'SPOKEDPY_TEMPLATE_ROLLBACK',
+        'default': '',
+        'label': 'Rollback message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
+    'template_degraded': {
+        'env': 'SPOKEDPY_TEMPLATE_DEGRADED',
+        'default': '',
+        'label': 'Degraded slot message template (empty = built-in)',
+        'group': 'notify',
+        'type': 'textarea',
+        'restart': True,
+    },
     # ── AI Agent ─────────────────────────────────────────────────────
     'ai_endpoint': {
         'env': 'SPOKEDPY_AI_ENDPOINT',
@@ -276,7 +741,9 @@ SETTINGS_MANIFEST: Dict[str, Dict[str, Any]] = {
 SETTING_GROUPS = [
     {'key': 'paths',   'label': 'Paths & Storage',   'icon': 'folder'},
     {'key': 'server',  'label': 'Server',             'icon': 'server'},
+    {'key': 'pipeline', 'label': 'Staging Pipeline',  'icon': 'git-branch'},
     {'key': 'marshal', 'label': 'Marshal Tokens',     'icon': 'key'},
+    {'key': 'notify',  'label': 'Notifications',      'icon': 'bell'},
     {'key': 'ai',      'label': 'AI Agent',           'icon': 'bot'},
     {'key': 'canvas',  'label': 'Canvas & Visuals',   'icon': 'layout'},
 ]
@@ -289,11 +756,11 @@ def _resolve_all() -> Dict[str, Dict[str, Any]]:
     for key, meta in SETTINGS_MANIFEST.items():
         db_val = db.get(key)
         env_val = os.environ.get(meta['env'], '').strip() or None
-        effective = db_val or env_val or meta['default']
-        source = 'database' if db_val else ('environment' if env_val else 'default')
+        resolved = explain_setting(key, meta['env'], meta['default'], db_val)
         out[key] = {
-            'value': effective,
-            'source': source,
+            'value': resolved['value'],
+            'source': resolved['source'],
+            'origin': resolved['origin'],
             'db_override': db_val,
             'env_value': env_val,
             'default': meta['default'],
@@ -309,6 +776,12 @@ def _resolve_all() -> Dict[str, Dict[str, Any]]:
 # ROUTES — Settings
 # ─────────────────────────────────────────────────────────────────────
 
+def _refuse_when_read_only():
+    """A read-only server keeps its settings DB too: a row outlives the restart."""
+    from web_interface.runtime import read_only_refusal
+    return read_only_refusal()
+
+
 @hub_bp.route('/api/hub/settings', methods=['GET'])
 def hub_settings_list():
     """Return every known setting with value, source, and metadata."""
@@ -325,6 +798,9 @@ def hub_settings_bulk():
 
     Body: ``{ "updates": { "key": "value", ... } }``
     """
+    refused = _refuse_when_read_only()
+    if refused is not None:
+        return refused
     data = request.get_json(force=True, silent=True) or {}
     updates = data.get('updates', {})
     if not updates:
@@ -356,6 +832,9 @@ def hub_settings_bulk():
 @hub_bp.route('/api/hub/settings/<key>', methods=['DELETE'])
 def hub_settings_revert(key: str):
     """Remove a DB override so the setting reverts to env/default."""
+    refused = _refuse_when_read_only()
+    if refused is not None:
+        return refused
     key = key.lower()
     meta = SETTINGS_MANIFEST.get(key)
     if not meta:
@@ -429,6 +908,9 @@ def hub_logs_write():
 
     Body: ``{ "level": "info", "source": "ui", "message": "...", "detail": "..." }``
     """
+    refused = _refuse_when_read_only()
+    if refused is not None:
+        return refused
     data = request.get_json(force=True, silent=True) or {}
     msg = data.get('message', '').strip()
     if not msg:
@@ -445,6 +927,9 @@ def hub_logs_write():
 @hub_bp.route('/api/hub/logs', methods=['DELETE'])
 def hub_logs_clear():
     """Clear all logs (or logs older than `before` epoch)."""
+    refused = _refuse_when_read_only()
+    if refused is not None:
+        return refused
     before = request.args.get('before', type=float)
     conn = _get_db()
     if before:
@@ -487,6 +972,9 @@ def hub_tests_run():
 
     Body (optional): ``{ "files": ["test_foo.py"], "verbose": true }``
     """
+    refused = _refuse_when_read_only()
+    if refused is not None:
+        return refused
     data = request.get_json(force=True, silent=True) or {}
     files = data.get('files', [])
     verbose = data.get('verbose', False)
@@ -63,13 +63,16 @@ class StatePersistence:
 
     COALESCE_SECONDS = 1.0   # debounce window
 
-    def __init__(self, path: Optional[str] = None):
+    def __init__(self, path: Optional[str] = None, read_only: bool = False):
         self._path = path or _resolve_checkpoint_path()
         self._lock = threading.Lock()
         self._pending: Optional[dict] = None
         self._timer: Optional[threading.Timer] = None
-        # Ensure directory exists
-        os.makedirs(os.path.dirname(self._path) or '.', exist_ok=True)
+        # Read-only store: restore() works, checkpoints are never written
+        self._read_only = read_only
+        if not read_only:
+            # Ensure directory exists
+            os.makedirs(os.path.dirname(self._path) or '.', exist_ok=True)
 
     # ─────────────────────────────────────────────────────────────────
     # CHECKPOINT — serialize current state
@@ -86,11 +89,12 @@ class StatePersistence:
             marshal_tokens:     { token -> token_record }
             promoted_snapshots: [ { staging_id, language, engine_letter,
                                     code, label, address, position,
-                                    engine_name, code_hash, origin,
+                                    engine_name, code_hash, meta, parent, origin,
                                     submitter, agent_id, token,
-                                    ttl, created_at, promoted_at,
+                                    ttl, created_at, promoted_at, promoted_seq,
                                     spec_output, spec_error,
-                                    spec_execution_time, spec_success } ]
+                                    spec_execution_time, spec_success,
+                                    approved_by } ]
         """
         now = time.time()
         state = {
@@ -136,6 +140,8 @@ class StatePersistence:
 
     def _write_atomic(self, state: dict):
         """Write state to a temp file, then atomically rename."""
+        if self._read_only:
+            return
         tmp_path = self._path + '.tmp'
         try:
             with open(tmp_path, 'w', encoding='utf-8') as f:
@@ -264,6 +270,8 @@ def build_promoted_snapshots(staging_pipeline, marshal_tokens: dict,
             'position': sn.reserved_position,
             'engine_name': sn.reserved_engine,
             'code_hash': sn.code_hash,
+            'meta': sn.meta,
+            'parent': sn.parent,
             'origin': token_rec.get('origin', 'api'),
             'submitter': token_rec.get('submitter', ''),
             'agent_id': token_rec.get('agent_id', ''),
@@ -271,10 +279,12 @@ def build_promoted_snapshots(staging_pipeline, marshal_tokens: dict,
             'ttl': token_rec.get('ttl', 0),
             'created_at': sn.created_at,
             'promoted_at': sn.promoted_at,
+            'promoted_seq': sn.promoted_seq,
             'spec_output': (sn.spec_output or '')[:2000],
             'spec_error': (sn.spec_error or '')[:2000],
             'spec_execution_time': sn.spec_execution_time,
             'spec_success': sn.spec_success,
+            'approved_by': sn.reviewed_by or sn.scheduled_by,
             'locked': is_locked,
             'saved_file_path': sn.saved_file_path,
             'ledger_node_id': sn.ledger_node_id,
@@ -310,6 +320,8 @@ def build_promoted_snapshots(staging_pipeline, marshal_tokens: dict,
             'position': sn.reserved_position,
             'engine_name': sn.reserved_engine,
             'code_hash': sn.code_hash,
+            'meta': sn.meta,
+            'parent': sn.parent,
             'origin': token_rec.get('origin', 'api'),
             'submitter': token_rec.get('submitter', ''),
             'agent_id': token_rec.get('agent_id', ''),
@@ -317,10 +329,12 @@ def build_promoted_snapshots(staging_pipeline, marshal_tokens: dict,
             'ttl': token_rec.get('ttl', 0),
             'created_at': sn.created_at,
             'promoted_at': sn.promoted_at,
+            'promoted_seq': sn.promoted_seq,
             'spec_output': (sn.spec_output or '')[:2000],
             'spec_error': (sn.spec_error or '')[:2000],
             'spec_execution_time': sn.spec_execution_time,
             'spec_success': sn.spec_success,
+            'approved_by': sn.reviewed_by or sn.scheduled_by,
             'locked': is_locked,
             'saved_file_path': sn.saved_file_path,
             'ledger_node_id': sn.ledger_node_id,
@@ -0,0 +1,40 @@
+"""
+Store UI — a small embedded web app for browsing promoted snippets.
+
+Mounted at /ui by ``spokedpy serve --ui`` (or [server] ui = true).  The
+page is static; everything it shows comes from the REST API:
+
+    ┌──────────────────┬──────────────────────────────────────────────────┐
+    │  slot grid       │  GET  /api/staging/snippets?include_history=1     │
+    │  snippet detail  │  GET  /api/staging/snippet/<id>                  │
+    │  history         │  GET  /api/staging/audit                         │
+    │  rollback        │  POST /api/staging/rollback/<id>                 │
+    │  approve/reject  │  POST /api/approvals/<id>/approve|reject          │
+    │                  │       (reviewer token from [server] reviewers)   │
+    └──────────────────┴──────────────────────────────────────────────────┘
+
+No build step and no third-party assets, so the UI works offline.
+"""
+
+import os
+
+from flask import Blueprint, send_from_directory
+
+_TEMPLATES = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'templates')
+
+store_ui_bp = Blueprint('store_ui', __name__)
+
+
+@store_ui_bp.route('/ui', methods=['GET'])
+@store_ui_bp.route('/ui/', methods=['GET'])
+def store_ui():
+    """Serve the store browser page."""
+    return send_from_directory(_TEMPLATES, 'store_ui.html')
+
+
+def register_store_ui(app):
+    """Register the store-UI blueprint (once)."""
+    if 'store_ui' in app.blueprints:
+        return
+    app.register_blueprint(store_ui_bp)
+    print(f"  Store UI:      /ui")
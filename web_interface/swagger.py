@@ -12,11 +12,28 @@ Usage:
 Provides:
     GET  /api/docs        → Swagger UI (HTML page)
     GET  /api/docs/spec   → OpenAPI 3.0 JSON spec
+
+Every operation has a stable ``operationId`` (its view function's name),
+a request body read from the docstring's one-line ``Body: { code, label?,
+workers?: int }`` (``?`` marks optional; ``name: 'a'|'b'``, ``bool``,
+``int``, ``number`` and ``[..]`` give the type), query parameters from
+its ``?limit=…&q=…`` examples, and a 200 response with the keys the view returns
+next to ``success``.  Snippets, degraded slots, lineages and errors refer
+to the StagedSnippet, Degradation, Ancestor and Error component schemas;
+every failure answers an Error (``kind`` is an ErrorKind value).
+
+``spokedpy openapi`` exports the document of the daemon's API (the runtime
+blueprint, see openapi_app) without a running server, and regenerates the
+typed client in spokedpy_client from it.
 """
 
+import dataclasses
+import enum
+import inspect
 import os
 import re
-from flask import Blueprint, jsonify, request
+import typing
+from flask import Blueprint, Flask, jsonify, request
 
 swagger_bp = Blueprint('swagger', __name__)
 
@@ -90,12 +107,193 @@ def _parse_path_params(rule_str: str):
     return params
 
 
+# ``Body: { … }`` lines and ``?a=…&b=…`` examples of a view docstring
+_BODY_RE = re.compile(r'^\s*Body:\s*`*\{(.*)\}`*', re.MULTILINE)
+_SAME_BODY_RE = re.compile(r'^\s*Body:\s*same as (/[\w/<>-]+)', re.MULTILINE)
+_QUERY_NAME_RE = re.compile(r'[?&](\w+)=')
+_FIELD_RE = re.compile(r'^"?(\w+)"?(\?)?\s*(?::\s*(.*))?$')
+
+# Body fields the staging API uses throughout, when the docstring gives no type
+_FIELD_TYPES = {
+    'engine_letter': {'type': 'string'}, 'language': {'type': 'string'},
+    'code': {'type': 'string'}, 'label': {'type': 'string'},
+    'author': {'type': 'string'}, 'reason': {'type': 'string'},
+    'engine': {'type': 'string'}, 'spec_format': {'type': 'string'},
+    'spec': {'description': 'sidecar text (spec_format), or SpecSuite.to_dict()'},
+    'meta': {'type': 'object'}, 'auto_promote': {'type': 'boolean'},
+    'stream': {'type': 'boolean'}, 'quarantine': {'type': 'boolean'},
+    'workers': {'type': 'integer'}, 'repeat': {'type': 'integer'},
+    'ttl': {'type': 'integer'}, 'staging_ids': {'type': 'array', 'items': {'type': 'string'}},
+    'parent': {'type': 'string', 'description': 'staging_id this snippet was staged from'},
+}
+
+# Where a view builds its success reply
+_SUCCESS_RE = re.compile(r"(?:jsonify\(|return )\{\s*'success': True")
+
+# Response keys with a component schema
+_REF = {'snippet': '#/components/schemas/StagedSnippet'}
+_REF_LISTS = {'snippets': '#/components/schemas/StagedSnippet',
+              'degraded': '#/components/schemas/Degradation',
+              'lineage': '#/components/schemas/Ancestor'}
+
+
+def _split_fields(text: str):
+    """'a, b?: [x, y], c' → ['a', 'b?: [x, y]', 'c'] (commas inside brackets kept)."""
+    fields, depth, current = [], 0, ''
+    for ch in text:
+        if ch in '[{(':
+            depth += 1
+        elif ch in ']})':
+            depth -= 1
+        if ch == ',' and depth == 0:
+            fields.append(current.strip())
+            current = ''
+        else:
+            current += ch
+    if current.strip():
+        fields.append(current.strip())
+    return fields
+
+
+def _field_schema(kind: str) -> dict:
+    """The schema a docstring field's type (``int``, ``'a'|'b'``, ``[..]``…) names."""
+    kind = re.split(r'\s+[(—]|\s{2,}', kind.strip())[0].strip()
+    if kind.startswith('['):
+        return {'type': 'array', 'items': {}}
+    if kind.startswith(("'", '"')) and '|' in kind:
+        return {'type': 'string', 'enum': [v.strip().strip('\'"') for v in kind.split('|')]}
+    if kind.startswith(("'", '"')):
+        return {'type': 'string'}
+    return {'bool': {'type': 'boolean'}, 'true': {'type': 'boolean'},
+            'false': {'type': 'boolean'}, 'int': {'type': 'integer'},
+            'number': {'type': 'number'}, 'string': {'type': 'string'}}.get(kind, {})
+
+
+def _parse_body(docstring: str, bodies: dict):
+    """The request body schema a view's ``Body:`` line describes, or None."""
+    same = _SAME_BODY_RE.search(docstring)
+    if same:
+        return bodies.get(_flask_to_openapi_path(same.group(1).rstrip('.')))
+    match = _BODY_RE.search(docstring)
+    if not match:
+        return None
+    properties, required = {}, []
+    for field in _split_fields(match.group(1)):
+        parsed = _FIELD_RE.match(re.sub(r'\s+\(.*\)$', '', field))
+        if not parsed:
+            continue
+        name, optional, kind = parsed.groups()
+        properties[name] = _field_schema(kind or '') or dict(_FIELD_TYPES.get(name, {}))
+        if not optional:
+            required.append(name)
+    schema = {'type': 'object', 'properties': properties}
+    if required:
+        schema['required'] = required
+    return schema
+
+
+def _response_keys(view_func):
+    """The keys a view's ``jsonify({'success': True, …})`` (or a streamed
+    view's result) returns next to ``success``; True among them: it also
+    spreads ``**`` a dict into the reply."""
+    try:
+        source = inspect.getsource(view_func)
+    except (OSError, TypeError):
+        return None
+    keys, found = [], False
+    for start in (m.end() for m in _SUCCESS_RE.finditer(source)):
+        found, depth, i = True, 1, start
+        while depth and i < len(source):
+            ch = source[i]
+            if ch in '([{':
+                depth += 1
+            elif ch in ')]}':
+                depth -= 1
+            elif depth == 1:
+                key = re.match(r",\s*'(\w+)':", source[i:])
+                if key and key.group(1) not in keys:
+                    keys.append(key.group(1))
+                elif source.startswith('**', i) and True not in keys:
+                    keys.append(True)
+            i += 1
+    return keys if found else None
+
+
+def _response_schema(keys) -> dict:
+    properties = {'success': {'type': 'boolean', 'enum': [True]}}
+    for key in keys:
+        if key is True:
+            continue
+        if key in _REF:
+            properties[key] = {'$ref': _REF[key]}
+        elif key in _REF_LISTS:
+            properties[key] = {'type': 'array', 'items': {'$ref': _REF_LISTS[key]}}
+        else:
+            properties[key] = {}
+    schema = {'type': 'object', 'properties': properties, 'required': ['success']}
+    if True in keys:
+        schema['additionalProperties'] = True
+    return schema
+
+
+def _type_schema(hint) -> dict:
+    """The schema of a dataclass field's type hint."""
+    origin, args = typing.get_origin(hint), typing.get_args(hint)
+    if origin is typing.Union:
+        others = [a for a in args if a is not type(None)]
+        schema = _type_schema(others[0]) if len(others) == 1 else {}
+        return {**schema, 'nullable': True} if schema else schema
+    if origin in (list, tuple, set, frozenset):
+        return {'type': 'array', 'items': _type_schema(args[0]) if args else {}}
+    if origin is dict or hint is dict:
+        return {'type': 'object'}
+    if isinstance(hint, type) and issubclass(hint, enum.Enum):
+        return {'type': 'string', 'enum': [m.value for m in hint]}
+    return {str: {'type': 'string'}, bool: {'type': 'boolean'}, int: {'type': 'integer'},
+            float: {'type': 'number'}, list: {'type': 'array', 'items': {}}}.get(hint, {})
+
+
+def _dataclass_schema(cls, extra=None) -> dict:
+    hints = typing.get_type_hints(cls)
+    properties = {f.name: _type_schema(hints[f.name]) for f in dataclasses.fields(cls)}
+    properties.update(extra or {})
+    return {'type': 'object', 'properties': properties}
+
+
+def _component_schemas() -> dict:
+    from visual_editor_core.errors import ErrorKind
+    from visual_editor_core.lineage import Ancestor
+    from visual_editor_core.revalidation import Degradation
+    from visual_editor_core.snippet_staging import StagedSnippet
+
+    return {
+        'StagedSnippet': _dataclass_schema(StagedSnippet, {
+            'created': {'type': 'string', 'description': 'created_at as a UTC timestamp'},
+            'promoted': {'type': 'string', 'description': "promoted_at, '' until promoted"},
+        }),
+        'Degradation': _dataclass_schema(Degradation),
+        'Ancestor': _dataclass_schema(Ancestor, {
+            'diff': {'type': 'string', 'description': "unified diff from its parent ('' if none)"},
+        }),
+        'Error': {
+            'type': 'object',
+            'properties': {
+                'success': {'type': 'boolean', 'enum': [False]},
+                'error': {'type': 'string'},
+                'kind': {'type': 'string', 'enum': [k.value for k in ErrorKind]},
+                'context': {'type': 'object'},
+            },
+            'required': ['success', 'error'],
+        },
+    }
+
+
 def _flask_to_openapi_path(rule_str: str) -> str:
     """Convert '/api/staging/snippet/<staging_id>' → '/api/staging/snippet/{staging_id}'."""
     return _PARAM_RE.sub(lambda m: '{' + m.group(2) + '}', rule_str)
 
 
-def _build_operation(rule, method, view_func):
+def _build_operation(rule, method, view_func, bodies=None):
     """Build one OpenAPI operation object from a Flask route."""
     docstring = (view_func.__doc__ or '').strip()
     summary_line = docstring.split('\n')[0] if docstring else f'{method} {rule.rule}'
@@ -106,8 +304,17 @@ def _build_operation(rule, method, view_func):
         'tags': [_tag_for_rule(rule.rule)],
         'responses': {
             '200': {'description': 'Successful response'},
+            'default': {
+                'description': 'Refused or failed (see ErrorKind)',
+                'content': {'application/json': {
+                    'schema': {'$ref': '#/components/schemas/Error'}}},
+            },
         },
     }
+    keys = _response_keys(view_func)
+    if keys is not None:
+        op['responses']['200']['content'] = {
+            'application/json': {'schema': _response_schema(keys)}}
     if description:
         op['description'] = description
 
@@ -131,17 +338,22 @@ def _build_operation(rule, method, view_func):
                 'name': 'last_n', 'in': 'query', 'required': False,
                 'schema': {'type': 'integer', 'default': 10},
             })
+        for name in _QUERY_NAME_RE.findall(docstring):
+            if all(p['name'] != name for p in params):
+                params.append({'name': name, 'in': 'query', 'required': False,
+                               'schema': {'type': 'string'}})
 
     if params:
         op['parameters'] = params
 
     # Request body for write methods
     if method in ('POST', 'PUT', 'PATCH'):
+        schema = _parse_body(docstring, bodies if bodies is not None else {})
         op['requestBody'] = {
             'required': True,
             'content': {
                 'application/json': {
-                    'schema': {'type': 'object'},
+                    'schema': schema or {'type': 'object'},
                 },
             },
         }
@@ -149,6 +361,17 @@ def _build_operation(rule, method, view_func):
     return op
 
 
+def _operation_id(rule, method, taken) -> str:
+    """The view function's name; a second method of one view, or a clash
+    across blueprints, adds the method / blueprint."""
+    blueprint, _, name = rule.endpoint.rpartition('.')
+    for candidate in (name, f"{name}_{method.lower()}", f"{blueprint}_{name}_{method.lower()}"):
+        if candidate not in taken:
+            taken.add(candidate)
+            return candidate
+    raise ValueError(f"duplicate operationId for {method} {rule.rule}")
+
+
 def build_openapi_spec(app) -> dict:
     """Build the complete OpenAPI 3.0 specification from the Flask app."""
     # Resolve host/port dynamically
@@ -181,7 +404,16 @@ def build_openapi_spec(app) -> dict:
             for _, tag in _TAG_MAP
         ],
         'paths': {},
+        'components': {
+            'schemas': _component_schemas(),
+            'securitySchemes': {
+                # A tenant's store (under /t/<tenant>/) may require its token
+                'tenantToken': {'type': 'apiKey', 'in': 'header', 'name': 'X-Spokedpy-Token'},
+            },
+        },
+        'security': [{}, {'tenantToken': []}],
     }
+    bodies, taken = {}, set()
 
     # Collect all /api/* routes
     for rule in app.url_map.iter_rules():
@@ -201,13 +433,26 @@ def build_openapi_spec(app) -> dict:
         for method in sorted(rule.methods & _DOC_METHODS):
             method_lower = method.lower()
             if method_lower not in spec['paths'][openapi_path]:
-                spec['paths'][openapi_path][method_lower] = _build_operation(
-                    rule, method, view_func
-                )
+                op = _build_operation(rule, method, view_func, bodies)
+                op['operationId'] = _operation_id(rule, method, taken)
+                spec['paths'][openapi_path][method_lower] = op
+                if method == 'POST' and 'requestBody' in op:
+                    bodies[openapi_path] = op['requestBody']['content'][
+                        'application/json']['schema']
 
     return spec
 
 
+def openapi_app() -> Flask:
+    """A bare Flask app with the daemon's API (the runtime blueprint) registered —
+    enough for build_openapi_spec without opening a store or starting a server."""
+    from web_interface.runtime import runtime_bp
+
+    app = Flask('spokedpy-openapi')
+    app.register_blueprint(runtime_bp)
+    return app
+
+
 # ─────────────────────────────────────────────────────────────────────
 # Routes
 # ─────────────────────────────────────────────────────────────────────
@@ -0,0 +1,246 @@
+"""
+Tenants — one ``spokedpy serve`` for several teams, each with a store of its own.
+
+Every ``[tenants.<name>]`` table of spokedpy.toml is a store the server
+hosts next to its own:
+
+    [tenants.payments]
+    token = "…"                            # optional; X-Spokedpy-Token
+    data_dir = "/srv/spoked/payments"      # default data/tenants/payments
+
+    [tenants.payments.retention]
+    history_limit = 200
+
+    [tenants.payments.gates]
+    policy_file = "/srv/spoked/payments/policy.toml"
+
+A request picks its tenant by URL prefix or by token:
+
+    GET /t/payments/api/staging/snippets
+    GET /api/staging/snippets               X-Spokedpy-Token: …
+
+Each tenant has its own snippets dir, audit log, checkpoint, slot
+reservations, registry matrix and slot locks, promotion policy /
+constraints / owners, retention and reviewers.  Its store paths default
+to files in its data_dir; what else it does not set comes from the
+server's settings, as do engines, sandbox and notifications, which every
+tenant shares.  A tenant with a token is served only to requests that
+present it; a token no tenant has is refused.  A request with neither
+reaches the server's own store, as before.  Under a tenant only the store
+API (the runtime routes) and /ui (for a tenant without a token) answer —
+settings, canvas and AI proxy stay the server's.
+
+runtime keeps a Store for itself and one per tenant; its module globals
+(staging_pipeline, node_registry, …) are Routed stand-ins for the store
+of the request at hand.
+"""
+
+import hmac
+import json
+import os
+from contextlib import contextmanager
+from contextvars import ContextVar
+from dataclasses import dataclass, field
+from typing import Any, Callable, Dict, Iterator, List, Optional
+
+# Blueprints a tenant request may reach (the rest answer 404 under a tenant)
+SCOPED_BLUEPRINTS = frozenset({'runtime', 'store_ui'})
+TOKEN_HEADER = 'X-Spokedpy-Token'
+PREFIX = '/t/'
+
+
+@dataclass
+class Store:
+    """The per-store state of the server; name '' is its own store."""
+    name: str
+    setting: Callable[[str, str, str], str]     # resolve_setting for this store
+    token: str = ''
+    data_dir: str = ''
+    pipeline: Any = None                        # StagingPipeline
+    node_registry: Any = None
+    slot_watch: Any = None
+    promotion_scheduler: Any = None
+    revalidator: Any = None
+    state_persistence: Any = None
+    locked_slots: Dict[str, Any] = field(default_factory=dict)
+
+
+_stores: Dict[str, Store] = {}
+_current: ContextVar[str] = ContextVar('spokedpy_tenant', default='')
+
+
+def tenant_setting(config, fallback: Callable[[str, str, str], str]):
+    """
+    resolve_setting for one tenant (a config_layers.TenantConfig): its own
+    table, then — for store paths — a file of that name in its data_dir,
+    then ``fallback`` (the server's settings).
+    """
+    from web_interface.config_layers import CONFIG_SCHEMA, TENANT_KEYS
+
+    def setting(key: str, env_var: str, default: str) -> str:
+        if key in config.values:
+            return config.values[key]
+        meta = CONFIG_SCHEMA.get(key)
+        if meta is not None and meta.is_path and key in TENANT_KEYS:
+            return os.path.join(config.data_dir, os.path.basename(meta.default))
+        return fallback(key, env_var, default)
+    return setting
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# REGISTRY
+# ═══════════════════════════════════════════════════════════════════════════
+
+def register(store: Store):
+    _stores[store.name] = store
+
+
+def stores() -> List[Store]:
+    """Every store, the server's own first."""
+    return sorted(_stores.values(), key=lambda s: s.name)
+
+
+def hosted() -> bool:
+    """Whether any tenant is configured."""
+    return any(name for name in _stores)
+
+
+def current() -> Optional[Store]:
+    """The store of this request (or of ``using``); None when no store is registered."""
+    return _stores.get(_current.get())
+
+
+@contextmanager
+def using(name: str) -> Iterator[Store]:
+    """Route this thread's runtime globals to ``name``'s store (startup, shutdown)."""
+    token = _current.set(name)
+    try:
+        yield _stores[name]
+    finally:
+        _current.reset(token)
+
+
+def setting(key: str, env_var: str, default: str) -> str:
+    """resolve_setting as the current store sees it (the server's own outside tenants)."""
+    store = current()
+    if store is None:
+        from web_interface.project_db import resolve_setting
+        return resolve_setting(key, env_var, default)
+    return store.setting(key, env_var, default)
+
+
+class Routed:
+    """Stands in for a runtime global: forwards to that attribute of the current store."""
+
+    __slots__ = ('_attr',)
+
+    def __init__(self, attr: str):
+        object.__setattr__(self, '_attr', attr)
+
+    def _target(self):
+        return getattr(_stores[_current.get()], self._attr)
+
+    def __getattr__(self, name):
+        return getattr(self._target(), name)
+
+    def __getitem__(self, key):
+        return self._target()[key]
+
+    def __setitem__(self, key, value):
+        self._target()[key] = value
+
+    def __delitem__(self, key):
+        del self._target()[key]
+
+    def __contains__(self, key):
+        return key in self._target()
+
+    def __iter__(self):
+        return iter(self._target())
+
+    def __len__(self):
+        return len(self._target())
+
+    def __bool__(self):
+        return bool(self._target())
+
+    def __repr__(self):
+        return f"<Routed {self._attr} of tenant {_current.get() or '(server)'!r}>"
+
+
+# ═══════════════════════════════════════════════════════════════════════════
+# REQUEST ROUTING
+# ═══════════════════════════════════════════════════════════════════════════
+
+class _Refused(Exception):
+    def __init__(self, status: str, kind: str, message: str, **context):
+        super().__init__(message)
+        self.status, self.kind, self.message, self.context = status, kind, message, context
+
+
+def select(environ: Dict[str, Any]) -> str:
+    """
+    The tenant a WSGI request is for; strips a ``/t/<name>`` prefix from
+    PATH_INFO (onto SCRIPT_NAME).  Raises _Refused for an unknown tenant
+    or a missing / wrong token.
+    """
+    presented = environ.get('HTTP_' + TOKEN_HEADER.upper().replace('-', '_'), '').strip()
+    by_token = None
+    if presented:
+        by_token = next((s.name for s in _stores.values() if s.name and s.token and
+                         hmac.compare_digest(presented.encode(), s.token.encode())), None)
+        if by_token is None:
+            raise _Refused('401 UNAUTHORIZED', 'usage', f"No tenant has that {TOKEN_HEADER}")
+    path = environ.get('PATH_INFO', '')
+    if not path.startswith(PREFIX):
+        return by_token or ''
+    name, _, rest = path[len(PREFIX):].partition('/')
+    store = _stores.get(name) if name else None
+    if store is None:
+        raise _Refused('404 NOT FOUND', 'not_found', f"No tenant '{name}' on this server",
+                       tenant=name)
+    if (store.token or by_token) and by_token != name:
+        raise _Refused('401 UNAUTHORIZED', 'usage',
+                       f"Tenant '{name}' needs its token ({TOKEN_HEADER})", tenant=name)
+    environ['SCRIPT_NAME'] = environ.get('SCRIPT_NAME', '') + PREFIX + name
+    environ['PATH_INFO'] = '/' + rest
+    return name
+
+
+class TenantRouter:
+    """WSGI middleware: run each request against its tenant's store (see select)."""
+
+    def __init__(self, app):
+        self.app = app
+
+    def __call__(self, environ, start_response):
+        try:
+            name = select(environ)
+        except _Refused as refused:
+            body = json.dumps({'success': False, 'error': refused.message,
+                               'kind': refused.kind, 'context': refused.context}).encode()
+            start_response(refused.status, [('Content-Type', 'application/json'),
+                                            ('Content-Length', str(len(body)))])
+            return [body]
+        environ['spokedpy.tenant'] = name
+        token = _current.set(name)
+        try:
+            return self.app(environ, start_response)
+        finally:
+            _current.reset(token)
+
+
+def install(app):
+    """Route ``app``'s requests by tenant (once tenants are registered)."""
+    from flask import jsonify, request
+
+    app.wsgi_app = TenantRouter(app.wsgi_app)
+
+    @app.before_request
+    def _tenant_scope():
+        name = request.environ.get('spokedpy.tenant', '')
+        if name and request.blueprint not in SCOPED_BLUEPRINTS:
+            return jsonify({'success': False, 'kind': 'not_found', 'context': {'tenant': name},
+                            'error': f"{request.path} is not part of a tenant's store API "
+                                     f"(tenant '{name}')"}), 404
+        return None